@@ -1,7 +1,18 @@
-use tauri::{State, Emitter, AppHandle};
+use tauri::{State, Emitter, AppHandle, Manager};
 use crate::AppState;
-use crate::models::ai::{AIAnalysisResult, AIStreamEvent};
+use crate::models::ai::{AIAnalysisResult, AIStreamEvent, StockInstructionResult, RouterAnswer, StockSummaryForAI, UserPosition};
+use crate::models::agent_session::AgentSession;
+use crate::models::audio::AudioExport;
+use crate::services::ai_router;
+use crate::services::chart_vision;
 use crate::services::ai_service::AIService;
+use crate::services::instruction_tracker;
+use crate::services::stock_tools::ToolContext;
+use crate::services::stream_forwarder;
+use crate::services::strategy_zone::{self, ZoneMembers};
+use crate::services::symbol_table;
+use crate::services::tts;
+use crate::error::AppError;
 
 #[tauri::command]
 pub async fn analyze_stock(
@@ -10,11 +21,11 @@ pub async fn analyze_stock(
     code: String,
     name: String,
     context_data: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     log::info!("[ai_cmd] analyze_stock code={} name={}", code, name);
     let settings = state.db.load_settings().map_err(|e| {
         log::error!("[ai_cmd] analyze_stock load_settings failed: {}", e);
-        e.to_string()
+        AppError::from(e)
     })?;
 
     let ai_config = settings.ai_configs.iter()
@@ -32,17 +43,12 @@ pub async fn analyze_stock(
         return Ok(());
     }
 
-    let (tx, mut rx) = tokio::sync::mpsc::channel::<AIStreamEvent>(100);
-
-    let app_clone = app.clone();
-    let code_clone = code.clone();
+    let (tx, rx) = tokio::sync::mpsc::channel::<AIStreamEvent>(100);
 
     // Spawn receiver to forward events to frontend
-    tokio::spawn(async move {
-        while let Some(event) = rx.recv().await {
-            let _ = app_clone.emit(&format!("ai-stream-{}", code_clone), &event);
-        }
-    });
+    stream_forwarder::spawn(&app, format!("ai-stream-{}", code), rx);
+
+    let (code, name) = symbol_table::resolve_input(&state.db, &code, &name);
 
     // Run the stream
     let result = AIService::analyze_stock_stream(
@@ -53,7 +59,7 @@ pub async fn analyze_stock(
         tx,
     ).await.map_err(|e| {
         log::error!("[ai_cmd] analyze_stock stream failed for {}: {}", code, e);
-        e.to_string()
+        AppError::from(e)
     })?;
 
     // Save result to DB
@@ -81,20 +87,197 @@ pub async fn get_analysis_history(
     state: State<'_, AppState>,
     code: String,
     limit: usize,
-) -> Result<Vec<AIAnalysisResult>, String> {
+) -> Result<Vec<AIAnalysisResult>, AppError> {
     log::info!("[ai_cmd] get_analysis_history code={} limit={}", code, limit);
     state.db.get_ai_analysis_history(&code, limit).map_err(|e| {
         log::error!("[ai_cmd] get_analysis_history failed: {}", e);
-        e.to_string()
+        AppError::from(e)
     })
 }
 
 #[tauri::command]
 pub async fn get_today_token_usage(
     state: State<'_, AppState>,
-) -> Result<u32, String> {
+) -> Result<u32, AppError> {
     state.db.get_today_token_usage().map_err(|e| {
         log::error!("[ai_cmd] get_today_token_usage failed: {}", e);
-        e.to_string()
+        AppError::from(e)
+    })
+}
+
+/// 获取单次 Agent 会话的审计记录（工具调用、参数、原始结果、token 消耗）
+#[tauri::command]
+pub async fn get_agent_session(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<Option<AgentSession>, AppError> {
+    log::info!("[ai_cmd] get_agent_session id={}", id);
+    state.db.get_agent_session(&id).map_err(|e| {
+        log::error!("[ai_cmd] get_agent_session failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 获取最近的 Agent 会话记录，kind 为空时返回全部类型
+#[tauri::command]
+pub async fn get_agent_sessions(
+    state: State<'_, AppState>,
+    kind: Option<String>,
+    limit: usize,
+) -> Result<Vec<AgentSession>, AppError> {
+    log::info!("[ai_cmd] get_agent_sessions kind={:?} limit={}", kind, limit);
+    state.db.get_agent_sessions(kind.as_deref(), limit).map_err(|e| {
+        log::error!("[ai_cmd] get_agent_sessions failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 回放 Agent 会话（dry-run）：基于已记录的工具结果与模型原始输出重放事件流，
+/// 不联网、不消耗 token，事件通过 agent-replay-{id} 推送
+#[tauri::command]
+pub async fn replay_agent_session(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    id: String,
+) -> Result<String, AppError> {
+    log::info!("[ai_cmd] replay_agent_session id={}", id);
+    let session = state.db.get_agent_session(&id).map_err(|e| {
+        log::error!("[ai_cmd] replay_agent_session load failed: {}", e);
+        AppError::from(e)
+    })?.ok_or_else(|| AppError::NotFound(format!("未找到 Agent 会话: {}", id)))?;
+
+    let (tx, rx) = tokio::sync::mpsc::channel::<AIStreamEvent>(100);
+    let forwarder = stream_forwarder::spawn(&app, format!("agent-replay-{}", id), rx);
+
+    let result = AIService::replay_agent_session(&session, tx.clone()).await;
+    let _ = tx.send(AIStreamEvent {
+        event_type: if result.is_ok() { "done" } else { "error" }.to_string(),
+        content: Some(match &result {
+            Ok(content) => content.clone(),
+            Err(e) => format!("回放失败: {}", e),
+        }),
+        done: true,
+        usage: None,
+        tool_name: None,
+    }).await;
+    drop(tx);
+    let _ = forwarder.await;
+
+    result.map_err(|e| {
+        log::error!("[ai_cmd] replay_agent_session failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 为策略区间内的股票批量生成操作指令，结果写入指令历史以便事后核对次日表现。
+/// 传入用户持仓时，持仓股给出加仓/减仓/持有建议，其余给出新开仓建议
+#[tauri::command]
+pub async fn generate_instructions(
+    state: State<'_, AppState>,
+    stocks: Vec<StockSummaryForAI>,
+    positions: Option<Vec<UserPosition>>,
+) -> Result<Vec<StockInstructionResult>, AppError> {
+    let positions = positions.unwrap_or_default();
+    log::info!("[ai_cmd] generate_instructions stocks={} positions={}", stocks.len(), positions.len());
+    let settings = state.db.load_settings().map_err(AppError::from)?;
+    let config = settings.active_ai_config().ok_or_else(|| {
+        log::error!("[ai_cmd] generate_instructions: 未配置AI模型");
+        "未配置AI模型".to_string()
+    })?;
+
+    let (instructions, usage) = AIService::batch_generate_instructions(&config, &stocks, &positions).await.map_err(|e| {
+        log::error!("[ai_cmd] generate_instructions failed: {}", e);
+        AppError::from(e)
+    })?;
+    if let Some(usage) = usage {
+        let _ = state.db.record_token_usage(&config.model_name, usage.prompt_tokens, usage.completion_tokens);
+    }
+    if let Err(e) = instruction_tracker::record_instructions(&state.db, &stocks, &instructions) {
+        log::warn!("[ai_cmd] generate_instructions record history failed: {}", e);
+    }
+    Ok(instructions)
+}
+
+/// 按用户配置的策略区间对竞价快照分类
+#[tauri::command]
+pub async fn classify_strategy_zones(
+    state: State<'_, AppState>,
+    stocks: Vec<StockSummaryForAI>,
+) -> Result<Vec<ZoneMembers>, AppError> {
+    let settings = state.db.load_settings().map_err(|e| {
+        log::error!("[ai_cmd] classify_strategy_zones load_settings failed: {}", e);
+        AppError::from(e)
+    })?;
+    Ok(strategy_zone::classify(&settings.strategy_zones, &stocks))
+}
+
+/// 自然语言统一入口：识别意图（诊断/选股/资讯/对比/大盘）后分派给对应 Agent，流式内容推送到 ai-router-stream
+#[tauri::command]
+pub async fn ai_router(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    input: String,
+) -> Result<RouterAnswer, AppError> {
+    log::info!("[ai_cmd] ai_router input_len={}", input.chars().count());
+    let settings = state.db.load_settings().map_err(AppError::from)?;
+    let config = settings.active_ai_config().ok_or_else(|| {
+        log::error!("[ai_cmd] ai_router: 未配置AI模型");
+        "未配置AI模型".to_string()
+    })?;
+    let mut tool_ctx = ToolContext::from_settings(&settings);
+    tool_ctx.load_market_history(&state.db);
+
+    ai_router::route(&state.db, &app, &config, &tool_ctx, &input).await.map_err(|e| {
+        log::error!("[ai_cmd] ai_router failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 识图分析：把用户粘贴的图表截图（base64）连同股票真实数据发给视觉模型交叉核对，
+/// 流式内容推送到 ai-image-analysis。config_id 为空时使用当前启用的模型
+#[tauri::command]
+pub async fn ai_analyze_image(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    image: String,
+    code: Option<String>,
+    question: Option<String>,
+    config_id: Option<String>,
+) -> Result<AIAnalysisResult, AppError> {
+    log::info!("[ai_cmd] ai_analyze_image code={:?} config_id={:?}", code, config_id);
+    let settings = state.db.load_settings().map_err(AppError::from)?;
+    let config = match &config_id {
+        Some(id) => settings.ai_configs.iter().find(|c| &c.id == id && c.enabled).cloned(),
+        None => settings.active_ai_config(),
+    }
+    .ok_or_else(|| {
+        log::error!("[ai_cmd] ai_analyze_image: 未配置AI模型");
+        "未配置AI模型".to_string()
+    })?;
+    let tool_ctx = ToolContext::from_settings(&settings);
+
+    chart_vision::analyze(&state.db, &app, &config, &tool_ctx, &image, code.as_deref(), question.as_deref().unwrap_or(""))
+        .await
+        .map_err(|e| {
+            log::error!("[ai_cmd] ai_analyze_image failed: {}", e);
+            AppError::from(e)
+        })
+}
+
+/// AI 分析记录（如个股诊断结论）转语音，返回文件记录
+#[tauri::command]
+pub async fn export_analysis_audio(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    id: String,
+) -> Result<AudioExport, AppError> {
+    log::info!("[ai_cmd] export_analysis_audio id={}", id);
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::from(e).context("获取数据目录失败"))?;
+    tts::export_analysis(&state.db, &dir, &id).await.map_err(|e| {
+        log::error!("[ai_cmd] export_analysis_audio failed: {}", e);
+        AppError::from(e)
     })
 }
@@ -1,11 +1,26 @@
-use tauri::{AppHandle, Emitter, Manager};
+use tauri::{AppHandle, Manager};
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
 use crate::AppState;
-use crate::models::ai::AIStreamEvent;
+use crate::models::ai::{AIStreamEvent, CachedPicks, PickSessionSummary, StockPick};
+use crate::models::agent_session::{AgentSession, InterruptedPick, PickCheckpoint, PickSessionDetail};
 use crate::models::agent_prompt::BUILTIN_DEFAULT_PROMPT_ID;
-use crate::services::ai_service::AIService;
+use crate::models::watchlist::{WatchlistImportResult, WatchlistStock};
+use crate::services::ai_service::{self, AIService};
+use crate::models::ai::AIConfig;
+use crate::models::settings::{AppSettings, PickPreferences};
+use crate::services::market_regime;
+use crate::services::model_capability;
+use crate::services::pick_checkpoint;
+use crate::services::pick_constraints;
+use crate::services::pick_store;
+use crate::services::pick_verifier;
+use crate::services::symbol_table;
+use crate::services::watchlist_prefetch;
+use crate::services::stock_tools::ToolContext;
+use crate::services::stream_forwarder;
+use crate::error::AppError;
 
 /// AI 自主选股命令
 /// 启动 AI Agent，让其自主获取新闻/板块/行情数据并做出选股决策
@@ -13,34 +28,124 @@ use crate::services::ai_service::AIService;
 pub async fn ai_pick_stocks(
     app: AppHandle,
     state: tauri::State<'_, AppState>,
-) -> Result<(), String> {
+    preferences: Option<PickPreferences>,
+) -> Result<(), AppError> {
     log::info!("[ai_pick_cmd] ai_pick_stocks started");
     // 单飞控制：防止重复提交
     if state.ai_picking.compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst).is_err() {
         log::warn!("[ai_pick_cmd] ai_pick_stocks already in progress");
-        return Err("AI 选股正在进行中，请等待当前任务完成".to_string());
+        return Err(AppError::Busy("AI 选股正在进行中，请等待当前任务完成".to_string()));
     }
 
-    // 重置取消信号
-    state.ai_pick_cancel.store(false, Ordering::SeqCst);
-    let cancel_token = Arc::clone(&state.ai_pick_cancel);
-
     let settings = state.db.load_settings().map_err(|e| {
         state.ai_picking.store(false, Ordering::SeqCst);
-        e.to_string()
+        AppError::from(e)
     })?;
-    let config = settings
+    let config = active_pick_config(&settings).ok_or_else(|| {
+        state.ai_picking.store(false, Ordering::SeqCst);
+        AppError::NotConfigured("未配置可用的 AI 模型，请在设置中添加".to_string())
+    })?;
+
+    // 本次调用传入的偏好优先，否则使用设置中保存的偏好
+    let preferences = preferences.unwrap_or_else(|| settings.pick_preferences.clone());
+    if let Err(e) = pick_constraints::validate(&preferences) {
+        state.ai_picking.store(false, Ordering::SeqCst);
+        return Err(AppError::InvalidInput(e));
+    }
+
+    let session = AgentSession::new("pick", "", &config.model_name);
+    spawn_pick_run(app, &state, &settings, config, preferences, session, None);
+    Ok(())
+}
+
+/// 从断点续跑中断的 AI 选股（沿用原会话、对话与偏好，使用当前启用的模型）
+#[tauri::command]
+pub async fn resume_ai_pick(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+    session_id: String,
+) -> Result<(), AppError> {
+    log::info!("[ai_pick_cmd] resume_ai_pick session_id={}", session_id);
+    if state.ai_picking.compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst).is_err() {
+        return Err(AppError::Busy("AI 选股正在进行中，请等待当前任务完成".to_string()));
+    }
+    let loaded = state.db.get_pick_checkpoint(&session_id).and_then(|c| Ok((c, state.db.load_settings()?)));
+    let (checkpoint, settings) = match loaded {
+        Ok((Some(checkpoint), settings)) => (checkpoint, settings),
+        Ok((None, _)) => {
+            state.ai_picking.store(false, Ordering::SeqCst);
+            return Err(AppError::NotFound("中断记录不存在或已失效".to_string()));
+        }
+        Err(e) => {
+            state.ai_picking.store(false, Ordering::SeqCst);
+            log::error!("[ai_pick_cmd] resume_ai_pick failed: {}", e);
+            return Err(AppError::from(e));
+        }
+    };
+    let config = active_pick_config(&settings).ok_or_else(|| {
+        state.ai_picking.store(false, Ordering::SeqCst);
+        AppError::NotConfigured("未配置可用的 AI 模型，请在设置中添加".to_string())
+    })?;
+
+    let mut session = checkpoint.session.clone();
+    session.error = None;
+    session.content.clear();
+    let preferences = checkpoint.preferences.clone();
+    spawn_pick_run(app, &state, &settings, config, preferences, session, Some(checkpoint));
+    Ok(())
+}
+
+/// 放弃中断的 AI 选股：删除断点，会话保留为中断状态
+#[tauri::command]
+pub async fn discard_interrupted_pick(
+    state: tauri::State<'_, AppState>,
+    session_id: String,
+) -> Result<(), AppError> {
+    log::info!("[ai_pick_cmd] discard_interrupted_pick session_id={}", session_id);
+    state.db.delete_pick_checkpoint(&session_id).map_err(|e| {
+        log::error!("[ai_pick_cmd] discard_interrupted_pick failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 最近一次可续跑的中断选股
+#[tauri::command]
+pub async fn get_interrupted_pick(
+    state: tauri::State<'_, AppState>,
+) -> Result<Option<InterruptedPick>, AppError> {
+    pick_checkpoint::latest_interrupted(&state.db).map_err(|e| {
+        log::error!("[ai_pick_cmd] get_interrupted_pick failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 当前启用的模型，未启用任何模型时取第一个可用模型
+fn active_pick_config(settings: &AppSettings) -> Option<AIConfig> {
+    settings
         .ai_configs
         .iter()
         .find(|c| Some(c.id.clone()) == settings.active_ai_config_id && c.enabled)
         .or_else(|| settings.ai_configs.iter().find(|c| c.enabled))
-        .ok_or_else(|| {
-            state.ai_picking.store(false, Ordering::SeqCst);
-            "未配置可用的 AI 模型，请在设置中添加".to_string()
-        })?
-        .clone();
+        .cloned()
+}
+
+/// 在后台运行选股 Agent（新开或续跑），每轮写入断点，结束后落库并推送 ai-pick-stream 事件。
+/// 调用前需已占用 ai_picking 标志位，任务结束时释放
+fn spawn_pick_run(
+    app: AppHandle,
+    state: &AppState,
+    settings: &AppSettings,
+    config: AIConfig,
+    preferences: PickPreferences,
+    mut session: AgentSession,
+    resume: Option<PickCheckpoint>,
+) {
+    // 重置取消信号
+    state.ai_pick_cancel.store(false, Ordering::SeqCst);
+    let cancel_token = Arc::clone(&state.ai_pick_cancel);
 
-    let qgqp_b_id = settings.qgqp_b_id.clone();
+    let mut tool_ctx = ToolContext::from_settings(settings);
+    tool_ctx.load_market_history(&state.db);
     let max_tool_rounds = settings.max_pick_tool_rounds;
     let max_token_budget = settings.max_pick_token_budget;
 
@@ -53,26 +158,110 @@ pub async fn ai_pick_stocks(
         })
         .map(|p| p.strategy_prompt.clone());
 
-    let (sender, mut receiver) = tokio::sync::mpsc::channel::<AIStreamEvent>(100);
+    let (sender, receiver) = tokio::sync::mpsc::channel::<AIStreamEvent>(100);
+    stream_forwarder::spawn(&app, "ai-pick-stream".to_string(), receiver);
 
-    let app_clone = app.clone();
+    let app_for_db = app;
     tokio::spawn(async move {
-        while let Some(event) = receiver.recv().await {
-            let _ = app_clone.emit("ai-pick-stream", &event);
+        // 登记为进行中任务：退出时会先触发取消，再等待断点与会话落库
+        let guard_state = app_for_db.state::<AppState>();
+        let _guard = guard_state.shutdown.track();
+        // 新开选股时计算当日市场状态写入系统提示词，续跑沿用断点中的对话
+        if resume.is_none() {
+            match market_regime::current(&app_for_db.state::<AppState>().db).await {
+                Ok(regime) => tool_ctx.market_regime = Some(regime),
+                Err(e) => log::warn!("[ai_pick_cmd] market_regime failed: {}", e),
+            }
         }
-    });
-
-    let app_for_db = app.clone();
-    tokio::spawn(async move {
-        let result = AIService::ai_pick_stocks_with_tools(&config, &qgqp_b_id, sender.clone(), cancel_token, max_tool_rounds, max_token_budget, custom_strategy.as_deref()).await;
+        let save_checkpoint = |checkpoint: &PickCheckpoint| {
+            if let Err(e) = app_for_db.state::<AppState>().db.save_pick_checkpoint(checkpoint) {
+                log::warn!("[ai_pick_cmd] save_pick_checkpoint failed: {}", e);
+            }
+        };
+        let result = AIService::ai_pick_stocks_with_tools(&config, &tool_ctx, sender.clone(), cancel_token, max_tool_rounds, max_token_budget, custom_strategy.as_deref(), &preferences, &mut session, resume, &save_checkpoint).await;
+        // <PICKS> 无法解析时补救一次，保证缓存的选股结果始终带结构化列表
+        let result = match result {
+            Ok((content, usage)) => Ok(AIService::ensure_structured_picks(&config, content, usage, &sender).await),
+            Err(e) => Err(e),
+        };
+        // 按本地代码表纠正模型给错的代码/名称，后续约束与校验都基于纠正后的代码
+        let result = match result {
+            Ok((content, usage)) => match symbol_table::resolve_picks(&app_for_db.state::<AppState>().db, &content) {
+                Ok((resolved, corrections)) => {
+                    if !corrections.is_empty() {
+                        let _ = sender.send(model_capability::thinking_event(&format!("已按代码表纠正推荐：{}", corrections.join("；")))).await;
+                    }
+                    Ok((resolved, usage))
+                }
+                Err(e) => {
+                    log::warn!("[ai_pick_cmd] resolve_picks failed: {}", e);
+                    Ok((content, usage))
+                }
+            },
+            Err(e) => Err(e),
+        };
+        // 按用户偏好硬性剔除违规推荐后再落库
+        let result = match result {
+            Ok((content, usage)) => match pick_constraints::enforce(&content, &preferences).await {
+                Ok((filtered, removed)) => {
+                    if !removed.is_empty() {
+                        let _ = sender.send(model_capability::thinking_event(&format!("已剔除不符合选股偏好的推荐：{}", removed.join("；")))).await;
+                    }
+                    Ok((filtered, usage))
+                }
+                Err(e) => {
+                    log::warn!("[ai_pick_cmd] enforce pick preferences failed: {}", e);
+                    Ok((content, usage))
+                }
+            },
+            Err(e) => Err(e),
+        };
 
         // 无论成功或失败，都重置标志位
         let app_state = app_for_db.state::<AppState>();
         app_state.ai_picking.store(false, Ordering::SeqCst);
 
+        session.finish(&result);
+        // 成功或用户取消时清除断点；出错或因应用退出而停止时保留断点供续跑，并把中断前的部分报告写入会话
+        let failure = if app_state.shutdown.is_requested() && result.is_err() {
+            Some(pick_checkpoint::APP_EXIT_ERROR.to_string())
+        } else {
+            result.as_ref().err().map(|e| e.to_string()).filter(|e| !e.contains("用户取消"))
+        };
+        match failure {
+            Some(err) => match pick_checkpoint::mark_failed(&app_state.db, &session.id, &err) {
+                Ok(Some(checkpoint)) => {
+                    session.error = Some(err);
+                    session.content = pick_checkpoint::interrupted_content(&checkpoint);
+                }
+                Ok(None) => {}
+                Err(e) => log::warn!("[ai_pick_cmd] mark checkpoint failed: {}", e),
+            },
+            None => {
+                if let Err(e) = app_state.db.delete_pick_checkpoint(&session.id) {
+                    log::warn!("[ai_pick_cmd] delete_pick_checkpoint failed: {}", e);
+                }
+            }
+        }
+        let _ = app_state.db.save_agent_session(&session);
+
         match result {
             Ok((content, usage)) => {
-                let _ = app_state.db.save_ai_pick_cache(&content);
+                match pick_store::record_picks(&app_state.db, &session.id, &content).await {
+                    // 提示词约束无法强制执行，行情校验发现问题时单独告警
+                    Ok(records) => {
+                        if let Some(warning) = pick_verifier::summarize(&records) {
+                            let _ = sender.send(AIStreamEvent {
+                                event_type: "warning".to_string(),
+                                content: Some(warning),
+                                done: false,
+                                usage: None,
+                                tool_name: None,
+                            }).await;
+                        }
+                    }
+                    Err(e) => log::warn!("[ai_pick_cmd] record_picks failed: {}", e),
+                }
 
                 let _ = sender.send(AIStreamEvent {
                     event_type: "done".to_string(),
@@ -103,31 +292,56 @@ pub async fn ai_pick_stocks(
             }
         }
     });
-
-    Ok(())
 }
 
 /// 停止 AI 选股
 #[tauri::command]
 pub async fn stop_ai_pick(
     state: tauri::State<'_, AppState>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     log::info!("[ai_pick_cmd] stop_ai_pick requested");
     if !state.ai_picking.load(Ordering::SeqCst) {
-        return Err("当前没有进行中的 AI 选股任务".to_string());
+        return Err(AppError::NotFound("当前没有进行中的 AI 选股任务".to_string()));
     }
     state.ai_pick_cancel.store(true, Ordering::SeqCst);
     Ok(())
 }
 
-/// 获取缓存的当日 AI 选股结果
+/// 获取当日最近一次 AI 选股结果（完整报告 + 结构化推荐列表）
 #[tauri::command]
 pub async fn get_cached_picks(
     state: tauri::State<'_, AppState>,
-) -> Result<Option<String>, String> {
-    state.db.get_ai_pick_cache().map_err(|e| {
+) -> Result<Option<CachedPicks>, AppError> {
+    pick_store::get_latest_picks(&state.db).map_err(|e| {
         log::error!("[ai_pick_cmd] get_cached_picks failed: {}", e);
-        e.to_string()
+        AppError::from(e)
+    })
+}
+
+/// 选股历史：按日期列出选股会话（date 为空时列出最近的会话）
+#[tauri::command]
+pub async fn list_pick_sessions(
+    state: tauri::State<'_, AppState>,
+    date: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<PickSessionSummary>, AppError> {
+    log::info!("[ai_pick_cmd] list_pick_sessions date={:?} limit={:?}", date, limit);
+    state.db.get_pick_sessions(date.as_deref(), limit.unwrap_or(50)).map_err(|e| {
+        log::error!("[ai_pick_cmd] list_pick_sessions failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 获取某次选股会话的完整报告、工具调用日志与结构化推荐
+#[tauri::command]
+pub async fn get_pick_session(
+    state: tauri::State<'_, AppState>,
+    session_id: String,
+) -> Result<Option<PickSessionDetail>, AppError> {
+    log::info!("[ai_pick_cmd] get_pick_session session_id={}", session_id);
+    pick_store::get_pick_session(&state.db, &session_id).map_err(|e| {
+        log::error!("[ai_pick_cmd] get_pick_session failed: {}", e);
+        AppError::from(e)
     })
 }
 
@@ -139,37 +353,39 @@ pub async fn find_similar_stocks(
     code: String,
     name: String,
     sector: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     log::info!("[ai_pick_cmd] find_similar_stocks code={} name={} sector={}", code, name, sector);
     let settings = state.db.load_settings().map_err(|e| {
         log::error!("[ai_pick_cmd] find_similar_stocks load_settings failed: {}", e);
-        e.to_string()
+        AppError::from(e)
     })?;
     let config = settings
         .ai_configs
         .iter()
         .find(|c| Some(c.id.clone()) == settings.active_ai_config_id && c.enabled)
         .or_else(|| settings.ai_configs.iter().find(|c| c.enabled))
-        .ok_or_else(|| "未配置可用的 AI 模型，请在设置中添加".to_string())?
+        .ok_or_else(|| AppError::NotConfigured("未配置可用的 AI 模型，请在设置中添加".to_string()))?
         .clone();
 
-    let qgqp_b_id = settings.qgqp_b_id.clone();
+    let tool_ctx = ToolContext::from_settings(&settings);
     let max_tool_rounds = settings.max_pick_tool_rounds;
     let max_token_budget = settings.max_pick_token_budget;
 
-    let (sender, mut receiver) = tokio::sync::mpsc::channel::<crate::models::ai::AIStreamEvent>(100);
+    let (sender, receiver) = tokio::sync::mpsc::channel::<crate::models::ai::AIStreamEvent>(100);
 
     let event_name = format!("ai-similar-{}", code);
-    let app_clone = app.clone();
-    let event_name_clone = event_name.clone();
-    tokio::spawn(async move {
-        while let Some(event) = receiver.recv().await {
-            let _ = app_clone.emit(&event_name_clone, &event);
-        }
-    });
+    stream_forwarder::spawn(&app, event_name.clone(), receiver);
+
+    let (code, name) = symbol_table::resolve_input(&state.db, &code, &name);
 
+    let app_for_db = app.clone();
     tokio::spawn(async move {
-        match AIService::find_similar_stocks_with_tools(&config, &code, &name, &sector, &qgqp_b_id, sender.clone(), max_tool_rounds, max_token_budget).await {
+        let mut session = AgentSession::new("similar", &code, &config.model_name);
+        let result = AIService::find_similar_stocks_with_tools(&config, &code, &name, &sector, &tool_ctx, sender.clone(), max_tool_rounds, max_token_budget, &mut session).await;
+        session.finish(&result);
+        let _ = app_for_db.state::<AppState>().db.save_agent_session(&session);
+
+        match result {
             Ok((content, usage)) => {
                 let _ = sender.send(crate::models::ai::AIStreamEvent {
                     event_type: "done".to_string(),
@@ -193,3 +409,84 @@ pub async fn find_similar_stocks(
 
     Ok(())
 }
+
+/// 将选股结果一键加入自选股，自动归入「AI精选 日期」分组，已在自选股中的代码跳过
+/// session_id 与 picks 二选一：前者从已记录的选股会话中解析 <PICKS>
+#[tauri::command]
+pub async fn add_picks_to_watchlist(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+    session_id: Option<String>,
+    picks: Option<Vec<StockPick>>,
+) -> Result<WatchlistImportResult, AppError> {
+    log::info!("[ai_pick_cmd] add_picks_to_watchlist session_id={:?} picks={}", session_id, picks.as_ref().map_or(0, |p| p.len()));
+    let (picks, date) = match (session_id, picks) {
+        (Some(id), _) => {
+            let session = state.db.get_agent_session(&id).map_err(|e| {
+                log::error!("[ai_pick_cmd] add_picks_to_watchlist load session failed: {}", e);
+                AppError::from(e)
+            })?.ok_or_else(|| AppError::NotFound(format!("未找到选股会话: {}", id)))?;
+            let date = session.created_at.chars().take(10).collect::<String>();
+            let stored = state.db.get_pick_records(&id).map_err(|e| {
+                log::error!("[ai_pick_cmd] add_picks_to_watchlist load picks failed: {}", e);
+                AppError::from(e)
+            })?;
+            let picks = if stored.is_empty() {
+                ai_service::parse_picks(&session.content)
+            } else {
+                // 行情校验驳回的推荐（停牌/ST/涨停）不加入自选
+                stored.into_iter().filter(|p| p.verify_status != pick_verifier::VERIFY_REJECTED).map(|p| StockPick {
+                    code: p.code,
+                    name: p.name,
+                    reason: p.reason,
+                    rating: p.rating,
+                    sector: p.sector,
+                    highlights: p.highlights,
+                    fund_flow: p.fund_flow,
+                    valuation: p.valuation,
+                }).collect()
+            };
+            (picks, date)
+        }
+        (None, Some(picks)) => (picks, chrono::Local::now().format("%Y-%m-%d").to_string()),
+        (None, None) => return Err(AppError::InvalidInput("未提供选股会话或股票列表".to_string())),
+    };
+    if picks.is_empty() {
+        return Err(AppError::InvalidInput("没有可加入的选股结果".to_string()));
+    }
+
+    let existing = state.db.get_watchlist_stocks().map_err(|e| {
+        log::error!("[ai_pick_cmd] add_picks_to_watchlist load watchlist failed: {}", e);
+        AppError::from(e)
+    })?;
+    let mut seen: std::collections::HashSet<String> = existing.iter().map(|s| s.code.clone()).collect();
+
+    let group_name = format!("AI精选 {}", date);
+    let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    let mut added = 0;
+    let mut skipped = 0;
+    let mut added_codes = Vec::new();
+    for pick in &picks {
+        if !seen.insert(pick.code.clone()) {
+            skipped += 1;
+            continue;
+        }
+        let stock = WatchlistStock {
+            code: pick.code.clone(),
+            name: pick.name.clone(),
+            sort_order: (existing.len() + added) as i32,
+            group_name: group_name.clone(),
+            created_at: now.clone(),
+        };
+        state.db.add_watchlist_stock(&stock).map_err(|e| {
+            log::error!("[ai_pick_cmd] add_picks_to_watchlist add {} failed: {}", pick.code, e);
+            AppError::from(e)
+        })?;
+        added += 1;
+        added_codes.push(stock.code);
+    }
+    watchlist_prefetch::spawn_prefetch(&app, added_codes);
+
+    log::info!("[ai_pick_cmd] add_picks_to_watchlist group={} added={} skipped={}", group_name, added, skipped);
+    Ok(WatchlistImportResult { added, skipped, invalid: 0 })
+}
@@ -1,20 +1,35 @@
-use tauri::State;
+use tauri::{AppHandle, Emitter, Manager, State};
 use crate::AppState;
+use crate::models::audio::AudioExport;
+use crate::models::briefing::MarketBriefing;
+use crate::models::stock::{BoardRotation, MarketBreadth, MarketHeatmap, MarketRegime, MarketStockCount, StockRps};
+use crate::models::watchlist::{SignalScreenHit, TechnicalDaily};
+use crate::services::board_rotation;
+use crate::services::briefing;
+use crate::services::market_breadth;
+use crate::services::market_heatmap;
 use crate::services::market_overview::{self, MarketOverview};
+use crate::services::market_regime;
+use crate::services::market_scanner::MarketScanner;
+use crate::services::rps;
+use crate::services::signal_screener;
+use crate::services::technical_store;
+use crate::services::tts;
+use crate::error::AppError;
 
 #[tauri::command]
 pub async fn get_market_overview(
     state: State<'_, AppState>,
-) -> Result<MarketOverview, String> {
+) -> Result<MarketOverview, AppError> {
     log::info!("[market_cmd] get_market_overview");
     let settings = state.db.load_settings().map_err(|e| {
         log::error!("[market_cmd] load_settings failed: {}", e);
-        e.to_string()
+        AppError::from(e)
     })?;
 
     market_overview::fetch_overview(&settings).await.map_err(|e| {
         log::error!("[market_cmd] fetch_overview failed: {}", e);
-        e.to_string()
+        AppError::from(e)
     })
 }
 
@@ -22,19 +37,312 @@ pub async fn get_market_overview(
 pub async fn generate_market_comment(
     state: State<'_, AppState>,
     overview_json: String,
-) -> Result<String, String> {
+) -> Result<String, AppError> {
     log::info!("[market_cmd] generate_market_comment");
-    let settings = state.db.load_settings().map_err(|e| e.to_string())?;
+    let settings = state.db.load_settings().map_err(AppError::from)?;
 
     let config = settings.ai_configs.iter()
         .find(|c| {
             settings.active_ai_config_id.as_deref() == Some(&c.id) && c.enabled
         })
         .or_else(|| settings.ai_configs.iter().find(|c| c.enabled))
-        .ok_or_else(|| "未配置 AI 模型，无法生成盘面解说".to_string())?;
+        .ok_or_else(|| AppError::NotConfigured("未配置 AI 模型，无法生成盘面解说".to_string()))?;
 
     market_overview::generate_market_comment(config, &overview_json).await.map_err(|e| {
         log::error!("[market_cmd] generate_market_comment failed: {}", e);
-        e.to_string()
+        AppError::from(e)
+    })
+}
+
+/// 形态选股：扫描本地日线缓存（或指定板块），返回最近 within_days 日内触发指定信号的股票
+#[tauri::command]
+pub async fn screen_by_signal(
+    state: State<'_, AppState>,
+    signal_type: String,
+    board_code: Option<String>,
+    within_days: Option<usize>,
+) -> Result<Vec<SignalScreenHit>, AppError> {
+    log::info!("[market_cmd] screen_by_signal signal={} board={:?}", signal_type, board_code);
+    let within_days = within_days.unwrap_or(3);
+    signal_screener::screen_by_signal(&state.db, &signal_type, board_code.as_deref(), within_days)
+        .await
+        .map_err(|e| {
+            log::error!("[market_cmd] screen_by_signal failed: {}", e);
+            AppError::from(e)
+        })
+}
+
+/// 增量刷新预计算的技术指标，force 为 true 时全部重算（修改信号参数后使用）
+#[tauri::command]
+pub async fn refresh_technical_daily(
+    state: State<'_, AppState>,
+    force: Option<bool>,
+) -> Result<usize, AppError> {
+    log::info!("[market_cmd] refresh_technical_daily force={:?}", force);
+    technical_store::refresh_technical_daily(&state.db, None, force.unwrap_or(false)).map_err(|e| {
+        log::error!("[market_cmd] refresh_technical_daily failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+#[tauri::command]
+pub async fn get_technical_daily(
+    state: State<'_, AppState>,
+    code: String,
+) -> Result<Option<TechnicalDaily>, AppError> {
+    state.db.get_technical_daily(&code).map_err(|e| {
+        log::error!("[market_cmd] get_technical_daily failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 生成当日早盘备忘，已生成过时直接返回（force 为 true 时重新生成），完成后推送 morning-briefing 事件
+#[tauri::command]
+pub async fn generate_morning_briefing(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    force: Option<bool>,
+) -> Result<MarketBriefing, AppError> {
+    log::info!("[market_cmd] generate_morning_briefing force={:?}", force);
+    if !force.unwrap_or(false) {
+        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+        if let Ok(Some(existing)) = state.db.get_market_briefing(&today, briefing::MORNING_KIND) {
+            return Ok(existing);
+        }
+    }
+
+    let settings = state.db.load_settings().map_err(AppError::from)?;
+    let config = settings.active_ai_config().ok_or_else(|| {
+        log::error!("[market_cmd] generate_morning_briefing: 未配置AI模型");
+        "未配置AI模型".to_string()
+    })?;
+
+    let result = briefing::generate_morning_briefing(&state.db, &config).await.map_err(|e| {
+        log::error!("[market_cmd] generate_morning_briefing failed: {}", e);
+        AppError::from(e)
+    })?;
+    let _ = app.emit(briefing::MORNING_BRIEFING_EVENT, &result);
+    Ok(result)
+}
+
+/// 查询指定日期、类型的市场日志，date 为空时取今天
+#[tauri::command]
+pub async fn get_market_briefing(
+    state: State<'_, AppState>,
+    date: Option<String>,
+    kind: String,
+) -> Result<Option<MarketBriefing>, AppError> {
+    let date = date.unwrap_or_else(|| chrono::Local::now().format("%Y-%m-%d").to_string());
+    state.db.get_market_briefing(&date, &kind).map_err(|e| {
+        log::error!("[market_cmd] get_market_briefing failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 市场日志转语音（mp3，保存在应用数据目录 audio 下），date 为空时取今天，返回文件记录
+#[tauri::command]
+pub async fn export_briefing_audio(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    date: Option<String>,
+    kind: String,
+) -> Result<AudioExport, AppError> {
+    let date = date.unwrap_or_else(|| chrono::Local::now().format("%Y-%m-%d").to_string());
+    log::info!("[market_cmd] export_briefing_audio date={} kind={}", date, kind);
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::from(e).context("获取数据目录失败"))?;
+    tts::export_briefing(&state.db, &dir, &date, &kind).await.map_err(|e| {
+        log::error!("[market_cmd] export_briefing_audio failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 查询已导出的语音文件，source_kind 为 briefing（source_id 为 "日期/类型"）或 analysis（source_id 为分析记录 id）
+#[tauri::command]
+pub async fn get_audio_export(
+    state: State<'_, AppState>,
+    source_kind: String,
+    source_id: String,
+) -> Result<Option<AudioExport>, AppError> {
+    let export = state.db.get_audio_export(&source_kind, &source_id).map_err(|e| {
+        log::error!("[market_cmd] get_audio_export failed: {}", e);
+        AppError::from(e)
+    })?;
+    // 文件被手动删除时视为未导出
+    Ok(export.filter(|e| std::path::Path::new(&e.path).exists()))
+}
+
+/// 用系统默认播放器打开已导出的语音文件
+#[tauri::command]
+pub async fn open_audio_export(
+    state: State<'_, AppState>,
+    source_kind: String,
+    source_id: String,
+) -> Result<(), AppError> {
+    let export = state
+        .db
+        .get_audio_export(&source_kind, &source_id)
+        .map_err(AppError::from)?
+        .filter(|e| std::path::Path::new(&e.path).exists())
+        .ok_or_else(|| AppError::NotFound("语音文件不存在，请重新生成".to_string()))?;
+    log::info!("[market_cmd] open_audio_export {}", export.path);
+
+    let mut command = if cfg!(target_os = "windows") {
+        let mut c = std::process::Command::new("cmd");
+        c.args(["/C", "start", ""]);
+        c
+    } else if cfg!(target_os = "macos") {
+        std::process::Command::new("open")
+    } else {
+        std::process::Command::new("xdg-open")
+    };
+    command.arg(&export.path).spawn().map_err(|e| {
+        log::error!("[market_cmd] open_audio_export failed: {}", e);
+        AppError::from(e).context("打开语音文件失败")
+    })?;
+    Ok(())
+}
+
+/// 生成当日收盘复盘（仅收盘后可用），已生成过时直接返回（force 为 true 时重新生成）
+#[tauri::command]
+pub async fn generate_daily_review(
+    state: State<'_, AppState>,
+    force: Option<bool>,
+) -> Result<MarketBriefing, AppError> {
+    log::info!("[market_cmd] generate_daily_review force={:?}", force);
+    if !force.unwrap_or(false) {
+        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+        if let Ok(Some(existing)) = state.db.get_market_briefing(&today, briefing::REVIEW_KIND) {
+            return Ok(existing);
+        }
+    }
+
+    let settings = state.db.load_settings().map_err(AppError::from)?;
+    let config = settings.active_ai_config().ok_or_else(|| {
+        log::error!("[market_cmd] generate_daily_review: 未配置AI模型");
+        "未配置AI模型".to_string()
+    })?;
+
+    briefing::generate_daily_review(&state.db, &settings, &config).await.map_err(|e| {
+        log::error!("[market_cmd] generate_daily_review failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 检索市场日志（早盘备忘 / 收盘复盘），keyword 为空时按日期倒序列出
+#[tauri::command]
+pub async fn search_market_journal(
+    state: State<'_, AppState>,
+    keyword: Option<String>,
+    kind: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<MarketBriefing>, AppError> {
+    let keyword = keyword.unwrap_or_default();
+    state.db.search_market_briefing(keyword.trim(), kind.as_deref(), limit.unwrap_or(30)).map_err(|e| {
+        log::error!("[market_cmd] search_market_journal failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// A股数量统计：主板/创业板/科创板/北交所、停牌、ST、本月新股
+#[tauri::command]
+pub async fn get_market_stock_count() -> Result<MarketStockCount, AppError> {
+    log::info!("[market_cmd] get_market_stock_count");
+    let scanner = MarketScanner::new().map_err(AppError::from)?;
+    scanner.fetch_market_stock_count().await.map_err(|e| {
+        log::error!("[market_cmd] get_market_stock_count failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 实时市场宽度：涨跌家数、涨跌停，以及沪深300样本的均线站上比例与 52 周新高新低（基于本地日线缓存）
+#[tauri::command]
+pub async fn get_market_breadth(state: State<'_, AppState>) -> Result<MarketBreadth, AppError> {
+    log::info!("[market_cmd] get_market_breadth");
+    market_breadth::compute_breadth(&state.db, false).await.map_err(|e| {
+        log::error!("[market_cmd] get_market_breadth failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 历史市场宽度（收盘后自动记录），按日期升序
+#[tauri::command]
+pub async fn get_market_breadth_history(
+    state: State<'_, AppState>,
+    days: Option<usize>,
+) -> Result<Vec<MarketBreadth>, AppError> {
+    let days = days.unwrap_or(60);
+    log::info!("[market_cmd] get_market_breadth_history days={}", days);
+    state.db.get_market_breadth_history(days).map_err(|e| {
+        log::error!("[market_cmd] get_market_breadth_history failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 当前市场状态：趋势、波动、大小盘风格，结合市场宽度与两市量能判定（按交易日缓存）
+#[tauri::command]
+pub async fn get_market_regime(state: State<'_, AppState>) -> Result<MarketRegime, AppError> {
+    log::info!("[market_cmd] get_market_regime");
+    market_regime::current(&state.db).await.map_err(|e| {
+        log::error!("[market_cmd] get_market_regime failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 板块热力图：全部行业与概念板块的涨跌幅、换手率、成交额、主力净流入与领涨股（30 秒缓存）
+#[tauri::command]
+pub async fn get_market_heatmap() -> Result<MarketHeatmap, AppError> {
+    log::info!("[market_cmd] get_market_heatmap");
+    market_heatmap::get_market_heatmap().await.map(|h| (*h).clone()).map_err(|e| {
+        log::error!("[market_cmd] get_market_heatmap failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// RPS 选股：按 period（20/60/120 日）相对强度降序返回 RPS 不低于 min_rps 的股票
+#[tauri::command]
+pub async fn screen_top_rps(
+    state: State<'_, AppState>,
+    period: Option<u32>,
+    min_rps: Option<f64>,
+    limit: Option<usize>,
+) -> Result<Vec<StockRps>, AppError> {
+    let period = period.unwrap_or(60);
+    let min_rps = min_rps.unwrap_or(90.0);
+    let limit = limit.unwrap_or(100);
+    log::info!("[market_cmd] screen_top_rps period={} min_rps={} limit={}", period, min_rps, limit);
+    rps::top_rps(&state.db, period, min_rps, limit).map_err(|e| {
+        log::error!("[market_cmd] screen_top_rps failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 查询个股最近一次计算的 RPS，尚未计算时返回 None
+#[tauri::command]
+pub async fn get_stock_rps(state: State<'_, AppState>, code: String) -> Result<Option<StockRps>, AppError> {
+    log::info!("[market_cmd] get_stock_rps code={}", code);
+    rps::load(&state.db).map(|t| t.get(&code).cloned()).map_err(|e| {
+        log::error!("[market_cmd] get_stock_rps failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 板块轮动：按 window（3/5/10 个记录日）排名变化返回升温与降温的行业或概念板块（收盘后自动记录排名）
+#[tauri::command]
+pub async fn get_board_rotation(
+    state: State<'_, AppState>,
+    kind: Option<String>,
+    window: Option<usize>,
+    limit: Option<usize>,
+) -> Result<BoardRotation, AppError> {
+    let kind = kind.unwrap_or_else(|| "concept".to_string());
+    let window = window.unwrap_or(5);
+    let limit = limit.unwrap_or(20);
+    log::info!("[market_cmd] get_board_rotation kind={} window={} limit={}", kind, window, limit);
+    board_rotation::get_board_rotation(&state.db, &kind, window, limit).map_err(|e| {
+        log::error!("[market_cmd] get_board_rotation failed: {}", e);
+        AppError::from(e)
     })
 }
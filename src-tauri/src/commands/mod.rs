@@ -7,3 +7,4 @@ pub mod news_cmd;
 pub mod ai_pick_cmd;
 pub mod tracking_cmd;
 pub mod market_cmd;
+pub mod window_cmd;
@@ -1,15 +1,16 @@
 use crate::models::news::{AnnouncementItem, NewsItem, ReportItem};
 use crate::services::news_service;
+use crate::error::AppError;
 
 /// 获取财联社电报快讯
 #[tauri::command]
-pub async fn fetch_cls_telegraph(count: Option<u32>) -> Result<Vec<NewsItem>, String> {
+pub async fn fetch_cls_telegraph(count: Option<u32>) -> Result<Vec<NewsItem>, AppError> {
     let count = count.unwrap_or(30);
     news_service::fetch_cls_telegraph(count)
         .await
         .map_err(|e| {
             log::error!("[news_cmd] fetch_cls_telegraph failed: {}", e);
-            format!("获取财联社快讯失败: {}", e)
+            AppError::from(e).context("获取财联社快讯失败")
         })
 }
 
@@ -18,14 +19,14 @@ pub async fn fetch_cls_telegraph(count: Option<u32>) -> Result<Vec<NewsItem>, St
 pub async fn fetch_eastmoney_news(
     page: Option<u32>,
     page_size: Option<u32>,
-) -> Result<Vec<NewsItem>, String> {
+) -> Result<Vec<NewsItem>, AppError> {
     let page = page.unwrap_or(1);
     let page_size = page_size.unwrap_or(20);
     news_service::fetch_eastmoney_news(page, page_size)
         .await
         .map_err(|e| {
             log::error!("[news_cmd] fetch_eastmoney_news failed: {}", e);
-            format!("获取东方财富新闻失败: {}", e)
+            AppError::from(e).context("获取东方财富新闻失败")
         })
 }
 
@@ -35,14 +36,14 @@ pub async fn fetch_stock_news(
     keyword: String,
     page: Option<u32>,
     page_size: Option<u32>,
-) -> Result<Vec<NewsItem>, String> {
+) -> Result<Vec<NewsItem>, AppError> {
     let page = page.unwrap_or(1);
     let page_size = page_size.unwrap_or(10);
     news_service::fetch_stock_news(&keyword, page, page_size)
         .await
         .map_err(|e| {
             log::error!("[news_cmd] fetch_stock_news keyword={} failed: {}", keyword, e);
-            format!("获取个股新闻失败: {}", e)
+            AppError::from(e).context("获取个股新闻失败")
         })
 }
 
@@ -52,14 +53,14 @@ pub async fn fetch_announcements(
     stock_code: Option<String>,
     page: Option<u32>,
     page_size: Option<u32>,
-) -> Result<Vec<AnnouncementItem>, String> {
+) -> Result<Vec<AnnouncementItem>, AppError> {
     let page = page.unwrap_or(1);
     let page_size = page_size.unwrap_or(20);
     news_service::fetch_announcements(stock_code.as_deref(), page, page_size)
         .await
         .map_err(|e| {
             log::error!("[news_cmd] fetch_announcements failed: {}", e);
-            format!("获取公司公告失败: {}", e)
+            AppError::from(e).context("获取公司公告失败")
         })
 }
 
@@ -69,14 +70,14 @@ pub async fn fetch_reports(
     stock_code: Option<String>,
     page: Option<u32>,
     page_size: Option<u32>,
-) -> Result<Vec<ReportItem>, String> {
+) -> Result<Vec<ReportItem>, AppError> {
     let page = page.unwrap_or(1);
     let page_size = page_size.unwrap_or(20);
     news_service::fetch_reports(stock_code.as_deref(), page, page_size)
         .await
         .map_err(|e| {
             log::error!("[news_cmd] fetch_reports failed: {}", e);
-            format!("获取研报失败: {}", e)
+            AppError::from(e).context("获取研报失败")
         })
 }
 
@@ -85,14 +86,14 @@ pub async fn fetch_reports(
 pub async fn fetch_sina_news(
     page: Option<u32>,
     count: Option<u32>,
-) -> Result<Vec<NewsItem>, String> {
+) -> Result<Vec<NewsItem>, AppError> {
     let page = page.unwrap_or(1);
     let count = count.unwrap_or(20);
     news_service::fetch_sina_roll_news(page, count)
         .await
         .map_err(|e| {
             log::error!("[news_cmd] fetch_sina_news failed: {}", e);
-            format!("获取新浪新闻失败: {}", e)
+            AppError::from(e).context("获取新浪新闻失败")
         })
 }
 
@@ -100,13 +101,13 @@ pub async fn fetch_sina_news(
 #[tauri::command]
 pub async fn fetch_sina_7x24(
     count: Option<u32>,
-) -> Result<Vec<NewsItem>, String> {
+) -> Result<Vec<NewsItem>, AppError> {
     let count = count.unwrap_or(30);
     news_service::fetch_sina_7x24(count)
         .await
         .map_err(|e| {
             log::error!("[news_cmd] fetch_sina_7x24 failed: {}", e);
-            format!("获取新浪7x24快讯失败: {}", e)
+            AppError::from(e).context("获取新浪7x24快讯失败")
         })
 }
 
@@ -114,12 +115,12 @@ pub async fn fetch_sina_7x24(
 #[tauri::command]
 pub async fn fetch_wallstreetcn_lives(
     count: Option<u32>,
-) -> Result<Vec<NewsItem>, String> {
+) -> Result<Vec<NewsItem>, AppError> {
     let count = count.unwrap_or(30);
     news_service::fetch_wallstreetcn_lives(count)
         .await
         .map_err(|e| {
             log::error!("[news_cmd] fetch_wallstreetcn_lives failed: {}", e);
-            format!("获取华尔街见闻快讯失败: {}", e)
+            AppError::from(e).context("获取华尔街见闻快讯失败")
         })
 }
@@ -1,43 +1,184 @@
 use tauri::{AppHandle, Manager, State};
 use crate::AppState;
-use crate::models::settings::AppSettings;
-use crate::models::ai::AIConfig;
+use crate::models::settings::{ApiServerStatus, AppSettings, NotifyTarget, SyncConfig};
+use crate::models::ai::{AIConfig, AIConnectionTest};
+use crate::models::deep_link::DeepLink;
+use crate::models::diagnostics::DiagnosticsReport;
+use crate::models::job::{JobRun, ScheduledJob};
+use crate::models::sync::{SyncResult, SyncStatus};
 use crate::services::ai_service::AIService;
+use crate::services::api_server;
+use crate::services::deep_link;
+use crate::services::diagnostics;
+use crate::services::hotkey;
+use crate::services::job_scheduler;
+use crate::services::notifier;
+use crate::services::settings_io;
+use crate::services::smart_stock::{self, SmartStockService};
+use crate::services::pick_constraints;
+use crate::services::strategy_zone;
+use crate::services::sync;
+use crate::utils::http;
+use crate::error::AppError;
+use crate::utils::http::SendLogged;
 
 #[tauri::command]
 pub async fn get_settings(
     state: State<'_, AppState>,
-) -> Result<AppSettings, String> {
+) -> Result<AppSettings, AppError> {
     state.db.load_settings().map_err(|e| {
         log::error!("[settings_cmd] get_settings failed: {}", e);
-        e.to_string()
+        AppError::from(e)
     })
 }
 
 #[tauri::command]
 pub async fn save_settings(
+    app: AppHandle,
     state: State<'_, AppState>,
     settings: AppSettings,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     log::info!("[settings_cmd] save_settings");
+    strategy_zone::validate_zones(&settings.strategy_zones)?;
+    pick_constraints::validate(&settings.pick_preferences)?;
+    hotkey::parse(&settings.quick_search_hotkey).map_err(|e| AppError::InvalidInput(e.to_string()))?;
+    let previous_hotkey = state.db.load_settings().map(|s| s.quick_search_hotkey).unwrap_or_default();
     state.db.save_settings(&settings).map_err(|e| {
         log::error!("[settings_cmd] save_settings failed: {}", e);
-        e.to_string()
-    })
+        AppError::from(e)
+    })?;
+    http::set_request_logging(settings.debug_logging);
+    api_server::apply(&app, &settings.api_server).await;
+    if settings.quick_search_hotkey != previous_hotkey {
+        hotkey::apply(&app, &settings.quick_search_hotkey).map_err(|e| {
+            log::error!("[settings_cmd] register hotkey failed: {}", e);
+            AppError::from(e).context("快捷键注册失败，可能已被其他程序占用")
+        })?;
+    }
+    Ok(())
+}
+
+/// 导出设置到 JSON 文件（系统对话框选择保存位置），include_secrets 为 false 时不含 API Key 与东财用户标识
+#[tauri::command]
+pub async fn export_settings(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    include_secrets: bool,
+) -> Result<String, AppError> {
+    use tauri_plugin_dialog::DialogExt;
+
+    log::info!("[settings_cmd] export_settings include_secrets={}", include_secrets);
+    let settings = state.db.load_settings().map_err(AppError::from)?;
+    let json = settings_io::export_json(&settings, include_secrets).map_err(|e| {
+        log::error!("[settings_cmd] export_settings failed: {}", e);
+        AppError::from(e)
+    })?;
+
+    let file_path = app
+        .dialog()
+        .file()
+        .set_title("导出设置")
+        .set_file_name("stock-helper-settings.json")
+        .add_filter("JSON 文件", &["json"])
+        .blocking_save_file();
+    let Some(path) = file_path.and_then(|p| p.into_path().ok()) else {
+        log::info!("[settings_cmd] export_settings cancelled by user");
+        return Err(AppError::Cancelled("用户取消了导出".to_string()));
+    };
+    std::fs::write(&path, json).map_err(|e| AppError::from(e).context("保存文件失败"))?;
+    log::info!("[settings_cmd] export_settings saved to {:?}", path);
+    Ok(if include_secrets { "设置已导出（含 API Key）".to_string() } else { "设置已导出（不含 API Key）".to_string() })
+}
+
+/// 从 JSON 文件导入设置（系统对话框选择文件），文件中缺少的 API Key 沿用当前配置
+#[tauri::command]
+pub async fn import_settings(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<AppSettings, AppError> {
+    use tauri_plugin_dialog::DialogExt;
+
+    log::info!("[settings_cmd] import_settings");
+    let file_path = app
+        .dialog()
+        .file()
+        .set_title("导入设置")
+        .add_filter("JSON 文件", &["json"])
+        .blocking_pick_file();
+    let Some(path) = file_path.and_then(|p| p.into_path().ok()) else {
+        log::info!("[settings_cmd] import_settings cancelled by user");
+        return Err(AppError::Cancelled("用户取消了导入".to_string()));
+    };
+    let json = std::fs::read_to_string(&path).map_err(|e| AppError::from(e).context("读取设置文件失败"))?;
+
+    let current = state.db.load_settings().map_err(AppError::from)?;
+    let settings = settings_io::import_json(&current, &json).map_err(|e| {
+        log::error!("[settings_cmd] import_settings failed: {}", e);
+        AppError::InvalidInput(e.to_string())
+    })?;
+    state.db.save_settings(&settings).map_err(AppError::from)?;
+    http::set_request_logging(settings.debug_logging);
+    api_server::apply(&app, &settings.api_server).await;
+    if let Err(e) = hotkey::apply(&app, &settings.quick_search_hotkey) {
+        log::warn!("[settings_cmd] register hotkey failed: {}", e);
+    }
+    log::info!("[settings_cmd] import_settings loaded from {:?}", path);
+    Ok(settings)
+}
+
+/// 将当前策略与 AI 参数保存为命名方案，同名方案覆盖
+#[tauri::command]
+pub async fn save_settings_profile(
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<AppSettings, AppError> {
+    log::info!("[settings_cmd] save_settings_profile name={}", name);
+    let mut settings = state.db.load_settings().map_err(AppError::from)?;
+    settings_io::save_profile(&mut settings, &name).map_err(|e| AppError::InvalidInput(e.to_string()))?;
+    state.db.save_settings(&settings).map_err(AppError::from)?;
+    Ok(settings)
+}
+
+/// 切换到指定设置方案
+#[tauri::command]
+pub async fn apply_settings_profile(
+    state: State<'_, AppState>,
+    profile_id: String,
+) -> Result<AppSettings, AppError> {
+    log::info!("[settings_cmd] apply_settings_profile id={}", profile_id);
+    let mut settings = state.db.load_settings().map_err(AppError::from)?;
+    settings_io::apply_profile(&mut settings, &profile_id).map_err(|e| {
+        log::error!("[settings_cmd] apply_settings_profile failed: {}", e);
+        AppError::from(e)
+    })?;
+    state.db.save_settings(&settings).map_err(AppError::from)?;
+    Ok(settings)
+}
+
+#[tauri::command]
+pub async fn delete_settings_profile(
+    state: State<'_, AppState>,
+    profile_id: String,
+) -> Result<AppSettings, AppError> {
+    log::info!("[settings_cmd] delete_settings_profile id={}", profile_id);
+    let mut settings = state.db.load_settings().map_err(AppError::from)?;
+    settings_io::delete_profile(&mut settings, &profile_id);
+    state.db.save_settings(&settings).map_err(AppError::from)?;
+    Ok(settings)
 }
 
 #[tauri::command]
 pub async fn add_ai_config(
     state: State<'_, AppState>,
     config: AIConfig,
-) -> Result<AppSettings, String> {
+) -> Result<AppSettings, AppError> {
     log::info!("[settings_cmd] add_ai_config model={}", config.model_name);
-    let mut settings = state.db.load_settings().map_err(|e| e.to_string())?;
+    let mut settings = state.db.load_settings().map_err(AppError::from)?;
     settings.ai_configs.push(config);
     if settings.active_ai_config_id.is_none() {
         settings.active_ai_config_id = settings.ai_configs.first().map(|c| c.id.clone());
     }
-    state.db.save_settings(&settings).map_err(|e| e.to_string())?;
+    state.db.save_settings(&settings).map_err(AppError::from)?;
     Ok(settings)
 }
 
@@ -45,14 +186,14 @@ pub async fn add_ai_config(
 pub async fn remove_ai_config(
     state: State<'_, AppState>,
     config_id: String,
-) -> Result<AppSettings, String> {
+) -> Result<AppSettings, AppError> {
     log::info!("[settings_cmd] remove_ai_config id={}", config_id);
-    let mut settings = state.db.load_settings().map_err(|e| e.to_string())?;
+    let mut settings = state.db.load_settings().map_err(AppError::from)?;
     settings.ai_configs.retain(|c| c.id != config_id);
     if settings.active_ai_config_id.as_deref() == Some(&config_id) {
         settings.active_ai_config_id = settings.ai_configs.first().map(|c| c.id.clone());
     }
-    state.db.save_settings(&settings).map_err(|e| e.to_string())?;
+    state.db.save_settings(&settings).map_err(AppError::from)?;
     Ok(settings)
 }
 
@@ -60,13 +201,13 @@ pub async fn remove_ai_config(
 pub async fn update_ai_config(
     state: State<'_, AppState>,
     config: AIConfig,
-) -> Result<AppSettings, String> {
+) -> Result<AppSettings, AppError> {
     log::info!("[settings_cmd] update_ai_config model={}", config.model_name);
-    let mut settings = state.db.load_settings().map_err(|e| e.to_string())?;
+    let mut settings = state.db.load_settings().map_err(AppError::from)?;
     if let Some(existing) = settings.ai_configs.iter_mut().find(|c| c.id == config.id) {
         *existing = config;
     }
-    state.db.save_settings(&settings).map_err(|e| e.to_string())?;
+    state.db.save_settings(&settings).map_err(AppError::from)?;
     Ok(settings)
 }
 
@@ -74,11 +215,11 @@ pub async fn update_ai_config(
 pub async fn set_active_ai_config(
     state: State<'_, AppState>,
     config_id: String,
-) -> Result<AppSettings, String> {
+) -> Result<AppSettings, AppError> {
     log::info!("[settings_cmd] set_active_ai_config id={}", config_id);
-    let mut settings = state.db.load_settings().map_err(|e| e.to_string())?;
+    let mut settings = state.db.load_settings().map_err(AppError::from)?;
     settings.active_ai_config_id = Some(config_id);
-    state.db.save_settings(&settings).map_err(|e| e.to_string())?;
+    state.db.save_settings(&settings).map_err(AppError::from)?;
     Ok(settings)
 }
 
@@ -86,17 +227,17 @@ pub async fn set_active_ai_config(
 #[tauri::command]
 pub async fn test_ai_config(
     config: AIConfig,
-) -> Result<String, String> {
+) -> Result<AIConnectionTest, AppError> {
     log::info!("[settings_cmd] test_ai_config model={}", config.model_name);
     AIService::test_ai_connection(&config).await.map_err(|e| {
         log::error!("[settings_cmd] test_ai_config failed: {}", e);
-        e.to_string()
+        AppError::from(e)
     })
 }
 
 /// 检查版本更新：通过 GitHub redirect 机制获取最新 release 版本号，再按需拉详情
 #[tauri::command]
-pub async fn check_update(app: AppHandle) -> Result<Option<serde_json::Value>, String> {
+pub async fn check_update(app: AppHandle) -> Result<Option<serde_json::Value>, AppError> {
     log::info!("[settings_cmd] check_update");
 
     let current_version = app.config().version.clone().unwrap_or_default();
@@ -106,16 +247,16 @@ pub async fn check_update(app: AppHandle) -> Result<Option<serde_json::Value>, S
         .timeout(std::time::Duration::from_secs(15))
         .redirect(reqwest::redirect::Policy::none()) // 不跟随重定向
         .build()
-        .map_err(|e| format!("创建 HTTP 客户端失败: {}", e))?;
+        .map_err(|e| AppError::from(e).context("创建 HTTP 客户端失败"))?;
 
     // 第一步：HEAD 请求 latest release 页面，从 302 Location 中提取版本号
     // 这不走 API 速率限制
     let resp = client
         .head("https://github.com/2yd/stock-helper-v2/releases/latest")
         .header("User-Agent", "StockHelper")
-        .send()
+        .send_logged()
         .await
-        .map_err(|e| format!("检查更新失败: {}", e))?;
+        .map_err(|e| AppError::from(e).context("检查更新失败"))?;
 
     let location = match resp.headers().get("location") {
         Some(loc) => loc.to_str().unwrap_or("").to_string(),
@@ -162,13 +303,13 @@ pub async fn check_update(app: AppHandle) -> Result<Option<serde_json::Value>, S
     let api_client = reqwest::Client::builder()
         .timeout(std::time::Duration::from_secs(10))
         .build()
-        .map_err(|e| format!("创建 HTTP 客户端失败: {}", e))?;
+        .map_err(|e| AppError::from(e).context("创建 HTTP 客户端失败"))?;
 
     let detail = api_client
         .get(&release_url)
         .header("User-Agent", "StockHelper")
         .header("Accept", "application/vnd.github.v3+json")
-        .send()
+        .send_logged()
         .await;
 
     let (body, published_at) = match detail {
@@ -224,9 +365,33 @@ fn compare_versions(latest: &str, current: &str) -> bool {
     false
 }
 
+/// 在系统文件管理器中打开日志目录，返回目录路径
+#[tauri::command]
+pub async fn open_log_dir(app: AppHandle) -> Result<String, AppError> {
+    let log_dir = app
+        .path()
+        .app_log_dir()
+        .map_err(|e| AppError::from(e).context("获取日志目录失败"))?;
+    log::info!("[settings_cmd] open_log_dir {:?}", log_dir);
+    std::fs::create_dir_all(&log_dir).map_err(|e| AppError::from(e).context("创建日志目录失败"))?;
+
+    let opener = if cfg!(target_os = "windows") {
+        "explorer"
+    } else if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    };
+    std::process::Command::new(opener).arg(&log_dir).spawn().map_err(|e| {
+        log::error!("[settings_cmd] open_log_dir failed: {}", e);
+        AppError::from(e).context("打开日志目录失败")
+    })?;
+    Ok(log_dir.to_string_lossy().to_string())
+}
+
 /// 导出日志：将 app_log_dir 下所有日志文件打包为 zip，通过系统对话框让用户选择保存位置
 #[tauri::command]
-pub async fn export_logs(app: AppHandle) -> Result<String, String> {
+pub async fn export_logs(app: AppHandle) -> Result<String, AppError> {
     use std::io::{Read, Write};
     use tauri_plugin_dialog::DialogExt;
     use zip::write::SimpleFileOptions;
@@ -237,15 +402,15 @@ pub async fn export_logs(app: AppHandle) -> Result<String, String> {
     let log_dir = app
         .path()
         .app_log_dir()
-        .map_err(|e| format!("获取日志目录失败: {}", e))?;
+        .map_err(|e| AppError::from(e).context("获取日志目录失败"))?;
 
     if !log_dir.exists() {
-        return Err("日志目录不存在".into());
+        return Err(AppError::NotFound("日志目录不存在".to_string()));
     }
 
     // 收集所有日志文件
     let entries = std::fs::read_dir(&log_dir)
-        .map_err(|e| format!("读取日志目录失败: {}", e))?;
+        .map_err(|e| AppError::from(e).context("读取日志目录失败"))?;
 
     let log_files: Vec<_> = entries
         .filter_map(|e| e.ok())
@@ -260,7 +425,7 @@ pub async fn export_logs(app: AppHandle) -> Result<String, String> {
         .collect();
 
     if log_files.is_empty() {
-        return Err("没有找到日志文件".into());
+        return Err(AppError::NotFound("没有找到日志文件".to_string()));
     }
 
     log::info!(
@@ -283,22 +448,22 @@ pub async fn export_logs(app: AppHandle) -> Result<String, String> {
             .to_string();
 
         let mut file = std::fs::File::open(&path)
-            .map_err(|e| format!("打开日志文件 {} 失败: {}", file_name, e))?;
+            .map_err(|e| AppError::from(e).context(&format!("打开日志文件 {} 失败", file_name)))?;
         let mut contents = Vec::new();
         file.read_to_end(&mut contents)
-            .map_err(|e| format!("读取日志文件 {} 失败: {}", file_name, e))?;
+            .map_err(|e| AppError::from(e).context(&format!("读取日志文件 {} 失败", file_name)))?;
 
         zip_writer
             .start_file(&file_name, options)
-            .map_err(|e| format!("写入 zip 失败: {}", e))?;
+            .map_err(|e| AppError::from(e).context("写入 zip 失败"))?;
         zip_writer
             .write_all(&contents)
-            .map_err(|e| format!("写入 zip 内容失败: {}", e))?;
+            .map_err(|e| AppError::from(e).context("写入 zip 内容失败"))?;
     }
 
     let cursor = zip_writer
         .finish()
-        .map_err(|e| format!("完成 zip 文件失败: {}", e))?;
+        .map_err(|e| AppError::from(e).context("完成 zip 文件失败"))?;
     let zip_data = cursor.into_inner();
 
     // 通过系统对话框让用户选择保存位置
@@ -313,7 +478,7 @@ pub async fn export_logs(app: AppHandle) -> Result<String, String> {
     match file_path {
         Some(path) => {
             std::fs::write(path.as_path().unwrap(), &zip_data)
-                .map_err(|e| format!("保存文件失败: {}", e))?;
+                .map_err(|e| AppError::from(e).context("保存文件失败"))?;
             log::info!("[settings_cmd] export_logs saved to {:?}", path);
             Ok(format!("日志已导出（{} 个文件）", log_files.len()))
         }
@@ -323,3 +488,118 @@ pub async fn export_logs(app: AppHandle) -> Result<String, String> {
         }
     }
 }
+
+/// 自动获取东财用户标识（qgqp_b_id）并保存到设置；force 为 true 时忽略现有值重新获取
+#[tauri::command]
+pub async fn acquire_qgqp_b_id(
+    state: State<'_, AppState>,
+    force: Option<bool>,
+) -> Result<String, AppError> {
+    log::info!("[settings_cmd] acquire_qgqp_b_id force={:?}", force);
+    smart_stock::acquire_fingerprint(&state.db, force.unwrap_or(false)).await.map_err(|e| {
+        log::error!("[settings_cmd] acquire_qgqp_b_id failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 校验当前 qgqp_b_id 是否仍可用
+#[tauri::command]
+pub async fn validate_qgqp_b_id(
+    state: State<'_, AppState>,
+) -> Result<bool, AppError> {
+    let settings = state.db.load_settings().map_err(AppError::from)?;
+    SmartStockService::validate_fingerprint(&settings.qgqp_b_id).await.map_err(|e| {
+        log::error!("[settings_cmd] validate_qgqp_b_id failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 全部后台任务的调度配置与最近运行记录
+#[tauri::command]
+pub async fn get_scheduled_jobs(app: AppHandle) -> Result<Vec<ScheduledJob>, AppError> {
+    job_scheduler::list_jobs(&app).map_err(|e| {
+        log::error!("[settings_cmd] get_scheduled_jobs failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 立即执行一次后台任务（忽略启用状态与调度时间），任务无事可做时返回 null
+#[tauri::command]
+pub async fn run_scheduled_job(app: AppHandle, id: String) -> Result<Option<JobRun>, AppError> {
+    job_scheduler::run_job_now(&app, &id).await.map_err(|e| {
+        log::error!("[settings_cmd] run_scheduled_job failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 诊断报告：数据源连通性与延迟、数据库与缓存状态、后台任务最近成功时间
+#[tauri::command]
+pub async fn get_diagnostics(app: AppHandle) -> Result<DiagnosticsReport, AppError> {
+    log::info!("[settings_cmd] get_diagnostics");
+    diagnostics::collect(&app).await.map_err(|e| {
+        log::error!("[settings_cmd] get_diagnostics failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 立即执行一次多设备同步
+#[tauri::command]
+pub async fn sync_now(state: State<'_, AppState>) -> Result<SyncResult, AppError> {
+    log::info!("[settings_cmd] sync_now");
+    let _guard = state.shutdown.track();
+    sync::sync_now(&state.db).await.map_err(|e| {
+        log::error!("[settings_cmd] sync_now failed: {}", e);
+        AppError::from(e).context("同步失败")
+    })
+}
+
+/// 测试同步服务连接（使用未保存的配置）
+#[tauri::command]
+pub async fn test_sync_connection(config: SyncConfig) -> Result<String, AppError> {
+    log::info!("[settings_cmd] test_sync_connection backend={}", config.backend);
+    sync::test_connection(&config).await.map_err(|e| {
+        log::error!("[settings_cmd] test_sync_connection failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 本机设备标识与最近一次同步结果
+#[tauri::command]
+pub async fn get_sync_status(state: State<'_, AppState>) -> Result<SyncStatus, AppError> {
+    sync::get_status(&state.db).map_err(|e| {
+        log::error!("[settings_cmd] get_sync_status failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 本机 HTTP API 的运行状态
+#[tauri::command]
+pub async fn get_api_server_status() -> Result<ApiServerStatus, AppError> {
+    Ok(api_server::status())
+}
+
+/// 向指定推送目标发送一条测试消息（使用未保存的配置）
+#[tauri::command]
+pub async fn test_notify_target(target: NotifyTarget) -> Result<(), AppError> {
+    log::info!("[settings_cmd] test_notify_target kind={}", target.kind);
+    notifier::send(&target, "test", "测试消息", "来自股票助手的推送测试，收到说明配置正确。").await.map_err(|e| {
+        log::error!("[settings_cmd] test_notify_target failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 取走应用由 stockhelper:// 链接启动时待处理的跳转目标
+#[tauri::command]
+pub async fn take_pending_deep_link() -> Result<Option<DeepLink>, AppError> {
+    Ok(deep_link::take_pending())
+}
+
+/// 在主窗口打开 stockhelper:// 链接（快速查股窗口选中股票后跳转）
+#[tauri::command]
+pub async fn open_deep_link(app: AppHandle, url: String) -> Result<(), AppError> {
+    if deep_link::parse(&url).is_none() {
+        return Err(AppError::InvalidInput(format!("无效的链接: {}", url)));
+    }
+    deep_link::handle(&app, &url);
+    Ok(())
+}
@@ -1,27 +1,73 @@
-use tauri::State;
-use crate::models::stock::{StockInfo, KLineData, StockSearchResult, MarketStockSnapshot};
+use tauri::{AppHandle, State};
+use crate::models::f10::{EarningsForecast, PeerComparison, StockProfile, ValuationBand};
+use crate::models::news::AnnouncementStudyResult;
+use crate::models::watchlist::{IndexKline, Seasonality};
+use crate::models::settings::CustomQuoteProvider;
+use crate::models::stock::{StockInfo, KLineData, StockSearchResult, MarketStockSnapshot, SmartSearchQuery, IndexConstituents, HistorySyncResult, ExpressionScreenResult, FactorScore, ThemeExposure, BoardMember, ThemeStudyResult, FundFlowDay, FundFlowBreakdown, FocusMonitorStatus, AuctionScanResult};
+use crate::services::announcement_study;
+use crate::services::auction_scanner;
+use crate::services::board_members;
+use crate::services::f10_service;
+use crate::services::factor_score::{self, BenchmarkReturns};
+use crate::services::filter_expr;
+use crate::services::focus_monitor;
+use crate::services::fund_flow;
+use crate::services::history_kline::{self, HistoryKlineService};
+use crate::services::history_sync;
+use crate::services::index_constituents;
+use crate::services::peer_comparison;
+use crate::services::quote_provider::{self, CustomProvider, QuoteProvider};
+use crate::services::seasonality;
+use crate::services::valuation;
 use crate::services::stock_data::{StockDataService, format_stock_code};
-use crate::services::market_scanner::MarketScanner;
-use crate::utils::http::build_stock_client;
+use crate::services::smart_stock::{self, SmartStockResponse, SmartStockService};
+use crate::services::symbol_table;
+use crate::services::theme_exposure;
+use crate::services::theme_study;
+use crate::services::watchlist_enriched;
+use crate::utils::http::{build_stock_client, SendLogged};
 use crate::AppState;
+use crate::error::AppError;
 
 #[tauri::command]
 pub async fn get_realtime_data(
     state: State<'_, AppState>,
     codes: Vec<String>,
-) -> Result<Vec<StockInfo>, String> {
+) -> Result<Vec<StockInfo>, AppError> {
     log::info!("[stock_cmd] get_realtime_data codes_count={}", codes.len());
     let formatted: Vec<String> = codes.iter().map(|c| format_stock_code(c)).collect();
     let settings = state.db.load_settings().map_err(|e| {
         log::error!("[stock_cmd] get_realtime_data load_settings failed: {}", e);
-        e.to_string()
+        AppError::from(e)
     })?;
-    let use_sina = matches!(settings.data_source_primary, crate::models::settings::DataSource::Sina);
-
-    let service = StockDataService::new().map_err(|e| e.to_string())?;
-    service.get_realtime_batch(&formatted, use_sina).await.map_err(|e| {
+    quote_provider::fetch_quotes(&settings, &formatted).await.map_err(|e| {
         log::error!("[stock_cmd] get_realtime_data failed: {}", e);
-        e.to_string()
+        AppError::from(e)
+    })
+}
+
+/// 用给定的自定义 JSON 行情接口配置试拉行情，用于在设置中校验字段映射
+#[tauri::command]
+pub async fn test_custom_quote_provider(
+    provider: CustomQuoteProvider,
+    codes: Vec<String>,
+) -> Result<Vec<StockInfo>, AppError> {
+    log::info!("[stock_cmd] test_custom_quote_provider id={} codes_count={}", provider.id, codes.len());
+    if !provider.url.contains("{codes}") {
+        return Err(AppError::InvalidInput("请求地址需包含 {codes} 占位符".to_string()));
+    }
+    let unknown = quote_provider::unknown_fields(&provider);
+    if !unknown.is_empty() {
+        return Err(AppError::InvalidInput(format!("无法识别的字段: {}", unknown.join(", "))));
+    }
+    let formatted: Vec<String> = codes.iter().map(|c| format_stock_code(c)).filter(|c| !c.is_empty()).collect();
+    if formatted.is_empty() {
+        return Err(AppError::InvalidInput("请至少填写一个股票代码".to_string()));
+    }
+    let provider = CustomProvider::new(provider).map_err(AppError::from)?;
+    provider.fetch(&formatted).await.map_err(|e| {
+        log::error!("[stock_cmd] test_custom_quote_provider failed: {}", e);
+        AppError::from(e)
     })
 }
 
@@ -30,32 +76,67 @@ pub async fn get_kline_data(
     code: String,
     scale: String,
     days: u32,
-) -> Result<Vec<KLineData>, String> {
+) -> Result<Vec<KLineData>, AppError> {
     log::info!("[stock_cmd] get_kline_data code={} scale={} days={}", code, scale, days);
-    let service = StockDataService::new().map_err(|e| e.to_string())?;
+    let service = StockDataService::new().map_err(AppError::from)?;
     let formatted = format_stock_code(&code);
     service.get_kline_data(&formatted, &scale, days).await.map_err(|e| {
         log::error!("[stock_cmd] get_kline_data failed for {}: {}", code, e);
-        e.to_string()
+        AppError::from(e)
     })
 }
 
+/// 股票搜索：优先查本地代码表（支持拼音首字母，离线可用），本地无结果时回退东财联想接口
 #[tauri::command]
-pub async fn search_stocks(keyword: String) -> Result<Vec<StockSearchResult>, String> {
+pub async fn search_stocks(
+    state: State<'_, AppState>,
+    keyword: String,
+) -> Result<Vec<StockSearchResult>, AppError> {
     log::info!("[stock_cmd] search_stocks keyword={}", keyword);
     let keyword = keyword.trim().to_string();
     if keyword.is_empty() {
         return Ok(vec![]);
     }
 
-    let client = build_stock_client().map_err(|e| e.to_string())?;
+    match symbol_table::load(&state.db) {
+        Ok(table) => {
+            let results: Vec<StockSearchResult> = table
+                .search(&keyword, SEARCH_LIMIT)
+                .into_iter()
+                .map(|s| StockSearchResult {
+                    code: s.code.clone(),
+                    name: s.name.clone(),
+                    market: market_label(&s.code).to_string(),
+                })
+                .collect();
+            if !results.is_empty() {
+                return Ok(results);
+            }
+        }
+        Err(e) => log::warn!("[stock_cmd] search_stocks load symbol table failed: {}", e),
+    }
+    search_stocks_remote(&keyword).await
+}
+
+const SEARCH_LIMIT: usize = 15;
+
+fn market_label(code: &str) -> &'static str {
+    match code.get(..2) {
+        Some("sh") => "沪A",
+        Some("bj") => "京A",
+        _ => "深A",
+    }
+}
+
+async fn search_stocks_remote(keyword: &str) -> Result<Vec<StockSearchResult>, AppError> {
+    let client = build_stock_client().map_err(AppError::from)?;
     let url = format!(
         "https://searchapi.eastmoney.com/api/suggest/get?input={}&type=14&token=D43BF722C8E33BDC906FB84D85E326E8&count=15",
-        urlencoding::encode(&keyword)
+        urlencoding::encode(keyword)
     );
 
-    let resp = client.get(&url).send().await.map_err(|e| e.to_string())?;
-    let body: serde_json::Value = resp.json().await.map_err(|e| e.to_string())?;
+    let resp = client.get(&url).send_logged().await.map_err(AppError::from)?;
+    let body: serde_json::Value = resp.json().await.map_err(AppError::from)?;
 
     let mut results = Vec::new();
     if let Some(data) = body.get("QuotationCodeTable")
@@ -90,18 +171,392 @@ pub async fn search_stocks(keyword: String) -> Result<Vec<StockSearchResult>, St
     Ok(results)
 }
 
-/// 获取指定代码列表的多维度快照（PE/PB/ROE/市值/换手率/量比/主力净流入/5日%/20日%等）
+/// 获取指定代码列表的多维度快照（PE/PB/ROE/市值/换手率/量比/主力净流入/5日%/20日%等），超时返回部分结果
 #[tauri::command]
 pub async fn get_watchlist_enriched(
+    state: State<'_, AppState>,
     codes: Vec<String>,
-) -> Result<Vec<MarketStockSnapshot>, String> {
+) -> Result<Vec<MarketStockSnapshot>, AppError> {
     log::info!("[stock_cmd] get_watchlist_enriched codes_count={}", codes.len());
     if codes.is_empty() {
         return Ok(vec![]);
     }
-    let scanner = MarketScanner::new().map_err(|e| e.to_string())?;
-    scanner.fetch_stocks_by_codes(&codes).await.map_err(|e| {
+    watchlist_enriched::fetch(&state.db, &codes).await.map_err(|e| {
         log::error!("[stock_cmd] get_watchlist_enriched failed: {}", e);
-        e.to_string()
+        AppError::from(e)
+    })
+}
+
+/// 公司简介 / 主营业务 / 主营构成（东方财富 F10）
+#[tauri::command]
+pub async fn get_stock_profile(code: String) -> Result<StockProfile, AppError> {
+    log::info!("[stock_cmd] get_stock_profile code={}", code);
+    f10_service::fetch_stock_profile(&code).await.map_err(|e| {
+        log::error!("[stock_cmd] get_stock_profile failed for {}: {}", code, e);
+        AppError::from(e)
+    })
+}
+
+/// 机构一致预期（今明两年 EPS / 净利润）与评级分布
+#[tauri::command]
+pub async fn get_earnings_forecast(code: String) -> Result<EarningsForecast, AppError> {
+    log::info!("[stock_cmd] get_earnings_forecast code={}", code);
+    f10_service::fetch_earnings_forecast(&code).await.map_err(|e| {
+        log::error!("[stock_cmd] get_earnings_forecast failed for {}: {}", code, e);
+        AppError::from(e)
+    })
+}
+
+/// 当前 PE/PB 在近 5 年自身历史中的分位（本地估值缓存增量同步）
+#[tauri::command]
+pub async fn get_valuation_band(
+    state: State<'_, AppState>,
+    code: String,
+) -> Result<ValuationBand, AppError> {
+    log::info!("[stock_cmd] get_valuation_band code={}", code);
+    valuation::get_valuation_band(&state.db, &code).await.map_err(|e| {
+        log::error!("[stock_cmd] get_valuation_band failed for {}: {}", code, e);
+        AppError::from(e)
+    })
+}
+
+/// 同行业可比公司对比表（按市值取前 count 只，默认 10）
+#[tauri::command]
+pub async fn get_peer_comparison(code: String, count: Option<usize>) -> Result<PeerComparison, AppError> {
+    log::info!("[stock_cmd] get_peer_comparison code={} count={:?}", code, count);
+    let count = count.unwrap_or(peer_comparison::DEFAULT_PEER_COUNT);
+    peer_comparison::get_peer_comparison(&code, count).await.map_err(|e| {
+        log::error!("[stock_cmd] get_peer_comparison failed for {}: {}", code, e);
+        AppError::from(e)
+    })
+}
+
+/// 历史季节性：近 years 年（默认 5 年）各月份、各星期几的平均涨跌幅与上涨概率，附所属行业板块月度统计
+#[tauri::command]
+pub async fn get_seasonality(code: String, years: Option<u32>) -> Result<Seasonality, AppError> {
+    log::info!("[stock_cmd] get_seasonality code={} years={:?}", code, years);
+    seasonality::get_seasonality(&code, years.unwrap_or(seasonality::DEFAULT_YEARS)).await.map_err(|e| {
+        log::error!("[stock_cmd] get_seasonality failed for {}: {}", code, e);
+        AppError::from(e)
+    })
+}
+
+/// 批量增量同步日线到本地缓存：每完成一只推送 history-sync-progress 事件，
+/// 返回的 failed_codes 可再次传入续传（已同步的股票只做增量请求）
+#[tauri::command]
+pub async fn sync_history_klines(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    codes: Vec<String>,
+    concurrency: Option<usize>,
+) -> Result<HistorySyncResult, AppError> {
+    log::info!("[stock_cmd] sync_history_klines codes_count={} concurrency={:?}", codes.len(), concurrency);
+    history_sync::sync_history_batch(
+        &state.db,
+        &app,
+        &codes,
+        concurrency.unwrap_or(history_sync::DEFAULT_CONCURRENCY),
+    ).await.map_err(|e| {
+        log::error!("[stock_cmd] sync_history_klines failed: {}", e);
+        AppError::from(e)
     })
 }
+
+/// NLP 智能选股（东财自然语言条件），每次执行记入选股历史
+#[tauri::command]
+pub async fn smart_search_stock(
+    state: State<'_, AppState>,
+    keyword: String,
+    page_size: Option<usize>,
+) -> Result<SmartStockResponse, AppError> {
+    log::info!("[stock_cmd] smart_search_stock keyword={}", keyword);
+    run_smart_search(&state, keyword.trim(), page_size.unwrap_or(50)).await
+}
+
+/// 重新执行历史中的选股条件
+#[tauri::command]
+pub async fn rerun_smart_search(
+    state: State<'_, AppState>,
+    keyword: String,
+) -> Result<SmartStockResponse, AppError> {
+    log::info!("[stock_cmd] rerun_smart_search keyword={}", keyword);
+    let saved = state.db.get_smart_search(&keyword).map_err(AppError::from)?;
+    if saved.is_none() {
+        return Err(AppError::NotFound("选股历史中不存在该条件".to_string()));
+    }
+    run_smart_search(&state, &keyword, 50).await
+}
+
+async fn run_smart_search(state: &AppState, keyword: &str, page_size: usize) -> Result<SmartStockResponse, AppError> {
+    if keyword.is_empty() {
+        return Err(AppError::InvalidInput("请输入选股条件".to_string()));
+    }
+    let settings = state.db.load_settings().map_err(AppError::from)?;
+    let mut resp = SmartStockService::search_stock(keyword, page_size, &settings.qgqp_b_id).await.map_err(|e| {
+        log::error!("[stock_cmd] smart_search_stock failed: {}", e);
+        AppError::from(e)
+    })?;
+    // 标识失效（探测条件也被拒绝）时重新获取并重试一次
+    if resp.code != 100 && !SmartStockService::validate_fingerprint(&settings.qgqp_b_id).await.unwrap_or(true) {
+        log::warn!("[stock_cmd] smart_search_stock qgqp_b_id expired, re-acquiring");
+        let qgqp_b_id = smart_stock::acquire_fingerprint(&state.db, true).await.map_err(AppError::from)?;
+        resp = SmartStockService::search_stock(keyword, page_size, &qgqp_b_id).await.map_err(AppError::from)?;
+    }
+    if resp.code != 100 {
+        let msg = resp.msg.clone().or(resp.message.clone()).unwrap_or_default();
+        return Err(AppError::Upstream(format!("选股条件解析失败(code={}): {}", resp.code, msg)));
+    }
+
+    let count = resp.data.as_ref().map(|d| d.result.data_list.len()).unwrap_or(0);
+    if let Err(e) = state.db.record_smart_search(keyword, count) {
+        log::warn!("[stock_cmd] record smart search history failed: {}", e);
+    }
+    Ok(resp)
+}
+
+/// NLP 选股历史，favorites_only 为 true 时只返回收藏
+#[tauri::command]
+pub async fn get_smart_search_history(
+    state: State<'_, AppState>,
+    favorites_only: Option<bool>,
+    limit: Option<usize>,
+) -> Result<Vec<SmartSearchQuery>, AppError> {
+    state.db.get_smart_search_history(favorites_only.unwrap_or(false), limit.unwrap_or(50)).map_err(|e| {
+        log::error!("[stock_cmd] get_smart_search_history failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+#[tauri::command]
+pub async fn set_smart_search_favorite(
+    state: State<'_, AppState>,
+    keyword: String,
+    favorite: bool,
+) -> Result<(), AppError> {
+    log::info!("[stock_cmd] set_smart_search_favorite keyword={} favorite={}", keyword, favorite);
+    state.db.set_smart_search_favorite(&keyword, favorite).map_err(|e| {
+        log::error!("[stock_cmd] set_smart_search_favorite failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+#[tauri::command]
+pub async fn delete_smart_search(
+    state: State<'_, AppState>,
+    keyword: String,
+) -> Result<(), AppError> {
+    log::info!("[stock_cmd] delete_smart_search keyword={}", keyword);
+    state.db.delete_smart_search(&keyword).map_err(|e| {
+        log::error!("[stock_cmd] delete_smart_search failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 立即从全市场扫描刷新本地股票代码表（后台任务每周自动刷新一次），返回写入条数
+#[tauri::command]
+pub async fn refresh_symbol_table(state: State<'_, AppState>) -> Result<usize, AppError> {
+    log::info!("[stock_cmd] refresh_symbol_table");
+    symbol_table::refresh(&state.db).await.map_err(|e| {
+        log::error!("[stock_cmd] refresh_symbol_table failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 获取指数成分股（沪深300/上证50/中证500，或行业板块代码 BKxxxx）及最新行情
+#[tauri::command]
+pub async fn get_index_constituents(index_code: String) -> Result<IndexConstituents, AppError> {
+    log::info!("[stock_cmd] get_index_constituents index_code={}", index_code);
+    index_constituents::get_index_constituents(&index_code).await.map_err(|e| {
+        log::error!("[stock_cmd] get_index_constituents failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+const DEFAULT_INDEX_KLINE_COUNT: u32 = 120;
+const MAX_INDEX_KLINE_COUNT: u32 = 640;
+
+/// 获取指数K线：支持指数代码（sh000001 / sz399006）或名称（中证500），周期 day/week/month/m1~m60
+#[tauri::command]
+pub async fn get_index_kline(index: String, period: Option<String>, count: Option<u32>) -> Result<IndexKline, AppError> {
+    let period = period.unwrap_or_else(|| "day".to_string());
+    log::info!("[stock_cmd] get_index_kline index={} period={}", index, period);
+    let code = history_kline::resolve_kline_code(&index);
+    if !history_kline::is_index_code(&code) {
+        return Err(AppError::InvalidInput(format!("不是指数代码: {}，上证指数请使用 sh000001 或名称", index)));
+    }
+    let count = count.unwrap_or(DEFAULT_INDEX_KLINE_COUNT).clamp(1, MAX_INDEX_KLINE_COUNT);
+    let service = HistoryKlineService::new().map_err(AppError::from)?;
+    let klines = service.fetch_recent(&code, &period, count).await.map_err(|e| {
+        log::error!("[stock_cmd] get_index_kline failed for {}: {}", code, e);
+        AppError::from(e)
+    })?;
+    Ok(IndexKline {
+        name: history_kline::index_name(&code).unwrap_or_default().to_string(),
+        code,
+        period,
+        klines,
+    })
+}
+
+/// 校验条件选股表达式，语法或字段错误返回具体位置说明
+#[tauri::command]
+pub fn validate_filter_expression(expression: String) -> Result<(), AppError> {
+    filter_expr::parse(&expression)
+        .map(|_| ())
+        .map_err(|e| AppError::InvalidInput(e.to_string()))
+}
+
+/// 按条件表达式扫描全市场，如 `pe_ttm < 30 && roe > 10 && !name.contains('ST')`
+#[tauri::command]
+pub async fn screen_by_expression(
+    state: State<'_, AppState>,
+    expression: String,
+    limit: Option<usize>,
+) -> Result<ExpressionScreenResult, AppError> {
+    log::info!("[stock_cmd] screen_by_expression expression={}", expression);
+    if let Err(e) = filter_expr::parse(&expression) {
+        return Err(AppError::InvalidInput(e.to_string()));
+    }
+    let benchmark = if state.db.load_settings()?.momentum_vs_benchmark {
+        BenchmarkReturns::fetch().await.map_err(|e| log::warn!("[stock_cmd] fetch benchmark failed: {}", e)).ok()
+    } else {
+        None
+    };
+    filter_expr::screen(&expression, limit.unwrap_or(200), benchmark.as_ref()).await.map_err(|e| {
+        log::error!("[stock_cmd] screen_by_expression failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 个股近一个月的综合因子评分走势（收盘后记录，按日期正序），用于判断评分在改善还是恶化
+#[tauri::command]
+pub fn get_score_history(state: State<'_, AppState>, code: String, days: Option<usize>) -> Result<Vec<FactorScore>, AppError> {
+    let code = format_stock_code(&code);
+    factor_score::get_history(&state.db, &code, days.unwrap_or(20)).map_err(|e| {
+        log::error!("[stock_cmd] get_score_history failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 个股题材暴露：所属题材板块按近期快讯热度与板块涨幅加权，附驱动快讯标题
+#[tauri::command]
+pub async fn get_stock_theme_exposure(code: String) -> Result<ThemeExposure, AppError> {
+    let code = format_stock_code(&code);
+    log::info!("[stock_cmd] get_stock_theme_exposure code={}", code);
+    theme_exposure::get_exposure(&code).await.map_err(|e| {
+        log::error!("[stock_cmd] get_stock_theme_exposure failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 近 days 天新纳入概念板块的成分股（来自成分股缓存，每日收盘后刷新）
+#[tauri::command]
+pub fn get_board_member_additions(days: Option<i64>) -> Result<Vec<BoardMember>, AppError> {
+    Ok(board_members::recent_additions(days.unwrap_or(board_members::NEW_MEMBER_DAYS)))
+}
+
+/// 题材事件研究：统计近 days 天（默认 90）快讯提及各题材后成分股的远期收益，检验题材快讯的预测力
+#[tauri::command]
+pub async fn run_theme_event_study(state: State<'_, AppState>, days: Option<i64>) -> Result<ThemeStudyResult, AppError> {
+    let days = days.unwrap_or(90);
+    if !(5..=180).contains(&days) {
+        return Err(AppError::InvalidInput("研究区间需在 5~180 天之间".to_string()));
+    }
+    log::info!("[stock_cmd] run_theme_event_study days={}", days);
+    theme_study::run_study(&state.db, days).await.map_err(|e| {
+        log::error!("[stock_cmd] run_theme_event_study failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 公告事件研究：回购、业绩预增、减持公告发布后 1/5/20 日的平均异常收益；backfill 为 true 时先为本地有日线的股票回补历史公告
+#[tauri::command]
+pub async fn run_announcement_study(
+    state: State<'_, AppState>,
+    days: Option<i64>,
+    backfill: Option<bool>,
+) -> Result<AnnouncementStudyResult, AppError> {
+    let days = days.unwrap_or(announcement_study::DEFAULT_STUDY_DAYS);
+    if !(30..=730).contains(&days) {
+        return Err(AppError::InvalidInput("研究区间需在 30~730 天之间".to_string()));
+    }
+    log::info!("[stock_cmd] run_announcement_study days={} backfill={:?}", days, backfill);
+    if backfill.unwrap_or(false) {
+        let added = announcement_study::backfill(&state.db).await.map_err(|e| {
+            log::error!("[stock_cmd] run_announcement_study backfill failed: {}", e);
+            AppError::from(e)
+        })?;
+        log::info!("[stock_cmd] backfilled {} announcement events", added);
+    }
+    announcement_study::run_study(&state.db, days).await.map_err(|e| {
+        log::error!("[stock_cmd] run_announcement_study failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 个股最近 days 个交易日（默认 20）的日级资金流向，拉取后保存到本地
+#[tauri::command]
+pub async fn get_fund_flow_history(state: State<'_, AppState>, code: String, days: Option<u32>) -> Result<Vec<FundFlowDay>, AppError> {
+    let code = format_stock_code(&code);
+    fund_flow::sync_history(&state.db, &code, days.unwrap_or(20)).await.map_err(|e| {
+        log::error!("[stock_cmd] get_fund_flow_history failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 个股当日分单资金流向（超大单/大单/中单/小单净额、形态与盘中累计走势）
+#[tauri::command]
+pub async fn get_fund_flow_breakdown(code: String) -> Result<Option<FundFlowBreakdown>, AppError> {
+    let code = format_stock_code(&code);
+    fund_flow::get_breakdown(&code).await.map_err(|e| {
+        log::error!("[stock_cmd] get_fund_flow_breakdown failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 焦点盯盘：盘中轮询单只股票的逐笔成交，超过大单阈值的成交通过事件推送
+#[tauri::command]
+pub async fn start_focus_monitor(app: AppHandle, code: String) -> Result<FocusMonitorStatus, AppError> {
+    let code = format_stock_code(&code);
+    if code.is_empty() {
+        return Err(AppError::InvalidInput("股票代码不能为空".to_string()));
+    }
+    focus_monitor::start(&app, &code).await.map_err(|e| {
+        log::error!("[stock_cmd] start_focus_monitor failed: {}", e);
+        AppError::from(e)
+    })?;
+    Ok(focus_monitor::status())
+}
+
+#[tauri::command]
+pub async fn stop_focus_monitor() -> Result<FocusMonitorStatus, AppError> {
+    focus_monitor::stop().await;
+    Ok(focus_monitor::status())
+}
+
+#[tauri::command]
+pub async fn get_focus_monitor_status() -> Result<FocusMonitorStatus, AppError> {
+    Ok(focus_monitor::status())
+}
+
+/// 集合竞价异动扫描：竞价跳空与竞价量相对 5 日均量放大，按强度排序并标注策略区间；watchlist_only 为空时按设置
+#[tauri::command]
+pub async fn scan_auction_gaps(
+    state: State<'_, AppState>,
+    watchlist_only: Option<bool>,
+) -> Result<AuctionScanResult, AppError> {
+    let watchlist_only = match watchlist_only {
+        Some(v) => v,
+        None => state.db.load_settings().map_err(AppError::from)?.auction_scan_watchlist_only,
+    };
+    auction_scanner::scan(&state.db, watchlist_only).await.map_err(|e| {
+        log::error!("[stock_cmd] scan_auction_gaps failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 今日最近一次竞价异动扫描结果
+#[tauri::command]
+pub async fn get_auction_gaps() -> Result<Option<AuctionScanResult>, AppError> {
+    Ok(auction_scanner::get_latest())
+}
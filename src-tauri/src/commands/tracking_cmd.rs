@@ -1,8 +1,16 @@
-use tauri::{AppHandle, Emitter, State};
+use tauri::{AppHandle, Manager, State};
 use crate::AppState;
-use crate::models::tracking::{AIPickTracking, LossStock};
+use crate::models::tracking::{AIPickTracking, InstructionOutcomeStats, InstructionRecord, LossStock, StrategyPerformance};
 use crate::models::ai::AIStreamEvent;
+use crate::models::agent_session::AgentSession;
+use crate::models::risk::RiskReport;
 use crate::services::ai_service::AIService;
+use crate::services::instruction_tracker;
+use crate::services::risk_control;
+use crate::services::strategy_performance;
+use crate::services::stock_tools::ToolContext;
+use crate::services::stream_forwarder;
+use crate::error::AppError;
 
 #[tauri::command]
 pub async fn add_tracking_stock(
@@ -13,7 +21,7 @@ pub async fn add_tracking_stock(
     rating: String,
     reason: String,
     sector: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     log::info!("[tracking_cmd] add_tracking_stock code={} name={}", code, name);
     let today = chrono::Local::now().format("%Y-%m-%d").to_string();
     let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
@@ -29,7 +37,7 @@ pub async fn add_tracking_stock(
     };
     state.db.add_tracking_stock(&tracking).map_err(|e| {
         log::error!("[tracking_cmd] add_tracking_stock failed: {}", e);
-        e.to_string()
+        AppError::from(e)
     })
 }
 
@@ -38,21 +46,21 @@ pub async fn remove_tracking_stock(
     state: State<'_, AppState>,
     code: String,
     added_date: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     log::info!("[tracking_cmd] remove_tracking_stock code={} date={}", code, added_date);
     state.db.remove_tracking_stock(&code, &added_date).map_err(|e| {
         log::error!("[tracking_cmd] remove_tracking_stock failed: {}", e);
-        e.to_string()
+        AppError::from(e)
     })
 }
 
 #[tauri::command]
 pub async fn get_tracking_stocks(
     state: State<'_, AppState>,
-) -> Result<Vec<AIPickTracking>, String> {
+) -> Result<Vec<AIPickTracking>, AppError> {
     state.db.get_tracking_stocks().map_err(|e| {
         log::error!("[tracking_cmd] get_tracking_stocks failed: {}", e);
-        e.to_string()
+        AppError::from(e)
     })
 }
 
@@ -60,14 +68,47 @@ pub async fn get_tracking_stocks(
 pub async fn clear_tracking_by_date(
     state: State<'_, AppState>,
     date: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     log::info!("[tracking_cmd] clear_tracking_by_date date={}", date);
     state.db.clear_tracking_by_date(&date).map_err(|e| {
         log::error!("[tracking_cmd] clear_tracking_by_date failed: {}", e);
-        e.to_string()
+        AppError::from(e)
     })
 }
 
+/// 将某次选股会话的结构化结果整体加入表现追踪，以选股时的入库价为基准，返回新增条数
+#[tauri::command]
+pub async fn track_pick_session(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<usize, AppError> {
+    log::info!("[tracking_cmd] track_pick_session session_id={}", session_id);
+    let picks = state.db.get_pick_records(&session_id).map_err(|e| {
+        log::error!("[tracking_cmd] track_pick_session load picks failed: {}", e);
+        AppError::from(e)
+    })?;
+    let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    let mut added = 0;
+    for pick in picks.into_iter().filter(|p| p.entry_price > 0.0) {
+        let tracking = AIPickTracking {
+            code: pick.code,
+            name: pick.name,
+            added_date: pick.date,
+            added_price: pick.entry_price,
+            rating: pick.rating,
+            reason: pick.reason,
+            sector: pick.sector,
+            created_at: now.clone(),
+        };
+        state.db.add_tracking_stock(&tracking).map_err(|e| {
+            log::error!("[tracking_cmd] track_pick_session add {} failed: {}", tracking.code, e);
+            AppError::from(e)
+        })?;
+        added += 1;
+    }
+    Ok(added)
+}
+
 /// AI 败因分析命令
 #[tauri::command]
 pub async fn analyze_loss_reasons(
@@ -75,13 +116,13 @@ pub async fn analyze_loss_reasons(
     state: State<'_, AppState>,
     date: String,
     loss_stocks: Vec<LossStock>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     log::info!("[tracking_cmd] analyze_loss_reasons date={} stocks={}", date, loss_stocks.len());
     if loss_stocks.is_empty() {
-        return Err("没有亏损股票需要分析".to_string());
+        return Err(AppError::InvalidInput("没有亏损股票需要分析".to_string()));
     }
 
-    let settings = state.db.load_settings().map_err(|e| e.to_string())?;
+    let settings = state.db.load_settings().map_err(AppError::from)?;
     let config = settings
         .ai_configs
         .iter()
@@ -90,23 +131,23 @@ pub async fn analyze_loss_reasons(
         .ok_or_else(|| "未配置可用的 AI 模型，请在设置中添加".to_string())?
         .clone();
 
-    let qgqp_b_id = settings.qgqp_b_id.clone();
+    let tool_ctx = ToolContext::from_settings(&settings);
     let max_tool_rounds = settings.max_pick_tool_rounds;
     let max_token_budget = settings.max_pick_token_budget;
 
-    let (sender, mut receiver) = tokio::sync::mpsc::channel::<AIStreamEvent>(100);
+    let (sender, receiver) = tokio::sync::mpsc::channel::<AIStreamEvent>(100);
 
     let event_name = format!("ai-loss-analysis-{}", date);
-    let app_clone = app.clone();
-    let event_name_clone = event_name.clone();
-    tokio::spawn(async move {
-        while let Some(event) = receiver.recv().await {
-            let _ = app_clone.emit(&event_name_clone, &event);
-        }
-    });
+    stream_forwarder::spawn(&app, event_name.clone(), receiver);
 
+    let app_for_db = app.clone();
     tokio::spawn(async move {
-        match AIService::analyze_loss_reasons_with_tools(&config, &date, &loss_stocks, &qgqp_b_id, sender.clone(), max_tool_rounds, max_token_budget).await {
+        let mut session = AgentSession::new("loss_analysis", &date, &config.model_name);
+        let result = AIService::analyze_loss_reasons_with_tools(&config, &date, &loss_stocks, &tool_ctx, sender.clone(), max_tool_rounds, max_token_budget, &mut session).await;
+        session.finish(&result);
+        let _ = app_for_db.state::<AppState>().db.save_agent_session(&session);
+
+        match result {
             Ok((content, usage)) => {
                 let _ = sender.send(AIStreamEvent {
                     event_type: "done".to_string(),
@@ -130,3 +171,65 @@ pub async fn analyze_loss_reasons(
 
     Ok(())
 }
+
+/// 查询策略指令历史（含次日表现），date 为空时返回全部日期
+#[tauri::command]
+pub async fn get_instruction_history(
+    state: State<'_, AppState>,
+    date: Option<String>,
+    limit: usize,
+) -> Result<Vec<InstructionRecord>, AppError> {
+    state.db.get_instruction_history(date.as_deref(), limit).map_err(|e| {
+        log::error!("[tracking_cmd] get_instruction_history failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 回填尚未评估的指令次日表现后，按指令类型汇总最近 days 天的胜率与平均涨幅
+#[tauri::command]
+pub async fn get_instruction_stats(
+    state: State<'_, AppState>,
+    days: Option<i64>,
+) -> Result<Vec<InstructionOutcomeStats>, AppError> {
+    log::info!("[tracking_cmd] get_instruction_stats days={:?}", days);
+    if let Err(e) = instruction_tracker::update_outcomes(&state.db).await {
+        log::warn!("[tracking_cmd] get_instruction_stats update outcomes failed: {}", e);
+    }
+    let since = (chrono::Local::now() - chrono::Duration::days(days.unwrap_or(30)))
+        .format("%Y-%m-%d")
+        .to_string();
+    state.db.get_instruction_stats(&since).map_err(|e| {
+        log::error!("[tracking_cmd] get_instruction_stats failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 策略表现看板：近 days 天技术信号、AI 买入指令与 AI 选股入选后持有 hold_days 个交易日的模拟收益与胜率
+#[tauri::command]
+pub async fn get_strategy_performance(
+    state: State<'_, AppState>,
+    days: Option<i64>,
+    hold_days: Option<usize>,
+) -> Result<Vec<StrategyPerformance>, AppError> {
+    let days = days.unwrap_or(30);
+    let hold_days = hold_days.unwrap_or(strategy_performance::DEFAULT_HOLD_DAYS);
+    log::info!("[tracking_cmd] get_strategy_performance days={} hold_days={}", days, hold_days);
+    if !(1..=365).contains(&days) || !(1..=20).contains(&hold_days) {
+        return Err(AppError::InvalidInput("统计天数需在 1~365，持有天数需在 1~20".to_string()));
+    }
+    strategy_performance::compute(&state.db, days, hold_days).await.map_err(|e| {
+        log::error!("[tracking_cmd] get_strategy_performance failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 按风控规则检查 AI 追踪持仓的仓位、行业、相关性集中度与回撤
+#[tauri::command]
+pub async fn get_risk_report(
+    state: State<'_, AppState>,
+) -> Result<RiskReport, AppError> {
+    risk_control::build_report(&state.db).await.map_err(|e| {
+        log::error!("[tracking_cmd] get_risk_report failed: {}", e);
+        AppError::from(e)
+    })
+}
@@ -1,18 +1,40 @@
-use tauri::{State, Emitter, AppHandle};
+use tauri::{State, AppHandle};
 use crate::AppState;
 use crate::models::watchlist::*;
-use crate::models::ai::{AIAnalysisResult, AIStreamEvent};
 use crate::models::stock::StockDailyHistory;
-use crate::services::history_kline::HistoryKlineService;
+use crate::models::replay::{ReplayDay, ReplayFrame};
+use crate::models::schedule::{ScheduleRun, StockSchedule};
+use crate::services::history_kline::{self, HistoryKlineService};
 use crate::services::technical_indicators;
-use crate::services::ai_service::AIService;
+use crate::services::risk_metrics;
+use crate::services::stock_tools::ToolContext;
+use crate::services::market_scanner::MarketScanner;
+use crate::services::snapshot_archiver;
+use crate::services::intraday_replay;
+use crate::services::signal_alert;
+use crate::services::anomaly_radar;
+use crate::services::watchlist_io::{self, WatchlistFormat};
+use crate::services::watchlist_diagnose;
+use crate::services::watchlist_prefetch;
+use crate::services::stock_schedule;
+use crate::services::symbol_table;
+use crate::error::AppError;
+
+/// 成交量分布默认统计的交易日数
+const DEFAULT_PROFILE_DAYS: usize = 60;
+/// 成交量分布的价格区间数
+const PROFILE_BINS: usize = 40;
+/// 统计窗口不超过该天数时优先使用 5 分钟线（每个交易日 48 根）
+const INTRADAY_PROFILE_MAX_DAYS: usize = 5;
+const BARS_PER_DAY_M5: usize = 48;
 
 #[tauri::command]
 pub async fn add_watchlist_stock(
+    app: AppHandle,
     state: State<'_, AppState>,
     code: String,
     name: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     log::info!("[watchlist_cmd] add_watchlist_stock code={} name={}", code, name);
     let stock = WatchlistStock {
         code,
@@ -23,29 +45,32 @@ pub async fn add_watchlist_stock(
     };
     state.db.add_watchlist_stock(&stock).map_err(|e| {
         log::error!("[watchlist_cmd] add_watchlist_stock failed: {}", e);
-        e.to_string()
-    })
+        AppError::from(e)
+    })?;
+    // 后台预取日线与基本面，首次技术分析与 AI 诊断不再冷启动
+    watchlist_prefetch::spawn_prefetch(&app, vec![stock.code]);
+    Ok(())
 }
 
 #[tauri::command]
 pub async fn remove_watchlist_stock(
     state: State<'_, AppState>,
     code: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     log::info!("[watchlist_cmd] remove_watchlist_stock code={}", code);
     state.db.remove_watchlist_stock(&code).map_err(|e| {
         log::error!("[watchlist_cmd] remove_watchlist_stock failed: {}", e);
-        e.to_string()
+        AppError::from(e)
     })
 }
 
 #[tauri::command]
 pub async fn get_watchlist_stocks(
     state: State<'_, AppState>,
-) -> Result<Vec<WatchlistStock>, String> {
+) -> Result<Vec<WatchlistStock>, AppError> {
     state.db.get_watchlist_stocks().map_err(|e| {
         log::error!("[watchlist_cmd] get_watchlist_stocks failed: {}", e);
-        e.to_string()
+        AppError::from(e)
     })
 }
 
@@ -53,37 +78,269 @@ pub async fn get_watchlist_stocks(
 pub async fn reorder_watchlist(
     state: State<'_, AppState>,
     codes: Vec<String>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     state.db.reorder_watchlist(&codes).map_err(|e| {
         log::error!("[watchlist_cmd] reorder_watchlist failed: {}", e);
-        e.to_string()
+        AppError::from(e)
+    })
+}
+
+/// 从文件批量导入自选股（CSV / 同花顺 / 通达信），已存在的代码跳过
+/// group_name: 文件未指定分组时使用的分组名
+#[tauri::command]
+pub async fn import_watchlist(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    format: String,
+    group_name: Option<String>,
+) -> Result<WatchlistImportResult, AppError> {
+    use tauri_plugin_dialog::DialogExt;
+
+    log::info!("[watchlist_cmd] import_watchlist format={} group={:?}", format, group_name);
+    let format = WatchlistFormat::from_name(&format).map_err(AppError::from)?;
+
+    let file_path = app
+        .dialog()
+        .file()
+        .set_title("导入自选股")
+        .add_filter("自选股文件", &["csv", "txt", "blk", "ebk"])
+        .blocking_pick_file()
+        .ok_or_else(|| "用户取消了导入".to_string())?;
+    let path = file_path.as_path().ok_or_else(|| "无效的文件路径".to_string())?;
+    let bytes = std::fs::read(path).map_err(|e| AppError::from(e).context("读取文件失败"))?;
+
+    let (mut parsed, invalid) = watchlist_io::parse_watchlist(&watchlist_io::decode_file(&bytes), format);
+    let existing = state.db.get_watchlist_stocks().map_err(|e| {
+        log::error!("[watchlist_cmd] import_watchlist load watchlist failed: {}", e);
+        AppError::from(e)
+    })?;
+
+    let mut seen: std::collections::HashSet<String> = existing.iter().map(|s| s.code.clone()).collect();
+    let total = parsed.len();
+    parsed.retain(|s| seen.insert(s.code.clone()));
+    let skipped = total - parsed.len();
+
+    // 通达信等格式不含名称，批量补全
+    let missing: Vec<String> = parsed.iter().filter(|s| s.name.is_empty()).map(|s| s.code.clone()).collect();
+    if !missing.is_empty() {
+        match MarketScanner::new().map_err(AppError::from) {
+            Ok(scanner) => match scanner.fetch_stocks_by_codes(&missing).await {
+                Ok(snapshots) => {
+                    for stock in parsed.iter_mut().filter(|s| s.name.is_empty()) {
+                        if let Some(snap) = snapshots.iter().find(|x| x.code == stock.code) {
+                            stock.name = snap.name.clone();
+                        }
+                    }
+                }
+                Err(e) => log::warn!("[watchlist_cmd] import_watchlist fetch names failed: {}", e),
+            },
+            Err(e) => log::warn!("[watchlist_cmd] import_watchlist scanner init failed: {}", e),
+        }
+    }
+
+    let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    let default_group = group_name.unwrap_or_default();
+    for (i, stock) in parsed.iter_mut().enumerate() {
+        if stock.name.is_empty() {
+            stock.name = stock.code.clone();
+        }
+        if stock.group_name.is_empty() {
+            stock.group_name = default_group.clone();
+        }
+        stock.sort_order = (existing.len() + i) as i32;
+        stock.created_at = now.clone();
+        state.db.add_watchlist_stock(stock).map_err(|e| {
+            log::error!("[watchlist_cmd] import_watchlist add {} failed: {}", stock.code, e);
+            AppError::from(e)
+        })?;
+    }
+
+    log::info!("[watchlist_cmd] import_watchlist added={} skipped={} invalid={}", parsed.len(), skipped, invalid);
+    watchlist_prefetch::spawn_prefetch(&app, parsed.iter().map(|s| s.code.clone()).collect());
+    Ok(WatchlistImportResult { added: parsed.len(), skipped, invalid })
+}
+
+/// 导出自选股到文件（CSV / 同花顺 / 通达信），group_name 为空时导出全部
+#[tauri::command]
+pub async fn export_watchlist(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    format: String,
+    group_name: Option<String>,
+) -> Result<String, AppError> {
+    use tauri_plugin_dialog::DialogExt;
+
+    log::info!("[watchlist_cmd] export_watchlist format={} group={:?}", format, group_name);
+    let format = WatchlistFormat::from_name(&format).map_err(AppError::from)?;
+    let stocks: Vec<WatchlistStock> = state.db.get_watchlist_stocks().map_err(|e| {
+        log::error!("[watchlist_cmd] export_watchlist failed: {}", e);
+        AppError::from(e)
+    })?
+        .into_iter()
+        .filter(|s| group_name.as_ref().map_or(true, |g| &s.group_name == g))
+        .collect();
+    if stocks.is_empty() {
+        return Err("没有可导出的自选股".into());
+    }
+
+    let data = watchlist_io::export_watchlist(&stocks, format);
+    let file_name = format!("自选股.{}", format.extension());
+    let file_path = app
+        .dialog()
+        .file()
+        .set_title("导出自选股")
+        .set_file_name(&file_name)
+        .add_filter("自选股文件", &[format.extension()])
+        .blocking_save_file();
+
+    match file_path {
+        Some(path) => {
+            let path = path.as_path().ok_or_else(|| "无效的文件路径".to_string())?;
+            std::fs::write(path, &data).map_err(|e| AppError::from(e).context("保存文件失败"))?;
+            log::info!("[watchlist_cmd] export_watchlist saved to {:?}", path);
+            Ok(format!("已导出 {} 只自选股", stocks.len()))
+        }
+        None => {
+            log::info!("[watchlist_cmd] export_watchlist cancelled by user");
+            Err("用户取消了导出".into())
+        }
+    }
+}
+
+/// 立即归档自选股收盘快照（后台任务会在交易日收盘后自动执行）
+#[tauri::command]
+pub async fn archive_watchlist_snapshot(
+    state: State<'_, AppState>,
+) -> Result<usize, AppError> {
+    log::info!("[watchlist_cmd] archive_watchlist_snapshot");
+    snapshot_archiver::archive_watchlist(&state.db).await.map_err(|e| {
+        log::error!("[watchlist_cmd] archive_watchlist_snapshot failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 获取某日的自选股快照，date 为空时取最近一次归档
+#[tauri::command]
+pub async fn get_watchlist_snapshots(
+    state: State<'_, AppState>,
+    date: Option<String>,
+) -> Result<Vec<WatchlistSnapshot>, AppError> {
+    log::info!("[watchlist_cmd] get_watchlist_snapshots date={:?}", date);
+    let date = match date {
+        Some(d) => d,
+        None => match state.db.get_latest_snapshot_date().map_err(AppError::from)? {
+            Some(d) => d,
+            None => return Ok(vec![]),
+        },
+    };
+    state.db.get_watchlist_snapshots(&date).map_err(|e| {
+        log::error!("[watchlist_cmd] get_watchlist_snapshots failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 获取单只股票的历史快照（按日期倒序），用于计算周/月变化
+#[tauri::command]
+pub async fn get_stock_snapshot_history(
+    state: State<'_, AppState>,
+    code: String,
+    limit: usize,
+) -> Result<Vec<WatchlistSnapshot>, AppError> {
+    state.db.get_stock_snapshots(&code, limit).map_err(|e| {
+        log::error!("[watchlist_cmd] get_stock_snapshot_history failed: {}", e);
+        AppError::from(e)
     })
 }
 
+/// 有盘中快照可回放的交易日
+#[tauri::command]
+pub async fn get_replay_days(state: State<'_, AppState>) -> Result<Vec<ReplayDay>, AppError> {
+    state.db.get_intraday_days().map_err(|e| {
+        log::error!("[watchlist_cmd] get_replay_days failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 盘后复盘回放：按分钟顺序返回某日集合竞价至收盘的行情帧，codes 为空时回放当日采集的全部股票
+#[tauri::command]
+pub async fn get_intraday_replay(
+    state: State<'_, AppState>,
+    date: String,
+    codes: Option<Vec<String>>,
+) -> Result<Vec<ReplayFrame>, AppError> {
+    log::info!("[watchlist_cmd] get_intraday_replay date={} codes={:?}", date, codes);
+    intraday_replay::load_replay(&state.db, &date, &codes.unwrap_or_default()).map_err(|e| {
+        log::error!("[watchlist_cmd] get_intraday_replay failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 立即扫描自选股的强信号（后台任务会在交易日收盘后自动执行），返回新增预警
+#[tauri::command]
+pub async fn scan_watchlist_signals(
+    state: State<'_, AppState>,
+) -> Result<Vec<SignalAlert>, AppError> {
+    log::info!("[watchlist_cmd] scan_watchlist_signals");
+    signal_alert::scan_watchlist_signals(&state.db).await.map_err(|e| {
+        log::error!("[watchlist_cmd] scan_watchlist_signals failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 查询信号预警历史，code 为空时返回全部自选股
+#[tauri::command]
+pub async fn get_signal_history(
+    state: State<'_, AppState>,
+    code: Option<String>,
+    limit: usize,
+) -> Result<Vec<SignalAlert>, AppError> {
+    state.db.get_signal_history(code.as_deref(), limit).map_err(|e| {
+        log::error!("[watchlist_cmd] get_signal_history failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 立即扫描自选股与 AI 选股跟踪股票的盘中异动（交易时段后台每分钟自动扫描），返回新出现的异动
+#[tauri::command]
+pub async fn scan_anomalies(state: State<'_, AppState>) -> Result<Vec<AnomalyEvent>, AppError> {
+    log::info!("[watchlist_cmd] scan_anomalies");
+    anomaly_radar::scan_anomalies(&state.db).await.map_err(|e| {
+        log::error!("[watchlist_cmd] scan_anomalies failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 今日已报告的盘中异动，最新在前
+#[tauri::command]
+pub async fn get_recent_anomalies() -> Result<Vec<AnomalyEvent>, AppError> {
+    Ok(anomaly_radar::get_recent_anomalies())
+}
+
 #[tauri::command]
 pub async fn get_stock_technical_analysis(
     state: State<'_, AppState>,
     code: String,
     name: String,
     period: String,
-) -> Result<StockTechnicalAnalysis, String> {
+    profile_days: Option<usize>,
+) -> Result<StockTechnicalAnalysis, AppError> {
     log::info!("[watchlist_cmd] get_stock_technical_analysis code={} period={}", code, period);
     let period = if period.is_empty() { "day".to_string() } else { period };
     let today = chrono::Local::now().format("%Y-%m-%d").to_string();
 
     // Check cached data
-    let latest_date = state.db.get_latest_history_date(&code).map_err(|e| e.to_string())?;
+    let latest_date = state.db.get_latest_history_date(&code).map_err(AppError::from)?;
 
     // Fetch from remote if needed
-    let kline_service = HistoryKlineService::new().map_err(|e| e.to_string())?;
+    let kline_service = HistoryKlineService::new().map_err(AppError::from)?;
 
-    let start_date = "2023-01-01".to_string();
+    let start_date = history_kline::HISTORY_START_DATE.to_string();
     let new_items = if let Some(ref latest) = latest_date {
         kline_service.fetch_kline_incremental(&code, &period, latest, &today)
-            .await.map_err(|e| e.to_string())?
+            .await.map_err(AppError::from)?
     } else {
         kline_service.fetch_kline_full(&code, &period, &start_date, &today)
-            .await.map_err(|e| e.to_string())?
+            .await.map_err(AppError::from)?
     };
 
     // Save new data to DB
@@ -105,30 +362,28 @@ pub async fn get_stock_technical_analysis(
     }
 
     // Load all cached data
-    let cached = state.db.get_daily_history_asc(&code, 500).map_err(|e| e.to_string())?;
-
-    let kline_data: Vec<KlineItem> = cached.iter().map(|h| KlineItem {
-        date: h.date.clone(),
-        open: h.open,
-        close: h.close,
-        high: h.high,
-        low: h.low,
-        volume: h.volume,
-        amount: h.amount,
-        change_pct: h.change_pct,
-        turnover_rate: h.turnover_rate,
-    }).collect();
+    let cached = state.db.get_daily_history_asc(&code, 500).map_err(AppError::from)?;
+
+    let kline_data = technical_indicators::klines_from_history(&cached);
 
     if kline_data.is_empty() {
-        return Err("无K线数据".to_string());
+        return Err(AppError::NotFound("无K线数据".to_string()));
     }
 
     // Compute indicators
+    let signal_config = state.db.load_settings().map_err(AppError::from)?.signal_config;
     let indicators = technical_indicators::compute_indicators(&kline_data);
-    let signals = technical_indicators::detect_signals(&kline_data, &indicators);
+    let signals = technical_indicators::detect_signals(&kline_data, &indicators, &signal_config);
     let ma_alignment = technical_indicators::determine_ma_alignment(&indicators);
     let volume_price_relation = technical_indicators::determine_volume_price_relation(&kline_data);
     let summary = technical_indicators::generate_summary(&ma_alignment, &volume_price_relation, &signals);
+    let gaps = technical_indicators::detect_gaps(&kline_data);
+    let volume_profile = build_volume_profile(&code, &kline_data, profile_days.unwrap_or(DEFAULT_PROFILE_DAYS).max(1)).await;
+    let risk = if period == "day" {
+        Some(risk_metrics::fetch_risk_metrics(&kline_data).await)
+    } else {
+        None
+    };
 
     Ok(StockTechnicalAnalysis {
         code,
@@ -139,9 +394,31 @@ pub async fn get_stock_technical_analysis(
         ma_alignment,
         volume_price_relation,
         summary,
+        risk,
+        gaps,
+        volume_profile,
     })
 }
 
+/// 成交量分布：短窗口优先使用 5 分钟线以获得更细的价格分布，获取失败或窗口较长时使用日线
+async fn build_volume_profile(code: &str, daily: &[KlineItem], days: usize) -> Option<VolumeProfile> {
+    if days <= INTRADAY_PROFILE_MAX_DAYS {
+        let minutes = match HistoryKlineService::new() {
+            Ok(service) => service.fetch_minute_kline(code, "m5", (days * BARS_PER_DAY_M5) as u32).await,
+            Err(e) => Err(e),
+        };
+        match minutes {
+            Ok(bars) if !bars.is_empty() => {
+                return technical_indicators::compute_volume_profile(&bars, PROFILE_BINS, "m5", days);
+            }
+            Ok(_) => {}
+            Err(e) => log::warn!("[watchlist_cmd] fetch_minute_kline {} failed: {}", code, e),
+        }
+    }
+    let window = &daily[daily.len().saturating_sub(days)..];
+    technical_indicators::compute_volume_profile(window, PROFILE_BINS, "day", window.len())
+}
+
 /// AI 诊断股票（Agent 模式：AI 自主调用工具获取真实数据后分析）
 #[tauri::command]
 pub async fn ai_diagnose_stock(
@@ -151,11 +428,11 @@ pub async fn ai_diagnose_stock(
     name: String,
     #[allow(unused_variables)]
     technical_summary: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     log::info!("[watchlist_cmd] ai_diagnose_stock code={} name={}", code, name);
     let settings = state.db.load_settings().map_err(|e| {
         log::error!("[watchlist_cmd] ai_diagnose_stock load_settings failed: {}", e);
-        e.to_string()
+        AppError::from(e)
     })?;
 
     let ai_config = settings.ai_configs.iter()
@@ -166,41 +443,144 @@ pub async fn ai_diagnose_stock(
             "未配置AI模型".to_string()
         })?;
 
-    let (tx, mut rx) = tokio::sync::mpsc::channel::<AIStreamEvent>(100);
-
-    let app_clone = app.clone();
-    let code_clone = code.clone();
-
-    tokio::spawn(async move {
-        while let Some(event) = rx.recv().await {
-            let _ = app_clone.emit(&format!("ai-diagnose-{}", code_clone), &event);
-        }
-    });
-
-    let result = AIService::diagnose_stock_with_tools(
+    watchlist_diagnose::diagnose_stock(
+        &state.db,
+        &app,
         &ai_config,
+        &ToolContext::from_settings(&settings),
         &code,
         &name,
-        tx,
     ).await.map_err(|e| {
         log::error!("[watchlist_cmd] ai_diagnose_stock failed for {}: {}", code, e);
-        e.to_string()
+        AppError::from(e)
     })?;
 
-    let analysis = AIAnalysisResult {
-        id: uuid::Uuid::new_v4().to_string(),
-        code: code.clone(),
-        name: name.clone(),
-        model_name: ai_config.model_name.clone(),
-        question: "AI技术诊断(Agent)".to_string(),
-        content: result.0,
-        created_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
-    };
-    let _ = state.db.save_ai_analysis(&analysis);
+    Ok(())
+}
 
-    if let Some(usage) = result.1 {
-        let _ = state.db.record_token_usage(&ai_config.model_name, usage.prompt_tokens, usage.completion_tokens);
+/// 批量 AI 诊断全部自选股：每完成一只推送 watchlist-diagnose-progress 事件，
+/// 单只的流式输出仍走 ai-diagnose-{code}；当日已诊断的直接复用（force 为 true 时重新诊断）
+#[tauri::command]
+pub async fn diagnose_watchlist(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    concurrency: Option<usize>,
+    force: Option<bool>,
+) -> Result<WatchlistDiagnoseDigest, AppError> {
+    log::info!("[watchlist_cmd] diagnose_watchlist concurrency={:?} force={:?}", concurrency, force);
+    let settings = state.db.load_settings().map_err(|e| {
+        log::error!("[watchlist_cmd] diagnose_watchlist load_settings failed: {}", e);
+        AppError::from(e)
+    })?;
+
+    let ai_config = settings.ai_configs.iter()
+        .find(|c| Some(c.id.clone()) == settings.active_ai_config_id && c.enabled)
+        .cloned()
+        .ok_or_else(|| {
+            log::error!("[watchlist_cmd] diagnose_watchlist: 未配置AI模型");
+            "未配置AI模型".to_string()
+        })?;
+
+    watchlist_diagnose::diagnose_watchlist(
+        &state.db,
+        &app,
+        &ai_config,
+        &ToolContext::from_settings(&settings),
+        concurrency.unwrap_or(watchlist_diagnose::DEFAULT_CONCURRENCY),
+        force.unwrap_or(false),
+    ).await.map_err(|e| {
+        log::error!("[watchlist_cmd] diagnose_watchlist failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 盯盘助手任务列表，code 不为空时只返回该股票的任务
+#[tauri::command]
+pub async fn get_stock_schedules(
+    state: State<'_, AppState>,
+    code: Option<String>,
+) -> Result<Vec<StockSchedule>, AppError> {
+    let schedules = state.db.get_stock_schedules().map_err(|e| {
+        log::error!("[watchlist_cmd] get_stock_schedules failed: {}", e);
+        AppError::from(e)
+    })?;
+    Ok(match code.filter(|c| !c.is_empty()) {
+        Some(code) => schedules.into_iter().filter(|s| s.code == code).collect(),
+        None => schedules,
+    })
+}
+
+/// 新增或更新盯盘助手任务，id 为空时新建；返回保存后的任务
+#[tauri::command]
+pub async fn save_stock_schedule(
+    state: State<'_, AppState>,
+    schedule: StockSchedule,
+) -> Result<StockSchedule, AppError> {
+    log::info!("[watchlist_cmd] save_stock_schedule code={} time={}", schedule.code, schedule.time);
+    let mut schedule = schedule;
+    schedule.time = stock_schedule::normalize_time(&schedule.time).map_err(|e| AppError::InvalidInput(e.to_string()))?;
+    schedule.question = schedule.question.trim().to_string();
+    if schedule.question.is_empty() {
+        return Err(AppError::InvalidInput("请填写要问 AI 的问题".to_string()));
     }
+    let (code, name) = symbol_table::resolve_input(&state.db, &schedule.code, &schedule.name);
+    schedule.code = code;
+    schedule.name = name;
+    if schedule.id.is_empty() {
+        schedule.id = uuid::Uuid::new_v4().to_string();
+        schedule.created_at = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    }
+    state.db.save_stock_schedule(&schedule).map_err(|e| {
+        log::error!("[watchlist_cmd] save_stock_schedule failed: {}", e);
+        AppError::from(e)
+    })?;
+    Ok(schedule)
+}
 
-    Ok(())
+#[tauri::command]
+pub async fn delete_stock_schedule(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<(), AppError> {
+    log::info!("[watchlist_cmd] delete_stock_schedule id={}", id);
+    state.db.delete_stock_schedule(&id).map_err(|e| {
+        log::error!("[watchlist_cmd] delete_stock_schedule failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 盯盘助手任务的历史结果（倒序），每条标注相对上一次的变化行
+#[tauri::command]
+pub async fn get_stock_schedule_runs(
+    state: State<'_, AppState>,
+    id: String,
+    limit: Option<usize>,
+) -> Result<Vec<ScheduleRun>, AppError> {
+    stock_schedule::get_runs(&state.db, &id, limit.unwrap_or(10)).map_err(|e| {
+        log::error!("[watchlist_cmd] get_stock_schedule_runs failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+/// 立即执行一次盯盘快检（不影响当日定时执行）
+#[tauri::command]
+pub async fn run_stock_schedule(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<ScheduleRun, AppError> {
+    log::info!("[watchlist_cmd] run_stock_schedule id={}", id);
+    let settings = state.db.load_settings().map_err(AppError::from)?;
+    let ai_config = settings.active_ai_config().ok_or_else(|| {
+        log::error!("[watchlist_cmd] run_stock_schedule: 未配置AI模型");
+        "未配置AI模型".to_string()
+    })?;
+    let schedule = state.db.get_stock_schedules().map_err(AppError::from)?
+        .into_iter()
+        .find(|s| s.id == id)
+        .ok_or_else(|| AppError::NotFound("盯盘任务不存在".to_string()))?;
+
+    stock_schedule::run_check_in(&state.db, &ai_config, &ToolContext::from_settings(&settings), &schedule).await.map_err(|e| {
+        log::error!("[watchlist_cmd] run_stock_schedule failed for {}: {}", schedule.code, e);
+        AppError::from(e)
+    })
 }
@@ -0,0 +1,43 @@
+use tauri::AppHandle;
+use crate::models::window::DetachedWindow;
+use crate::services::window_manager;
+use crate::error::AppError;
+
+/// 打开独立窗口（mini_quote 迷你行情条 / kline 独立 K 线窗口）
+#[tauri::command]
+pub async fn open_detached_window(
+    app: AppHandle,
+    kind: String,
+    code: Option<String>,
+    name: Option<String>,
+) -> Result<DetachedWindow, AppError> {
+    window_manager::open(&app, &kind, code, name).map_err(|e| {
+        log::error!("[window_cmd] open_detached_window failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+#[tauri::command]
+pub async fn close_detached_window(app: AppHandle, label: String) -> Result<(), AppError> {
+    window_manager::close(&app, &label).map_err(|e| {
+        log::error!("[window_cmd] close_detached_window failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+#[tauri::command]
+pub async fn set_detached_window_on_top(
+    app: AppHandle,
+    label: String,
+    on_top: bool,
+) -> Result<DetachedWindow, AppError> {
+    window_manager::set_always_on_top(&app, &label, on_top).map_err(|e| {
+        log::error!("[window_cmd] set_detached_window_on_top failed: {}", e);
+        AppError::from(e)
+    })
+}
+
+#[tauri::command]
+pub async fn list_detached_windows(app: AppHandle) -> Result<Vec<DetachedWindow>, AppError> {
+    Ok(window_manager::list(&app))
+}
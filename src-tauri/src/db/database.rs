@@ -1,13 +1,23 @@
 use anyhow::Result;
 use rusqlite::Connection;
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Mutex;
 
-use crate::models::ai::AIAnalysisResult;
+use crate::models::ai::{AIAnalysisResult, PickRecord, PickSessionSummary};
+use crate::models::audio::AudioExport;
+use crate::models::briefing::MarketBriefing;
+use crate::models::f10::ValuationPoint;
+use crate::models::job::JobRun;
+use crate::models::news::{AnnouncementEvent, NewsCategory, NewsItem};
+use crate::models::replay::{IntradaySnapshot, ReplayDay};
+use crate::models::schedule::StockSchedule;
 use crate::models::settings::AppSettings;
-use crate::models::stock::StockDailyHistory;
-use crate::models::watchlist::WatchlistStock;
-use crate::models::tracking::AIPickTracking;
+use crate::models::stock::{BoardMember, BoardRankRecord, ClosingAuction, FactorScore, FundFlowDay, MarketBreadth, SmartSearchQuery, StockDailyHistory, StockRps, StockSymbol};
+use crate::models::watchlist::{SignalAlert, TechnicalDaily, WatchlistSnapshot, WatchlistStock};
+use crate::models::tracking::{AIPickTracking, InstructionOutcomeStats, InstructionRecord};
+use crate::models::agent_session::{AgentSession, PickCheckpoint};
+use crate::models::ai::TokenUsage;
 
 pub struct Database {
     conn: Mutex<Connection>,
@@ -110,11 +120,343 @@ impl Database {
             );
 
             CREATE INDEX IF NOT EXISTS idx_tracking_date ON ai_pick_tracking(added_date);
+
+            CREATE TABLE IF NOT EXISTS agent_sessions (
+                id TEXT PRIMARY KEY,
+                kind TEXT NOT NULL,
+                subject TEXT NOT NULL DEFAULT '',
+                model_name TEXT NOT NULL,
+                tool_calls TEXT NOT NULL DEFAULT '[]',
+                content TEXT NOT NULL DEFAULT '',
+                raw_content TEXT NOT NULL DEFAULT '',
+                error TEXT,
+                prompt_tokens INTEGER NOT NULL DEFAULT 0,
+                completion_tokens INTEGER NOT NULL DEFAULT 0,
+                total_tokens INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_agent_sessions_date ON agent_sessions(created_at);
+
+            CREATE TABLE IF NOT EXISTS watchlist_snapshots (
+                code TEXT NOT NULL,
+                date TEXT NOT NULL,
+                name TEXT NOT NULL,
+                price REAL NOT NULL,
+                change_pct REAL NOT NULL,
+                turnover_rate REAL NOT NULL DEFAULT 0,
+                volume_ratio REAL NOT NULL DEFAULT 0,
+                amount REAL NOT NULL DEFAULT 0,
+                pe_ttm REAL NOT NULL DEFAULT 0,
+                pb REAL NOT NULL DEFAULT 0,
+                total_market_cap REAL NOT NULL DEFAULT 0,
+                main_net_inflow REAL NOT NULL DEFAULT 0,
+                main_net_pct REAL NOT NULL DEFAULT 0,
+                pct_5d REAL NOT NULL DEFAULT 0,
+                pct_20d REAL NOT NULL DEFAULT 0,
+                ma_alignment TEXT NOT NULL DEFAULT '',
+                tech_summary TEXT NOT NULL DEFAULT '',
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                PRIMARY KEY (code, date)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_snapshot_date ON watchlist_snapshots(date);
+
+            CREATE TABLE IF NOT EXISTS signals_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                code TEXT NOT NULL,
+                name TEXT NOT NULL,
+                signal_type TEXT NOT NULL,
+                direction TEXT NOT NULL,
+                description TEXT NOT NULL,
+                strength INTEGER NOT NULL,
+                date TEXT NOT NULL,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                UNIQUE (code, date, signal_type, description)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_signals_date ON signals_history(date);
+
+            CREATE TABLE IF NOT EXISTS technical_daily (
+                code TEXT PRIMARY KEY,
+                date TEXT NOT NULL,
+                close REAL NOT NULL,
+                change_pct REAL NOT NULL,
+                ma5 REAL,
+                ma10 REAL,
+                ma20 REAL,
+                ma60 REAL,
+                macd_dif REAL,
+                macd_dea REAL,
+                macd_hist REAL,
+                kdj_k REAL,
+                kdj_d REAL,
+                kdj_j REAL,
+                rsi6 REAL,
+                boll_upper REAL,
+                boll_middle REAL,
+                boll_lower REAL,
+                ma_alignment TEXT NOT NULL DEFAULT '',
+                signals TEXT NOT NULL DEFAULT '[]',
+                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_technical_daily_date ON technical_daily(date);
+
+            CREATE TABLE IF NOT EXISTS valuation_history (
+                code TEXT NOT NULL,
+                date TEXT NOT NULL,
+                close REAL NOT NULL DEFAULT 0,
+                pe_ttm REAL NOT NULL DEFAULT 0,
+                pb REAL NOT NULL DEFAULT 0,
+                PRIMARY KEY (code, date)
+            );
+
+            CREATE TABLE IF NOT EXISTS market_briefing (
+                date TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                content TEXT NOT NULL,
+                model_name TEXT NOT NULL DEFAULT '',
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                PRIMARY KEY (date, kind)
+            );
+
+            CREATE TABLE IF NOT EXISTS instruction_history (
+                date TEXT NOT NULL,
+                code TEXT NOT NULL,
+                name TEXT NOT NULL,
+                action TEXT NOT NULL,
+                label TEXT NOT NULL DEFAULT '',
+                reason TEXT NOT NULL DEFAULT '',
+                score INTEGER NOT NULL DEFAULT 0,
+                current_pct REAL NOT NULL DEFAULT 0,
+                next_date TEXT,
+                next_day_pct REAL,
+                next_day_high_pct REAL,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                PRIMARY KEY (date, code)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_instruction_history_action ON instruction_history(action);
+
+            CREATE TABLE IF NOT EXISTS ai_picks (
+                session_id TEXT NOT NULL,
+                date TEXT NOT NULL,
+                code TEXT NOT NULL,
+                name TEXT NOT NULL,
+                rating TEXT NOT NULL DEFAULT '',
+                reason TEXT NOT NULL DEFAULT '',
+                sector TEXT NOT NULL DEFAULT '',
+                highlights TEXT NOT NULL DEFAULT '[]',
+                fund_flow TEXT NOT NULL DEFAULT '',
+                valuation TEXT NOT NULL DEFAULT '',
+                entry_price REAL NOT NULL DEFAULT 0,
+                verify_status TEXT NOT NULL DEFAULT 'unverified',
+                verify_note TEXT NOT NULL DEFAULT '',
+                created_at TEXT NOT NULL,
+                PRIMARY KEY (session_id, code)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_ai_picks_date ON ai_picks(date);
+
+            CREATE TABLE IF NOT EXISTS smart_search_history (
+                keyword TEXT PRIMARY KEY,
+                result_count INTEGER NOT NULL DEFAULT 0,
+                run_count INTEGER NOT NULL DEFAULT 1,
+                is_favorite INTEGER NOT NULL DEFAULT 0,
+                last_run_at TEXT NOT NULL,
+                created_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+
+            CREATE TABLE IF NOT EXISTS stock_symbols (
+                code TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS market_breadth (
+                date TEXT PRIMARY KEY,
+                total INTEGER NOT NULL,
+                advancers INTEGER NOT NULL,
+                decliners INTEGER NOT NULL,
+                flat INTEGER NOT NULL,
+                limit_up INTEGER NOT NULL,
+                limit_down INTEGER NOT NULL,
+                above_ma20_pct REAL,
+                above_ma60_pct REAL,
+                new_high_52w INTEGER NOT NULL DEFAULT 0,
+                new_low_52w INTEGER NOT NULL DEFAULT 0,
+                sample_size INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS stock_rps (
+                date TEXT NOT NULL,
+                code TEXT NOT NULL,
+                name TEXT NOT NULL,
+                close REAL NOT NULL,
+                pct_20d REAL,
+                pct_60d REAL,
+                pct_120d REAL,
+                rps_20 REAL,
+                rps_60 REAL,
+                rps_120 REAL,
+                PRIMARY KEY (date, code)
+            );
+
+            CREATE TABLE IF NOT EXISTS board_rank_history (
+                date TEXT NOT NULL,
+                code TEXT NOT NULL,
+                name TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                change_pct REAL NOT NULL,
+                rank INTEGER NOT NULL,
+                total INTEGER NOT NULL,
+                main_net_inflow REAL NOT NULL DEFAULT 0,
+                PRIMARY KEY (date, code)
+            );
+
+            CREATE TABLE IF NOT EXISTS job_runs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                job_id TEXT NOT NULL,
+                started_at TEXT NOT NULL,
+                finished_at TEXT NOT NULL,
+                success INTEGER NOT NULL,
+                message TEXT NOT NULL DEFAULT ''
+            );
+            CREATE INDEX IF NOT EXISTS idx_job_runs_job ON job_runs(job_id, id);
+
+            CREATE TABLE IF NOT EXISTS pick_checkpoints (
+                session_id TEXT PRIMARY KEY,
+                data TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS sync_state (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS intraday_snapshots (
+                date TEXT NOT NULL,
+                code TEXT NOT NULL,
+                time TEXT NOT NULL,
+                name TEXT NOT NULL,
+                price REAL NOT NULL,
+                pre_close REAL NOT NULL,
+                volume REAL NOT NULL,
+                amount REAL NOT NULL,
+                bid1 REAL NOT NULL DEFAULT 0,
+                ask1 REAL NOT NULL DEFAULT 0,
+                bid1_vol REAL NOT NULL DEFAULT 0,
+                ask1_vol REAL NOT NULL DEFAULT 0,
+                PRIMARY KEY (date, code, time)
+            );
+
+            CREATE TABLE IF NOT EXISTS audio_exports (
+                source_kind TEXT NOT NULL,
+                source_id TEXT NOT NULL,
+                path TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                PRIMARY KEY (source_kind, source_id)
+            );
+
+            CREATE TABLE IF NOT EXISTS stock_schedules (
+                id TEXT PRIMARY KEY,
+                code TEXT NOT NULL,
+                name TEXT NOT NULL,
+                time TEXT NOT NULL,
+                question TEXT NOT NULL,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                last_run_date TEXT NOT NULL DEFAULT '',
+                created_at TEXT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS factor_scores (
+                date TEXT NOT NULL,
+                code TEXT NOT NULL,
+                name TEXT NOT NULL,
+                score REAL NOT NULL,
+                quality REAL,
+                growth REAL,
+                value REAL,
+                momentum REAL,
+                flow REAL,
+                PRIMARY KEY (date, code)
+            );
+            CREATE INDEX IF NOT EXISTS idx_factor_scores_code ON factor_scores(code, date);
+
+            CREATE TABLE IF NOT EXISTS board_members (
+                board_code TEXT NOT NULL,
+                board_name TEXT NOT NULL,
+                code TEXT NOT NULL,
+                name TEXT NOT NULL,
+                first_seen TEXT NOT NULL,
+                is_new INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (board_code, code)
+            );
+            CREATE INDEX IF NOT EXISTS idx_board_members_code ON board_members(code);
+
+            CREATE TABLE IF NOT EXISTS news_archive (
+                id TEXT PRIMARY KEY,
+                publish_time TEXT NOT NULL,
+                title TEXT NOT NULL,
+                summary TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_news_archive_time ON news_archive(publish_time);
+
+            CREATE TABLE IF NOT EXISTS announcement_events (
+                id TEXT PRIMARY KEY,
+                code TEXT NOT NULL,
+                name TEXT NOT NULL,
+                notice_date TEXT NOT NULL,
+                category TEXT NOT NULL,
+                title TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_announcement_events_date ON announcement_events(notice_date);
+
+            CREATE TABLE IF NOT EXISTS fund_flow_history (
+                code TEXT NOT NULL,
+                date TEXT NOT NULL,
+                main_net REAL NOT NULL,
+                super_large_net REAL,
+                large_net REAL,
+                medium_net REAL,
+                small_net REAL,
+                main_pct REAL NOT NULL DEFAULT 0,
+                close REAL NOT NULL DEFAULT 0,
+                change_pct REAL NOT NULL DEFAULT 0,
+                PRIMARY KEY (code, date)
+            );
+            CREATE INDEX IF NOT EXISTS idx_fund_flow_date ON fund_flow_history(date);
+
+            CREATE TABLE IF NOT EXISTS closing_auctions (
+                code TEXT NOT NULL,
+                date TEXT NOT NULL,
+                pre_price REAL NOT NULL,
+                close REAL NOT NULL,
+                deviation_pct REAL NOT NULL,
+                auction_volume REAL NOT NULL,
+                auction_amount REAL NOT NULL,
+                volume_share REAL NOT NULL,
+                after_hours_volume REAL,
+                after_hours_amount REAL,
+                flag TEXT,
+                PRIMARY KEY (code, date)
+            );
             ",
         )?;
         Ok(())
     }
 
+    /// 退出前调用：等待进行中的写入完成（持有连接锁），并让 SQLite 更新查询统计
+    pub fn flush(&self) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute_batch("PRAGMA optimize;")?;
+        Ok(())
+    }
+
     pub fn save_settings(&self, settings: &AppSettings) -> Result<()> {
         let conn = self.conn.lock().unwrap();
         let data = serde_json::to_string(settings)?;
@@ -159,6 +501,54 @@ impl Database {
         Ok(())
     }
 
+    pub fn get_ai_analysis(&self, id: &str) -> Result<Option<AIAnalysisResult>> {
+        let conn = self.conn.lock().unwrap();
+        let result = conn.query_row(
+            "SELECT id, code, name, model_name, question, content, created_at FROM ai_analysis WHERE id = ?1",
+            rusqlite::params![id],
+            |row| {
+                Ok(AIAnalysisResult {
+                    id: row.get(0)?,
+                    code: row.get(1)?,
+                    name: row.get(2)?,
+                    model_name: row.get(3)?,
+                    question: row.get(4)?,
+                    content: row.get(5)?,
+                    created_at: row.get(6)?,
+                })
+            },
+        );
+        match result {
+            Ok(r) => Ok(Some(r)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// 某只股票同一问题的分析记录（如盯盘快检），按时间倒序
+    pub fn get_ai_analysis_by_question(&self, code: &str, question: &str, limit: usize) -> Result<Vec<AIAnalysisResult>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, code, name, model_name, question, content, created_at FROM ai_analysis WHERE code = ?1 AND question = ?2 ORDER BY created_at DESC LIMIT ?3",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![code, question, limit], |row| {
+            Ok(AIAnalysisResult {
+                id: row.get(0)?,
+                code: row.get(1)?,
+                name: row.get(2)?,
+                model_name: row.get(3)?,
+                question: row.get(4)?,
+                content: row.get(5)?,
+                created_at: row.get(6)?,
+            })
+        })?;
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(row?);
+        }
+        Ok(results)
+    }
+
     pub fn get_ai_analysis_history(&self, code: &str, limit: usize) -> Result<Vec<AIAnalysisResult>> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
@@ -207,6 +597,32 @@ impl Database {
         }
     }
 
+    /// 当日指定类型（question）的最新一条分析
+    pub fn get_today_ai_analysis_by_question(&self, code: &str, question: &str) -> Result<Option<AIAnalysisResult>> {
+        let conn = self.conn.lock().unwrap();
+        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+        let result = conn.query_row(
+            "SELECT id, code, name, model_name, question, content, created_at FROM ai_analysis WHERE code = ?1 AND question = ?2 AND created_at >= ?3 ORDER BY created_at DESC LIMIT 1",
+            rusqlite::params![code, question, today],
+            |row| {
+                Ok(AIAnalysisResult {
+                    id: row.get(0)?,
+                    code: row.get(1)?,
+                    name: row.get(2)?,
+                    model_name: row.get(3)?,
+                    question: row.get(4)?,
+                    content: row.get(5)?,
+                    created_at: row.get(6)?,
+                })
+            },
+        );
+        match result {
+            Ok(r) => Ok(Some(r)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
     pub fn save_daily_history(&self, records: &[StockDailyHistory]) -> Result<()> {
         log::info!("[database] save_daily_history: {} records", records.len());
         let conn = self.conn.lock().unwrap();
@@ -248,6 +664,35 @@ impl Database {
         Ok(results)
     }
 
+    /// 所有股票 since（含）之后的日线缓存，按代码分组、日期正序
+    pub fn get_daily_histories_since(&self, since: &str) -> Result<HashMap<String, Vec<StockDailyHistory>>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT code, date, close, high, low, open_price, volume, amount, change_pct, is_limit_up, turnover_rate FROM stock_daily_history WHERE date >= ?1 ORDER BY code, date ASC",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![since], |row| {
+            Ok(StockDailyHistory {
+                code: row.get(0)?,
+                date: row.get(1)?,
+                close: row.get(2)?,
+                high: row.get(3)?,
+                low: row.get(4)?,
+                open: row.get(5)?,
+                volume: row.get(6)?,
+                amount: row.get(7)?,
+                change_pct: row.get(8)?,
+                is_limit_up: row.get::<_, i32>(9)? != 0,
+                turnover_rate: row.get(10)?,
+            })
+        })?;
+        let mut results: HashMap<String, Vec<StockDailyHistory>> = HashMap::new();
+        for row in rows {
+            let record = row?;
+            results.entry(record.code.clone()).or_default().push(record);
+        }
+        Ok(results)
+    }
+
     pub fn record_token_usage(&self, model_name: &str, prompt_tokens: u32, completion_tokens: u32) -> Result<()> {
         let conn = self.conn.lock().unwrap();
         let today = chrono::Local::now().format("%Y-%m-%d").to_string();
@@ -394,42 +839,106 @@ impl Database {
         Ok(results)
     }
 
-    // ====== AI Pick Cache ======
+    // ====== AI Pick Methods ======
 
-    pub fn save_ai_pick_cache(&self, content: &str) -> Result<()> {
+    pub fn save_pick_records(&self, records: &[PickRecord]) -> Result<()> {
         let conn = self.conn.lock().unwrap();
-        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
-        conn.execute(
-            "INSERT OR REPLACE INTO ai_pick_cache (date, content, created_at) VALUES (?1, ?2, datetime('now'))",
-            rusqlite::params![today, content],
-        )?;
+        let tx = conn.unchecked_transaction()?;
+        for r in records {
+            let highlights = serde_json::to_string(&r.highlights)?;
+            tx.execute(
+                "INSERT OR REPLACE INTO ai_picks (session_id, date, code, name, rating, reason, sector, highlights, fund_flow, valuation, entry_price, verify_status, verify_note, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+                rusqlite::params![r.session_id, r.date, r.code, r.name, r.rating, r.reason, r.sector, highlights, r.fund_flow, r.valuation, r.entry_price, r.verify_status, r.verify_note, r.created_at],
+            )?;
+        }
+        tx.commit()?;
         Ok(())
     }
 
-    pub fn get_ai_pick_cache(&self) -> Result<Option<String>> {
+    pub fn get_pick_records(&self, session_id: &str) -> Result<Vec<PickRecord>> {
         let conn = self.conn.lock().unwrap();
-        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
-        let result = conn.query_row(
-            "SELECT content FROM ai_pick_cache WHERE date = ?1",
-            rusqlite::params![today],
-            |row| row.get::<_, String>(0),
-        );
-        match result {
-            Ok(content) => Ok(Some(content)),
-            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-            Err(e) => Err(e.into()),
+        let mut stmt = conn.prepare(
+            "SELECT session_id, date, code, name, rating, reason, sector, highlights, fund_flow, valuation, entry_price, verify_status, verify_note, created_at FROM ai_picks WHERE session_id = ?1 ORDER BY rowid",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![session_id], Self::row_to_pick_record)?;
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(row?);
         }
+        Ok(results)
     }
 
-    // ====== AI Pick Tracking Methods ======
-
-    pub fn add_tracking_stock(&self, tracking: &AIPickTracking) -> Result<()> {
+    /// since（含）之后的全部选股推荐，按日期升序
+    pub fn get_picks_since(&self, since: &str) -> Result<Vec<PickRecord>> {
         let conn = self.conn.lock().unwrap();
-        conn.execute(
-            "INSERT OR REPLACE INTO ai_pick_tracking (code, name, added_date, added_price, rating, reason, sector, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
-            rusqlite::params![tracking.code, tracking.name, tracking.added_date, tracking.added_price, tracking.rating, tracking.reason, tracking.sector, tracking.created_at],
+        let mut stmt = conn.prepare(
+            "SELECT session_id, date, code, name, rating, reason, sector, highlights, fund_flow, valuation, entry_price, verify_status, verify_note, created_at FROM ai_picks WHERE date >= ?1 ORDER BY date, rowid",
         )?;
-        Ok(())
+        let rows = stmt.query_map(rusqlite::params![since], Self::row_to_pick_record)?;
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(row?);
+        }
+        Ok(results)
+    }
+
+    /// 选股会话列表（按时间倒序），date 为空时不限日期
+    pub fn get_pick_sessions(&self, date: Option<&str>, limit: usize) -> Result<Vec<PickSessionSummary>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT s.id, s.model_name, s.total_tokens, s.error, s.created_at, \
+             (SELECT COUNT(*) FROM ai_picks p WHERE p.session_id = s.id) \
+             FROM agent_sessions s WHERE s.kind = 'pick' AND (?1 IS NULL OR substr(s.created_at, 1, 10) = ?1) \
+             ORDER BY s.created_at DESC LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![date, limit], |row| {
+            let created_at: String = row.get(4)?;
+            Ok(PickSessionSummary {
+                session_id: row.get(0)?,
+                date: created_at.chars().take(10).collect(),
+                model_name: row.get(1)?,
+                total_tokens: row.get(2)?,
+                error: row.get(3)?,
+                created_at,
+                pick_count: row.get::<_, i64>(5)? as usize,
+            })
+        })?;
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(row?);
+        }
+        Ok(results)
+    }
+
+    fn row_to_pick_record(row: &rusqlite::Row) -> rusqlite::Result<PickRecord> {
+        let highlights: String = row.get(7)?;
+        Ok(PickRecord {
+            session_id: row.get(0)?,
+            date: row.get(1)?,
+            code: row.get(2)?,
+            name: row.get(3)?,
+            rating: row.get(4)?,
+            reason: row.get(5)?,
+            sector: row.get(6)?,
+            highlights: serde_json::from_str(&highlights).unwrap_or_default(),
+            fund_flow: row.get(8)?,
+            valuation: row.get(9)?,
+            entry_price: row.get(10)?,
+            verify_status: row.get(11)?,
+            verify_note: row.get(12)?,
+            created_at: row.get(13)?,
+        })
+    }
+
+    // ====== AI Pick Tracking Methods ======
+
+    pub fn add_tracking_stock(&self, tracking: &AIPickTracking) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO ai_pick_tracking (code, name, added_date, added_price, rating, reason, sector, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            rusqlite::params![tracking.code, tracking.name, tracking.added_date, tracking.added_price, tracking.rating, tracking.reason, tracking.sector, tracking.created_at],
+        )?;
+        Ok(())
     }
 
     pub fn remove_tracking_stock(&self, code: &str, added_date: &str) -> Result<()> {
@@ -473,4 +982,1470 @@ impl Database {
         )?;
         Ok(())
     }
+
+    // ====== Agent Session Methods ======
+
+    pub fn save_agent_session(&self, session: &AgentSession) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let tool_calls = serde_json::to_string(&session.tool_calls)?;
+        let usage = session.usage.clone().unwrap_or(TokenUsage {
+            prompt_tokens: 0,
+            completion_tokens: 0,
+            total_tokens: 0,
+        });
+        conn.execute(
+            "INSERT OR REPLACE INTO agent_sessions (id, kind, subject, model_name, tool_calls, content, raw_content, error, prompt_tokens, completion_tokens, total_tokens, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            rusqlite::params![session.id, session.kind, session.subject, session.model_name, tool_calls, session.content, session.raw_content, session.error, usage.prompt_tokens, usage.completion_tokens, usage.total_tokens, session.created_at],
+        ).map_err(|e| {
+            log::error!("[database] save_agent_session failed: {}", e);
+            e
+        })?;
+        Ok(())
+    }
+
+    pub fn get_agent_session(&self, id: &str) -> Result<Option<AgentSession>> {
+        let conn = self.conn.lock().unwrap();
+        let result = conn.query_row(
+            "SELECT id, kind, subject, model_name, tool_calls, content, raw_content, error, prompt_tokens, completion_tokens, total_tokens, created_at FROM agent_sessions WHERE id = ?1",
+            rusqlite::params![id],
+            Self::row_to_agent_session,
+        );
+        match result {
+            Ok(session) => Ok(Some(session)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn get_agent_sessions(&self, kind: Option<&str>, limit: usize) -> Result<Vec<AgentSession>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, kind, subject, model_name, tool_calls, content, raw_content, error, prompt_tokens, completion_tokens, total_tokens, created_at FROM agent_sessions WHERE (?1 IS NULL OR kind = ?1) ORDER BY created_at DESC LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![kind, limit], Self::row_to_agent_session)?;
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(row?);
+        }
+        Ok(results)
+    }
+
+    /// 指定日期内最近一次成功完成的某类会话
+    pub fn get_latest_agent_session(&self, kind: &str, date: &str) -> Result<Option<AgentSession>> {
+        let conn = self.conn.lock().unwrap();
+        let result = conn.query_row(
+            "SELECT id, kind, subject, model_name, tool_calls, content, raw_content, error, prompt_tokens, completion_tokens, total_tokens, created_at FROM agent_sessions WHERE kind = ?1 AND substr(created_at, 1, 10) = ?2 AND error IS NULL AND content != '' ORDER BY created_at DESC LIMIT 1",
+            rusqlite::params![kind, date],
+            Self::row_to_agent_session,
+        );
+        match result {
+            Ok(session) => Ok(Some(session)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn row_to_agent_session(row: &rusqlite::Row) -> rusqlite::Result<AgentSession> {
+        let tool_calls: String = row.get(4)?;
+        let total_tokens: u32 = row.get(10)?;
+        Ok(AgentSession {
+            id: row.get(0)?,
+            kind: row.get(1)?,
+            subject: row.get(2)?,
+            model_name: row.get(3)?,
+            tool_calls: serde_json::from_str(&tool_calls).unwrap_or_default(),
+            content: row.get(5)?,
+            raw_content: row.get(6)?,
+            error: row.get(7)?,
+            usage: if total_tokens > 0 {
+                Some(TokenUsage {
+                    prompt_tokens: row.get(8)?,
+                    completion_tokens: row.get(9)?,
+                    total_tokens,
+                })
+            } else {
+                None
+            },
+            created_at: row.get(11)?,
+        })
+    }
+
+    // ====== Watchlist Snapshot Methods ======
+
+    pub fn save_watchlist_snapshots(&self, snapshots: &[WatchlistSnapshot]) -> Result<()> {
+        log::info!("[database] save_watchlist_snapshots: {} records", snapshots.len());
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        for s in snapshots {
+            tx.execute(
+                "INSERT OR REPLACE INTO watchlist_snapshots (code, date, name, price, change_pct, turnover_rate, volume_ratio, amount, pe_ttm, pb, total_market_cap, main_net_inflow, main_net_pct, pct_5d, pct_20d, ma_alignment, tech_summary, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)",
+                rusqlite::params![s.code, s.date, s.name, s.price, s.change_pct, s.turnover_rate, s.volume_ratio, s.amount, s.pe_ttm, s.pb, s.total_market_cap, s.main_net_inflow, s.main_net_pct, s.pct_5d, s.pct_20d, s.ma_alignment, s.tech_summary, s.created_at],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn get_latest_snapshot_date(&self) -> Result<Option<String>> {
+        let conn = self.conn.lock().unwrap();
+        let result = conn.query_row(
+            "SELECT MAX(date) FROM watchlist_snapshots",
+            [],
+            |row| row.get::<_, Option<String>>(0),
+        )?;
+        Ok(result)
+    }
+
+    /// 获取某日全部自选股快照
+    pub fn get_watchlist_snapshots(&self, date: &str) -> Result<Vec<WatchlistSnapshot>> {
+        self.query_snapshots("WHERE date = ?1 ORDER BY code ASC LIMIT ?2", date, i64::MAX)
+    }
+
+    /// 获取单只股票最近的快照（按日期倒序）
+    pub fn get_stock_snapshots(&self, code: &str, limit: usize) -> Result<Vec<WatchlistSnapshot>> {
+        self.query_snapshots("WHERE code = ?1 ORDER BY date DESC LIMIT ?2", code, limit as i64)
+    }
+
+    fn query_snapshots(&self, clause: &str, key: &str, limit: i64) -> Result<Vec<WatchlistSnapshot>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT code, name, date, price, change_pct, turnover_rate, volume_ratio, amount, pe_ttm, pb, total_market_cap, main_net_inflow, main_net_pct, pct_5d, pct_20d, ma_alignment, tech_summary, created_at FROM watchlist_snapshots {}",
+            clause
+        ))?;
+        let rows = stmt.query_map(rusqlite::params![key, limit], |row| {
+            Ok(WatchlistSnapshot {
+                code: row.get(0)?,
+                name: row.get(1)?,
+                date: row.get(2)?,
+                price: row.get(3)?,
+                change_pct: row.get(4)?,
+                turnover_rate: row.get(5)?,
+                volume_ratio: row.get(6)?,
+                amount: row.get(7)?,
+                pe_ttm: row.get(8)?,
+                pb: row.get(9)?,
+                total_market_cap: row.get(10)?,
+                main_net_inflow: row.get(11)?,
+                main_net_pct: row.get(12)?,
+                pct_5d: row.get(13)?,
+                pct_20d: row.get(14)?,
+                ma_alignment: row.get(15)?,
+                tech_summary: row.get(16)?,
+                created_at: row.get(17)?,
+            })
+        })?;
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(row?);
+        }
+        Ok(results)
+    }
+
+    // ====== Signal History Methods ======
+
+    /// 写入信号记录，已存在的（同股票/日期/类型/描述）忽略；返回实际新增的记录
+    pub fn save_signal_alerts(&self, alerts: &[SignalAlert]) -> Result<Vec<SignalAlert>> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        let mut inserted = Vec::new();
+        for a in alerts {
+            let changed = tx.execute(
+                "INSERT OR IGNORE INTO signals_history (code, name, signal_type, direction, description, strength, date, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                rusqlite::params![a.code, a.name, a.signal_type, a.direction, a.description, a.strength, a.date, a.created_at],
+            )?;
+            if changed > 0 {
+                inserted.push(a.clone());
+            }
+        }
+        tx.commit()?;
+        Ok(inserted)
+    }
+
+    pub fn get_signal_history(&self, code: Option<&str>, limit: usize) -> Result<Vec<SignalAlert>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT code, name, signal_type, direction, description, strength, date, created_at FROM signals_history WHERE (?1 IS NULL OR code = ?1) ORDER BY date DESC, strength DESC LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![code, limit], |row| {
+            Ok(SignalAlert {
+                code: row.get(0)?,
+                name: row.get(1)?,
+                signal_type: row.get(2)?,
+                direction: row.get(3)?,
+                description: row.get(4)?,
+                strength: row.get(5)?,
+                date: row.get(6)?,
+                created_at: row.get(7)?,
+            })
+        })?;
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(row?);
+        }
+        Ok(results)
+    }
+
+    /// since（含）之后触发的信号，按日期升序
+    pub fn get_signals_since(&self, since: &str) -> Result<Vec<SignalAlert>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT code, name, signal_type, direction, description, strength, date, created_at FROM signals_history WHERE date >= ?1 ORDER BY date ASC, strength DESC",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![since], |row| {
+            Ok(SignalAlert {
+                code: row.get(0)?,
+                name: row.get(1)?,
+                signal_type: row.get(2)?,
+                direction: row.get(3)?,
+                description: row.get(4)?,
+                strength: row.get(5)?,
+                date: row.get(6)?,
+                created_at: row.get(7)?,
+            })
+        })?;
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(row?);
+        }
+        Ok(results)
+    }
+
+    // ====== Technical Daily Methods ======
+
+    pub fn save_technical_daily(&self, records: &[TechnicalDaily]) -> Result<()> {
+        log::info!("[database] save_technical_daily: {} records", records.len());
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        for t in records {
+            let signals = serde_json::to_string(&t.signals)?;
+            tx.execute(
+                "INSERT OR REPLACE INTO technical_daily (code, date, close, change_pct, ma5, ma10, ma20, ma60, macd_dif, macd_dea, macd_hist, kdj_k, kdj_d, kdj_j, rsi6, boll_upper, boll_middle, boll_lower, ma_alignment, signals, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21)",
+                rusqlite::params![t.code, t.date, t.close, t.change_pct, t.ma5, t.ma10, t.ma20, t.ma60, t.macd_dif, t.macd_dea, t.macd_hist, t.kdj_k, t.kdj_d, t.kdj_j, t.rsi6, t.boll_upper, t.boll_middle, t.boll_lower, t.ma_alignment, signals, t.updated_at],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn get_technical_daily(&self, code: &str) -> Result<Option<TechnicalDaily>> {
+        Ok(self.query_technical_daily(Some(code))?.pop())
+    }
+
+    /// 获取全部预计算的技术指标记录
+    pub fn get_all_technical_daily(&self) -> Result<Vec<TechnicalDaily>> {
+        self.query_technical_daily(None)
+    }
+
+    /// 各股票已预计算到的K线日期，用于增量更新
+    pub fn get_technical_daily_dates(&self) -> Result<HashMap<String, String>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT code, date FROM technical_daily")?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+        let mut results = HashMap::new();
+        for row in rows {
+            let (code, date) = row?;
+            results.insert(code, date);
+        }
+        Ok(results)
+    }
+
+    /// 各股票本地日线缓存的最新日期
+    pub fn get_latest_history_dates(&self) -> Result<HashMap<String, String>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT code, MAX(date) FROM stock_daily_history GROUP BY code")?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+        let mut results = HashMap::new();
+        for row in rows {
+            let (code, date) = row?;
+            results.insert(code, date);
+        }
+        Ok(results)
+    }
+
+    /// 本地日线缓存中最近的 n 个交易日（倒序）
+    pub fn get_recent_trade_dates(&self, n: usize) -> Result<Vec<String>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT DISTINCT date FROM stock_daily_history ORDER BY date DESC LIMIT ?1")?;
+        let rows = stmt.query_map(rusqlite::params![n], |row| row.get::<_, String>(0))?;
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(row?);
+        }
+        Ok(results)
+    }
+
+    fn query_technical_daily(&self, code: Option<&str>) -> Result<Vec<TechnicalDaily>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT code, date, close, change_pct, ma5, ma10, ma20, ma60, macd_dif, macd_dea, macd_hist, kdj_k, kdj_d, kdj_j, rsi6, boll_upper, boll_middle, boll_lower, ma_alignment, signals, updated_at FROM technical_daily WHERE (?1 IS NULL OR code = ?1) ORDER BY code ASC",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![code], |row| {
+            let signals: String = row.get(19)?;
+            Ok(TechnicalDaily {
+                code: row.get(0)?,
+                date: row.get(1)?,
+                close: row.get(2)?,
+                change_pct: row.get(3)?,
+                ma5: row.get(4)?,
+                ma10: row.get(5)?,
+                ma20: row.get(6)?,
+                ma60: row.get(7)?,
+                macd_dif: row.get(8)?,
+                macd_dea: row.get(9)?,
+                macd_hist: row.get(10)?,
+                kdj_k: row.get(11)?,
+                kdj_d: row.get(12)?,
+                kdj_j: row.get(13)?,
+                rsi6: row.get(14)?,
+                boll_upper: row.get(15)?,
+                boll_middle: row.get(16)?,
+                boll_lower: row.get(17)?,
+                ma_alignment: row.get(18)?,
+                signals: serde_json::from_str(&signals).unwrap_or_default(),
+                updated_at: row.get(20)?,
+            })
+        })?;
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(row?);
+        }
+        Ok(results)
+    }
+
+    // ====== Valuation History Methods ======
+
+    pub fn save_valuation_history(&self, code: &str, points: &[ValuationPoint]) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        for p in points {
+            tx.execute(
+                "INSERT OR REPLACE INTO valuation_history (code, date, close, pe_ttm, pb) VALUES (?1, ?2, ?3, ?4, ?5)",
+                rusqlite::params![code, p.date, p.close, p.pe_ttm, p.pb],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn get_latest_valuation_date(&self, code: &str) -> Result<Option<String>> {
+        let conn = self.conn.lock().unwrap();
+        let result = conn.query_row(
+            "SELECT MAX(date) FROM valuation_history WHERE code = ?1",
+            rusqlite::params![code],
+            |row| row.get::<_, Option<String>>(0),
+        )?;
+        Ok(result)
+    }
+
+    /// 获取 since（含）之后的估值历史，按日期升序
+    pub fn get_valuation_history(&self, code: &str, since: &str) -> Result<Vec<ValuationPoint>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT date, close, pe_ttm, pb FROM valuation_history WHERE code = ?1 AND date >= ?2 ORDER BY date ASC",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![code, since], |row| {
+            Ok(ValuationPoint {
+                date: row.get(0)?,
+                close: row.get(1)?,
+                pe_ttm: row.get(2)?,
+                pb: row.get(3)?,
+            })
+        })?;
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(row?);
+        }
+        Ok(results)
+    }
+
+    // ====== Market Briefing Methods ======
+
+    pub fn save_market_briefing(&self, briefing: &MarketBriefing) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO market_briefing (date, kind, content, model_name, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![briefing.date, briefing.kind, briefing.content, briefing.model_name, briefing.created_at],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_market_briefing(&self, date: &str, kind: &str) -> Result<Option<MarketBriefing>> {
+        let conn = self.conn.lock().unwrap();
+        let result = conn.query_row(
+            "SELECT date, kind, content, model_name, created_at FROM market_briefing WHERE date = ?1 AND kind = ?2",
+            rusqlite::params![date, kind],
+            |row| {
+                Ok(MarketBriefing {
+                    date: row.get(0)?,
+                    kind: row.get(1)?,
+                    content: row.get(2)?,
+                    model_name: row.get(3)?,
+                    created_at: row.get(4)?,
+                })
+            },
+        );
+        match result {
+            Ok(r) => Ok(Some(r)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// 按关键词检索市场日志（匹配正文），kind 为空时检索全部类型，按日期倒序
+    pub fn search_market_briefing(&self, keyword: &str, kind: Option<&str>, limit: usize) -> Result<Vec<MarketBriefing>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT date, kind, content, model_name, created_at FROM market_briefing WHERE (?1 IS NULL OR kind = ?1) AND content LIKE ?2 ORDER BY date DESC, kind LIMIT ?3",
+        )?;
+        let pattern = format!("%{}%", keyword);
+        let rows = stmt.query_map(rusqlite::params![kind, pattern, limit], |row| {
+            Ok(MarketBriefing {
+                date: row.get(0)?,
+                kind: row.get(1)?,
+                content: row.get(2)?,
+                model_name: row.get(3)?,
+                created_at: row.get(4)?,
+            })
+        })?;
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(row?);
+        }
+        Ok(results)
+    }
+
+    // ====== Instruction History Methods ======
+
+    /// 保存当日指令；同日同代码重复生成时覆盖
+    pub fn save_instructions(&self, records: &[InstructionRecord]) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        for r in records {
+            tx.execute(
+                "INSERT OR REPLACE INTO instruction_history (date, code, name, action, label, reason, score, current_pct, next_date, next_day_pct, next_day_high_pct, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                rusqlite::params![r.date, r.code, r.name, r.action, r.label, r.reason, r.score, r.current_pct, r.next_date, r.next_day_pct, r.next_day_high_pct, r.created_at],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 查询指令历史：date 为空时返回全部日期，按日期倒序
+    pub fn get_instruction_history(&self, date: Option<&str>, limit: usize) -> Result<Vec<InstructionRecord>> {
+        self.query_instructions(
+            "WHERE (?1 IS NULL OR date = ?1) ORDER BY date DESC, score DESC LIMIT ?2",
+            rusqlite::params![date, limit],
+        )
+    }
+
+    /// since（含）之后的指令，按日期升序
+    pub fn get_instructions_since(&self, since: &str) -> Result<Vec<InstructionRecord>> {
+        self.query_instructions(
+            "WHERE date >= ?1 ORDER BY date ASC, score DESC",
+            rusqlite::params![since],
+        )
+    }
+
+    /// 尚未回填次日表现、且早于 before 的指令
+    pub fn get_pending_instruction_outcomes(&self, before: &str) -> Result<Vec<InstructionRecord>> {
+        self.query_instructions(
+            "WHERE next_date IS NULL AND date < ?1 ORDER BY date ASC",
+            rusqlite::params![before],
+        )
+    }
+
+    pub fn update_instruction_outcome(&self, date: &str, code: &str, next_date: &str, next_day_pct: f64, next_day_high_pct: f64) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE instruction_history SET next_date = ?3, next_day_pct = ?4, next_day_high_pct = ?5 WHERE date = ?1 AND code = ?2",
+            rusqlite::params![date, code, next_date, next_day_pct, next_day_high_pct],
+        )?;
+        Ok(())
+    }
+
+    /// 按指令类型汇总 since（含）之后的次日表现
+    pub fn get_instruction_stats(&self, since: &str) -> Result<Vec<InstructionOutcomeStats>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT action, COUNT(*), COUNT(next_day_pct), COALESCE(AVG(next_day_pct), 0), COALESCE(AVG(next_day_high_pct), 0), \
+             COALESCE(100.0 * SUM(CASE WHEN next_day_pct > 0 THEN 1 ELSE 0 END) / NULLIF(COUNT(next_day_pct), 0), 0) \
+             FROM instruction_history WHERE date >= ?1 GROUP BY action ORDER BY action",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![since], |row| {
+            Ok(InstructionOutcomeStats {
+                action: row.get(0)?,
+                total: row.get(1)?,
+                evaluated: row.get(2)?,
+                avg_next_day_pct: row.get(3)?,
+                avg_next_day_high_pct: row.get(4)?,
+                win_rate: row.get(5)?,
+            })
+        })?;
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(row?);
+        }
+        Ok(results)
+    }
+
+    fn query_instructions(&self, clause: &str, params: &[&dyn rusqlite::ToSql]) -> Result<Vec<InstructionRecord>> {
+        let conn = self.conn.lock().unwrap();
+        let sql = format!(
+            "SELECT date, code, name, action, label, reason, score, current_pct, next_date, next_day_pct, next_day_high_pct, created_at FROM instruction_history {}",
+            clause
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params, |row| {
+            Ok(InstructionRecord {
+                date: row.get(0)?,
+                code: row.get(1)?,
+                name: row.get(2)?,
+                action: row.get(3)?,
+                label: row.get(4)?,
+                reason: row.get(5)?,
+                score: row.get(6)?,
+                current_pct: row.get(7)?,
+                next_date: row.get(8)?,
+                next_day_pct: row.get(9)?,
+                next_day_high_pct: row.get(10)?,
+                created_at: row.get(11)?,
+            })
+        })?;
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(row?);
+        }
+        Ok(results)
+    }
+
+    // ====== Smart Search History Methods ======
+
+    /// 记录一次 NLP 选股执行：新条件插入，已有条件累加次数并更新结果数
+    pub fn record_smart_search(&self, keyword: &str, result_count: usize) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        conn.execute(
+            "INSERT INTO smart_search_history (keyword, result_count, run_count, is_favorite, last_run_at, created_at) VALUES (?1, ?2, 1, 0, ?3, ?3) \
+             ON CONFLICT(keyword) DO UPDATE SET result_count = excluded.result_count, run_count = run_count + 1, last_run_at = excluded.last_run_at",
+            rusqlite::params![keyword, result_count, now],
+        )?;
+        Ok(())
+    }
+
+    /// 查询 NLP 选股历史，收藏优先、按最近执行时间倒序
+    pub fn get_smart_search_history(&self, favorites_only: bool, limit: usize) -> Result<Vec<SmartSearchQuery>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT keyword, result_count, run_count, is_favorite, last_run_at, created_at FROM smart_search_history WHERE (?1 = 0 OR is_favorite = 1) ORDER BY is_favorite DESC, last_run_at DESC LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![favorites_only, limit], |row| {
+            Ok(SmartSearchQuery {
+                keyword: row.get(0)?,
+                result_count: row.get(1)?,
+                run_count: row.get(2)?,
+                is_favorite: row.get(3)?,
+                last_run_at: row.get(4)?,
+                created_at: row.get(5)?,
+            })
+        })?;
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(row?);
+        }
+        Ok(results)
+    }
+
+    pub fn get_smart_search(&self, keyword: &str) -> Result<Option<SmartSearchQuery>> {
+        let conn = self.conn.lock().unwrap();
+        let result = conn.query_row(
+            "SELECT keyword, result_count, run_count, is_favorite, last_run_at, created_at FROM smart_search_history WHERE keyword = ?1",
+            rusqlite::params![keyword],
+            |row| {
+                Ok(SmartSearchQuery {
+                    keyword: row.get(0)?,
+                    result_count: row.get(1)?,
+                    run_count: row.get(2)?,
+                    is_favorite: row.get(3)?,
+                    last_run_at: row.get(4)?,
+                    created_at: row.get(5)?,
+                })
+            },
+        );
+        match result {
+            Ok(r) => Ok(Some(r)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn set_smart_search_favorite(&self, keyword: &str, favorite: bool) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE smart_search_history SET is_favorite = ?2 WHERE keyword = ?1",
+            rusqlite::params![keyword, favorite],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_smart_search(&self, keyword: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM smart_search_history WHERE keyword = ?1",
+            rusqlite::params![keyword],
+        )?;
+        Ok(())
+    }
+
+    // ====== Stock Symbol Methods ======
+
+    /// 写入本次扫描到的代码表，并移除 stale_before 之前就未再出现的条目（退市股）。
+    /// 全市场扫描会跳过停牌股，因此不整表替换，避免短期停牌的股票丢失
+    pub fn save_stock_symbols(&self, symbols: &[StockSymbol], stale_before: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        let tx = conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM stock_symbols WHERE updated_at < ?1", rusqlite::params![stale_before])?;
+        for s in symbols {
+            tx.execute(
+                "INSERT OR REPLACE INTO stock_symbols (code, name, updated_at) VALUES (?1, ?2, ?3)",
+                rusqlite::params![s.code, s.name, now],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn get_stock_symbols(&self) -> Result<Vec<StockSymbol>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT code, name FROM stock_symbols ORDER BY code")?;
+        let rows = stmt.query_map([], |row| {
+            Ok(StockSymbol {
+                code: row.get(0)?,
+                name: row.get(1)?,
+            })
+        })?;
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(row?);
+        }
+        Ok(results)
+    }
+
+    /// 代码表最近一次刷新时间，表为空时返回 None
+    pub fn get_stock_symbols_updated_at(&self) -> Result<Option<String>> {
+        let conn = self.conn.lock().unwrap();
+        let updated_at: Option<String> = conn.query_row("SELECT MAX(updated_at) FROM stock_symbols", [], |row| row.get(0))?;
+        Ok(updated_at)
+    }
+
+    // ====== Market Breadth Methods ======
+
+    pub fn save_market_breadth(&self, b: &MarketBreadth) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO market_breadth (date, total, advancers, decliners, flat, limit_up, limit_down, above_ma20_pct, above_ma60_pct, new_high_52w, new_low_52w, sample_size, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            rusqlite::params![
+                b.date, b.total, b.advancers, b.decliners, b.flat, b.limit_up, b.limit_down,
+                b.above_ma20_pct, b.above_ma60_pct, b.new_high_52w, b.new_low_52w, b.sample_size, b.created_at
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// 最近 days 个交易日的市场宽度，按日期升序
+    pub fn get_market_breadth_history(&self, days: usize) -> Result<Vec<MarketBreadth>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT date, total, advancers, decliners, flat, limit_up, limit_down, above_ma20_pct, above_ma60_pct, new_high_52w, new_low_52w, sample_size, created_at FROM market_breadth ORDER BY date DESC LIMIT ?1",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![days], |row| {
+            Ok(MarketBreadth {
+                date: row.get(0)?,
+                total: row.get(1)?,
+                advancers: row.get(2)?,
+                decliners: row.get(3)?,
+                flat: row.get(4)?,
+                limit_up: row.get(5)?,
+                limit_down: row.get(6)?,
+                above_ma20_pct: row.get(7)?,
+                above_ma60_pct: row.get(8)?,
+                new_high_52w: row.get(9)?,
+                new_low_52w: row.get(10)?,
+                sample_size: row.get(11)?,
+                created_at: row.get(12)?,
+            })
+        })?;
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(row?);
+        }
+        results.reverse();
+        Ok(results)
+    }
+
+    // ====== Stock RPS Methods ======
+
+    /// 写入一天的 RPS 记录，并只保留最近 keep_days 个记录日（用于推算 120 日涨幅）
+    pub fn save_stock_rps(&self, records: &[StockRps], keep_days: usize) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        for r in records {
+            tx.execute(
+                "INSERT OR REPLACE INTO stock_rps (date, code, name, close, pct_20d, pct_60d, pct_120d, rps_20, rps_60, rps_120) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                rusqlite::params![r.date, r.code, r.name, r.close, r.pct_20d, r.pct_60d, r.pct_120d, r.rps_20, r.rps_60, r.rps_120],
+            )?;
+        }
+        tx.execute(
+            "DELETE FROM stock_rps WHERE date NOT IN (SELECT DISTINCT date FROM stock_rps ORDER BY date DESC LIMIT ?1)",
+            rusqlite::params![keep_days],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 已记录 RPS 的日期，按日期倒序
+    pub fn get_stock_rps_dates(&self, limit: usize) -> Result<Vec<String>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT DISTINCT date FROM stock_rps ORDER BY date DESC LIMIT ?1")?;
+        let rows = stmt.query_map(rusqlite::params![limit], |row| row.get(0))?;
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(row?);
+        }
+        Ok(results)
+    }
+
+    /// 某个记录日的全部 RPS 记录
+    pub fn get_stock_rps_by_date(&self, date: &str) -> Result<Vec<StockRps>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT date, code, name, close, pct_20d, pct_60d, pct_120d, rps_20, rps_60, rps_120 FROM stock_rps WHERE date = ?1",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![date], |row| {
+            Ok(StockRps {
+                date: row.get(0)?,
+                code: row.get(1)?,
+                name: row.get(2)?,
+                close: row.get(3)?,
+                pct_20d: row.get(4)?,
+                pct_60d: row.get(5)?,
+                pct_120d: row.get(6)?,
+                rps_20: row.get(7)?,
+                rps_60: row.get(8)?,
+                rps_120: row.get(9)?,
+            })
+        })?;
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(row?);
+        }
+        Ok(results)
+    }
+
+    // ====== Board Rank History Methods ======
+
+    /// 写入一天的板块排名记录，并只保留最近 keep_days 个记录日
+    pub fn save_board_ranks(&self, records: &[BoardRankRecord], keep_days: usize) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        for r in records {
+            tx.execute(
+                "INSERT OR REPLACE INTO board_rank_history (date, code, name, kind, change_pct, rank, total, main_net_inflow) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                rusqlite::params![r.date, r.code, r.name, r.kind, r.change_pct, r.rank, r.total, r.main_net_inflow],
+            )?;
+        }
+        tx.execute(
+            "DELETE FROM board_rank_history WHERE date NOT IN (SELECT DISTINCT date FROM board_rank_history ORDER BY date DESC LIMIT ?1)",
+            rusqlite::params![keep_days],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 某类板块最近 days 个记录日的排名记录，按日期倒序
+    pub fn get_board_rank_history(&self, kind: &str, days: usize) -> Result<Vec<BoardRankRecord>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT date, code, name, kind, change_pct, rank, total, main_net_inflow FROM board_rank_history
+             WHERE kind = ?1 AND date IN (SELECT DISTINCT date FROM board_rank_history WHERE kind = ?1 ORDER BY date DESC LIMIT ?2)
+             ORDER BY date DESC, rank ASC",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![kind, days], |row| {
+            Ok(BoardRankRecord {
+                date: row.get(0)?,
+                code: row.get(1)?,
+                name: row.get(2)?,
+                kind: row.get(3)?,
+                change_pct: row.get(4)?,
+                rank: row.get(5)?,
+                total: row.get(6)?,
+                main_net_inflow: row.get(7)?,
+            })
+        })?;
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(row?);
+        }
+        Ok(results)
+    }
+
+    // ====== Job Run Methods ======
+
+    /// 写入一条后台任务运行记录，每个任务只保留最近 keep 条
+    pub fn insert_job_run(&self, run: &JobRun, keep: usize) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO job_runs (job_id, started_at, finished_at, success, message) VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![run.job_id, run.started_at, run.finished_at, run.success, run.message],
+        )?;
+        conn.execute(
+            "DELETE FROM job_runs WHERE job_id = ?1 AND id NOT IN (SELECT id FROM job_runs WHERE job_id = ?1 ORDER BY id DESC LIMIT ?2)",
+            rusqlite::params![run.job_id, keep],
+        )?;
+        Ok(())
+    }
+
+    /// 某任务最近 limit 条运行记录，最新在前
+    pub fn get_job_runs(&self, job_id: &str, limit: usize) -> Result<Vec<JobRun>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, job_id, started_at, finished_at, success, message FROM job_runs WHERE job_id = ?1 ORDER BY id DESC LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![job_id, limit], |row| {
+            Ok(JobRun {
+                id: row.get(0)?,
+                job_id: row.get(1)?,
+                started_at: row.get(2)?,
+                finished_at: row.get(3)?,
+                success: row.get(4)?,
+                message: row.get(5)?,
+            })
+        })?;
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(row?);
+        }
+        Ok(results)
+    }
+
+    /// 各任务最近一次成功运行的开始时间，用于重启后恢复每日任务的完成状态
+    pub fn get_last_job_successes(&self) -> Result<HashMap<String, String>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT job_id, MAX(started_at) FROM job_runs WHERE success = 1 GROUP BY job_id")?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+        let mut results = HashMap::new();
+        for row in rows {
+            let (job_id, started_at) = row?;
+            results.insert(job_id, started_at);
+        }
+        Ok(results)
+    }
+
+    // ====== Pick Checkpoint Methods ======
+
+    pub fn save_pick_checkpoint(&self, checkpoint: &PickCheckpoint) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let data = serde_json::to_string(checkpoint)?;
+        conn.execute(
+            "INSERT OR REPLACE INTO pick_checkpoints (session_id, data, updated_at) VALUES (?1, ?2, ?3)",
+            rusqlite::params![checkpoint.session.id, data, checkpoint.updated_at],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_pick_checkpoint(&self, session_id: &str) -> Result<Option<PickCheckpoint>> {
+        let conn = self.conn.lock().unwrap();
+        let result = conn.query_row(
+            "SELECT data FROM pick_checkpoints WHERE session_id = ?1",
+            rusqlite::params![session_id],
+            |row| row.get::<_, String>(0),
+        );
+        match result {
+            Ok(data) => Ok(Some(serde_json::from_str(&data)?)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// 全部选股断点，最近更新的在前
+    pub fn get_pick_checkpoints(&self) -> Result<Vec<PickCheckpoint>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT data FROM pick_checkpoints ORDER BY updated_at DESC")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        let mut results = Vec::new();
+        for row in rows {
+            match serde_json::from_str(&row?) {
+                Ok(checkpoint) => results.push(checkpoint),
+                Err(e) => log::warn!("[database] skip unreadable pick checkpoint: {}", e),
+            }
+        }
+        Ok(results)
+    }
+
+    pub fn delete_pick_checkpoint(&self, session_id: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM pick_checkpoints WHERE session_id = ?1", rusqlite::params![session_id])?;
+        Ok(())
+    }
+
+    // ====== Diagnostics Methods ======
+
+    /// 数据库文件路径与大小（字节）
+    pub fn file_info(&self) -> Result<(String, u64)> {
+        let conn = self.conn.lock().unwrap();
+        let path: String = conn.query_row("SELECT file FROM pragma_database_list WHERE name = 'main'", [], |row| row.get(0))?;
+        let page_count: u64 = conn.query_row("PRAGMA page_count", [], |row| row.get(0))?;
+        let page_size: u64 = conn.query_row("PRAGMA page_size", [], |row| row.get(0))?;
+        Ok((path, page_count * page_size))
+    }
+
+    /// 表的行数与日期列最大值。表名、列名来自代码内常量，不接受外部输入
+    pub fn get_table_stat(&self, table: &str, date_column: Option<&str>) -> Result<(i64, Option<String>)> {
+        let conn = self.conn.lock().unwrap();
+        let rows: i64 = conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0))?;
+        let latest = match date_column {
+            Some(column) => conn.query_row(&format!("SELECT MAX({}) FROM {}", column, table), [], |row| row.get(0))?,
+            None => None,
+        };
+        Ok((rows, latest))
+    }
+
+    // ====== Sync Methods ======
+
+    pub fn get_sync_state(&self, key: &str) -> Result<Option<String>> {
+        let conn = self.conn.lock().unwrap();
+        let result = conn.query_row(
+            "SELECT value FROM sync_state WHERE key = ?1",
+            rusqlite::params![key],
+            |row| row.get::<_, String>(0),
+        );
+        match result {
+            Ok(value) => Ok(Some(value)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn set_sync_state(&self, key: &str, value: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        conn.execute(
+            "INSERT OR REPLACE INTO sync_state (key, value, updated_at) VALUES (?1, ?2, ?3)",
+            rusqlite::params![key, value, now],
+        )?;
+        Ok(())
+    }
+
+    /// 指定时间之后的全部 AI 分析记录（同步用）
+    pub fn get_ai_analyses_since(&self, since: &str) -> Result<Vec<AIAnalysisResult>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, code, name, model_name, question, content, created_at FROM ai_analysis WHERE created_at >= ?1 ORDER BY created_at ASC",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![since], |row| {
+            Ok(AIAnalysisResult {
+                id: row.get(0)?,
+                code: row.get(1)?,
+                name: row.get(2)?,
+                model_name: row.get(3)?,
+                question: row.get(4)?,
+                content: row.get(5)?,
+                created_at: row.get(6)?,
+            })
+        })?;
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(row?);
+        }
+        Ok(results)
+    }
+
+    /// 用同步合并结果整体替换自选股
+    pub fn replace_watchlist(&self, stocks: &[WatchlistStock]) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM watchlist_stocks", [])?;
+        for stock in stocks {
+            tx.execute(
+                "INSERT INTO watchlist_stocks (code, name, sort_order, group_name, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+                rusqlite::params![stock.code, stock.name, stock.sort_order, stock.group_name, stock.created_at],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 保存盘中快照（同一行情时间重复采集时忽略），只保留最近 keep_days 个交易日，返回新增条数
+    pub fn save_intraday_snapshots(&self, snapshots: &[IntradaySnapshot], keep_days: usize) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        let mut inserted = 0;
+        for s in snapshots {
+            inserted += tx.execute(
+                "INSERT OR IGNORE INTO intraday_snapshots (date, code, time, name, price, pre_close, volume, amount, bid1, ask1, bid1_vol, ask1_vol)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                rusqlite::params![s.date, s.code, s.time, s.name, s.price, s.pre_close, s.volume, s.amount, s.bid1, s.ask1, s.bid1_vol, s.ask1_vol],
+            )?;
+        }
+        tx.execute(
+            "DELETE FROM intraday_snapshots WHERE date NOT IN (SELECT DISTINCT date FROM intraday_snapshots ORDER BY date DESC LIMIT ?1)",
+            rusqlite::params![keep_days],
+        )?;
+        tx.commit()?;
+        Ok(inserted)
+    }
+
+    /// 某日的盘中快照，按时间排序；codes 为空时返回全部股票
+    pub fn get_intraday_snapshots(&self, date: &str, codes: &[String]) -> Result<Vec<IntradaySnapshot>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT code, name, date, time, price, pre_close, volume, amount, bid1, ask1, bid1_vol, ask1_vol
+             FROM intraday_snapshots WHERE date = ?1 ORDER BY time ASC, code ASC",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![date], |row| {
+            let price: f64 = row.get(4)?;
+            let pre_close: f64 = row.get(5)?;
+            Ok(IntradaySnapshot {
+                code: row.get(0)?,
+                name: row.get(1)?,
+                date: row.get(2)?,
+                time: row.get(3)?,
+                price,
+                pre_close,
+                change_pct: if pre_close > 0.0 { (price - pre_close) / pre_close * 100.0 } else { 0.0 },
+                volume: row.get(6)?,
+                amount: row.get(7)?,
+                bid1: row.get(8)?,
+                ask1: row.get(9)?,
+                bid1_vol: row.get(10)?,
+                ask1_vol: row.get(11)?,
+            })
+        })?;
+        let mut results = Vec::new();
+        for row in rows {
+            let snapshot = row?;
+            if codes.is_empty() || codes.contains(&snapshot.code) {
+                results.push(snapshot);
+            }
+        }
+        Ok(results)
+    }
+
+    /// 有盘中快照的交易日，按日期倒序
+    pub fn get_intraday_days(&self) -> Result<Vec<ReplayDay>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT date, COUNT(DISTINCT code), COUNT(*) FROM intraday_snapshots GROUP BY date ORDER BY date DESC",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(ReplayDay {
+                date: row.get(0)?,
+                stock_count: row.get(1)?,
+                snapshot_count: row.get(2)?,
+            })
+        })?;
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(row?);
+        }
+        Ok(results)
+    }
+
+    pub fn save_audio_export(&self, export: &AudioExport) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO audio_exports (source_kind, source_id, path, created_at) VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![export.source_kind, export.source_id, export.path, export.created_at],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_audio_export(&self, source_kind: &str, source_id: &str) -> Result<Option<AudioExport>> {
+        let conn = self.conn.lock().unwrap();
+        let result = conn.query_row(
+            "SELECT source_kind, source_id, path, created_at FROM audio_exports WHERE source_kind = ?1 AND source_id = ?2",
+            rusqlite::params![source_kind, source_id],
+            |row| {
+                Ok(AudioExport {
+                    source_kind: row.get(0)?,
+                    source_id: row.get(1)?,
+                    path: row.get(2)?,
+                    created_at: row.get(3)?,
+                })
+            },
+        );
+        match result {
+            Ok(r) => Ok(Some(r)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save_stock_schedule(&self, schedule: &StockSchedule) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO stock_schedules (id, code, name, time, question, enabled, last_run_date, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            rusqlite::params![schedule.id, schedule.code, schedule.name, schedule.time, schedule.question, schedule.enabled, schedule.last_run_date, schedule.created_at],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_stock_schedules(&self) -> Result<Vec<StockSchedule>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, code, name, time, question, enabled, last_run_date, created_at FROM stock_schedules ORDER BY time ASC, created_at ASC",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(StockSchedule {
+                id: row.get(0)?,
+                code: row.get(1)?,
+                name: row.get(2)?,
+                time: row.get(3)?,
+                question: row.get(4)?,
+                enabled: row.get(5)?,
+                last_run_date: row.get(6)?,
+                created_at: row.get(7)?,
+            })
+        })?;
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(row?);
+        }
+        Ok(results)
+    }
+
+    pub fn delete_stock_schedule(&self, id: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM stock_schedules WHERE id = ?1", rusqlite::params![id])?;
+        Ok(())
+    }
+
+    pub fn set_schedule_last_run(&self, id: &str, date: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE stock_schedules SET last_run_date = ?2 WHERE id = ?1",
+            rusqlite::params![id, date],
+        )?;
+        Ok(())
+    }
+
+    // ====== Board Member Methods ======
+
+    /// 用最新成分股替换某个板块的缓存：保留已有成分股的首次出现日期，删除已调出的，返回新纳入的代码。
+    /// 板块此前没有缓存时视为首次建立，新成分股不标记为新纳入
+    pub fn save_board_members(&self, board_code: &str, board_name: &str, members: &[(String, String)], today: &str) -> Result<Vec<String>> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        let mut existing = std::collections::HashSet::new();
+        {
+            let mut stmt = tx.prepare("SELECT code FROM board_members WHERE board_code = ?1")?;
+            let rows = stmt.query_map(rusqlite::params![board_code], |row| row.get::<_, String>(0))?;
+            for row in rows {
+                existing.insert(row?);
+            }
+        }
+        let initial = existing.is_empty();
+        let mut added = Vec::new();
+        for (code, name) in members {
+            if existing.contains(code) {
+                tx.execute(
+                    "UPDATE board_members SET board_name = ?3, name = ?4 WHERE board_code = ?1 AND code = ?2",
+                    rusqlite::params![board_code, code, board_name, name],
+                )?;
+            } else {
+                tx.execute(
+                    "INSERT INTO board_members (board_code, board_name, code, name, first_seen, is_new) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    rusqlite::params![board_code, board_name, code, name, today, !initial],
+                )?;
+                if !initial {
+                    added.push(code.clone());
+                }
+            }
+        }
+        let current: std::collections::HashSet<&String> = members.iter().map(|(c, _)| c).collect();
+        for code in existing.iter().filter(|c| !current.contains(c)) {
+            tx.execute(
+                "DELETE FROM board_members WHERE board_code = ?1 AND code = ?2",
+                rusqlite::params![board_code, code],
+            )?;
+        }
+        tx.commit()?;
+        Ok(added)
+    }
+
+    /// 全部已缓存的板块成分股
+    pub fn get_all_board_members(&self) -> Result<Vec<BoardMember>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT board_code, board_name, code, name, first_seen, is_new FROM board_members",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(BoardMember {
+                board_code: row.get(0)?,
+                board_name: row.get(1)?,
+                code: row.get(2)?,
+                name: row.get(3)?,
+                first_seen: row.get(4)?,
+                is_new: row.get(5)?,
+            })
+        })?;
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(row?);
+        }
+        Ok(results)
+    }
+
+    /// 归档快讯（按 id 去重），返回新增条数
+    pub fn save_news_archive(&self, items: &[NewsItem]) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        let mut inserted = 0;
+        for item in items.iter().filter(|n| !n.publish_time.is_empty()) {
+            inserted += tx.execute(
+                "INSERT OR IGNORE INTO news_archive (id, publish_time, title, summary) VALUES (?1, ?2, ?3, ?4)",
+                rusqlite::params![item.id, item.publish_time, item.title, item.summary],
+            )?;
+        }
+        tx.commit()?;
+        Ok(inserted)
+    }
+
+    /// 发布时间不早于 since 的归档快讯，按时间正序
+    pub fn get_news_archive(&self, since: &str) -> Result<Vec<NewsItem>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, publish_time, title, summary FROM news_archive WHERE publish_time >= ?1 ORDER BY publish_time ASC",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![since], |row| {
+            Ok(NewsItem {
+                id: row.get(0)?,
+                category: NewsCategory::ClsTelegraph,
+                title: row.get(2)?,
+                summary: row.get(3)?,
+                source: "财联社".to_string(),
+                publish_time: row.get(1)?,
+                url: String::new(),
+                importance: 0,
+                related_stocks: vec![],
+            })
+        })?;
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(row?);
+        }
+        Ok(results)
+    }
+
+    /// 删除 before 之前的归档快讯
+    pub fn prune_news_archive(&self, before: &str) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        let deleted = conn.execute("DELETE FROM news_archive WHERE publish_time < ?1", rusqlite::params![before])?;
+        Ok(deleted)
+    }
+
+    /// 归档分类公告事件（按公告 id 去重），返回新增条数
+    pub fn save_announcement_events(&self, events: &[AnnouncementEvent]) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        let mut inserted = 0;
+        for e in events {
+            inserted += tx.execute(
+                "INSERT OR IGNORE INTO announcement_events (id, code, name, notice_date, category, title) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                rusqlite::params![e.id, e.code, e.name, e.notice_date, e.category, e.title],
+            )?;
+        }
+        tx.commit()?;
+        Ok(inserted)
+    }
+
+    /// 公告日不早于 since 的分类公告事件，按日期正序
+    pub fn get_announcement_events(&self, since: &str) -> Result<Vec<AnnouncementEvent>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, code, name, notice_date, category, title FROM announcement_events WHERE notice_date >= ?1 ORDER BY notice_date ASC",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![since], |row| {
+            Ok(AnnouncementEvent {
+                id: row.get(0)?,
+                code: row.get(1)?,
+                name: row.get(2)?,
+                notice_date: row.get(3)?,
+                category: row.get(4)?,
+                title: row.get(5)?,
+            })
+        })?;
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(row?);
+        }
+        Ok(results)
+    }
+
+    /// 保存日级资金流向。detailed 为 true 时覆盖已有记录（东财明细），否则只补缺失日期（全市场快照），
+    /// 并删除 keep_days 个记录日之前的数据
+    pub fn save_fund_flows(&self, flows: &[FundFlowDay], detailed: bool, keep_days: usize) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        let sql = if detailed {
+            "INSERT OR REPLACE INTO fund_flow_history (code, date, main_net, super_large_net, large_net, medium_net, small_net, main_pct, close, change_pct) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)"
+        } else {
+            "INSERT OR IGNORE INTO fund_flow_history (code, date, main_net, super_large_net, large_net, medium_net, small_net, main_pct, close, change_pct) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)"
+        };
+        for f in flows {
+            tx.execute(
+                sql,
+                rusqlite::params![f.code, f.date, f.main_net, f.super_large_net, f.large_net, f.medium_net, f.small_net, f.main_pct, f.close, f.change_pct],
+            )?;
+        }
+        tx.execute(
+            "DELETE FROM fund_flow_history WHERE date < (SELECT MIN(date) FROM (SELECT DISTINCT date FROM fund_flow_history ORDER BY date DESC LIMIT ?1))",
+            rusqlite::params![keep_days],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 最近 days 个记录日的资金流向（按代码分组，日期正序）；code 为空时返回全部股票
+    pub fn get_fund_flows(&self, code: Option<&str>, days: usize) -> Result<HashMap<String, Vec<FundFlowDay>>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT code, date, main_net, super_large_net, large_net, medium_net, small_net, main_pct, close, change_pct FROM fund_flow_history
+             WHERE (?1 IS NULL OR code = ?1)
+               AND date >= (SELECT MIN(date) FROM (SELECT DISTINCT date FROM fund_flow_history WHERE (?1 IS NULL OR code = ?1) ORDER BY date DESC LIMIT ?2))
+             ORDER BY code, date ASC",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![code, days], |row| {
+            Ok(FundFlowDay {
+                code: row.get(0)?,
+                date: row.get(1)?,
+                main_net: row.get(2)?,
+                super_large_net: row.get(3)?,
+                large_net: row.get(4)?,
+                medium_net: row.get(5)?,
+                small_net: row.get(6)?,
+                main_pct: row.get(7)?,
+                close: row.get(8)?,
+                change_pct: row.get(9)?,
+            })
+        })?;
+        let mut results: HashMap<String, Vec<FundFlowDay>> = HashMap::new();
+        for row in rows {
+            let flow = row?;
+            results.entry(flow.code.clone()).or_default().push(flow);
+        }
+        Ok(results)
+    }
+
+    // ====== Closing Auction Methods ======
+
+    /// 写入尾盘竞价记录，并只保留最近 keep_days 个记录日
+    pub fn save_closing_auctions(&self, records: &[ClosingAuction], keep_days: usize) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        for r in records {
+            tx.execute(
+                "INSERT OR REPLACE INTO closing_auctions (code, date, pre_price, close, deviation_pct, auction_volume, auction_amount, volume_share, after_hours_volume, after_hours_amount, flag) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                rusqlite::params![r.code, r.date, r.pre_price, r.close, r.deviation_pct, r.auction_volume, r.auction_amount, r.volume_share, r.after_hours_volume, r.after_hours_amount, r.flag],
+            )?;
+        }
+        tx.execute(
+            "DELETE FROM closing_auctions WHERE date < (SELECT MIN(date) FROM (SELECT DISTINCT date FROM closing_auctions ORDER BY date DESC LIMIT ?1))",
+            rusqlite::params![keep_days],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 单只股票最近 limit 条尾盘竞价记录（最新在前）
+    pub fn get_closing_auctions(&self, code: &str, limit: usize) -> Result<Vec<ClosingAuction>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT code, date, pre_price, close, deviation_pct, auction_volume, auction_amount, volume_share, after_hours_volume, after_hours_amount, flag FROM closing_auctions WHERE code = ?1 ORDER BY date DESC LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![code, limit], Self::row_to_closing_auction)?;
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(row?);
+        }
+        Ok(results)
+    }
+
+    /// 每只股票最新的一条尾盘竞价记录
+    pub fn get_latest_closing_auctions(&self) -> Result<Vec<ClosingAuction>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT code, date, pre_price, close, deviation_pct, auction_volume, auction_amount, volume_share, after_hours_volume, after_hours_amount, flag FROM closing_auctions c
+             WHERE date = (SELECT MAX(date) FROM closing_auctions WHERE code = c.code)",
+        )?;
+        let rows = stmt.query_map([], Self::row_to_closing_auction)?;
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(row?);
+        }
+        Ok(results)
+    }
+
+    fn row_to_closing_auction(row: &rusqlite::Row) -> rusqlite::Result<ClosingAuction> {
+        Ok(ClosingAuction {
+            code: row.get(0)?,
+            date: row.get(1)?,
+            pre_price: row.get(2)?,
+            close: row.get(3)?,
+            deviation_pct: row.get(4)?,
+            auction_volume: row.get(5)?,
+            auction_amount: row.get(6)?,
+            volume_share: row.get(7)?,
+            after_hours_volume: row.get(8)?,
+            after_hours_amount: row.get(9)?,
+            flag: row.get(10)?,
+        })
+    }
+
+    // ====== Factor Score Methods ======
+
+    /// 写入一天的因子评分，并只保留最近 keep_days 个记录日
+    pub fn save_factor_scores(&self, records: &[FactorScore], keep_days: usize) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        for r in records {
+            tx.execute(
+                "INSERT OR REPLACE INTO factor_scores (date, code, name, score, quality, growth, value, momentum, flow) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                rusqlite::params![r.date, r.code, r.name, r.score, r.quality, r.growth, r.value, r.momentum, r.flow],
+            )?;
+        }
+        tx.execute(
+            "DELETE FROM factor_scores WHERE date NOT IN (SELECT DISTINCT date FROM factor_scores ORDER BY date DESC LIMIT ?1)",
+            rusqlite::params![keep_days],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 个股最近 limit 个记录日的因子评分，按日期正序
+    pub fn get_factor_score_history(&self, code: &str, limit: usize) -> Result<Vec<FactorScore>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT date, code, name, score, quality, growth, value, momentum, flow FROM factor_scores WHERE code = ?1 ORDER BY date DESC LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![code, limit], |row| {
+            Ok(FactorScore {
+                date: row.get(0)?,
+                code: row.get(1)?,
+                name: row.get(2)?,
+                score: row.get(3)?,
+                quality: row.get(4)?,
+                growth: row.get(5)?,
+                value: row.get(6)?,
+                momentum: row.get(7)?,
+                flow: row.get(8)?,
+            })
+        })?;
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(row?);
+        }
+        results.reverse();
+        Ok(results)
+    }
 }
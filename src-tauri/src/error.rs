@@ -0,0 +1,223 @@
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use std::fmt;
+
+/// 命令层统一错误：序列化为 { code, message }，前端按 code 区分网络、鉴权、限流等情况，message 为面向用户的中文提示
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AppError {
+    /// 网络不可用或无法连接服务器
+    Network(String),
+    /// 请求超时
+    Timeout(String),
+    /// API Key 无效或无权限（401/403）
+    Unauthorized(String),
+    /// 请求过于频繁被限流（429）
+    RateLimited(String),
+    /// 数据源或 AI 服务返回错误
+    Upstream(String),
+    /// 缺少必要配置，例如未配置 AI 模型
+    NotConfigured(String),
+    NotFound(String),
+    InvalidInput(String),
+    /// 已有同类任务在运行
+    Busy(String),
+    Cancelled(String),
+    Database(String),
+    Internal(String),
+}
+
+impl AppError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::Network(_) => "network",
+            AppError::Timeout(_) => "timeout",
+            AppError::Unauthorized(_) => "unauthorized",
+            AppError::RateLimited(_) => "rate_limited",
+            AppError::Upstream(_) => "upstream",
+            AppError::NotConfigured(_) => "not_configured",
+            AppError::NotFound(_) => "not_found",
+            AppError::InvalidInput(_) => "invalid_input",
+            AppError::Busy(_) => "busy",
+            AppError::Cancelled(_) => "cancelled",
+            AppError::Database(_) => "database",
+            AppError::Internal(_) => "internal",
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            AppError::Network(m)
+            | AppError::Timeout(m)
+            | AppError::Unauthorized(m)
+            | AppError::RateLimited(m)
+            | AppError::Upstream(m)
+            | AppError::NotConfigured(m)
+            | AppError::NotFound(m)
+            | AppError::InvalidInput(m)
+            | AppError::Busy(m)
+            | AppError::Cancelled(m)
+            | AppError::Database(m)
+            | AppError::Internal(m) => m,
+        }
+    }
+
+    fn with_message(&self, message: String) -> Self {
+        match self {
+            AppError::Network(_) => AppError::Network(message),
+            AppError::Timeout(_) => AppError::Timeout(message),
+            AppError::Unauthorized(_) => AppError::Unauthorized(message),
+            AppError::RateLimited(_) => AppError::RateLimited(message),
+            AppError::Upstream(_) => AppError::Upstream(message),
+            AppError::NotConfigured(_) => AppError::NotConfigured(message),
+            AppError::NotFound(_) => AppError::NotFound(message),
+            AppError::InvalidInput(_) => AppError::InvalidInput(message),
+            AppError::Busy(_) => AppError::Busy(message),
+            AppError::Cancelled(_) => AppError::Cancelled(message),
+            AppError::Database(_) => AppError::Database(message),
+            AppError::Internal(_) => AppError::Internal(message),
+        }
+    }
+
+    /// 在提示前加上操作说明，错误类别不变，例如 "获取研报失败: 请求超时…"
+    pub fn context(self, action: &str) -> Self {
+        let message = format!("{}: {}", action, self.message());
+        self.with_message(message)
+    }
+
+    /// 按错误文本归类，用于 anyhow 链中没有可识别的底层错误时
+    pub fn classify(message: &str) -> Self {
+        let lower = message.to_lowercase();
+        let has = |keys: &[&str]| keys.iter().any(|k| lower.contains(k));
+        let message = message.to_string();
+        if has(&["用户取消", "cancelled", "canceled"]) {
+            AppError::Cancelled(message)
+        } else if has(&["(401", "(403", "401 unauthorized", "api key 无效", "invalid api key", "incorrect api key"]) {
+            AppError::Unauthorized(message)
+        } else if has(&["(429", "429 too many", "rate limit", "too many requests", "限流", "请求过于频繁"]) {
+            AppError::RateLimited(message)
+        } else if has(&["超时", "timed out", "timeout"]) {
+            AppError::Timeout(message)
+        } else if has(&["无法连接", "error sending request", "connection refused", "dns error", "network is unreachable"]) {
+            AppError::Network(message)
+        } else if has(&["未配置"]) {
+            AppError::NotConfigured(message)
+        } else if has(&["api error", "api 返回错误", "api 地址不存在", "响应解析失败", "response parse error"]) {
+            AppError::Upstream(message)
+        } else {
+            AppError::Internal(message)
+        }
+    }
+
+    fn from_reqwest(e: &reqwest::Error, message: String) -> Self {
+        if e.is_timeout() {
+            return AppError::Timeout(format!("请求超时，请检查网络后重试（{}）", message));
+        }
+        if e.is_connect() {
+            return AppError::Network(format!("网络连接失败，请检查网络或代理设置（{}）", message));
+        }
+        match e.status().map(|s| s.as_u16()) {
+            Some(401) | Some(403) => AppError::Unauthorized(format!("API Key 无效或无权限（{}）", message)),
+            Some(429) => AppError::RateLimited(format!("请求过于频繁，请稍后再试（{}）", message)),
+            Some(_) => AppError::Upstream(message),
+            None if e.is_request() => AppError::Network(format!("网络请求失败（{}）", message)),
+            None => AppError::classify(&message),
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("AppError", 2)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", self.message())?;
+        state.end()
+    }
+}
+
+/// 服务层错误：先在错误链中查找网络/数据库等底层错误，找不到再按文本归类
+impl From<anyhow::Error> for AppError {
+    fn from(e: anyhow::Error) -> Self {
+        let message = e.to_string();
+        for cause in e.chain() {
+            if let Some(app) = cause.downcast_ref::<AppError>() {
+                return app.clone();
+            }
+            if let Some(re) = cause.downcast_ref::<reqwest::Error>() {
+                return AppError::from_reqwest(re, message);
+            }
+            if let Some(db) = cause.downcast_ref::<rusqlite::Error>() {
+                return match db {
+                    rusqlite::Error::QueryReturnedNoRows => AppError::NotFound(message),
+                    _ => AppError::Database(format!("数据库操作失败: {}", message)),
+                };
+            }
+        }
+        AppError::classify(&message)
+    }
+}
+
+impl From<reqwest::Error> for AppError {
+    fn from(e: reqwest::Error) -> Self {
+        let message = e.to_string();
+        AppError::from_reqwest(&e, message)
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(e: std::io::Error) -> Self {
+        AppError::Internal(e.to_string())
+    }
+}
+
+impl From<zip::result::ZipError> for AppError {
+    fn from(e: zip::result::ZipError) -> Self {
+        AppError::Internal(e.to_string())
+    }
+}
+
+impl From<tauri::Error> for AppError {
+    fn from(e: tauri::Error) -> Self {
+        AppError::Internal(e.to_string())
+    }
+}
+
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        AppError::classify(&message)
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        AppError::classify(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_and_serialize() {
+        assert_eq!(AppError::classify("API Key 无效（401 Unauthorized）").code(), "unauthorized");
+        assert_eq!(AppError::classify("AI API error (429): rate limit exceeded").code(), "rate_limited");
+        assert_eq!(AppError::classify("连接超时，请检查 API 地址是否正确").code(), "timeout");
+        assert_eq!(AppError::classify("无法连接到服务器，请检查 API 地址").code(), "network");
+        assert_eq!(AppError::classify("用户取消了 AI 选股").code(), "cancelled");
+        assert_eq!(AppError::classify("AI API error (500): bad gateway").code(), "upstream");
+        assert_eq!(AppError::classify("something else").code(), "internal");
+
+        let err = AppError::from(anyhow::anyhow!("未配置可用的 AI 模型")).context("生成解说失败");
+        assert_eq!(err, AppError::NotConfigured("生成解说失败: 未配置可用的 AI 模型".to_string()));
+        let json = serde_json::to_value(&err).unwrap();
+        assert_eq!(json["code"], "not_configured");
+        assert_eq!(json["message"], "生成解说失败: 未配置可用的 AI 模型");
+    }
+}
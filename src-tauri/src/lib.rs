@@ -3,10 +3,14 @@ pub mod services;
 pub mod commands;
 pub mod db;
 pub mod utils;
+pub mod error;
 
 use db::database::Database;
+use models::window::DetachedWindow;
+use services::shutdown::ShutdownController;
+use std::collections::HashMap;
 use std::sync::atomic::AtomicBool;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use tauri::Manager;
 use tauri_plugin_log::{Target, TargetKind, RotationStrategy, TimezoneStrategy};
 
@@ -15,26 +19,34 @@ pub struct AppState {
     pub ai_picking: AtomicBool,
     /// AI 选股取消信号：true 表示请求取消
     pub ai_pick_cancel: Arc<AtomicBool>,
+    /// 应用退出控制：后台任务监听退出信号，进行中的任务登记后退出前等待其完成
+    pub shutdown: ShutdownController,
+    /// 已打开的独立窗口（迷你行情条、独立 K 线窗口），按窗口标签索引
+    pub windows: Mutex<HashMap<String, DetachedWindow>>,
 }
 
+/// 轮转后保留的日志文件数（单个文件 5MB）
+const LOG_FILES_KEPT: usize = 10;
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    tauri::Builder::default()
-        .setup(|app| {
-            let log_level = if cfg!(debug_assertions) {
-                log::LevelFilter::Info
-            } else {
-                log::LevelFilter::Warn
-            };
+    // 通过 stockhelper:// 链接再次启动时，交给已运行的实例处理后直接退出
+    let launch_url = services::deep_link::url_from_args(std::env::args());
+    if launch_url.as_deref().is_some_and(services::deep_link::forward_to_running) {
+        return;
+    }
 
+    tauri::Builder::default()
+        .setup(move |app| {
+            // 发布版同样写入日志文件，便于用户导出反馈问题
             app.handle().plugin(
                 tauri_plugin_log::Builder::default()
-                    .level(log_level)
+                    .level(log::LevelFilter::Info)
                     .targets([
                         Target::new(TargetKind::Stdout),
                         Target::new(TargetKind::LogDir { file_name: None }),
                     ])
-                    .rotation_strategy(RotationStrategy::KeepAll)
+                    .rotation_strategy(RotationStrategy::KeepSome(LOG_FILES_KEPT))
                     .max_file_size(5_000_000) // 5MB per file
                     .timezone_strategy(TimezoneStrategy::UseLocal)
                     .build(),
@@ -44,25 +56,146 @@ pub fn run() {
                 .expect("Failed to get app data directory");
             let database = Database::new(app_data_dir)
                 .expect("Failed to initialize database");
+            if let Err(e) = services::pick_checkpoint::recover_interrupted(&database) {
+                log::warn!("[lib] recover interrupted picks failed: {}", e);
+            }
+            let startup_settings = database.load_settings().unwrap_or_else(|e| {
+                log::warn!("[lib] load settings for logging failed: {}", e);
+                Default::default()
+            });
+            utils::http::set_request_logging(startup_settings.debug_logging);
 
             app.manage(AppState {
                 db: database,
                 ai_picking: AtomicBool::new(false),
                 ai_pick_cancel: Arc::new(AtomicBool::new(false)),
+                shutdown: ShutdownController::new(),
+                windows: Mutex::new(HashMap::new()),
+            });
+
+            services::job_scheduler::start(
+                app.handle().clone(),
+                vec![
+                    services::smart_stock::fingerprint_bootstrap_job(),
+                    services::rps::rps_preload_job(),
+                    services::symbol_table::symbol_refresh_job(),
+                    services::briefing::morning_briefing_job(),
+                    services::anomaly_radar::anomaly_radar_job(),
+                    services::snapshot_archiver::eod_archive_job(),
+                    services::signal_alert::signal_scan_job(),
+                    services::technical_store::technical_refresh_job(),
+                    services::market_breadth::breadth_record_job(),
+                    services::rps::rps_record_job(),
+                    services::board_rotation::rotation_record_job(),
+                    services::sync::sync_job(),
+                    services::tray::tray_ticker_job(),
+                    services::intraday_replay::intraday_capture_job(),
+                    services::risk_control::risk_check_job(),
+                    services::stock_schedule::stock_schedule_job(),
+                    services::factor_score::factor_score_record_job(),
+                    services::board_members::board_members_preload_job(),
+                    services::board_members::board_members_refresh_job(),
+                    services::theme_study::news_archive_job(),
+                    services::announcement_study::announcement_archive_job(),
+                    services::auction_scanner::auction_scan_job(),
+                    services::closing_auction::closing_auction_record_job(),
+                ],
+            );
+
+            if let Err(e) = services::hotkey::apply(app.handle(), &startup_settings.quick_search_hotkey) {
+                log::warn!("[lib] register quick search hotkey failed: {}", e);
+            }
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                services::api_server::apply(&handle, &startup_settings.api_server).await;
+            });
+
+            if let Err(e) = services::tray::setup(app.handle()) {
+                log::warn!("[lib] create tray icon failed: {}", e);
+            }
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = services::tray::refresh(&handle).await {
+                    log::warn!("[lib] refresh tray quotes failed: {}", e);
+                }
+            });
+
+            if let Some(url) = &launch_url {
+                services::deep_link::handle(app.handle(), url);
+            }
+            tauri::async_runtime::spawn(services::deep_link::listen(app.handle().clone()));
+            tauri::async_runtime::spawn_blocking(|| {
+                if let Err(e) = services::deep_link::register_scheme() {
+                    log::warn!("[lib] register url scheme failed: {}", e);
+                }
             });
 
             Ok(())
         })
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        .on_window_event(|window, event| {
+            let tauri::WindowEvent::CloseRequested { api, .. } = event else { return };
+            if window.label() != "main" {
+                return;
+            }
+            // 开启托盘模式时关闭主窗口只隐藏，后台任务与托盘行情继续运行；
+            // 否则一并关闭独立窗口，避免应用只剩迷你行情条而无法退出
+            let app = window.app_handle();
+            let close_to_tray = app.state::<AppState>().db.load_settings().map(|s| s.close_to_tray).unwrap_or(true);
+            if close_to_tray && app.tray_by_id(services::tray::TRAY_ID).is_some() {
+                api.prevent_close();
+                let _ = window.hide();
+            } else {
+                services::window_manager::close_all(app);
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             commands::stock_cmd::get_realtime_data,
+            commands::stock_cmd::test_custom_quote_provider,
             commands::stock_cmd::get_kline_data,
             commands::stock_cmd::search_stocks,
             commands::stock_cmd::get_watchlist_enriched,
+            commands::stock_cmd::get_stock_profile,
+            commands::stock_cmd::get_earnings_forecast,
+            commands::stock_cmd::get_valuation_band,
+            commands::stock_cmd::get_peer_comparison,
+            commands::stock_cmd::get_seasonality,
+            commands::stock_cmd::sync_history_klines,
+            commands::stock_cmd::smart_search_stock,
+            commands::stock_cmd::rerun_smart_search,
+            commands::stock_cmd::get_smart_search_history,
+            commands::stock_cmd::set_smart_search_favorite,
+            commands::stock_cmd::delete_smart_search,
+            commands::stock_cmd::refresh_symbol_table,
+            commands::stock_cmd::get_index_constituents,
+            commands::stock_cmd::get_index_kline,
+            commands::stock_cmd::validate_filter_expression,
+            commands::stock_cmd::screen_by_expression,
+            commands::stock_cmd::get_score_history,
+            commands::stock_cmd::get_stock_theme_exposure,
+            commands::stock_cmd::get_board_member_additions,
+            commands::stock_cmd::run_theme_event_study,
+            commands::stock_cmd::run_announcement_study,
+            commands::stock_cmd::get_fund_flow_history,
+            commands::stock_cmd::get_fund_flow_breakdown,
+            commands::stock_cmd::start_focus_monitor,
+            commands::stock_cmd::stop_focus_monitor,
+            commands::stock_cmd::get_focus_monitor_status,
+            commands::stock_cmd::scan_auction_gaps,
+            commands::stock_cmd::get_auction_gaps,
             commands::ai_cmd::analyze_stock,
             commands::ai_cmd::get_analysis_history,
             commands::ai_cmd::get_today_token_usage,
+            commands::ai_cmd::get_agent_session,
+            commands::ai_cmd::get_agent_sessions,
+            commands::ai_cmd::replay_agent_session,
+            commands::ai_cmd::generate_instructions,
+            commands::ai_cmd::ai_router,
+            commands::ai_cmd::ai_analyze_image,
+            commands::ai_cmd::export_analysis_audio,
+            commands::ai_cmd::classify_strategy_zones,
             commands::settings_cmd::get_settings,
             commands::settings_cmd::save_settings,
             commands::settings_cmd::add_ai_config,
@@ -77,8 +210,25 @@ pub fn run() {
             commands::watchlist_cmd::remove_watchlist_stock,
             commands::watchlist_cmd::get_watchlist_stocks,
             commands::watchlist_cmd::reorder_watchlist,
+            commands::watchlist_cmd::import_watchlist,
+            commands::watchlist_cmd::export_watchlist,
+            commands::watchlist_cmd::archive_watchlist_snapshot,
+            commands::watchlist_cmd::get_watchlist_snapshots,
+            commands::watchlist_cmd::get_stock_snapshot_history,
+            commands::watchlist_cmd::get_replay_days,
+            commands::watchlist_cmd::get_intraday_replay,
+            commands::watchlist_cmd::scan_watchlist_signals,
+            commands::watchlist_cmd::get_signal_history,
+            commands::watchlist_cmd::scan_anomalies,
+            commands::watchlist_cmd::get_recent_anomalies,
             commands::watchlist_cmd::get_stock_technical_analysis,
             commands::watchlist_cmd::ai_diagnose_stock,
+            commands::watchlist_cmd::diagnose_watchlist,
+            commands::watchlist_cmd::get_stock_schedules,
+            commands::watchlist_cmd::save_stock_schedule,
+            commands::watchlist_cmd::delete_stock_schedule,
+            commands::watchlist_cmd::get_stock_schedule_runs,
+            commands::watchlist_cmd::run_stock_schedule,
             commands::news_cmd::fetch_cls_telegraph,
             commands::news_cmd::fetch_eastmoney_news,
             commands::news_cmd::fetch_stock_news,
@@ -89,18 +239,84 @@ pub fn run() {
             commands::news_cmd::fetch_wallstreetcn_lives,
             commands::ai_pick_cmd::ai_pick_stocks,
             commands::ai_pick_cmd::get_cached_picks,
+            commands::ai_pick_cmd::list_pick_sessions,
+            commands::ai_pick_cmd::get_pick_session,
             commands::ai_pick_cmd::find_similar_stocks,
             commands::ai_pick_cmd::stop_ai_pick,
+            commands::ai_pick_cmd::resume_ai_pick,
+            commands::ai_pick_cmd::get_interrupted_pick,
+            commands::ai_pick_cmd::discard_interrupted_pick,
+            commands::ai_pick_cmd::add_picks_to_watchlist,
             commands::tracking_cmd::add_tracking_stock,
             commands::tracking_cmd::remove_tracking_stock,
             commands::tracking_cmd::get_tracking_stocks,
             commands::tracking_cmd::clear_tracking_by_date,
+            commands::tracking_cmd::track_pick_session,
             commands::tracking_cmd::analyze_loss_reasons,
+            commands::tracking_cmd::get_instruction_history,
+            commands::tracking_cmd::get_instruction_stats,
+            commands::tracking_cmd::get_strategy_performance,
+            commands::tracking_cmd::get_risk_report,
             commands::settings_cmd::export_logs,
+            commands::settings_cmd::open_log_dir,
+            commands::settings_cmd::export_settings,
+            commands::settings_cmd::import_settings,
+            commands::settings_cmd::save_settings_profile,
+            commands::settings_cmd::apply_settings_profile,
+            commands::settings_cmd::delete_settings_profile,
             commands::settings_cmd::check_update,
+            commands::settings_cmd::acquire_qgqp_b_id,
+            commands::settings_cmd::validate_qgqp_b_id,
+            commands::settings_cmd::get_scheduled_jobs,
+            commands::settings_cmd::run_scheduled_job,
+            commands::settings_cmd::get_diagnostics,
+            commands::settings_cmd::sync_now,
+            commands::settings_cmd::test_sync_connection,
+            commands::settings_cmd::get_sync_status,
+            commands::settings_cmd::get_api_server_status,
+            commands::settings_cmd::test_notify_target,
+            commands::settings_cmd::take_pending_deep_link,
+            commands::settings_cmd::open_deep_link,
+            commands::window_cmd::open_detached_window,
+            commands::window_cmd::close_detached_window,
+            commands::window_cmd::set_detached_window_on_top,
+            commands::window_cmd::list_detached_windows,
             commands::market_cmd::get_market_overview,
             commands::market_cmd::generate_market_comment,
+            commands::market_cmd::screen_by_signal,
+            commands::market_cmd::refresh_technical_daily,
+            commands::market_cmd::get_technical_daily,
+            commands::market_cmd::generate_morning_briefing,
+            commands::market_cmd::get_market_briefing,
+            commands::market_cmd::export_briefing_audio,
+            commands::market_cmd::get_audio_export,
+            commands::market_cmd::open_audio_export,
+            commands::market_cmd::generate_daily_review,
+            commands::market_cmd::search_market_journal,
+            commands::market_cmd::get_market_stock_count,
+            commands::market_cmd::get_market_breadth,
+            commands::market_cmd::get_market_breadth_history,
+            commands::market_cmd::get_market_regime,
+            commands::market_cmd::get_market_heatmap,
+            commands::market_cmd::screen_top_rps,
+            commands::market_cmd::get_stock_rps,
+            commands::market_cmd::get_board_rotation,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| match event {
+            tauri::RunEvent::ExitRequested { api, .. } => {
+                services::shutdown::on_exit_requested(app, &api);
+            }
+            // macOS 点击 Dock 图标时恢复已隐藏到托盘的主窗口
+            #[cfg(target_os = "macos")]
+            tauri::RunEvent::Reopen { .. } => services::tray::show_main(app),
+            #[cfg(any(target_os = "macos", target_os = "ios"))]
+            tauri::RunEvent::Opened { urls } => {
+                for url in urls {
+                    services::deep_link::handle(app, url.as_str());
+                }
+            }
+            _ => {}
+        });
 }
@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::ai::{ChatMessage, PickRecord, TokenUsage};
+use crate::models::settings::PickPreferences;
+
+/// Agent 会话中的单次工具调用记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentToolCallRecord {
+    pub round: usize,
+    pub tool_name: String,
+    pub arguments: String,
+    /// 工具返回的原始结果（未摘要）
+    pub result: String,
+    /// 是否命中会话内缓存
+    #[serde(default)]
+    pub cached: bool,
+    #[serde(default)]
+    pub elapsed_ms: u64,
+}
+
+/// Agent 会话审计记录：一次诊股/选股/找相似/败因分析的完整工具调用过程
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentSession {
+    pub id: String,
+    /// 会话类型："diagnose" | "pick" | "similar" | "loss_analysis"
+    pub kind: String,
+    /// 分析对象（股票代码 / 日期等）
+    pub subject: String,
+    pub model_name: String,
+    pub tool_calls: Vec<AgentToolCallRecord>,
+    pub content: String,
+    /// 模型最终阶段的原始输出（后处理之前），用于回放复现解析问题
+    #[serde(default)]
+    pub raw_content: String,
+    pub error: Option<String>,
+    pub usage: Option<TokenUsage>,
+    pub created_at: String,
+}
+
+/// 选股会话详情：完整报告、工具调用日志与结构化推荐
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PickSessionDetail {
+    pub session: AgentSession,
+    pub picks: Vec<PickRecord>,
+}
+
+/// 选股 Agent 断点（pick_checkpoints 表）：每轮工具调用后写入，应用异常退出或运行出错后可据此续跑
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PickCheckpoint {
+    /// 截至断点的会话（含已完成的工具调用，最终阶段时 raw_content 为已生成的部分报告）
+    pub session: AgentSession,
+    /// 续跑时的起始轮次
+    pub next_round: usize,
+    /// 是否已进入最终报告阶段
+    pub final_phase: bool,
+    /// 续跑时发送给模型的完整对话
+    pub messages: Vec<ChatMessage>,
+    pub usage: Option<TokenUsage>,
+    pub preferences: PickPreferences,
+    pub updated_at: String,
+}
+
+/// 可续跑的中断选股（前端提示用）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterruptedPick {
+    pub session_id: String,
+    pub model_name: String,
+    pub started_at: String,
+    pub updated_at: String,
+    pub next_round: usize,
+    pub final_phase: bool,
+    pub tool_calls: usize,
+    /// 中断原因（应用退出 / 运行出错）
+    pub reason: String,
+}
+
+impl AgentSession {
+    pub fn new(kind: &str, subject: &str, model_name: &str) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            kind: kind.to_string(),
+            subject: subject.to_string(),
+            model_name: model_name.to_string(),
+            tool_calls: Vec::new(),
+            content: String::new(),
+            raw_content: String::new(),
+            error: None,
+            usage: None,
+            created_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        }
+    }
+
+    pub fn record_tool_call(
+        &mut self,
+        round: usize,
+        tool_name: &str,
+        arguments: &str,
+        result: &str,
+        cached: bool,
+        elapsed_ms: u64,
+    ) {
+        self.tool_calls.push(AgentToolCallRecord {
+            round,
+            tool_name: tool_name.to_string(),
+            arguments: arguments.to_string(),
+            result: result.to_string(),
+            cached,
+            elapsed_ms,
+        });
+    }
+
+    /// 根据 Agent 执行结果补全最终输出 / 错误信息
+    pub fn finish(&mut self, result: &anyhow::Result<(String, Option<TokenUsage>)>) {
+        match result {
+            Ok((content, usage)) => {
+                self.content = content.clone();
+                self.usage = usage.clone();
+            }
+            Err(e) => self.error = Some(e.to_string()),
+        }
+    }
+}
@@ -66,6 +66,38 @@ pub struct AIAnalysisResult {
     pub created_at: String,
 }
 
+/// AI 模型配置连通性测试结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AIConnectionTest {
+    /// 接口返回的模型标识（缺省时为配置中的模型名）
+    pub model: String,
+    pub reply: String,
+    /// 最小对话请求的往返耗时（毫秒）
+    pub latency_ms: u64,
+    /// 是否支持 Function Calling（探测请求返回了 tool_calls）
+    pub supports_tools: bool,
+    /// 工具调用探测失败或未返回 tool_calls 时的说明
+    pub tool_probe_message: Option<String>,
+}
+
+/// 模型能力（按 AIConfig 缓存），不支持的能力在调用时自动降级
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelCapabilities {
+    /// 是否支持原生 Function Calling，不支持时改用提示词注入工具模式
+    pub supports_tools: bool,
+    /// 是否支持流式输出，不支持时改用非流式请求后一次性推送
+    pub supports_stream: bool,
+}
+
+impl Default for ModelCapabilities {
+    fn default() -> Self {
+        Self {
+            supports_tools: true,
+            supports_stream: true,
+        }
+    }
+}
+
 // ========== Chat Completion 数据结构（支持 Function Calling）==========
 
 /// Chat message with optional tool_calls and tool_call_id
@@ -189,6 +221,9 @@ pub struct ChatCompletionRequest {
     pub messages: Vec<ChatMessage>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_tokens: Option<u32>,
+    /// OpenAI o 系列推理模型用 max_completion_tokens 代替 max_tokens
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_completion_tokens: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub temperature: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -228,6 +263,8 @@ pub struct ChatChoiceMessage {
 pub struct ChatDelta {
     pub role: Option<String>,
     pub content: Option<String>,
+    /// 推理模型流式输出的思考链增量
+    pub reasoning_content: Option<String>,
     pub tool_calls: Option<Vec<DeltaToolCall>>,
 }
 
@@ -277,18 +314,124 @@ pub struct BatchInstructionResponse {
     pub instructions: Vec<StockInstructionResult>,
 }
 
+/// 用户持仓，用于区分持仓中的加减仓建议与新开仓建议
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserPosition {
+    pub code: String,
+    /// 持仓成本价
+    pub cost: f64,
+    /// 持仓数量（股）
+    pub size: u64,
+    #[serde(default)]
+    pub holding_days: u32,
+}
+
+/// 新开仓指令：buy / watch / eliminate；持仓股指令：add(加仓) / trim(减仓) / hold(持有) / eliminate(清仓)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StockInstructionResult {
     pub code: String,
     pub action: String,
     pub label: String,
     pub reason: String,
+    /// 是否为持仓股的指令（按传入的持仓判定，不采信模型输出）
+    #[serde(default)]
+    pub held: bool,
+}
+
+/// 自然语言入口识别出的股票
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RoutedStock {
+    #[serde(default)]
+    pub code: String,
+    #[serde(default)]
+    pub name: String,
+}
+
+/// 自然语言入口识别出的意图与参数
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RoutedIntent {
+    /// diagnose / screen / news / compare / macro
+    pub intent: String,
+    #[serde(default)]
+    pub stocks: Vec<RoutedStock>,
+    /// 板块、概念或主题关键词
+    #[serde(default)]
+    pub keyword: String,
+}
+
+/// 自然语言入口的最终回答（流式内容同时推送到 ai-router-stream）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouterAnswer {
+    pub intent: RoutedIntent,
+    pub content: String,
+}
+
+/// AI 选股推荐项（对应 <PICKS> 标签内的 JSON 元素）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StockPick {
+    pub code: String,
+    pub name: String,
+    #[serde(default)]
+    pub reason: String,
+    #[serde(default)]
+    pub rating: String,
+    #[serde(default)]
+    pub sector: String,
+    #[serde(default)]
+    pub highlights: Vec<String>,
+    #[serde(default)]
+    pub fund_flow: String,
+    #[serde(default)]
+    pub valuation: String,
+}
+
+/// 已落库的选股结果（ai_picks 表），每次选股会话一组
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PickRecord {
+    pub session_id: String,
+    pub date: String,
+    pub code: String,
+    pub name: String,
+    pub rating: String,
+    pub reason: String,
+    pub sector: String,
+    pub highlights: Vec<String>,
+    pub fund_flow: String,
+    pub valuation: String,
+    /// 选股完成时的最新价，作为后续表现追踪的基准价（行情获取失败时为 0）
+    pub entry_price: f64,
+    /// 行情校验结果：passed / flagged / rejected / unverified
+    pub verify_status: String,
+    /// 校验未通过的原因，如「涨停（+10.01%）」
+    pub verify_note: String,
+    pub created_at: String,
+}
+
+/// 当日最近一次选股：完整报告 + 结构化推荐列表
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedPicks {
+    pub session_id: String,
+    pub content: String,
+    pub picks: Vec<PickRecord>,
+    pub created_at: String,
+}
+
+/// 选股会话列表项（历史浏览用，不含报告正文与工具日志）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PickSessionSummary {
+    pub session_id: String,
+    pub date: String,
+    pub model_name: String,
+    pub pick_count: usize,
+    pub total_tokens: u32,
+    pub error: Option<String>,
+    pub created_at: String,
 }
 
 /// 前端流式事件（支持工具调用状态通知）
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AIStreamEvent {
-    pub event_type: String,  // "content" | "tool_call" | "tool_result" | "done" | "error" | "thinking"
+    pub event_type: String,  // "content" | "tool_call" | "tool_result" | "done" | "error" | "thinking" | "mode" | "warning"
     pub content: Option<String>,
     pub done: bool,
     pub usage: Option<TokenUsage>,
@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+/// 语音播报导出记录（audio_exports 表，同一来源仅保留最新一份）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioExport {
+    /// 来源类型："briefing"（市场日志）| "analysis"（AI 分析记录）
+    pub source_kind: String,
+    /// 来源 id：市场日志为 "日期/类型"，分析记录为 ai_analysis.id
+    pub source_id: String,
+    /// 音频文件绝对路径（mp3）
+    pub path: String,
+    pub created_at: String,
+}
@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+/// 市场日志条目（market_briefing 表，同一日期同一类型仅保留最新一份）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketBriefing {
+    pub date: String,
+    /// 类型："morning"（早盘备忘）| "review"（收盘复盘）
+    pub kind: String,
+    /// AI 生成的 Markdown 正文
+    pub content: String,
+    pub model_name: String,
+    pub created_at: String,
+}
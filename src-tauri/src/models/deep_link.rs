@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+/// 外部链接（stockhelper://stock/sh600519）解析后的跳转目标
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeepLink {
+    /// stock（个股详情）/ analysis（个股详情并展开 AI 诊断）
+    pub target: String,
+    pub code: String,
+}
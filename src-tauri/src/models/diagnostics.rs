@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::models::job::ScheduledJob;
+
+/// 数据源连通性探测结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceHealth {
+    pub name: String,
+    pub url: String,
+    /// 收到 HTTP 响应即视为可达（AI 接口未带 Key 时返回 401 也算可达）
+    pub reachable: bool,
+    pub latency_ms: Option<u64>,
+    pub status: Option<u16>,
+    pub error: Option<String>,
+}
+
+/// 本地缓存表统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheStat {
+    pub table: String,
+    pub label: String,
+    pub rows: i64,
+    /// 最新数据日期，无日期列或空表时为 None
+    pub latest: Option<String>,
+}
+
+/// 诊断报告：数据源、数据库、缓存与后台任务状态，供排查“数据不更新”等问题
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosticsReport {
+    pub generated_at: String,
+    pub app_version: String,
+    pub os: String,
+    pub db_path: String,
+    pub db_size_bytes: u64,
+    pub sources: Vec<SourceHealth>,
+    /// 数据源格式变更记录
+    #[serde(default)]
+    pub schema_drifts: Vec<SchemaDrift>,
+    pub caches: Vec<CacheStat>,
+    /// 后台任务状态，含最近运行记录
+    pub jobs: Vec<ScheduledJob>,
+    /// 各任务最近一次成功时间
+    pub last_successes: HashMap<String, String>,
+    /// 退出前需等待的进行中任务数
+    pub active_tasks: usize,
+    pub ai_picking: bool,
+}
+
+/// 上游接口格式偏差：字段数或必需键与预期不符，通常意味着数据源改版
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaDrift {
+    pub source: String,
+    /// 累计发生次数（自启动起）
+    pub count: u64,
+    pub last_at: String,
+    /// 最近一次偏差详情，如“字段数 30，预期至少 32”
+    pub detail: String,
+}
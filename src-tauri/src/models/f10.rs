@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+
+/// 公司简介（东方财富 F10 公司概况 + 经营分析）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StockProfile {
+    pub code: String,
+    pub name: String,
+    /// 公司全称
+    pub full_name: String,
+    /// 东财行业分类
+    pub industry: String,
+    /// 证监会行业分类
+    pub csrc_industry: String,
+    pub listing_date: String,
+    pub chairman: String,
+    pub province: String,
+    pub website: String,
+    /// 公司简介
+    pub profile: String,
+    /// 主营业务（经营范围摘要）
+    pub main_business: String,
+    /// 主营构成对应的报告期
+    pub report_date: String,
+    /// 最新报告期的主营构成
+    pub segments: Vec<BusinessSegment>,
+}
+
+/// 主营构成分项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BusinessSegment {
+    /// 分类口径："行业" | "产品" | "地区"
+    pub category: String,
+    pub name: String,
+    /// 主营收入（元）
+    pub revenue: f64,
+    /// 收入占比（%）
+    pub revenue_ratio: f64,
+    /// 主营利润（元）
+    pub profit: f64,
+    /// 毛利率（%）
+    pub gross_margin: f64,
+}
+
+/// 机构一致预期（基于近半年个股研报汇总）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EarningsForecast {
+    pub code: String,
+    pub name: String,
+    /// 参与统计的研报数量
+    pub report_count: usize,
+    /// 参与统计的机构数量
+    pub org_count: usize,
+    pub ratings: RatingDistribution,
+    /// 未来两个会计年度的一致预期（按年份升序）
+    pub years: Vec<ForecastYear>,
+}
+
+/// 机构评级分布（研报数）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RatingDistribution {
+    pub buy: usize,
+    pub overweight: usize,
+    pub neutral: usize,
+    pub underweight: usize,
+    pub sell: usize,
+}
+
+/// 单个会计年度的一致预期
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForecastYear {
+    pub year: i32,
+    /// 预测 EPS 均值（元）
+    pub eps: f64,
+    pub eps_min: f64,
+    pub eps_max: f64,
+    /// 预测净利润（元）= EPS 均值 × 总股本；行情缺失时为 0
+    pub net_profit: f64,
+    /// 以最新价计算的预测市盈率；行情缺失时为 0
+    pub forward_pe: f64,
+    /// 给出该年度预测的研报数
+    pub sample_count: usize,
+}
+
+/// 单日估值（valuation_history 表）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValuationPoint {
+    pub date: String,
+    pub close: f64,
+    /// 市盈率 TTM，亏损时为负
+    pub pe_ttm: f64,
+    /// 市净率 MRQ
+    pub pb: f64,
+}
+
+/// 当前估值在自身历史中的分位
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValuationBand {
+    pub code: String,
+    /// 最新估值日期
+    pub date: String,
+    pub pe_ttm: f64,
+    pub pb: f64,
+    /// 当前 PE 在历史正值 PE 中的分位（0-100），当前亏损或样本不足时为 None
+    pub pe_percentile: Option<f64>,
+    pub pb_percentile: Option<f64>,
+    pub pe_min: f64,
+    pub pe_median: f64,
+    pub pe_max: f64,
+    pub pb_min: f64,
+    pub pb_median: f64,
+    pub pb_max: f64,
+    /// 参与统计的交易日数与起始日期
+    pub sample_days: usize,
+    pub start_date: String,
+}
+
+/// 同行业可比公司对比表
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeerComparison {
+    pub code: String,
+    pub name: String,
+    pub industry: String,
+    pub board_code: String,
+    /// 行业成分股总数
+    pub industry_size: usize,
+    /// 目标股在行业内的市值排名（从 1 开始）
+    pub market_cap_rank: usize,
+    /// 行业中位数（全部成分股，剔除无效值）
+    pub median_pe: f64,
+    pub median_pb: f64,
+    pub median_roe: f64,
+    /// 按市值降序的可比公司（始终包含目标股）
+    pub peers: Vec<PeerRow>,
+}
+
+/// 可比公司单行数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeerRow {
+    pub code: String,
+    pub name: String,
+    pub price: f64,
+    pub change_pct: f64,
+    pub total_market_cap: f64,
+    pub pe_ttm: f64,
+    pub pb: f64,
+    pub roe: f64,
+    /// 营收同比增长（%）
+    pub revenue_yoy: f64,
+    pub pct_20d: f64,
+    pub is_target: bool,
+}
@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+/// 后台任务单次运行记录（job_runs 表）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobRun {
+    pub id: i64,
+    pub job_id: String,
+    pub started_at: String,
+    pub finished_at: String,
+    pub success: bool,
+    /// 成功时为任务摘要，失败时为错误信息
+    pub message: String,
+}
+
+/// 后台任务状态（调度配置 + 最近运行记录）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledJob {
+    pub id: String,
+    pub name: String,
+    /// 调度规则说明，如 "交易日 15:30 后每日一次"
+    pub schedule: String,
+    pub enabled: bool,
+    /// 当前生效的检查/执行间隔（秒）
+    pub interval_secs: u64,
+    pub default_interval_secs: u64,
+    pub running: bool,
+    /// 最近的运行记录，最新在前
+    pub recent_runs: Vec<JobRun>,
+}
@@ -5,3 +5,15 @@ pub mod watchlist;
 pub mod news;
 pub mod tracking;
 pub mod agent_prompt;
+pub mod agent_session;
+pub mod f10;
+pub mod briefing;
+pub mod job;
+pub mod diagnostics;
+pub mod sync;
+pub mod deep_link;
+pub mod window;
+pub mod replay;
+pub mod risk;
+pub mod audio;
+pub mod schedule;
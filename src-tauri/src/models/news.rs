@@ -57,6 +57,45 @@ pub struct AnnouncementItem {
     pub category: String,
 }
 
+/// 归档的分类公告事件（回购、业绩预增、减持等），code 为带市场前缀的代码
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnnouncementEvent {
+    pub id: String,
+    pub code: String,
+    pub name: String,
+    pub notice_date: String,
+    pub category: String,
+    pub title: String,
+}
+
+/// 某类公告发布后的平均异常收益（%，个股收益减沪深300同期收益）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AnnouncementEventStats {
+    pub category: String,
+    /// 有本地日线的事件数
+    pub events: usize,
+    /// 公告日当天（相对前一交易日收盘）的异常收益
+    pub avg_ar_0d: Option<f64>,
+    /// 自公告日收盘起 1/5/20 个交易日的累计异常收益
+    pub avg_ar_1d: Option<f64>,
+    pub avg_ar_5d: Option<f64>,
+    pub avg_ar_20d: Option<f64>,
+    /// 5 日异常收益为正的事件占比
+    pub win_rate_5d: Option<f64>,
+    pub t_stat_5d: Option<f64>,
+}
+
+/// 公告事件研究结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AnnouncementStudyResult {
+    pub start: String,
+    pub end: String,
+    /// 参与研究的归档公告数（含缺少本地日线的）
+    pub announcements: usize,
+    pub stats: Vec<AnnouncementEventStats>,
+    pub updated_at: String,
+}
+
 /// 研报条目
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReportItem {
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+/// 盘中分时快照（集合竞价与连续竞价时段定时采集，用于盘后复盘回放）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IntradaySnapshot {
+    pub code: String,
+    pub name: String,
+    /// YYYY-MM-DD
+    pub date: String,
+    /// 行情时间 HH:MM:SS
+    pub time: String,
+    pub price: f64,
+    pub pre_close: f64,
+    pub change_pct: f64,
+    /// 累计成交量（股）
+    pub volume: f64,
+    /// 累计成交额（元）
+    pub amount: f64,
+    pub bid1: f64,
+    pub ask1: f64,
+    pub bid1_vol: f64,
+    pub ask1_vol: f64,
+}
+
+/// 回放的一帧：截至该分钟各股票的最新快照
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayFrame {
+    /// HH:MM
+    pub time: String,
+    pub quotes: Vec<IntradaySnapshot>,
+}
+
+/// 可回放的交易日
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayDay {
+    pub date: String,
+    pub stock_count: usize,
+    pub snapshot_count: usize,
+}
@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+
+/// 单只持仓的敞口（同一股票多次追踪合并）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionExposure {
+    pub code: String,
+    pub name: String,
+    /// 行业，为空表示未分类
+    pub sector: String,
+    /// 追踪记录数（每条视为一份等额建仓）
+    pub lots: usize,
+    /// 平均建仓价
+    pub entry_price: f64,
+    pub price: f64,
+    pub return_pct: f64,
+    /// 按当前市值计算的仓位 %
+    pub weight_pct: f64,
+    /// 自建仓以来最高收盘价的回撤 %
+    pub drawdown_pct: f64,
+    pub is_st: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SectorExposure {
+    pub sector: String,
+    pub weight_pct: f64,
+    pub codes: Vec<String>,
+}
+
+/// 日收益率高度相关的一组持仓
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CorrelatedCluster {
+    pub codes: Vec<String>,
+    pub names: Vec<String>,
+    pub weight_pct: f64,
+    /// 组内相连股票对的最低相关系数
+    pub min_correlation: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RiskViolation {
+    /// position / sector / cluster / st / drawdown
+    pub rule: String,
+    /// 触发对象（股票名称、行业名称或股票群）
+    pub subject: String,
+    pub message: String,
+    pub value: f64,
+    pub limit: f64,
+}
+
+/// 风控报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskReport {
+    pub generated_at: String,
+    pub positions: Vec<PositionExposure>,
+    pub sectors: Vec<SectorExposure>,
+    pub clusters: Vec<CorrelatedCluster>,
+    pub violations: Vec<RiskViolation>,
+}
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::ai::AIAnalysisResult;
+
+/// 盯盘助手：对单只股票的每日定时 AI 快检（stock_schedules 表）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StockSchedule {
+    pub id: String,
+    pub code: String,
+    pub name: String,
+    /// 每个交易日的执行时间，HH:MM
+    pub time: String,
+    /// 要问的问题，如“分析尾盘该不该走”
+    pub question: String,
+    pub enabled: bool,
+    /// 最近一次执行的日期，从未执行为空
+    #[serde(default)]
+    pub last_run_date: String,
+    #[serde(default)]
+    pub created_at: String,
+}
+
+/// 一次快检结果，changed_lines 为与上一次结果相比新增或变化的行号（从 0 开始）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleRun {
+    pub schedule_id: String,
+    pub analysis: AIAnalysisResult,
+    /// 上一次结果的 id，首次运行为空
+    pub previous_id: Option<String>,
+    pub changed_lines: Vec<usize>,
+}
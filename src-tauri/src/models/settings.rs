@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use super::ai::AIConfig;
 use super::agent_prompt::AgentPrompt;
+use super::stock::{RegimeTrend, RegimeVolatility};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppSettings {
@@ -28,12 +30,99 @@ pub struct AppSettings {
     pub agent_prompts: Vec<AgentPrompt>,
     #[serde(default)]
     pub active_pick_prompt_id: Option<String>,
+    #[serde(default)]
+    pub signal_config: SignalConfig,
+    /// 交易日开盘前自动生成早盘备忘
+    #[serde(default = "default_true")]
+    pub morning_briefing_enabled: bool,
+    /// 用户自定义的策略区间（按顺序评估）
+    #[serde(default = "default_strategy_zones")]
+    pub strategy_zones: Vec<StrategyZone>,
+    /// AI 选股偏好约束（注入选股提示词，并在缓存前硬性过滤违规结果）
+    #[serde(default)]
+    pub pick_preferences: PickPreferences,
+    /// 后台任务配置，按任务 id 索引，未配置的任务启用并使用默认间隔
+    #[serde(default)]
+    pub job_settings: HashMap<String, JobSetting>,
+    /// 调试日志：记录上游请求的 URL、状态码、耗时与请求体大小（不记录内容）
+    #[serde(default)]
+    pub debug_logging: bool,
+    /// 命名的设置方案（策略与 AI 参数组合），可在运行时切换
+    #[serde(default)]
+    pub profiles: Vec<SettingsProfile>,
+    /// 最近一次应用的方案
+    #[serde(default)]
+    pub active_profile_id: Option<String>,
+    /// 多设备同步配置
+    #[serde(default)]
+    pub sync: SyncConfig,
+    /// 本机 HTTP API 配置
+    #[serde(default)]
+    pub api_server: ApiServerConfig,
+    /// 消息推送目标（钉钉、企业微信、Telegram、Server酱、通用 Webhook）
+    #[serde(default)]
+    pub notify_targets: Vec<NotifyTarget>,
+    /// 关闭主窗口时最小化到托盘，后台任务继续运行
+    #[serde(default = "default_true")]
+    pub close_to_tray: bool,
+    /// 唤起快速查股窗口的全局快捷键（如 CommandOrControl+Alt+K），为空表示不启用
+    #[serde(default = "default_quick_search_hotkey")]
+    pub quick_search_hotkey: String,
+    /// 持仓风控规则（AI 追踪组合）
+    #[serde(default)]
+    pub risk_rules: RiskRules,
+    /// 语音播报（TTS）接口配置，兼容 OpenAI /audio/speech
+    #[serde(default)]
+    pub tts: TtsConfig,
+    /// 条件选股方案：用户编写的筛选表达式
+    #[serde(default)]
+    pub screener_presets: Vec<ScreenerPreset>,
+    /// 动量因子按相对沪深300的超额收益计算，全市场普涨普跌时不整体抬高或压低评分
+    #[serde(default)]
+    pub momentum_vs_benchmark: bool,
+    /// 焦点盯盘：单笔成交额达到该值（元）视为大单
+    #[serde(default = "default_large_order_threshold")]
+    pub large_order_threshold: f64,
+    /// 竞价异动扫描只扫自选股（默认扫描全市场）
+    #[serde(default)]
+    pub auction_scan_watchlist_only: bool,
+    /// 实时行情源优先级（provider id，如 sina / tencent / eastmoney / 自定义接口 id），依次尝试直至取得数据；
+    /// 为空时按 data_source_primary 决定新浪与腾讯的先后，东财与已启用的自定义接口依次兜底
+    #[serde(default)]
+    pub quote_provider_order: Vec<String>,
+    /// 用户自定义的 JSON 行情接口
+    #[serde(default)]
+    pub custom_quote_providers: Vec<CustomQuoteProvider>,
+    /// 因子评分按市场状态切换分项权重，按顺序取第一条匹配的映射；为空或无匹配时各分项等权
+    #[serde(default = "default_regime_factor_weights")]
+    pub regime_factor_weights: Vec<RegimeFactorWeights>,
+    /// AI 流式正文合并推送，减少长报告逐 token 触发的前端事件
+    #[serde(default)]
+    pub stream_batch: StreamBatchConfig,
 }
 
 fn default_refresh_interval() -> u64 { 30 }
 fn default_true() -> bool { true }
 fn default_max_pick_tool_rounds() -> usize { 10 }
 fn default_max_pick_token_budget() -> u32 { 100_000 }
+fn default_quick_search_hotkey() -> String { "CommandOrControl+Alt+K".to_string() }
+fn default_large_order_threshold() -> f64 { 1_000_000.0 }
+
+/// 默认映射：上升趋势加重动量与资金，下跌趋势加重估值与质量
+fn default_regime_factor_weights() -> Vec<RegimeFactorWeights> {
+    vec![
+        RegimeFactorWeights {
+            trend: Some(RegimeTrend::Up),
+            volatility: None,
+            weights: FactorWeights { quality: 1.0, growth: 1.2, value: 0.6, momentum: 2.0, flow: 1.5 },
+        },
+        RegimeFactorWeights {
+            trend: Some(RegimeTrend::Down),
+            volatility: None,
+            weights: FactorWeights { quality: 2.0, growth: 1.0, value: 2.0, momentum: 0.5, flow: 0.8 },
+        },
+    ]
+}
 
 impl Default for AppSettings {
     fn default() -> Self {
@@ -50,10 +139,433 @@ impl Default for AppSettings {
             max_pick_token_budget: 100_000,
             agent_prompts: vec![],
             active_pick_prompt_id: None,
+            signal_config: SignalConfig::default(),
+            morning_briefing_enabled: true,
+            strategy_zones: default_strategy_zones(),
+            pick_preferences: PickPreferences::default(),
+            job_settings: HashMap::new(),
+            debug_logging: false,
+            profiles: vec![],
+            active_profile_id: None,
+            sync: SyncConfig::default(),
+            api_server: ApiServerConfig::default(),
+            notify_targets: vec![],
+            close_to_tray: true,
+            quick_search_hotkey: default_quick_search_hotkey(),
+            risk_rules: RiskRules::default(),
+            tts: TtsConfig::default(),
+            screener_presets: vec![],
+            momentum_vs_benchmark: false,
+            large_order_threshold: default_large_order_threshold(),
+            auction_scan_watchlist_only: false,
+            quote_provider_order: vec![],
+            custom_quote_providers: vec![],
+            regime_factor_weights: default_regime_factor_weights(),
+            stream_batch: StreamBatchConfig::default(),
+        }
+    }
+}
+
+impl AppSettings {
+    /// 当前启用的 AI 模型配置
+    pub fn active_ai_config(&self) -> Option<AIConfig> {
+        self.ai_configs.iter()
+            .find(|c| Some(c.id.clone()) == self.active_ai_config_id && c.enabled)
+            .cloned()
+    }
+
+    /// 当前设置中属于方案的参数
+    pub fn profile_params(&self) -> ProfileParams {
+        ProfileParams {
+            active_ai_config_id: self.active_ai_config_id.clone(),
+            active_pick_prompt_id: self.active_pick_prompt_id.clone(),
+            max_pick_tool_rounds: self.max_pick_tool_rounds,
+            max_pick_token_budget: self.max_pick_token_budget,
+            signal_config: self.signal_config.clone(),
+            strategy_zones: self.strategy_zones.clone(),
+            pick_preferences: self.pick_preferences.clone(),
+        }
+    }
+
+    /// 应用方案参数；方案引用的 AI 模型或提示词已被删除时保留当前选择
+    pub fn apply_profile_params(&mut self, params: &ProfileParams) {
+        if let Some(id) = &params.active_ai_config_id {
+            if self.ai_configs.iter().any(|c| &c.id == id) {
+                self.active_ai_config_id = Some(id.clone());
+            }
+        }
+        match &params.active_pick_prompt_id {
+            Some(id) if self.agent_prompts.iter().any(|p| &p.id == id) => self.active_pick_prompt_id = Some(id.clone()),
+            Some(_) => {}
+            None => self.active_pick_prompt_id = None,
+        }
+        self.max_pick_tool_rounds = params.max_pick_tool_rounds;
+        self.max_pick_token_budget = params.max_pick_token_budget;
+        self.signal_config = params.signal_config.clone();
+        self.strategy_zones = params.strategy_zones.clone();
+        self.pick_preferences = params.pick_preferences.clone();
+    }
+}
+
+/// 因子评分各分项的相对权重，综合分为有数据分项的加权均值
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FactorWeights {
+    #[serde(default = "default_factor_weight")]
+    pub quality: f64,
+    #[serde(default = "default_factor_weight")]
+    pub growth: f64,
+    #[serde(default = "default_factor_weight")]
+    pub value: f64,
+    #[serde(default = "default_factor_weight")]
+    pub momentum: f64,
+    #[serde(default = "default_factor_weight")]
+    pub flow: f64,
+}
+
+fn default_factor_weight() -> f64 { 1.0 }
+
+impl Default for FactorWeights {
+    fn default() -> Self {
+        Self { quality: 1.0, growth: 1.0, value: 1.0, momentum: 1.0, flow: 1.0 }
+    }
+}
+
+/// 市场状态到因子权重的映射，trend / volatility 为空表示匹配任意状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegimeFactorWeights {
+    #[serde(default)]
+    pub trend: Option<RegimeTrend>,
+    #[serde(default)]
+    pub volatility: Option<RegimeVolatility>,
+    #[serde(default)]
+    pub weights: FactorWeights,
+}
+
+/// 条件选股方案，expression 如 `pe_ttm < 30 && roe > 10 && !name.contains('ST')`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScreenerPreset {
+    pub id: String,
+    pub name: String,
+    pub expression: String,
+    #[serde(default)]
+    pub updated_at: String,
+}
+
+/// 设置方案：一组可整体切换的策略与 AI 参数，如“激进短线”“稳健中线”
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingsProfile {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub updated_at: String,
+    pub params: ProfileParams,
+}
+
+/// 方案包含的参数：当前模型与选股提示词、Agent 轮次与 Token 预算、信号参数、策略区间、选股偏好
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileParams {
+    #[serde(default)]
+    pub active_ai_config_id: Option<String>,
+    #[serde(default)]
+    pub active_pick_prompt_id: Option<String>,
+    #[serde(default = "default_max_pick_tool_rounds")]
+    pub max_pick_tool_rounds: usize,
+    #[serde(default = "default_max_pick_token_budget")]
+    pub max_pick_token_budget: u32,
+    #[serde(default)]
+    pub signal_config: SignalConfig,
+    #[serde(default = "default_strategy_zones")]
+    pub strategy_zones: Vec<StrategyZone>,
+    #[serde(default)]
+    pub pick_preferences: PickPreferences,
+}
+
+/// 单个后台任务的用户配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobSetting {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// 检查/执行间隔（秒），为空使用任务默认值
+    #[serde(default)]
+    pub interval_secs: Option<u64>,
+}
+
+/// 多设备同步配置：自选股、设置与 AI 分析记录同步到 WebDAV 或 S3 兼容存储
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncConfig {
+    /// 启用后由后台任务定时同步
+    #[serde(default)]
+    pub enabled: bool,
+    /// webdav / s3
+    #[serde(default = "default_sync_backend")]
+    pub backend: String,
+    /// WebDAV 服务地址，或 S3 兼容服务的 Endpoint（如 https://s3.amazonaws.com、MinIO 地址）
+    #[serde(default)]
+    pub endpoint: String,
+    /// WebDAV 用户名，或 S3 Access Key ID
+    #[serde(default)]
+    pub username: String,
+    /// WebDAV 密码，或 S3 Secret Access Key
+    #[serde(default)]
+    pub password: String,
+    /// S3 存储桶，WebDAV 不使用
+    #[serde(default)]
+    pub bucket: String,
+    #[serde(default = "default_sync_region")]
+    pub region: String,
+    /// 远端目录（WebDAV）或对象前缀（S3）
+    #[serde(default = "default_sync_path")]
+    pub remote_path: String,
+}
+
+fn default_sync_backend() -> String { "webdav".to_string() }
+fn default_sync_region() -> String { "us-east-1".to_string() }
+fn default_sync_path() -> String { "stock-helper".to_string() }
+
+impl Default for SyncConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            backend: default_sync_backend(),
+            endpoint: String::new(),
+            username: String::new(),
+            password: String::new(),
+            bucket: String::new(),
+            region: default_sync_region(),
+            remote_path: default_sync_path(),
+        }
+    }
+}
+
+/// 技术信号检测参数（诊股工具输出与信号预警共用）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignalConfig {
+    /// 检测最近 N 个交易日内的信号
+    #[serde(default = "default_lookback_days")]
+    pub lookback_days: usize,
+    #[serde(default = "default_overbought")]
+    pub kdj_overbought: f64,
+    #[serde(default = "default_oversold")]
+    pub kdj_oversold: f64,
+    #[serde(default = "default_overbought")]
+    pub rsi_overbought: f64,
+    #[serde(default = "default_oversold")]
+    pub rsi_oversold: f64,
+    /// 放量判定：当日成交量 / 前5日均量
+    #[serde(default = "default_volume_surge_ratio")]
+    pub volume_surge_ratio: f64,
+    /// MACD 背离检测窗口（交易日）
+    #[serde(default = "default_divergence_window")]
+    pub divergence_window: usize,
+    /// 信号预警的最低强度（1-5）
+    #[serde(default = "default_alert_min_strength")]
+    pub alert_min_strength: u8,
+    /// 启用的信号类型（signal_type），为空表示全部启用
+    #[serde(default)]
+    pub enabled_signals: Vec<String>,
+}
+
+fn default_lookback_days() -> usize { 5 }
+fn default_overbought() -> f64 { 80.0 }
+fn default_oversold() -> f64 { 20.0 }
+fn default_volume_surge_ratio() -> f64 { 2.0 }
+fn default_divergence_window() -> usize { 30 }
+fn default_alert_min_strength() -> u8 { 4 }
+
+impl Default for SignalConfig {
+    fn default() -> Self {
+        Self {
+            lookback_days: 5,
+            kdj_overbought: 80.0,
+            kdj_oversold: 20.0,
+            rsi_overbought: 80.0,
+            rsi_oversold: 20.0,
+            volume_surge_ratio: 2.0,
+            divergence_window: 30,
+            alert_min_strength: 4,
+            enabled_signals: vec![],
+        }
+    }
+}
+
+impl SignalConfig {
+    pub fn is_enabled(&self, signal_type: &str) -> bool {
+        self.enabled_signals.is_empty() || self.enabled_signals.iter().any(|s| s == signal_type)
+    }
+}
+
+/// 持仓风控规则：按 AI 追踪组合（每条追踪记录视为等额建仓）计算敞口并检查
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskRules {
+    /// 收盘后自动检查并推送违规项
+    #[serde(default = "default_true")]
+    pub alert_enabled: bool,
+    /// 单只股票最大仓位 %
+    #[serde(default = "default_max_position_pct")]
+    pub max_position_pct: f64,
+    /// 单个行业最大仓位 %
+    #[serde(default = "default_max_sector_pct")]
+    pub max_sector_pct: f64,
+    /// 高相关股票群最大合计仓位 %
+    #[serde(default = "default_max_cluster_pct")]
+    pub max_cluster_pct: f64,
+    /// 日收益率相关系数不低于该值视为高相关
+    #[serde(default = "default_correlation_threshold")]
+    pub correlation_threshold: f64,
+    /// 禁止持有 ST 股
+    #[serde(default = "default_true")]
+    pub forbid_st: bool,
+    /// 单只股票自建仓后最高点回撤超过该值告警 %
+    #[serde(default = "default_max_drawdown_pct")]
+    pub max_drawdown_pct: f64,
+}
+
+fn default_max_position_pct() -> f64 { 20.0 }
+fn default_max_sector_pct() -> f64 { 40.0 }
+fn default_max_cluster_pct() -> f64 { 50.0 }
+fn default_correlation_threshold() -> f64 { 0.8 }
+fn default_max_drawdown_pct() -> f64 { 15.0 }
+
+impl Default for RiskRules {
+    fn default() -> Self {
+        Self {
+            alert_enabled: true,
+            max_position_pct: 20.0,
+            max_sector_pct: 40.0,
+            max_cluster_pct: 50.0,
+            correlation_threshold: 0.8,
+            forbid_st: true,
+            max_drawdown_pct: 15.0,
+        }
+    }
+}
+
+/// 语音播报配置：调用 OpenAI 兼容的 /audio/speech 接口把早盘备忘、诊断结论转为音频
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TtsConfig {
+    /// 接口地址，如 https://api.openai.com/v1，为空表示未配置
+    #[serde(default)]
+    pub base_url: String,
+    #[serde(default)]
+    pub api_key: String,
+    #[serde(default = "default_tts_model")]
+    pub model: String,
+    /// 音色，如 alloy、nova
+    #[serde(default = "default_tts_voice")]
+    pub voice: String,
+    /// 语速，0.25 ~ 4.0
+    #[serde(default = "default_tts_speed")]
+    pub speed: f64,
+}
+
+fn default_tts_model() -> String { "tts-1".to_string() }
+fn default_tts_voice() -> String { "alloy".to_string() }
+fn default_tts_speed() -> f64 { 1.0 }
+
+impl Default for TtsConfig {
+    fn default() -> Self {
+        Self {
+            base_url: String::new(),
+            api_key: String::new(),
+            model: default_tts_model(),
+            voice: default_tts_voice(),
+            speed: default_tts_speed(),
         }
     }
 }
 
+/// AI 选股偏好：风险偏好、板块包含/排除、市值区间、持有周期、股价上限、动量因子与价值/质量因子，均为空表示不限
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PickPreferences {
+    /// conservative（稳健）/ balanced（均衡）/ aggressive（激进）
+    #[serde(default)]
+    pub risk_appetite: String,
+    #[serde(default)]
+    pub include_sectors: Vec<String>,
+    #[serde(default)]
+    pub exclude_sectors: Vec<String>,
+    /// 总市值下限（亿元）
+    #[serde(default)]
+    pub min_market_cap: Option<f64>,
+    /// 总市值上限（亿元）
+    #[serde(default)]
+    pub max_market_cap: Option<f64>,
+    /// short（1~5 个交易日）/ swing（1~4 周）/ medium（1~6 个月）
+    #[serde(default)]
+    pub holding_horizon: String,
+    /// 股价上限（元）
+    #[serde(default)]
+    pub max_price: Option<f64>,
+    /// 选股范围：指数代码（如 000300 表示只在沪深300成分股中选），为空不限
+    #[serde(default)]
+    pub universe: String,
+    /// 动量因子：60 日 RPS 下限（0~100），为空不限
+    #[serde(default)]
+    pub min_rps: Option<f64>,
+    /// 价值因子：股息率（TTM）下限 %
+    #[serde(default)]
+    pub min_dividend_yield: Option<f64>,
+    /// 价值因子：现金流收益率（100 / 市现率TTM）下限 %
+    #[serde(default)]
+    pub min_fcf_yield: Option<f64>,
+    /// 质量因子：资产负债率上限 %
+    #[serde(default)]
+    pub max_debt_ratio: Option<f64>,
+    /// 质量因子：净利润同比增长下限 %
+    #[serde(default)]
+    pub min_profit_yoy: Option<f64>,
+}
+
+/// 策略区间：一组作用于竞价快照字段的规则，全部满足即归入该区间
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrategyZone {
+    pub id: String,
+    pub name: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    pub rules: Vec<ZoneRule>,
+}
+
+/// 区间规则：字段取值落在 [min, max] 内（任一端为空表示不限）
+/// field 可选：open_pct / current_pct / score / bid_amount（元）/ streak_days / turnover
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZoneRule {
+    pub field: String,
+    #[serde(default)]
+    pub min: Option<f64>,
+    #[serde(default)]
+    pub max: Option<f64>,
+}
+
+impl ZoneRule {
+    fn range(field: &str, min: Option<f64>, max: Option<f64>) -> Self {
+        Self { field: field.to_string(), min, max }
+    }
+}
+
+fn default_strategy_zones() -> Vec<StrategyZone> {
+    vec![
+        StrategyZone {
+            id: "strong_bid".to_string(),
+            name: "高强度竞价区".to_string(),
+            enabled: true,
+            rules: vec![
+                ZoneRule::range("open_pct", Some(3.0), Some(9.5)),
+                ZoneRule::range("bid_amount", Some(30_000_000.0), None),
+            ],
+        },
+        StrategyZone {
+            id: "low_absorb".to_string(),
+            name: "低吸区".to_string(),
+            enabled: true,
+            rules: vec![
+                ZoneRule::range("open_pct", Some(-3.0), Some(0.0)),
+                ZoneRule::range("streak_days", Some(2.0), None),
+            ],
+        },
+    ]
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub enum DataSource {
     #[default]
@@ -62,3 +574,117 @@ pub enum DataSource {
     #[serde(rename = "tencent")]
     Tencent,
 }
+
+/// 自定义 JSON 行情接口：通过字段映射把任意返回 JSON 的行情接口接入行情源注册表，无需改代码
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomQuoteProvider {
+    pub id: String,
+    pub name: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// 请求地址，{codes} 替换为逗号分隔的股票代码。地址会随设置导出与多设备同步，不要在其中携带密钥
+    pub url: String,
+    /// 代码格式：prefixed（sh600000）或 pure（600000）
+    #[serde(default = "default_code_format")]
+    pub code_format: String,
+    /// 附加请求头，如 Referer、Authorization；视为密钥，不含密钥的导出与多设备同步时清空
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// 行情列表在响应中的路径（点分隔，如 data.list）；为空表示根节点。列表可以是数组，也可以是以代码为键的对象
+    #[serde(default)]
+    pub list_path: String,
+    /// 字段映射：StockInfo 字段名（code/name/price/pre_close/open/high/low/volume/amount/bid/ask/date/time）→ 条目内的字段路径
+    #[serde(default)]
+    pub fields: HashMap<String, String>,
+}
+
+fn default_code_format() -> String { "prefixed".to_string() }
+
+/// 本机 HTTP API：仅监听 127.0.0.1，供 Python/Excel 等脚本调用行情、自选股、选股与诊断
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiServerConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_api_port")]
+    pub port: u16,
+    /// 访问令牌，非空时请求需携带 Authorization: Bearer <token> 或 ?token=<token>
+    #[serde(default)]
+    pub token: String,
+    /// 在 /mcp 提供 MCP（Model Context Protocol）服务，供外部 AI 客户端调用诊股与选股工具
+    #[serde(default)]
+    pub mcp_enabled: bool,
+}
+
+fn default_api_port() -> u16 { 18520 }
+
+impl Default for ApiServerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: default_api_port(),
+            token: String::new(),
+            mcp_enabled: false,
+        }
+    }
+}
+
+/// AI 流式正文合并推送：攒够 max_chars 个字符或距上次推送满 flush_interval_ms 毫秒时推送一次，
+/// 流结束时 done 事件附带完整正文
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamBatchConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_stream_flush_interval_ms")]
+    pub flush_interval_ms: u64,
+    #[serde(default = "default_stream_max_chars")]
+    pub max_chars: usize,
+}
+
+fn default_stream_flush_interval_ms() -> u64 { 50 }
+fn default_stream_max_chars() -> usize { 200 }
+
+impl Default for StreamBatchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            flush_interval_ms: default_stream_flush_interval_ms(),
+            max_chars: default_stream_max_chars(),
+        }
+    }
+}
+
+/// 本机 HTTP API 运行状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiServerStatus {
+    pub running: bool,
+    /// 监听地址，如 http://127.0.0.1:18520
+    pub address: Option<String>,
+    /// 最近一次启动失败的原因（如端口被占用）
+    pub error: Option<String>,
+}
+
+/// 消息推送目标
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotifyTarget {
+    pub id: String,
+    pub name: String,
+    /// dingtalk / wecom / telegram / serverchan / webhook
+    pub kind: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// 钉钉、企业微信机器人或通用 Webhook 地址
+    #[serde(default)]
+    pub url: String,
+    /// Server酱 SendKey 或 Telegram Bot Token
+    #[serde(default)]
+    pub key: String,
+    /// 钉钉机器人加签密钥（未开启加签时留空）
+    #[serde(default)]
+    pub secret: String,
+    /// Telegram 接收消息的 chat_id
+    #[serde(default)]
+    pub chat_id: String,
+    /// 订阅的事件：signal_alert / anomaly / morning_briefing / risk_alert / auction_gap，为空时推送全部
+    #[serde(default)]
+    pub events: Vec<String>,
+}
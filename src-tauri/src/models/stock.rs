@@ -1,7 +1,8 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-/// 全市场股票快照数据（来自东方财富 clist API）
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// 全市场股票快照数据（来自东方财富 clist API），各来源在解析时统一换算为 utils::units 约定的单位
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct MarketStockSnapshot {
     pub code: String,          // "sz000001"
     pub name: String,
@@ -32,10 +33,35 @@ pub struct MarketStockSnapshot {
     pub main_net_pct: f64,     // 主力净占比 %
     #[serde(default)]
     pub list_date: String,     // 上市日期 "YYYYMMDD"（来自东财 f26）
+    #[serde(default)]
+    pub dividend_yield: f64,   // 股息率 %（TTM，补充财务字段）
+    #[serde(default)]
+    pub fcf_yield: f64,        // 现金流收益率 %（100 / 市现率TTM，补充财务字段）
+    #[serde(default)]
+    pub debt_ratio: f64,       // 资产负债率 %（补充财务字段）
+    #[serde(default)]
+    pub tail_flag: Option<String>, // 最近一个交易日尾盘集合竞价标记："尾盘抢筹" / "尾盘砸盘"（自选股增强行情填入）
 }
 
-/// 实时行情数据（用于已选股票的详细盘口）
+/// 条件选股结果：全市场扫描后按表达式筛选，按涨跌幅倒序
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpressionScreenResult {
+    pub expression: String,
+    /// 参与筛选的股票数
+    pub scanned: usize,
+    /// 满足条件的股票总数（stocks 可能被截断）
+    pub matched: usize,
+    pub stocks: Vec<MarketStockSnapshot>,
+    /// 选股理由（按代码索引），列出排名靠前的因子及数值，如 "ROE前15% + 主力净流入1.2亿"
+    #[serde(default)]
+    pub explanations: HashMap<String, String>,
+    /// 前若干只结果的主要题材（按代码索引，按暴露权重降序）
+    #[serde(default)]
+    pub themes: HashMap<String, Vec<String>>,
+}
+
+/// 实时行情数据（用于已选股票的详细盘口）
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct StockInfo {
     pub code: String,
     pub name: String,
@@ -116,7 +142,7 @@ pub struct KLineData {
     pub amount: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct StockDailyHistory {
     pub code: String,
     pub date: String,
@@ -131,6 +157,35 @@ pub struct StockDailyHistory {
     pub turnover_rate: f64,
 }
 
+/// 批量同步日线缓存的单只结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistorySyncItem {
+    pub code: String,
+    /// 本次新增的日线条数
+    pub added: usize,
+    /// 本地缓存已含当日K线，未发起请求
+    pub cached: bool,
+    pub error: Option<String>,
+}
+
+/// 批量同步日线的进度事件，每完成一只推送一次
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistorySyncProgress {
+    pub completed: usize,
+    pub total: usize,
+    pub item: HistorySyncItem,
+}
+
+/// 批量同步日线的汇总；failed_codes 可原样传回同步命令续传
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistorySyncResult {
+    pub total: usize,
+    pub synced: usize,
+    pub cached: usize,
+    pub added_rows: usize,
+    pub failed_codes: Vec<String>,
+}
+
 /// 股票搜索结果
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StockSearchResult {
@@ -138,3 +193,470 @@ pub struct StockSearchResult {
     pub name: String,
     pub market: String,
 }
+
+/// 行业/概念板块行情（东财板块列表）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BoardQuote {
+    /// 板块代码，如 BK0477
+    pub code: String,
+    pub name: String,
+    /// "industry" 行业板块 / "concept" 概念板块
+    pub kind: String,
+    pub change_pct: f64,
+    pub turnover_rate: f64,
+    /// 成交额（元）
+    pub amount: f64,
+    pub total_market_cap: f64,
+    /// 主力净流入（元）
+    pub main_net_inflow: f64,
+    pub up_count: u32,
+    pub down_count: u32,
+    /// 领涨股
+    pub leader_code: String,
+    pub leader_name: String,
+    pub leader_change_pct: f64,
+}
+
+/// 板块热力图：全部行业与概念板块的当日行情
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MarketHeatmap {
+    pub industries: Vec<BoardQuote>,
+    pub concepts: Vec<BoardQuote>,
+    pub updated_at: String,
+}
+
+/// A股数量统计：按板块与状态拆分
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MarketStockCount {
+    pub total: usize,
+    /// 沪深主板
+    pub main_board: usize,
+    /// 创业板
+    pub chinext: usize,
+    /// 科创板
+    pub star: usize,
+    /// 北交所
+    pub bse: usize,
+    /// 停牌（无最新价）
+    pub suspended: usize,
+    /// ST / *ST
+    pub st: usize,
+    /// 本月新上市
+    pub new_this_month: usize,
+    pub updated_at: String,
+}
+
+/// 市场宽度：涨跌家数、涨跌停与样本股的均线/新高新低分布
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MarketBreadth {
+    /// 交易日 "YYYY-MM-DD"
+    pub date: String,
+    /// 参与统计的股票数（不含停牌）
+    pub total: usize,
+    pub advancers: usize,
+    pub decliners: usize,
+    pub flat: usize,
+    pub limit_up: usize,
+    pub limit_down: usize,
+    /// 样本股中收盘价站上 20 日均线的比例 %，本地日线不足时为 None
+    pub above_ma20_pct: Option<f64>,
+    pub above_ma60_pct: Option<f64>,
+    /// 样本股中创 52 周新高 / 新低的数量
+    pub new_high_52w: usize,
+    pub new_low_52w: usize,
+    /// 均线与新高新低统计的样本数（沪深300成分股中本地日线充足的部分）
+    pub sample_size: usize,
+    pub created_at: String,
+}
+
+/// 市场趋势状态
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum RegimeTrend {
+    #[serde(rename = "up")]
+    Up,
+    #[serde(rename = "down")]
+    Down,
+    #[serde(rename = "range")]
+    Range,
+}
+
+/// 市场波动状态（近 20 日波动率相对近一年水平）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum RegimeVolatility {
+    #[serde(rename = "high")]
+    High,
+    #[serde(rename = "normal")]
+    Normal,
+    #[serde(rename = "low")]
+    Low,
+}
+
+/// 市场风格（大盘价值 vs 小盘成长）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum RegimeStyle {
+    #[serde(rename = "large_value")]
+    LargeValue,
+    #[serde(rename = "small_growth")]
+    SmallGrowth,
+    #[serde(rename = "balanced")]
+    Balanced,
+}
+
+/// 市场状态：由指数均线、波动率、大小盘相对强弱、市场宽度与成交量综合判定，按交易日缓存
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketRegime {
+    /// 判定所用指数日线的最新日期 "YYYY-MM-DD"
+    pub date: String,
+    pub trend: RegimeTrend,
+    pub volatility: RegimeVolatility,
+    pub style: RegimeStyle,
+    /// 沪深300收盘点位与 20/60 日均线
+    pub index_close: f64,
+    pub ma20: f64,
+    pub ma60: f64,
+    /// 沪深300近 20 日涨幅 %
+    pub return_20d: f64,
+    /// 沪深300近 20 日与近一年的年化波动率 %
+    pub volatility_20d: f64,
+    pub volatility_1y: f64,
+    /// 上证50（大盘价值）与中证1000（小盘成长）近 20 日涨幅 %
+    pub large_cap_return_20d: f64,
+    pub small_cap_return_20d: f64,
+    /// 最近一次记录的沪深300成分股站上 MA20 比例 %，无宽度记录时为 None
+    pub above_ma20_pct: Option<f64>,
+    /// 沪深两市成交量相对 20 日均量的倍数，数据不足时为 None
+    pub volume_ratio: Option<f64>,
+    /// 一句话描述，如“震荡市 · 低波动 · 小盘成长占优”
+    pub summary: String,
+    pub created_at: String,
+}
+
+/// 个股相对强度（欧奈尔 RPS）：N 日涨幅在全市场中的百分位排名（0~100，越大越强）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StockRps {
+    pub code: String,
+    pub name: String,
+    /// 计算日期 "YYYY-MM-DD"
+    pub date: String,
+    pub close: f64,
+    pub pct_20d: Option<f64>,
+    pub pct_60d: Option<f64>,
+    /// 120 日涨幅由本地记录的收盘价推算，记录不足 120 个交易日时为 None
+    pub pct_120d: Option<f64>,
+    pub rps_20: Option<f64>,
+    pub rps_60: Option<f64>,
+    pub rps_120: Option<f64>,
+}
+
+/// 概念板块成分股（本地缓存，每日刷新）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BoardMember {
+    pub board_code: String,
+    pub board_name: String,
+    pub code: String,
+    pub name: String,
+    /// 首次出现在缓存中的日期 "YYYY-MM-DD"
+    pub first_seen: String,
+    /// 是否为增量刷新时新纳入的成分股（首次建立缓存的成分股为 false）
+    pub is_new: bool,
+}
+
+/// 个股在某个题材上的暴露：权重由题材热度（近期快讯提及次数、板块今日涨幅）决定，所有题材权重之和为 1
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ThemeWeight {
+    /// 板块代码，如 BK0968
+    pub board_code: String,
+    pub name: String,
+    pub weight: f64,
+    /// 板块今日涨跌幅 %
+    pub change_pct: f64,
+    /// 近期快讯中提及该题材的次数
+    pub mentions: u32,
+    /// 驱动该题材的快讯标题（最多 3 条，按时间倒序）
+    pub headlines: Vec<String>,
+    /// 近期新纳入该板块（新纳入成分股往往伴随题材炒作）
+    #[serde(default)]
+    pub newly_added: bool,
+}
+
+/// 个股题材暴露向量，themes 按权重降序
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ThemeExposure {
+    pub code: String,
+    pub themes: Vec<ThemeWeight>,
+    pub updated_at: String,
+}
+
+/// 个股某日资金流向（元）；分单净额为空表示该日只有全市场快照中的主力净流入
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FundFlowDay {
+    pub code: String,
+    pub date: String,
+    /// 主力净流入（超大单 + 大单）
+    pub main_net: f64,
+    pub super_large_net: Option<f64>,
+    pub large_net: Option<f64>,
+    pub medium_net: Option<f64>,
+    pub small_net: Option<f64>,
+    /// 主力净占比 %
+    pub main_pct: f64,
+    pub close: f64,
+    pub change_pct: f64,
+}
+
+/// 盘中累计资金净流入（元），按分钟
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IntradayFlowPoint {
+    /// "HH:MM"
+    pub time: String,
+    pub main_net: f64,
+    pub super_large_net: f64,
+    pub large_net: f64,
+    pub medium_net: f64,
+    pub small_net: f64,
+}
+
+/// 尾盘集合竞价（14:57~15:00）与科创板盘后固定价格交易（15:05~15:30）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ClosingAuction {
+    pub code: String,
+    pub date: String,
+    /// 14:57 竞价开始前的价格
+    pub pre_price: f64,
+    /// 收盘价（竞价撮合价）
+    pub close: f64,
+    /// 收盘价相对竞价前价格的偏离 %
+    pub deviation_pct: f64,
+    /// 尾盘竞价成交量（手）
+    pub auction_volume: f64,
+    /// 尾盘竞价成交额（元）
+    pub auction_amount: f64,
+    /// 尾盘竞价成交量占全天的百分比
+    pub volume_share: f64,
+    /// 盘后固定价格交易成交量（手），仅科创板/创业板有
+    pub after_hours_volume: Option<f64>,
+    pub after_hours_amount: Option<f64>,
+    /// "尾盘抢筹" / "尾盘砸盘"
+    pub flag: Option<String>,
+}
+
+/// 集合竞价异动：竞价跳空幅度与竞价成交量相对 5 日均量的放大程度
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuctionGap {
+    pub code: String,
+    pub name: String,
+    pub pre_close: f64,
+    /// 竞价匹配价
+    pub price: f64,
+    /// 竞价跳空幅度 %
+    pub gap_pct: f64,
+    /// 竞价成交量（手）
+    pub volume: f64,
+    /// 竞价成交额（元）
+    pub amount: f64,
+    /// 近 5 个交易日日均成交量（手）
+    pub avg_volume_5d: f64,
+    /// 竞价成交量占 5 日均量的百分比
+    pub volume_pct: f64,
+    pub turnover_rate: f64,
+    /// 截至昨日的连板天数（无本地日线时为 0）
+    pub streak_days: u32,
+    /// 异动强度 0~100
+    pub score: u32,
+    pub labels: Vec<String>,
+    /// 落入的策略区间名称
+    pub zones: Vec<String>,
+}
+
+/// 竞价异动扫描结果，按异动强度降序
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuctionScanResult {
+    /// 扫描时间 "YYYY-MM-DD HH:MM:SS"
+    pub time: String,
+    /// 扫描范围："market" 全市场 / "watchlist" 自选股
+    pub scope: String,
+    pub scanned: usize,
+    pub gaps: Vec<AuctionGap>,
+}
+
+/// 逐笔成交
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct TickPrint {
+    pub code: String,
+    /// "HH:MM:SS"
+    pub time: String,
+    pub price: f64,
+    /// 成交量（手）
+    pub volume: f64,
+    /// 成交额（元）
+    pub amount: f64,
+    /// 主动方向："buy" / "sell" / "neutral"
+    pub side: String,
+}
+
+/// 焦点盯盘（逐笔大单监控）运行状态
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FocusMonitorStatus {
+    pub running: bool,
+    pub code: Option<String>,
+    /// 本次监控已推送的大单
+    pub prints: Vec<TickPrint>,
+}
+
+/// 个股当日资金流向分单明细（元）：超大单 ≥ 100 万元或 ≥ 50 万股，大单 20~100 万元，中单 4~20 万元，小单 < 4 万元；
+/// 主力 = 超大单 + 大单
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FundFlowBreakdown {
+    pub code: String,
+    pub main_net: f64,
+    /// 主力净占比 %
+    pub main_pct: f64,
+    pub super_large_net: f64,
+    pub large_net: f64,
+    pub medium_net: f64,
+    pub small_net: f64,
+    /// 分单资金的典型形态，如"超大单流入、大单流出"
+    pub pattern: Option<String>,
+    /// 盘中累计净额走势（批量查询时为空）
+    #[serde(default)]
+    pub intraday: Vec<IntradayFlowPoint>,
+}
+
+/// 近 5/10/20 个交易日的累计资金净流入（元），记录不足对应天数时为 None
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CumulativeFlow {
+    pub code: String,
+    /// 可用的记录天数
+    pub days: usize,
+    pub main_5d: Option<f64>,
+    pub main_10d: Option<f64>,
+    pub main_20d: Option<f64>,
+    pub super_large_5d: Option<f64>,
+    pub super_large_10d: Option<f64>,
+    pub super_large_20d: Option<f64>,
+}
+
+/// 题材事件研究：快讯提及题材后该板块成分股的平均远期收益（%），超额收益相对沪深300
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ThemeEventStats {
+    pub board_code: String,
+    pub theme: String,
+    /// 提及该题材的交易日数（同日多条快讯计一次）
+    pub events: usize,
+    /// 事件 × 成分股样本数
+    pub samples: usize,
+    pub avg_return_1d: Option<f64>,
+    pub avg_return_5d: Option<f64>,
+    pub avg_return_10d: Option<f64>,
+    pub avg_excess_5d: Option<f64>,
+    pub avg_excess_10d: Option<f64>,
+    /// 5 日跑赢沪深300 的样本占比
+    pub win_rate_5d: Option<f64>,
+    /// 5 日超额收益均值的 t 统计量
+    pub t_stat_5d: Option<f64>,
+    /// 样本充足且 |t| ≥ 2，认为该题材快讯具有预测力
+    pub significant: bool,
+}
+
+/// 题材事件研究结果，themes 按 5 日平均超额收益降序
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ThemeStudyResult {
+    pub start: String,
+    pub end: String,
+    /// 参与研究的归档快讯条数
+    pub news_count: usize,
+    pub themes: Vec<ThemeEventStats>,
+}
+
+/// 个股某日的综合因子评分（0~100），各分项为对应因子在全市场的百分位均值，数据缺失时为 None
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FactorScore {
+    pub code: String,
+    pub name: String,
+    /// 记录日期 "YYYY-MM-DD"
+    pub date: String,
+    /// 综合评分：各分项按当日市场状态对应权重的加权均值
+    pub score: f64,
+    /// 质量：ROE、毛利率
+    pub quality: Option<f64>,
+    /// 成长：营收、净利润同比
+    pub growth: Option<f64>,
+    /// 估值：PE、PB（越低越高分）
+    pub value: Option<f64>,
+    /// 动量：20 日、60 日涨幅
+    pub momentum: Option<f64>,
+    /// 资金：主力净占比
+    pub flow: Option<f64>,
+}
+
+/// 某日收盘后的板块涨幅排名记录（rank 为同类板块内按涨跌幅的名次，1 为最强）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BoardRankRecord {
+    pub date: String,
+    pub code: String,
+    pub name: String,
+    /// "industry" / "concept"
+    pub kind: String,
+    pub change_pct: f64,
+    pub rank: u32,
+    /// 当日同类板块总数
+    pub total: u32,
+    pub main_net_inflow: f64,
+}
+
+/// 单个板块的排名动量：rank_change 为 N 个记录日前的名次减去最新名次（正数表示排名上升）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BoardRotationItem {
+    pub code: String,
+    pub name: String,
+    pub rank: u32,
+    pub rank_change_3d: Option<i32>,
+    pub rank_change_5d: Option<i32>,
+    pub rank_change_10d: Option<i32>,
+    /// 近 N 个记录日的累计涨跌幅 %，记录不足 N 日时为 None
+    pub pct_3d: Option<f64>,
+    pub pct_5d: Option<f64>,
+    pub pct_10d: Option<f64>,
+}
+
+/// 板块轮动：按 window 日排名变化排序的升温/降温板块
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BoardRotation {
+    pub kind: String,
+    /// 最新记录日
+    pub date: String,
+    pub window: usize,
+    /// 已记录的交易日数
+    pub history_days: usize,
+    pub gaining: Vec<BoardRotationItem>,
+    pub losing: Vec<BoardRotationItem>,
+}
+
+/// 指数（或行业板块）成分股及最新行情，stocks 按总市值降序
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexConstituents {
+    pub index_code: String,
+    /// 行业板块按代码查询时为空
+    pub index_name: String,
+    pub stocks: Vec<MarketStockSnapshot>,
+    pub updated_at: String,
+}
+
+/// 本地股票代码表条目（stock_symbols 表，由全市场扫描每周刷新）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StockSymbol {
+    pub code: String,
+    pub name: String,
+}
+
+/// NLP 选股查询记录（smart_search_history 表，同一条件只保留一条）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmartSearchQuery {
+    pub keyword: String,
+    /// 最近一次执行返回的股票数
+    pub result_count: usize,
+    pub run_count: u32,
+    pub is_favorite: bool,
+    pub last_run_at: String,
+    pub created_at: String,
+}
@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::ai::AIAnalysisResult;
+use crate::models::settings::AppSettings;
+use crate::models::watchlist::WatchlistStock;
+
+/// 同步快照：远端保存的完整同步数据，本地另存一份上次同步结果作为三方合并的基准
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncSnapshot {
+    pub version: u32,
+    /// 最后写入该快照的设备
+    pub device_id: String,
+    pub updated_at: String,
+    #[serde(default)]
+    pub watchlist: Vec<WatchlistStock>,
+    /// 可共享的设置（不含 API Key、设备标识与同步配置）
+    #[serde(default)]
+    pub settings: Option<AppSettings>,
+    /// 近期 AI 分析记录，按 id 合并
+    #[serde(default)]
+    pub analyses: Vec<AIAnalysisResult>,
+}
+
+/// 一次同步的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncResult {
+    pub synced_at: String,
+    /// 从远端合并到本地的变更数（自选股条目、设置、分析记录）
+    pub pulled: usize,
+    /// 是否向远端写入了新快照
+    pub pushed: bool,
+    /// 两端同时修改、按本地优先处理的冲突数
+    pub conflicts: usize,
+    pub message: String,
+}
+
+/// 同步状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncStatus {
+    pub device_id: String,
+    pub last_synced_at: Option<String>,
+    pub last_result: Option<SyncResult>,
+}
@@ -25,3 +25,72 @@ pub struct LossStock {
     pub reason: String,
     pub sector: String,
 }
+
+/// 策略指令历史（instruction_history 表）：当日 AI 指令 + 次日实际表现
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstructionRecord {
+    pub date: String,
+    pub code: String,
+    pub name: String,
+    /// buy / watch / eliminate
+    pub action: String,
+    pub label: String,
+    pub reason: String,
+    /// 生成指令时的策略得分与涨幅
+    pub score: u32,
+    pub current_pct: f64,
+    /// 次一交易日日期，未回填时为 None
+    pub next_date: Option<String>,
+    /// 次日收盘涨跌幅 %
+    pub next_day_pct: Option<f64>,
+    /// 次日最高价相对前收的涨幅 %
+    pub next_day_high_pct: Option<f64>,
+    pub created_at: String,
+}
+
+/// 按指令类型汇总的次日表现
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstructionOutcomeStats {
+    pub action: String,
+    pub total: usize,
+    /// 已回填次日表现的条数
+    pub evaluated: usize,
+    pub avg_next_day_pct: f64,
+    pub avg_next_day_high_pct: f64,
+    /// 次日收盘上涨的占比 %
+    pub win_rate: f64,
+}
+
+/// 策略模拟表现中单个入选日的样本
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PerformancePoint {
+    pub date: String,
+    /// 当日入选且持有期已满的股票数
+    pub samples: usize,
+    /// 当日入选股票的平均持有收益 %
+    pub avg_return: f64,
+}
+
+/// 策略近期模拟表现：入选后按固定持有天数模拟买卖的收益与胜率
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrategyPerformance {
+    /// technical_signal / ai_instruction / ai_pick
+    pub strategy: String,
+    pub name: String,
+    /// 统计区间（自然日）与持有交易日数
+    pub days: i64,
+    pub hold_days: usize,
+    /// 区间内入选次数
+    pub total: usize,
+    /// 持有期已满、计入统计的次数
+    pub evaluated: usize,
+    /// 每次入选的平均持有收益 %
+    pub avg_return: f64,
+    /// 持有收益为正的占比 %
+    pub win_rate: f64,
+    pub best_return: Option<f64>,
+    pub worst_return: Option<f64>,
+    /// 按入选日汇总，日期升序
+    pub points: Vec<PerformancePoint>,
+    pub updated_at: String,
+}
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 /// 自选股条目
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WatchlistStock {
     pub code: String,
     pub name: String,
@@ -13,6 +13,44 @@ pub struct WatchlistStock {
     pub created_at: String,
 }
 
+/// 自选股收盘快照（每日收盘后归档，用于盘后复盘与周/月对比）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchlistSnapshot {
+    pub code: String,
+    pub name: String,
+    pub date: String,
+    pub price: f64,
+    pub change_pct: f64,
+    pub turnover_rate: f64,
+    pub volume_ratio: f64,
+    pub amount: f64,
+    pub pe_ttm: f64,
+    pub pb: f64,
+    pub total_market_cap: f64,
+    pub main_net_inflow: f64,
+    pub main_net_pct: f64,
+    pub pct_5d: f64,
+    pub pct_20d: f64,
+    /// 均线排列："bullish" | "bearish" | "tangled"，本地无K线缓存时为空
+    #[serde(default)]
+    pub ma_alignment: String,
+    /// 技术面摘要（同 generate_summary）
+    #[serde(default)]
+    pub tech_summary: String,
+    #[serde(default)]
+    pub created_at: String,
+}
+
+/// 自选股批量导入结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchlistImportResult {
+    pub added: usize,
+    /// 已在自选股中或文件内重复的条目
+    pub skipped: usize,
+    /// 无法识别代码的行
+    pub invalid: usize,
+}
+
 /// 技术指标计算结果（Rust端 -> 前端）
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TechnicalIndicators {
@@ -35,6 +73,18 @@ pub struct TechnicalIndicators {
     pub boll_upper: Vec<Option<f64>>,
     pub boll_middle: Vec<Option<f64>>,
     pub boll_lower: Vec<Option<f64>>,
+    /// 唐奇安通道（海龟突破价位）：前 20 日最高价 / 最低价，不含当日
+    #[serde(default)]
+    pub donchian_upper: Vec<Option<f64>>,
+    #[serde(default)]
+    pub donchian_lower: Vec<Option<f64>>,
+    /// 肯特纳通道：EMA20 ± 2 × ATR10
+    #[serde(default)]
+    pub keltner_upper: Vec<Option<f64>>,
+    #[serde(default)]
+    pub keltner_middle: Vec<Option<f64>>,
+    #[serde(default)]
+    pub keltner_lower: Vec<Option<f64>>,
 }
 
 /// 技术信号
@@ -47,6 +97,116 @@ pub struct TechnicalSignal {
     pub date: String,
 }
 
+/// 信号预警记录（signals_history 表）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignalAlert {
+    pub code: String,
+    pub name: String,
+    pub signal_type: String,
+    pub direction: String,
+    pub description: String,
+    pub strength: u8,
+    /// 信号触发的K线日期
+    pub date: String,
+    #[serde(default)]
+    pub created_at: String,
+}
+
+/// 盘中异动事件（异动雷达）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnomalyEvent {
+    pub code: String,
+    pub name: String,
+    /// volume_spike / rapid_rise / rapid_fall / auction_surge / auction_plunge
+    pub anomaly_type: String,
+    pub description: String,
+    /// 排序分值：异动幅度相对触发阈值的倍数，越大越剧烈
+    pub score: f64,
+    pub price: f64,
+    pub change_pct: f64,
+    /// 异动发生的分钟 "YYYY-MM-DD HH:MM"
+    pub time: String,
+}
+
+/// 单只股票最新交易日的技术指标与近期信号（technical_daily 表，收盘后预计算）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TechnicalDaily {
+    pub code: String,
+    /// 指标对应的K线日期
+    pub date: String,
+    pub close: f64,
+    pub change_pct: f64,
+    pub ma5: Option<f64>,
+    pub ma10: Option<f64>,
+    pub ma20: Option<f64>,
+    pub ma60: Option<f64>,
+    pub macd_dif: Option<f64>,
+    pub macd_dea: Option<f64>,
+    pub macd_hist: Option<f64>,
+    pub kdj_k: Option<f64>,
+    pub kdj_d: Option<f64>,
+    pub kdj_j: Option<f64>,
+    pub rsi6: Option<f64>,
+    pub boll_upper: Option<f64>,
+    pub boll_middle: Option<f64>,
+    pub boll_lower: Option<f64>,
+    pub ma_alignment: String,
+    /// 最近若干交易日内检测到的全部信号
+    pub signals: Vec<TechnicalSignal>,
+    pub updated_at: String,
+}
+
+/// 形态选股命中结果（本地日线 + 技术信号扫描）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignalScreenHit {
+    pub code: String,
+    pub name: String,
+    pub signal_type: String,
+    pub direction: String,
+    pub description: String,
+    pub strength: u8,
+    /// 信号触发的K线日期
+    pub date: String,
+    /// 最新收盘价与涨跌幅（取自本地日线缓存）
+    pub close: f64,
+    pub change_pct: f64,
+}
+
+/// 批量诊断中单只股票的结果摘要
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchlistDiagnoseItem {
+    pub code: String,
+    pub name: String,
+    /// 从诊断正文提取的操作建议：买入/持有/观望/减仓/清仓，未识别时为空
+    pub action: String,
+    /// 排序分值（买入最高、清仓最低），诊断失败为 -1
+    pub score: i32,
+    /// 操作建议段落的首句
+    pub summary: String,
+    /// 是否直接复用了当日已有的诊断
+    pub cached: bool,
+    pub error: Option<String>,
+}
+
+/// 批量诊断进度事件（每完成一只推送一次）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchlistDiagnoseProgress {
+    pub completed: usize,
+    pub total: usize,
+    pub item: WatchlistDiagnoseItem,
+}
+
+/// 批量诊断完成后的排序汇总
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchlistDiagnoseDigest {
+    pub date: String,
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    /// 按操作建议从强到弱排序
+    pub items: Vec<WatchlistDiagnoseItem>,
+}
+
 /// 均线排列状态
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum MaAlignment {
@@ -84,6 +244,114 @@ pub struct StockTechnicalAnalysis {
     pub ma_alignment: MaAlignment,
     pub volume_price_relation: VolumePriceRelation,
     pub summary: String,
+    /// 波动率/Beta/最大回撤，仅日线周期计算
+    #[serde(default)]
+    pub risk: Option<RiskMetrics>,
+    /// 未回补缺口与历史回补统计
+    #[serde(default)]
+    pub gaps: GapAnalysis,
+    /// 成交量价格分布（筹码峰），K线不足时为 None
+    #[serde(default)]
+    pub volume_profile: Option<VolumeProfile>,
+}
+
+/// 成交量分布的单个价格区间
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumeBin {
+    pub price_low: f64,
+    pub price_high: f64,
+    pub volume: f64,
+}
+
+/// 成交量价格分布：按价格区间累计成交量，标出成交最密集价位（POC）、70% 价值区与高量节点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumeProfile {
+    /// 数据来源："day" 日线 / "m5" 5 分钟线
+    pub source: String,
+    /// 统计的交易日数
+    pub days: usize,
+    /// 价格从低到高排列
+    pub bins: Vec<VolumeBin>,
+    /// 成交量最大区间的中间价
+    pub poc_price: f64,
+    pub value_area_low: f64,
+    pub value_area_high: f64,
+    /// 高量节点（局部成交量峰值区间的中间价），按成交量降序
+    pub high_volume_nodes: Vec<f64>,
+}
+
+/// 跳空缺口：upper/lower 为尚未回补的价格区间（部分回补后会收窄）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceGap {
+    /// "up" 向上跳空 / "down" 向下跳空
+    pub direction: String,
+    /// 出现缺口的交易日
+    pub date: String,
+    pub upper: f64,
+    pub lower: f64,
+    /// 缺口原始幅度（相对前一日收盘价）%
+    pub size_pct: f64,
+    /// 出现后经过的交易日数
+    pub days_open: usize,
+}
+
+/// 缺口检测结果：未回补缺口（由近到远）与历史回补统计
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GapAnalysis {
+    pub open_gaps: Vec<PriceGap>,
+    /// 统计区间内出现的缺口总数
+    pub total: usize,
+    /// 已完全回补的缺口数
+    pub filled: usize,
+    /// 回补率 %
+    pub fill_rate_pct: Option<f64>,
+    /// 已回补缺口的回补天数中位数 / 平均数（交易日）
+    pub median_fill_days: Option<f64>,
+    pub avg_fill_days: Option<f64>,
+}
+
+/// 个股风险统计（基于日线收盘价）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RiskMetrics {
+    /// 20 日年化历史波动率 %
+    pub volatility_20d: Option<f64>,
+    /// 60 日年化历史波动率 %
+    pub volatility_60d: Option<f64>,
+    /// 近一年相对沪深300的 Beta，基准数据不可用时为 None
+    pub beta: Option<f64>,
+    /// 近一年最大回撤 %（正数）
+    pub max_drawdown_1y: Option<f64>,
+    /// 最大回撤的起止日期（峰值日、谷底日）
+    pub drawdown_peak_date: String,
+    pub drawdown_trough_date: String,
+}
+
+/// 季节性统计的单个分组（某个月份或某个星期几）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SeasonalityBucket {
+    /// 月份 1~12，或星期 1~5（周一为 1）
+    pub key: u32,
+    pub label: String,
+    /// 平均涨跌幅 %，无样本时为 None
+    pub avg_return_pct: Option<f64>,
+    /// 上涨概率 %
+    pub win_rate_pct: Option<f64>,
+    pub samples: usize,
+}
+
+/// 历史季节性：近 N 年各月份、各星期几的平均涨跌幅，附所属行业板块的月度统计
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct Seasonality {
+    pub code: String,
+    pub years: u32,
+    pub start_date: String,
+    pub end_date: String,
+    pub monthly: Vec<SeasonalityBucket>,
+    pub weekday: Vec<SeasonalityBucket>,
+    /// 所属东财行业板块，未找到（如指数）时为空
+    pub sector_code: String,
+    pub sector_name: String,
+    pub sector_monthly: Vec<SeasonalityBucket>,
 }
 
 /// K线单条数据
@@ -102,3 +370,12 @@ pub struct KlineItem {
     #[serde(default)]
     pub turnover_rate: f64,
 }
+
+/// 指数K线（上证指数、创业板指、中证500 等），name 为空表示非常用指数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexKline {
+    pub code: String,
+    pub name: String,
+    pub period: String,
+    pub klines: Vec<KlineItem>,
+}
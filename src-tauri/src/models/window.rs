@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+/// 迷你行情条
+pub const WINDOW_KIND_MINI_QUOTE: &str = "mini_quote";
+/// 独立 K 线窗口
+pub const WINDOW_KIND_KLINE: &str = "kline";
+/// 全局快捷键唤起的快速查股窗口
+pub const WINDOW_KIND_QUICK_SEARCH: &str = "quick_search";
+
+/// 主窗口之外打开的独立窗口，用于多显示器盯盘
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DetachedWindow {
+    pub label: String,
+    /// mini_quote / kline / quick_search
+    pub kind: String,
+    /// K 线窗口对应的股票代码
+    #[serde(default)]
+    pub code: Option<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+    /// 窗口置顶
+    #[serde(default)]
+    pub always_on_top: bool,
+}
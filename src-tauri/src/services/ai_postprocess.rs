@@ -0,0 +1,274 @@
+use crate::services::model_capability;
+
+/// DeepSeek DSML 等模型内部格式的起始标记（全角竖线）
+const DSML_MARKER: &str = "<\u{ff5c}";
+/// 部分模型泄漏到正文中的特殊 token
+const SPECIAL_TOKENS: [&str; 4] = ["<|im_end|>", "<|im_start|>", "<|endoftext|>", "<|eot_id|>"];
+const PICKS_OPEN: &str = "<PICKS>";
+const PICKS_CLOSE: &str = "</PICKS>";
+
+/// AI 输出后处理步骤
+pub trait PostProcessor: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn process(&self, content: &str) -> String;
+}
+
+/// 按顺序执行的后处理流水线，所有 AI 输出在展示落库与解析前统一经过这里
+pub struct Pipeline {
+    steps: Vec<Box<dyn PostProcessor>>,
+}
+
+impl Pipeline {
+    pub fn new() -> Self {
+        Self { steps: Vec::new() }
+    }
+
+    pub fn with(mut self, step: impl PostProcessor + 'static) -> Self {
+        self.steps.push(Box::new(step));
+        self
+    }
+
+    /// 默认流程：剥离思考链 → 清理模型残留标记 → 修复 <PICKS> 块 → 规范化 Markdown
+    pub fn standard() -> Self {
+        Self::new()
+            .with(StripReasoning)
+            .with(StripArtifacts)
+            .with(RepairPicks)
+            .with(NormalizeMarkdown)
+    }
+
+    pub fn run(&self, content: &str) -> String {
+        let mut out = content.to_string();
+        for step in &self.steps {
+            let next = step.process(&out);
+            if next.len() != out.len() {
+                log::debug!("[ai_postprocess] {} changed {} -> {} bytes", step.name(), out.len(), next.len());
+            }
+            out = next;
+        }
+        out
+    }
+}
+
+impl Default for Pipeline {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+/// 以默认流程处理 AI 输出
+pub fn clean(content: &str) -> String {
+    Pipeline::standard().run(content)
+}
+
+/// 流式输出中是否出现了模型内部格式标记（出现后不再向前端透传）
+pub fn contains_artifact_marker(chunk: &str) -> bool {
+    chunk.contains(DSML_MARKER) || chunk.contains("DSML")
+}
+
+/// 剥离 <think> 思考块
+pub struct StripReasoning;
+
+impl PostProcessor for StripReasoning {
+    fn name(&self) -> &'static str {
+        "strip_reasoning"
+    }
+
+    fn process(&self, content: &str) -> String {
+        model_capability::strip_reasoning(content)
+    }
+}
+
+/// 清理 DSML 块（从首个标记起到末尾均为工具调用残留）、提示词注入模式残留的 <tool_call> 块与特殊 token
+pub struct StripArtifacts;
+
+impl PostProcessor for StripArtifacts {
+    fn name(&self) -> &'static str {
+        "strip_artifacts"
+    }
+
+    fn process(&self, content: &str) -> String {
+        let mut out = match content.find(DSML_MARKER) {
+            Some(pos) => content[..pos].to_string(),
+            None => content.to_string(),
+        };
+        out = model_capability::extract_prompt_tool_calls(&out).0;
+        for token in SPECIAL_TOKENS {
+            out = out.replace(token, "");
+        }
+        out.trim_end().to_string()
+    }
+}
+
+/// 修复 <PICKS> 块：输出截断导致缺少闭合标签或 JSON 不完整时，保留已完整的条目并补齐；
+/// 无法修复时整块移除，避免残缺 JSON 出现在报告里
+pub struct RepairPicks;
+
+impl PostProcessor for RepairPicks {
+    fn name(&self) -> &'static str {
+        "repair_picks"
+    }
+
+    fn process(&self, content: &str) -> String {
+        let start = match content.find(PICKS_OPEN) {
+            Some(start) => start,
+            None => return content.to_string(),
+        };
+        let body_start = start + PICKS_OPEN.len();
+        let (body, tail) = match content[body_start..].find(PICKS_CLOSE) {
+            Some(end) => (&content[body_start..body_start + end], &content[body_start + end + PICKS_CLOSE.len()..]),
+            None => (&content[body_start..], ""),
+        };
+
+        let prefix = &content[..start];
+        match repair_json_array(body) {
+            Some(json) => format!("{}{}\n{}\n{}{}", prefix, PICKS_OPEN, json, PICKS_CLOSE, tail),
+            None => {
+                log::warn!("[ai_postprocess] drop unrepairable PICKS block len={}", body.len());
+                format!("{}{}", prefix.trim_end(), tail)
+            }
+        }
+    }
+}
+
+/// 规范化 Markdown：统一换行、去掉行尾空白、压缩多余空行、补齐未闭合的代码块
+pub struct NormalizeMarkdown;
+
+impl PostProcessor for NormalizeMarkdown {
+    fn name(&self) -> &'static str {
+        "normalize_markdown"
+    }
+
+    fn process(&self, content: &str) -> String {
+        let content = content.replace("\r\n", "\n");
+        let mut lines: Vec<&str> = Vec::new();
+        let mut blank_run = 0;
+        for line in content.lines().map(str::trim_end) {
+            if line.is_empty() {
+                blank_run += 1;
+                if blank_run > 1 {
+                    continue;
+                }
+            } else {
+                blank_run = 0;
+            }
+            lines.push(line);
+        }
+        let mut out = lines.join("\n").trim().to_string();
+        let fences = out.lines().filter(|l| l.trim_start().starts_with("```")).count();
+        if fences % 2 == 1 {
+            out.push_str("\n```");
+        }
+        out
+    }
+}
+
+/// 从文本中提取并修复 JSON 数组：去掉代码块标记与尾随逗号，截断时保留最后一个完整元素并补齐括号。
+/// 找不到数组或没有任何完整元素时返回 None
+pub fn repair_json_array(text: &str) -> Option<String> {
+    let start = text.find('[')?;
+    let candidate = &text[start..];
+
+    // 逐字符扫描（识别字符串与转义），记录最外层数组闭合位置或最后一个完整元素的结束位置
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut last_complete = None;
+    let mut closed_at = None;
+    for (i, c) in candidate.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '[' | '{' => depth += 1,
+            ']' | '}' => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    closed_at = Some(i);
+                    break;
+                }
+                if depth == 1 {
+                    last_complete = Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let json = match (closed_at, last_complete) {
+        (Some(end), _) => candidate[..=end].to_string(),
+        (None, Some(end)) => format!("{}]", &candidate[..=end]),
+        (None, None) => return None,
+    };
+    let json = strip_trailing_commas(&json);
+    serde_json::from_str::<serde_json::Value>(&json)
+        .ok()
+        .filter(|v| v.is_array())
+        .map(|_| json)
+}
+
+/// 去掉 `,]` / `,}` 形式的尾随逗号（字符串内部不处理）
+fn strip_trailing_commas(json: &str) -> String {
+    let mut out = String::with_capacity(json.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    let chars: Vec<char> = json.chars().collect();
+    for (i, &c) in chars.iter().enumerate() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+        } else if c == '"' {
+            in_string = true;
+        } else if c == ',' {
+            let next = chars[i + 1..].iter().find(|c| !c.is_whitespace());
+            if matches!(next, Some(']') | Some('}')) {
+                continue;
+            }
+        }
+        out.push(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncated_picks_are_repaired() {
+        let content = "## 推荐\n<PICKS>\n[{\"code\":\"600519\",\"name\":\"贵州茅台\"},{\"code\":\"000858\",\"name\":\"五粮";
+        let out = clean(content);
+        assert_eq!(out, "## 推荐\n<PICKS>\n[{\"code\":\"600519\",\"name\":\"贵州茅台\"}]\n</PICKS>");
+
+        let unrepairable = clean("报告正文\n<PICKS>\n[{\"code\":\"6005");
+        assert_eq!(unrepairable, "报告正文");
+    }
+
+    #[test]
+    fn test_garbled_artifacts_are_stripped() {
+        let content = "结论：持有\r\n\r\n\r\n\r\n<|im_end|>风险提示   \n<\u{ff5c}DSML\u{ff5c}function_calls>\n<\u{ff5c}invoke name=\"x\">";
+        assert_eq!(clean(content), "结论：持有\n\n风险提示");
+
+        let fenced = clean("<think>先看量能</think>```json\n[1,2]");
+        assert_eq!(fenced, "```json\n[1,2]\n```");
+    }
+
+    #[test]
+    fn test_repair_json_array() {
+        assert_eq!(repair_json_array("```json\n[{\"a\":\"x]\"},]\n```").as_deref(), Some("[{\"a\":\"x]\"}]"));
+        assert_eq!(repair_json_array("[{\"a\":1},{\"b\":").as_deref(), Some("[{\"a\":1}]"));
+        assert_eq!(repair_json_array("没有数组"), None);
+        assert_eq!(repair_json_array("[{\"a\":"), None);
+    }
+}
@@ -0,0 +1,255 @@
+use anyhow::{anyhow, Result};
+use tauri::AppHandle;
+
+use crate::db::database::Database;
+use crate::models::agent_session::AgentSession;
+use crate::models::ai::{AIAnalysisResult, AIConfig, AIStreamEvent, ChatMessage, RoutedIntent, RoutedStock, RouterAnswer};
+use crate::services::ai_service::AIService;
+use crate::services::model_capability;
+use crate::services::stock_data::format_stock_code;
+use crate::services::stock_tools::{self, ToolContext};
+use crate::services::stream_forwarder;
+use crate::services::symbol_table;
+use crate::services::watchlist_diagnose::DIAGNOSE_QUESTION;
+
+/// 前端监听的流式回答事件名
+pub const ROUTER_STREAM_EVENT: &str = "ai-router-stream";
+
+pub const INTENT_DIAGNOSE: &str = "diagnose";
+pub const INTENT_SCREEN: &str = "screen";
+pub const INTENT_NEWS: &str = "news";
+pub const INTENT_COMPARE: &str = "compare";
+pub const INTENT_MACRO: &str = "macro";
+const INTENTS: [&str; 5] = [INTENT_DIAGNOSE, INTENT_SCREEN, INTENT_NEWS, INTENT_COMPARE, INTENT_MACRO];
+
+/// 对比最多取前 5 只，避免工具调用轮数耗尽
+const MAX_COMPARE_STOCKS: usize = 5;
+
+fn intent_label(intent: &str) -> &'static str {
+    match intent {
+        INTENT_DIAGNOSE => "个股诊断",
+        INTENT_SCREEN => "选股",
+        INTENT_NEWS => "资讯",
+        INTENT_COMPARE => "个股对比",
+        _ => "大盘研判",
+    }
+}
+
+/// 输入中独立出现的 6 位数字视为股票代码
+fn extract_codes(input: &str) -> Vec<RoutedStock> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut stocks: Vec<RoutedStock> = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if !chars[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < chars.len() && chars[i].is_ascii_digit() {
+            i += 1;
+        }
+        if i - start == 6 {
+            let code = format_stock_code(&chars[start..i].iter().collect::<String>());
+            if !stocks.iter().any(|s| s.code == code) {
+                stocks.push(RoutedStock { code, name: String::new() });
+            }
+        }
+    }
+    stocks
+}
+
+/// 模型意图识别失败时按关键词兜底
+pub fn fallback_intent(input: &str) -> RoutedIntent {
+    let stocks = extract_codes(input);
+    let has = |words: &[&str]| words.iter().any(|w| input.contains(w));
+    let intent = if has(&["对比", "比较", "哪个好", "哪只好"]) {
+        INTENT_COMPARE
+    } else if has(&["新闻", "消息", "公告", "资讯", "研报", "利好", "利空"]) {
+        INTENT_NEWS
+    } else if has(&["大盘", "宏观", "指数", "情绪", "经济", "外围", "政策"]) {
+        INTENT_MACRO
+    } else if !stocks.is_empty() {
+        INTENT_DIAGNOSE
+    } else {
+        INTENT_SCREEN
+    };
+    normalize(RoutedIntent { intent: intent.to_string(), stocks, keyword: String::new() })
+}
+
+/// 按识别出的股票数量修正意图：诊断多只改为对比，对比只有一只改为诊断，
+/// 缺少股票时有关键词按选股处理，否则按大盘研判处理
+pub fn normalize(mut intent: RoutedIntent) -> RoutedIntent {
+    let fallback = if intent.keyword.trim().is_empty() { INTENT_MACRO } else { INTENT_SCREEN };
+    let fixed = match (intent.intent.as_str(), intent.stocks.len()) {
+        (INTENT_DIAGNOSE | INTENT_COMPARE, 0) => fallback,
+        (INTENT_DIAGNOSE, n) if n > 1 => INTENT_COMPARE,
+        (INTENT_COMPARE, 1) => INTENT_DIAGNOSE,
+        (other, _) => other,
+    };
+    intent.intent = fixed.to_string();
+    intent.stocks.truncate(MAX_COMPARE_STOCKS);
+    intent
+}
+
+/// 解析模型输出的意图 JSON，无法解析或意图不在支持范围内时按关键词兜底
+pub fn parse_intent(reply: &str, input: &str) -> RoutedIntent {
+    let json = match (reply.find('{'), reply.rfind('}')) {
+        (Some(start), Some(end)) if start < end => &reply[start..=end],
+        _ => return fallback_intent(input),
+    };
+    match serde_json::from_str::<RoutedIntent>(json) {
+        Ok(mut intent) if INTENTS.contains(&intent.intent.as_str()) => {
+            intent.stocks.retain(|s| !s.code.trim().is_empty() || !s.name.trim().is_empty());
+            normalize(intent)
+        }
+        _ => fallback_intent(input),
+    }
+}
+
+/// 按本地代码表校验识别出的股票，丢弃无法确定代码的条目
+fn resolve_stocks(db: &Database, intent: RoutedIntent) -> RoutedIntent {
+    let mut stocks: Vec<RoutedStock> = Vec::new();
+    for s in &intent.stocks {
+        let (code, name) = symbol_table::resolve_input(db, s.code.trim(), s.name.trim());
+        if code.trim().is_empty() {
+            log::warn!("[ai_router] cannot resolve stock name={}", s.name);
+            continue;
+        }
+        let code = format_stock_code(&code);
+        if !stocks.iter().any(|x| x.code == code) {
+            stocks.push(RoutedStock { code, name });
+        }
+    }
+    normalize(RoutedIntent { stocks, ..intent })
+}
+
+/// 非诊断意图的系统提示词（使用选股工具集，覆盖行情、板块、资讯与宏观数据）
+fn system_prompt(intent: &RoutedIntent) -> String {
+    let stocks = intent.stocks.iter().map(|s| format!("{}({})", s.name, s.code)).collect::<Vec<_>>().join("、");
+    let task = match intent.intent.as_str() {
+        INTENT_SCREEN => format!(
+            "用户希望按板块或条件找股{}。先用 search_concept_boards、get_board_rotation 或 search_stocks_by_condition 找到候选，\
+            再用 batch_get_stock_quotes、batch_get_fund_flow 比较强弱，给出 3-5 只值得关注的股票及理由，并说明板块近期走势与风险。",
+            if intent.keyword.is_empty() { String::new() } else { format!("（关键词：{}）", intent.keyword) }
+        ),
+        INTENT_NEWS => format!(
+            "用户想了解资讯{}。涉及个股时调用 search_stock_news、get_stock_notices、get_industry_report，\
+            否则调用 get_market_news、get_financial_calendar。按重要性归纳要点，并说明可能的市场影响。",
+            if stocks.is_empty() { String::new() } else { format!("，相关股票：{}", stocks) }
+        ),
+        INTENT_COMPARE => format!(
+            "请对比以下股票：{}。分别调用 get_stock_quote、get_technical_indicators、get_fund_flow，需要时调用 get_peer_comparison，\
+            用表格对比估值、技术面与资金面，最后给出相对优劣结论。",
+            stocks
+        ),
+        _ => "用户关心大盘与宏观环境。调用 get_global_indexes、get_market_breadth、get_board_rotation、get_economic_data、get_market_news，\
+            研判市场情绪、主线板块与外部影响，给出仓位建议。"
+            .to_string(),
+    };
+    format!(
+        "你是一位专业的A股投研助手，只能通过提供的工具获取数据，禁止编造。今天是 {}。\n\n{}\n\n请用简洁专业的中文回答，引用具体数据支撑观点。",
+        chrono::Local::now().format("%Y-%m-%d"),
+        task
+    )
+}
+
+/// 自然语言入口：识别意图与参数后分派给对应的 Agent，流式内容推送到 ai-router-stream
+pub async fn route(db: &Database, app: &AppHandle, config: &AIConfig, tool_ctx: &ToolContext, input: &str) -> Result<RouterAnswer> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err(anyhow!("请输入要查询的内容"));
+    }
+    let (reply, usage) = AIService::classify_intent(config, input).await?;
+    if let Some(usage) = usage {
+        let _ = db.record_token_usage(&config.model_name, usage.prompt_tokens, usage.completion_tokens);
+    }
+    let intent = resolve_stocks(db, parse_intent(&reply, input));
+    log::info!("[ai_router] route intent={} stocks={} keyword={}", intent.intent, intent.stocks.len(), intent.keyword);
+
+    let (tx, rx) = tokio::sync::mpsc::channel::<AIStreamEvent>(100);
+    let forwarder = stream_forwarder::spawn(app, ROUTER_STREAM_EVENT.to_string(), rx);
+    let target = intent.stocks.iter().map(|s| s.name.as_str()).collect::<Vec<_>>().join("、");
+    let label = format!("{} {}", intent_label(&intent.intent), target);
+    let _ = tx.send(model_capability::mode_event(label.trim_end())).await;
+
+    let result = if intent.intent == INTENT_DIAGNOSE {
+        let stock = &intent.stocks[0];
+        let mut session = AgentSession::new("diagnose", &stock.code, &config.model_name);
+        let result = AIService::diagnose_stock_with_tools(config, &stock.code, &stock.name, tool_ctx, tx.clone(), &mut session).await;
+        session.finish(&result);
+        let _ = db.save_agent_session(&session);
+        if let Ok((content, _)) = &result {
+            let analysis = AIAnalysisResult {
+                id: uuid::Uuid::new_v4().to_string(),
+                code: stock.code.clone(),
+                name: stock.name.clone(),
+                model_name: config.model_name.clone(),
+                question: DIAGNOSE_QUESTION.to_string(),
+                content: content.clone(),
+                created_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            };
+            let _ = db.save_ai_analysis(&analysis);
+        }
+        result
+    } else {
+        let mut session = AgentSession::new("router", input, &config.model_name);
+        let messages = vec![ChatMessage::system(&system_prompt(&intent)), ChatMessage::user(input)];
+        let result = AIService::run_tool_agent(config, stock_tools::get_pick_tool_definitions(), messages, tool_ctx, tx.clone(), &mut session).await;
+        session.finish(&result);
+        let _ = db.save_agent_session(&session);
+        result
+    };
+    if let Err(e) = &result {
+        let _ = tx.send(AIStreamEvent {
+            event_type: "error".to_string(),
+            content: Some(format!("{}失败: {}", intent_label(&intent.intent), e)),
+            done: true,
+            usage: None,
+            tool_name: None,
+        }).await;
+    }
+    drop(tx);
+    let _ = forwarder.await;
+
+    let (content, usage) = result?;
+    if let Some(usage) = usage {
+        let _ = db.record_token_usage(&config.model_name, usage.prompt_tokens, usage.completion_tokens);
+    }
+    log::info!("[ai_router] route done intent={} len={}", intent.intent, content.len());
+    Ok(RouterAnswer { intent, content })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stock(code: &str, name: &str) -> RoutedStock {
+        RoutedStock { code: code.to_string(), name: name.to_string() }
+    }
+
+    #[test]
+    fn test_parse_intent() {
+        let intent = parse_intent(r#"```json {"intent":"screen","stocks":[],"keyword":"军工"} ```"#, "帮我看看最近军工板块");
+        assert_eq!(intent, RoutedIntent { intent: "screen".into(), stocks: vec![], keyword: "军工".into() });
+
+        let intent = parse_intent(
+            r#"{"intent":"diagnose","stocks":[{"code":"600519","name":"贵州茅台"},{"code":"","name":"五粮液"}]}"#,
+            "茅台和五粮液",
+        );
+        assert_eq!(intent.intent, INTENT_COMPARE);
+        assert_eq!(intent.stocks[1], stock("", "五粮液"));
+
+        let intent = parse_intent(r#"{"intent":"compare","stocks":[{"name":"宁德时代"}]}"#, "宁德时代");
+        assert_eq!(intent.intent, INTENT_DIAGNOSE);
+
+        let intent = parse_intent("我不确定", "600519 最近有什么公告");
+        assert_eq!(intent.intent, INTENT_NEWS);
+        assert_eq!(intent.stocks, vec![stock("sh600519", "")]);
+
+        assert_eq!(parse_intent(r#"{"intent":"chat"}"#, "000001 怎么样").intent, INTENT_DIAGNOSE);
+        assert_eq!(fallback_intent("今天大盘情绪如何").intent, INTENT_MACRO);
+        assert_eq!(fallback_intent("帮我看看最近军工板块").intent, INTENT_SCREEN);
+        assert_eq!(extract_codes("比较 000001 和 1234567"), vec![stock("sz000001", "")]);
+    }
+}
@@ -1,13 +1,25 @@
 use anyhow::{Result, anyhow};
-use futures::StreamExt;
+use futures::{Stream, StreamExt};
+use std::pin::Pin;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use crate::models::ai::*;
+use crate::models::agent_session::{AgentSession, PickCheckpoint};
+use crate::models::settings::PickPreferences;
+use crate::services::ai_postprocess;
+use crate::services::model_capability;
+use crate::services::market_regime;
+use crate::services::pick_constraints;
+use crate::services::stock_data::format_stock_code;
 use crate::services::stock_tools;
-use crate::utils::http::build_ai_client;
+use crate::utils::http::{build_ai_client, SendLogged};
 use crate::utils::retry::retry_with_backoff;
+use crate::utils::sse::SseStream;
+use crate::utils::units;
 
 const MAX_TOOL_ROUNDS: usize = 8;
+/// 选股最终报告流式生成期间保存断点的间隔
+const STREAM_CHECKPOINT_INTERVAL: std::time::Duration = std::time::Duration::from_secs(3);
 
 /// 内置默认选股策略提示词（用户可自定义替换此部分）
 /// 占位符 {today} 对应当前日期时间
@@ -27,15 +39,19 @@ const DEFAULT_PICK_STRATEGY_PROMPT: &str = "\
 - get_economic_data：GDP/CPI/PPI/PMI 宏观数据\n\
 - get_global_indexes：全球主要指数行情\n\
 - get_financial_calendar：近期财经事件日历\n\
+- get_market_breadth：市场宽度（涨跌家数、涨跌停、均线站上比例、52周新高新低），判断市场情绪\n\
 \n\
 **大盘/板块类**（帮你判断方向和识别风险）：\n\
 - get_kline_data：K线数据（可用于指数或个股）\n\
 - get_technical_indicators：技术指标（MA/MACD/KDJ/RSI/BOLL）\n\
+- get_market_heatmap：全部行业/概念板块涨跌榜与主力净流入榜（一次调用看清板块全貌）\n\
+- get_board_rotation：板块轮动（近3/5/10日排名升温/降温的板块），识别主线切换\n\
 - search_concept_boards：按关键词搜索概念板块\n\
 \n\
 **选股类**（帮你筛选标的）：\n\
 - search_stocks_by_condition：自然语言条件选股（如\"新能源,涨幅大于0%,涨幅小于5%,市盈率小于30\"）\n\
 - batch_get_stock_quotes：批量获取个股行情快照\n\
+- get_index_constituents：指数成分股（沪深300/上证50/中证500/行业板块），用于限定选股范围\n\
 - get_stock_quote：单只个股详细行情\n\
 \n\
 **资金面工具**（帮你验证候选股的资金动向）：\n\
@@ -46,6 +62,11 @@ const DEFAULT_PICK_STRATEGY_PROMPT: &str = "\
 - search_stock_news：个股/关键词新闻\n\
 - get_stock_notices：上市公司公告\n\
 - get_industry_report：机构研报\n\
+- get_stock_profile：公司简介与主营构成（确认公司实际业务，不要凭印象描述）\n\
+- get_earnings_forecast：机构一致预期（今明两年EPS/预测PE/评级分布）\n\
+- get_peer_comparison：同行业可比公司对比表（一次调用即可，不要逐只查询同行）\n\
+- get_seasonality：历史季节性（各月份/星期几的平均涨跌幅与上涨概率，附行业板块月度统计）\n\
+- get_stock_theme_exposure：题材暴露（所属题材按快讯热度与板块涨幅加权，附驱动快讯），确认候选股的主线归属\n\
 \n\
 # 决策原则\n\
 \n\
@@ -138,31 +159,130 @@ valuation：必填，简述估值水平（如\"PE 15.2 低估\"或\"PE 45 偏高
 \n\
 **重要**：只能通过提供的工具获取数据，禁止编造。";
 
+/// <PICKS> 解析失败时补救请求的系统提示词
+const PICKS_REASK_PROMPT: &str = "\
+你是格式整理助手。下面是一份 A 股选股报告，其中的推荐股票列表格式损坏或被截断。\n\
+请只根据报告中已经出现的推荐股票，输出一个 JSON 数组，不要输出任何其他文字、标签或代码块标记。\n\
+每个元素包含字段：code（股票代码，如 sh600519）、name、reason、rating（strong_buy/buy/watch）、\
+sector、highlights（字符串数组）、fund_flow、valuation。报告中没有的信息填空字符串，禁止编造新的股票。";
+
+/// 自然语言入口的意图识别提示词
+const ROUTER_CLASSIFY_PROMPT: &str = "\
+你是 A 股助手的指令路由器。判断用户输入的意图，只输出一个 JSON 对象，不要输出其他文字。\n\
+字段：intent（diagnose=诊断个股、screen=条件选股或板块找股、news=新闻资讯与公告、compare=多只股票对比、macro=大盘宏观与市场情绪）、\
+stocks（输入中提到的股票数组，每项含 code 与 name，代码不确定时 code 填空字符串）、\
+keyword（板块、概念或主题关键词，没有填空字符串）。\n\
+示例：{\"intent\":\"screen\",\"stocks\":[],\"keyword\":\"军工\"}";
+
+/// 识图分析的系统提示词
+const VISION_SYSTEM_PROMPT: &str = "\
+你是一位资深 A 股技术分析师，擅长解读 K 线图、分时图与指标截图。\n\
+先描述图中可辨认的信息（标的、周期、价格区间、形态、均线与指标、成交量），再结合用户提供的真实行情数据交叉核对：\
+图中价格或走势与真实数据明显不符时必须指出（可能是其他股票、旧图或经过处理的图片）。\n\
+最后给出形态研判、支撑压力位与操作建议，并提示风险。图中看不清的内容如实说明，禁止编造。";
+
+/// 盯盘助手定时快检的系统提示词
+const CHECK_IN_SYSTEM_PROMPT: &str = "\
+你是盯盘助手，按用户设定的时间对单只 A 股做一次简短快检。只根据提供的真实数据回答用户的问题，\
+结论先行，给出明确操作（持有/减仓/离场/加仓/观望）与关键价位，全文不超过 300 字，每个要点单独一行，不要使用表格。\n\
+提供了上一次快检结论时，第一行用“较上次：”概括变化（结论是否改变、关键数据如何变化），没有变化也要说明。";
+
+/// 最终回答的字节流：真实的 SSE 响应或由非流式响应改写的等价字节
+type ChatByteStream = Pin<Box<dyn Stream<Item = reqwest::Result<Vec<u8>>> + Send>>;
+
 pub struct AIService;
 
 impl AIService {
-    /// 测试 AI 配置是否可用：发送一个简单请求验证 API 连通性
-    pub async fn test_ai_connection(config: &AIConfig) -> Result<String> {
+    /// 测试 AI 配置是否可用：先发送最小对话请求验证连通性并计时，再发送带工具定义的探测请求判断是否支持 Function Calling
+    pub async fn test_ai_connection(config: &AIConfig) -> Result<AIConnectionTest> {
         log::info!("[ai_service] test_ai_connection model={} url={}", config.model_name, config.base_url);
         let client = build_ai_client(config.timeout_secs.min(30))?; // 测试时最多等30秒
         let url = format!("{}/chat/completions", config.base_url.trim_end_matches('/'));
 
-        let req = ChatCompletionRequest {
+        let mut req = ChatCompletionRequest {
             model: config.model_name.clone(),
             messages: vec![ChatMessage::user("请回复'连接成功'四个字，不要输出其他内容。")],
             max_tokens: Some(20),
+            max_completion_tokens: None,
             temperature: Some(0.0),
             stream: Some(false),
             tools: None,
             tool_choice: None,
         };
 
+        model_capability::adapt_request(&mut req);
+        let started = std::time::Instant::now();
+        let response = Self::send_test_request(&client, &url, config, &req).await?;
+        let latency_ms = started.elapsed().as_millis() as u64;
+
+        let reply = response.choices.first()
+            .and_then(|c| c.message.as_ref())
+            .and_then(|m| m.content.clone())
+            .unwrap_or_default();
+        let model = response.id.filter(|id| !id.is_empty()).unwrap_or_else(|| config.model_name.clone());
+
+        // 工具调用探测：基础连通已验证，探测失败只记录原因，不视为配置不可用
+        let mut probe = ChatCompletionRequest {
+            model: config.model_name.clone(),
+            messages: vec![ChatMessage::user("现在几点了？请调用工具获取。")],
+            max_tokens: Some(100),
+            max_completion_tokens: None,
+            temperature: Some(0.0),
+            stream: Some(false),
+            tools: Some(vec![serde_json::json!({
+                "type": "function",
+                "function": {
+                    "name": "get_current_time",
+                    "description": "获取当前时间",
+                    "parameters": { "type": "object", "properties": {} }
+                }
+            })]),
+            tool_choice: Some("auto".to_string()),
+        };
+        model_capability::adapt_request(&mut probe);
+        let (supports_tools, tool_probe_message) = match Self::send_test_request(&client, &url, config, &probe).await {
+            Ok(resp) => {
+                let called = resp.choices.first()
+                    .and_then(|c| c.message.as_ref())
+                    .and_then(|m| m.tool_calls.as_ref())
+                    .is_some_and(|calls| !calls.is_empty());
+                // 仅在探测请求本身成功时更新能力缓存，网络错误不作为不支持的依据
+                model_capability::record(config, ModelCapabilities {
+                    supports_tools: called,
+                    ..model_capability::get(config)
+                });
+                if called {
+                    (true, None)
+                } else {
+                    (false, Some("模型未返回 tool_calls，可能不支持 Function Calling，AI 选股/诊断等 Agent 功能将以提示词注入模式运行".to_string()))
+                }
+            }
+            Err(e) => (false, Some(format!("工具调用探测失败: {}", e))),
+        };
+
+        log::info!("[ai_service] test_ai_connection ok latency={}ms supports_tools={}", latency_ms, supports_tools);
+        Ok(AIConnectionTest {
+            model,
+            reply: ai_postprocess::clean(&reply),
+            latency_ms,
+            supports_tools,
+            tool_probe_message,
+        })
+    }
+
+    /// 发送一次非流式测试请求，将常见 HTTP 错误转换为可读提示
+    async fn send_test_request(
+        client: &reqwest::Client,
+        url: &str,
+        config: &AIConfig,
+        req: &ChatCompletionRequest,
+    ) -> Result<ChatCompletionResponse> {
         let resp = client
-            .post(&url)
+            .post(url)
             .header("Authorization", format!("Bearer {}", config.api_key))
             .header("Content-Type", "application/json")
-            .json(&req)
-            .send()
+            .json(req)
+            .send_logged()
             .await
             .map_err(|e| {
                 if e.is_timeout() {
@@ -184,22 +304,199 @@ impl AIService {
             return Err(anyhow!("API 地址不存在（404），请检查 base_url 是否正确"));
         }
         if !status.is_success() {
-            return Err(anyhow!("API 返回错误 ({}): {}", status.as_u16(), &body[..200.min(body.len())]));
+            return Err(anyhow!("API 返回错误 ({}): {}", status.as_u16(), body.chars().take(200).collect::<String>()));
         }
 
-        let response: ChatCompletionResponse = serde_json::from_str(&body)
-            .map_err(|e| anyhow!("响应解析失败: {}，可能不是标准 OpenAI 兼容 API", e))?;
+        serde_json::from_str(&body)
+            .map_err(|e| anyhow!("响应解析失败: {}，可能不是标准 OpenAI 兼容 API", e))
+    }
 
-        let reply = response.choices.first()
-            .and_then(|c| c.message.as_ref())
-            .and_then(|m| m.content.clone())
-            .unwrap_or_default();
+    /// 发送一次非流式请求并返回响应体；非 2xx 时错误信息包含状态码与响应体，便于重试与降级判断
+    async fn post_chat(
+        client: &reqwest::Client,
+        url: &str,
+        config: &AIConfig,
+        req: &ChatCompletionRequest,
+        retries: u32,
+    ) -> Result<String> {
+        retry_with_backoff(retries, || async {
+            let resp = client
+                .post(url)
+                .header("Authorization", format!("Bearer {}", config.api_key))
+                .header("Content-Type", "application/json")
+                .json(req)
+                .send_logged()
+                .await
+                .map_err(|e| anyhow!("AI API request failed: {}", e))?;
+
+            if !resp.status().is_success() {
+                let status = resp.status();
+                let body = resp.text().await.unwrap_or_default();
+                return Err(anyhow!("AI API error ({}): {}", status.as_u16(), body));
+            }
+
+            resp.text().await.map_err(|e| anyhow!("Read response body failed: {}", e))
+        }).await
+    }
+
+    fn parse_completion(body: &str) -> Result<ChatCompletionResponse> {
+        serde_json::from_str(body).map_err(|e| {
+            anyhow!("AI response parse error: {} body: {}", e, body.chars().take(500).collect::<String>())
+        })
+    }
+
+    /// 工具调用轮次的非流式请求。模型不支持 Function Calling 时降级为提示词注入模式，
+    /// 并把回复中的工具调用解析回 tool_calls，调用方按原生模式统一处理。
+    /// announce 为 true 时（首轮）推送一次降级模式事件
+    async fn request_tool_round(
+        client: &reqwest::Client,
+        url: &str,
+        config: &AIConfig,
+        mut req: ChatCompletionRequest,
+        retries: u32,
+        announce: bool,
+        sender: &tokio::sync::mpsc::Sender<AIStreamEvent>,
+    ) -> Result<ChatCompletionResponse> {
+        model_capability::adapt_request(&mut req);
+        let mut announce = announce;
+        if model_capability::get(config).supports_tools {
+            match Self::post_chat(client, url, config, &req, retries).await {
+                Ok(body) => return Self::parse_completion(&body),
+                Err(e) if model_capability::is_tools_rejection(&e.to_string()) => {
+                    log::warn!("[ai_service] model {} rejected tools, fallback to prompt mode: {}", config.model_name, e);
+                    model_capability::mark_tools_unsupported(config);
+                    announce = true;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        if announce {
+            let _ = sender.send(model_capability::mode_event("当前模型不支持工具调用，已切换为提示词注入模式")).await;
+        }
+
+        let tools = req.tools.take().unwrap_or_default();
+        req.messages = model_capability::to_prompt_messages(&req.messages, &tools);
+        req.tool_choice = None;
+        let body = Self::post_chat(client, url, config, &req, retries).await?;
+        let mut response = Self::parse_completion(&body)?;
+
+        if let Some(choice) = response.choices.first_mut() {
+            if let Some(msg) = choice.message.as_mut() {
+                let (text, calls) = model_capability::extract_prompt_tool_calls(msg.content.as_deref().unwrap_or(""));
+                if !calls.is_empty() {
+                    log::info!("[ai_service] prompt mode parsed {} tool calls", calls.len());
+                    msg.content = Some(text);
+                    msg.tool_calls = Some(calls);
+                    choice.finish_reason = Some("tool_calls".to_string());
+                }
+            }
+        }
+        Ok(response)
+    }
+
+    /// 打开最终回答的流式响应。模型不支持流式输出时降级为非流式请求，
+    /// 并把完整回复包装成等价的 SSE 事件，调用方的流式解析逻辑无需改动
+    async fn open_final_stream(
+        client: &reqwest::Client,
+        url: &str,
+        config: &AIConfig,
+        mut req: ChatCompletionRequest,
+        sender: &tokio::sync::mpsc::Sender<AIStreamEvent>,
+    ) -> Result<SseStream<ChatByteStream>> {
+        model_capability::adapt_request(&mut req);
+        let caps = model_capability::get(config);
+        if !caps.supports_tools {
+            // 历史中的 tool_calls / tool 消息改写为纯文本，避免不支持工具的接口拒绝请求
+            req.messages = model_capability::to_prompt_messages(&req.messages, &[]);
+        }
+
+        if caps.supports_stream {
+            req.stream = Some(true);
+            let resp = client
+                .post(url)
+                .header("Authorization", format!("Bearer {}", config.api_key))
+                .header("Content-Type", "application/json")
+                .json(&req)
+                .send_logged()
+                .await?;
+
+            let status = resp.status();
+            let is_json = resp
+                .headers()
+                .get(reqwest::header::CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .is_some_and(|v| v.contains("application/json"));
+            if status.is_success() && !is_json {
+                let stream: ChatByteStream = Box::pin(resp.bytes_stream().map(|r| r.map(|b| b.to_vec())));
+                return Ok(SseStream::new(stream));
+            }
+
+            let body = resp.text().await?;
+            if status.is_success() {
+                // 忽略 stream 参数、直接返回完整 JSON 的服务
+                model_capability::mark_stream_unsupported(config);
+                let _ = sender.send(model_capability::mode_event("当前模型不支持流式输出，已切换为非流式模式")).await;
+                return Ok(Self::sse_from_completion(&Self::parse_completion(&body)?));
+            }
+            let err = format!("AI API error ({}): {}", status.as_u16(), body);
+            if !model_capability::is_stream_rejection(&err) {
+                return Err(anyhow!(err));
+            }
+            log::warn!("[ai_service] model {} rejected stream, fallback to non-stream: {}", config.model_name, err);
+            model_capability::mark_stream_unsupported(config);
+        }
 
-        let model_info = response.id.unwrap_or_default();
-        Ok(format!("模型 {} 连接正常。回复: {}", 
-            if model_info.is_empty() { &config.model_name } else { &model_info },
-            reply.trim()
-        ))
+        let _ = sender.send(model_capability::mode_event("当前模型不支持流式输出，已切换为非流式模式")).await;
+        req.stream = Some(false);
+        let body = Self::post_chat(client, url, config, &req, 0).await?;
+        Ok(Self::sse_from_completion(&Self::parse_completion(&body)?))
+    }
+
+    async fn send_thinking(sender: &tokio::sync::mpsc::Sender<AIStreamEvent>, content: Option<String>) {
+        if let Some(content) = content {
+            let _ = sender.send(model_capability::thinking_event(&content)).await;
+        }
+    }
+
+    /// 执行一次工具调用：相同工具与参数在缓存有效期内直接复用结果，执行失败时把错误作为结果交给模型，
+    /// 并把调用记入会话审计
+    async fn execute_cached_tool<F, Fut>(
+        cache: &mut stock_tools::ToolResultCache,
+        session: &mut AgentSession,
+        round: usize,
+        name: &str,
+        args: &str,
+        exec: F,
+    ) -> String
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<String>>,
+    {
+        let started = std::time::Instant::now();
+        let cached = cache.get(name, args);
+        let from_cache = cached.is_some();
+        let result = match cached {
+            Some(cached) => {
+                log::info!("[ai_service] tool cache hit name={}", name);
+                cached
+            }
+            None => {
+                let r = match exec().await {
+                    Ok(r) => r,
+                    Err(e) => format!("工具调用失败: {}", e),
+                };
+                cache.insert(name, args, &r);
+                r
+            }
+        };
+        session.record_tool_call(round, name, args, &result, from_cache, started.elapsed().as_millis() as u64);
+        result
+    }
+
+    fn sse_from_completion(response: &ChatCompletionResponse) -> SseStream<ChatByteStream> {
+        let bytes = model_capability::to_sse_bytes(response);
+        let stream: ChatByteStream = Box::pin(futures::stream::iter(vec![Ok(bytes)]));
+        SseStream::new(stream)
     }
 
     /// 判断选股工具返回结果是否为空（基于 JSON 解析，避免字符串匹配的格式敏感问题）
@@ -226,8 +523,12 @@ impl AIService {
     pub async fn batch_generate_instructions(
         config: &AIConfig,
         stocks: &[StockSummaryForAI],
+        positions: &[UserPosition],
     ) -> Result<(Vec<StockInstructionResult>, Option<TokenUsage>)> {
-        log::info!("[ai_service] batch_generate_instructions: {} stocks, model={}", stocks.len(), config.model_name);
+        log::info!(
+            "[ai_service] batch_generate_instructions: {} stocks, {} positions, model={}",
+            stocks.len(), positions.len(), config.model_name
+        );
         if stocks.is_empty() {
             return Ok((vec![], None));
         }
@@ -235,12 +536,16 @@ impl AIService {
         let client = build_ai_client(config.timeout_secs)?;
 
         let stocks_text = stocks.iter().map(|s| {
-            format!(
-                "{}({}) 今开{:.1}% 最新{:.1}% 得分{} 竞价{:.0}万 {}板 换手{:.1}% 标签:{}",
+            let line = format!(
+                "{}({}) 今开{:.1}% 最新{:.1}% 得分{} 竞价{} {}板 换手{:.1}% 标签:{}",
                 s.name, s.code, s.open_pct, s.current_pct, s.score,
-                s.bid_amount / 10000.0, s.streak_days, s.turnover,
+                units::format_amount(s.bid_amount), s.streak_days, s.turnover,
                 s.labels.join(",")
-            )
+            );
+            match find_position(positions, &s.code) {
+                Some(p) => format!("{} 【持仓】成本{:.2} {}股 已持有{}天", line, p.cost, p.size, p.holding_days),
+                None => line,
+            }
         }).collect::<Vec<_>>().join("\n");
 
         let prompt = format!(
@@ -252,29 +557,36 @@ impl AIService {
             - 得分60-80或有被卡位风险的：watch，标签如\"梯队PK被卡位\"\n\
             - 得分<60或深水低开的：eliminate，标签如\"淘汰:深水核按钮\"\n\
             \n\
+            标注【持仓】的股票为用户已持有，给出持仓中的操作建议而非新开仓建议，指令类型改为：\n\
+            add(加仓)、trim(减仓)、hold(持有)、eliminate(清仓)，需结合成本价、持有天数与当日强弱判断，\n\
+            reason 中说明相对成本的盈亏情况\n\
+            \n\
             股票数据：\n{}\n\
             \n\
             请严格以JSON数组格式输出，每个元素包含code、action、label、reason字段，不要输出其他内容：",
             stocks_text
         );
 
-        let req = ChatCompletionRequest {
+        let mut req = ChatCompletionRequest {
             model: config.model_name.clone(),
             messages: vec![ChatMessage::user(&prompt)],
             max_tokens: Some(config.max_tokens),
+            max_completion_tokens: None,
             temperature: Some(config.temperature),
             stream: Some(false),
             tools: None,
             tool_choice: None,
         };
 
+        model_capability::adapt_request(&mut req);
+
         let url = format!("{}/chat/completions", config.base_url.trim_end_matches('/'));
         let resp = client
             .post(&url)
             .header("Authorization", format!("Bearer {}", config.api_key))
             .header("Content-Type", "application/json")
             .json(&req)
-            .send()
+            .send_logged()
             .await?;
 
         let status = resp.status();
@@ -294,9 +606,10 @@ impl AIService {
             .and_then(|m| m.content.clone())
             .unwrap_or_default();
 
-        let json_str = extract_json_array(&content)?;
-        let instructions: Vec<StockInstructionResult> = serde_json::from_str(&json_str)
+        let json_str = extract_json_array(&ai_postprocess::clean(&content))?;
+        let mut instructions: Vec<StockInstructionResult> = serde_json::from_str(&json_str)
             .map_err(|e| anyhow!("Instruction parse error: {} content: {}", e, &json_str[..200.min(json_str.len())]))?;
+        apply_position_actions(&mut instructions, positions);
 
         Ok((instructions, token_usage))
     }
@@ -307,12 +620,11 @@ impl AIService {
         config: &AIConfig,
         code: &str,
         name: &str,
+        tool_ctx: &stock_tools::ToolContext,
         sender: tokio::sync::mpsc::Sender<AIStreamEvent>,
+        session: &mut AgentSession,
     ) -> Result<(String, Option<TokenUsage>)> {
         log::info!("[ai_service] diagnose_stock_with_tools code={} name={} model={}", code, name, config.model_name);
-        let client = build_ai_client(config.timeout_secs)?;
-        let url = format!("{}/chat/completions", config.base_url.trim_end_matches('/'));
-        let tools = stock_tools::get_tool_definitions();
 
         let system_prompt = format!(
             "你是一位拥有20年实战经验的顶级A股技术分析师。你可以通过工具获取股票的真实数据。\n\
@@ -324,7 +636,10 @@ impl AIService {
             2. 调用 get_kline_data 获取最近60根日K线数据\n\
             3. 调用 get_technical_indicators 获取技术指标（MA/MACD/KDJ/RSI/BOLL/信号等）\n\
             4. 如需要，调用 get_fund_flow 获取详细资金流向\n\
-            5. 综合所有数据给出专业分析\n\
+            5. 涉及公司业务时调用 get_stock_profile 获取主营构成，不要凭印象描述公司做什么\n\
+            6. 评估估值时可调用 get_earnings_forecast 获取机构一致预期与预测PE，调用 get_peer_comparison 一次性获取同行业对比\n\
+            7. 需要择时参考时可调用 get_seasonality 查看该股与所属板块历史上当月的平均表现（仅作背景参考，不作为买卖依据）\n\
+            8. 综合所有数据给出专业分析\n\
             \n\
             **分析要求**：\n\
             基于真实数据进行分析，给出：\n\
@@ -332,48 +647,48 @@ impl AIService {
             2. **技术面分析**：K线形态、均线系统、MACD/KDJ/RSI/BOLL 等指标研判、支撑压力位\n\
             3. **资金面分析**：主力资金动向、换手率、量比分析\n\
             4. **操作建议**：明确给出买入/持有/减仓/清仓建议，附具体参考价位（止盈/止损位）\n\
-            5. **风险提示**：当前主要风险因素\n\
+            5. **风险提示**：当前主要风险因素，参考技术指标中的波动率、Beta 与近一年最大回撤量化风险\n\
             \n\
             请用简洁专业的语言，引用具体数据支撑你的观点。",
             name, code
         );
 
-        let mut messages: Vec<ChatMessage> = vec![
+        let messages = vec![
             ChatMessage::system(&system_prompt),
             ChatMessage::user(&format!("请对 {}({}) 进行全面的技术分析和诊断。", name, code)),
         ];
+        Self::run_tool_agent(config, stock_tools::get_tool_definitions(), messages, tool_ctx, sender, session).await
+    }
 
+    /// 通用工具调用 Agent：先非流式多轮执行工具调用，再以流式输出最终回答
+    pub async fn run_tool_agent(
+        config: &AIConfig,
+        tools: Vec<serde_json::Value>,
+        mut messages: Vec<ChatMessage>,
+        tool_ctx: &stock_tools::ToolContext,
+        sender: tokio::sync::mpsc::Sender<AIStreamEvent>,
+        session: &mut AgentSession,
+    ) -> Result<(String, Option<TokenUsage>)> {
+        let client = build_ai_client(config.timeout_secs)?;
+        let url = format!("{}/chat/completions", config.base_url.trim_end_matches('/'));
         let mut full_content = String::new();
         let mut total_usage: Option<TokenUsage> = None;
+        let mut tool_cache = stock_tools::ToolResultCache::default();
 
         // Phase 1: Tool calling loop (non-streaming, easy to parse tool_calls)
-        for _round in 0..MAX_TOOL_ROUNDS {
+        for round in 0..MAX_TOOL_ROUNDS {
             let req = ChatCompletionRequest {
                 model: config.model_name.clone(),
                 messages: messages.clone(),
                 max_tokens: Some(config.max_tokens),
+                max_completion_tokens: None,
                 temperature: Some(config.temperature),
                 stream: Some(false),
                 tools: Some(tools.clone()),
                 tool_choice: None,
             };
 
-            let resp = client
-                .post(&url)
-                .header("Authorization", format!("Bearer {}", config.api_key))
-                .header("Content-Type", "application/json")
-                .json(&req)
-                .send()
-                .await?;
-
-            if !resp.status().is_success() {
-                let body = resp.text().await?;
-                return Err(anyhow!("AI API error: {}", body));
-            }
-
-            let body = resp.text().await?;
-            let response: ChatCompletionResponse = serde_json::from_str(&body)
-                .map_err(|e| anyhow!("AI response parse error: {} body: {}", e, &body[..500.min(body.len())]))?;
+            let response = Self::request_tool_round(&client, &url, config, req, 0, round == 0, &sender).await?;
 
             // Accumulate usage
             if let Some(usage) = &response.usage {
@@ -447,10 +762,9 @@ impl AIService {
                     }).await;
 
                     // Execute the tool
-                    let result = match stock_tools::execute_tool(tool_name, tool_args).await {
-                        Ok(r) => r,
-                        Err(e) => format!("工具调用失败: {}", e),
-                    };
+                    let result = Self::execute_cached_tool(&mut tool_cache, session, round, tool_name, tool_args, || {
+                        stock_tools::execute_tool(tool_name, tool_args, tool_ctx)
+                    }).await;
 
                     // Notify frontend about result
                     let _ = sender.send(AIStreamEvent {
@@ -499,84 +813,255 @@ impl AIService {
             model: config.model_name.clone(),
             messages: messages.clone(),
             max_tokens: Some(config.max_tokens),
+            max_completion_tokens: None,
             temperature: Some(config.temperature),
             stream: Some(true),
             tools: None,
             tool_choice: None,
         };
 
-        let resp = client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", config.api_key))
-            .header("Content-Type", "application/json")
-            .json(&req)
-            .send()
-            .await?;
-
-        if !resp.status().is_success() {
-            let body = resp.text().await?;
-            return Err(anyhow!("AI API error: {}", body));
-        }
-
         // Stream final response
-        let mut stream = resp.bytes_stream();
-        let mut buffer = String::new();
-
-        while let Some(chunk) = stream.next().await {
-            let chunk = chunk?;
-            buffer.push_str(&String::from_utf8_lossy(&chunk));
-
-            while let Some(line_end) = buffer.find('\n') {
-                let line = buffer[..line_end].trim().to_string();
-                buffer = buffer[line_end + 1..].to_string();
-
-                if line.is_empty() || line == "data: [DONE]" {
-                    if line == "data: [DONE]" {
-                        let _ = sender.send(AIStreamEvent {
-                            event_type: "done".to_string(),
-                            content: None,
-                            done: true,
-                            usage: total_usage.clone(),
-                            tool_name: None,
-                        }).await;
-                    }
-                    continue;
-                }
+        let mut events = Self::open_final_stream(&client, &url, config, req, &sender).await?;
+        let mut reasoning = model_capability::ReasoningBuffer::default();
+
+        while let Some(event) = events.next_event().await {
+            let event = event?;
+            if event.is_done() {
+                Self::send_thinking(&sender, reasoning.flush()).await;
+                let _ = sender.send(AIStreamEvent {
+                    event_type: "done".to_string(),
+                    content: None,
+                    done: true,
+                    usage: total_usage.clone(),
+                    tool_name: None,
+                }).await;
+                continue;
+            }
 
-                if let Some(data) = line.strip_prefix("data: ") {
-                    if let Ok(chunk_resp) = serde_json::from_str::<ChatCompletionResponse>(data) {
-                        if let Some(choice) = chunk_resp.choices.first() {
-                            if let Some(delta) = &choice.delta {
-                                if let Some(content) = &delta.content {
-                                    full_content.push_str(content);
-                                    let _ = sender.send(AIStreamEvent {
-                                        event_type: "content".to_string(),
-                                        content: Some(content.clone()),
-                                        done: false,
-                                        usage: None,
-                                        tool_name: None,
-                                    }).await;
-                                }
-                            }
+            for chunk_resp in parse_stream_chunks(&event.data) {
+                if let Some(choice) = chunk_resp.choices.first() {
+                    if let Some(delta) = &choice.delta {
+                        if let Some(r) = delta.reasoning_content.as_deref() {
+                            Self::send_thinking(&sender, reasoning.push(r)).await;
                         }
-                        // Accumulate streaming usage if present
-                        if let Some(usage) = &chunk_resp.usage {
-                            total_usage = Some(match total_usage {
-                                Some(mut u) => {
-                                    u.prompt_tokens += usage.prompt_tokens;
-                                    u.completion_tokens += usage.completion_tokens;
-                                    u.total_tokens += usage.total_tokens;
-                                    u
-                                }
-                                None => usage.clone(),
-                            });
+                        if let Some(content) = &delta.content {
+                            Self::send_thinking(&sender, reasoning.flush()).await;
+                            full_content.push_str(content);
+                            let _ = sender.send(AIStreamEvent {
+                                event_type: "content".to_string(),
+                                content: Some(content.clone()),
+                                done: false,
+                                usage: None,
+                                tool_name: None,
+                            }).await;
                         }
                     }
                 }
+                // Accumulate streaming usage if present
+                if let Some(usage) = &chunk_resp.usage {
+                    total_usage = Some(match total_usage {
+                        Some(mut u) => {
+                            u.prompt_tokens += usage.prompt_tokens;
+                            u.completion_tokens += usage.completion_tokens;
+                            u.total_tokens += usage.total_tokens;
+                            u
+                        }
+                        None => usage.clone(),
+                    });
+                }
+            }
+        }
+
+        session.raw_content = full_content.clone();
+        Ok((ai_postprocess::clean(&full_content), total_usage))
+    }
+
+    /// 确保选股报告包含可解析的 <PICKS> 列表：后处理已尝试修复括号与尾随逗号，
+    /// 仍解析不出时追加一次低成本请求，让模型根据报告只重新输出 JSON，成功后补回报告末尾。
+    /// 补救失败不影响原报告，返回值中的 usage 已合并补救请求的消耗
+    pub async fn ensure_structured_picks(
+        config: &AIConfig,
+        content: String,
+        usage: Option<TokenUsage>,
+        sender: &tokio::sync::mpsc::Sender<AIStreamEvent>,
+    ) -> (String, Option<TokenUsage>) {
+        if content.trim().is_empty() || !parse_picks(&content).is_empty() {
+            return (content, usage);
+        }
+        log::warn!("[ai_service] ensure_structured_picks no valid PICKS, re-asking model={}", config.model_name);
+        let _ = sender.send(model_capability::thinking_event("选股列表格式不完整，正在请求模型重新输出结构化结果")).await;
+
+        match Self::reask_picks_json(config, &content).await {
+            Ok((json, extra)) => {
+                let usage = match (usage, extra) {
+                    (Some(mut u), Some(e)) => {
+                        u.prompt_tokens += e.prompt_tokens;
+                        u.completion_tokens += e.completion_tokens;
+                        u.total_tokens += e.total_tokens;
+                        Some(u)
+                    }
+                    (u, e) => u.or(e),
+                };
+                log::info!("[ai_service] ensure_structured_picks recovered picks");
+                (format!("{}\n\n<PICKS>\n{}\n</PICKS>", content.trim_end(), json), usage)
             }
+            Err(e) => {
+                log::warn!("[ai_service] ensure_structured_picks re-ask failed: {}", e);
+                (content, usage)
+            }
+        }
+    }
+
+    /// 自然语言入口的意图识别：返回模型输出的 JSON 原文，由调用方解析
+    pub async fn classify_intent(config: &AIConfig, input: &str) -> Result<(String, Option<TokenUsage>)> {
+        let client = build_ai_client(config.timeout_secs)?;
+        let url = format!("{}/chat/completions", config.base_url.trim_end_matches('/'));
+        let mut req = ChatCompletionRequest {
+            model: config.model_name.clone(),
+            messages: vec![
+                ChatMessage::system(ROUTER_CLASSIFY_PROMPT),
+                ChatMessage::user(input),
+            ],
+            max_tokens: Some(512),
+            max_completion_tokens: None,
+            temperature: Some(0.0),
+            stream: Some(false),
+            tools: None,
+            tool_choice: None,
+        };
+        model_capability::adapt_request(&mut req);
+        let body = Self::post_chat(&client, &url, config, &req, 1).await?;
+        let response = Self::parse_completion(&body)?;
+        let reply = response.choices.first()
+            .and_then(|c| c.message.as_ref())
+            .and_then(|m| m.content.clone())
+            .unwrap_or_default();
+        Ok((ai_postprocess::clean(&reply), response.usage))
+    }
+
+    /// 盯盘助手快检：把真实数据与上一次结论一起发给模型，返回简短结论（非流式）
+    pub async fn stock_check_in(
+        config: &AIConfig,
+        code: &str,
+        name: &str,
+        question: &str,
+        context: &str,
+        previous: Option<&str>,
+    ) -> Result<(String, Option<TokenUsage>)> {
+        log::info!("[ai_service] stock_check_in code={} model={}", code, config.model_name);
+        let client = build_ai_client(config.timeout_secs)?;
+        let url = format!("{}/chat/completions", config.base_url.trim_end_matches('/'));
+        let mut prompt = format!(
+            "现在是 {}。股票：{}({})\n问题：{}\n\n真实数据：\n{}",
+            chrono::Local::now().format("%Y-%m-%d %H:%M"), name, code, question, context
+        );
+        if let Some(previous) = previous {
+            prompt.push_str(&format!("\n\n上一次快检结论：\n{}", previous));
+        }
+        let mut req = ChatCompletionRequest {
+            model: config.model_name.clone(),
+            messages: vec![
+                ChatMessage::system(CHECK_IN_SYSTEM_PROMPT),
+                ChatMessage::user(&prompt),
+            ],
+            max_tokens: Some(config.max_tokens),
+            max_completion_tokens: None,
+            temperature: Some(config.temperature),
+            stream: Some(false),
+            tools: None,
+            tool_choice: None,
+        };
+        model_capability::adapt_request(&mut req);
+        let body = Self::post_chat(&client, &url, config, &req, 1).await?;
+        let response = Self::parse_completion(&body)?;
+        let reply = response.choices.first()
+            .and_then(|c| c.message.as_ref())
+            .and_then(|m| m.content.clone())
+            .unwrap_or_default();
+        let content = ai_postprocess::clean(&reply);
+        if content.trim().is_empty() {
+            return Err(anyhow!("模型未返回内容"));
+        }
+        Ok((content, response.usage))
+    }
+
+    /// 请模型仅根据已生成的报告重新输出选股 JSON 数组，返回修复校验后的 JSON
+    async fn reask_picks_json(config: &AIConfig, report: &str) -> Result<(String, Option<TokenUsage>)> {
+        let client = build_ai_client(config.timeout_secs)?;
+        let url = format!("{}/chat/completions", config.base_url.trim_end_matches('/'));
+        let mut req = ChatCompletionRequest {
+            model: config.model_name.clone(),
+            messages: vec![
+                ChatMessage::system(PICKS_REASK_PROMPT),
+                ChatMessage::user(&format!("选股报告如下：\n\n{}", report)),
+            ],
+            max_tokens: Some(2048),
+            max_completion_tokens: None,
+            temperature: Some(0.0),
+            stream: Some(false),
+            tools: None,
+            tool_choice: None,
+        };
+        model_capability::adapt_request(&mut req);
+        let body = Self::post_chat(&client, &url, config, &req, 1).await?;
+        let response = Self::parse_completion(&body)?;
+        let reply = response.choices.first()
+            .and_then(|c| c.message.as_ref())
+            .and_then(|m| m.content.clone())
+            .unwrap_or_default();
+
+        let json = ai_postprocess::repair_json_array(&ai_postprocess::clean(&reply))
+            .ok_or_else(|| anyhow!("补救输出中没有 JSON 数组"))?;
+        if parse_picks(&format!("<PICKS>{}</PICKS>", json)).is_empty() {
+            return Err(anyhow!("补救输出中没有有效的股票条目"));
+        }
+        Ok((json, response.usage))
+    }
+
+    /// 回放已记录的 Agent 会话（dry-run）：按记录顺序重发工具调用事件，
+    /// 再对原始模型输出重新执行后处理。不发起任何网络请求，也不消耗 token
+    pub async fn replay_agent_session(
+        session: &AgentSession,
+        sender: tokio::sync::mpsc::Sender<AIStreamEvent>,
+    ) -> Result<String> {
+        log::info!("[ai_service] replay_agent_session id={} kind={} tool_calls={}", session.id, session.kind, session.tool_calls.len());
+        if session.raw_content.is_empty() && session.tool_calls.is_empty() {
+            return Err(anyhow!("会话没有可回放的记录"));
+        }
+
+        for record in &session.tool_calls {
+            let _ = sender.send(AIStreamEvent {
+                event_type: "tool_call".to_string(),
+                content: Some(format!("正在获取数据: {}", stock_tools::pick_tool_name_to_chinese(&record.tool_name))),
+                done: false,
+                usage: None,
+                tool_name: Some(record.tool_name.clone()),
+            }).await;
+            let _ = sender.send(AIStreamEvent {
+                event_type: "tool_result".to_string(),
+                content: Some(stock_tools::summarize_tool_result(&record.tool_name, &record.result)),
+                done: false,
+                usage: None,
+                tool_name: Some(record.tool_name.clone()),
+            }).await;
+        }
+
+        // 与实时流程一致：检测到 DSML 标记后不再向前端透传
+        let visible = match session.raw_content.find("<\u{ff5c}") {
+            Some(pos) => &session.raw_content[..pos],
+            None => session.raw_content.as_str(),
+        };
+        if !visible.is_empty() {
+            let _ = sender.send(AIStreamEvent {
+                event_type: "content".to_string(),
+                content: Some(visible.to_string()),
+                done: false,
+                usage: None,
+                tool_name: None,
+            }).await;
         }
 
-        Ok((full_content, total_usage))
+        Ok(ai_postprocess::clean(&session.raw_content))
     }
 
     /// 原版流式分析（保留给其他场景使用）
@@ -610,6 +1095,7 @@ impl AIService {
             model: config.model_name.clone(),
             messages: vec![ChatMessage::user(&prompt)],
             max_tokens: Some(config.max_tokens),
+            max_completion_tokens: None,
             temperature: Some(config.temperature),
             stream: Some(true),
             tools: None,
@@ -617,79 +1103,164 @@ impl AIService {
         };
 
         let url = format!("{}/chat/completions", config.base_url.trim_end_matches('/'));
+        let mut full_content = String::new();
+        let mut events = Self::open_final_stream(&client, &url, config, req, &sender).await?;
+        let mut reasoning = model_capability::ReasoningBuffer::default();
+
+        while let Some(event) = events.next_event().await {
+            let event = event?;
+            if event.is_done() {
+                Self::send_thinking(&sender, reasoning.flush()).await;
+                let _ = sender.send(AIStreamEvent {
+                    event_type: "done".to_string(),
+                    content: None,
+                    done: true,
+                    usage: None,
+                    tool_name: None,
+                }).await;
+                continue;
+            }
+
+            for chunk_resp in parse_stream_chunks(&event.data) {
+                if let Some(choice) = chunk_resp.choices.first() {
+                    if let Some(delta) = &choice.delta {
+                        if let Some(r) = delta.reasoning_content.as_deref() {
+                            Self::send_thinking(&sender, reasoning.push(r)).await;
+                        }
+                        if let Some(content) = &delta.content {
+                            Self::send_thinking(&sender, reasoning.flush()).await;
+                            full_content.push_str(content);
+                            let _ = sender.send(AIStreamEvent {
+                                event_type: "content".to_string(),
+                                content: Some(content.clone()),
+                                done: false,
+                                usage: None,
+                                tool_name: None,
+                            }).await;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok((ai_postprocess::clean(&full_content), None))
+    }
+
+    /// 识图分析：把图片（data URL）与股票真实数据一起发给视觉模型，流式返回分析。
+    /// 图片消息需要数组形式的 content，因此直接构造请求体，不经过 ChatMessage
+    pub async fn analyze_image_stream(
+        config: &AIConfig,
+        image_url: &str,
+        prompt: &str,
+        sender: tokio::sync::mpsc::Sender<AIStreamEvent>,
+    ) -> Result<(String, Option<TokenUsage>)> {
+        log::info!("[ai_service] analyze_image_stream model={} image_len={}", config.model_name, image_url.len());
+        let client = build_ai_client(config.timeout_secs)?;
+        let url = format!("{}/chat/completions", config.base_url.trim_end_matches('/'));
+        let stream = model_capability::get(config).supports_stream;
+        let body = serde_json::json!({
+            "model": config.model_name,
+            "messages": [
+                { "role": "system", "content": VISION_SYSTEM_PROMPT },
+                { "role": "user", "content": [
+                    { "type": "text", "text": prompt },
+                    { "type": "image_url", "image_url": { "url": image_url } },
+                ] },
+            ],
+            "max_tokens": config.max_tokens,
+            "temperature": config.temperature,
+            "stream": stream,
+        });
+
         let resp = client
             .post(&url)
             .header("Authorization", format!("Bearer {}", config.api_key))
             .header("Content-Type", "application/json")
-            .json(&req)
-            .send()
+            .json(&body)
+            .send_logged()
             .await?;
-
-        if !resp.status().is_success() {
-            let body = resp.text().await?;
-            return Err(anyhow!("AI API error: {}", body));
-        }
+        let status = resp.status();
+        let is_json = resp
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.contains("application/json"));
+        let mut events = if status.is_success() && !is_json {
+            let stream: ChatByteStream = Box::pin(resp.bytes_stream().map(|r| r.map(|b| b.to_vec())));
+            SseStream::new(stream)
+        } else {
+            let text = resp.text().await?;
+            if !status.is_success() {
+                let lower = text.to_lowercase();
+                if ["image", "vision", "multimodal", "content type"].iter().any(|k| lower.contains(k)) {
+                    return Err(anyhow!("模型 {} 不支持图片输入，请选择支持视觉的模型（如 gpt-4o、qwen-vl、glm-4v）", config.model_name));
+                }
+                return Err(anyhow!("AI API error ({}): {}", status.as_u16(), text.chars().take(300).collect::<String>()));
+            }
+            Self::sse_from_completion(&Self::parse_completion(&text)?)
+        };
 
         let mut full_content = String::new();
-        let mut stream = resp.bytes_stream();
-        let mut buffer = String::new();
-
-        while let Some(chunk) = stream.next().await {
-            let chunk = chunk?;
-            buffer.push_str(&String::from_utf8_lossy(&chunk));
-
-            while let Some(line_end) = buffer.find('\n') {
-                let line = buffer[..line_end].trim().to_string();
-                buffer = buffer[line_end + 1..].to_string();
-
-                if line.is_empty() || line == "data: [DONE]" {
-                    if line == "data: [DONE]" {
-                        let _ = sender.send(AIStreamEvent {
-                            event_type: "done".to_string(),
-                            content: None,
-                            done: true,
-                            usage: None,
-                            tool_name: None,
-                        }).await;
-                    }
-                    continue;
-                }
+        let mut total_usage: Option<TokenUsage> = None;
+        let mut reasoning = model_capability::ReasoningBuffer::default();
+        while let Some(event) = events.next_event().await {
+            let event = event?;
+            if event.is_done() {
+                Self::send_thinking(&sender, reasoning.flush()).await;
+                let _ = sender.send(AIStreamEvent {
+                    event_type: "done".to_string(),
+                    content: None,
+                    done: true,
+                    usage: total_usage.clone(),
+                    tool_name: None,
+                }).await;
+                continue;
+            }
 
-                if let Some(data) = line.strip_prefix("data: ") {
-                    if let Ok(chunk_resp) = serde_json::from_str::<ChatCompletionResponse>(data) {
-                        if let Some(choice) = chunk_resp.choices.first() {
-                            if let Some(delta) = &choice.delta {
-                                if let Some(content) = &delta.content {
-                                    full_content.push_str(content);
-                                    let _ = sender.send(AIStreamEvent {
-                                        event_type: "content".to_string(),
-                                        content: Some(content.clone()),
-                                        done: false,
-                                        usage: None,
-                                        tool_name: None,
-                                    }).await;
-                                }
-                            }
+            for chunk_resp in parse_stream_chunks(&event.data) {
+                if let Some(choice) = chunk_resp.choices.first() {
+                    if let Some(delta) = &choice.delta {
+                        if let Some(r) = delta.reasoning_content.as_deref() {
+                            Self::send_thinking(&sender, reasoning.push(r)).await;
+                        }
+                        if let Some(content) = &delta.content {
+                            Self::send_thinking(&sender, reasoning.flush()).await;
+                            full_content.push_str(content);
+                            let _ = sender.send(AIStreamEvent {
+                                event_type: "content".to_string(),
+                                content: Some(content.clone()),
+                                done: false,
+                                usage: None,
+                                tool_name: None,
+                            }).await;
                         }
                     }
                 }
+                if let Some(usage) = &chunk_resp.usage {
+                    total_usage = Some(usage.clone());
+                }
             }
         }
 
-        Ok((full_content, None))
+        Ok((ai_postprocess::clean(&full_content), total_usage))
     }
 
     /// AI 自主选股：Agent 模式，让 AI 自主获取新闻/板块/行情，独立做出选股决策
+    #[allow(clippy::too_many_arguments)]
     pub async fn ai_pick_stocks_with_tools(
         config: &AIConfig,
-        qgqp_b_id: &str,
+        tool_ctx: &stock_tools::ToolContext,
         sender: tokio::sync::mpsc::Sender<AIStreamEvent>,
         cancel: Arc<AtomicBool>,
         max_tool_rounds: usize,
         max_token_budget: u32,
         custom_strategy_prompt: Option<&str>,
+        preferences: &PickPreferences,
+        session: &mut AgentSession,
+        resume: Option<PickCheckpoint>,
+        on_checkpoint: &(dyn Fn(&PickCheckpoint) + Send + Sync),
     ) -> Result<(String, Option<TokenUsage>)> {
-        log::info!("[ai_service] ai_pick_stocks_with_tools model={} max_rounds={} max_budget={} custom_prompt={}", config.model_name, max_tool_rounds, max_token_budget, custom_strategy_prompt.is_some());
+        log::info!("[ai_service] ai_pick_stocks_with_tools model={} max_rounds={} max_budget={} custom_prompt={} resume={:?}", config.model_name, max_tool_rounds, max_token_budget, custom_strategy_prompt.is_some(), resume.as_ref().map(|c| c.next_round));
         let client = build_ai_client(config.timeout_secs)?;
         let url = format!("{}/chat/completions", config.base_url.trim_end_matches('/'));
         let tools = stock_tools::get_pick_tool_definitions();
@@ -700,21 +1271,47 @@ impl AIService {
             Some(custom) => custom.replace("{today}", &today),
             None => DEFAULT_PICK_STRATEGY_PROMPT.replace("{today}", &today),
         };
-        let system_prompt = format!("{}\n\n{}", strategy_part, PICK_OUTPUT_FORMAT_PROMPT);
-
-        let mut messages: Vec<ChatMessage> = vec![
-            ChatMessage::system(&system_prompt),
-            ChatMessage::user("请开始分析当前A股市场，自主获取数据并给出你的选股推荐。"),
-        ];
+        let mut sections = vec![strategy_part];
+        sections.extend(tool_ctx.market_regime.as_ref().map(market_regime::to_prompt));
+        sections.extend(pick_constraints::to_prompt(preferences));
+        sections.push(PICK_OUTPUT_FORMAT_PROMPT.to_string());
+        let system_prompt = sections.join("\n\n");
+
+        // 续跑时沿用断点中的对话、用量与轮次，已进入最终阶段的直接重新生成报告
+        let (mut messages, mut total_usage, start_round) = match resume {
+            Some(checkpoint) => {
+                let start_round = if checkpoint.final_phase { max_tool_rounds } else { checkpoint.next_round };
+                (checkpoint.messages, checkpoint.usage, start_round)
+            }
+            None => (
+                vec![
+                    ChatMessage::system(&system_prompt),
+                    ChatMessage::user("请开始分析当前A股市场，自主获取数据并给出你的选股推荐。"),
+                ],
+                None,
+                0,
+            ),
+        };
+        let checkpoint = |session: &AgentSession, messages: &[ChatMessage], usage: &Option<TokenUsage>, next_round: usize, final_phase: bool| {
+            on_checkpoint(&PickCheckpoint {
+                session: session.clone(),
+                next_round,
+                final_phase,
+                messages: messages.to_vec(),
+                usage: usage.clone(),
+                preferences: preferences.clone(),
+                updated_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            });
+        };
 
         let mut full_content = String::new();
-        let mut total_usage: Option<TokenUsage> = None;
+        let mut tool_cache = stock_tools::ToolResultCache::default();
         let mut empty_search_count: u32 = 0; // 连续空结果计数
         let mut reflection_injected = false;  // 反思提示是否已注入
         let mut budget_exceeded = false;      // token 预算是否已超限
 
         // Phase 1: Tool calling loop
-        for _round in 0..max_tool_rounds {
+        for round in start_round..max_tool_rounds {
             // 取消检查
             if cancel.load(Ordering::SeqCst) {
                 return Err(anyhow!("用户取消了 AI 选股"));
@@ -738,41 +1335,14 @@ impl AIService {
                 model: config.model_name.clone(),
                 messages: messages.clone(),
                 max_tokens: Some(config.max_tokens),
+                max_completion_tokens: None,
                 temperature: Some(config.pick_temperature),
                 stream: Some(false),
                 tools: Some(tools.clone()),
                 tool_choice: None,
             };
 
-            let req_json = serde_json::to_value(&req)
-                .map_err(|e| anyhow!("Failed to serialize request: {}", e))?;
-            let body = {
-                let client_ref = &client;
-                let url_ref = &url;
-                let api_key = &config.api_key;
-                let req_json_ref = &req_json;
-                retry_with_backoff(2, || async {
-                    let resp = client_ref
-                        .post(url_ref.as_str())
-                        .header("Authorization", format!("Bearer {}", api_key))
-                        .header("Content-Type", "application/json")
-                        .json(req_json_ref)
-                        .send()
-                        .await
-                        .map_err(|e| anyhow!("AI API request failed: {}", e))?;
-
-                    if !resp.status().is_success() {
-                        let status = resp.status();
-                        let body = resp.text().await.unwrap_or_default();
-                        return Err(anyhow!("AI API error ({}): {}", status.as_u16(), body));
-                    }
-
-                    resp.text().await.map_err(|e| anyhow!("Read response body failed: {}", e))
-                }).await?
-            };
-
-            let response: ChatCompletionResponse = serde_json::from_str(&body)
-                .map_err(|e| anyhow!("AI response parse error: {} body: {}", e, &body[..500.min(body.len())]))?;
+            let response = Self::request_tool_round(&client, &url, config, req, 2, round == 0, &sender).await?;
 
             if let Some(usage) = &response.usage {
                 total_usage = Some(match total_usage {
@@ -840,10 +1410,9 @@ impl AIService {
                         tool_name: Some(tool_name.clone()),
                     }).await;
 
-                    let result = match stock_tools::execute_pick_tool(tool_name, tool_args, qgqp_b_id).await {
-                        Ok(r) => r,
-                        Err(e) => format!("工具调用失败: {}", e),
-                    };
+                    let result = Self::execute_cached_tool(&mut tool_cache, session, round, tool_name, tool_args, || {
+                        stock_tools::execute_pick_tool(tool_name, tool_args, tool_ctx)
+                    }).await;
 
                     // 空结果反思兜底：仅针对 search_stocks_by_condition，连续2次空结果注入提示
                     if tool_name == "search_stocks_by_condition" {
@@ -886,6 +1455,7 @@ impl AIService {
 
                     messages.push(ChatMessage::tool_result(&tc.id, tool_name, &result));
                 }
+                checkpoint(session, &messages, &total_usage, round + 1, false);
                 continue;
             }
 
@@ -910,6 +1480,7 @@ impl AIService {
         if last_is_assistant {
             messages.pop();
         }
+        checkpoint(session, &messages, &total_usage, max_tool_rounds, true);
 
         // 选股分析报告需要较长输出（宏观分析 + 投资逻辑 + PICKS JSON + 风险提示），
         // 确保 max_tokens 不低于 4096，避免输出被截断导致 <PICKS> 标签不完整
@@ -919,98 +1490,92 @@ impl AIService {
             model: config.model_name.clone(),
             messages: messages.clone(),
             max_tokens: Some(pick_max_tokens),
+            max_completion_tokens: None,
             temperature: Some(config.pick_temperature),
             stream: Some(true),
             tools: None,
             tool_choice: None,
         };
 
-        let resp = client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", config.api_key))
-            .header("Content-Type", "application/json")
-            .json(&req)
-            .send()
-            .await?;
-
-        if !resp.status().is_success() {
-            let body = resp.text().await?;
-            return Err(anyhow!("AI API error: {}", body));
-        }
-
-        let mut stream = resp.bytes_stream();
-        let mut buffer = String::new();
+        let mut events = Self::open_final_stream(&client, &url, config, req, &sender).await?;
+        let mut reasoning = model_capability::ReasoningBuffer::default();
         let mut dsml_detected = false;
+        let mut last_checkpoint = std::time::Instant::now();
 
-        while let Some(chunk) = stream.next().await {
+        while let Some(event) = events.next_event().await {
             // 流式阶段取消检查
             if cancel.load(Ordering::SeqCst) {
                 break;
             }
-            let chunk = chunk?;
-            buffer.push_str(&String::from_utf8_lossy(&chunk));
-
-            while let Some(line_end) = buffer.find('\n') {
-                let line = buffer[..line_end].trim().to_string();
-                buffer = buffer[line_end + 1..].to_string();
-
-                if line.is_empty() || line == "data: [DONE]" {
-                    // done 事件由 ai_pick_cmd.rs 统一发送，此处不再发送，避免重复
-                    continue;
-                }
+            let event = event?;
+            if event.is_done() {
+                Self::send_thinking(&sender, reasoning.flush()).await;
+                // done 事件由 ai_pick_cmd.rs 统一发送，此处不再发送，避免重复
+                continue;
+            }
 
-                if let Some(data) = line.strip_prefix("data: ") {
-                    if let Ok(chunk_resp) = serde_json::from_str::<ChatCompletionResponse>(data) {
-                        if let Some(choice) = chunk_resp.choices.first() {
-                            if let Some(delta) = &choice.delta {
-                                if let Some(content) = &delta.content {
-                                    if content.contains("<\u{ff5c}") || content.contains("DSML") || content.contains("<｜") {
-                                        dsml_detected = true;
-                                    }
-                                    full_content.push_str(content);
-                                    if !dsml_detected {
-                                        let _ = sender.send(AIStreamEvent {
-                                            event_type: "content".to_string(),
-                                            content: Some(content.clone()),
-                                            done: false,
-                                            usage: None,
-                                            tool_name: None,
-                                        }).await;
-                                    }
-                                }
-                            }
+            for chunk_resp in parse_stream_chunks(&event.data) {
+                if let Some(choice) = chunk_resp.choices.first() {
+                    if let Some(delta) = &choice.delta {
+                        if let Some(r) = delta.reasoning_content.as_deref() {
+                            Self::send_thinking(&sender, reasoning.push(r)).await;
                         }
-                        if let Some(usage) = &chunk_resp.usage {
-                            total_usage = Some(match total_usage {
-                                Some(mut u) => {
-                                    u.prompt_tokens += usage.prompt_tokens;
-                                    u.completion_tokens += usage.completion_tokens;
-                                    u.total_tokens += usage.total_tokens;
-                                    u
-                                }
-                                None => usage.clone(),
-                            });
+                        if let Some(content) = &delta.content {
+                            Self::send_thinking(&sender, reasoning.flush()).await;
+                            if ai_postprocess::contains_artifact_marker(content) {
+                                dsml_detected = true;
+                            }
+                            full_content.push_str(content);
+                            // 定期保存已生成的部分报告，异常退出后可作为中断报告展示
+                            if last_checkpoint.elapsed() >= STREAM_CHECKPOINT_INTERVAL {
+                                last_checkpoint = std::time::Instant::now();
+                                session.raw_content = full_content.clone();
+                                checkpoint(session, &messages, &total_usage, max_tool_rounds, true);
+                            }
+                            if !dsml_detected {
+                                let _ = sender.send(AIStreamEvent {
+                                    event_type: "content".to_string(),
+                                    content: Some(content.clone()),
+                                    done: false,
+                                    usage: None,
+                                    tool_name: None,
+                                }).await;
+                            }
                         }
                     }
                 }
+                if let Some(usage) = &chunk_resp.usage {
+                    total_usage = Some(match total_usage {
+                        Some(mut u) => {
+                            u.prompt_tokens += usage.prompt_tokens;
+                            u.completion_tokens += usage.completion_tokens;
+                            u.total_tokens += usage.total_tokens;
+                            u
+                        }
+                        None => usage.clone(),
+                    });
+                }
             }
         }
 
         // 返回清理后的内容（done 事件由调用方统一发送）
-        let clean_content = clean_dsml_artifacts(&full_content);
+        session.raw_content = full_content.clone();
+        let clean_content = ai_postprocess::clean(&full_content);
         Ok((clean_content, total_usage))
     }
 
     /// AI 找相似股：基于给定股票，从同板块中找出低位补涨机会
+    #[allow(clippy::too_many_arguments)]
     pub async fn find_similar_stocks_with_tools(
         config: &AIConfig,
         code: &str,
         name: &str,
         sector: &str,
-        qgqp_b_id: &str,
+        tool_ctx: &stock_tools::ToolContext,
         sender: tokio::sync::mpsc::Sender<AIStreamEvent>,
         max_tool_rounds: usize,
         max_token_budget: u32,
+        session: &mut AgentSession,
     ) -> Result<(String, Option<TokenUsage>)> {
         log::info!("[ai_service] find_similar_stocks_with_tools code={} name={} sector={} model={}", code, name, sector, config.model_name);
         let client = build_ai_client(config.timeout_secs)?;
@@ -1074,10 +1639,11 @@ impl AIService {
 
         let mut full_content = String::new();
         let mut total_usage: Option<TokenUsage> = None;
+        let mut tool_cache = stock_tools::ToolResultCache::default();
         let mut budget_exceeded = false;
 
         // Phase 1: Tool calling loop (reuse same pattern as ai_pick)
-        for _round in 0..max_tool_rounds {
+        for round in 0..max_tool_rounds {
             // Token 预算闸门
             if budget_exceeded {
                 break;
@@ -1097,41 +1663,14 @@ impl AIService {
                 model: config.model_name.clone(),
                 messages: messages.clone(),
                 max_tokens: Some(config.max_tokens),
+                max_completion_tokens: None,
                 temperature: Some(config.pick_temperature),
                 stream: Some(false),
                 tools: Some(tools.clone()),
                 tool_choice: None,
             };
 
-            let req_json = serde_json::to_value(&req)
-                .map_err(|e| anyhow!("Failed to serialize request: {}", e))?;
-            let body = {
-                let client_ref = &client;
-                let url_ref = &url;
-                let api_key = &config.api_key;
-                let req_json_ref = &req_json;
-                retry_with_backoff(2, || async {
-                    let resp = client_ref
-                        .post(url_ref.as_str())
-                        .header("Authorization", format!("Bearer {}", api_key))
-                        .header("Content-Type", "application/json")
-                        .json(req_json_ref)
-                        .send()
-                        .await
-                        .map_err(|e| anyhow!("AI API request failed: {}", e))?;
-
-                    if !resp.status().is_success() {
-                        let status = resp.status();
-                        let body = resp.text().await.unwrap_or_default();
-                        return Err(anyhow!("AI API error ({}): {}", status.as_u16(), body));
-                    }
-
-                    resp.text().await.map_err(|e| anyhow!("Read response body failed: {}", e))
-                }).await?
-            };
-
-            let response: ChatCompletionResponse = serde_json::from_str(&body)
-                .map_err(|e| anyhow!("AI response parse error: {} body: {}", e, &body[..500.min(body.len())]))?;
+            let response = Self::request_tool_round(&client, &url, config, req, 2, round == 0, &sender).await?;
 
             if let Some(usage) = &response.usage {
                 total_usage = Some(match total_usage {
@@ -1199,10 +1738,9 @@ impl AIService {
                         tool_name: Some(tool_name.clone()),
                     }).await;
 
-                    let result = match stock_tools::execute_pick_tool(tool_name, tool_args, qgqp_b_id).await {
-                        Ok(r) => r,
-                        Err(e) => format!("工具调用失败: {}", e),
-                    };
+                    let result = Self::execute_cached_tool(&mut tool_cache, session, round, tool_name, tool_args, || {
+                        stock_tools::execute_pick_tool(tool_name, tool_args, tool_ctx)
+                    }).await;
 
                     let summary = stock_tools::summarize_tool_result(tool_name, &result);
 
@@ -1244,92 +1782,79 @@ impl AIService {
             model: config.model_name.clone(),
             messages: messages.clone(),
             max_tokens: Some(similar_max_tokens),
+            max_completion_tokens: None,
             temperature: Some(config.pick_temperature),
             stream: Some(true),
             tools: None,
             tool_choice: None,
         };
 
-        let resp = client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", config.api_key))
-            .header("Content-Type", "application/json")
-            .json(&req)
-            .send()
-            .await?;
-
-        if !resp.status().is_success() {
-            let body = resp.text().await?;
-            return Err(anyhow!("AI API error: {}", body));
-        }
-
-        let mut stream = resp.bytes_stream();
-        let mut buffer = String::new();
+        let mut events = Self::open_final_stream(&client, &url, config, req, &sender).await?;
+        let mut reasoning = model_capability::ReasoningBuffer::default();
         let mut dsml_detected = false;
 
-        while let Some(chunk) = stream.next().await {
-            let chunk = chunk?;
-            buffer.push_str(&String::from_utf8_lossy(&chunk));
-
-            while let Some(line_end) = buffer.find('\n') {
-                let line = buffer[..line_end].trim().to_string();
-                buffer = buffer[line_end + 1..].to_string();
-
-                if line.is_empty() || line == "data: [DONE]" {
-                    // done 事件由 ai_pick_cmd.rs 统一发送，此处不再发送
-                    continue;
-                }
+        while let Some(event) = events.next_event().await {
+            let event = event?;
+            if event.is_done() {
+                Self::send_thinking(&sender, reasoning.flush()).await;
+                // done 事件由 ai_pick_cmd.rs 统一发送，此处不再发送
+                continue;
+            }
 
-                if let Some(data) = line.strip_prefix("data: ") {
-                    if let Ok(chunk_resp) = serde_json::from_str::<ChatCompletionResponse>(data) {
-                        if let Some(choice) = chunk_resp.choices.first() {
-                            if let Some(delta) = &choice.delta {
-                                if let Some(content) = &delta.content {
-                                    if content.contains("<\u{ff5c}") || content.contains("DSML") || content.contains("<｜") {
-                                        dsml_detected = true;
-                                    }
-                                    full_content.push_str(content);
-                                    if !dsml_detected {
-                                        let _ = sender.send(AIStreamEvent {
-                                            event_type: "content".to_string(),
-                                            content: Some(content.clone()),
-                                            done: false,
-                                            usage: None,
-                                            tool_name: None,
-                                        }).await;
-                                    }
-                                }
-                            }
+            for chunk_resp in parse_stream_chunks(&event.data) {
+                if let Some(choice) = chunk_resp.choices.first() {
+                    if let Some(delta) = &choice.delta {
+                        if let Some(r) = delta.reasoning_content.as_deref() {
+                            Self::send_thinking(&sender, reasoning.push(r)).await;
                         }
-                        if let Some(usage) = &chunk_resp.usage {
-                            total_usage = Some(match total_usage {
-                                Some(mut u) => {
-                                    u.prompt_tokens += usage.prompt_tokens;
-                                    u.completion_tokens += usage.completion_tokens;
-                                    u.total_tokens += usage.total_tokens;
-                                    u
-                                }
-                                None => usage.clone(),
-                            });
+                        if let Some(content) = &delta.content {
+                            Self::send_thinking(&sender, reasoning.flush()).await;
+                            if ai_postprocess::contains_artifact_marker(content) {
+                                dsml_detected = true;
+                            }
+                            full_content.push_str(content);
+                            if !dsml_detected {
+                                let _ = sender.send(AIStreamEvent {
+                                    event_type: "content".to_string(),
+                                    content: Some(content.clone()),
+                                    done: false,
+                                    usage: None,
+                                    tool_name: None,
+                                }).await;
+                            }
                         }
                     }
                 }
+                if let Some(usage) = &chunk_resp.usage {
+                    total_usage = Some(match total_usage {
+                        Some(mut u) => {
+                            u.prompt_tokens += usage.prompt_tokens;
+                            u.completion_tokens += usage.completion_tokens;
+                            u.total_tokens += usage.total_tokens;
+                            u
+                        }
+                        None => usage.clone(),
+                    });
+                }
             }
         }
 
-        let clean_content = clean_dsml_artifacts(&full_content);
+        session.raw_content = full_content.clone();
+        let clean_content = ai_postprocess::clean(&full_content);
         Ok((clean_content, total_usage))
     }
 
     /// 败因分析 Agent：对某日亏损股进行归因分析
+    #[allow(clippy::too_many_arguments)]
     pub async fn analyze_loss_reasons_with_tools(
         config: &AIConfig,
         date: &str,
         loss_stocks: &[crate::models::tracking::LossStock],
-        qgqp_b_id: &str,
+        tool_ctx: &stock_tools::ToolContext,
         sender: tokio::sync::mpsc::Sender<AIStreamEvent>,
         max_tool_rounds: usize,
         max_token_budget: u32,
+        session: &mut AgentSession,
     ) -> Result<(String, Option<TokenUsage>)> {
         log::info!("[ai_service] analyze_loss_reasons_with_tools date={} stocks={} model={}", date, loss_stocks.len(), config.model_name);
         let client = build_ai_client(config.timeout_secs)?;
@@ -1387,6 +1912,7 @@ impl AIService {
             - search_stock_news：个股新闻\n\
             - get_stock_notices：公司公告\n\
             - get_industry_report：研报\n\
+            - get_stock_profile：公司简介与主营构成\n\
             - search_concept_boards：概念板块搜索\n\
             \n\
             # 分析要求\n\
@@ -1436,10 +1962,11 @@ impl AIService {
 
         let mut full_content = String::new();
         let mut total_usage: Option<TokenUsage> = None;
+        let mut tool_cache = stock_tools::ToolResultCache::default();
         let mut budget_exceeded = false;
 
         // Phase 1: Tool calling loop
-        for _round in 0..max_tool_rounds {
+        for round in 0..max_tool_rounds {
             if budget_exceeded {
                 break;
             }
@@ -1458,41 +1985,14 @@ impl AIService {
                 model: config.model_name.clone(),
                 messages: messages.clone(),
                 max_tokens: Some(config.max_tokens),
+                max_completion_tokens: None,
                 temperature: Some(config.pick_temperature),
                 stream: Some(false),
                 tools: Some(tools.clone()),
                 tool_choice: None,
             };
 
-            let req_json = serde_json::to_value(&req)
-                .map_err(|e| anyhow!("Failed to serialize request: {}", e))?;
-            let body = {
-                let client_ref = &client;
-                let url_ref = &url;
-                let api_key = &config.api_key;
-                let req_json_ref = &req_json;
-                retry_with_backoff(2, || async {
-                    let resp = client_ref
-                        .post(url_ref.as_str())
-                        .header("Authorization", format!("Bearer {}", api_key))
-                        .header("Content-Type", "application/json")
-                        .json(req_json_ref)
-                        .send()
-                        .await
-                        .map_err(|e| anyhow!("AI API request failed: {}", e))?;
-
-                    if !resp.status().is_success() {
-                        let status = resp.status();
-                        let body = resp.text().await.unwrap_or_default();
-                        return Err(anyhow!("AI API error ({}): {}", status.as_u16(), body));
-                    }
-
-                    resp.text().await.map_err(|e| anyhow!("Read response body failed: {}", e))
-                }).await?
-            };
-
-            let response: ChatCompletionResponse = serde_json::from_str(&body)
-                .map_err(|e| anyhow!("AI response parse error: {} body: {}", e, &body[..500.min(body.len())]))?;
+            let response = Self::request_tool_round(&client, &url, config, req, 2, round == 0, &sender).await?;
 
             if let Some(usage) = &response.usage {
                 total_usage = Some(match total_usage {
@@ -1559,10 +2059,9 @@ impl AIService {
                         tool_name: Some(tool_name.clone()),
                     }).await;
 
-                    let result = match stock_tools::execute_pick_tool(tool_name, tool_args, qgqp_b_id).await {
-                        Ok(r) => r,
-                        Err(e) => format!("工具调用失败: {}", e),
-                    };
+                    let result = Self::execute_cached_tool(&mut tool_cache, session, round, tool_name, tool_args, || {
+                        stock_tools::execute_pick_tool(tool_name, tool_args, tool_ctx)
+                    }).await;
 
                     let summary = stock_tools::summarize_tool_result(tool_name, &result);
 
@@ -1603,101 +2102,134 @@ impl AIService {
             model: config.model_name.clone(),
             messages: messages.clone(),
             max_tokens: Some(loss_max_tokens),
+            max_completion_tokens: None,
             temperature: Some(config.pick_temperature),
             stream: Some(true),
             tools: None,
             tool_choice: None,
         };
 
-        let resp = client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", config.api_key))
-            .header("Content-Type", "application/json")
-            .json(&req)
-            .send()
-            .await?;
-
-        if !resp.status().is_success() {
-            let body = resp.text().await?;
-            return Err(anyhow!("AI API error: {}", body));
-        }
-
-        let mut stream = resp.bytes_stream();
-        let mut buffer = String::new();
+        let mut events = Self::open_final_stream(&client, &url, config, req, &sender).await?;
+        let mut reasoning = model_capability::ReasoningBuffer::default();
         let mut dsml_detected = false;
 
-        while let Some(chunk) = stream.next().await {
-            let chunk = chunk?;
-            buffer.push_str(&String::from_utf8_lossy(&chunk));
-
-            while let Some(line_end) = buffer.find('\n') {
-                let line = buffer[..line_end].trim().to_string();
-                buffer = buffer[line_end + 1..].to_string();
-
-                if line.is_empty() || line == "data: [DONE]" {
-                    continue;
-                }
+        while let Some(event) = events.next_event().await {
+            let event = event?;
+            if event.is_done() {
+                Self::send_thinking(&sender, reasoning.flush()).await;
+                continue;
+            }
 
-                if let Some(data) = line.strip_prefix("data: ") {
-                    if let Ok(chunk_resp) = serde_json::from_str::<ChatCompletionResponse>(data) {
-                        if let Some(choice) = chunk_resp.choices.first() {
-                            if let Some(delta) = &choice.delta {
-                                if let Some(content) = &delta.content {
-                                    if content.contains("<\u{ff5c}") || content.contains("DSML") || content.contains("<｜") {
-                                        dsml_detected = true;
-                                    }
-                                    full_content.push_str(content);
-                                    if !dsml_detected {
-                                        let _ = sender.send(AIStreamEvent {
-                                            event_type: "content".to_string(),
-                                            content: Some(content.clone()),
-                                            done: false,
-                                            usage: None,
-                                            tool_name: None,
-                                        }).await;
-                                    }
-                                }
-                            }
+            for chunk_resp in parse_stream_chunks(&event.data) {
+                if let Some(choice) = chunk_resp.choices.first() {
+                    if let Some(delta) = &choice.delta {
+                        if let Some(r) = delta.reasoning_content.as_deref() {
+                            Self::send_thinking(&sender, reasoning.push(r)).await;
                         }
-                        if let Some(usage) = &chunk_resp.usage {
-                            total_usage = Some(match total_usage {
-                                Some(mut u) => {
-                                    u.prompt_tokens += usage.prompt_tokens;
-                                    u.completion_tokens += usage.completion_tokens;
-                                    u.total_tokens += usage.total_tokens;
-                                    u
-                                }
-                                None => usage.clone(),
-                            });
+                        if let Some(content) = &delta.content {
+                            Self::send_thinking(&sender, reasoning.flush()).await;
+                            if ai_postprocess::contains_artifact_marker(content) {
+                                dsml_detected = true;
+                            }
+                            full_content.push_str(content);
+                            if !dsml_detected {
+                                let _ = sender.send(AIStreamEvent {
+                                    event_type: "content".to_string(),
+                                    content: Some(content.clone()),
+                                    done: false,
+                                    usage: None,
+                                    tool_name: None,
+                                }).await;
+                            }
                         }
                     }
                 }
+                if let Some(usage) = &chunk_resp.usage {
+                    total_usage = Some(match total_usage {
+                        Some(mut u) => {
+                            u.prompt_tokens += usage.prompt_tokens;
+                            u.completion_tokens += usage.completion_tokens;
+                            u.total_tokens += usage.total_tokens;
+                            u
+                        }
+                        None => usage.clone(),
+                    });
+                }
             }
         }
 
-        let clean_content = clean_dsml_artifacts(&full_content);
+        session.raw_content = full_content.clone();
+        let clean_content = ai_postprocess::clean(&full_content);
         Ok((clean_content, total_usage))
     }
 }
 
-fn extract_json_array(text: &str) -> Result<String> {
-    let text = text.trim();
-    if let Some(start) = text.find('[') {
-        if let Some(end) = text.rfind(']') {
-            return Ok(text[start..=end].to_string());
-        }
+/// 从选股报告中解析 <PICKS> 推荐列表（与前端 parseRecommendations 规则一致）
+/// 缺少 code/name 的条目被丢弃，代码统一为 sh/sz/bj 前缀格式
+pub fn parse_picks(content: &str) -> Vec<StockPick> {
+    let body = match (content.find("<PICKS>"), content.find("</PICKS>")) {
+        (Some(start), Some(end)) if end > start => &content[start + "<PICKS>".len()..end],
+        _ => return Vec::new(),
+    };
+    let items: Vec<serde_json::Value> = match serde_json::from_str(body.trim()) {
+        Ok(items) => items,
+        Err(e) => match ai_postprocess::repair_json_array(body).and_then(|json| serde_json::from_str(&json).ok()) {
+            Some(items) => items,
+            None => {
+                log::warn!("[ai_service] parse_picks invalid PICKS JSON: {}", e);
+                return Vec::new();
+            }
+        },
+    };
+    items
+        .into_iter()
+        .filter_map(|item| serde_json::from_value::<StockPick>(item).ok())
+        .filter(|p| !p.code.trim().is_empty() && !p.name.trim().is_empty())
+        .map(|mut p| {
+            p.code = format_stock_code(&p.code);
+            p
+        })
+        .collect()
+}
+
+/// 用给定的推荐列表替换报告中的 <PICKS> 块（列表为空时整块移除）；报告中没有 <PICKS> 时原样返回
+pub fn replace_picks(content: &str, picks: &[StockPick]) -> String {
+    let (start, end) = match (content.find("<PICKS>"), content.find("</PICKS>")) {
+        (Some(start), Some(end)) if end > start => (start, end + "</PICKS>".len()),
+        _ => return content.to_string(),
+    };
+    if picks.is_empty() {
+        return format!("{}{}", content[..start].trim_end(), &content[end..]);
     }
-    let stripped = text
-        .trim_start_matches("```json")
-        .trim_start_matches("```")
-        .trim_end_matches("```")
-        .trim();
-    if let Some(start) = stripped.find('[') {
-        if let Some(end) = stripped.rfind(']') {
-            return Ok(stripped[start..=end].to_string());
-        }
+    let json = serde_json::to_string_pretty(picks).unwrap_or_else(|_| "[]".to_string());
+    format!("{}<PICKS>\n{}\n</PICKS>{}", &content[..start], json, &content[end..])
+}
+
+fn find_position<'a>(positions: &'a [UserPosition], code: &str) -> Option<&'a UserPosition> {
+    let code = format_stock_code(code);
+    positions.iter().find(|p| format_stock_code(&p.code) == code)
+}
+
+/// 按持仓标记指令，并把模型混用的指令类型映射到对应场景：
+/// 持仓股 buy→add、watch→hold；未持仓股 add→buy、hold→watch、trim→eliminate
+fn apply_position_actions(instructions: &mut [StockInstructionResult], positions: &[UserPosition]) {
+    for ins in instructions.iter_mut() {
+        ins.held = find_position(positions, &ins.code).is_some();
+        let action = ins.action.trim().to_lowercase();
+        let mapped = match (ins.held, action.as_str()) {
+            (true, "buy") => "add",
+            (true, "watch") => "hold",
+            (false, "add") => "buy",
+            (false, "hold") => "watch",
+            (false, "trim") => "eliminate",
+            _ => action.as_str(),
+        };
+        ins.action = mapped.to_string();
     }
-    Err(anyhow!("Cannot find JSON array in AI response"))
+}
+
+fn extract_json_array(text: &str) -> Result<String> {
+    ai_postprocess::repair_json_array(text).ok_or_else(|| anyhow!("Cannot find JSON array in AI response"))
 }
 
 fn tool_name_to_chinese(name: &str) -> &str {
@@ -1706,21 +2238,33 @@ fn tool_name_to_chinese(name: &str) -> &str {
         "get_kline_data" => "K线数据",
         "get_technical_indicators" => "技术指标",
         "get_fund_flow" => "资金流向",
+        "get_stock_profile" => "公司简介",
+        "get_earnings_forecast" => "盈利预测",
+        "get_peer_comparison" => "同业对比",
+        "get_seasonality" => "季节性统计",
+        "get_stock_theme_exposure" => "题材暴露",
         _ => name,
     }
 }
 
-/// 清理 DeepSeek DSML 标记等模型内部格式
-fn clean_dsml_artifacts(content: &str) -> String {
-    // 匹配 <｜...｜> 相关的 DSML 块（包括 function_calls 等）
-    let re = regex::Regex::new(r"<[｜\u{ff5c}][^>]*>[\s\S]*").unwrap_or_else(|_| regex::Regex::new(".^").unwrap());
-    let cleaned = re.replace(content, "").to_string();
-    // 清理未闭合的 <PICKS> 标签（AI 输出截断时可能残留）
-    let re_picks = regex::Regex::new(r"<PICKS>[\s\S]*").unwrap_or_else(|_| regex::Regex::new(".^").unwrap());
-    let cleaned = if cleaned.contains("<PICKS>") && !cleaned.contains("</PICKS>") {
-        re_picks.replace(&cleaned, "").to_string()
-    } else {
-        cleaned
-    };
-    cleaned.trim_end().to_string()
+/// 解析流式响应中的 data 字段
+/// 部分兼容服务未按规范用空行分隔事件，多条 JSON 会被拼进同一个多行 data，此时逐行解析；
+/// 无法解析的片段记录告警而不是静默丢弃
+fn parse_stream_chunks(data: &str) -> Vec<ChatCompletionResponse> {
+    if let Ok(resp) = serde_json::from_str::<ChatCompletionResponse>(data) {
+        return vec![resp];
+    }
+    let mut chunks = Vec::new();
+    for line in data.lines() {
+        let line = line.trim();
+        let line = line.strip_prefix("data:").map(str::trim_start).unwrap_or(line);
+        if line.is_empty() || line == "[DONE]" {
+            continue;
+        }
+        match serde_json::from_str::<ChatCompletionResponse>(line) {
+            Ok(resp) => chunks.push(resp),
+            Err(e) => log::warn!("[ai_service] malformed stream chunk: {} data={}", e, line.chars().take(200).collect::<String>()),
+        }
+    }
+    chunks
 }
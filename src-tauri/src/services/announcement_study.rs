@@ -0,0 +1,258 @@
+use anyhow::{anyhow, Result};
+use chrono::Local;
+use futures::stream::{self, StreamExt};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, OnceLock, RwLock};
+use tauri::Manager;
+
+use crate::AppState;
+use crate::db::database::Database;
+use crate::models::news::{AnnouncementEvent, AnnouncementEventStats, AnnouncementItem, AnnouncementStudyResult};
+use crate::services::job_scheduler::{JobSpec, JobTrigger};
+use crate::services::news_service;
+use crate::services::risk_metrics;
+use crate::services::stock_data::format_stock_code;
+use crate::services::theme_study::{mean, round2, t_stat, win_rate, CloseSeries};
+
+/// 参与研究的公告类别：(类别, 标题关键词, 排除词)；排除进展、结果类的重复公告
+const CATEGORIES: [(&str, &[&str], &[&str]); 3] = [
+    ("回购", &["回购"], &["进展", "结果", "完成", "注销", "限制性股票", "终止"]),
+    ("业绩预增", &["预增", "扭亏", "业绩大幅增长", "业绩大幅上升"], &[]),
+    ("减持", &["减持"], &["进展", "结果", "完成", "届满", "终止"]),
+];
+/// 异常收益的持有期（交易日）
+const HORIZONS: [usize; 3] = [1, 5, 20];
+/// 默认研究区间（天）
+pub const DEFAULT_STUDY_DAYS: i64 = 365;
+/// AI 引用统计所需的最少事件数
+pub const MIN_CITE_EVENTS: usize = 10;
+/// 每次归档拉取的全市场公告页数（每页 100 条）
+const ARCHIVE_PAGES: u32 = 3;
+const ARCHIVE_INTERVAL_SECS: u64 = 1800;
+/// 回补历史公告的股票数上限与并发数
+const BACKFILL_LIMIT: usize = 300;
+const BACKFILL_CONCURRENCY: usize = 4;
+
+/// 按标题归类公告，不属于研究类别返回 None
+pub fn classify(title: &str) -> Option<&'static str> {
+    CATEGORIES
+        .iter()
+        .find(|(_, keywords, excludes)| {
+            keywords.iter().any(|k| title.contains(k)) && !excludes.iter().any(|k| title.contains(k))
+        })
+        .map(|(category, _, _)| *category)
+}
+
+/// 把公告列表中属于研究类别的转换为事件
+fn to_events(items: &[AnnouncementItem]) -> Vec<AnnouncementEvent> {
+    items
+        .iter()
+        .filter(|a| !a.stock_code.is_empty() && a.notice_date.len() >= 10)
+        .filter_map(|a| {
+            Some(AnnouncementEvent {
+                id: a.id.clone(),
+                code: format_stock_code(&a.stock_code),
+                name: a.stock_name.clone(),
+                notice_date: a.notice_date[..10].to_string(),
+                category: classify(&a.title)?.to_string(),
+                title: a.title.clone(),
+            })
+        })
+        .collect()
+}
+
+fn cache() -> &'static RwLock<Option<Arc<AnnouncementStudyResult>>> {
+    static CACHE: OnceLock<RwLock<Option<Arc<AnnouncementStudyResult>>>> = OnceLock::new();
+    CACHE.get_or_init(|| RwLock::new(None))
+}
+
+/// 最近一次研究中某类公告的统计，事件数不足 MIN_CITE_EVENTS 时返回 None（供 AI 工具引用）
+pub fn cached_stats(category: &str) -> Option<AnnouncementEventStats> {
+    let result = cache().read().unwrap().as_ref().cloned()?;
+    result
+        .stats
+        .iter()
+        .find(|s| s.category == category && s.events >= MIN_CITE_EVENTS)
+        .cloned()
+}
+
+/// 单个事件的异常收益：[公告日, 1 日, 5 日, 20 日]；个股与基准交易日对不上时为 None
+fn abnormal_returns(series: &CloseSeries, benchmark: &CloseSeries, notice_date: &str) -> Option<[Option<f64>; 4]> {
+    let entry = series.entry_index(notice_date)?;
+    let bench = benchmark
+        .entry_index(notice_date)
+        .filter(|&b| benchmark.dates[b] == series.dates[entry])?;
+    let ar = |from: usize, bench_from: usize, days: usize| {
+        Some(series.forward_return(from, days)? - benchmark.forward_return(bench_from, days)?)
+    };
+    let day0 = (entry > 0 && bench > 0).then(|| ar(entry - 1, bench - 1, 1)).flatten();
+    Some([day0, ar(entry, bench, HORIZONS[0]), ar(entry, bench, HORIZONS[1]), ar(entry, bench, HORIZONS[2])])
+}
+
+/// 按类别汇总异常收益；同一股票同一天的同类公告只计一次
+fn compute_stats(
+    events: &[AnnouncementEvent],
+    histories: &HashMap<String, CloseSeries>,
+    benchmark: &CloseSeries,
+) -> Vec<AnnouncementEventStats> {
+    let mut seen = HashSet::new();
+    let mut by_category: HashMap<&str, Vec<[Option<f64>; 4]>> = HashMap::new();
+    for e in events {
+        if !seen.insert((e.code.as_str(), e.category.as_str(), e.notice_date.as_str())) {
+            continue;
+        }
+        let Some(series) = histories.get(&e.code) else {
+            continue;
+        };
+        if let Some(ars) = abnormal_returns(series, benchmark, &e.notice_date) {
+            by_category.entry(e.category.as_str()).or_default().push(ars);
+        }
+    }
+
+    CATEGORIES
+        .iter()
+        .filter_map(|(category, _, _)| {
+            let samples = by_category.get(category)?;
+            let column = |i: usize| samples.iter().filter_map(|s| s[i]).collect::<Vec<f64>>();
+            let ar_5d = column(2);
+            Some(AnnouncementEventStats {
+                category: category.to_string(),
+                events: samples.len(),
+                avg_ar_0d: mean(&column(0)).map(round2),
+                avg_ar_1d: mean(&column(1)).map(round2),
+                avg_ar_5d: mean(&ar_5d).map(round2),
+                avg_ar_20d: mean(&column(3)).map(round2),
+                win_rate_5d: win_rate(&ar_5d).map(round2),
+                t_stat_5d: t_stat(&ar_5d).map(round2),
+            })
+        })
+        .collect()
+}
+
+/// 公告事件研究：近 days 天归档的分类公告，结合本地日线计算公告后的平均异常收益（相对沪深300），结果缓存供 AI 引用
+pub async fn run_study(db: &Database, days: i64) -> Result<AnnouncementStudyResult> {
+    let today = Local::now().date_naive();
+    let start = (today - chrono::Duration::days(days)).format("%Y-%m-%d").to_string();
+    let end = today.format("%Y-%m-%d").to_string();
+    // 多取几天日线，保证公告日前一交易日的收盘价可用
+    let history_start = (today - chrono::Duration::days(days + 10)).format("%Y-%m-%d").to_string();
+
+    let events = db.get_announcement_events(&start)?;
+    let benchmark = CloseSeries::from_klines(&risk_metrics::benchmark_klines().await?);
+    let mut histories = HashMap::new();
+    for code in events.iter().map(|e| &e.code).collect::<HashSet<_>>() {
+        let rows = db.get_daily_history_range(code, &history_start, &end)?;
+        if !rows.is_empty() {
+            histories.insert(code.clone(), CloseSeries::from_history(&rows));
+        }
+    }
+
+    let result = AnnouncementStudyResult {
+        start,
+        end,
+        announcements: events.len(),
+        stats: compute_stats(&events, &histories, &benchmark),
+        updated_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+    };
+    *cache().write().unwrap() = Some(Arc::new(result.clone()));
+    log::info!("[announcement_study] {} announcements, {} stocks with history", events.len(), histories.len());
+    Ok(result)
+}
+
+/// 为本地已同步日线的股票回补历史公告（每只最近 100 条），返回新增事件数
+pub async fn backfill(db: &Database) -> Result<usize> {
+    let mut codes: Vec<String> = db.get_latest_history_dates()?.into_keys().collect();
+    if codes.is_empty() {
+        return Err(anyhow!("本地暂无日线数据，请先同步历史K线"));
+    }
+    codes.sort();
+    codes.truncate(BACKFILL_LIMIT);
+    let batches: Vec<Vec<AnnouncementEvent>> = stream::iter(codes)
+        .map(|code| async move {
+            match news_service::fetch_announcements(Some(&code), 1, 100).await {
+                Ok(items) => to_events(&items),
+                Err(e) => {
+                    log::warn!("[announcement_study] fetch announcements {} failed: {}", code, e);
+                    vec![]
+                }
+            }
+        })
+        .buffer_unordered(BACKFILL_CONCURRENCY)
+        .collect()
+        .await;
+    let events: Vec<AnnouncementEvent> = batches.into_iter().flatten().collect();
+    db.save_announcement_events(&events)
+}
+
+/// 后台任务：定时归档全市场最新的分类公告，有新增时重新计算事件研究
+pub fn announcement_archive_job() -> JobSpec {
+    JobSpec {
+        id: "announcement_archive",
+        name: "公告事件归档",
+        trigger: JobTrigger::Interval,
+        interval_secs: ARCHIVE_INTERVAL_SECS,
+        run: |app| Box::pin(async move {
+            let db = &app.state::<AppState>().db;
+            let mut inserted = 0;
+            for page in 1..=ARCHIVE_PAGES {
+                let items = news_service::fetch_announcements(None, page, 100).await?;
+                inserted += db.save_announcement_events(&to_events(&items))?;
+            }
+            if inserted > 0 || cache().read().unwrap().is_none() {
+                run_study(db, DEFAULT_STUDY_DAYS).await?;
+            }
+            Ok(Some(format!("归档 {} 条分类公告", inserted)))
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series(closes: &[f64]) -> CloseSeries {
+        CloseSeries {
+            dates: (0..closes.len()).map(|i| format!("2024-05-{:02}", i + 1)).collect(),
+            closes: closes.to_vec(),
+        }
+    }
+
+    fn event(code: &str, date: &str, category: &str) -> AnnouncementEvent {
+        AnnouncementEvent {
+            id: format!("{}{}", code, date),
+            code: code.to_string(),
+            name: code.to_string(),
+            notice_date: date.to_string(),
+            category: category.to_string(),
+            title: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(classify("关于以集中竞价交易方式回购公司股份的方案"), Some("回购"));
+        assert_eq!(classify("关于回购股份进展情况的公告"), None);
+        assert_eq!(classify("2024年半年度业绩预增公告"), Some("业绩预增"));
+        assert_eq!(classify("关于持股5%以上股东减持股份计划的预披露公告"), Some("减持"));
+        assert_eq!(classify("第八届董事会第三次会议决议公告"), None);
+    }
+
+    #[test]
+    fn test_compute_stats() {
+        let mut closes = vec![10.0, 10.0, 11.0];
+        closes.extend([11.0; 25]);
+        let histories = HashMap::from([("sh600000".to_string(), series(&closes))]);
+        let benchmark = series(&[100.0; 28]);
+        let events = vec![
+            event("sh600000", "2024-05-03", "回购"),
+            event("sh600000", "2024-05-03", "回购"),
+            event("sz000001", "2024-05-03", "减持"),
+        ];
+        let stats = compute_stats(&events, &histories, &benchmark);
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].events, 1);
+        assert!((stats[0].avg_ar_0d.unwrap() - 10.0).abs() < 1e-9);
+        assert_eq!(stats[0].avg_ar_5d, Some(0.0));
+        assert_eq!(stats[0].avg_ar_20d, Some(0.0));
+    }
+}
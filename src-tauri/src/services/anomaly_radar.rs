@@ -0,0 +1,225 @@
+use anyhow::Result;
+use chrono::{Local, Timelike};
+use futures::stream::{self, StreamExt};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+
+use crate::AppState;
+use crate::db::database::Database;
+use crate::models::stock::MarketStockSnapshot;
+use crate::models::watchlist::{AnomalyEvent, KlineItem};
+use crate::services::history_kline::HistoryKlineService;
+use crate::services::market_scanner::MarketScanner;
+use crate::services::job_scheduler::{JobSpec, JobTrigger};
+use crate::services::notifier;
+
+/// 当前分钟成交量达到此前分钟均量的倍数即为放量异动
+const VOLUME_SPIKE_RATIO: f64 = 5.0;
+/// 计算分钟均量至少需要的当日分钟数（不含开盘第一分钟）
+const MIN_BASELINE_BARS: usize = 10;
+/// 5 分钟内涨跌幅超过该值即为急涨/急跌 %
+const RAPID_MOVE_PCT: f64 = 2.0;
+const RAPID_MOVE_BARS: usize = 5;
+/// 集合竞价开盘涨跌幅超过该值即为竞价异动 %
+const AUCTION_GAP_PCT: f64 = 3.0;
+/// 竞价异动只在开盘后这段时间内报告（HHMM）
+const AUCTION_REPORT_UNTIL: u32 = 935;
+/// 每只股票拉取的分钟K线数
+const MINUTE_BARS: u32 = 30;
+const FETCH_CONCURRENCY: usize = 4;
+/// 同一股票同类异动的最短报告间隔
+const COOLDOWN: Duration = Duration::from_secs(600);
+/// 内存中保留的最近异动数
+const MAX_RECENT_EVENTS: usize = 200;
+const SCAN_INTERVAL_SECS: u64 = 60;
+/// 前端监听的异动事件名
+pub const ANOMALY_EVENT: &str = "anomaly-radar";
+
+type Cooldowns = HashMap<String, Instant>;
+
+fn recent_events() -> &'static Mutex<Vec<AnomalyEvent>> {
+    static EVENTS: OnceLock<Mutex<Vec<AnomalyEvent>>> = OnceLock::new();
+    EVENTS.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+fn cooldowns() -> &'static Mutex<Cooldowns> {
+    static COOLDOWNS: OnceLock<Mutex<Cooldowns>> = OnceLock::new();
+    COOLDOWNS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn event(quote: &MarketStockSnapshot, anomaly_type: &str, description: String, score: f64, time: &str) -> AnomalyEvent {
+    AnomalyEvent {
+        code: quote.code.clone(),
+        name: quote.name.clone(),
+        anomaly_type: anomaly_type.to_string(),
+        description,
+        score,
+        price: quote.price,
+        change_pct: quote.change_pct,
+        time: time.to_string(),
+    }
+}
+
+/// 检测单只股票最新一分钟的异动。bars 为当日 1 分钟K线（按时间升序），now_hhmm 用于判断是否仍在竞价异动的报告时段
+pub fn detect_anomalies(quote: &MarketStockSnapshot, bars: &[KlineItem], now_hhmm: u32) -> Vec<AnomalyEvent> {
+    let mut events = Vec::new();
+    let time = bars.last().map(|b| b.date.clone()).unwrap_or_else(|| Local::now().format("%Y-%m-%d %H:%M").to_string());
+
+    if now_hhmm <= AUCTION_REPORT_UNTIL && quote.open > 0.0 && quote.pre_close > 0.0 {
+        let gap = (quote.open / quote.pre_close - 1.0) * 100.0;
+        if gap.abs() >= AUCTION_GAP_PCT {
+            let (kind, label) = if gap > 0.0 { ("auction_surge", "竞价高开") } else { ("auction_plunge", "竞价低开") };
+            events.push(event(quote, kind, format!("{} {:+.2}%", label, gap), gap.abs() / AUCTION_GAP_PCT, &time));
+        }
+    }
+
+    let Some(last) = bars.last() else { return events };
+    // 开盘第一分钟包含集合竞价成交量，不计入均量
+    if bars.len() > MIN_BASELINE_BARS + 1 {
+        let baseline = &bars[1..bars.len() - 1];
+        let avg = baseline.iter().map(|b| b.volume).sum::<f64>() / baseline.len() as f64;
+        if avg > 0.0 && last.volume >= avg * VOLUME_SPIKE_RATIO {
+            let ratio = last.volume / avg;
+            events.push(event(quote, "volume_spike", format!("分钟放量 {:.1} 倍于均量", ratio), ratio / VOLUME_SPIKE_RATIO, &time));
+        }
+    }
+    if bars.len() > RAPID_MOVE_BARS {
+        let base = &bars[bars.len() - 1 - RAPID_MOVE_BARS];
+        if base.close > 0.0 {
+            let pct = (last.close / base.close - 1.0) * 100.0;
+            if pct.abs() >= RAPID_MOVE_PCT {
+                let (kind, label) = if pct > 0.0 { ("rapid_rise", "急速拉升") } else { ("rapid_fall", "快速下跌") };
+                events.push(event(quote, kind, format!("{}分钟{} {:+.2}%", RAPID_MOVE_BARS, label, pct), pct.abs() / RAPID_MOVE_PCT, &time));
+            }
+        }
+    }
+    events
+}
+
+/// 扫描自选股与 AI 选股跟踪股票的盘中异动，按分值降序返回本次新出现的异动（同类异动 10 分钟内不重复报告）
+pub async fn scan_anomalies(db: &Database) -> Result<Vec<AnomalyEvent>> {
+    let mut seen = HashSet::new();
+    let codes: Vec<String> = db
+        .get_watchlist_stocks()?
+        .into_iter()
+        .map(|s| s.code)
+        .chain(db.get_tracking_stocks()?.into_iter().map(|t| t.code))
+        .filter(|c| seen.insert(c.clone()))
+        .collect();
+    if codes.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let quotes = MarketScanner::new()?.fetch_stocks_by_codes(&codes).await?;
+    let today = Local::now().format("%Y-%m-%d").to_string();
+    let now = Local::now();
+    let now_hhmm = now.hour() * 100 + now.minute();
+    let service = HistoryKlineService::new()?;
+    let results: Vec<Vec<AnomalyEvent>> = stream::iter(quotes)
+        .map(|quote| {
+            let service = &service;
+            let today = &today;
+            async move {
+                let bars = match service.fetch_minute_kline(&quote.code, "m1", MINUTE_BARS).await {
+                    Ok(bars) => bars.into_iter().filter(|b| b.date.starts_with(today.as_str())).collect(),
+                    Err(e) => {
+                        log::warn!("[anomaly_radar] fetch minute kline failed for {}: {}", quote.code, e);
+                        vec![]
+                    }
+                };
+                detect_anomalies(&quote, &bars, now_hhmm)
+            }
+        })
+        .buffer_unordered(FETCH_CONCURRENCY)
+        .collect()
+        .await;
+
+    let mut events: Vec<AnomalyEvent> = {
+        let mut cooldowns = cooldowns().lock().unwrap();
+        cooldowns.retain(|_, at| at.elapsed() < COOLDOWN);
+        results
+            .into_iter()
+            .flatten()
+            .filter(|e| {
+                let key = format!("{}:{}", e.code, e.anomaly_type);
+                if cooldowns.contains_key(&key) {
+                    return false;
+                }
+                cooldowns.insert(key, Instant::now());
+                true
+            })
+            .collect()
+    };
+    events.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+    if !events.is_empty() {
+        let mut recent = recent_events().lock().unwrap();
+        recent.splice(0..0, events.iter().cloned());
+        recent.truncate(MAX_RECENT_EVENTS);
+    }
+    log::info!("[anomaly_radar] scanned {} stocks, {} new anomalies", codes.len(), events.len());
+    Ok(events)
+}
+
+/// 今日已报告的异动（最新在前）
+pub fn get_recent_anomalies() -> Vec<AnomalyEvent> {
+    let today = Local::now().format("%Y-%m-%d").to_string();
+    let recent = recent_events().lock().unwrap();
+    recent.iter().filter(|e| e.time.starts_with(&today)).cloned().collect()
+}
+
+/// 后台任务：交易时段每分钟扫描一次，新异动通过 anomaly-radar 事件推送给前端，并推送到已配置的消息目标
+pub fn anomaly_radar_job() -> JobSpec {
+    JobSpec {
+        id: "anomaly_radar",
+        name: "盘中异动雷达",
+        trigger: JobTrigger::TradingHours,
+        interval_secs: SCAN_INTERVAL_SECS,
+        run: |app| Box::pin(async move {
+            let db = &app.state::<AppState>().db;
+            let events = scan_anomalies(db).await?;
+            if !events.is_empty() {
+                let _ = app.emit(ANOMALY_EVENT, &events);
+                let (title, content) = notifier::format_anomalies(&events);
+                notifier::notify(db, notifier::EVENT_ANOMALY, &title, &content).await;
+            }
+            Ok(Some(format!("新异动 {} 条", events.len())))
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(minute: usize, close: f64, volume: f64) -> KlineItem {
+        KlineItem {
+            date: format!("2024-06-06 10:{:02}", minute),
+            open: close,
+            close,
+            high: close,
+            low: close,
+            volume,
+            amount: 0.0,
+            change_pct: 0.0,
+            turnover_rate: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_detect_anomalies() {
+        let quote = MarketStockSnapshot { code: "sz000001".to_string(), open: 10.4, pre_close: 10.0, price: 10.3, ..Default::default() };
+        let mut bars: Vec<KlineItem> = (0..15).map(|i| bar(i, 10.0, if i == 0 { 5000.0 } else { 100.0 })).collect();
+        assert!(detect_anomalies(&quote, &bars, 1000).is_empty());
+
+        bars.push(bar(15, 10.25, 800.0));
+        let types: Vec<String> = detect_anomalies(&quote, &bars, 1000).into_iter().map(|e| e.anomaly_type).collect();
+        assert_eq!(types, vec!["volume_spike", "rapid_rise"]);
+
+        let early = detect_anomalies(&quote, &bars[..3], 931);
+        assert_eq!(early.len(), 1);
+        assert_eq!(early[0].anomaly_type, "auction_surge");
+    }
+}
@@ -0,0 +1,395 @@
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+use crate::AppState;
+use crate::error::AppError;
+use crate::models::settings::{ApiServerConfig, ApiServerStatus};
+use crate::services::mcp_server;
+use crate::services::quote_provider;
+use crate::services::signal_screener;
+use crate::services::stock_data::format_stock_code;
+use crate::services::stock_tools::ToolContext;
+use crate::services::watchlist_diagnose;
+use crate::services::watchlist_enriched;
+
+/// 请求头最大长度，超出直接拒绝
+const MAX_HEADER_BYTES: usize = 16 * 1024;
+/// 请求体最大长度（仅 MCP 接口读取请求体）
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+/// 单次行情查询的代码数上限
+const MAX_QUOTE_CODES: usize = 200;
+
+struct Running {
+    port: u16,
+    handle: JoinHandle<()>,
+}
+
+#[derive(Default)]
+struct ServerState {
+    running: Option<Running>,
+    error: Option<String>,
+}
+
+fn server() -> &'static Mutex<ServerState> {
+    static SERVER: OnceLock<Mutex<ServerState>> = OnceLock::new();
+    SERVER.get_or_init(|| Mutex::new(ServerState::default()))
+}
+
+/// 解析后的 HTTP 请求
+#[derive(Debug, PartialEq)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub query: HashMap<String, String>,
+    /// 请求头，名称小写
+    pub headers: HashMap<String, String>,
+}
+
+/// 解析请求行与请求头（不含请求体，接口参数均通过查询串传递）
+pub fn parse_request(head: &str) -> Option<Request> {
+    let mut lines = head.split("\r\n");
+    let mut parts = lines.next()?.split_whitespace();
+    let method = parts.next()?.to_uppercase();
+    let target = parts.next()?;
+    if !parts.next()?.starts_with("HTTP/1.") {
+        return None;
+    }
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let query = query
+        .split('&')
+        .filter(|p| !p.is_empty())
+        .map(|pair| {
+            let (k, v) = pair.split_once('=').unwrap_or((pair, ""));
+            (decode(k), decode(v))
+        })
+        .collect();
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(k, v)| (k.trim().to_lowercase(), v.trim().to_string()))
+        .collect();
+    Some(Request { method, path: path.trim_end_matches('/').to_string(), query, headers })
+}
+
+fn decode(s: &str) -> String {
+    let s = s.replace('+', " ");
+    urlencoding::decode(&s).map(|v| v.into_owned()).unwrap_or(s)
+}
+
+/// 令牌为空时不校验；否则接受 Authorization: Bearer <token> 或 ?token=<token>
+pub fn is_authorized(request: &Request, token: &str) -> bool {
+    if token.is_empty() {
+        return true;
+    }
+    let bearer = request
+        .headers
+        .get("authorization")
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim);
+    bearer == Some(token) || request.query.get("token").map(String::as_str) == Some(token)
+}
+
+fn status_for(error: &AppError) -> u16 {
+    match error {
+        AppError::InvalidInput(_) => 400,
+        AppError::Unauthorized(_) => 401,
+        AppError::NotFound(_) => 404,
+        AppError::Busy(_) | AppError::NotConfigured(_) => 409,
+        AppError::RateLimited(_) => 429,
+        AppError::Network(_) | AppError::Timeout(_) | AppError::Upstream(_) => 502,
+        _ => 500,
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        202 => "Accepted",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        429 => "Too Many Requests",
+        502 => "Bad Gateway",
+        _ => "Internal Server Error",
+    }
+}
+
+async fn write_json(stream: &mut TcpStream, status: u16, body: &str) -> std::io::Result<()> {
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason(status),
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+fn to_body<T: Serialize>(value: &T) -> std::result::Result<String, AppError> {
+    serde_json::to_string(value).map_err(|e| AppError::Internal(e.to_string()))
+}
+
+fn required<'a>(request: &'a Request, key: &str) -> std::result::Result<&'a str, AppError> {
+    request
+        .query
+        .get(key)
+        .map(|v| v.trim())
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| AppError::InvalidInput(format!("缺少参数 {}", key)))
+}
+
+/// 按路径分发到对应功能，返回 JSON 正文
+async fn route(app: &AppHandle, request: &Request) -> std::result::Result<String, AppError> {
+    let state = app.state::<AppState>();
+    let db = &state.db;
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/api/health") => to_body(&serde_json::json!({
+            "status": "ok",
+            "version": app.package_info().version.to_string(),
+        })),
+        // 实时行情：codes 以逗号分隔，如 sh600519,000001
+        ("GET", "/api/quotes") => {
+            let codes: Vec<String> = required(request, "codes")?
+                .split(',')
+                .map(str::trim)
+                .filter(|c| !c.is_empty())
+                .map(format_stock_code)
+                .collect();
+            if codes.len() > MAX_QUOTE_CODES {
+                return Err(AppError::InvalidInput(format!("单次最多查询 {} 只股票", MAX_QUOTE_CODES)));
+            }
+            let settings = db.load_settings()?;
+            let quotes = quote_provider::fetch_quotes(&settings, &codes).await?;
+            to_body(&quotes)
+        }
+        // 自选股列表，enriched=1 时返回估值、资金等多维度快照
+        ("GET", "/api/watchlist") => {
+            let stocks = db.get_watchlist_stocks()?;
+            if request.query.get("enriched").is_some_and(|v| v == "1" || v == "true") {
+                let codes: Vec<String> = stocks.iter().map(|s| s.code.clone()).collect();
+                return to_body(&watchlist_enriched::fetch(db, &codes).await?);
+            }
+            to_body(&stocks)
+        }
+        // 形态选股：signal 为信号名称，可选 board（板块代码）与 within_days
+        ("GET", "/api/screen") => {
+            let signal = required(request, "signal")?;
+            let within_days = match request.query.get("within_days") {
+                Some(v) => v.parse().map_err(|_| AppError::InvalidInput(format!("within_days 无效: {}", v)))?,
+                None => 3,
+            };
+            let board = request.query.get("board").map(String::as_str).filter(|b| !b.is_empty());
+            to_body(&signal_screener::screen_by_signal(db, signal, board, within_days).await?)
+        }
+        // AI 诊断单只股票，会消耗模型 Token，因此仅接受 POST
+        ("POST", "/api/diagnose") => {
+            let code = required(request, "code")?;
+            let name = request.query.get("name").map(String::as_str).unwrap_or_default();
+            let settings = db.load_settings()?;
+            let config = settings
+                .active_ai_config()
+                .ok_or_else(|| AppError::NotConfigured("未配置AI模型".to_string()))?;
+            let _guard = state.shutdown.track();
+            let analysis = watchlist_diagnose::diagnose_stock(
+                db,
+                app,
+                &config,
+                &ToolContext::from_settings(&settings),
+                code,
+                name,
+            )
+            .await?;
+            to_body(&analysis)
+        }
+        (_, "/api/health" | "/api/quotes" | "/api/watchlist" | "/api/screen" | "/api/diagnose") => {
+            Err(AppError::InvalidInput(format!("不支持的请求方法 {}", request.method)))
+        }
+        _ => Err(AppError::NotFound(format!("接口不存在: {}", request.path))),
+    }
+}
+
+/// 读取请求头（到空行为止），返回请求头与已读到的部分请求体
+async fn read_head(stream: &mut TcpStream) -> Result<(String, Vec<u8>)> {
+    let mut buf = Vec::with_capacity(1024);
+    let mut chunk = [0u8; 1024];
+    loop {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Err(anyhow!("连接已关闭"));
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(end) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            let rest = buf.split_off(end + 4);
+            buf.truncate(end);
+            return Ok((String::from_utf8_lossy(&buf).into_owned(), rest));
+        }
+        if buf.len() > MAX_HEADER_BYTES {
+            return Err(anyhow!("请求头过长"));
+        }
+    }
+}
+
+/// 按 Content-Length 读取请求体
+async fn read_body(stream: &mut TcpStream, request: &Request, mut body: Vec<u8>) -> Result<Vec<u8>> {
+    let length: usize = request.headers.get("content-length").and_then(|v| v.parse().ok()).unwrap_or(0);
+    if length > MAX_BODY_BYTES {
+        return Err(anyhow!("请求体过大"));
+    }
+    let mut chunk = [0u8; 8192];
+    while body.len() < length {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Err(anyhow!("连接已关闭"));
+        }
+        body.extend_from_slice(&chunk[..n]);
+    }
+    body.truncate(length);
+    Ok(body)
+}
+
+/// MCP Streamable HTTP 端点：POST 收发 JSON-RPC；不提供服务端推送流，GET 返回 405
+async fn serve_mcp(app: &AppHandle, stream: &mut TcpStream, request: &Request, partial: Vec<u8>) -> (u16, String) {
+    if request.method != "POST" {
+        return (405, String::new());
+    }
+    match tokio::time::timeout(READ_TIMEOUT, read_body(stream, request, partial)).await {
+        Ok(Ok(body)) => mcp_server::handle_http(app, &body).await,
+        Ok(Err(e)) => (400, to_body(&AppError::InvalidInput(e.to_string())).unwrap_or_default()),
+        Err(_) => (400, to_body(&AppError::Timeout("读取请求体超时".to_string())).unwrap_or_default()),
+    }
+}
+
+async fn handle_connection(app: AppHandle, mut stream: TcpStream) {
+    let (head, partial) = match tokio::time::timeout(READ_TIMEOUT, read_head(&mut stream)).await {
+        Ok(Ok(read)) => read,
+        _ => return,
+    };
+    let Some(request) = parse_request(&head) else {
+        let _ = write_json(&mut stream, 400, &to_body(&AppError::InvalidInput("请求格式不正确".to_string())).unwrap_or_default()).await;
+        return;
+    };
+
+    // 浏览器跨站请求会带 Origin，拒绝以防网页借用户本机发起调用
+    let config = app
+        .state::<AppState>()
+        .db
+        .load_settings()
+        .map(|s| s.api_server)
+        .unwrap_or_default();
+    let result = if request.headers.contains_key("origin") {
+        Err((403, AppError::Unauthorized("不接受浏览器跨站请求".to_string())))
+    } else if !is_authorized(&request, &config.token) {
+        Err((401, AppError::Unauthorized("访问令牌无效".to_string())))
+    } else if request.path == "/mcp" {
+        if config.mcp_enabled {
+            Ok(serve_mcp(&app, &mut stream, &request, partial).await)
+        } else {
+            Err((404, AppError::NotFound("MCP 服务未启用".to_string())))
+        }
+    } else {
+        route(&app, &request).await.map(|body| (200, body)).map_err(|e| (status_for(&e), e))
+    };
+
+    let (status, body) = match result {
+        Ok(response) => response,
+        Err((status, e)) => {
+            log::warn!("[api_server] {} {} failed status={}: {}", request.method, request.path, status, e);
+            (status, to_body(&e).unwrap_or_default())
+        }
+    };
+    log::info!("[api_server] {} {} status={}", request.method, request.path, status);
+    let _ = write_json(&mut stream, status, &body).await;
+}
+
+async fn serve(app: AppHandle, listener: TcpListener) {
+    loop {
+        let shutdown = &app.state::<AppState>().shutdown;
+        tokio::select! {
+            _ = shutdown.cancelled() => break,
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    tokio::spawn(handle_connection(app.clone(), stream));
+                }
+                Err(e) => log::warn!("[api_server] accept failed: {}", e),
+            },
+        }
+    }
+    log::info!("[api_server] stopped");
+}
+
+/// 按配置启动、重启或停止本机 API（保存设置与应用启动时调用）
+pub async fn apply(app: &AppHandle, config: &ApiServerConfig) {
+    let previous = {
+        let mut state = server().lock().unwrap();
+        if config.enabled && state.running.as_ref().is_some_and(|r| r.port == config.port && !r.handle.is_finished()) {
+            return;
+        }
+        state.error = None;
+        state.running.take()
+    };
+    if let Some(running) = previous {
+        running.handle.abort();
+        let _ = running.handle.await;
+        log::info!("[api_server] stopped port={}", running.port);
+    }
+    if !config.enabled {
+        return;
+    }
+
+    match TcpListener::bind(("127.0.0.1", config.port)).await {
+        Ok(listener) => {
+            log::info!("[api_server] listening on 127.0.0.1:{} token={}", config.port, !config.token.is_empty());
+            let handle = tokio::spawn(serve(app.clone(), listener));
+            server().lock().unwrap().running = Some(Running { port: config.port, handle });
+        }
+        Err(e) => {
+            log::error!("[api_server] bind port {} failed: {}", config.port, e);
+            server().lock().unwrap().error = Some(format!("端口 {} 启动失败: {}", config.port, e));
+        }
+    }
+}
+
+pub fn status() -> ApiServerStatus {
+    let state = server().lock().unwrap();
+    let running = state.running.as_ref().filter(|r| !r.handle.is_finished());
+    ApiServerStatus {
+        running: running.is_some(),
+        address: running.map(|r| format!("http://127.0.0.1:{}", r.port)),
+        error: state.error.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_request_and_auth() {
+        let head = "GET /api/quotes/?codes=sh600519%2C000001&token=abc HTTP/1.1\r\nHost: 127.0.0.1\r\nAuthorization: Bearer s3cret";
+        let request = parse_request(head).unwrap();
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.path, "/api/quotes");
+        assert_eq!(request.query["codes"], "sh600519,000001");
+        assert_eq!(request.headers["host"], "127.0.0.1");
+
+        assert!(is_authorized(&request, ""));
+        assert!(is_authorized(&request, "s3cret"));
+        assert!(is_authorized(&request, "abc"));
+        assert!(!is_authorized(&request, "other"));
+
+        assert!(parse_request("garbage").is_none());
+        assert_eq!(status_for(&AppError::InvalidInput(String::new())), 400);
+        assert_eq!(status_for(&AppError::Timeout(String::new())), 502);
+    }
+}
@@ -0,0 +1,237 @@
+use anyhow::Result;
+use chrono::{Duration as ChronoDuration, Local};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use tauri::{Emitter, Manager};
+
+use crate::AppState;
+use crate::db::database::Database;
+use crate::models::ai::StockSummaryForAI;
+use crate::models::stock::{AuctionGap, AuctionScanResult, MarketStockSnapshot, StockDailyHistory};
+use crate::services::job_scheduler::{JobSpec, JobTrigger};
+use crate::services::market_scanner::MarketScanner;
+use crate::services::notifier;
+use crate::services::strategy_zone;
+
+/// 竞价跳空幅度达到该值即为异动 %
+const GAP_PCT: f64 = 3.0;
+/// 竞价成交量达到 5 日均量的该百分比即为竞价爆量
+const VOLUME_PCT: f64 = 10.0;
+/// 计算 5 日均量至少需要的本地日线天数，不足时按量比估算
+const MIN_HISTORY_DAYS: usize = 3;
+/// 量比按全天 240 分钟折算，竞价阶段的累计开市时间按 1 分钟计
+const TRADING_MINUTES: f64 = 240.0;
+/// 结果保留的异动数
+const MAX_GAPS: usize = 100;
+/// 集合竞价不可撤单阶段扫描（HHMM），匹配价已基本稳定
+const SCAN_FROM: u32 = 922;
+const SCAN_UNTIL: u32 = 926;
+const CHECK_INTERVAL_SECS: u64 = 30;
+/// 前端监听的竞价异动事件名
+pub const AUCTION_GAP_EVENT: &str = "auction-gaps";
+
+fn latest() -> &'static Mutex<Option<AuctionScanResult>> {
+    static LATEST: OnceLock<Mutex<Option<AuctionScanResult>>> = OnceLock::new();
+    LATEST.get_or_init(|| Mutex::new(None))
+}
+
+/// 近 5 个交易日日均成交量（手）：优先用本地日线（不含今日），不足时由量比反推
+pub fn avg_volume_5d(quote: &MarketStockSnapshot, history: &[StockDailyHistory], today: &str) -> Option<f64> {
+    let past: Vec<f64> = history.iter().filter(|h| h.date.as_str() < today).map(|h| h.volume).collect();
+    if past.len() >= MIN_HISTORY_DAYS {
+        let recent = &past[past.len().saturating_sub(5)..];
+        let avg = recent.iter().sum::<f64>() / recent.len() as f64;
+        return (avg > 0.0).then_some(avg);
+    }
+    (quote.volume_ratio > 0.0 && quote.volume > 0.0).then(|| quote.volume * TRADING_MINUTES / quote.volume_ratio)
+}
+
+/// 截至昨日的连板天数
+pub fn limit_up_streak(history: &[StockDailyHistory], today: &str) -> u32 {
+    history
+        .iter()
+        .rev()
+        .filter(|h| h.date.as_str() < today)
+        .take_while(|h| h.is_limit_up)
+        .count() as u32
+}
+
+/// 判断单只股票的竞价是否异动：跳空幅度或竞价量放大任一达标
+pub fn evaluate(quote: &MarketStockSnapshot, avg_volume: Option<f64>, streak_days: u32) -> Option<AuctionGap> {
+    if quote.price <= 0.0 || quote.pre_close <= 0.0 || quote.volume <= 0.0 {
+        return None;
+    }
+    let gap_pct = (quote.price / quote.pre_close - 1.0) * 100.0;
+    let volume_pct = avg_volume.filter(|v| *v > 0.0).map_or(0.0, |avg| quote.volume / avg * 100.0);
+    if gap_pct.abs() < GAP_PCT && volume_pct < VOLUME_PCT {
+        return None;
+    }
+
+    let mut labels = Vec::new();
+    if gap_pct >= GAP_PCT {
+        labels.push("竞价高开".to_string());
+    } else if gap_pct <= -GAP_PCT {
+        labels.push("竞价低开".to_string());
+    }
+    if volume_pct >= VOLUME_PCT {
+        labels.push("竞价爆量".to_string());
+    }
+    if streak_days > 0 {
+        labels.push(format!("{}连板", streak_days));
+    }
+    let strength = gap_pct.abs() / GAP_PCT + volume_pct / VOLUME_PCT;
+    Some(AuctionGap {
+        code: quote.code.clone(),
+        name: quote.name.clone(),
+        pre_close: quote.pre_close,
+        price: quote.price,
+        gap_pct: (gap_pct * 100.0).round() / 100.0,
+        volume: quote.volume,
+        amount: quote.amount,
+        avg_volume_5d: avg_volume.unwrap_or(0.0).round(),
+        volume_pct: (volume_pct * 10.0).round() / 10.0,
+        turnover_rate: quote.turnover_rate,
+        streak_days,
+        score: (strength * 25.0).round().min(100.0) as u32,
+        labels,
+        zones: vec![],
+    })
+}
+
+/// 转为策略区间使用的竞价快照
+fn to_summary(gap: &AuctionGap) -> StockSummaryForAI {
+    StockSummaryForAI {
+        code: gap.code.clone(),
+        name: gap.name.clone(),
+        open_pct: gap.gap_pct,
+        current_pct: gap.gap_pct,
+        score: gap.score,
+        bid_amount: gap.amount,
+        streak_days: gap.streak_days,
+        turnover: gap.turnover_rate,
+        labels: gap.labels.clone(),
+    }
+}
+
+/// 扫描集合竞价异动（全市场或自选股），按强度排序并标注落入的策略区间
+pub async fn scan(db: &Database, watchlist_only: bool) -> Result<AuctionScanResult> {
+    let scanner = MarketScanner::new()?;
+    let quotes = if watchlist_only {
+        let codes: Vec<String> = db.get_watchlist_stocks()?.into_iter().map(|s| s.code).collect();
+        if codes.is_empty() {
+            vec![]
+        } else {
+            scanner.fetch_stocks_by_codes(&codes).await?
+        }
+    } else {
+        scanner.scan_full_market().await?
+    };
+
+    let now = Local::now();
+    let today = now.format("%Y-%m-%d").to_string();
+    let since = (now - ChronoDuration::days(14)).format("%Y-%m-%d").to_string();
+    let histories = db.get_daily_histories_since(&since).unwrap_or_else(|e| {
+        log::warn!("[auction_scanner] load daily history failed: {}", e);
+        HashMap::new()
+    });
+
+    let mut gaps: Vec<AuctionGap> = quotes
+        .iter()
+        .filter_map(|q| {
+            let history = histories.get(&q.code).map(Vec::as_slice).unwrap_or_default();
+            evaluate(q, avg_volume_5d(q, history, &today), limit_up_streak(history, &today))
+        })
+        .collect();
+    gaps.sort_by(|a, b| b.score.cmp(&a.score).then(b.gap_pct.total_cmp(&a.gap_pct)));
+    gaps.truncate(MAX_GAPS);
+
+    let zones = db.load_settings()?.strategy_zones;
+    let summaries: Vec<StockSummaryForAI> = gaps.iter().map(to_summary).collect();
+    for members in strategy_zone::classify(&zones, &summaries) {
+        for stock in &members.stocks {
+            if let Some(gap) = gaps.iter_mut().find(|g| g.code == stock.code) {
+                gap.zones.push(members.zone_name.clone());
+            }
+        }
+    }
+
+    let result = AuctionScanResult {
+        time: now.format("%Y-%m-%d %H:%M:%S").to_string(),
+        scope: if watchlist_only { "watchlist" } else { "market" }.to_string(),
+        scanned: quotes.len(),
+        gaps,
+    };
+    log::info!("[auction_scanner] scope={} scanned={} gaps={}", result.scope, result.scanned, result.gaps.len());
+    *latest().lock().unwrap() = Some(result.clone());
+    Ok(result)
+}
+
+/// 今日最近一次扫描结果
+pub fn get_latest() -> Option<AuctionScanResult> {
+    let today = Local::now().format("%Y-%m-%d").to_string();
+    latest().lock().unwrap().as_ref().filter(|r| r.time.starts_with(&today)).cloned()
+}
+
+/// 后台任务：交易日 09:22~09:26 扫描一次竞价异动，通过 auction-gaps 事件推送给前端，并推送到已配置的消息目标
+pub fn auction_scan_job() -> JobSpec {
+    JobSpec {
+        id: "auction_scan",
+        name: "竞价异动扫描",
+        trigger: JobTrigger::DailyWindow(SCAN_FROM, SCAN_UNTIL),
+        interval_secs: CHECK_INTERVAL_SECS,
+        run: |app| Box::pin(async move {
+            let db = &app.state::<AppState>().db;
+            let watchlist_only = db.load_settings()?.auction_scan_watchlist_only;
+            let result = scan(db, watchlist_only).await?;
+            let _ = app.emit(AUCTION_GAP_EVENT, &result);
+            if !result.gaps.is_empty() {
+                let (title, content) = notifier::format_auction_gaps(&result.gaps);
+                notifier::notify(db, notifier::EVENT_AUCTION_GAP, &title, &content).await;
+            }
+            Ok(Some(format!("扫描 {} 只，异动 {} 只", result.scanned, result.gaps.len())))
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(date: &str, volume: f64, is_limit_up: bool) -> StockDailyHistory {
+        StockDailyHistory { code: "sz000001".to_string(), date: date.to_string(), volume, is_limit_up, ..Default::default() }
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let quote = MarketStockSnapshot {
+            code: "sz000001".to_string(),
+            price: 10.5,
+            pre_close: 10.0,
+            volume: 1500.0,
+            amount: 1.575e6,
+            volume_ratio: 20.0,
+            ..Default::default()
+        };
+        let history = vec![
+            day("2024-06-03", 10000.0, false),
+            day("2024-06-04", 10000.0, true),
+            day("2024-06-05", 10000.0, true),
+            day("2024-06-06", 99999.0, false),
+        ];
+        let avg = avg_volume_5d(&quote, &history, "2024-06-06");
+        assert_eq!(avg, Some(10000.0));
+        assert_eq!(limit_up_streak(&history, "2024-06-06"), 2);
+
+        let gap = evaluate(&quote, avg, 2).unwrap();
+        assert_eq!(gap.gap_pct, 5.0);
+        assert_eq!(gap.volume_pct, 15.0);
+        assert_eq!(gap.labels, vec!["竞价高开", "竞价爆量", "2连板"]);
+        assert_eq!(gap.score, 79);
+
+        // 本地日线不足时按量比估算：1500 * 240 / 20 = 18000
+        assert_eq!(avg_volume_5d(&quote, &history[..1], "2024-06-06"), Some(18000.0));
+
+        let flat = MarketStockSnapshot { price: 10.1, ..quote };
+        assert!(evaluate(&flat, Some(100000.0), 0).is_none());
+    }
+}
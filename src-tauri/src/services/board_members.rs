@@ -0,0 +1,209 @@
+use anyhow::{anyhow, Result};
+use chrono::Local;
+use futures::stream::{self, StreamExt};
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+use tauri::Manager;
+
+use crate::AppState;
+use crate::db::database::Database;
+use crate::models::stock::BoardMember;
+use crate::services::job_scheduler::{JobSpec, JobTrigger};
+use crate::services::market_scanner::MarketScanner;
+
+/// 新纳入成分股在多少天内视为"近期新纳入"
+pub const NEW_MEMBER_DAYS: i64 = 5;
+/// 收盘后刷新的时间（HHMM）
+const REFRESH_AFTER: u32 = 1600;
+const CHECK_INTERVAL_SECS: u64 = 1800;
+/// 逐个拉取板块成分股的并发数，避免触发东财限流
+const FETCH_CONCURRENCY: usize = 4;
+
+/// 成分股缓存的内存索引：按股票与按板块两个方向
+#[derive(Default)]
+pub struct MemberIndex {
+    by_stock: HashMap<String, Vec<BoardMember>>,
+    by_board: HashMap<String, Vec<BoardMember>>,
+}
+
+impl MemberIndex {
+    fn build(members: Vec<BoardMember>) -> Self {
+        let mut index = Self::default();
+        for m in members {
+            index.by_stock.entry(m.code.clone()).or_default().push(m.clone());
+            index.by_board.entry(m.board_code.clone()).or_default().push(m);
+        }
+        index
+    }
+
+    /// 个股所属的已缓存概念板块
+    pub fn boards_of(&self, code: &str) -> &[BoardMember] {
+        self.by_stock.get(code).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// 板块的已缓存成分股
+    pub fn members_of(&self, board_code: &str) -> &[BoardMember] {
+        self.by_board.get(board_code).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// 全部已缓存板块：(板块代码, 成分股)
+    pub fn boards(&self) -> impl Iterator<Item = (&String, &[BoardMember])> {
+        self.by_board.iter().map(|(code, members)| (code, members.as_slice()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_board.is_empty()
+    }
+}
+
+fn cache() -> &'static RwLock<Option<Arc<MemberIndex>>> {
+    static CACHE: OnceLock<RwLock<Option<Arc<MemberIndex>>>> = OnceLock::new();
+    CACHE.get_or_init(|| RwLock::new(None))
+}
+
+/// 是否为近期新纳入（增量刷新发现且在 NEW_MEMBER_DAYS 天内）
+pub fn is_recent_addition(member: &BoardMember, today: &str) -> bool {
+    if !member.is_new {
+        return false;
+    }
+    let parse = |d: &str| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").ok();
+    match (parse(&member.first_seen), parse(today)) {
+        (Some(seen), Some(today)) => (today - seen).num_days() < NEW_MEMBER_DAYS,
+        _ => false,
+    }
+}
+
+/// 从数据库加载成分股缓存到内存
+pub fn load(db: &Database) -> Result<Arc<MemberIndex>> {
+    let index = Arc::new(MemberIndex::build(db.get_all_board_members()?));
+    *cache().write().unwrap() = Some(Arc::clone(&index));
+    Ok(index)
+}
+
+/// 内存中的成分股缓存，未加载或为空时返回 None（供无数据库句柄的题材打分与选股工具使用）
+pub fn cached() -> Option<Arc<MemberIndex>> {
+    cache().read().unwrap().as_ref().filter(|i| !i.is_empty()).cloned()
+}
+
+/// 近 days 天新纳入概念板块的成分股，按日期倒序
+pub fn recent_additions(days: i64) -> Vec<BoardMember> {
+    let Some(index) = cached() else {
+        return vec![];
+    };
+    let today = Local::now().date_naive();
+    let since = (today - chrono::Duration::days(days.max(1) - 1)).format("%Y-%m-%d").to_string();
+    let mut additions: Vec<BoardMember> = index
+        .by_board
+        .values()
+        .flatten()
+        .filter(|m| m.is_new && m.first_seen >= since)
+        .cloned()
+        .collect();
+    additions.sort_by(|a, b| b.first_seen.cmp(&a.first_seen).then(a.board_code.cmp(&b.board_code)));
+    additions
+}
+
+/// 刷新全部概念板块的成分股缓存，返回 (板块数, 新纳入成分股数)
+pub async fn refresh(db: &Database) -> Result<(usize, usize)> {
+    let scanner = MarketScanner::new()?;
+    let boards = scanner.fetch_boards("concept").await?;
+    if boards.is_empty() {
+        return Err(anyhow!("概念板块列表为空"));
+    }
+    let today = Local::now().format("%Y-%m-%d").to_string();
+    let scanner = &scanner;
+    let results: Vec<_> = stream::iter(boards)
+        .map(|board| async move {
+            let members = scanner.fetch_board_stocks(&board.code).await;
+            (board, members)
+        })
+        .buffer_unordered(FETCH_CONCURRENCY)
+        .collect()
+        .await;
+
+    let (mut synced, mut added) = (0, 0);
+    for (board, members) in results {
+        match members {
+            // 接口偶发返回空列表，此时保留旧缓存，避免把全部成分股当作调出
+            Ok(members) if !members.is_empty() => {
+                let members: Vec<(String, String)> = members.into_iter().map(|s| (s.code, s.name)).collect();
+                let new_codes = db.save_board_members(&board.code, &board.name, &members, &today)?;
+                if !new_codes.is_empty() {
+                    log::info!("[board_members] {} 新纳入 {:?}", board.name, new_codes);
+                }
+                added += new_codes.len();
+                synced += 1;
+            }
+            Ok(_) => log::warn!("[board_members] {} returned no members", board.code),
+            Err(e) => log::warn!("[board_members] fetch {} failed: {}", board.code, e),
+        }
+    }
+    load(db)?;
+    log::info!("[board_members] refreshed {} boards, {} additions", synced, added);
+    Ok((synced, added))
+}
+
+/// 启动任务：加载成分股缓存，缓存为空时立即建立
+pub fn board_members_preload_job() -> JobSpec {
+    JobSpec {
+        id: "board_members_preload",
+        name: "概念成分股预加载",
+        trigger: JobTrigger::Startup,
+        interval_secs: 0,
+        run: |app| Box::pin(async move {
+            let db = &app.state::<AppState>().db;
+            if !load(db)?.is_empty() {
+                return Ok(None);
+            }
+            let (boards, _) = refresh(db).await?;
+            Ok(Some(format!("建立 {} 个板块的成分股缓存", boards)))
+        }),
+    }
+}
+
+/// 后台任务：交易日收盘后刷新概念板块成分股，记录新纳入的成分股
+pub fn board_members_refresh_job() -> JobSpec {
+    JobSpec {
+        id: "board_members_refresh",
+        name: "概念成分股刷新",
+        trigger: JobTrigger::DailyAfter(REFRESH_AFTER),
+        interval_secs: CHECK_INTERVAL_SECS,
+        run: |app| Box::pin(async move {
+            let (boards, added) = refresh(&app.state::<AppState>().db).await?;
+            Ok(Some(format!("刷新 {} 个板块，新纳入 {} 只", boards, added)))
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(board: &str, code: &str, first_seen: &str, is_new: bool) -> BoardMember {
+        BoardMember {
+            board_code: board.to_string(),
+            board_name: board.to_string(),
+            code: code.to_string(),
+            name: code.to_string(),
+            first_seen: first_seen.to_string(),
+            is_new,
+        }
+    }
+
+    #[test]
+    fn test_member_index() {
+        let index = MemberIndex::build(vec![
+            member("BK1", "sh600519", "2024-06-01", false),
+            member("BK2", "sh600519", "2024-06-05", true),
+            member("BK2", "sz000001", "2024-06-01", false),
+        ]);
+        assert_eq!(index.boards_of("sh600519").len(), 2);
+        assert_eq!(index.members_of("BK2").len(), 2);
+        assert!(index.boards_of("sz300750").is_empty());
+
+        let added = &index.boards_of("sh600519")[1];
+        assert!(is_recent_addition(added, "2024-06-06"));
+        assert!(!is_recent_addition(added, "2024-06-12"));
+        assert!(!is_recent_addition(&index.boards_of("sh600519")[0], "2024-06-01"));
+    }
+}
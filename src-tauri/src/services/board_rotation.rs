@@ -0,0 +1,177 @@
+use anyhow::{Result, anyhow};
+use chrono::Local;
+use std::collections::HashMap;
+use tauri::Manager;
+
+use crate::AppState;
+use crate::db::database::Database;
+use crate::models::stock::{BoardQuote, BoardRankRecord, BoardRotation, BoardRotationItem};
+use crate::services::market_heatmap;
+use crate::services::job_scheduler::{JobSpec, JobTrigger};
+
+/// 支持的排名动量窗口（记录日）
+pub const ROTATION_WINDOWS: [usize; 3] = [3, 5, 10];
+/// 计算 10 日排名变化需要 11 个记录日
+pub const ROTATION_HISTORY_DAYS: usize = 11;
+/// 保留的记录日数
+const KEEP_DAYS: usize = 60;
+/// 收盘后记录的时间（HHMM）
+const RECORD_AFTER: u32 = 1530;
+const CHECK_INTERVAL_SECS: u64 = 600;
+
+/// 由当日板块行情生成排名记录：同类板块按涨跌幅降序排名
+pub fn rank_records(boards: &[BoardQuote], date: &str) -> Vec<BoardRankRecord> {
+    let mut sorted: Vec<&BoardQuote> = boards.iter().collect();
+    sorted.sort_by(|a, b| b.change_pct.total_cmp(&a.change_pct));
+    let total = sorted.len() as u32;
+    sorted
+        .into_iter()
+        .enumerate()
+        .map(|(i, b)| BoardRankRecord {
+            date: date.to_string(),
+            code: b.code.clone(),
+            name: b.name.clone(),
+            kind: b.kind.clone(),
+            change_pct: b.change_pct,
+            rank: i as u32 + 1,
+            total,
+            main_net_inflow: b.main_net_inflow,
+        })
+        .collect()
+}
+
+/// 由同类板块的排名记录（任意顺序）计算轮动：按 window 日排名变化取升温与降温各 limit 个
+pub fn compute_rotation(history: &[BoardRankRecord], kind: &str, window: usize, limit: usize) -> BoardRotation {
+    let mut dates: Vec<&str> = history.iter().map(|r| r.date.as_str()).collect();
+    dates.sort_unstable_by(|a, b| b.cmp(a));
+    dates.dedup();
+    let mut by_date: HashMap<&str, HashMap<&str, &BoardRankRecord>> = HashMap::new();
+    for r in history {
+        by_date.entry(r.date.as_str()).or_default().insert(r.code.as_str(), r);
+    }
+
+    let mut rotation = BoardRotation {
+        kind: kind.to_string(),
+        date: dates.first().map(|d| d.to_string()).unwrap_or_default(),
+        window,
+        history_days: dates.len(),
+        ..Default::default()
+    };
+    let Some(latest) = dates.first().and_then(|d| by_date.get(d)) else {
+        return rotation;
+    };
+
+    let rank_change = |code: &str, rank: u32, n: usize| -> Option<i32> {
+        let past = by_date.get(dates.get(n)?)?.get(code)?;
+        Some(past.rank as i32 - rank as i32)
+    };
+    let cumulative_pct = |code: &str, n: usize| -> Option<f64> {
+        if dates.len() < n {
+            return None;
+        }
+        let mut growth = 1.0;
+        for d in &dates[..n] {
+            growth *= 1.0 + by_date.get(d)?.get(code)?.change_pct / 100.0;
+        }
+        Some((growth - 1.0) * 100.0)
+    };
+
+    let items: Vec<BoardRotationItem> = latest
+        .values()
+        .map(|r| BoardRotationItem {
+            code: r.code.clone(),
+            name: r.name.clone(),
+            rank: r.rank,
+            rank_change_3d: rank_change(&r.code, r.rank, 3),
+            rank_change_5d: rank_change(&r.code, r.rank, 5),
+            rank_change_10d: rank_change(&r.code, r.rank, 10),
+            pct_3d: cumulative_pct(&r.code, 3),
+            pct_5d: cumulative_pct(&r.code, 5),
+            pct_10d: cumulative_pct(&r.code, 10),
+        })
+        .collect();
+
+    let change_of = |item: &BoardRotationItem| match window {
+        3 => item.rank_change_3d,
+        5 => item.rank_change_5d,
+        _ => item.rank_change_10d,
+    };
+    let mut ranked: Vec<(i32, &BoardRotationItem)> = items.iter().filter_map(|i| Some((change_of(i)?, i))).collect();
+    ranked.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.rank.cmp(&b.1.rank)));
+    rotation.gaining = ranked.iter().filter(|(c, _)| *c > 0).take(limit).map(|(_, i)| (*i).clone()).collect();
+    rotation.losing = ranked.iter().rev().filter(|(c, _)| *c < 0).take(limit).map(|(_, i)| (*i).clone()).collect();
+    rotation
+}
+
+/// 读取本地排名记录计算板块轮动，kind 为 "industry" / "concept"，window 为 3/5/10
+pub fn get_board_rotation(db: &Database, kind: &str, window: usize, limit: usize) -> Result<BoardRotation> {
+    if !ROTATION_WINDOWS.contains(&window) {
+        return Err(anyhow!("不支持的轮动窗口: {}，可选 3/5/10", window));
+    }
+    if kind != "industry" && kind != "concept" {
+        return Err(anyhow!("未知的板块类型: {}", kind));
+    }
+    let history = db.get_board_rank_history(kind, ROTATION_HISTORY_DAYS)?;
+    Ok(compute_rotation(&history, kind, window, limit))
+}
+
+/// 记录当日行业与概念板块的涨幅排名，返回写入条数
+pub async fn record_today(db: &Database) -> Result<usize> {
+    let heatmap = market_heatmap::get_market_heatmap().await?;
+    if heatmap.industries.is_empty() && heatmap.concepts.is_empty() {
+        return Err(anyhow!("板块行情返回为空"));
+    }
+    let today = Local::now().format("%Y-%m-%d").to_string();
+    let mut records = rank_records(&heatmap.industries, &today);
+    records.extend(rank_records(&heatmap.concepts, &today));
+    db.save_board_ranks(&records, KEEP_DAYS)?;
+    log::info!("[board_rotation] recorded {} boards date={}", records.len(), today);
+    Ok(records.len())
+}
+
+/// 后台任务：交易日收盘后记录当日板块排名
+pub fn rotation_record_job() -> JobSpec {
+    JobSpec {
+        id: "board_rank_record",
+        name: "板块排名记录",
+        trigger: JobTrigger::DailyAfter(RECORD_AFTER),
+        interval_secs: CHECK_INTERVAL_SECS,
+        run: |app| Box::pin(async move {
+            let count = record_today(&app.state::<AppState>().db).await?;
+            Ok(Some(format!("记录 {} 个板块", count)))
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board(code: &str, change_pct: f64) -> BoardQuote {
+        BoardQuote { code: code.to_string(), name: code.to_string(), kind: "concept".to_string(), change_pct, ..Default::default() }
+    }
+
+    #[test]
+    fn test_rotation() {
+        // 4 个记录日：BK1 从末位升到第一，BK3 从第一跌到末位
+        let days = [
+            ("2024-06-03", [("BK1", -2.0), ("BK2", 0.0), ("BK3", 3.0)]),
+            ("2024-06-04", [("BK1", -1.0), ("BK2", 1.0), ("BK3", 2.0)]),
+            ("2024-06-05", [("BK1", 1.0), ("BK2", 2.0), ("BK3", -1.0)]),
+            ("2024-06-06", [("BK1", 5.0), ("BK2", 1.0), ("BK3", -3.0)]),
+        ];
+        let history: Vec<BoardRankRecord> = days
+            .iter()
+            .flat_map(|(date, boards)| rank_records(&boards.map(|(c, p)| board(c, p)), date))
+            .collect();
+
+        let r = compute_rotation(&history, "concept", 3, 5);
+        assert_eq!((r.date.as_str(), r.history_days), ("2024-06-06", 4));
+        assert_eq!(r.gaining.iter().map(|i| i.code.as_str()).collect::<Vec<_>>(), vec!["BK1"]);
+        assert_eq!(r.gaining[0].rank_change_3d, Some(2));
+        assert_eq!(r.losing[0].code, "BK3");
+        assert_eq!(r.losing[0].rank_change_5d, None);
+        // BK1 近 3 日累计：-1%、+1%、+5%
+        assert!((r.gaining[0].pct_3d.unwrap() - (0.99 * 1.01 * 1.05 - 1.0) * 100.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,385 @@
+use anyhow::{anyhow, Result};
+use chrono::{Datelike, Local, Timelike, Weekday};
+use serde_json::Value;
+use tauri::{Emitter, Manager};
+
+use crate::db::database::Database;
+use crate::models::ai::{AIConfig, ChatCompletionRequest, ChatCompletionResponse, ChatMessage, TokenUsage};
+use crate::models::briefing::MarketBriefing;
+use crate::models::settings::AppSettings;
+use crate::services::market_overview;
+use crate::services::market_pool::{MarketPoolService, PoolStock};
+use crate::services::market_scanner::MarketScanner;
+use crate::services::ai_postprocess;
+use crate::services::model_capability;
+use crate::services::news_service;
+use crate::services::stock_tools;
+use crate::services::job_scheduler::{JobSpec, JobTrigger};
+use crate::services::notifier;
+use crate::utils::http::{build_ai_client, SendLogged};
+use crate::utils::units;
+use crate::AppState;
+
+pub const MORNING_KIND: &str = "morning";
+pub const REVIEW_KIND: &str = "review";
+/// 前端监听的早盘备忘推送事件名
+pub const MORNING_BRIEFING_EVENT: &str = "morning-briefing";
+/// 自动生成早盘备忘的时间窗口（HHMM），需在集合竞价开始前完成
+const BRIEFING_AFTER: u32 = 830;
+const BRIEFING_DEADLINE: u32 = 915;
+const CHECK_INTERVAL_SECS: u64 = 300;
+/// 纳入备忘的财联社重要电报条数
+const TOP_NEWS_COUNT: usize = 10;
+/// 纳入备忘的自选股上限，避免上下文过长
+const MAX_WATCHLIST_STOCKS: usize = 30;
+/// 收盘时间（HHMM），交易日此前不生成复盘
+const MARKET_CLOSE: u32 = 1500;
+
+/// 生成早盘备忘：汇总隔夜外盘、财经日历、财联社重要电报与自选股盘前状态，交由 AI 写成一页备忘并保存
+pub async fn generate_morning_briefing(db: &Database, config: &AIConfig) -> Result<MarketBriefing> {
+    log::info!("[briefing] generate_morning_briefing model={}", config.model_name);
+    let (global_res, calendar_res, news_res) = tokio::join!(
+        stock_tools::get_global_indexes(),
+        stock_tools::get_financial_calendar(),
+        news_service::fetch_cls_telegraph(50),
+    );
+
+    let global_indexes = parse_tool_json(global_res, "indexes");
+    let calendar = parse_tool_json(calendar_res, "events");
+    let top_news: Vec<Value> = match news_res {
+        Ok(items) => {
+            let mut items = items;
+            items.sort_by_key(|n| std::cmp::Reverse(n.importance));
+            items.iter().take(TOP_NEWS_COUNT).map(|n| {
+                serde_json::json!({
+                    "title": n.title,
+                    "summary": n.summary.chars().take(120).collect::<String>(),
+                    "time": n.publish_time,
+                })
+            }).collect()
+        }
+        Err(e) => {
+            log::warn!("[briefing] fetch cls telegraph failed: {}", e);
+            vec![]
+        }
+    };
+    let watchlist = watchlist_status(db).await;
+
+    let context = serde_json::json!({
+        "date": Local::now().format("%Y-%m-%d").to_string(),
+        "global_indexes": global_indexes,
+        "calendar": calendar,
+        "top_news": top_news,
+        "watchlist": watchlist,
+    });
+
+    let system_prompt = r#"你是一位 A 股投资顾问，每个交易日开盘前为用户撰写一页「早盘备忘」。请根据提供的数据（JSON）输出 Markdown，结构如下：
+
+## 隔夜外盘
+2-3 句概括美股、欧股、亚太及 A50 等表现，指出对今日 A 股开盘的可能影响。
+
+## 今日关注
+列出今日重要财经事件与政策/行业消息（不超过 5 条），每条一句话说明可能受影响的方向或板块。
+
+## 自选股提示
+仅挑出需要特别留意的自选股（有技术信号、资金异动、或与消息面相关），每只一句话说明原因；无特别情况则写"无异常"。
+
+## 开盘策略
+1-2 句给出今日整体操作思路（仓位、节奏），保持客观克制。
+
+要求：引用具体数据，不要编造数据中没有的信息，总字数控制在 600 字以内。"#;
+
+    let user_msg = format!("以下是今日盘前数据，请生成早盘备忘：\n\n{}", serde_json::to_string(&context)?);
+    let (content, usage) = chat_once(config, system_prompt, &user_msg, 1500).await?;
+    if let Some(usage) = usage {
+        let _ = db.record_token_usage(&config.model_name, usage.prompt_tokens, usage.completion_tokens);
+    }
+
+    let briefing = MarketBriefing {
+        date: Local::now().format("%Y-%m-%d").to_string(),
+        kind: MORNING_KIND.to_string(),
+        content,
+        model_name: config.model_name.clone(),
+        created_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+    };
+    db.save_market_briefing(&briefing)?;
+    Ok(briefing)
+}
+
+/// 生成收盘复盘：涨停池统计、板块涨跌、自选股当日盈亏与触发信号，交由 AI 写成复盘并按日期保存
+pub async fn generate_daily_review(db: &Database, settings: &AppSettings, config: &AIConfig) -> Result<MarketBriefing> {
+    let now = Local::now();
+    let is_weekday = !matches!(now.weekday(), Weekday::Sat | Weekday::Sun);
+    if is_weekday && now.hour() * 100 + now.minute() < MARKET_CLOSE {
+        return Err(anyhow!("请在收盘后生成复盘"));
+    }
+    let today = now.format("%Y-%m-%d").to_string();
+    log::info!("[briefing] generate_daily_review date={} model={}", today, config.model_name);
+
+    let pool_service = MarketPoolService::new()?;
+    let (overview_res, pool_res) = tokio::join!(
+        market_overview::fetch_overview(settings),
+        pool_service.fetch_limit_up_pool(&today),
+    );
+    let overview = overview_res.unwrap_or_default();
+    let limit_up = match pool_res {
+        Ok(pool) => limit_up_stats(&pool),
+        Err(e) => {
+            log::warn!("[briefing] fetch limit up pool failed: {}", e);
+            Value::Null
+        }
+    };
+
+    let watchlist = watchlist_pnl(db).await;
+    let signals: Vec<Value> = db.get_signal_history(None, 200)?
+        .into_iter()
+        .filter(|s| s.date == today)
+        .map(|s| serde_json::json!({
+            "code": s.code,
+            "name": s.name,
+            "direction": s.direction,
+            "description": s.description,
+            "strength": s.strength,
+        }))
+        .collect();
+
+    let context = serde_json::json!({
+        "date": today,
+        "indexes": overview.indexes.iter().map(|i| serde_json::json!({
+            "name": i.name,
+            "price": i.price,
+            "change_pct": format!("{:.2}%", i.change_pct),
+        })).collect::<Vec<_>>(),
+        "market_stats": overview.market_stats,
+        "total_amount": units::format_amount(overview.total_amount),
+        "volume_ratio": format!("{:.2}", overview.volume_compare.ratio),
+        "sentiment": overview.sentiment,
+        "sector_top": overview.sector_top,
+        "sector_bottom": overview.sector_bottom,
+        "limit_up": limit_up,
+        "watchlist": watchlist,
+        "signals": signals,
+    });
+
+    let system_prompt = r#"你是一位 A 股复盘分析师，每个交易日收盘后为用户撰写当日「复盘」。请根据提供的数据（JSON）输出 Markdown，结构如下：
+
+## 大盘总结
+一句话定性今日行情，再补充指数涨跌、成交额与量能变化、涨跌家数。
+
+## 情绪与涨停
+根据涨停家数、连板高度、封板类型与涨停行业分布，判断短线情绪处于什么阶段。
+
+## 板块主线
+点出领涨与领跌板块，判断主线是延续还是切换。
+
+## 自选股复盘
+概述自选股整体表现（平均涨跌、强弱分化），点名表现突出或触发信号的个股并说明原因。
+
+## 明日展望
+1-2 句给出明日关注重点，保持客观克制。
+
+要求：引用具体数据，不要编造数据中没有的信息，总字数控制在 800 字以内。"#;
+
+    let user_msg = format!("以下是今日收盘数据，请生成复盘：\n\n{}", serde_json::to_string(&context)?);
+    let (content, usage) = chat_once(config, system_prompt, &user_msg, 2000).await?;
+    if let Some(usage) = usage {
+        let _ = db.record_token_usage(&config.model_name, usage.prompt_tokens, usage.completion_tokens);
+    }
+
+    let review = MarketBriefing {
+        date: today,
+        kind: REVIEW_KIND.to_string(),
+        content,
+        model_name: config.model_name.clone(),
+        created_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+    };
+    db.save_market_briefing(&review)?;
+    Ok(review)
+}
+
+/// 涨停池统计：家数、连板梯队、封板类型与行业分布
+fn limit_up_stats(pool: &[PoolStock]) -> Value {
+    let mut streaks: std::collections::BTreeMap<u32, Vec<&str>> = std::collections::BTreeMap::new();
+    let mut types: std::collections::HashMap<&str, usize> = std::collections::HashMap::new();
+    let mut industries: std::collections::HashMap<&str, usize> = std::collections::HashMap::new();
+    for s in pool {
+        streaks.entry(s.streak_days).or_default().push(&s.name);
+        *types.entry(s.limit_up_type.as_str()).or_default() += 1;
+        if !s.industry.is_empty() {
+            *industries.entry(s.industry.as_str()).or_default() += 1;
+        }
+    }
+    let mut industries: Vec<(&str, usize)> = industries.into_iter().collect();
+    industries.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+
+    serde_json::json!({
+        "count": pool.len(),
+        "max_streak": streaks.keys().next_back().copied().unwrap_or(0),
+        "streak_ladder": streaks.iter().rev().filter(|(d, _)| **d >= 2).map(|(d, names)| {
+            serde_json::json!({ "days": d, "count": names.len(), "stocks": names.iter().take(5).collect::<Vec<_>>() })
+        }).collect::<Vec<_>>(),
+        "limit_up_types": types,
+        "top_industries": industries.iter().take(5).map(|(name, count)| {
+            serde_json::json!({ "industry": name, "count": count })
+        }).collect::<Vec<_>>(),
+    })
+}
+
+/// 自选股当日盈亏：等权平均涨跌幅与个股明细（按涨跌幅降序）
+async fn watchlist_pnl(db: &Database) -> Value {
+    let codes: Vec<String> = match db.get_watchlist_stocks() {
+        Ok(stocks) => stocks.into_iter().map(|s| s.code).collect(),
+        Err(e) => {
+            log::warn!("[briefing] load watchlist failed: {}", e);
+            return Value::Null;
+        }
+    };
+    let mut quotes = match MarketScanner::new() {
+        Ok(scanner) => scanner.fetch_stocks_by_codes(&codes).await.unwrap_or_else(|e| {
+            log::warn!("[briefing] fetch watchlist quotes failed: {}", e);
+            vec![]
+        }),
+        Err(_) => vec![],
+    };
+    if quotes.is_empty() {
+        return Value::Null;
+    }
+    quotes.sort_by(|a, b| b.change_pct.partial_cmp(&a.change_pct).unwrap_or(std::cmp::Ordering::Equal));
+    let avg = quotes.iter().map(|q| q.change_pct).sum::<f64>() / quotes.len() as f64;
+    let up = quotes.iter().filter(|q| q.change_pct > 0.0).count();
+
+    serde_json::json!({
+        "count": quotes.len(),
+        "up_count": up,
+        "down_count": quotes.iter().filter(|q| q.change_pct < 0.0).count(),
+        "avg_change_pct": format!("{:.2}%", avg),
+        "stocks": quotes.iter().take(MAX_WATCHLIST_STOCKS).map(|q| serde_json::json!({
+            "code": q.code,
+            "name": q.name,
+            "close": q.price,
+            "change_pct": format!("{:.2}%", q.change_pct),
+            "turnover_rate": format!("{:.2}%", q.turnover_rate),
+            "main_net_inflow": units::format_amount(q.main_net_inflow),
+        })).collect::<Vec<_>>(),
+    })
+}
+
+/// 自选股盘前状态：上一交易日收盘行情 + 本地预计算的技术信号
+async fn watchlist_status(db: &Database) -> Vec<Value> {
+    let stocks = match db.get_watchlist_stocks() {
+        Ok(s) => s,
+        Err(e) => {
+            log::warn!("[briefing] load watchlist failed: {}", e);
+            return vec![];
+        }
+    };
+    let codes: Vec<String> = stocks.iter().take(MAX_WATCHLIST_STOCKS).map(|s| s.code.clone()).collect();
+    let quotes = match MarketScanner::new() {
+        Ok(scanner) => scanner.fetch_stocks_by_codes(&codes).await.unwrap_or_else(|e| {
+            log::warn!("[briefing] fetch watchlist quotes failed: {}", e);
+            vec![]
+        }),
+        Err(_) => vec![],
+    };
+
+    stocks.iter().take(MAX_WATCHLIST_STOCKS).map(|s| {
+        let mut item = serde_json::json!({ "code": s.code, "name": s.name });
+        if let Some(q) = quotes.iter().find(|q| q.code == s.code) {
+            item["close"] = serde_json::json!(q.price);
+            item["change_pct"] = serde_json::json!(format!("{:.2}%", q.change_pct));
+            item["pct_5d"] = serde_json::json!(format!("{:.2}%", q.pct_5d));
+            item["main_net_inflow"] = serde_json::json!(units::format_amount(q.main_net_inflow));
+        }
+        if let Ok(Some(t)) = db.get_technical_daily(&s.code) {
+            item["ma_alignment"] = serde_json::json!(t.ma_alignment);
+            let signals: Vec<String> = t.signals.iter()
+                .filter(|sig| sig.date == t.date)
+                .map(|sig| sig.description.clone())
+                .collect();
+            if !signals.is_empty() {
+                item["signals"] = serde_json::json!(signals);
+            }
+        }
+        item
+    }).collect()
+}
+
+/// 取工具 JSON 输出中的数组字段，失败时返回空数组
+fn parse_tool_json(res: Result<String>, field: &str) -> Value {
+    res.ok()
+        .and_then(|raw| serde_json::from_str::<Value>(&raw).ok())
+        .map(|v| v[field].clone())
+        .filter(|v| v.is_array())
+        .unwrap_or_else(|| Value::Array(vec![]))
+}
+
+/// 非流式单轮对话
+async fn chat_once(
+    config: &AIConfig,
+    system_prompt: &str,
+    user_msg: &str,
+    max_tokens: u32,
+) -> Result<(String, Option<TokenUsage>)> {
+    let client = build_ai_client(config.timeout_secs)?;
+    let url = format!("{}/chat/completions", config.base_url.trim_end_matches('/'));
+    let mut req = ChatCompletionRequest {
+        model: config.model_name.clone(),
+        messages: vec![ChatMessage::system(system_prompt), ChatMessage::user(user_msg)],
+        max_tokens: Some(max_tokens),
+        max_completion_tokens: None,
+        temperature: Some(0.3),
+        stream: Some(false),
+        tools: None,
+        tool_choice: None,
+    };
+
+    model_capability::adapt_request(&mut req);
+
+    let resp = client
+        .post(&url)
+        .header("Authorization", format!("Bearer {}", config.api_key))
+        .header("Content-Type", "application/json")
+        .json(&req)
+        .send_logged()
+        .await
+        .map_err(|e| anyhow!("AI 请求失败: {}", e))?;
+
+    let status = resp.status();
+    let body = resp.text().await.map_err(|e| anyhow!("读取AI响应失败: {}", e))?;
+    if !status.is_success() {
+        return Err(anyhow!("AI 返回错误 ({}): {}", status.as_u16(), body.chars().take(200).collect::<String>()));
+    }
+
+    let response: ChatCompletionResponse = serde_json::from_str(&body)
+        .map_err(|e| anyhow!("AI 响应解析失败: {}", e))?;
+    let content = response.choices.first()
+        .and_then(|c| c.message.as_ref())
+        .and_then(|m| m.content.clone())
+        .ok_or_else(|| anyhow!("AI 未返回内容"))?;
+    Ok((ai_postprocess::clean(&content), response.usage))
+}
+
+/// 后台任务：交易日 08:30~09:15 之间生成当日备忘（已存在或未启用则跳过），完成后通过 morning-briefing 事件推送
+pub fn morning_briefing_job() -> JobSpec {
+    JobSpec {
+        id: "morning_briefing",
+        name: "早盘备忘",
+        trigger: JobTrigger::DailyWindow(BRIEFING_AFTER, BRIEFING_DEADLINE),
+        interval_secs: CHECK_INTERVAL_SECS,
+        run: |app| Box::pin(async move {
+            let state = app.state::<AppState>();
+            let today = Local::now().format("%Y-%m-%d").to_string();
+            let exists = state.db.get_market_briefing(&today, MORNING_KIND)?.is_some();
+            let config = state.db.load_settings()?;
+            let config = config.active_ai_config().filter(|_| config.morning_briefing_enabled);
+            let (false, Some(config)) = (exists, config) else {
+                return Ok(None);
+            };
+            let briefing = generate_morning_briefing(&state.db, &config).await?;
+            let _ = app.emit(MORNING_BRIEFING_EVENT, &briefing);
+            let (title, content) = notifier::format_briefing(&briefing);
+            notifier::notify(&state.db, notifier::EVENT_MORNING_BRIEFING, &title, &content).await;
+            Ok(Some(format!("已生成，模型 {}", briefing.model_name)))
+        }),
+    }
+}
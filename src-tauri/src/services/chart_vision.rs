@@ -0,0 +1,162 @@
+use anyhow::{anyhow, Result};
+use base64::Engine;
+use tauri::AppHandle;
+
+use crate::db::database::Database;
+use crate::models::ai::{AIAnalysisResult, AIConfig, AIStreamEvent};
+use crate::services::ai_service::AIService;
+use crate::services::stock_tools::{self, ToolContext};
+use crate::services::stream_forwarder;
+use crate::services::symbol_table;
+
+/// 前端监听的识图分析流式事件名
+pub const IMAGE_ANALYSIS_EVENT: &str = "ai-image-analysis";
+/// ai_analysis 表中识图分析记录的 question 标识
+pub const IMAGE_QUESTION: &str = "AI识图分析";
+/// 解码后的图片大小上限，多数视觉模型接口限制在 10MB 以内
+const MAX_IMAGE_BYTES: usize = 8 * 1024 * 1024;
+/// 交叉核对用的日K根数
+const CONTEXT_KLINE_COUNT: u64 = 30;
+
+fn sniff_mime(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(&[0x89, b'P', b'N', b'G']) {
+        Some("image/png")
+    } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if bytes.starts_with(b"GIF8") {
+        Some("image/gif")
+    } else if bytes.len() > 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else {
+        None
+    }
+}
+
+/// 把前端传入的 base64（可带 data:image/...;base64, 前缀）校验后转为 data URL，
+/// 按文件头识别格式，不采信前缀中声明的类型
+pub fn image_data_url(image: &str) -> Result<String> {
+    let raw = match image.trim().split_once(";base64,") {
+        Some((prefix, data)) if prefix.starts_with("data:") => data,
+        _ => image.trim(),
+    };
+    let raw: String = raw.chars().filter(|c| !c.is_whitespace()).collect();
+    if raw.is_empty() {
+        return Err(anyhow!("图片为空"));
+    }
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(&raw)
+        .map_err(|_| anyhow!("图片不是有效的 base64 数据"))?;
+    if bytes.len() > MAX_IMAGE_BYTES {
+        return Err(anyhow!("图片过大（{:.1}MB），请裁剪后重试，上限 {}MB", bytes.len() as f64 / 1048576.0, MAX_IMAGE_BYTES / 1048576));
+    }
+    let mime = sniff_mime(&bytes).ok_or_else(|| anyhow!("仅支持 PNG、JPEG、GIF、WebP 格式的图片"))?;
+    Ok(format!("data:{};base64,{}", mime, raw))
+}
+
+/// 获取股票真实行情、日K与技术指标，供模型与图片交叉核对；单项失败时注明而不中断
+pub async fn stock_context(code: &str, tool_ctx: &ToolContext) -> String {
+    let calls = [
+        ("实时行情", "get_stock_quote", serde_json::json!({ "code": code })),
+        ("最近日K", "get_kline_data", serde_json::json!({ "code": code, "count": CONTEXT_KLINE_COUNT })),
+        ("技术指标", "get_technical_indicators", serde_json::json!({ "code": code })),
+    ];
+    let mut sections = Vec::with_capacity(calls.len());
+    for (label, tool, args) in calls {
+        let result = match stock_tools::execute_tool(tool, &args.to_string(), tool_ctx).await {
+            Ok(r) => r,
+            Err(e) => {
+                log::warn!("[chart_vision] {} failed for {}: {}", tool, code, e);
+                format!("获取失败: {}", e)
+            }
+        };
+        sections.push(format!("【{}】\n{}", label, result));
+    }
+    sections.join("\n\n")
+}
+
+pub fn build_prompt(stock: Option<(&str, &str)>, question: &str, context: &str) -> String {
+    let question = if question.trim().is_empty() { "请分析这张图表" } else { question.trim() };
+    match stock {
+        Some((code, name)) => format!(
+            "用户提问：{}\n\n图表对应的股票：{}({})。以下是该股的真实数据，请与图片交叉核对：\n\n{}",
+            question, name, code, context
+        ),
+        None => format!("用户提问：{}\n\n用户未指定股票，请先从图中识别标的与周期，无法识别时说明。", question),
+    }
+}
+
+/// 识图分析：图片与股票真实数据一起发给视觉模型，流式内容推送到 ai-image-analysis，指定股票时保存分析记录
+pub async fn analyze(
+    db: &Database,
+    app: &AppHandle,
+    config: &AIConfig,
+    tool_ctx: &ToolContext,
+    image: &str,
+    code: Option<&str>,
+    question: &str,
+) -> Result<AIAnalysisResult> {
+    let image_url = image_data_url(image)?;
+    let stock = code
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+        .map(|c| symbol_table::resolve_input(db, c, ""));
+    let context = match &stock {
+        Some((code, _)) => stock_context(code, tool_ctx).await,
+        None => String::new(),
+    };
+    let prompt = build_prompt(stock.as_ref().map(|(c, n)| (c.as_str(), n.as_str())), question, &context);
+
+    let (tx, rx) = tokio::sync::mpsc::channel::<AIStreamEvent>(100);
+    let forwarder = stream_forwarder::spawn(app, IMAGE_ANALYSIS_EVENT.to_string(), rx);
+    let result = AIService::analyze_image_stream(config, &image_url, &prompt, tx.clone()).await;
+    if let Err(e) = &result {
+        let _ = tx.send(AIStreamEvent {
+            event_type: "error".to_string(),
+            content: Some(e.to_string()),
+            done: true,
+            usage: None,
+            tool_name: None,
+        }).await;
+    }
+    drop(tx);
+    let _ = forwarder.await;
+    let (content, usage) = result?;
+
+    if let Some(usage) = usage {
+        let _ = db.record_token_usage(&config.model_name, usage.prompt_tokens, usage.completion_tokens);
+    }
+    let (code, name) = stock.unwrap_or_default();
+    let analysis = AIAnalysisResult {
+        id: uuid::Uuid::new_v4().to_string(),
+        code,
+        name,
+        model_name: config.model_name.clone(),
+        question: IMAGE_QUESTION.to_string(),
+        content,
+        created_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+    };
+    if !analysis.code.is_empty() {
+        let _ = db.save_ai_analysis(&analysis);
+    }
+    log::info!("[chart_vision] analyze done code={} len={}", analysis.code, analysis.content.len());
+    Ok(analysis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_data_url() {
+        let png = base64::engine::general_purpose::STANDARD.encode([0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]);
+        let expected = format!("data:image/png;base64,{}", png);
+        assert_eq!(image_data_url(&png).unwrap(), expected);
+        // 前缀声明的类型与文件头不符时以文件头为准
+        assert_eq!(image_data_url(&format!("data:image/jpeg;base64,{}\n", png)).unwrap(), expected);
+
+        let text = base64::engine::general_purpose::STANDARD.encode("hello");
+        assert!(image_data_url(&text).is_err());
+        assert!(image_data_url("not base64!").is_err());
+        assert!(image_data_url("  ").is_err());
+    }
+}
@@ -0,0 +1,197 @@
+use anyhow::Result;
+use futures::stream::{self, StreamExt};
+use std::collections::{HashMap, HashSet};
+use tauri::Manager;
+
+use crate::AppState;
+use crate::db::database::Database;
+use crate::models::stock::{ClosingAuction, MarketStockSnapshot};
+use crate::models::watchlist::KlineItem;
+use crate::services::job_scheduler::{JobSpec, JobTrigger};
+use crate::services::market_scanner::MarketScanner;
+
+/// 尾盘集合竞价开始与收盘时刻（HH:MM）
+const AUCTION_START: &str = "14:57";
+const AUCTION_END: &str = "15:00";
+/// 尾盘竞价价格偏离达到该值才可能标记 %
+const TAIL_MOVE_PCT: f64 = 1.0;
+/// 尾盘竞价成交量占全天达到该百分比才可能标记
+const TAIL_VOLUME_SHARE: f64 = 4.0;
+/// 本地保留的记录日数
+const KEEP_DAYS: usize = 60;
+const FETCH_CONCURRENCY: usize = 4;
+/// 科创板盘后固定价格交易 15:30 结束，之后记录当日数据
+const RECORD_AFTER: u32 = 1535;
+const CHECK_INTERVAL_SECS: u64 = 600;
+
+fn minute(bar: &KlineItem) -> &str {
+    bar.date.rsplit(' ').next().unwrap_or("")
+}
+
+/// 尾盘竞价放量拉升为抢筹，放量打压为砸盘
+pub fn tail_flag(deviation_pct: f64, volume_share: f64) -> Option<&'static str> {
+    if volume_share < TAIL_VOLUME_SHARE {
+        return None;
+    }
+    if deviation_pct >= TAIL_MOVE_PCT {
+        Some("尾盘抢筹")
+    } else if deviation_pct <= -TAIL_MOVE_PCT {
+        Some("尾盘砸盘")
+    } else {
+        None
+    }
+}
+
+/// 由一个交易日的分钟走势提取尾盘竞价与盘后交易数据；尚未收盘（无 15:00 竞价成交）时返回 None
+pub fn analyze(code: &str, bars: &[KlineItem]) -> Option<ClosingAuction> {
+    let pre = bars.iter().rfind(|b| minute(b) <= AUCTION_START)?;
+    let auction: Vec<&KlineItem> = bars
+        .iter()
+        .filter(|b| minute(b) > AUCTION_START && minute(b) <= AUCTION_END)
+        .collect();
+    let last = auction.iter().find(|b| minute(b) == AUCTION_END)?;
+    let auction_volume: f64 = auction.iter().map(|b| b.volume).sum();
+    let auction_amount: f64 = auction.iter().map(|b| b.amount).sum();
+    let day_volume: f64 = bars.iter().filter(|b| minute(b) <= AUCTION_END).map(|b| b.volume).sum();
+    if pre.close <= 0.0 || day_volume <= 0.0 {
+        return None;
+    }
+
+    let after_hours: Vec<&KlineItem> = bars.iter().filter(|b| minute(b) > AUCTION_END).collect();
+    let (after_hours_volume, after_hours_amount) = if after_hours.is_empty() {
+        (None, None)
+    } else {
+        (Some(after_hours.iter().map(|b| b.volume).sum()), Some(after_hours.iter().map(|b| b.amount).sum()))
+    };
+
+    let deviation_pct = ((last.close / pre.close - 1.0) * 100.0 * 100.0).round() / 100.0;
+    let volume_share = (auction_volume / day_volume * 100.0 * 100.0).round() / 100.0;
+    Some(ClosingAuction {
+        code: code.to_string(),
+        date: last.date.split(' ').next().unwrap_or("").to_string(),
+        pre_price: pre.close,
+        close: last.close,
+        deviation_pct,
+        auction_volume,
+        auction_amount,
+        volume_share,
+        after_hours_volume,
+        after_hours_amount,
+        flag: tail_flag(deviation_pct, volume_share).map(str::to_string),
+    })
+}
+
+/// 拉取单只股票最近一个交易日的尾盘竞价数据
+pub async fn fetch(code: &str) -> Result<Option<ClosingAuction>> {
+    let bars = MarketScanner::new()?.fetch_trends(code).await?;
+    Ok(analyze(code, &bars))
+}
+
+/// 记录自选股与 AI 追踪股票当日的尾盘竞价与盘后交易数据，返回记录数
+pub async fn record(db: &Database) -> Result<usize> {
+    let mut seen = HashSet::new();
+    let codes: Vec<String> = db
+        .get_watchlist_stocks()?
+        .into_iter()
+        .map(|s| s.code)
+        .chain(db.get_tracking_stocks()?.into_iter().map(|t| t.code))
+        .filter(|c| seen.insert(c.clone()))
+        .collect();
+    if codes.is_empty() {
+        return Ok(0);
+    }
+
+    let scanner = MarketScanner::new()?;
+    let records: Vec<ClosingAuction> = stream::iter(codes)
+        .map(|code| {
+            let scanner = &scanner;
+            async move {
+                match scanner.fetch_trends(&code).await {
+                    Ok(bars) => analyze(&code, &bars),
+                    Err(e) => {
+                        log::warn!("[closing_auction] fetch trends {} failed: {}", code, e);
+                        None
+                    }
+                }
+            }
+        })
+        .buffer_unordered(FETCH_CONCURRENCY)
+        .filter_map(|r| async move { r })
+        .collect()
+        .await;
+    db.save_closing_auctions(&records, KEEP_DAYS)?;
+    log::info!("[closing_auction] recorded {} stocks", records.len());
+    Ok(records.len())
+}
+
+/// 为增强行情填入最近一个交易日的尾盘标记
+pub fn attach_flags(db: &Database, stocks: &mut [MarketStockSnapshot]) {
+    let latest: HashMap<String, ClosingAuction> = match db.get_latest_closing_auctions() {
+        Ok(records) => records.into_iter().map(|r| (r.code.clone(), r)).collect(),
+        Err(e) => {
+            log::warn!("[closing_auction] load closing auctions failed: {}", e);
+            return;
+        }
+    };
+    for stock in stocks {
+        stock.tail_flag = latest.get(&stock.code).and_then(|r| r.flag.clone());
+    }
+}
+
+/// 后台任务：交易日 15:35 后记录自选股与追踪股票的尾盘竞价与盘后交易数据
+pub fn closing_auction_record_job() -> JobSpec {
+    JobSpec {
+        id: "closing_auction_record",
+        name: "尾盘竞价记录",
+        trigger: JobTrigger::DailyAfter(RECORD_AFTER),
+        interval_secs: CHECK_INTERVAL_SECS,
+        run: |app| Box::pin(async move {
+            let db = &app.state::<AppState>().db;
+            let count = record(db).await?;
+            Ok(Some(format!("记录 {} 只", count)))
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(time: &str, close: f64, volume: f64) -> KlineItem {
+        KlineItem {
+            date: format!("2024-06-06 {}", time),
+            open: close,
+            close,
+            high: close,
+            low: close,
+            volume,
+            amount: close * volume * 100.0,
+            change_pct: 0.0,
+            turnover_rate: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_analyze() {
+        let mut bars = vec![bar("14:55", 10.0, 900.0), bar("14:56", 10.0, 500.0), bar("14:57", 10.0, 400.0)];
+        assert!(analyze("sh688001", &bars).is_none());
+
+        bars.extend([bar("14:58", 10.0, 0.0), bar("14:59", 10.0, 0.0), bar("15:00", 10.15, 200.0)]);
+        let auction = analyze("sh688001", &bars).unwrap();
+        assert_eq!(auction.date, "2024-06-06");
+        assert_eq!(auction.deviation_pct, 1.5);
+        assert_eq!(auction.volume_share, 10.0);
+        assert_eq!(auction.flag.as_deref(), Some("尾盘抢筹"));
+        assert_eq!(auction.after_hours_volume, None);
+
+        bars.push(bar("15:30", 10.15, 30.0));
+        assert_eq!(analyze("sh688001", &bars).unwrap().after_hours_volume, Some(30.0));
+    }
+
+    #[test]
+    fn test_tail_flag() {
+        assert_eq!(tail_flag(-2.0, 6.0), Some("尾盘砸盘"));
+        assert_eq!(tail_flag(-2.0, 1.0), None);
+        assert_eq!(tail_flag(0.3, 10.0), None);
+    }
+}
@@ -0,0 +1,218 @@
+use anyhow::{anyhow, Result};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+use crate::AppState;
+use crate::models::deep_link::DeepLink;
+use crate::services::stock_data::format_stock_code;
+
+pub const SCHEME: &str = "stockhelper";
+/// 前端监听的跳转事件名
+pub const DEEP_LINK_EVENT: &str = "deep-link";
+/// 已运行实例接收转发链接的本机端口（再次通过链接启动时把链接交给已运行的实例）
+const FORWARD_PORT: u16 = 18519;
+const FORWARD_TIMEOUT: Duration = Duration::from_millis(500);
+const FORWARD_ACK: &str = "ok";
+
+/// 前端就绪前收到的链接，由前端启动后取走
+fn pending() -> &'static Mutex<Option<DeepLink>> {
+    static PENDING: OnceLock<Mutex<Option<DeepLink>>> = OnceLock::new();
+    PENDING.get_or_init(|| Mutex::new(None))
+}
+
+/// 解析 stockhelper://stock/sh600519、stockhelper://analysis/600519 形式的链接
+pub fn parse(url: &str) -> Option<DeepLink> {
+    let url = url.trim();
+    let prefix = format!("{}://", SCHEME);
+    if !url.get(..prefix.len())?.eq_ignore_ascii_case(&prefix) {
+        return None;
+    }
+    let rest = url[prefix.len()..].split(['?', '#']).next()?;
+    let mut parts = rest.split('/').filter(|p| !p.is_empty());
+    let target = parts.next()?.to_lowercase();
+    if target != "stock" && target != "analysis" {
+        return None;
+    }
+    let code = format_stock_code(parts.next()?);
+    let valid = code.len() == 8
+        && code.is_ascii()
+        && ["sh", "sz", "bj"].contains(&&code[..2])
+        && code[2..].chars().all(|c| c.is_ascii_digit());
+    valid.then_some(DeepLink { target, code })
+}
+
+/// 个股详情链接，用于推送消息与导出报告
+pub fn stock_url(code: &str) -> String {
+    format!("{}://stock/{}", SCHEME, code)
+}
+
+/// 启动参数中的链接（Windows / Linux 由系统作为参数传入）
+pub fn url_from_args(args: impl IntoIterator<Item = String>) -> Option<String> {
+    let prefix = format!("{}://", SCHEME);
+    args.into_iter().find(|a| a.to_lowercase().starts_with(&prefix))
+}
+
+/// 把链接转交给已运行的实例，成功返回 true（当前进程随后直接退出）
+pub fn forward_to_running(url: &str) -> bool {
+    let addr = SocketAddr::from(([127, 0, 0, 1], FORWARD_PORT));
+    let Ok(mut stream) = TcpStream::connect_timeout(&addr, FORWARD_TIMEOUT) else {
+        return false;
+    };
+    let _ = stream.set_read_timeout(Some(FORWARD_TIMEOUT));
+    if writeln!(stream, "{}", url).is_err() {
+        return false;
+    }
+    let mut ack = String::new();
+    BufReader::new(stream).read_line(&mut ack).is_ok() && ack.trim() == FORWARD_ACK
+}
+
+/// 处理链接：记录待处理目标、通知前端并把主窗口切到前台
+pub fn handle(app: &AppHandle, url: &str) {
+    let Some(link) = parse(url) else {
+        log::warn!("[deep_link] ignore invalid url: {}", url);
+        return;
+    };
+    log::info!("[deep_link] open target={} code={}", link.target, link.code);
+    *pending().lock().unwrap() = Some(link.clone());
+    let _ = app.emit(DEEP_LINK_EVENT, &link);
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+/// 取走待处理的链接（前端启动时调用，处理应用由链接冷启动的情况）
+pub fn take_pending() -> Option<DeepLink> {
+    pending().lock().unwrap().take()
+}
+
+/// 监听其他进程转发的链接，应用退出时停止
+pub async fn listen(app: AppHandle) {
+    let listener = match TcpListener::bind(("127.0.0.1", FORWARD_PORT)).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            log::warn!("[deep_link] bind forward port {} failed: {}", FORWARD_PORT, e);
+            return;
+        }
+    };
+    loop {
+        let shutdown = &app.state::<AppState>().shutdown;
+        let stream = tokio::select! {
+            _ = shutdown.cancelled() => break,
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    log::warn!("[deep_link] accept failed: {}", e);
+                    continue;
+                }
+            },
+        };
+        let app = app.clone();
+        tokio::spawn(async move {
+            let mut stream = tokio::io::BufReader::new(stream);
+            let mut line = String::new();
+            let read = tokio::time::timeout(FORWARD_TIMEOUT, stream.read_line(&mut line)).await;
+            if matches!(read, Ok(Ok(n)) if n > 0) && parse(&line).is_some() {
+                handle(&app, &line);
+                let _ = stream.get_mut().write_all(format!("{}\n", FORWARD_ACK).as_bytes()).await;
+            }
+        });
+    }
+}
+
+/// 为当前用户注册 stockhelper:// 协议（Windows 写注册表，Linux 写 .desktop 并设为默认处理程序）。
+/// macOS 由安装包 Info.plist 中的 CFBundleURLTypes 声明；开发构建不注册，避免指向临时可执行文件
+pub fn register_scheme() -> Result<()> {
+    if cfg!(debug_assertions) {
+        return Ok(());
+    }
+    let exe = std::env::current_exe()?;
+    register_for_os(&exe.to_string_lossy())
+}
+
+#[cfg(target_os = "windows")]
+fn register_for_os(exe: &str) -> Result<()> {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    let key = format!(r"HKCU\Software\Classes\{}", SCHEME);
+    let command = format!("\"{}\" \"%1\"", exe);
+    let entries: [(String, &str, &str); 3] = [
+        (key.clone(), "", "URL:Stock Helper"),
+        (key.clone(), "URL Protocol", ""),
+        (format!(r"{}\shell\open\command", key), "", command.as_str()),
+    ];
+    for (path, name, value) in &entries {
+        let mut cmd = std::process::Command::new("reg");
+        cmd.args(["add", path]);
+        if name.is_empty() {
+            cmd.arg("/ve");
+        } else {
+            cmd.args(["/v", name]);
+        }
+        let status = cmd.args(["/d", value, "/f"]).creation_flags(CREATE_NO_WINDOW).status()?;
+        if !status.success() {
+            return Err(anyhow!("写入注册表失败: {}", path));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn register_for_os(exe: &str) -> Result<()> {
+    let dir = std::env::var_os("XDG_DATA_HOME")
+        .map(std::path::PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| std::path::PathBuf::from(h).join(".local/share")))
+        .ok_or_else(|| anyhow!("无法确定用户数据目录"))?
+        .join("applications");
+    std::fs::create_dir_all(&dir)?;
+    let desktop = "stock-helper-url-handler.desktop";
+    std::fs::write(
+        dir.join(desktop),
+        format!(
+            "[Desktop Entry]\nType=Application\nName=Stock Helper\nExec=\"{}\" %u\nNoDisplay=true\nMimeType=x-scheme-handler/{};\n",
+            exe, SCHEME
+        ),
+    )?;
+    let status = std::process::Command::new("xdg-mime")
+        .args(["default", desktop, &format!("x-scheme-handler/{}", SCHEME)])
+        .status()?;
+    if !status.success() {
+        return Err(anyhow!("xdg-mime 设置默认处理程序失败"));
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn register_for_os(_exe: &str) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_deep_link() {
+        let link = |target: &str, code: &str| Some(DeepLink { target: target.to_string(), code: code.to_string() });
+        assert_eq!(parse("stockhelper://stock/sh600519"), link("stock", "sh600519"));
+        assert_eq!(parse("StockHelper://analysis/000001/?from=push"), link("analysis", "sz000001"));
+        assert_eq!(parse("stockhelper://stock/600519\n"), link("stock", "sh600519"));
+        assert_eq!(parse("stockhelper://stock/abc"), None);
+        assert_eq!(parse("stockhelper://stock/中abcde"), None);
+        assert_eq!(parse("stockhelper://analysis/中中ab"), None);
+        assert_eq!(parse("stockhelper://settings/sh600519"), None);
+        assert_eq!(parse("https://stock/sh600519"), None);
+        assert_eq!(parse("stockhelper://"), None);
+
+        let args = vec!["app.exe".to_string(), "stockhelper://stock/sh600519".to_string()];
+        assert_eq!(url_from_args(args).as_deref(), Some("stockhelper://stock/sh600519"));
+        assert_eq!(stock_url("sz000001"), "stockhelper://stock/sz000001");
+    }
+}
@@ -0,0 +1,117 @@
+use anyhow::Result;
+use futures::future::join_all;
+use std::sync::atomic::Ordering;
+use std::time::Instant;
+use tauri::{AppHandle, Manager};
+
+use crate::AppState;
+use crate::models::diagnostics::{CacheStat, DiagnosticsReport, SourceHealth};
+use crate::services::job_scheduler;
+use crate::services::schema_guard;
+use crate::utils::http::{build_ai_client, build_stock_client, SendLogged};
+
+/// 探测的数据源：名称与一个轻量请求地址（与实际取数使用的接口一致）
+const SOURCES: &[(&str, &str)] = &[
+    ("新浪行情", "http://hq.sinajs.cn/list=sh000001"),
+    ("腾讯行情", "http://qt.gtimg.cn/q=sh000001"),
+    ("东方财富行情", "https://push2.eastmoney.com/api/qt/stock/get?fltt=2&invt=2&fields=f57,f58&secid=1.000001"),
+    ("东方财富K线", "https://push2his.eastmoney.com/api/qt/stock/kline/get?secid=1.000001&klt=101&fqt=1&lmt=1&end=20500101&fields1=f1&fields2=f51"),
+    ("东方财富数据中心", "https://datacenter-web.eastmoney.com/api/data/v1/get?reportName=RPT_ECONOMY_GDP&columns=REPORT_DATE&pageSize=1"),
+    ("财联社", "https://www.cls.cn/"),
+];
+
+/// 统计的缓存表：表名、说明、日期列
+const CACHE_TABLES: &[(&str, &str, Option<&str>)] = &[
+    ("stock_daily_history", "日K线缓存", Some("date")),
+    ("technical_daily", "技术指标", Some("date")),
+    ("stock_rps", "RPS 排名", Some("date")),
+    ("market_breadth", "市场宽度", Some("date")),
+    ("board_rank_history", "板块排名", Some("date")),
+    ("valuation_history", "估值历史", Some("date")),
+    ("watchlist_snapshots", "自选股快照", Some("date")),
+    ("stock_symbols", "股票代码表", Some("updated_at")),
+    ("agent_sessions", "AI 会话", Some("created_at")),
+    ("job_runs", "任务运行记录", Some("started_at")),
+];
+
+/// AI 接口探测超时（秒），数据源使用行情 client 的默认超时
+const AI_PROBE_TIMEOUT_SECS: u64 = 8;
+
+async fn probe(client: &reqwest::Client, name: &str, url: &str) -> SourceHealth {
+    let start = Instant::now();
+    match client.get(url).send_logged().await {
+        Ok(resp) => SourceHealth {
+            name: name.to_string(),
+            url: url.to_string(),
+            reachable: true,
+            latency_ms: Some(start.elapsed().as_millis() as u64),
+            status: Some(resp.status().as_u16()),
+            error: None,
+        },
+        Err(e) => SourceHealth {
+            name: name.to_string(),
+            url: url.to_string(),
+            reachable: false,
+            latency_ms: None,
+            status: None,
+            error: Some(e.to_string()),
+        },
+    }
+}
+
+/// 并发探测各数据源与当前 AI 接口的连通性和延迟
+pub async fn probe_sources(ai_base_url: Option<&str>) -> Result<Vec<SourceHealth>> {
+    let client = build_stock_client()?;
+    let ai_client = build_ai_client(AI_PROBE_TIMEOUT_SECS)?;
+    let ai_url = ai_base_url.map(|base| format!("{}/models", base.trim_end_matches('/')));
+
+    let mut results = join_all(SOURCES.iter().map(|(name, url)| probe(&client, name, url))).await;
+    if let Some(url) = ai_url {
+        results.push(probe(&ai_client, "AI 接口", &url).await);
+    }
+    Ok(results)
+}
+
+fn cache_stats(state: &AppState) -> Vec<CacheStat> {
+    CACHE_TABLES
+        .iter()
+        .filter_map(|(table, label, date_column)| match state.db.get_table_stat(table, *date_column) {
+            Ok((rows, latest)) => Some(CacheStat { table: table.to_string(), label: label.to_string(), rows, latest }),
+            Err(e) => {
+                log::warn!("[diagnostics] stat table {} failed: {}", table, e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// 生成诊断报告：数据源连通性与格式变更、数据库位置与大小、缓存表统计、后台任务最近运行情况
+pub async fn collect(app: &AppHandle) -> Result<DiagnosticsReport> {
+    let state = app.state::<AppState>();
+    let settings = state.db.load_settings()?;
+    let ai_base_url = settings.active_ai_config().map(|c| c.base_url);
+
+    let sources = probe_sources(ai_base_url.as_deref()).await?;
+    let (db_path, db_size_bytes) = state.db.file_info()?;
+    let report = DiagnosticsReport {
+        generated_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        app_version: app.package_info().version.to_string(),
+        os: format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+        db_path,
+        db_size_bytes,
+        sources,
+        schema_drifts: schema_guard::list(),
+        caches: cache_stats(&state),
+        jobs: job_scheduler::list_jobs(app)?,
+        last_successes: state.db.get_last_job_successes()?,
+        active_tasks: state.shutdown.active_tasks(),
+        ai_picking: state.ai_picking.load(Ordering::SeqCst),
+    };
+    log::info!(
+        "[diagnostics] collect sources_down={} schema_drifts={} db_size={}",
+        report.sources.iter().filter(|s| !s.reachable).count(),
+        report.schema_drifts.len(),
+        report.db_size_bytes
+    );
+    Ok(report)
+}
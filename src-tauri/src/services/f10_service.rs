@@ -0,0 +1,266 @@
+use anyhow::{anyhow, Result};
+use chrono::Datelike;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::models::f10::{BusinessSegment, EarningsForecast, ForecastYear, RatingDistribution, StockProfile};
+use crate::services::market_scanner::MarketScanner;
+use crate::services::stock_data::{code_to_pure, format_stock_code};
+use crate::utils::http::build_f10_client;
+use crate::utils::http::SendLogged;
+
+const F10_BASE: &str = "https://emweb.securities.eastmoney.com/PC_HSF10";
+/// 一致预期统计的研报回看天数
+const FORECAST_LOOKBACK_DAYS: i64 = 180;
+/// 公司资料与一致预期的内存缓存有效期：两者按季度/研报发布更新，半天内无需重复拉取
+const CACHE_TTL: Duration = Duration::from_secs(12 * 3600);
+
+type TtlCache<T> = Mutex<HashMap<String, (Instant, T)>>;
+
+fn profile_cache() -> &'static TtlCache<StockProfile> {
+    static CACHE: OnceLock<TtlCache<StockProfile>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn forecast_cache() -> &'static TtlCache<EarningsForecast> {
+    static CACHE: OnceLock<TtlCache<EarningsForecast>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn cached<T: Clone>(cache: &TtlCache<T>, code: &str) -> Option<T> {
+    cache
+        .lock()
+        .unwrap()
+        .get(code)
+        .filter(|(at, _)| at.elapsed() < CACHE_TTL)
+        .map(|(_, v)| v.clone())
+}
+
+/// sh600519 -> SH600519（F10 接口要求大写市场前缀）
+fn f10_code(code: &str) -> String {
+    format_stock_code(code).to_uppercase()
+}
+
+fn str_field(item: &Value, key: &str) -> String {
+    match &item[key] {
+        Value::String(s) => s.trim().to_string(),
+        Value::Number(n) => n.to_string(),
+        _ => String::new(),
+    }
+}
+
+/// 日期字段形如 "2001-08-27 00:00:00"，只保留日期部分
+fn date_field(item: &Value, key: &str) -> String {
+    str_field(item, key).chars().take(10).collect()
+}
+
+async fn fetch_f10_json(page: &str, code: &str) -> Result<Value> {
+    let client = build_f10_client()?;
+    let url = format!("{}/{}/PageAjax?code={}", F10_BASE, page, f10_code(code));
+    let json: Value = client.get(&url).send_logged().await?.json().await?;
+    Ok(json)
+}
+
+/// 获取公司简介、主营业务与最新报告期的主营构成（带 12 小时内存缓存）
+pub async fn fetch_stock_profile(code: &str) -> Result<StockProfile> {
+    let code = format_stock_code(code);
+    if let Some(profile) = cached(profile_cache(), &code) {
+        return Ok(profile);
+    }
+    let profile = load_stock_profile(code.clone()).await?;
+    profile_cache().lock().unwrap().insert(code, (Instant::now(), profile.clone()));
+    Ok(profile)
+}
+
+async fn load_stock_profile(code: String) -> Result<StockProfile> {
+    let (survey, business) = tokio::join!(
+        fetch_f10_json("CompanySurvey", &code),
+        fetch_f10_json("BusinessAnalysis", &code),
+    );
+    let survey = survey?;
+    let basic = survey["jbzl"]
+        .as_array()
+        .and_then(|arr| arr.first())
+        .ok_or_else(|| anyhow!("未找到 {} 的公司资料", code))?;
+    let listing_date = survey["fxxg"]
+        .as_array()
+        .and_then(|arr| arr.first())
+        .map(|item| date_field(item, "LISTING_DATE"))
+        .unwrap_or_default();
+
+    // 经营分析失败不影响基本资料
+    let business = business.unwrap_or_else(|e| {
+        log::warn!("[f10_service] fetch BusinessAnalysis failed for {}: {}", code, e);
+        Value::Null
+    });
+    let main_business = business["zyfw"]
+        .as_array()
+        .and_then(|arr| arr.first())
+        .map(|item| str_field(item, "BUSINESS_SCOPE"))
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| str_field(basic, "BUSINESS_SCOPE"));
+    let (report_date, segments) = parse_segments(&business["zygcfx"]);
+
+    Ok(StockProfile {
+        code: code.clone(),
+        name: str_field(basic, "SECURITY_NAME_ABBR"),
+        full_name: str_field(basic, "ORG_NAME"),
+        industry: str_field(basic, "EM2016"),
+        csrc_industry: str_field(basic, "INDUSTRYCSRC1"),
+        listing_date,
+        chairman: str_field(basic, "CHAIRMAN"),
+        province: str_field(basic, "PROVINCE"),
+        website: str_field(basic, "ORG_WEB"),
+        profile: str_field(basic, "ORG_PROFILE"),
+        main_business,
+        report_date,
+        segments,
+    })
+}
+
+/// 主营构成按报告期倒序返回，只取最新一期；MAINOP_TYPE 1=按行业 2=按产品 3=按地区
+fn parse_segments(data: &Value) -> (String, Vec<BusinessSegment>) {
+    let items = match data.as_array() {
+        Some(arr) if !arr.is_empty() => arr,
+        _ => return (String::new(), vec![]),
+    };
+    let report_date = items
+        .iter()
+        .map(|item| date_field(item, "REPORT_DATE"))
+        .max()
+        .unwrap_or_default();
+
+    let segments = items
+        .iter()
+        .filter(|item| date_field(item, "REPORT_DATE") == report_date)
+        .filter_map(|item| {
+            let category = match str_field(item, "MAINOP_TYPE").as_str() {
+                "1" => "行业",
+                "2" => "产品",
+                "3" => "地区",
+                _ => return None,
+            };
+            Some(BusinessSegment {
+                category: category.to_string(),
+                name: str_field(item, "ITEM_NAME"),
+                revenue: item["MAIN_BUSINESS_INCOME"].as_f64().unwrap_or(0.0),
+                // 比例字段为小数（0.85 = 85%）
+                revenue_ratio: item["MBI_RATIO"].as_f64().unwrap_or(0.0) * 100.0,
+                profit: item["MAIN_BUSINESS_RPOFIT"].as_f64().unwrap_or(0.0),
+                gross_margin: item["GROSS_RPOFIT_RATIO"].as_f64().unwrap_or(0.0) * 100.0,
+            })
+        })
+        .collect();
+    (report_date, segments)
+}
+
+/// 汇总近半年个股研报的盈利预测，得到未来两个会计年度的一致预期 EPS / 净利润与评级分布（带 12 小时内存缓存）
+pub async fn fetch_earnings_forecast(code: &str) -> Result<EarningsForecast> {
+    let code = format_stock_code(code);
+    if let Some(forecast) = cached(forecast_cache(), &code) {
+        return Ok(forecast);
+    }
+    let forecast = load_earnings_forecast(code.clone()).await?;
+    forecast_cache().lock().unwrap().insert(code, (Instant::now(), forecast.clone()));
+    Ok(forecast)
+}
+
+async fn load_earnings_forecast(code: String) -> Result<EarningsForecast> {
+    let client = build_f10_client()?;
+    let today = chrono::Local::now().date_naive();
+    let begin = today - chrono::Duration::days(FORECAST_LOOKBACK_DAYS);
+    let url = format!(
+        "https://reportapi.eastmoney.com/report/list?industryCode=*&pageSize=100&industry=*&rating=*&ratingChange=*&beginTime={}&endTime={}&pageNo=1&fields=&qType=0&orgCode=&code={}&rcode=&_={}",
+        begin.format("%Y-%m-%d"),
+        today.format("%Y-%m-%d"),
+        code_to_pure(&code),
+        chrono::Utc::now().timestamp_millis()
+    );
+    let json: Value = client.get(&url).send_logged().await?.json().await?;
+    let reports = json["data"].as_array().cloned().unwrap_or_default();
+
+    let mut name = String::new();
+    let mut ratings = RatingDistribution::default();
+    let mut orgs = HashSet::new();
+    // 年份 -> 各研报的 EPS 预测；"今年"以研报发布年份为准，跨年的研报自动落到正确的会计年度
+    let mut eps_by_year: BTreeMap<i32, Vec<f64>> = BTreeMap::new();
+    for r in &reports {
+        if name.is_empty() {
+            name = str_field(r, "stockName");
+        }
+        orgs.insert(str_field(r, "orgSName"));
+        match str_field(r, "emRatingName").as_str() {
+            "买入" => ratings.buy += 1,
+            "增持" => ratings.overweight += 1,
+            "中性" => ratings.neutral += 1,
+            "减持" => ratings.underweight += 1,
+            "卖出" => ratings.sell += 1,
+            _ => {}
+        }
+        let publish_year = match str_field(r, "publishDate").get(..4).and_then(|y| y.parse::<i32>().ok()) {
+            Some(y) => y,
+            None => continue,
+        };
+        for (offset, key) in [(0, "predictThisYearEps"), (1, "predictNextYearEps"), (2, "predictNextTwoYearEps")] {
+            if let Some(eps) = number_field(r, key) {
+                eps_by_year.entry(publish_year + offset).or_default().push(eps);
+            }
+        }
+    }
+
+    // 总股本与最新价用于换算净利润和预测 PE，行情失败时只返回 EPS
+    let quote = match MarketScanner::new()?.fetch_stocks_by_codes(std::slice::from_ref(&code)).await {
+        Ok(mut quotes) => quotes.pop(),
+        Err(e) => {
+            log::warn!("[f10_service] fetch quote failed for {}: {}", code, e);
+            None
+        }
+    };
+    let (price, total_shares) = match &quote {
+        Some(q) if q.price > 0.0 => (q.price, q.total_market_cap / q.price),
+        _ => (0.0, 0.0),
+    };
+    if name.is_empty() {
+        name = quote.map(|q| q.name).unwrap_or_default();
+    }
+
+    let current_year = today.year();
+    let years = eps_by_year
+        .into_iter()
+        .filter(|(year, _)| *year >= current_year && *year <= current_year + 1)
+        .map(|(year, values)| {
+            let eps = values.iter().sum::<f64>() / values.len() as f64;
+            ForecastYear {
+                year,
+                eps,
+                eps_min: values.iter().cloned().fold(f64::INFINITY, f64::min),
+                eps_max: values.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+                net_profit: eps * total_shares,
+                forward_pe: if eps > 0.0 && price > 0.0 { price / eps } else { 0.0 },
+                sample_count: values.len(),
+            }
+        })
+        .collect();
+
+    orgs.remove("");
+    Ok(EarningsForecast {
+        code,
+        name,
+        report_count: reports.len(),
+        org_count: orgs.len(),
+        ratings,
+        years,
+    })
+}
+
+/// 研报预测字段可能是数字、数字字符串或空串
+fn number_field(item: &Value, key: &str) -> Option<f64> {
+    match &item[key] {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse::<f64>().ok(),
+        _ => None,
+    }
+    .filter(|v| v.is_finite())
+}
@@ -0,0 +1,357 @@
+use anyhow::{anyhow, Result};
+use chrono::Local;
+use std::collections::HashMap;
+use tauri::Manager;
+
+use crate::AppState;
+use crate::db::database::Database;
+use crate::models::settings::{FactorWeights, RegimeFactorWeights};
+use crate::models::stock::{CumulativeFlow, FactorScore, MarketRegime, MarketStockSnapshot};
+use crate::models::watchlist::KlineItem;
+use crate::services::filter_expr;
+use crate::services::fund_flow;
+use crate::services::job_scheduler::{JobSpec, JobTrigger};
+use crate::services::market_regime;
+use crate::services::market_scanner::MarketScanner;
+use crate::services::risk_metrics;
+use crate::services::rps::rank_percentiles;
+
+/// 保留的记录日数：覆盖约一个半月，满足近一月走势
+const KEEP_DAYS: usize = 30;
+/// 收盘后计算的时间（HHMM），与 RPS 错开
+const RECORD_AFTER: u32 = 1540;
+const CHECK_INTERVAL_SECS: u64 = 600;
+/// 相对动量的映射尺度：超额收益 ±20% 约对应 12 / 88 分
+const EXCESS_SCALE: f64 = 20.0;
+
+/// (字段, 是否越低越好, 0 值是否视为缺失)
+type FactorField = (&'static str, bool, bool);
+
+/// 分项因子：(分项, 字段列表)
+const FACTOR_GROUPS: [(&str, &[FactorField]); 5] = [
+    ("quality", &[("roe", false, true), ("gross_margin", false, true)]),
+    ("growth", &[("revenue_yoy", false, true), ("profit_yoy", false, true)]),
+    ("value", &[("pe_ttm", true, true), ("pb", true, true)]),
+    ("momentum", &[("pct_20d", false, false), ("pct_60d", false, false)]),
+    ("flow", &[("main_net_pct", false, false), ("main_10d_ratio", false, false)]),
+];
+
+/// 基准（沪深300）在与个股相同窗口内的涨幅 %，用于计算相对动量
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BenchmarkReturns {
+    pub pct_5d: f64,
+    pub pct_20d: f64,
+    pub pct_60d: f64,
+}
+
+impl BenchmarkReturns {
+    /// 由按日期升序的指数日线计算，日线不足 61 根时返回 None
+    pub fn from_klines(klines: &[KlineItem]) -> Option<Self> {
+        let n = klines.len();
+        if n < 61 {
+            return None;
+        }
+        let last = klines[n - 1].close;
+        let pct = |days: usize| {
+            let base = klines[n - 1 - days].close;
+            if base > 0.0 { (last / base - 1.0) * 100.0 } else { 0.0 }
+        };
+        Some(Self { pct_5d: pct(5), pct_20d: pct(20), pct_60d: pct(60) })
+    }
+
+    /// 拉取沪深300日线（复用风险统计的基准缓存）计算窗口涨幅
+    pub async fn fetch() -> Result<Self> {
+        let klines = risk_metrics::benchmark_klines().await?;
+        Self::from_klines(&klines).ok_or_else(|| anyhow!("沪深300日线不足 61 根"))
+    }
+
+    /// 个股相对基准的超额收益 %，窗口为 5 / 20 / 60 日
+    pub fn excess(&self, stock: &MarketStockSnapshot, days: u32) -> f64 {
+        match days {
+            5 => stock.pct_5d - self.pct_5d,
+            20 => stock.pct_20d - self.pct_20d,
+            _ => stock.pct_60d - self.pct_60d,
+        }
+    }
+}
+
+/// 相对动量分：20 日、60 日超额收益各自映射到 0~100（跑平基准为 50 分）后取均值。
+/// 绝对分值不随当日全市场涨跌漂移，评分走势可直接比较不同日期
+fn relative_momentum(stock: &MarketStockSnapshot, benchmark: &BenchmarkReturns) -> f64 {
+    let score = |days| 50.0 + 50.0 * (benchmark.excess(stock, days) / EXCESS_SCALE).tanh();
+    (score(20) + score(60)) / 2.0
+}
+
+fn factor_value(stock: &MarketStockSnapshot, field: &str, flows: &HashMap<String, CumulativeFlow>) -> f64 {
+    match field {
+        "main_net_pct" => stock.main_net_pct,
+        // 近 10 日累计主力净流入占流通市值 %，本地记录不足 10 日时缺失
+        "main_10d_ratio" => flows
+            .get(&stock.code)
+            .and_then(|f| f.main_10d)
+            .filter(|_| stock.float_market_cap > 0.0)
+            .map_or(f64::NAN, |m| m / stock.float_market_cap * 100.0),
+        _ => filter_expr::numeric(stock, field),
+    }
+}
+
+/// 按市场状态选取因子权重：取映射表中第一条趋势与波动均匹配的记录，无匹配或市场状态未知时等权
+pub fn weights_for(table: &[RegimeFactorWeights], regime: Option<&MarketRegime>) -> FactorWeights {
+    let Some(regime) = regime else {
+        return FactorWeights::default();
+    };
+    table
+        .iter()
+        .find(|m| {
+            m.trend.as_ref().map_or(true, |t| *t == regime.trend)
+                && m.volatility.as_ref().map_or(true, |v| *v == regime.volatility)
+        })
+        .map(|m| m.weights.clone())
+        .unwrap_or_default()
+}
+
+/// 综合分：有数据分项的加权均值，权重全为 0 时退化为等权
+fn weighted_score(parts: &[Option<f64>], weights: &FactorWeights) -> Option<f64> {
+    // 与 FACTOR_GROUPS 顺序一致
+    let group_weights = [weights.quality, weights.growth, weights.value, weights.momentum, weights.flow];
+    let present: Vec<(f64, f64)> = parts
+        .iter()
+        .zip(group_weights)
+        .filter_map(|(p, w)| p.map(|p| (p, w.max(0.0))))
+        .collect();
+    if present.is_empty() {
+        return None;
+    }
+    let total: f64 = present.iter().map(|(_, w)| w).sum();
+    if total > 0.0 {
+        Some(present.iter().map(|(p, w)| p * w).sum::<f64>() / total)
+    } else {
+        Some(present.iter().map(|(p, _)| p).sum::<f64>() / present.len() as f64)
+    }
+}
+
+fn round1(v: f64) -> f64 {
+    (v * 10.0).round() / 10.0
+}
+
+/// 计算全市场当日因子评分：每个字段先算全市场百分位，分项取字段均值，综合分取分项按 weights 的加权均值（停牌股不参与）。
+/// 传入 benchmark 时动量分项改用相对沪深300的超额收益；资金分项综合当日主力净占比与近 10 日累计净流入
+pub fn compute_scores(
+    stocks: &[MarketStockSnapshot],
+    date: &str,
+    benchmark: Option<&BenchmarkReturns>,
+    flows: &HashMap<String, CumulativeFlow>,
+    weights: &FactorWeights,
+) -> Vec<FactorScore> {
+    let stocks: Vec<&MarketStockSnapshot> = stocks.iter().filter(|s| s.price > 0.0).collect();
+    // groups[g][i]：第 g 个分项下第 i 只股票的得分
+    let groups: Vec<Vec<Option<f64>>> = FACTOR_GROUPS
+        .iter()
+        .map(|(group, fields)| {
+            if let (&"momentum", Some(benchmark)) = (group, benchmark) {
+                return stocks.iter().map(|s| Some(relative_momentum(s, benchmark))).collect();
+            }
+            let field_ranks: Vec<Vec<Option<f64>>> = fields
+                .iter()
+                .map(|(field, lower_better, zero_missing)| {
+                    let values: Vec<Option<f64>> = stocks
+                        .iter()
+                        .map(|s| {
+                            let v = factor_value(s, field, flows);
+                            // 越低越好的估值类因子为负（亏损、资不抵债）时无意义
+                            let valid = v.is_finite() && !(*zero_missing && v == 0.0) && !(*lower_better && v < 0.0);
+                            valid.then_some(if *lower_better { -v } else { v })
+                        })
+                        .collect();
+                    rank_percentiles(&values)
+                })
+                .collect();
+            (0..stocks.len())
+                .map(|i| {
+                    let ranks: Vec<f64> = field_ranks.iter().filter_map(|r| r[i]).collect();
+                    (!ranks.is_empty()).then(|| ranks.iter().sum::<f64>() / ranks.len() as f64)
+                })
+                .collect()
+        })
+        .collect();
+
+    stocks
+        .iter()
+        .enumerate()
+        .filter_map(|(i, s)| {
+            let parts: Vec<Option<f64>> = groups.iter().map(|g| g[i].map(round1)).collect();
+            let score = weighted_score(&parts, weights)?;
+            Some(FactorScore {
+                code: s.code.clone(),
+                name: s.name.clone(),
+                date: date.to_string(),
+                score: round1(score),
+                quality: parts[0],
+                growth: parts[1],
+                value: parts[2],
+                momentum: parts[3],
+                flow: parts[4],
+            })
+        })
+        .collect()
+}
+
+/// 扫描全市场计算当日因子评分并保存，返回记录的股票数
+pub async fn record(db: &Database) -> Result<usize> {
+    let stocks = MarketScanner::new()?.scan_full_market().await?;
+    if stocks.is_empty() {
+        return Err(anyhow!("全市场扫描返回为空"));
+    }
+    let settings = db.load_settings()?;
+    let benchmark = if settings.momentum_vs_benchmark {
+        match BenchmarkReturns::fetch().await {
+            Ok(b) => Some(b),
+            Err(e) => {
+                log::warn!("[factor_score] fetch benchmark failed, fallback to absolute momentum: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let today = Local::now().format("%Y-%m-%d").to_string();
+    fund_flow::record_snapshot(db, &stocks, &today)?;
+    let flows = fund_flow::cumulative_all(db)?;
+    let regime = match market_regime::current(db).await {
+        Ok(r) => Some(r),
+        Err(e) => {
+            log::warn!("[factor_score] market_regime failed, fallback to equal weights: {}", e);
+            None
+        }
+    };
+    let weights = weights_for(&settings.regime_factor_weights, regime.as_ref());
+    log::info!("[factor_score] regime={:?} weights={:?}", regime.as_ref().map(|r| &r.summary), weights);
+    let records = compute_scores(&stocks, &today, benchmark.as_ref(), &flows, &weights);
+    db.save_factor_scores(&records, KEEP_DAYS)?;
+    log::info!("[factor_score] recorded {} stocks", records.len());
+    Ok(records.len())
+}
+
+/// 个股最近 days 个记录日的评分走势（按日期正序）
+pub fn get_history(db: &Database, code: &str, days: usize) -> Result<Vec<FactorScore>> {
+    db.get_factor_score_history(code, days.clamp(1, KEEP_DAYS))
+}
+
+/// 后台任务：交易日收盘后记录全市场因子评分
+pub fn factor_score_record_job() -> JobSpec {
+    JobSpec {
+        id: "factor_score_record",
+        name: "因子评分记录",
+        trigger: JobTrigger::DailyAfter(RECORD_AFTER),
+        interval_secs: CHECK_INTERVAL_SECS,
+        run: |app| Box::pin(async move {
+            let count = record(&app.state::<AppState>().db).await?;
+            Ok(Some(format!("记录 {} 只股票", count)))
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stock(code: &str, roe: f64, pe_ttm: f64, pct_20d: f64) -> MarketStockSnapshot {
+        MarketStockSnapshot {
+            code: code.to_string(),
+            name: code.to_string(),
+            price: 10.0,
+            roe,
+            pe_ttm,
+            pct_20d,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_compute_scores() {
+        let stocks = vec![
+            stock("a", 20.0, 10.0, 10.0),
+            stock("b", 10.0, 30.0, 0.0),
+            stock("c", 0.0, -5.0, -10.0),
+            MarketStockSnapshot { code: "halt".to_string(), ..Default::default() },
+        ];
+        let scores = compute_scores(&stocks, "2024-06-06", None, &HashMap::new(), &FactorWeights::default());
+        assert_eq!(scores.len(), 3);
+        assert_eq!(scores[0].quality, Some(100.0));
+        assert_eq!(scores[0].value, Some(100.0));
+        assert_eq!(scores[1].value, Some(0.0));
+        // ROE 为 0、PE 为负视为缺失，只剩动量与资金
+        assert_eq!(scores[2].quality, None);
+        assert_eq!(scores[2].value, None);
+        assert_eq!(scores[2].momentum, Some(0.0));
+        assert_eq!(scores[0].growth, None);
+        assert!(scores[0].score > scores[1].score && scores[1].score > scores[2].score);
+    }
+
+    #[test]
+    fn test_regime_weights() {
+        use crate::models::stock::{RegimeStyle, RegimeTrend, RegimeVolatility};
+
+        let table = crate::models::settings::AppSettings::default().regime_factor_weights;
+        let regime = |trend| MarketRegime {
+            date: String::new(),
+            trend,
+            volatility: RegimeVolatility::Normal,
+            style: RegimeStyle::Balanced,
+            index_close: 0.0,
+            ma20: 0.0,
+            ma60: 0.0,
+            return_20d: 0.0,
+            volatility_20d: 0.0,
+            volatility_1y: 0.0,
+            large_cap_return_20d: 0.0,
+            small_cap_return_20d: 0.0,
+            above_ma20_pct: None,
+            volume_ratio: None,
+            summary: String::new(),
+            created_at: String::new(),
+        };
+        assert_eq!(weights_for(&table, Some(&regime(RegimeTrend::Up))).momentum, 2.0);
+        assert_eq!(weights_for(&table, Some(&regime(RegimeTrend::Down))).value, 2.0);
+        assert_eq!(weights_for(&table, Some(&regime(RegimeTrend::Range))), FactorWeights::default());
+        assert_eq!(weights_for(&table, None), FactorWeights::default());
+
+        // 缺失分项不参与加权
+        let weights = FactorWeights { momentum: 3.0, ..Default::default() };
+        assert_eq!(weighted_score(&[Some(40.0), None, None, Some(80.0), None], &weights), Some(70.0));
+        assert_eq!(weighted_score(&[None; 5], &weights), None);
+    }
+
+    #[test]
+    fn test_relative_momentum() {
+        let klines: Vec<KlineItem> = (0..=60)
+            .map(|i| {
+                let close = if i >= 45 { 110.0 } else { 100.0 };
+                KlineItem {
+                    date: format!("d{}", i),
+                    open: close,
+                    close,
+                    high: close,
+                    low: close,
+                    volume: 0.0,
+                    amount: 0.0,
+                    change_pct: 0.0,
+                    turnover_rate: 0.0,
+                }
+            })
+            .collect();
+        let benchmark = BenchmarkReturns::from_klines(&klines).unwrap();
+        assert!((benchmark.pct_20d - 10.0).abs() < 1e-9);
+        assert!((benchmark.pct_60d - 10.0).abs() < 1e-9);
+        assert!(BenchmarkReturns::from_klines(&klines[1..]).is_none());
+
+        // 普涨行情下跟涨基准得 50 分，跑赢得分更高
+        let mut follower = stock("a", 0.0, 0.0, 10.0);
+        follower.pct_60d = 10.0;
+        let mut leader = stock("b", 0.0, 0.0, 30.0);
+        leader.pct_60d = 30.0;
+        let scores = compute_scores(&[follower, leader], "2024-06-06", Some(&benchmark), &HashMap::new(), &FactorWeights::default());
+        assert!((scores[0].momentum.unwrap() - 50.0).abs() < 0.1);
+        assert!(scores[1].momentum.unwrap() > 80.0);
+    }
+}
@@ -0,0 +1,544 @@
+use anyhow::{anyhow, Result};
+
+use crate::models::stock::{ExpressionScreenResult, MarketStockSnapshot};
+use crate::services::factor_score::BenchmarkReturns;
+use crate::services::market_scanner::MarketScanner;
+use crate::services::scan_explain;
+use crate::services::theme_exposure;
+
+/// 表达式中可用的数值字段（字段名, 说明），与 MarketStockSnapshot 同名
+pub const NUMERIC_FIELDS: [(&str, &str); 27] = [
+    ("price", "最新价"),
+    ("change_pct", "涨跌幅 %"),
+    ("change_amount", "涨跌额"),
+    ("volume", "成交量（手）"),
+    ("amount", "成交额（元）"),
+    ("amplitude", "振幅 %"),
+    ("turnover_rate", "换手率 %"),
+    ("pe_ttm", "市盈率 TTM"),
+    ("pb", "市净率"),
+    ("total_market_cap", "总市值（元）"),
+    ("float_market_cap", "流通市值（元）"),
+    ("volume_ratio", "量比"),
+    ("high", "最高价"),
+    ("low", "最低价"),
+    ("open", "开盘价"),
+    ("pre_close", "昨收"),
+    ("pct_5d", "5 日涨幅 %"),
+    ("pct_20d", "20 日涨幅 %"),
+    ("pct_60d", "60 日涨幅 %"),
+    ("roe", "ROE %"),
+    ("gross_margin", "毛利率 %"),
+    ("revenue_yoy", "营收同比 %"),
+    ("profit_yoy", "净利润同比 %"),
+    ("main_net_inflow", "主力净流入（元）"),
+    ("dividend_yield", "股息率 %"),
+    ("fcf_yield", "现金流收益率 %"),
+    ("debt_ratio", "资产负债率 %"),
+];
+/// 表达式中可用的文本字段
+pub const TEXT_FIELDS: [(&str, &str); 3] = [("code", "代码"), ("name", "名称"), ("list_date", "上市日期 YYYYMMDD")];
+/// 需要补充请求才能获取的财务字段，表达式用到时全市场逐批补充
+const FUNDAMENTAL_FIELDS: [&str; 4] = ["dividend_yield", "fcf_yield", "debt_ratio", "profit_yoy"];
+/// 结果中查询主要题材的股票数（每只需单独请求所属板块）
+const THEME_LOOKUP_LIMIT: usize = 30;
+/// 文本字段支持的方法
+const TEXT_METHODS: [&str; 3] = ["contains", "starts_with", "ends_with"];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Num(f64),
+    Str(String),
+    Ident(String),
+    Op(&'static str),
+    LParen,
+    RParen,
+    Dot,
+}
+
+fn tokenize(input: &str) -> Result<Vec<(Token, usize)>> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        if c.is_ascii_digit() || (c == '.' && chars.get(i + 1).is_some_and(|n| n.is_ascii_digit())) {
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            // 科学计数法，如 1e8
+            if i < chars.len() && (chars[i] == 'e' || chars[i] == 'E') && chars.get(i + 1).is_some_and(|n| n.is_ascii_digit() || *n == '-') {
+                i += 2;
+                while i < chars.len() && chars[i].is_ascii_digit() {
+                    i += 1;
+                }
+            }
+            let text: String = chars[start..i].iter().collect();
+            let n = text.parse::<f64>().map_err(|_| anyhow!("第 {} 个字符处的数字无效: {}", start + 1, text))?;
+            tokens.push((Token::Num(n), start));
+            continue;
+        }
+        if c == '\'' || c == '"' {
+            i += 1;
+            while i < chars.len() && chars[i] != c {
+                i += 1;
+            }
+            if i >= chars.len() {
+                return Err(anyhow!("第 {} 个字符处的字符串缺少结束引号", start + 1));
+            }
+            tokens.push((Token::Str(chars[start + 1..i].iter().collect()), start));
+            i += 1;
+            continue;
+        }
+        if c.is_alphabetic() || c == '_' {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push((Token::Ident(chars[start..i].iter().collect()), start));
+            continue;
+        }
+        let two: String = chars[i..(i + 2).min(chars.len())].iter().collect();
+        let op = ["&&", "||", "<=", ">=", "==", "!="].into_iter().find(|op| *op == two);
+        let token = match (op, c) {
+            (Some(op), _) => {
+                i += 2;
+                tokens.push((Token::Op(op), start));
+                continue;
+            }
+            (None, '(') => Token::LParen,
+            (None, ')') => Token::RParen,
+            (None, '.') => Token::Dot,
+            (None, '<') => Token::Op("<"),
+            (None, '>') => Token::Op(">"),
+            (None, '!') => Token::Op("!"),
+            (None, '+') => Token::Op("+"),
+            (None, '-') => Token::Op("-"),
+            (None, '*') => Token::Op("*"),
+            (None, '/') => Token::Op("/"),
+            _ => return Err(anyhow!("第 {} 个字符处无法识别: {}", start + 1, c)),
+        };
+        tokens.push((token, start));
+        i += 1;
+    }
+    Ok(tokens)
+}
+
+/// 表达式语法树，解析时已完成字段与类型检查，求值不会出错
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Num(f64),
+    Field(String),
+    Text(String),
+    Neg(Box<Expr>),
+    Arith(&'static str, Box<Expr>, Box<Expr>),
+    Compare(&'static str, Box<Expr>, Box<Expr>),
+    TextCompare(&'static str, Box<Expr>, Box<Expr>),
+    Between(Box<Expr>, Box<Expr>, Box<Expr>),
+    TextMethod(String, &'static str, String),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Num,
+    Text,
+    Bool,
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(t, _)| t)
+    }
+
+    fn at(&self) -> String {
+        match self.tokens.get(self.pos) {
+            Some((_, offset)) => format!("第 {} 个字符处", offset + 1),
+            None => "表达式末尾".to_string(),
+        }
+    }
+
+    fn eat_op(&mut self, op: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Op(o)) if *o == op) {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Ident(w)) if w.eq_ignore_ascii_case(keyword)) {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+
+    fn expect_bool(&self, expr: (Expr, Kind), what: &str) -> Result<Expr> {
+        if expr.1 != Kind::Bool {
+            return Err(anyhow!("{}需要是条件表达式（比较、between 或 contains）", what));
+        }
+        Ok(expr.0)
+    }
+
+    fn or(&mut self) -> Result<(Expr, Kind)> {
+        let mut left = self.and()?;
+        while self.eat_op("||") || self.eat_keyword("or") {
+            let l = self.expect_bool(left, "|| 左侧")?;
+            let r = self.and()?;
+            let r = self.expect_bool(r, "|| 右侧")?;
+            left = (Expr::Or(Box::new(l), Box::new(r)), Kind::Bool);
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<(Expr, Kind)> {
+        let mut left = self.not()?;
+        while self.eat_op("&&") || self.eat_keyword("and") {
+            let l = self.expect_bool(left, "&& 左侧")?;
+            let r = self.not()?;
+            let r = self.expect_bool(r, "&& 右侧")?;
+            left = (Expr::And(Box::new(l), Box::new(r)), Kind::Bool);
+        }
+        Ok(left)
+    }
+
+    fn not(&mut self) -> Result<(Expr, Kind)> {
+        if self.eat_op("!") || self.eat_keyword("not") {
+            let inner = self.not()?;
+            let inner = self.expect_bool(inner, "! 之后")?;
+            return Ok((Expr::Not(Box::new(inner)), Kind::Bool));
+        }
+        self.compare()
+    }
+
+    fn compare(&mut self) -> Result<(Expr, Kind)> {
+        let left = self.sum()?;
+        if self.eat_keyword("between") {
+            let low = self.sum()?;
+            if !self.eat_keyword("and") {
+                return Err(anyhow!("{}缺少 between ... and ... 中的 and", self.at()));
+            }
+            let high = self.sum()?;
+            for (e, what) in [(&left, "between 左侧"), (&low, "between 下限"), (&high, "between 上限")] {
+                if e.1 != Kind::Num {
+                    return Err(anyhow!("{}需要是数值", what));
+                }
+            }
+            return Ok((Expr::Between(Box::new(left.0), Box::new(low.0), Box::new(high.0)), Kind::Bool));
+        }
+        let op = match self.peek() {
+            Some(Token::Op(op)) if ["<", "<=", ">", ">=", "==", "!="].contains(op) => *op,
+            _ => return Ok(left),
+        };
+        self.pos += 1;
+        let right = self.sum()?;
+        match (left.1, right.1) {
+            (Kind::Num, Kind::Num) => Ok((Expr::Compare(op, Box::new(left.0), Box::new(right.0)), Kind::Bool)),
+            (Kind::Text, Kind::Text) if op == "==" || op == "!=" => {
+                Ok((Expr::TextCompare(op, Box::new(left.0), Box::new(right.0)), Kind::Bool))
+            }
+            _ => Err(anyhow!("{} 两侧类型不匹配（文本只支持 == 与 !=）", op)),
+        }
+    }
+
+    fn sum(&mut self) -> Result<(Expr, Kind)> {
+        let mut left = self.product()?;
+        while let Some(Token::Op(op @ ("+" | "-"))) = self.peek() {
+            let op = *op;
+            self.pos += 1;
+            let right = self.product()?;
+            left = (self.arith(op, left, right)?, Kind::Num);
+        }
+        Ok(left)
+    }
+
+    fn product(&mut self) -> Result<(Expr, Kind)> {
+        let mut left = self.unary()?;
+        while let Some(Token::Op(op @ ("*" | "/"))) = self.peek() {
+            let op = *op;
+            self.pos += 1;
+            let right = self.unary()?;
+            left = (self.arith(op, left, right)?, Kind::Num);
+        }
+        Ok(left)
+    }
+
+    fn arith(&self, op: &'static str, left: (Expr, Kind), right: (Expr, Kind)) -> Result<Expr> {
+        if left.1 != Kind::Num || right.1 != Kind::Num {
+            return Err(anyhow!("{} 两侧需要是数值", op));
+        }
+        Ok(Expr::Arith(op, Box::new(left.0), Box::new(right.0)))
+    }
+
+    fn unary(&mut self) -> Result<(Expr, Kind)> {
+        if self.peek() == Some(&Token::Op("-")) {
+            self.pos += 1;
+            let inner = self.unary()?;
+            if inner.1 != Kind::Num {
+                return Err(anyhow!("负号之后需要是数值"));
+            }
+            return Ok((Expr::Neg(Box::new(inner.0)), Kind::Num));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<(Expr, Kind)> {
+        let at = self.at();
+        let token = self.tokens.get(self.pos).map(|(t, _)| t.clone()).ok_or_else(|| anyhow!("表达式不完整"))?;
+        self.pos += 1;
+        match token {
+            Token::Num(n) => Ok((Expr::Num(n), Kind::Num)),
+            Token::Str(s) => Ok((Expr::Text(s), Kind::Text)),
+            Token::LParen => {
+                let inner = self.or()?;
+                if self.peek() != Some(&Token::RParen) {
+                    return Err(anyhow!("{}缺少右括号", self.at()));
+                }
+                self.pos += 1;
+                Ok(inner)
+            }
+            Token::Ident(name) => {
+                if NUMERIC_FIELDS.iter().any(|(f, _)| *f == name) {
+                    return Ok((Expr::Field(name), Kind::Num));
+                }
+                if !TEXT_FIELDS.iter().any(|(f, _)| *f == name) {
+                    return Err(anyhow!("{}未知字段: {}", at, name));
+                }
+                if self.peek() != Some(&Token::Dot) {
+                    return Ok((Expr::Field(name), Kind::Text));
+                }
+                self.pos += 1;
+                let method = match self.tokens.get(self.pos) {
+                    Some((Token::Ident(m), _)) => TEXT_METHODS.iter().find(|x| **x == m.as_str()).copied(),
+                    _ => None,
+                }
+                .ok_or_else(|| anyhow!("{}只支持 {} 方法", self.at(), TEXT_METHODS.join("/")))?;
+                self.pos += 1;
+                let arg = match (self.tokens.get(self.pos), self.tokens.get(self.pos + 1), self.tokens.get(self.pos + 2)) {
+                    (Some((Token::LParen, _)), Some((Token::Str(s), _)), Some((Token::RParen, _))) => s.clone(),
+                    _ => return Err(anyhow!("{}{}() 的参数需要是带引号的文本", self.at(), method)),
+                };
+                self.pos += 3;
+                Ok((Expr::TextMethod(name, method, arg), Kind::Bool))
+            }
+            _ => Err(anyhow!("{}缺少字段或数值", at)),
+        }
+    }
+}
+
+/// 解析筛选表达式，如 `pe_ttm < 30 && roe > 10 && turnover_rate between 3 and 15 && !name.contains('ST')`
+pub fn parse(input: &str) -> Result<Expr> {
+    let tokens = tokenize(input)?;
+    if tokens.is_empty() {
+        return Err(anyhow!("表达式为空"));
+    }
+    let mut parser = Parser { tokens, pos: 0 };
+    let expr = parser.or()?;
+    if parser.pos < parser.tokens.len() {
+        return Err(anyhow!("{}存在多余内容", parser.at()));
+    }
+    parser.expect_bool(expr, "整个表达式")
+}
+
+/// 取快照中的数值字段，未知字段返回 NaN
+pub fn numeric(stock: &MarketStockSnapshot, field: &str) -> f64 {
+    match field {
+        "price" => stock.price,
+        "change_pct" => stock.change_pct,
+        "change_amount" => stock.change_amount,
+        "volume" => stock.volume,
+        "amount" => stock.amount,
+        "amplitude" => stock.amplitude,
+        "turnover_rate" => stock.turnover_rate,
+        "pe_ttm" => stock.pe_ttm,
+        "pb" => stock.pb,
+        "total_market_cap" => stock.total_market_cap,
+        "float_market_cap" => stock.float_market_cap,
+        "volume_ratio" => stock.volume_ratio,
+        "high" => stock.high,
+        "low" => stock.low,
+        "open" => stock.open,
+        "pre_close" => stock.pre_close,
+        "pct_5d" => stock.pct_5d,
+        "pct_20d" => stock.pct_20d,
+        "pct_60d" => stock.pct_60d,
+        "roe" => stock.roe,
+        "gross_margin" => stock.gross_margin,
+        "revenue_yoy" => stock.revenue_yoy,
+        "profit_yoy" => stock.profit_yoy,
+        "main_net_inflow" => stock.main_net_inflow,
+        "dividend_yield" => stock.dividend_yield,
+        "fcf_yield" => stock.fcf_yield,
+        "debt_ratio" => stock.debt_ratio,
+        _ => f64::NAN,
+    }
+}
+
+fn field_text<'a>(stock: &'a MarketStockSnapshot, field: &str) -> &'a str {
+    match field {
+        "code" => &stock.code,
+        "name" => &stock.name,
+        "list_date" => &stock.list_date,
+        _ => "",
+    }
+}
+
+fn text<'a>(expr: &'a Expr, stock: &'a MarketStockSnapshot) -> &'a str {
+    match expr {
+        Expr::Text(s) => s,
+        Expr::Field(f) => field_text(stock, f),
+        _ => "",
+    }
+}
+
+fn number(expr: &Expr, stock: &MarketStockSnapshot) -> f64 {
+    match expr {
+        Expr::Num(n) => *n,
+        Expr::Field(f) => numeric(stock, f),
+        Expr::Neg(e) => -number(e, stock),
+        Expr::Arith(op, l, r) => {
+            let (l, r) = (number(l, stock), number(r, stock));
+            match *op {
+                "+" => l + r,
+                "-" => l - r,
+                "*" => l * r,
+                _ => l / r,
+            }
+        }
+        _ => f64::NAN,
+    }
+}
+
+/// 判断股票是否满足表达式；除零等产生 NaN 的比较视为不满足
+pub fn matches(expr: &Expr, stock: &MarketStockSnapshot) -> bool {
+    match expr {
+        Expr::And(l, r) => matches(l, stock) && matches(r, stock),
+        Expr::Or(l, r) => matches(l, stock) || matches(r, stock),
+        Expr::Not(e) => !matches(e, stock),
+        Expr::Between(v, low, high) => {
+            let v = number(v, stock);
+            v >= number(low, stock) && v <= number(high, stock)
+        }
+        Expr::TextMethod(field, method, arg) => {
+            let value = field_text(stock, field);
+            match *method {
+                "contains" => value.contains(arg.as_str()),
+                "starts_with" => value.starts_with(arg.as_str()),
+                _ => value.ends_with(arg.as_str()),
+            }
+        }
+        Expr::TextCompare(op, l, r) => {
+            let equal = text(l, stock) == text(r, stock);
+            if *op == "==" { equal } else { !equal }
+        }
+        Expr::Compare(op, l, r) => {
+            let (l, r) = (number(l, stock), number(r, stock));
+            match *op {
+                "<" => l < r,
+                "<=" => l <= r,
+                ">" => l > r,
+                ">=" => l >= r,
+                "==" => (l - r).abs() < 1e-9,
+                _ => (l - r).abs() >= 1e-9,
+            }
+        }
+        _ => false,
+    }
+}
+
+fn uses_field(expr: &Expr, fields: &[&str]) -> bool {
+    match expr {
+        Expr::Field(f) | Expr::TextMethod(f, _, _) => fields.contains(&f.as_str()),
+        Expr::Neg(e) | Expr::Not(e) => uses_field(e, fields),
+        Expr::Arith(_, l, r) | Expr::Compare(_, l, r) | Expr::TextCompare(_, l, r) | Expr::And(l, r) | Expr::Or(l, r) => {
+            uses_field(l, fields) || uses_field(r, fields)
+        }
+        Expr::Between(v, low, high) => uses_field(v, fields) || uses_field(low, fields) || uses_field(high, fields),
+        Expr::Num(_) | Expr::Text(_) => false,
+    }
+}
+
+/// 条件选股：扫描全市场快照并按表达式筛选，结果按涨跌幅倒序，最多返回 limit 只，并附每只的选股理由与前 30 只的主要题材；
+/// 传入 benchmark 时理由中的动量描述为相对沪深300的超额收益
+pub async fn screen(expression: &str, limit: usize, benchmark: Option<&BenchmarkReturns>) -> Result<ExpressionScreenResult> {
+    let expr = parse(expression)?;
+    let scanner = MarketScanner::new()?;
+    let mut stocks = scanner.scan_full_market().await?;
+    if stocks.is_empty() {
+        return Err(anyhow!("未获取到全市场行情，请稍后重试"));
+    }
+    if uses_field(&expr, &FUNDAMENTAL_FIELDS) {
+        scanner.enrich_fundamentals(&mut stocks).await?;
+    }
+    let scanned = stocks.len();
+    let mut hits: Vec<MarketStockSnapshot> = stocks.iter().filter(|s| matches(&expr, s)).cloned().collect();
+    hits.sort_by(|a, b| b.change_pct.partial_cmp(&a.change_pct).unwrap_or(std::cmp::Ordering::Equal));
+    let matched = hits.len();
+    hits.truncate(limit);
+    let explanations = scan_explain::explain_all(&hits, &stocks, benchmark);
+    let theme_codes: Vec<String> = hits.iter().take(THEME_LOOKUP_LIMIT).map(|s| s.code.clone()).collect();
+    let themes = theme_exposure::top_themes(&theme_codes, 3).await;
+    log::info!("[filter_expr] screen scanned={} matched={} expr={}", scanned, matched, expression);
+    Ok(ExpressionScreenResult {
+        expression: expression.trim().to_string(),
+        scanned,
+        matched,
+        stocks: hits,
+        explanations,
+        themes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stock(name: &str, pe_ttm: f64, roe: f64, turnover_rate: f64) -> MarketStockSnapshot {
+        MarketStockSnapshot {
+            code: "sh600000".to_string(),
+            name: name.to_string(),
+            pe_ttm,
+            roe,
+            turnover_rate,
+            total_market_cap: 5e10,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_and_match() {
+        let expr = parse("pe_ttm < 30 && roe > 10 && turnover_rate between 3 and 15 && !name.contains('ST')").unwrap();
+        assert!(matches(&expr, &stock("浦发银行", 8.0, 12.0, 5.0)));
+        assert!(!matches(&expr, &stock("*ST浦发", 8.0, 12.0, 5.0)));
+        assert!(!matches(&expr, &stock("浦发银行", 8.0, 12.0, 20.0)));
+
+        let expr = parse("(pe_ttm > 50 || roe >= 15) && total_market_cap / 1e8 > 100 && code.starts_with(\"sh\")").unwrap();
+        assert!(matches(&expr, &stock("A", 10.0, 15.0, 1.0)));
+        assert!(!matches(&expr, &stock("A", 10.0, 14.0, 1.0)));
+        assert!(matches(&parse("name == 'A'").unwrap(), &stock("A", 0.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse("").is_err());
+        assert!(parse("pe < 30").unwrap_err().to_string().contains("未知字段"));
+        assert!(parse("pe_ttm + 1").is_err());
+        assert!(parse("name > 'A'").is_err());
+        assert!(parse("roe between 1").is_err());
+        assert!(parse("(roe > 1").is_err());
+        assert!(parse("roe > 1 roe").is_err());
+        assert!(parse("name.lower('a')").is_err());
+    }
+}
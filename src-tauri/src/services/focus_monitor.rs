@@ -0,0 +1,156 @@
+use anyhow::Result;
+use std::collections::HashSet;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::task::JoinHandle;
+
+use crate::models::stock::{FocusMonitorStatus, TickPrint};
+use crate::services::market_scanner::MarketScanner;
+use crate::services::scheduler::TradingScheduler;
+use crate::AppState;
+
+pub const FOCUS_LARGE_ORDER_EVENT: &str = "focus-large-order";
+
+/// 盘中逐笔轮询间隔
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+/// 每次拉取的逐笔条数，需覆盖一个轮询间隔内的成交
+const TICK_COUNT: u32 = 100;
+/// 状态中保留的最近大单条数
+const MAX_KEPT: usize = 200;
+
+struct Running {
+    code: String,
+    handle: JoinHandle<()>,
+}
+
+#[derive(Default)]
+struct MonitorState {
+    running: Option<Running>,
+    prints: Vec<TickPrint>,
+}
+
+fn monitor() -> &'static Mutex<MonitorState> {
+    static MONITOR: OnceLock<Mutex<MonitorState>> = OnceLock::new();
+    MONITOR.get_or_init(|| Mutex::new(MonitorState::default()))
+}
+
+fn print_key(print: &TickPrint) -> String {
+    format!("{}|{}|{}", print.time, print.price, print.volume)
+}
+
+/// 从本次拉取的逐笔中挑出未推送过的大单（成交额 ≥ threshold），并记入 seen
+pub fn large_prints(ticks: &[TickPrint], threshold: f64, seen: &mut HashSet<String>) -> Vec<TickPrint> {
+    ticks
+        .iter()
+        .filter(|t| t.amount >= threshold && seen.insert(print_key(t)))
+        .cloned()
+        .collect()
+}
+
+async fn poll(app: &AppHandle, scanner: &MarketScanner, code: &str, seen: &mut HashSet<String>) {
+    let threshold = match app.state::<AppState>().db.load_settings() {
+        Ok(settings) => settings.large_order_threshold,
+        Err(e) => {
+            log::warn!("[focus_monitor] load settings failed: {}", e);
+            return;
+        }
+    };
+    let ticks = match scanner.fetch_ticks(code, TICK_COUNT).await {
+        Ok(ticks) => ticks,
+        Err(e) => {
+            log::warn!("[focus_monitor] fetch ticks {} failed: {}", code, e);
+            return;
+        }
+    };
+    let prints = large_prints(&ticks, threshold, seen);
+    if prints.is_empty() {
+        return;
+    }
+    {
+        let mut state = monitor().lock().unwrap();
+        state.prints.extend(prints.iter().cloned());
+        let overflow = state.prints.len().saturating_sub(MAX_KEPT);
+        state.prints.drain(..overflow);
+    }
+    for print in &prints {
+        let _ = app.emit(FOCUS_LARGE_ORDER_EVENT, print);
+    }
+}
+
+async fn run(app: AppHandle, scanner: MarketScanner, code: String) {
+    let mut seen = HashSet::new();
+    loop {
+        if TradingScheduler::is_trading_time() {
+            poll(&app, &scanner, &code, &mut seen).await;
+        }
+        let shutdown = &app.state::<AppState>().shutdown;
+        tokio::select! {
+            _ = shutdown.cancelled() => break,
+            _ = tokio::time::sleep(POLL_INTERVAL) => {}
+        }
+    }
+    log::info!("[focus_monitor] stopped code={}", code);
+}
+
+/// 开始监控单只股票的逐笔大单，已在监控的其他股票会被替换
+pub async fn start(app: &AppHandle, code: &str) -> Result<()> {
+    stop().await;
+    let scanner = MarketScanner::new()?;
+    let handle = tokio::spawn(run(app.clone(), scanner, code.to_string()));
+    let mut state = monitor().lock().unwrap();
+    state.prints.clear();
+    state.running = Some(Running { code: code.to_string(), handle });
+    log::info!("[focus_monitor] started code={}", code);
+    Ok(())
+}
+
+/// 停止监控（保留已推送的大单供面板回看）
+pub async fn stop() {
+    let previous = monitor().lock().unwrap().running.take();
+    if let Some(running) = previous {
+        running.handle.abort();
+        let _ = running.handle.await;
+        log::info!("[focus_monitor] stopped code={}", running.code);
+    }
+}
+
+pub fn status() -> FocusMonitorStatus {
+    let state = monitor().lock().unwrap();
+    let running = state.running.as_ref().filter(|r| !r.handle.is_finished());
+    FocusMonitorStatus {
+        running: running.is_some(),
+        code: running.map(|r| r.code.clone()),
+        prints: state.prints.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tick(time: &str, price: f64, volume: f64) -> TickPrint {
+        TickPrint {
+            code: "sh600519".to_string(),
+            time: time.to_string(),
+            price,
+            volume,
+            amount: price * volume * 100.0,
+            side: "buy".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_large_prints() {
+        let mut seen = HashSet::new();
+        let ticks = vec![tick("09:30:03", 10.0, 500.0), tick("09:30:06", 10.0, 2000.0), tick("09:30:09", 10.1, 1000.0)];
+        let found = large_prints(&ticks, 1_000_000.0, &mut seen);
+        assert_eq!(found.iter().map(|t| t.time.as_str()).collect::<Vec<_>>(), vec!["09:30:06", "09:30:09"]);
+
+        // 下一次拉取与上次重叠，只推送新增的大单
+        let ticks = vec![tick("09:30:09", 10.1, 1000.0), tick("09:30:12", 10.2, 1500.0)];
+        let found = large_prints(&ticks, 1_000_000.0, &mut seen);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].time, "09:30:12");
+    }
+}
@@ -0,0 +1,155 @@
+use anyhow::Result;
+use std::collections::HashMap;
+
+use crate::db::database::Database;
+use crate::models::stock::{CumulativeFlow, FundFlowBreakdown, FundFlowDay, MarketStockSnapshot};
+use crate::services::market_scanner::MarketScanner;
+
+/// 累计资金流的统计窗口（交易日）
+pub const WINDOWS: [usize; 3] = [5, 10, 20];
+/// 本地保留的记录日数
+const KEEP_DAYS: usize = 120;
+/// 分单净额低于该值（元）视为无明显方向
+const SIGNIFICANT_NET: f64 = 1e6;
+
+/// 由按日期正序的资金流记录计算 5/10/20 日累计净流入；超大单只在窗口内每天都有明细时给出
+pub fn cumulative(code: &str, flows: &[FundFlowDay]) -> CumulativeFlow {
+    let window = |n: usize| (flows.len() >= n).then(|| &flows[flows.len() - n..]);
+    let main = |n| window(n).map(|w| w.iter().map(|f| f.main_net).sum());
+    let super_large = |n| window(n).and_then(|w| w.iter().map(|f| f.super_large_net).sum::<Option<f64>>());
+    CumulativeFlow {
+        code: code.to_string(),
+        days: flows.len(),
+        main_5d: main(WINDOWS[0]),
+        main_10d: main(WINDOWS[1]),
+        main_20d: main(WINDOWS[2]),
+        super_large_5d: super_large(WINDOWS[0]),
+        super_large_10d: super_large(WINDOWS[1]),
+        super_large_20d: super_large(WINDOWS[2]),
+    }
+}
+
+/// 识别分单资金的典型形态：超大单与大单方向背离、主力与散户方向相反等
+pub fn flow_pattern(flow: &FundFlowBreakdown) -> Option<String> {
+    let dir = |v: f64| if v >= SIGNIFICANT_NET { 1 } else if v <= -SIGNIFICANT_NET { -1 } else { 0 };
+    let pattern = match (dir(flow.super_large_net), dir(flow.large_net), dir(flow.small_net)) {
+        (1, -1, _) => "超大单流入、大单流出：大资金吸筹，中等资金离场",
+        (-1, 1, _) => "超大单流出、大单承接：大资金借大单承接出货",
+        (1, 1, -1) => "超大单与大单同步流入、散户流出：主力吸筹",
+        (-1, -1, 1) => "超大单与大单同步流出、散户接盘：主力派发",
+        (1, 1, _) => "超大单与大单同步流入",
+        (-1, -1, _) => "超大单与大单同步流出",
+        _ => return None,
+    };
+    Some(pattern.to_string())
+}
+
+/// 批量查询当日分单资金流向并标注形态
+pub async fn fetch_breakdowns(codes: &[String]) -> Result<Vec<FundFlowBreakdown>> {
+    let mut flows = MarketScanner::new()?.fetch_fund_flow(codes).await?;
+    for f in &mut flows {
+        f.pattern = flow_pattern(f);
+    }
+    Ok(flows)
+}
+
+/// 个股当日分单资金流向，附盘中累计净额走势（走势获取失败时为空）
+pub async fn get_breakdown(code: &str) -> Result<Option<FundFlowBreakdown>> {
+    let Some(mut flow) = fetch_breakdowns(&[code.to_string()]).await?.into_iter().next() else {
+        return Ok(None);
+    };
+    match MarketScanner::new()?.fetch_intraday_fund_flow(code).await {
+        Ok(points) => flow.intraday = points,
+        Err(e) => log::warn!("[fund_flow] fetch intraday flow {} failed: {}", code, e),
+    }
+    Ok(Some(flow))
+}
+
+/// 拉取个股最近 days 个交易日的资金流向（东财日级资金流，不落库，供 AI 工具使用）
+pub async fn fetch_history(code: &str, days: u32) -> Result<Vec<FundFlowDay>> {
+    MarketScanner::new()?.fetch_fund_flow_history(code, days.clamp(1, KEEP_DAYS as u32)).await
+}
+
+/// 拉取并保存个股资金流向历史；接口失败时回退到本地记录
+pub async fn sync_history(db: &Database, code: &str, days: u32) -> Result<Vec<FundFlowDay>> {
+    match fetch_history(code, days).await {
+        Ok(flows) => {
+            db.save_fund_flows(&flows, true, KEEP_DAYS)?;
+            Ok(flows)
+        }
+        Err(e) => {
+            log::warn!("[fund_flow] fetch history {} failed, using local records: {}", code, e);
+            Ok(db.get_fund_flows(Some(code), days as usize)?.remove(code).unwrap_or_default())
+        }
+    }
+}
+
+/// 记录全市场当日主力净流入（来自行情快照，只补缺失日期，不覆盖已拉取的明细）
+pub fn record_snapshot(db: &Database, stocks: &[MarketStockSnapshot], date: &str) -> Result<usize> {
+    let flows: Vec<FundFlowDay> = stocks
+        .iter()
+        .filter(|s| s.price > 0.0)
+        .map(|s| FundFlowDay {
+            code: s.code.clone(),
+            date: date.to_string(),
+            main_net: s.main_net_inflow,
+            super_large_net: None,
+            large_net: None,
+            medium_net: None,
+            small_net: None,
+            main_pct: s.main_net_pct,
+            close: s.price,
+            change_pct: s.change_pct,
+        })
+        .collect();
+    db.save_fund_flows(&flows, false, KEEP_DAYS)?;
+    Ok(flows.len())
+}
+
+/// 全部股票的本地累计资金流，供资金面因子使用
+pub fn cumulative_all(db: &Database) -> Result<HashMap<String, CumulativeFlow>> {
+    let flows = db.get_fund_flows(None, WINDOWS[2])?;
+    Ok(flows.iter().map(|(code, f)| (code.clone(), cumulative(code, f))).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(i: usize, main_net: f64, super_large_net: Option<f64>) -> FundFlowDay {
+        FundFlowDay {
+            code: "sh600519".to_string(),
+            date: format!("2024-06-{:02}", i + 1),
+            main_net,
+            super_large_net,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_flow_pattern() {
+        let flow = |super_large_net, large_net, small_net| FundFlowBreakdown {
+            super_large_net,
+            large_net,
+            small_net,
+            ..Default::default()
+        };
+        assert_eq!(flow_pattern(&flow(5e7, -2e7, 0.0)).as_deref(), Some("超大单流入、大单流出：大资金吸筹，中等资金离场"));
+        assert_eq!(flow_pattern(&flow(-3e7, -1e7, 4e7)).as_deref(), Some("超大单与大单同步流出、散户接盘：主力派发"));
+        assert_eq!(flow_pattern(&flow(5e5, -2e7, 0.0)), None);
+    }
+
+    #[test]
+    fn test_cumulative() {
+        let mut flows: Vec<FundFlowDay> = (0..8).map(|i| day(i, 1e7, None)).collect();
+        flows.extend((8..12).map(|i| day(i, -2e7, Some(-1e7))));
+        flows.push(day(12, 5e7, Some(3e7)));
+        let c = cumulative("sh600519", &flows);
+        assert_eq!(c.days, 13);
+        assert_eq!(c.main_5d, Some(-3e7));
+        assert_eq!(c.main_10d, Some(2e7));
+        assert_eq!(c.main_20d, None);
+        assert_eq!(c.super_large_5d, Some(-1e7));
+        assert_eq!(c.super_large_10d, None);
+    }
+}
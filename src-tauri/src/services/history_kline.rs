@@ -1,8 +1,51 @@
 use anyhow::{Result, anyhow};
+use crate::db::database::Database;
+use crate::models::stock::StockDailyHistory;
 use crate::models::watchlist::KlineItem;
-use crate::utils::http::build_stock_client;
+use crate::services::stock_data::format_stock_code;
+use crate::utils::http::{build_stock_client, SendLogged};
 
 const QQ_KLINE_URL: &str = "https://web.ifzq.gtimg.cn/appstock/app/fqkline/get";
+const QQ_MINUTE_KLINE_URL: &str = "https://ifzq.gtimg.cn/appstock/app/kline/mkline";
+const EM_KLINE_URL: &str = "https://push2his.eastmoney.com/api/qt/stock/kline/get";
+
+/// 本地日线缓存的起始日期（首次全量拉取）
+pub const HISTORY_START_DATE: &str = "2023-01-01";
+pub const DAILY_PERIODS: [&str; 3] = ["day", "week", "month"];
+pub const MINUTE_PERIODS: [&str; 5] = ["m1", "m5", "m15", "m30", "m60"];
+
+/// 常用指数：(代码, 名称)，可按名称查询K线
+const KNOWN_INDEXES: [(&str, &str); 9] = [
+    ("sh000001", "上证指数"),
+    ("sz399001", "深证成指"),
+    ("sz399006", "创业板指"),
+    ("sh000300", "沪深300"),
+    ("sh000016", "上证50"),
+    ("sh000905", "中证500"),
+    ("sh000852", "中证1000"),
+    ("sh000688", "科创50"),
+    ("sz399005", "中小100"),
+];
+
+/// 规范化K线查询代码：指数名称（中证500）映射为指数代码，带前缀的代码原样保留，纯数字按个股补全交易所前缀。
+/// 000001 这类与个股重号的指数需带 sh 前缀或使用名称
+pub fn resolve_kline_code(input: &str) -> String {
+    let key = input.trim();
+    if let Some((code, _)) = KNOWN_INDEXES.iter().find(|(_, name)| *name == key) {
+        return code.to_string();
+    }
+    format_stock_code(key)
+}
+
+/// 是否为指数代码（上证 sh000xxx、深证 sz399xxx）
+pub fn is_index_code(code: &str) -> bool {
+    code.starts_with("sh000") || code.starts_with("sz399")
+}
+
+/// 指数名称，非常用指数返回 None
+pub fn index_name(code: &str) -> Option<&'static str> {
+    KNOWN_INDEXES.iter().find(|(c, _)| *c == code).map(|(_, name)| *name)
+}
 
 pub struct HistoryKlineService {
     client: reqwest::Client,
@@ -32,54 +75,82 @@ impl HistoryKlineService {
         let param = format!("{},{},{},{},{},{}", code, period, start, end, count, fq);
         let url = format!("{}?param={}", QQ_KLINE_URL, param);
 
-        let resp = self.client.get(&url).send().await?;
+        let resp = self.client.get(&url).send_logged().await?;
         let text = resp.text().await?;
 
         let json: serde_json::Value = serde_json::from_str(&text)
             .map_err(|e| anyhow!("腾讯K线数据JSON解析失败: {}", e))?;
+        parse_qq_kline(&json, code, period)
+    }
 
-        let code_key = code.to_lowercase();
-        let data = json.get("data")
-            .and_then(|d| d.get(&code_key))
-            .ok_or_else(|| anyhow!("腾讯K线数据中未找到 {} 的数据", code))?;
-
-        // 前复权数据在 qfqday/qfqweek/qfqmonth 字段
-        // 指数数据在 day/week/month 字段
-        let period_key = format!("qfq{}", period);
-        let klines = data.get(&period_key)
-            .or_else(|| data.get(period))
-            .and_then(|v| v.as_array())
-            .ok_or_else(|| anyhow!("腾讯K线数据中未找到 {} 字段", period_key))?;
-
-        let mut items = Vec::with_capacity(klines.len());
-        for kline in klines {
-            let arr = kline.as_array();
-            if let Some(arr) = arr {
-                if arr.len() >= 6 {
-                    let item = KlineItem {
-                        date: arr[0].as_str().unwrap_or("").to_string(),
-                        open: parse_kline_f64(&arr[1]),
-                        close: parse_kline_f64(&arr[2]),
-                        high: parse_kline_f64(&arr[3]),
-                        low: parse_kline_f64(&arr[4]),
-                        volume: parse_kline_f64(&arr[5]),
-                        amount: 0.0,
-                        change_pct: 0.0,
-                        turnover_rate: 0.0,
-                    };
-                    items.push(item);
-                }
-            }
+    /// 从腾讯接口拉取最近 count 根分钟K线（不复权），date 为 "YYYY-MM-DD HH:MM"
+    /// period: m1 / m5 / m15 / m30 / m60
+    pub async fn fetch_minute_kline(&self, code: &str, period: &str, count: u32) -> Result<Vec<KlineItem>> {
+        let url = format!("{}?param={},{},,{}", QQ_MINUTE_KLINE_URL, code, period, count);
+        let text = self.client.get(&url).send_logged().await?.text().await?;
+        let json: serde_json::Value = serde_json::from_str(&text)
+            .map_err(|e| anyhow!("腾讯分钟K线JSON解析失败: {}", e))?;
+        parse_qq_minute_kline(&json, code, period)
+    }
+
+    /// 拉取最近 count 根K线，period 为 day / week / month 或 m1 / m5 / m15 / m30 / m60；个股与指数通用
+    pub async fn fetch_recent(&self, code: &str, period: &str, count: u32) -> Result<Vec<KlineItem>> {
+        if MINUTE_PERIODS.contains(&period) {
+            return self.fetch_minute_kline(code, period, count).await;
         }
+        if !DAILY_PERIODS.contains(&period) {
+            return Err(anyhow!("不支持的K线周期: {}", period));
+        }
+        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+        let items = self.fetch_kline(code, period, HISTORY_START_DATE, &today, 640).await?;
+        Ok(items[items.len().saturating_sub(count as usize)..].to_vec())
+    }
 
-        // 计算涨跌幅
+    /// 从东方财富拉取行业/概念板块指数K线（如 BK0475），腾讯接口不提供板块指数
+    /// period: day / week / month
+    pub async fn fetch_board_kline(&self, board_code: &str, period: &str, start: &str, end: &str) -> Result<Vec<KlineItem>> {
+        let klt = match period {
+            "week" => 102,
+            "month" => 103,
+            _ => 101,
+        };
+        let url = format!(
+            "{}?secid=90.{}&fields1=f1,f2,f3&fields2=f51,f52,f53,f54,f55,f56,f57&klt={}&fqt=0&beg={}&end={}",
+            EM_KLINE_URL, board_code, klt, start.replace('-', ""), end.replace('-', "")
+        );
+        let json: serde_json::Value = self.client.get(&url)
+            .header("Referer", "https://quote.eastmoney.com/")
+            .send_logged().await?
+            .json().await?;
+        let klines = json["data"]["klines"]
+            .as_array()
+            .ok_or_else(|| anyhow!("东财板块K线中未找到 {} 的数据", board_code))?;
+
+        // 每条格式：日期,开,收,高,低,成交量,成交额
+        let mut items: Vec<KlineItem> = klines.iter().filter_map(|k| {
+            let fields: Vec<&str> = k.as_str()?.split(',').collect();
+            if fields.len() < 7 {
+                return None;
+            }
+            let num = |i: usize| fields[i].parse::<f64>().unwrap_or(0.0);
+            Some(KlineItem {
+                date: fields[0].to_string(),
+                open: num(1),
+                close: num(2),
+                high: num(3),
+                low: num(4),
+                volume: num(5),
+                amount: num(6),
+                change_pct: 0.0,
+                turnover_rate: 0.0,
+            })
+        }).collect();
         for i in 1..items.len() {
             let prev_close = items[i - 1].close;
             if prev_close > 0.0 {
                 items[i].change_pct = (items[i].close - prev_close) / prev_close * 100.0;
             }
         }
-
         Ok(items)
     }
 
@@ -142,6 +213,33 @@ impl HistoryKlineService {
         }
         self.fetch_kline(code, period, &start, end, 640).await
     }
+
+    /// 将日线增量同步到本地 stock_daily_history 缓存，返回新增条数
+    pub async fn sync_daily_history(&self, db: &Database, code: &str) -> Result<usize> {
+        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+        let items = match db.get_latest_history_date(code)? {
+            Some(latest) => self.fetch_kline_incremental(code, "day", &latest, &today).await?,
+            None => self.fetch_kline_full(code, "day", HISTORY_START_DATE, &today).await?,
+        };
+        if items.is_empty() {
+            return Ok(0);
+        }
+        let records: Vec<StockDailyHistory> = items.iter().map(|k| StockDailyHistory {
+            code: code.to_string(),
+            date: k.date.clone(),
+            close: k.close,
+            high: k.high,
+            low: k.low,
+            open: k.open,
+            volume: k.volume,
+            amount: k.amount,
+            change_pct: k.change_pct,
+            is_limit_up: false,
+            turnover_rate: k.turnover_rate,
+        }).collect();
+        db.save_daily_history(&records)?;
+        Ok(records.len())
+    }
 }
 
 fn parse_kline_f64(val: &serde_json::Value) -> f64 {
@@ -152,8 +250,124 @@ fn parse_kline_f64(val: &serde_json::Value) -> f64 {
     }
 }
 
+/// 解析腾讯日/周/月K线：个股取前复权 qfqday/qfqweek/qfqmonth 字段，指数无复权数据，取 day/week/month 字段
+fn parse_qq_kline(json: &serde_json::Value, code: &str, period: &str) -> Result<Vec<KlineItem>> {
+    let code_key = code.to_lowercase();
+    let data = json.get("data")
+        .and_then(|d| d.get(&code_key))
+        .ok_or_else(|| anyhow!("腾讯K线数据中未找到 {} 的数据", code))?;
+
+    let period_key = format!("qfq{}", period);
+    let klines = data.get(&period_key)
+        .or_else(|| data.get(period))
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| anyhow!("腾讯K线数据中未找到 {} 字段", period_key))?;
+
+    let mut items: Vec<KlineItem> = klines.iter().filter_map(|kline| {
+        let arr = kline.as_array().filter(|a| a.len() >= 6)?;
+        Some(KlineItem {
+            date: arr[0].as_str().unwrap_or("").to_string(),
+            open: parse_kline_f64(&arr[1]),
+            close: parse_kline_f64(&arr[2]),
+            high: parse_kline_f64(&arr[3]),
+            low: parse_kline_f64(&arr[4]),
+            volume: parse_kline_f64(&arr[5]),
+            amount: 0.0,
+            change_pct: 0.0,
+            turnover_rate: 0.0,
+        })
+    }).collect();
+
+    // 计算涨跌幅
+    for i in 1..items.len() {
+        let prev_close = items[i - 1].close;
+        if prev_close > 0.0 {
+            items[i].change_pct = (items[i].close - prev_close) / prev_close * 100.0;
+        }
+    }
+    Ok(items)
+}
+
+/// 解析腾讯分钟K线，时间 "202406061035" 转为 "2024-06-06 10:35"
+fn parse_qq_minute_kline(json: &serde_json::Value, code: &str, period: &str) -> Result<Vec<KlineItem>> {
+    let klines = json.get("data")
+        .and_then(|d| d.get(code.to_lowercase()))
+        .and_then(|d| d.get(period))
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| anyhow!("腾讯分钟K线数据中未找到 {} 的 {} 字段", code, period))?;
+
+    let items = klines.iter().filter_map(|k| {
+        let arr = k.as_array().filter(|a| a.len() >= 6)?;
+        let time = arr[0].as_str()?;
+        if time.len() < 12 {
+            return None;
+        }
+        Some(KlineItem {
+            date: format!("{}-{}-{} {}:{}", &time[0..4], &time[4..6], &time[6..8], &time[8..10], &time[10..12]),
+            open: parse_kline_f64(&arr[1]),
+            close: parse_kline_f64(&arr[2]),
+            high: parse_kline_f64(&arr[3]),
+            low: parse_kline_f64(&arr[4]),
+            volume: parse_kline_f64(&arr[5]),
+            amount: 0.0,
+            change_pct: 0.0,
+            turnover_rate: 0.0,
+        })
+    }).collect();
+    Ok(items)
+}
+
 fn next_day(date_str: &str) -> Option<String> {
     let date = chrono::NaiveDate::parse_from_str(date_str, "%Y-%m-%d").ok()?;
     let next = date + chrono::Duration::days(1);
     Some(next.format("%Y-%m-%d").to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_resolve_kline_code() {
+        assert_eq!(resolve_kline_code("中证500"), "sh000905");
+        assert_eq!(resolve_kline_code("sh000001"), "sh000001");
+        assert_eq!(resolve_kline_code("SZ399006"), "sz399006");
+        assert_eq!(resolve_kline_code("000001"), "sz000001");
+        assert!(is_index_code("sz399006") && is_index_code("sh000300") && !is_index_code("sh600519"));
+        assert_eq!(index_name("sh000905"), Some("中证500"));
+    }
+
+    #[test]
+    fn test_parse_index_kline() {
+        // 指数没有前复权字段，日/周K线位于 day/week
+        let json = json!({"data": {"sh000300": {
+            "week": [["2024-05-31", "3600.00", "3580.00", "3620.00", "3570.00", "98765432.000"],
+                     ["2024-06-07", "3580.00", "3598.00", "3610.00", "3560.00", "87654321.000"]]
+        }}});
+        let items = parse_qq_kline(&json, "sh000300", "week").unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[1].close, 3598.0);
+        assert!((items[1].change_pct - 0.5028).abs() < 1e-3);
+        assert!(parse_qq_kline(&json, "sh000300", "day").is_err());
+
+        // 个股优先取前复权字段
+        let json = json!({"data": {"sh600519": {
+            "qfqday": [["2024-06-06", "1600", "1610", "1620", "1590", "12345", {"nd": "2023"}]],
+            "day": [["2024-06-06", "1700", "1710", "1720", "1690", "12345"]]
+        }}});
+        assert_eq!(parse_qq_kline(&json, "sh600519", "day").unwrap()[0].close, 1610.0);
+    }
+
+    #[test]
+    fn test_parse_index_minute_kline() {
+        let json = json!({"data": {"sz399006": {
+            "m5": [["202406061035", "1750.10", "1752.30", "1753.00", "1749.80", "1234567.00"], ["2024", "1", "1", "1", "1", "1"]]
+        }}});
+        let items = parse_qq_minute_kline(&json, "sz399006", "m5").unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].date, "2024-06-06 10:35");
+        assert_eq!(items[0].close, 1752.3);
+        assert!(parse_qq_minute_kline(&json, "sz399006", "m30").is_err());
+    }
+}
@@ -0,0 +1,73 @@
+use anyhow::Result;
+use futures::stream::{self, StreamExt};
+use std::collections::HashSet;
+use tauri::{AppHandle, Emitter};
+
+use crate::db::database::Database;
+use crate::models::stock::{HistorySyncItem, HistorySyncProgress, HistorySyncResult};
+use crate::services::history_kline::HistoryKlineService;
+use crate::services::stock_data::format_stock_code;
+
+pub const DEFAULT_CONCURRENCY: usize = 4;
+pub const MAX_CONCURRENCY: usize = 8;
+/// 前端监听的批量同步进度事件名
+pub const HISTORY_SYNC_PROGRESS_EVENT: &str = "history-sync-progress";
+
+/// 批量增量同步日线到本地缓存：限制并发，本地已有当日K线的股票直接跳过，每完成一只推送进度事件。
+/// 单只失败不影响其余股票，已同步的数据逐只落库，失败的代码汇总返回，重新传入即可续传
+pub async fn sync_history_batch(
+    db: &Database,
+    app: &AppHandle,
+    codes: &[String],
+    concurrency: usize,
+) -> Result<HistorySyncResult> {
+    let mut codes: Vec<String> = codes.iter().map(|c| format_stock_code(c)).filter(|c| !c.is_empty()).collect();
+    let mut seen = HashSet::new();
+    codes.retain(|c| seen.insert(c.clone()));
+    let total = codes.len();
+    let concurrency = concurrency.clamp(1, MAX_CONCURRENCY);
+    log::info!("[history_sync] sync_history_batch total={} concurrency={}", total, concurrency);
+
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let latest_dates = db.get_latest_history_dates()?;
+    let service = HistoryKlineService::new()?;
+    let mut results = stream::iter(codes)
+        .map(|code| {
+            let service = &service;
+            let cached = latest_dates.get(&code).is_some_and(|d| *d >= today);
+            async move {
+                if cached {
+                    return HistorySyncItem { code, added: 0, cached: true, error: None };
+                }
+                match service.sync_daily_history(db, &code).await {
+                    Ok(added) => HistorySyncItem { code, added, cached: false, error: None },
+                    Err(e) => {
+                        log::warn!("[history_sync] sync {} failed: {}", code, e);
+                        HistorySyncItem { code, added: 0, cached: false, error: Some(e.to_string()) }
+                    }
+                }
+            }
+        })
+        .buffer_unordered(concurrency);
+
+    let mut summary = HistorySyncResult { total, synced: 0, cached: 0, added_rows: 0, failed_codes: vec![] };
+    let mut completed = 0;
+    while let Some(item) = results.next().await {
+        completed += 1;
+        if item.error.is_some() {
+            summary.failed_codes.push(item.code.clone());
+        } else if item.cached {
+            summary.cached += 1;
+        } else {
+            summary.synced += 1;
+            summary.added_rows += item.added;
+        }
+        let _ = app.emit(HISTORY_SYNC_PROGRESS_EVENT, &HistorySyncProgress { completed, total, item });
+    }
+
+    log::info!(
+        "[history_sync] sync_history_batch done synced={} cached={} failed={} rows={}",
+        summary.synced, summary.cached, summary.failed_codes.len(), summary.added_rows
+    );
+    Ok(summary)
+}
@@ -0,0 +1,52 @@
+use anyhow::{anyhow, Result};
+use tauri::AppHandle;
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::services::window_manager;
+use crate::models::window::WINDOW_KIND_QUICK_SEARCH;
+
+/// 校验快捷键写法（如 CommandOrControl+Shift+K），空字符串表示不启用
+pub fn parse(hotkey: &str) -> Result<Option<Shortcut>> {
+    let hotkey = hotkey.trim();
+    if hotkey.is_empty() {
+        return Ok(None);
+    }
+    hotkey
+        .parse::<Shortcut>()
+        .map(Some)
+        .map_err(|e| anyhow!("快捷键「{}」无效: {}", hotkey, e))
+}
+
+/// 按设置重新注册快速查股的全局快捷键；按下时打开快速查股窗口，已打开时关闭
+pub fn apply(app: &AppHandle, hotkey: &str) -> Result<()> {
+    let shortcuts = app.global_shortcut();
+    shortcuts.unregister_all()?;
+    let Some(shortcut) = parse(hotkey)? else {
+        log::info!("[hotkey] quick search hotkey disabled");
+        return Ok(());
+    };
+    shortcuts.on_shortcut(shortcut, |app, _, event| {
+        if event.state != ShortcutState::Pressed {
+            return;
+        }
+        if let Err(e) = window_manager::toggle(app, WINDOW_KIND_QUICK_SEARCH) {
+            log::warn!("[hotkey] toggle quick search failed: {}", e);
+        }
+    })?;
+    log::info!("[hotkey] registered quick search hotkey={}", hotkey.trim());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hotkey() {
+        assert!(parse("").unwrap().is_none());
+        assert!(parse("CommandOrControl+Shift+K").unwrap().is_some());
+        assert!(parse("Alt+Space").unwrap().is_some());
+        assert!(parse("Ctrl+Shift+").is_err());
+        assert!(parse("Foo+K").is_err());
+    }
+}
@@ -0,0 +1,83 @@
+use anyhow::{Result, anyhow};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::models::stock::IndexConstituents;
+use crate::services::market_scanner::MarketScanner;
+
+/// 宽基指数 → 东财对应成分股板块：(指数代码, 指数名称, 板块代码)
+const BROAD_INDEXES: [(&str, &str, &str); 3] = [
+    ("000300", "沪深300", "BK0500"),
+    ("000016", "上证50", "BK0611"),
+    ("000905", "中证500", "BK0701"),
+];
+/// 成分股缓存有效期：成分股每半年调整一次，行情部分按小时刷新即可
+const CACHE_TTL: Duration = Duration::from_secs(3600);
+
+fn cache() -> &'static Mutex<HashMap<String, (Instant, IndexConstituents)>> {
+    static CACHE: OnceLock<Mutex<HashMap<String, (Instant, IndexConstituents)>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 解析指数标识，返回 (规范化指数代码, 指数名称, 东财板块代码)。
+/// 支持宽基指数代码（000300 / sh000300）、指数名称（沪深300）以及行业/概念板块代码（BK0477）
+pub fn resolve_index(index: &str) -> Result<(String, String, String)> {
+    let key = index.trim();
+    let upper = key.to_uppercase();
+    if upper.starts_with("BK") {
+        return Ok((upper.clone(), String::new(), upper));
+    }
+    let digits: String = key.chars().filter(|c| c.is_ascii_digit()).collect();
+    BROAD_INDEXES
+        .iter()
+        .find(|(code, name, _)| (!digits.is_empty() && *code == digits) || *name == key)
+        .map(|(code, name, board)| (code.to_string(), name.to_string(), board.to_string()))
+        .ok_or_else(|| {
+            let supported: Vec<String> = BROAD_INDEXES.iter().map(|(c, n, _)| format!("{}({})", n, c)).collect();
+            anyhow!("不支持的指数: {}，可选 {} 或行业板块代码 BKxxxx", index, supported.join("、"))
+        })
+}
+
+/// 获取指数/板块成分股及其最新行情（带 1 小时内存缓存），按总市值降序
+pub async fn get_index_constituents(index: &str) -> Result<IndexConstituents> {
+    let (index_code, index_name, board) = resolve_index(index)?;
+    if let Some((at, cached)) = cache().lock().unwrap().get(&index_code) {
+        if at.elapsed() < CACHE_TTL {
+            return Ok(cached.clone());
+        }
+    }
+
+    let mut stocks = MarketScanner::new()?.fetch_board_stocks(&board).await?;
+    if stocks.is_empty() {
+        return Err(anyhow!("未获取到 {} 的成分股", index));
+    }
+    stocks.sort_by(|a, b| b.total_market_cap.partial_cmp(&a.total_market_cap).unwrap_or(std::cmp::Ordering::Equal));
+    let result = IndexConstituents {
+        index_code: index_code.clone(),
+        index_name,
+        stocks,
+        updated_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+    };
+    log::info!("[index_constituents] fetched {} constituents for {}", result.stocks.len(), index_code);
+    cache().lock().unwrap().insert(index_code, (Instant::now(), result.clone()));
+    Ok(result)
+}
+
+/// 成分股代码集合，用于按指数范围过滤
+pub async fn constituent_codes(index: &str) -> Result<HashSet<String>> {
+    Ok(get_index_constituents(index).await?.stocks.into_iter().map(|s| s.code).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_index() {
+        assert_eq!(resolve_index("sh000300").unwrap().1, "沪深300");
+        assert_eq!(resolve_index("中证500").unwrap().2, "BK0701");
+        assert_eq!(resolve_index("bk0477").unwrap().2, "BK0477");
+        assert!(resolve_index("399006").is_err());
+    }
+}
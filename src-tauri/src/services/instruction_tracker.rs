@@ -0,0 +1,70 @@
+use anyhow::Result;
+use chrono::Local;
+
+use crate::db::database::Database;
+use crate::models::ai::{StockInstructionResult, StockSummaryForAI};
+use crate::models::tracking::InstructionRecord;
+use crate::services::history_kline::HistoryKlineService;
+
+/// 将当日生成的指令与对应的竞价数据合并后落库
+pub fn record_instructions(
+    db: &Database,
+    stocks: &[StockSummaryForAI],
+    instructions: &[StockInstructionResult],
+) -> Result<()> {
+    let date = Local::now().format("%Y-%m-%d").to_string();
+    let created_at = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    let records: Vec<InstructionRecord> = instructions.iter().map(|ins| {
+        let stock = stocks.iter().find(|s| s.code == ins.code);
+        InstructionRecord {
+            date: date.clone(),
+            code: ins.code.clone(),
+            name: stock.map(|s| s.name.clone()).unwrap_or_default(),
+            action: ins.action.clone(),
+            label: ins.label.clone(),
+            reason: ins.reason.clone(),
+            score: stock.map(|s| s.score).unwrap_or(0),
+            current_pct: stock.map(|s| s.current_pct).unwrap_or(0.0),
+            next_date: None,
+            next_day_pct: None,
+            next_day_high_pct: None,
+            created_at: created_at.clone(),
+        }
+    }).collect();
+    db.save_instructions(&records)?;
+    log::info!("[instruction_tracker] recorded {} instructions for {}", records.len(), date);
+    Ok(())
+}
+
+/// 回填历史指令的次日表现（次日收盘涨幅、次日最高涨幅），返回本次回填条数
+pub async fn update_outcomes(db: &Database) -> Result<usize> {
+    let today = Local::now().format("%Y-%m-%d").to_string();
+    let pending = db.get_pending_instruction_outcomes(&today)?;
+    if pending.is_empty() {
+        return Ok(0);
+    }
+    let kline_service = HistoryKlineService::new()?;
+    let mut updated = 0;
+
+    for record in &pending {
+        let klines = match kline_service.fetch_kline_incremental(&record.code, "day", &record.date, &today).await {
+            Ok(k) => k,
+            Err(e) => {
+                log::warn!("[instruction_tracker] fetch kline failed for {}: {}", record.code, e);
+                continue;
+            }
+        };
+        // 次日尚未收盘时不回填，留待下次
+        let next = match klines.first() {
+            Some(k) if k.date < today => k,
+            _ => continue,
+        };
+        let prev_close = next.close / (1.0 + next.change_pct / 100.0);
+        let high_pct = if prev_close > 0.0 { (next.high / prev_close - 1.0) * 100.0 } else { 0.0 };
+        db.update_instruction_outcome(&record.date, &record.code, &next.date, next.change_pct, high_pct)?;
+        updated += 1;
+    }
+
+    log::info!("[instruction_tracker] updated {} of {} pending outcomes", updated, pending.len());
+    Ok(updated)
+}
@@ -0,0 +1,147 @@
+use anyhow::Result;
+use chrono::Local;
+use std::collections::{BTreeMap, HashSet};
+use tauri::Manager;
+
+use crate::AppState;
+use crate::db::database::Database;
+use crate::models::replay::{IntradaySnapshot, ReplayFrame};
+use crate::models::stock::StockInfo;
+use crate::services::job_scheduler::{JobSpec, JobTrigger};
+use crate::services::quote_provider;
+
+/// 盘中快照保留的交易日数
+const KEEP_DAYS: usize = 30;
+/// 默认采集间隔（秒），可在后台任务设置中调整
+const CAPTURE_INTERVAL_SECS: u64 = 60;
+
+/// 由实时行情生成快照；无成交且无买卖盘报价（停牌、竞价前）时跳过
+pub fn snapshot_from_quote(quote: &StockInfo, date: &str, time: &str) -> Option<IntradaySnapshot> {
+    if quote.price <= 0.0 && quote.buy1_price <= 0.0 {
+        return None;
+    }
+    // 集合竞价阶段尚无成交价时以买一（虚拟匹配价）代替
+    let price = if quote.price > 0.0 { quote.price } else { quote.buy1_price };
+    Some(IntradaySnapshot {
+        code: quote.code.clone(),
+        name: quote.name.clone(),
+        date: date.to_string(),
+        time: time.to_string(),
+        price,
+        pre_close: quote.pre_close,
+        change_pct: if quote.pre_close > 0.0 { (price - quote.pre_close) / quote.pre_close * 100.0 } else { 0.0 },
+        volume: quote.volume,
+        amount: quote.amount,
+        bid1: quote.buy1_price,
+        ask1: quote.sell1_price,
+        bid1_vol: quote.buy1_vol,
+        ask1_vol: quote.sell1_vol,
+    })
+}
+
+/// 采集自选股与 AI 追踪股票的当前行情，返回新增快照数
+pub async fn capture(db: &Database) -> Result<usize> {
+    let mut seen = HashSet::new();
+    let codes: Vec<String> = db
+        .get_watchlist_stocks()?
+        .into_iter()
+        .map(|s| s.code)
+        .chain(db.get_tracking_stocks()?.into_iter().map(|t| t.code))
+        .filter(|c| seen.insert(c.clone()))
+        .collect();
+    if codes.is_empty() {
+        return Ok(0);
+    }
+    let quotes = quote_provider::fetch_quotes(&db.load_settings()?, &codes).await?;
+    let now = Local::now();
+    let date = now.format("%Y-%m-%d").to_string();
+    let time = now.format("%H:%M:%S").to_string();
+    let snapshots: Vec<IntradaySnapshot> = quotes.iter().filter_map(|q| snapshot_from_quote(q, &date, &time)).collect();
+    let inserted = db.save_intraday_snapshots(&snapshots, KEEP_DAYS)?;
+    log::info!("[intraday_replay] capture codes={} inserted={}", codes.len(), inserted);
+    Ok(inserted)
+}
+
+/// 按分钟合并为回放帧：每帧包含截至该分钟出现过的全部股票的最新快照，保证逐帧播放时行情连续
+pub fn build_frames(snapshots: &[IntradaySnapshot]) -> Vec<ReplayFrame> {
+    let mut by_minute: BTreeMap<&str, Vec<&IntradaySnapshot>> = BTreeMap::new();
+    for snapshot in snapshots {
+        let minute = snapshot.time.get(..5).unwrap_or(&snapshot.time);
+        by_minute.entry(minute).or_default().push(snapshot);
+    }
+    let mut latest: BTreeMap<&str, &IntradaySnapshot> = BTreeMap::new();
+    by_minute
+        .into_iter()
+        .map(|(minute, items)| {
+            for item in items {
+                latest.insert(&item.code, item);
+            }
+            ReplayFrame {
+                time: minute.to_string(),
+                quotes: latest.values().map(|s| (*s).clone()).collect(),
+            }
+        })
+        .collect()
+}
+
+/// 读取某日快照并生成回放帧
+pub fn load_replay(db: &Database, date: &str, codes: &[String]) -> Result<Vec<ReplayFrame>> {
+    let snapshots = db.get_intraday_snapshots(date, codes)?;
+    Ok(build_frames(&snapshots))
+}
+
+/// 集合竞价与盘中定时采集分时快照
+pub fn intraday_capture_job() -> JobSpec {
+    JobSpec {
+        id: "intraday_capture",
+        name: "盘中快照采集",
+        trigger: JobTrigger::TradingSession,
+        interval_secs: CAPTURE_INTERVAL_SECS,
+        run: |app| Box::pin(async move {
+            let db = &app.state::<AppState>().db;
+            let inserted = capture(db).await?;
+            Ok(Some(format!("新增快照 {} 条", inserted)))
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(code: &str, time: &str, price: f64) -> IntradaySnapshot {
+        IntradaySnapshot {
+            code: code.to_string(),
+            name: code.to_string(),
+            date: "2026-03-02".to_string(),
+            time: time.to_string(),
+            price,
+            pre_close: 10.0,
+            change_pct: (price - 10.0) * 10.0,
+            volume: 0.0,
+            amount: 0.0,
+            bid1: 0.0,
+            ask1: 0.0,
+            bid1_vol: 0.0,
+            ask1_vol: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_build_frames() {
+        let snapshots = vec![
+            snapshot("sh600000", "09:15:05", 10.2),
+            snapshot("sz000001", "09:16:02", 9.9),
+            snapshot("sh600000", "09:16:03", 10.3),
+            snapshot("sh600000", "09:16:40", 10.4),
+        ];
+        let frames = build_frames(&snapshots);
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].time, "09:15");
+        assert_eq!(frames[0].quotes.len(), 1);
+        assert_eq!(frames[1].time, "09:16");
+        let prices: Vec<f64> = frames[1].quotes.iter().map(|q| q.price).collect();
+        assert_eq!(prices, vec![10.4, 9.9]);
+        assert!(build_frames(&[]).is_empty());
+    }
+}
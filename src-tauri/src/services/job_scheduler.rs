@@ -0,0 +1,344 @@
+use anyhow::{Result, anyhow};
+use chrono::{Datelike, Duration as ChronoDuration, Local, Timelike, Weekday};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::AppState;
+use crate::models::job::{JobRun, ScheduledJob};
+use crate::models::settings::JobSetting;
+use crate::services::history_kline::HistoryKlineService;
+
+/// 每个任务保留的运行记录数
+const KEEP_RUNS: usize = 50;
+/// 任务状态中返回的最近运行记录数
+const STATUS_RUNS: usize = 10;
+/// 用户可设置的最短间隔（秒）
+const MIN_INTERVAL_SECS: u64 = 10;
+/// 上证指数当日K线在开盘后才出现，此前按工作日判断是否交易日（HHMM）
+const CALENDAR_CONFIRM_AFTER: u32 = 935;
+const CALENDAR_INDEX: &str = "sh000001";
+
+/// 任务单次执行结果：Ok(None) 表示本轮无事可做，不写运行记录，每日任务也不视为当日已完成
+pub type JobOutcome = Result<Option<String>>;
+pub type JobFuture = Pin<Box<dyn Future<Output = JobOutcome> + Send>>;
+
+/// 任务调度规则
+#[derive(Debug, Clone, Copy)]
+pub enum JobTrigger {
+    /// 应用启动后执行一次
+    Startup,
+    /// 全天按间隔执行
+    Interval,
+    /// 交易日连续竞价时段（09:30~11:30、13:00~15:00）按间隔执行
+    TradingHours,
+    /// 交易日含开盘集合竞价的交易时段（09:15~11:30、13:00~15:00）按间隔执行
+    TradingSession,
+    /// 交易日 HHMM 之后执行一次，未完成时按间隔重试
+    DailyAfter(u32),
+    /// 交易日 [开始, 截止) 时段内执行一次（HHMM）
+    DailyWindow(u32, u32),
+}
+
+impl JobTrigger {
+    fn describe(&self) -> String {
+        let hhmm = |t: u32| format!("{:02}:{:02}", t / 100, t % 100);
+        match self {
+            JobTrigger::Startup => "启动时执行一次".to_string(),
+            JobTrigger::Interval => "全天按间隔执行".to_string(),
+            JobTrigger::TradingHours => "交易时段按间隔执行".to_string(),
+            JobTrigger::TradingSession => "集合竞价及交易时段按间隔执行".to_string(),
+            JobTrigger::DailyAfter(t) => format!("交易日 {} 后每日一次", hhmm(*t)),
+            JobTrigger::DailyWindow(from, until) => format!("交易日 {}~{} 每日一次", hhmm(*from), hhmm(*until)),
+        }
+    }
+
+    /// 不考虑交易日历时，当前时刻是否应执行。done_today 为每日任务当日是否已完成
+    fn is_due(&self, hhmm: u32, done_today: bool) -> bool {
+        match *self {
+            JobTrigger::Startup | JobTrigger::Interval => true,
+            JobTrigger::TradingHours => (930..=1130).contains(&hhmm) || (1300..=1500).contains(&hhmm),
+            JobTrigger::TradingSession => (915..=1130).contains(&hhmm) || (1300..=1500).contains(&hhmm),
+            JobTrigger::DailyAfter(t) => !done_today && hhmm >= t,
+            JobTrigger::DailyWindow(from, until) => !done_today && (from..until).contains(&hhmm),
+        }
+    }
+
+    /// 在 hhmm 开始的成功执行是否算作每日任务当日已完成：早于触发时间的手动执行不算，
+    /// 以免提前执行（如收盘前手动归档）跳过当日正式调度
+    fn completes_day(&self, hhmm: u32) -> bool {
+        match *self {
+            JobTrigger::DailyAfter(t) | JobTrigger::DailyWindow(t, _) => hhmm >= t,
+            _ => true,
+        }
+    }
+
+    fn needs_trading_day(&self) -> bool {
+        !matches!(self, JobTrigger::Startup | JobTrigger::Interval)
+    }
+}
+
+/// 后台任务定义，由各服务模块提供并在 lib.rs 中统一注册
+pub struct JobSpec {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub trigger: JobTrigger,
+    /// 默认检查/执行间隔（秒），可在设置中按任务覆盖
+    pub interval_secs: u64,
+    pub run: fn(AppHandle) -> JobFuture,
+}
+
+#[derive(Default)]
+struct JobRuntime {
+    running: bool,
+    /// 每日任务最近一次完成的日期
+    done_date: Option<String>,
+}
+
+fn jobs() -> &'static OnceLock<Vec<JobSpec>> {
+    static JOBS: OnceLock<Vec<JobSpec>> = OnceLock::new();
+    &JOBS
+}
+
+fn runtime() -> &'static Mutex<HashMap<&'static str, JobRuntime>> {
+    static RUNTIME: OnceLock<Mutex<HashMap<&'static str, JobRuntime>>> = OnceLock::new();
+    RUNTIME.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 交易日历：周末休市；工作日开盘后以上证指数是否出现当日日K识别节假日，结果按日缓存，取数失败时按交易日处理
+pub async fn is_trading_day() -> bool {
+    static CACHE: OnceLock<Mutex<Option<(String, bool)>>> = OnceLock::new();
+    let now = Local::now();
+    if matches!(now.weekday(), Weekday::Sat | Weekday::Sun) {
+        return false;
+    }
+    if now.hour() * 100 + now.minute() < CALENDAR_CONFIRM_AFTER {
+        return true;
+    }
+    let today = now.format("%Y-%m-%d").to_string();
+    let cache = CACHE.get_or_init(|| Mutex::new(None));
+    if let Some((date, trading)) = cache.lock().unwrap().as_ref() {
+        if *date == today {
+            return *trading;
+        }
+    }
+    let start = (now - ChronoDuration::days(10)).format("%Y-%m-%d").to_string();
+    let latest = match HistoryKlineService::new() {
+        Ok(service) => service.fetch_kline(CALENDAR_INDEX, "day", &start, &today, 10).await,
+        Err(e) => Err(e),
+    };
+    match latest {
+        Ok(klines) => {
+            let trading = klines.last().is_some_and(|k| k.date == today);
+            if !trading {
+                log::info!("[job_scheduler] {} is not a trading day", today);
+            }
+            *cache.lock().unwrap() = Some((today, trading));
+            trading
+        }
+        Err(e) => {
+            log::warn!("[job_scheduler] trading calendar check failed: {}", e);
+            true
+        }
+    }
+}
+
+fn job_setting(app: &AppHandle, id: &str) -> JobSetting {
+    app.state::<AppState>()
+        .db
+        .load_settings()
+        .ok()
+        .and_then(|s| s.job_settings.get(id).cloned())
+        .unwrap_or(JobSetting { enabled: true, interval_secs: None })
+}
+
+fn effective_interval(job: &JobSpec, setting: &JobSetting) -> u64 {
+    setting.interval_secs.unwrap_or(job.interval_secs).max(MIN_INTERVAL_SECS)
+}
+
+/// 执行一次任务并写入运行记录。任务正在执行或应用正在退出时返回 None
+async fn execute(app: &AppHandle, job: &'static JobSpec) -> Option<JobRun> {
+    let app_state = app.state::<AppState>();
+    if app_state.shutdown.is_requested() {
+        return None;
+    }
+    // 登记为进行中任务，退出流程会等待本次执行写完数据
+    let _guard = app_state.shutdown.track();
+    {
+        let mut runtime = runtime().lock().unwrap();
+        let state = runtime.entry(job.id).or_default();
+        if state.running {
+            return None;
+        }
+        state.running = true;
+    }
+    let started_at = Local::now();
+    let outcome = (job.run)(app.clone()).await;
+    let finished_at = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+
+    let run = match outcome {
+        Ok(None) => None,
+        Ok(Some(message)) => Some((true, message)),
+        Err(e) => {
+            log::warn!("[job_scheduler] job {} failed: {}", job.id, e);
+            Some((false, e.to_string()))
+        }
+    };
+    let mut runtime = runtime().lock().unwrap();
+    let state = runtime.entry(job.id).or_default();
+    state.running = false;
+    let (success, message) = run?;
+    if success && job.trigger.completes_day(started_at.hour() * 100 + started_at.minute()) {
+        state.done_date = Some(started_at.format("%Y-%m-%d").to_string());
+    }
+    drop(runtime);
+
+    let run = JobRun {
+        id: 0,
+        job_id: job.id.to_string(),
+        started_at: started_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        finished_at,
+        success,
+        message,
+    };
+    if let Err(e) = app_state.db.insert_job_run(&run, KEEP_RUNS) {
+        log::warn!("[job_scheduler] save run of {} failed: {}", job.id, e);
+    }
+    Some(run)
+}
+
+async fn run_loop(app: AppHandle, job: &'static JobSpec) {
+    loop {
+        let setting = job_setting(&app, job.id);
+        if setting.enabled {
+            let now = Local::now();
+            let today = now.format("%Y-%m-%d").to_string();
+            let done_today = runtime().lock().unwrap().get(job.id).and_then(|s| s.done_date.clone()) == Some(today);
+            if job.trigger.is_due(now.hour() * 100 + now.minute(), done_today)
+                && (!job.trigger.needs_trading_day() || is_trading_day().await)
+            {
+                execute(&app, job).await;
+            }
+        }
+        if matches!(job.trigger, JobTrigger::Startup) {
+            return;
+        }
+        let shutdown = &app.state::<AppState>().shutdown;
+        tokio::select! {
+            _ = tokio::time::sleep(Duration::from_secs(effective_interval(job, &setting))) => {}
+            _ = shutdown.cancelled() => {
+                log::info!("[job_scheduler] job {} stopped", job.id);
+                return;
+            }
+        }
+    }
+}
+
+/// 运行记录开始时间（YYYY-MM-DD HH:MM:SS）中的 HHMM
+fn started_hhmm(started_at: &str) -> Option<u32> {
+    let hour: u32 = started_at.get(11..13)?.parse().ok()?;
+    let minute: u32 = started_at.get(14..16)?.parse().ok()?;
+    Some(hour * 100 + minute)
+}
+
+/// 注册并启动全部后台任务：每个任务一个循环，按设置中的启用状态与间隔运行。重复调用时忽略
+pub fn start(app: AppHandle, specs: Vec<JobSpec>) {
+    if jobs().set(specs).is_err() {
+        log::warn!("[job_scheduler] already started");
+        return;
+    }
+    let registered = jobs().get().unwrap();
+    // 从运行记录恢复每日任务的完成日期，避免重启后同日重复执行；早于触发时间的手动执行不算完成
+    match app.state::<AppState>().db.get_last_job_successes() {
+        Ok(successes) => {
+            let mut runtime = runtime().lock().unwrap();
+            for job in registered {
+                let done_date = successes
+                    .get(job.id)
+                    .filter(|t| started_hhmm(t).is_some_and(|hhmm| job.trigger.completes_day(hhmm)))
+                    .and_then(|t| t.get(..10))
+                    .map(str::to_string);
+                runtime.insert(job.id, JobRuntime { running: false, done_date });
+            }
+        }
+        Err(e) => log::warn!("[job_scheduler] load run history failed: {}", e),
+    }
+    log::info!("[job_scheduler] starting {} jobs", registered.len());
+    for job in registered {
+        tauri::async_runtime::spawn(run_loop(app.clone(), job));
+    }
+}
+
+/// 全部已注册任务的配置与最近运行记录
+pub fn list_jobs(app: &AppHandle) -> Result<Vec<ScheduledJob>> {
+    let Some(registered) = jobs().get() else {
+        return Ok(vec![]);
+    };
+    let state = app.state::<AppState>();
+    let settings = state.db.load_settings()?;
+    let mut result = Vec::with_capacity(registered.len());
+    for job in registered {
+        let setting = settings
+            .job_settings
+            .get(job.id)
+            .cloned()
+            .unwrap_or(JobSetting { enabled: true, interval_secs: None });
+        let running = runtime().lock().unwrap().get(job.id).is_some_and(|s| s.running);
+        result.push(ScheduledJob {
+            id: job.id.to_string(),
+            name: job.name.to_string(),
+            schedule: job.trigger.describe(),
+            enabled: setting.enabled,
+            interval_secs: effective_interval(job, &setting),
+            default_interval_secs: job.interval_secs,
+            running,
+            recent_runs: state.db.get_job_runs(job.id, STATUS_RUNS)?,
+        });
+    }
+    Ok(result)
+}
+
+/// 立即执行指定任务（忽略启用状态与调度时间），返回本次运行记录；任务无事可做时返回 None。
+/// 每日任务在触发时间前手动执行不影响当日的正式调度
+pub async fn run_job_now(app: &AppHandle, id: &str) -> Result<Option<JobRun>> {
+    let job = jobs()
+        .get()
+        .and_then(|all| all.iter().find(|j| j.id == id))
+        .ok_or_else(|| anyhow!("未知的后台任务: {}", id))?;
+    if runtime().lock().unwrap().get(job.id).is_some_and(|s| s.running) {
+        return Err(anyhow!("任务 {} 正在执行", job.name));
+    }
+    log::info!("[job_scheduler] run_job_now id={}", id);
+    Ok(execute(app, job).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trigger_due() {
+        assert!(JobTrigger::TradingHours.is_due(1000, false));
+        assert!(!JobTrigger::TradingHours.is_due(1200, false));
+        assert!(JobTrigger::TradingSession.is_due(918, false));
+        assert!(!JobTrigger::TradingHours.is_due(918, false));
+        assert!(JobTrigger::DailyAfter(1530).is_due(1600, false));
+        assert!(!JobTrigger::DailyAfter(1530).is_due(1600, true));
+        assert!(!JobTrigger::DailyAfter(1530).is_due(1500, false));
+        assert!(JobTrigger::DailyWindow(830, 915).is_due(900, false));
+        assert!(!JobTrigger::DailyWindow(830, 915).is_due(915, false));
+        assert!(JobTrigger::Interval.is_due(300, true));
+
+        // 触发时间前的手动执行不算当日完成
+        assert!(!JobTrigger::DailyAfter(1530).completes_day(1400));
+        assert!(JobTrigger::DailyAfter(1530).completes_day(1530));
+        assert!(!JobTrigger::DailyWindow(830, 915).completes_day(800));
+        assert!(JobTrigger::DailyWindow(830, 915).completes_day(1000));
+        assert!(JobTrigger::Interval.completes_day(100));
+        assert_eq!(started_hhmm("2024-06-07 15:36:02"), Some(1536));
+        assert_eq!(started_hhmm("2024-06-07"), None);
+    }
+}
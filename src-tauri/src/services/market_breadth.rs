@@ -0,0 +1,210 @@
+use anyhow::{Result, anyhow};
+use chrono::Local;
+use futures::stream::{self, StreamExt};
+use tauri::Manager;
+
+use crate::AppState;
+use crate::db::database::Database;
+use crate::models::stock::{MarketBreadth, MarketStockSnapshot, StockDailyHistory};
+use crate::services::history_kline::HistoryKlineService;
+use crate::services::index_constituents;
+use crate::services::market_scanner::{MarketScanner, limit_pct};
+use crate::services::job_scheduler::{JobSpec, JobTrigger};
+
+/// 均线与新高新低统计的样本指数（沪深300）
+const SAMPLE_INDEX: &str = "000300";
+/// 52 周约 250 个交易日
+const YEAR_BARS: usize = 250;
+/// 样本股至少需要的日线条数（MA60）
+const MIN_SAMPLE_BARS: usize = 60;
+/// 距涨跌停价不足该幅度即计入涨跌停（行情涨跌幅保留两位小数，存在舍入误差）
+const LIMIT_TOLERANCE: f64 = 0.3;
+/// 同步样本股日线的并发数
+const SYNC_CONCURRENCY: usize = 4;
+/// 收盘后记录当日宽度的时间（HHMM）
+const RECORD_AFTER: u32 = 1530;
+const CHECK_INTERVAL_SECS: u64 = 600;
+
+/// 由全市场快照统计涨跌家数与涨跌停数（停牌股不计入），均线类字段留空
+pub fn snapshot_stats(stocks: &[MarketStockSnapshot]) -> MarketBreadth {
+    let mut b = MarketBreadth {
+        date: Local::now().format("%Y-%m-%d").to_string(),
+        created_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        ..Default::default()
+    };
+    for s in stocks.iter().filter(|s| s.price > 0.0 && s.volume > 0.0) {
+        b.total += 1;
+        if s.change_pct > 0.0 {
+            b.advancers += 1;
+        } else if s.change_pct < 0.0 {
+            b.decliners += 1;
+        } else {
+            b.flat += 1;
+        }
+        let limit = limit_pct(&s.code, &s.name);
+        if s.change_pct >= limit - LIMIT_TOLERANCE {
+            b.limit_up += 1;
+        } else if s.change_pct <= -limit + LIMIT_TOLERANCE {
+            b.limit_down += 1;
+        }
+    }
+    b
+}
+
+/// 由样本股日线（按日期升序）统计站上 MA20/MA60 的比例与 52 周新高新低数量，日线不足 60 条的股票不计入样本
+pub fn apply_trend_stats(breadth: &mut MarketBreadth, histories: &[Vec<StockDailyHistory>]) {
+    let samples: Vec<&Vec<StockDailyHistory>> = histories.iter().filter(|h| h.len() >= MIN_SAMPLE_BARS).collect();
+    breadth.sample_size = samples.len();
+    if samples.is_empty() {
+        breadth.above_ma20_pct = None;
+        breadth.above_ma60_pct = None;
+        return;
+    }
+
+    let (mut above_ma20, mut above_ma60) = (0, 0);
+    breadth.new_high_52w = 0;
+    breadth.new_low_52w = 0;
+    for h in &samples {
+        let last = &h[h.len() - 1];
+        if last.close > average_close(h, 20) {
+            above_ma20 += 1;
+        }
+        if last.close > average_close(h, 60) {
+            above_ma60 += 1;
+        }
+        let year = &h[h.len().saturating_sub(YEAR_BARS)..];
+        if year.iter().all(|d| last.high >= d.high) {
+            breadth.new_high_52w += 1;
+        }
+        if year.iter().all(|d| last.low <= d.low) {
+            breadth.new_low_52w += 1;
+        }
+    }
+    breadth.above_ma20_pct = Some(above_ma20 as f64 * 100.0 / samples.len() as f64);
+    breadth.above_ma60_pct = Some(above_ma60 as f64 * 100.0 / samples.len() as f64);
+}
+
+fn average_close(history: &[StockDailyHistory], n: usize) -> f64 {
+    let window = &history[history.len() - n..];
+    window.iter().map(|d| d.close).sum::<f64>() / n as f64
+}
+
+/// 计算当前市场宽度。sync 为 true 时先增量同步样本股日线（收盘后记录用），否则只读本地缓存
+pub async fn compute_breadth(db: &Database, sync: bool) -> Result<MarketBreadth> {
+    let stocks = MarketScanner::new()?.scan_full_market().await?;
+    if stocks.is_empty() {
+        return Err(anyhow!("全市场扫描返回为空"));
+    }
+    let mut breadth = snapshot_stats(&stocks);
+
+    let codes: Vec<String> = match index_constituents::get_index_constituents(SAMPLE_INDEX).await {
+        Ok(c) => c.stocks.into_iter().map(|s| s.code).collect(),
+        Err(e) => {
+            log::warn!("[market_breadth] fetch sample constituents failed: {}", e);
+            vec![]
+        }
+    };
+    if sync && !codes.is_empty() {
+        let service = HistoryKlineService::new()?;
+        let results: Vec<bool> = stream::iter(codes.clone())
+            .map(|code| {
+                let service = &service;
+                async move { service.sync_daily_history(db, &code).await.is_ok() }
+            })
+            .buffer_unordered(SYNC_CONCURRENCY)
+            .collect()
+            .await;
+        let failed = results.iter().filter(|ok| !**ok).count();
+        if failed > 0 {
+            log::warn!("[market_breadth] sync sample history failed for {} stocks", failed);
+        }
+    }
+
+    let mut histories = Vec::with_capacity(codes.len());
+    for code in &codes {
+        histories.push(db.get_daily_history_asc(code, YEAR_BARS)?);
+    }
+    apply_trend_stats(&mut breadth, &histories);
+    log::info!(
+        "[market_breadth] compute_breadth total={} up={} down={} sample={}",
+        breadth.total, breadth.advancers, breadth.decliners, breadth.sample_size
+    );
+    Ok(breadth)
+}
+
+/// 后台任务：交易日收盘后计算并保存当日市场宽度
+pub fn breadth_record_job() -> JobSpec {
+    JobSpec {
+        id: "breadth_record",
+        name: "市场宽度记录",
+        trigger: JobTrigger::DailyAfter(RECORD_AFTER),
+        interval_secs: CHECK_INTERVAL_SECS,
+        run: |app| Box::pin(async move {
+            let db = &app.state::<AppState>().db;
+            let breadth = compute_breadth(db, true).await?;
+            db.save_market_breadth(&breadth)?;
+            Ok(Some(format!("样本 {} 只", breadth.sample_size)))
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quote(code: &str, name: &str, change_pct: f64) -> MarketStockSnapshot {
+        MarketStockSnapshot {
+            code: code.to_string(),
+            name: name.to_string(),
+            price: 10.0,
+            volume: 1000.0,
+            change_pct,
+            ..Default::default()
+        }
+    }
+
+    fn history(closes: &[f64]) -> Vec<StockDailyHistory> {
+        closes
+            .iter()
+            .map(|&c| StockDailyHistory {
+                code: String::new(),
+                date: String::new(),
+                close: c,
+                high: c,
+                low: c,
+                open: c,
+                volume: 0.0,
+                amount: 0.0,
+                change_pct: 0.0,
+                is_limit_up: false,
+                turnover_rate: 0.0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_snapshot_stats() {
+        let stocks = vec![
+            quote("sh600000", "浦发银行", 9.98),
+            quote("sz300750", "宁德时代", 9.98),
+            quote("sz300001", "特锐德", -19.99),
+            quote("sh600001", "*ST某某", -4.95),
+            quote("sh600002", "某某股份", 0.0),
+            MarketStockSnapshot { price: 0.0, ..quote("sh600003", "停牌股", 0.0) },
+        ];
+        let b = snapshot_stats(&stocks);
+        assert_eq!((b.total, b.advancers, b.decliners, b.flat), (5, 2, 2, 1));
+        assert_eq!((b.limit_up, b.limit_down), (1, 2));
+    }
+
+    #[test]
+    fn test_trend_stats() {
+        let rising: Vec<f64> = (1..=100).map(|i| i as f64).collect();
+        let falling: Vec<f64> = rising.iter().rev().copied().collect();
+        let mut b = MarketBreadth::default();
+        apply_trend_stats(&mut b, &[history(&rising), history(&falling), history(&[1.0; 10])]);
+        assert_eq!(b.sample_size, 2);
+        assert_eq!(b.above_ma20_pct, Some(50.0));
+        assert_eq!((b.new_high_52w, b.new_low_52w), (1, 1));
+    }
+}
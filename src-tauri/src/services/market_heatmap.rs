@@ -0,0 +1,48 @@
+use anyhow::Result;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::models::stock::MarketHeatmap;
+use crate::services::market_scanner::MarketScanner;
+
+/// 热力图缓存有效期：盘中板块行情变化快，30 秒内的重复请求直接复用
+const HEATMAP_TTL: Duration = Duration::from_secs(30);
+
+type HeatmapCache = Mutex<Option<(Instant, Arc<MarketHeatmap>)>>;
+
+fn cache() -> &'static HeatmapCache {
+    static CACHE: OnceLock<HeatmapCache> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(None))
+}
+
+/// 获取全部行业与概念板块的当日行情（带 30 秒内存缓存）。单类板块获取失败时该类为空，两类都失败才报错
+pub async fn get_market_heatmap() -> Result<Arc<MarketHeatmap>> {
+    if let Some((at, cached)) = cache().lock().unwrap().as_ref() {
+        if at.elapsed() < HEATMAP_TTL {
+            return Ok(Arc::clone(cached));
+        }
+    }
+    let scanner = MarketScanner::new()?;
+    let (industries, concepts) = tokio::join!(scanner.fetch_boards("industry"), scanner.fetch_boards("concept"));
+    let (industries, concepts) = match (industries, concepts) {
+        (Err(e), Err(_)) => return Err(e),
+        (i, c) => (
+            i.unwrap_or_else(|e| {
+                log::warn!("[market_heatmap] fetch industry boards failed: {}", e);
+                vec![]
+            }),
+            c.unwrap_or_else(|e| {
+                log::warn!("[market_heatmap] fetch concept boards failed: {}", e);
+                vec![]
+            }),
+        ),
+    };
+    log::info!("[market_heatmap] refreshed industries={} concepts={}", industries.len(), concepts.len());
+    let heatmap = Arc::new(MarketHeatmap {
+        industries,
+        concepts,
+        updated_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+    });
+    *cache().lock().unwrap() = Some((Instant::now(), Arc::clone(&heatmap)));
+    Ok(heatmap)
+}
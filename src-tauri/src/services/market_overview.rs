@@ -3,10 +3,14 @@ use serde::{Deserialize, Serialize};
 use crate::models::settings::AppSettings;
 use crate::models::ai::AIConfig;
 use crate::models::ai::{ChatCompletionRequest, ChatCompletionResponse, ChatMessage};
+use crate::services::ai_postprocess;
+use crate::services::model_capability;
+use crate::services::quote_provider;
 use crate::services::stock_data::StockDataService;
 use crate::services::scheduler::TradingScheduler;
 use crate::services::stock_tools;
-use crate::utils::http::{build_stock_client, build_ai_client};
+use crate::utils::encoding::response_text;
+use crate::utils::http::{build_stock_client, build_ai_client, SendLogged};
 
 // ============================================================
 // 数据结构定义
@@ -83,11 +87,9 @@ pub struct GlobalIndex {
 // ============================================================
 
 pub async fn fetch_overview(settings: &AppSettings) -> Result<MarketOverview> {
-    let use_sina = matches!(settings.data_source_primary, crate::models::settings::DataSource::Sina);
-
     let (indexes_res, stats_res, sectors_res, global_res, kline_res) =
         tokio::join!(
-            fetch_index_quotes(use_sina),
+            fetch_index_quotes(settings),
             fetch_market_stats(),
             fetch_sector_ranking_sina(),
             fetch_global_indexes(),
@@ -135,15 +137,14 @@ pub async fn fetch_overview(settings: &AppSettings) -> Result<MarketOverview> {
 // 三大指数实时行情
 // ============================================================
 
-async fn fetch_index_quotes(use_sina: bool) -> Result<Vec<IndexQuote>> {
-    let svc = StockDataService::new()?;
+async fn fetch_index_quotes(settings: &AppSettings) -> Result<Vec<IndexQuote>> {
     let codes = vec![
         "sh000001".to_string(), // 上证指数
         "sz399001".to_string(), // 深证成指
         "sz399006".to_string(), // 创业板指
     ];
 
-    let stocks = svc.get_realtime_batch(&codes, use_sina).await?;
+    let stocks = quote_provider::fetch_quotes(settings, &codes).await?;
 
     let names = ["上证指数", "深证成指", "创业板指"];
     let quotes: Vec<IndexQuote> = stocks.into_iter().enumerate().map(|(i, s)| {
@@ -175,7 +176,7 @@ async fn fetch_market_stats() -> Result<MarketStats> {
 
     let text = client.get(url)
         .header("Referer", "https://quote.eastmoney.com/")
-        .send()
+        .send_logged()
         .await?
         .text()
         .await?;
@@ -208,12 +209,12 @@ async fn fetch_sector_ranking_sina() -> Result<(Vec<SectorInfo>, Vec<SectorInfo>
     let client = build_stock_client()?;
     let url = "https://vip.stock.finance.sina.com.cn/q/view/newSinaHy.php";
 
-    let text = client.get(url)
+    // 新浪板块接口返回 GBK 编码
+    let resp = client.get(url)
         .header("Referer", "https://finance.sina.com.cn/")
-        .send()
-        .await?
-        .text()
+        .send_logged()
         .await?;
+    let text = response_text(resp).await?;
 
     // 接口返回格式: var S_Finance_bankuai_sinaindustry = {"key":"val1,val2,...", ...}
     // 提取 JSON 对象部分
@@ -357,25 +358,28 @@ pub async fn generate_market_comment(config: &AIConfig, overview_json: &str) ->
 
     let user_msg = format!("以下是当前A股大盘实时数据（JSON格式），请据此生成盘面点评：\n\n{}", overview_json);
 
-    let req = ChatCompletionRequest {
+    let mut req = ChatCompletionRequest {
         model: config.model_name.clone(),
         messages: vec![
             ChatMessage::system(system_prompt),
             ChatMessage::user(&user_msg),
         ],
         max_tokens: Some(300),
+        max_completion_tokens: None,
         temperature: Some(0.3),
         stream: Some(false),
         tools: None,
         tool_choice: None,
     };
 
+    model_capability::adapt_request(&mut req);
+
     let resp = client
         .post(&url)
         .header("Authorization", format!("Bearer {}", config.api_key))
         .header("Content-Type", "application/json")
         .json(&req)
-        .send()
+        .send_logged()
         .await
         .map_err(|e| anyhow!("AI 盘面解说请求失败: {}", e))?;
 
@@ -394,5 +398,5 @@ pub async fn generate_market_comment(config: &AIConfig, overview_json: &str) ->
         .and_then(|m| m.content.clone())
         .unwrap_or_else(|| "暂无解说".to_string());
 
-    Ok(reply.trim().to_string())
+    Ok(ai_postprocess::clean(&reply))
 }
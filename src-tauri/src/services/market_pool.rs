@@ -1,6 +1,6 @@
 use anyhow::{Result, anyhow};
 use serde::{Deserialize, Serialize};
-use crate::utils::http::build_stock_client;
+use crate::utils::http::{build_stock_client, SendLogged};
 
 /// 东方财富涨停池/连板池数据
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -51,7 +51,7 @@ impl MarketPoolService {
             .get(&url)
             .header("Referer", "https://quote.eastmoney.com/")
             .header("User-Agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36")
-            .send()
+            .send_logged()
             .await?;
 
         let body: serde_json::Value = resp.json().await?;
@@ -105,7 +105,7 @@ impl MarketPoolService {
             .get(&url)
             .header("Referer", "https://quote.eastmoney.com/")
             .header("User-Agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36")
-            .send()
+            .send_logged()
             .await?;
 
         let body: serde_json::Value = resp.json().await?;
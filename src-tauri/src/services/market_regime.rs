@@ -0,0 +1,285 @@
+use anyhow::{Result, anyhow};
+use chrono::Local;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use crate::db::database::Database;
+use crate::models::stock::{MarketBreadth, MarketRegime, RegimeStyle, RegimeTrend, RegimeVolatility};
+use crate::models::watchlist::KlineItem;
+use crate::services::history_kline::HistoryKlineService;
+use crate::services::risk_metrics;
+
+/// 风格对比：上证50 代表大盘价值，中证1000 代表小盘成长
+const LARGE_CAP_INDEX: &str = "sh000016";
+const SMALL_CAP_INDEX: &str = "sh000852";
+/// 两市成交量：上证指数 + 深证成指
+const VOLUME_INDEXES: [&str; 2] = ["sh000001", "sz399001"];
+/// 风格与量比只需近期日线
+const RECENT_BARS: u32 = 40;
+/// 一年约 250 个交易日
+const YEAR_BARS: usize = 250;
+/// 判定趋势至少需要的日线条数（MA60 + 1）
+const MIN_TREND_BARS: usize = 61;
+/// 20 日波动率相对一年波动率的高/低阈值
+const HIGH_VOL_RATIO: f64 = 1.3;
+const LOW_VOL_RATIO: f64 = 0.75;
+/// 大小盘 20 日涨幅差超过该值（百分点）判定风格占优
+const STYLE_SPREAD: f64 = 3.0;
+/// 站上 MA20 比例低于/高于该值时，指数趋势与多数个股背离，降级为震荡
+const BREADTH_WEAK: f64 = 40.0;
+const BREADTH_STRONG: f64 = 60.0;
+/// 量比高于/低于该值视为放量/缩量
+const ACTIVE_VOLUME_RATIO: f64 = 1.3;
+const QUIET_VOLUME_RATIO: f64 = 0.7;
+
+fn cache() -> &'static Mutex<Option<MarketRegime>> {
+    static CACHE: OnceLock<Mutex<Option<MarketRegime>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(None))
+}
+
+fn average_close(klines: &[KlineItem], n: usize) -> f64 {
+    let window = &klines[klines.len() - n..];
+    window.iter().map(|k| k.close).sum::<f64>() / n as f64
+}
+
+/// 近 n 日涨幅 %，日线不足时为 None
+fn return_pct(klines: &[KlineItem], n: usize) -> Option<f64> {
+    let last = klines.last()?;
+    let base = klines.get(klines.len().checked_sub(n + 1)?)?;
+    (base.close > 0.0).then(|| (last.close / base.close - 1.0) * 100.0)
+}
+
+/// 按日期合并沪深两市成交量，最新一日相对前 20 日均量的倍数
+fn volume_ratio(sh: &[KlineItem], sz: &[KlineItem]) -> Option<f64> {
+    let sz_volume: HashMap<&str, f64> = sz.iter().map(|k| (k.date.as_str(), k.volume)).collect();
+    let combined: Vec<f64> = sh
+        .iter()
+        .filter_map(|k| sz_volume.get(k.date.as_str()).map(|v| k.volume + v))
+        .collect();
+    if combined.len() < 21 {
+        return None;
+    }
+    let (history, last) = combined.split_at(combined.len() - 1);
+    let avg = history[history.len() - 20..].iter().sum::<f64>() / 20.0;
+    (avg > 0.0).then(|| last[0] / avg)
+}
+
+fn trend_label(trend: &RegimeTrend) -> &'static str {
+    match trend {
+        RegimeTrend::Up => "上升趋势",
+        RegimeTrend::Down => "下跌趋势",
+        RegimeTrend::Range => "震荡市",
+    }
+}
+
+fn volatility_label(volatility: &RegimeVolatility) -> &'static str {
+    match volatility {
+        RegimeVolatility::High => "高波动",
+        RegimeVolatility::Normal => "波动正常",
+        RegimeVolatility::Low => "低波动",
+    }
+}
+
+fn style_label(style: &RegimeStyle) -> &'static str {
+    match style {
+        RegimeStyle::LargeValue => "大盘价值占优",
+        RegimeStyle::SmallGrowth => "小盘成长占优",
+        RegimeStyle::Balanced => "大小盘均衡",
+    }
+}
+
+/// 由指数日线（按日期升序）与最近一次市场宽度判定市场状态，沪深300日线不足 61 条时返回 None。
+/// 趋势：收盘 > MA20 > MA60 且 20 日上涨为上升，反之为下跌，其余为震荡；宽度与指数背离时降级为震荡
+pub fn classify(
+    csi300: &[KlineItem],
+    large_cap: &[KlineItem],
+    small_cap: &[KlineItem],
+    sh_volume: &[KlineItem],
+    sz_volume: &[KlineItem],
+    breadth: Option<&MarketBreadth>,
+) -> Option<MarketRegime> {
+    if csi300.len() < MIN_TREND_BARS {
+        return None;
+    }
+    let last = csi300.last()?;
+    let ma20 = average_close(csi300, 20);
+    let ma60 = average_close(csi300, 60);
+    let return_20d = return_pct(csi300, 20)?;
+    let above_ma20_pct = breadth.and_then(|b| b.above_ma20_pct);
+
+    let mut trend = if last.close > ma20 && ma20 > ma60 && return_20d > 0.0 {
+        RegimeTrend::Up
+    } else if last.close < ma20 && ma20 < ma60 && return_20d < 0.0 {
+        RegimeTrend::Down
+    } else {
+        RegimeTrend::Range
+    };
+    match (&trend, above_ma20_pct) {
+        (RegimeTrend::Up, Some(pct)) if pct < BREADTH_WEAK => trend = RegimeTrend::Range,
+        (RegimeTrend::Down, Some(pct)) if pct > BREADTH_STRONG => trend = RegimeTrend::Range,
+        _ => {}
+    }
+
+    let year = &csi300[csi300.len().saturating_sub(YEAR_BARS + 1)..];
+    let returns = risk_metrics::daily_returns(year);
+    let volatility_20d = risk_metrics::volatility(&returns, 20)?;
+    let volatility_1y = risk_metrics::volatility(&returns, returns.len())?;
+    let volatility = if volatility_20d >= volatility_1y * HIGH_VOL_RATIO {
+        RegimeVolatility::High
+    } else if volatility_20d <= volatility_1y * LOW_VOL_RATIO {
+        RegimeVolatility::Low
+    } else {
+        RegimeVolatility::Normal
+    };
+
+    let large_cap_return_20d = return_pct(large_cap, 20).unwrap_or(0.0);
+    let small_cap_return_20d = return_pct(small_cap, 20).unwrap_or(0.0);
+    let spread = large_cap_return_20d - small_cap_return_20d;
+    let style = if spread >= STYLE_SPREAD {
+        RegimeStyle::LargeValue
+    } else if spread <= -STYLE_SPREAD {
+        RegimeStyle::SmallGrowth
+    } else {
+        RegimeStyle::Balanced
+    };
+
+    let volume_ratio = volume_ratio(sh_volume, sz_volume);
+    let mut summary = format!("{} · {} · {}", trend_label(&trend), volatility_label(&volatility), style_label(&style));
+    match volume_ratio {
+        Some(r) if r >= ACTIVE_VOLUME_RATIO => summary.push_str(" · 放量"),
+        Some(r) if r <= QUIET_VOLUME_RATIO => summary.push_str(" · 缩量"),
+        _ => {}
+    }
+
+    Some(MarketRegime {
+        date: last.date.clone(),
+        trend,
+        volatility,
+        style,
+        index_close: last.close,
+        ma20,
+        ma60,
+        return_20d,
+        volatility_20d,
+        volatility_1y,
+        large_cap_return_20d,
+        small_cap_return_20d,
+        above_ma20_pct,
+        volume_ratio,
+        summary,
+        created_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+    })
+}
+
+/// 选股 Agent 系统提示词中的市场状态段落
+pub fn to_prompt(regime: &MarketRegime) -> String {
+    let mut lines = vec![
+        format!("【当前市场状态】{}（截至 {}）", regime.summary, regime.date),
+        format!(
+            "- 沪深300 收盘 {:.2}，MA20 {:.2}，MA60 {:.2}，近20日 {:+.2}%",
+            regime.index_close, regime.ma20, regime.ma60, regime.return_20d
+        ),
+        format!(
+            "- 20日年化波动率 {:.1}%（近一年 {:.1}%）",
+            regime.volatility_20d, regime.volatility_1y
+        ),
+        format!(
+            "- 近20日 上证50 {:+.2}%，中证1000 {:+.2}%",
+            regime.large_cap_return_20d, regime.small_cap_return_20d
+        ),
+    ];
+    if let Some(pct) = regime.above_ma20_pct {
+        lines.push(format!("- 沪深300成分股站上 MA20 比例 {:.0}%", pct));
+    }
+    if let Some(ratio) = regime.volume_ratio {
+        lines.push(format!("- 两市成交量为 20 日均量的 {:.2} 倍", ratio));
+    }
+    lines.push("请结合市场状态调整选股方向与仓位建议：下跌或高波动时侧重防御与控制仓位，风格占优一侧优先。".to_string());
+    lines.join("\n")
+}
+
+/// 当前市场状态（按交易日内存缓存，当日首次调用时计算）
+pub async fn current(db: &Database) -> Result<MarketRegime> {
+    let today = Local::now().format("%Y-%m-%d").to_string();
+    if let Some(cached) = cache().lock().unwrap().as_ref() {
+        if cached.created_at.starts_with(&today) {
+            return Ok(cached.clone());
+        }
+    }
+
+    let csi300 = risk_metrics::benchmark_klines().await?;
+    let service = HistoryKlineService::new()?;
+    let (large_cap, small_cap, sh_volume, sz_volume) = tokio::join!(
+        service.fetch_recent(LARGE_CAP_INDEX, "day", RECENT_BARS),
+        service.fetch_recent(SMALL_CAP_INDEX, "day", RECENT_BARS),
+        service.fetch_recent(VOLUME_INDEXES[0], "day", RECENT_BARS),
+        service.fetch_recent(VOLUME_INDEXES[1], "day", RECENT_BARS),
+    );
+    let or_empty = |res: Result<Vec<KlineItem>>, code: &str| {
+        res.unwrap_or_else(|e| {
+            log::warn!("[market_regime] fetch {} kline failed: {}", code, e);
+            vec![]
+        })
+    };
+    let large_cap = or_empty(large_cap, LARGE_CAP_INDEX);
+    let small_cap = or_empty(small_cap, SMALL_CAP_INDEX);
+    let sh_volume = or_empty(sh_volume, VOLUME_INDEXES[0]);
+    let sz_volume = or_empty(sz_volume, VOLUME_INDEXES[1]);
+    let breadth = db.get_market_breadth_history(1)?.pop();
+
+    let regime = classify(&csi300, &large_cap, &small_cap, &sh_volume, &sz_volume, breadth.as_ref())
+        .ok_or_else(|| anyhow!("沪深300日线不足，无法判定市场状态"))?;
+    log::info!("[market_regime] current date={} summary={}", regime.date, regime.summary);
+    *cache().lock().unwrap() = Some(regime.clone());
+    Ok(regime)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series(closes: &[f64]) -> Vec<KlineItem> {
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, c)| KlineItem {
+                date: format!("d{:03}", i),
+                open: *c,
+                close: *c,
+                high: *c,
+                low: *c,
+                volume: 1000.0,
+                amount: 0.0,
+                change_pct: 0.0,
+                turnover_rate: 0.0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_classify() {
+        // 平稳上行、后期波动收窄
+        let closes: Vec<f64> = (0..120)
+            .map(|i| 100.0 + i as f64 * 0.3 + if i < 90 { (i % 2) as f64 * 2.0 } else { (i % 2) as f64 * 0.2 })
+            .collect();
+        let csi300 = series(&closes);
+        let large = series(&(0..30).map(|i| 100.0 + i as f64 * 0.05).collect::<Vec<_>>());
+        let small = series(&(0..30).map(|i| 100.0 + i as f64 * 0.5).collect::<Vec<_>>());
+        let mut sh = csi300.clone();
+        sh.last_mut().unwrap().volume = 3000.0;
+        let regime = classify(&csi300, &large, &small, &sh, &csi300, None).unwrap();
+        assert_eq!(regime.trend, RegimeTrend::Up);
+        assert_eq!(regime.volatility, RegimeVolatility::Low);
+        assert_eq!(regime.style, RegimeStyle::SmallGrowth);
+        assert_eq!(regime.volume_ratio, Some(2.0));
+        assert_eq!(regime.summary, "上升趋势 · 低波动 · 小盘成长占优 · 放量");
+
+        // 宽度不足 40% 时上涨降级为震荡
+        let weak = MarketBreadth { above_ma20_pct: Some(30.0), ..Default::default() };
+        let regime = classify(&csi300, &large, &small, &[], &[], Some(&weak)).unwrap();
+        assert_eq!(regime.trend, RegimeTrend::Range);
+        assert_eq!(regime.volume_ratio, None);
+
+        assert!(classify(&csi300[..50], &large, &small, &[], &[], None).is_none());
+    }
+}
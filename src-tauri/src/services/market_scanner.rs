@@ -1,6 +1,10 @@
 use anyhow::{Result, anyhow};
-use crate::models::stock::MarketStockSnapshot;
-use crate::utils::http::build_stock_client;
+use crate::models::stock::{BoardQuote, FundFlowBreakdown, FundFlowDay, IntradayFlowPoint, MarketStockCount, MarketStockSnapshot, TickPrint};
+use crate::models::watchlist::KlineItem;
+use crate::services::schema_guard;
+use crate::utils::encoding::{clean_text, response_text};
+use crate::utils::http::{build_stock_client, SendLogged};
+use crate::utils::units;
 
 /// 全市场扫描器：通过东方财富 API 获取沪深A股全量多维度数据
 /// 当东财接口不可用时（非交易时间/限流），自动 fallback 到腾讯行情接口
@@ -44,8 +48,185 @@ impl MarketScanner {
         Ok(all_stocks)
     }
 
+    /// 拉取某个行业/概念板块（如 BK0477）的全部成分股快照
+    pub async fn fetch_board_stocks(&self, board_code: &str) -> Result<Vec<MarketStockSnapshot>> {
+        let board_code = board_code.trim().to_uppercase();
+        if !board_code.starts_with("BK") {
+            return Err(anyhow!("无效的板块代码: {}", board_code));
+        }
+        let fs = format!("b:{}", board_code);
+        let mut all_stocks = Vec::new();
+        let mut page = 1;
+        loop {
+            let stocks = self.fetch_clist_page(&fs, page).await?;
+            let count = stocks.len();
+            all_stocks.extend(stocks);
+            if count < 5000 {
+                break;
+            }
+            page += 1;
+        }
+        Ok(all_stocks)
+    }
+
+    /// 查询个股所属的东财行业板块，返回 (板块代码, 板块名称)
+    /// 优先使用个股行情的 f198 字段，缺失时按行业名称 (f127) 在行业板块列表中匹配
+    pub async fn fetch_stock_industry(&self, code: &str) -> Result<Option<(String, String)>> {
+        let url = format!(
+            "https://push2.eastmoney.com/api/qt/stock/get?fltt=2&invt=2&fields=f57,f127,f198&secid={}",
+            code_to_secid(code)
+        );
+        let json: serde_json::Value = self.client.get(&url)
+            .header("Referer", "https://quote.eastmoney.com/")
+            .send_logged().await?
+            .json().await?;
+        let data = &json["data"];
+        let industry = data["f127"].as_str().unwrap_or("").trim().to_string();
+        if let Some(board) = data["f198"].as_str().filter(|b| b.starts_with("BK")) {
+            return Ok(Some((board.to_string(), industry)));
+        }
+        if industry.is_empty() || industry == "-" {
+            return Ok(None);
+        }
+
+        let url = "https://push2.eastmoney.com/api/qt/clist/get?pn=1&pz=1000&po=1&np=1&fltt=2&invt=2&fid=f3&fs=m:90+t:2&fields=f12,f14";
+        let json: serde_json::Value = self.client.get(url)
+            .header("Referer", "https://quote.eastmoney.com/")
+            .send_logged().await?
+            .json().await?;
+        let board = json["data"]["diff"].as_array().and_then(|boards| {
+            boards.iter().find(|b| b["f14"].as_str() == Some(industry.as_str()))
+        });
+        Ok(board.and_then(|b| b["f12"].as_str()).map(|c| (c.to_string(), industry)))
+    }
+
+    /// 拉取全部行业（kind = "industry"）或概念（kind = "concept"）板块行情，按涨跌幅降序
+    /// 字段映射：f3=涨跌幅, f6=成交额, f8=换手率, f12=板块代码, f14=名称, f20=总市值, f62=主力净流入,
+    ///   f104=上涨家数, f105=下跌家数, f128=领涨股名称, f136=领涨股涨跌幅, f140=领涨股代码, f141=领涨股市场(0深1沪)
+    pub async fn fetch_boards(&self, kind: &str) -> Result<Vec<BoardQuote>> {
+        let fs = match kind {
+            "industry" => "m:90+t:2",
+            "concept" => "m:90+t:3",
+            _ => return Err(anyhow!("未知的板块类型: {}", kind)),
+        };
+        let url = format!(
+            "https://push2.eastmoney.com/api/qt/clist/get?pn=1&pz=1000&po=1&np=1&fltt=2&invt=2&fid=f3&fs={}&fields=f3,f6,f8,f12,f14,f20,f62,f104,f105,f128,f136,f140,f141",
+            fs
+        );
+        let json: serde_json::Value = self.client.get(&url)
+            .header("Referer", "https://quote.eastmoney.com/")
+            .send_logged().await?
+            .json().await?;
+        let items = match json["data"]["diff"].as_array() {
+            Some(arr) => arr,
+            None => return Ok(vec![]),
+        };
+        let boards = items.iter().filter_map(|item| {
+            let leader_code = item["f140"].as_str().unwrap_or("");
+            let leader_prefix = if item["f141"].as_i64() == Some(1) { "sh" } else { "sz" };
+            Some(BoardQuote {
+                code: item["f12"].as_str()?.to_string(),
+                name: item["f14"].as_str()?.to_string(),
+                kind: kind.to_string(),
+                change_pct: get_f64(item, "f3"),
+                turnover_rate: get_f64(item, "f8"),
+                amount: get_f64(item, "f6"),
+                total_market_cap: get_f64(item, "f20"),
+                main_net_inflow: get_f64(item, "f62"),
+                up_count: get_f64(item, "f104") as u32,
+                down_count: get_f64(item, "f105") as u32,
+                leader_code: if leader_code.is_empty() { String::new() } else { format!("{}{}", leader_prefix, leader_code) },
+                leader_name: item["f128"].as_str().unwrap_or("").to_string(),
+                leader_change_pct: get_f64(item, "f136"),
+            })
+        }).collect();
+        Ok(boards)
+    }
+
+    /// 个股所属的全部板块（行业、概念、地域混合，东财 slist spt=3），按今日涨跌幅降序；kind 留空
+    pub async fn fetch_stock_boards(&self, code: &str) -> Result<Vec<BoardQuote>> {
+        let url = format!(
+            "https://push2.eastmoney.com/api/qt/slist/get?spt=3&pi=0&pn=1&pz=100&po=1&fid=f3&fltt=2&invt=2&fields=f3,f12,f14,f62&secid={}",
+            code_to_secid(code)
+        );
+        let json: serde_json::Value = self.client.get(&url)
+            .header("Referer", "https://quote.eastmoney.com/")
+            .send_logged().await?
+            .json().await?;
+        let boards = json["data"]["diff"]
+            .as_array()
+            .map(|items| {
+                items.iter().filter_map(|item| {
+                    Some(BoardQuote {
+                        code: item["f12"].as_str()?.to_string(),
+                        name: item["f14"].as_str()?.to_string(),
+                        change_pct: get_f64(item, "f3"),
+                        main_net_inflow: get_f64(item, "f62"),
+                        ..Default::default()
+                    })
+                }).collect()
+            })
+            .unwrap_or_default();
+        Ok(boards)
+    }
+
+    /// 统计全部A股（含科创板、北交所）的板块分布与停牌/ST/本月新股数量
+    /// 停牌股在行情快照中会被过滤，因此这里单独拉取精简字段的原始列表
+    pub async fn fetch_market_stock_count(&self) -> Result<MarketStockCount> {
+        let fs = "m:0+t:6,m:0+t:80,m:1+t:2,m:1+t:23,m:0+t:81+s:2048";
+        let this_month = chrono::Local::now().format("%Y%m").to_string();
+        let mut count = MarketStockCount::default();
+        let mut page = 1;
+        loop {
+            let url = format!(
+                "https://push2.eastmoney.com/api/qt/clist/get?pn={}&pz=5000&po=1&np=1&ut=bd1d9ddb04089700cf9c27f6f7426281&fltt=2&invt=2&fid=f12&fs={}&fields=f2,f12,f13,f14,f26",
+                page, fs
+            );
+            let json: serde_json::Value = self.client.get(&url)
+                .header("Referer", "https://quote.eastmoney.com/")
+                .send_logged().await?
+                .json().await?;
+            let items = json["data"]["diff"].as_array().cloned().unwrap_or_default();
+            for item in &items {
+                let code = item["f12"].as_str().unwrap_or("");
+                let name = item["f14"].as_str().unwrap_or("");
+                if code.is_empty() {
+                    continue;
+                }
+                count.total += 1;
+                match board_of(code) {
+                    "star" => count.star += 1,
+                    "chinext" => count.chinext += 1,
+                    "bse" => count.bse += 1,
+                    _ => count.main_board += 1,
+                }
+                if !item["f2"].is_number() {
+                    count.suspended += 1;
+                }
+                if name.contains("ST") {
+                    count.st += 1;
+                }
+                let list_date = item["f26"].as_str().map(|s| s.to_string())
+                    .or_else(|| item["f26"].as_i64().map(|n| n.to_string()))
+                    .unwrap_or_default();
+                if list_date.starts_with(&this_month) {
+                    count.new_this_month += 1;
+                }
+            }
+            if items.len() < 5000 {
+                break;
+            }
+            page += 1;
+        }
+        count.updated_at = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        Ok(count)
+    }
+
     async fn fetch_page(&self, page: u32) -> Result<Vec<MarketStockSnapshot>> {
-        let fs = "m:0+t:6,m:0+t:80,m:1+t:2";
+        self.fetch_clist_page("m:0+t:6,m:0+t:80,m:1+t:2", page).await
+    }
+
+    async fn fetch_clist_page(&self, fs: &str, page: u32) -> Result<Vec<MarketStockSnapshot>> {
         let fields = "f2,f3,f4,f5,f6,f7,f8,f9,f10,f12,f13,f14,f15,f16,f17,f18,f20,f21,f23,f24,f25,f26,f37,f115,f62";
 
         let url = format!(
@@ -55,7 +236,7 @@ impl MarketScanner {
 
         let text = match self.client.get(&url)
             .header("Referer", "https://quote.eastmoney.com/")
-            .send().await
+            .send_logged().await
         {
             Ok(resp) => resp.text().await.unwrap_or_default(),
             Err(e) => {
@@ -82,6 +263,9 @@ impl MarketScanner {
             Some(arr) => arr,
             None => return Ok(vec![]),
         };
+        if items.first().is_some_and(|first| !schema_guard::check_keys(schema_guard::EASTMONEY_QUOTE, first, schema_guard::EASTMONEY_QUOTE_KEYS)) {
+            return Err(anyhow!("东财行情字段缺失，数据源格式可能已变更"));
+        }
 
         let mut stocks = Vec::with_capacity(items.len());
         for item in items {
@@ -111,8 +295,45 @@ impl MarketScanner {
         self.fetch_stocks_by_codes_tencent(codes).await
     }
 
+    /// 按代码获取快照并补充股息率、现金流收益率、资产负债率、净利润增速；补充字段失败时保留基础快照
+    pub async fn fetch_stocks_with_fundamentals(&self, codes: &[String]) -> Result<Vec<MarketStockSnapshot>> {
+        let mut stocks = self.fetch_stocks_by_codes(codes).await?;
+        if let Err(e) = self.enrich_fundamentals(&mut stocks).await {
+            log::warn!("[market_scanner] enrich_fundamentals failed: {}", e);
+        }
+        Ok(stocks)
+    }
+
+    /// 补充财务字段（单独请求，避免全市场扫描的响应过大），每批 100 只：
+    ///   f46=净利润同比增长, f57=资产负债率, f131=市现率TTM, f133=股息率TTM
+    pub async fn enrich_fundamentals(&self, stocks: &mut [MarketStockSnapshot]) -> Result<()> {
+        for chunk in stocks.chunks_mut(100) {
+            let secids: Vec<String> = chunk.iter().map(|s| code_to_secid(&s.code)).collect();
+            let url = format!(
+                "https://push2.eastmoney.com/api/qt/ulist.np/get?fltt=2&invt=2&fields=f12,f13,f46,f57,f131,f133&secids={}",
+                secids.join(",")
+            );
+            let text = self.client.get(&url)
+                .header("Referer", "https://quote.eastmoney.com/")
+                .send_logged().await?
+                .text().await?;
+            let json: serde_json::Value = serde_json::from_str(&text)
+                .map_err(|e| anyhow!("东财财务字段解析失败: {}", e))?;
+            let items = json["data"]["diff"].as_array().cloned().unwrap_or_default();
+            for item in &items {
+                let code_num = item["f12"].as_str().unwrap_or("");
+                let prefix = if item["f13"].as_i64() == Some(1) { "sh" } else { "sz" };
+                let code = format!("{}{}", prefix, code_num);
+                if let Some(stock) = chunk.iter_mut().find(|s| s.code == code) {
+                    apply_fundamentals(stock, item);
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// 东财 ulist.np 接口
-    async fn fetch_stocks_by_codes_eastmoney(&self, codes: &[String]) -> Result<Vec<MarketStockSnapshot>> {
+    pub async fn fetch_stocks_by_codes_eastmoney(&self, codes: &[String]) -> Result<Vec<MarketStockSnapshot>> {
         let secids: Vec<String> = codes.iter().map(|c| code_to_secid(c)).collect();
         let secid_str = secids.join(",");
         let fields = "f2,f3,f4,f5,f6,f7,f8,f9,f10,f12,f13,f14,f15,f16,f17,f18,f20,f21,f23,f24,f25,f26,f37,f115,f62";
@@ -124,7 +345,7 @@ impl MarketScanner {
 
         let resp = self.client.get(&url)
             .header("Referer", "https://quote.eastmoney.com/")
-            .send().await?;
+            .send_logged().await?;
         let text = resp.text().await?;
         if text.is_empty() {
             return Ok(vec![]);
@@ -136,6 +357,9 @@ impl MarketScanner {
         if let Some(data) = json.get("data") {
             if let Some(diff) = data.get("diff") {
                 if let Some(items) = diff.as_array() {
+                    if items.first().is_some_and(|first| !schema_guard::check_keys(schema_guard::EASTMONEY_QUOTE, first, schema_guard::EASTMONEY_QUOTE_KEYS)) {
+                        return Err(anyhow!("东财行情字段缺失，数据源格式可能已变更"));
+                    }
                     for item in items {
                         if let Some(stock) = parse_eastmoney_item(item) {
                             stocks.push(stock);
@@ -160,11 +384,9 @@ impl MarketScanner {
 
             let resp = self.client.get(&url)
                 .header("Referer", "https://finance.qq.com/")
-                .send().await?;
-            // 腾讯接口返回 GBK 编码，reqwest 默认按 UTF-8 读取会乱码
-            // 但数值字段不受影响，名称可能乱码
-            let bytes = resp.bytes().await?;
-            let (text, _, _) = encoding_rs::GBK.decode(&bytes);
+                .send_logged().await?;
+            // 腾讯接口返回 GBK 编码
+            let text = response_text(resp).await?;
 
             for line in text.lines() {
                 if let Some(stock) = parse_tencent_quote(line) {
@@ -175,9 +397,9 @@ impl MarketScanner {
         Ok(all_stocks)
     }
 
-    /// 拉取个股资金流向数据（主力净流入），合并到快照中
+    /// 拉取个股当日资金流向（主力净流入与超大单/大单/中单/小单净额）
     /// 东财失败时优雅降级，返回空列表（腾讯无资金流向数据）
-    pub async fn fetch_fund_flow(&self, codes: &[String]) -> Result<Vec<(String, f64, f64)>> {
+    pub async fn fetch_fund_flow(&self, codes: &[String]) -> Result<Vec<FundFlowBreakdown>> {
         if codes.is_empty() {
             return Ok(vec![]);
         }
@@ -186,13 +408,13 @@ impl MarketScanner {
         let secid_str = secids.join(",");
 
         let url = format!(
-            "https://push2.eastmoney.com/api/qt/ulist.np/get?fltt=2&invt=2&fields=f3,f12,f13,f62,f184&secids={}",
+            "https://push2.eastmoney.com/api/qt/ulist.np/get?fltt=2&invt=2&fields=f3,f12,f13,f62,f66,f72,f78,f84,f184&secids={}",
             secid_str
         );
 
         let text = match self.client.get(&url)
             .header("Referer", "https://quote.eastmoney.com/")
-            .send().await
+            .send_logged().await
         {
             Ok(resp) => resp.text().await.unwrap_or_default(),
             Err(e) => {
@@ -218,13 +440,24 @@ impl MarketScanner {
         if let Some(data) = json.get("data") {
             if let Some(diff) = data.get("diff") {
                 if let Some(items) = diff.as_array() {
+                    if items.first().is_some_and(|first| !schema_guard::check_keys(schema_guard::EASTMONEY_FUND_FLOW, first, schema_guard::EASTMONEY_FUND_FLOW_KEYS)) {
+                        log::warn!("东财资金流向字段缺失，数据源格式可能已变更，跳过");
+                        return Ok(vec![]);
+                    }
                     for item in items {
                         let code_num = item.get("f12").and_then(|v| v.as_str()).unwrap_or("");
                         let market = item.get("f13").and_then(|v| v.as_i64()).unwrap_or(0);
-                        let net_inflow = item.get("f62").and_then(|v| v.as_f64()).unwrap_or(0.0);
-                        let net_pct = item.get("f184").and_then(|v| v.as_f64()).unwrap_or(0.0);
                         let prefix = if market == 1 { "sh" } else { "sz" };
-                        results.push((format!("{}{}", prefix, code_num), net_inflow, net_pct));
+                        results.push(FundFlowBreakdown {
+                            code: format!("{}{}", prefix, code_num),
+                            main_net: get_f64(item, "f62"),
+                            main_pct: get_f64(item, "f184"),
+                            super_large_net: get_f64(item, "f66"),
+                            large_net: get_f64(item, "f72"),
+                            medium_net: get_f64(item, "f78"),
+                            small_net: get_f64(item, "f84"),
+                            ..Default::default()
+                        });
                     }
                 }
             }
@@ -232,6 +465,178 @@ impl MarketScanner {
 
         Ok(results)
     }
+
+    /// 拉取个股当日盘中按分钟累计的资金净流入（非交易日返回最近一个交易日）
+    pub async fn fetch_intraday_fund_flow(&self, code: &str) -> Result<Vec<IntradayFlowPoint>> {
+        let url = format!(
+            "https://push2.eastmoney.com/api/qt/stock/fflow/kline/get?lmt=0&klt=1&fields1=f1,f2,f3,f7&fields2=f51,f52,f53,f54,f55,f56&secid={}",
+            code_to_secid(code)
+        );
+        let json: serde_json::Value = self.client.get(&url)
+            .header("Referer", "https://data.eastmoney.com/")
+            .send_logged().await?
+            .json().await?;
+        Ok(json["data"]["klines"]
+            .as_array()
+            .map(|lines| lines.iter().filter_map(|l| parse_intraday_flow_line(l.as_str()?)).collect())
+            .unwrap_or_default())
+    }
+
+    /// 拉取个股最近 days 个交易日的日级资金流向（按日期正序），东财最多返回约 120 日
+    pub async fn fetch_fund_flow_history(&self, code: &str, days: u32) -> Result<Vec<FundFlowDay>> {
+        let url = format!(
+            "https://push2his.eastmoney.com/api/qt/stock/fflow/daykline/get?lmt={}&klt=101&fields1=f1,f2,f3,f7&fields2=f51,f52,f53,f54,f55,f56,f57,f58,f59,f60,f61,f62,f63&secid={}",
+            days, code_to_secid(code)
+        );
+        let json: serde_json::Value = self.client.get(&url)
+            .header("Referer", "https://data.eastmoney.com/")
+            .send_logged().await?
+            .json().await?;
+        let flows: Vec<FundFlowDay> = json["data"]["klines"]
+            .as_array()
+            .map(|lines| lines.iter().filter_map(|l| parse_fund_flow_line(code, l.as_str()?)).collect())
+            .unwrap_or_default();
+        if flows.is_empty() {
+            return Err(anyhow!("未获取到 {} 的历史资金流向", code));
+        }
+        Ok(flows)
+    }
+
+    /// 拉取个股最近一个交易日的分钟走势（含尾盘集合竞价；科创板/创业板含盘后固定价格交易），date 为 "YYYY-MM-DD HH:MM"
+    pub async fn fetch_trends(&self, code: &str) -> Result<Vec<KlineItem>> {
+        let url = format!(
+            "https://push2.eastmoney.com/api/qt/stock/trends2/get?fields1=f1,f2,f3,f4,f5,f6,f7,f8&fields2=f51,f52,f53,f54,f55,f56,f57,f58&iscr=0&iscca=1&ndays=1&secid={}",
+            code_to_secid(code)
+        );
+        let json: serde_json::Value = self.client.get(&url)
+            .header("Referer", "https://quote.eastmoney.com/")
+            .send_logged().await?
+            .json().await?;
+        Ok(json["data"]["trends"]
+            .as_array()
+            .map(|lines| lines.iter().filter_map(|l| parse_trend_line(l.as_str()?)).collect())
+            .unwrap_or_default())
+    }
+
+    /// 拉取个股当日最近 count 笔逐笔成交（按时间正序）
+    pub async fn fetch_ticks(&self, code: &str, count: u32) -> Result<Vec<TickPrint>> {
+        let url = format!(
+            "https://push2.eastmoney.com/api/qt/stock/details/get?fields1=f1,f2,f3,f4&fields2=f51,f52,f53,f54,f55&pos=-{}&secid={}",
+            count, code_to_secid(code)
+        );
+        let json: serde_json::Value = self.client.get(&url)
+            .header("Referer", "https://quote.eastmoney.com/")
+            .send_logged().await?
+            .json().await?;
+        Ok(json["data"]["details"]
+            .as_array()
+            .map(|lines| lines.iter().filter_map(|l| parse_tick_line(code, l.as_str()?)).collect())
+            .unwrap_or_default())
+    }
+}
+
+/// 解析东财日级资金流：日期,主力,小单,中单,大单,超大单,主力占比,小单占比,中单占比,大单占比,超大单占比,收盘价,涨跌幅
+fn parse_fund_flow_line(code: &str, line: &str) -> Option<FundFlowDay> {
+    let parts: Vec<&str> = line.split(',').collect();
+    if parts.len() < 13 {
+        return None;
+    }
+    let num = |i: usize| parts[i].parse::<f64>().ok();
+    Some(FundFlowDay {
+        code: code.to_string(),
+        date: parts[0].to_string(),
+        main_net: num(1)?,
+        super_large_net: num(5),
+        large_net: num(4),
+        medium_net: num(3),
+        small_net: num(2),
+        main_pct: num(6).unwrap_or(0.0),
+        close: num(11).unwrap_or(0.0),
+        change_pct: num(12).unwrap_or(0.0),
+    })
+}
+
+/// 解析东财分钟级资金流：时间,主力,小单,中单,大单,超大单（当日累计净额）
+fn parse_intraday_flow_line(line: &str) -> Option<IntradayFlowPoint> {
+    let parts: Vec<&str> = line.split(',').collect();
+    if parts.len() < 6 {
+        return None;
+    }
+    let num = |i: usize| parts[i].parse::<f64>().ok();
+    let time = parts[0].rsplit(' ').next()?;
+    Some(IntradayFlowPoint {
+        time: time.get(..5).unwrap_or(time).to_string(),
+        main_net: num(1)?,
+        small_net: num(2)?,
+        medium_net: num(3)?,
+        large_net: num(4)?,
+        super_large_net: num(5)?,
+    })
+}
+
+/// 解析东财分钟走势：时间,开盘,收盘,最高,最低,成交量(手),成交额,均价
+fn parse_trend_line(line: &str) -> Option<KlineItem> {
+    let parts: Vec<&str> = line.split(',').collect();
+    if parts.len() < 7 {
+        return None;
+    }
+    let num = |i: usize| parts[i].parse::<f64>().ok();
+    Some(KlineItem {
+        date: parts[0].to_string(),
+        open: num(1)?,
+        close: num(2)?,
+        high: num(3)?,
+        low: num(4)?,
+        volume: num(5)?,
+        amount: num(6)?,
+        change_pct: 0.0,
+        turnover_rate: 0.0,
+    })
+}
+
+/// 解析东财逐笔成交：时间,价格,成交量(手),笔数,方向(1 主动卖 / 2 主动买 / 4 中性)
+fn parse_tick_line(code: &str, line: &str) -> Option<TickPrint> {
+    let parts: Vec<&str> = line.split(',').collect();
+    if parts.len() < 5 {
+        return None;
+    }
+    let price = parts[1].parse::<f64>().ok()?;
+    let volume = parts[2].parse::<f64>().ok()?;
+    let side = match parts[4] {
+        "1" => "sell",
+        "2" => "buy",
+        _ => "neutral",
+    };
+    Some(TickPrint {
+        code: code.to_string(),
+        time: parts[0].to_string(),
+        price,
+        volume,
+        amount: units::lot_amount(price, volume),
+        side: side.to_string(),
+    })
+}
+
+/// 按6位代码判断所属板块：star / chinext / bse / main
+fn board_of(code: &str) -> &'static str {
+    match code.get(..3).unwrap_or("") {
+        "688" | "689" => "star",
+        "300" | "301" | "302" => "chinext",
+        p if p.starts_with('4') || p.starts_with('8') || p == "920" => "bse",
+        _ => "main",
+    }
+}
+
+/// 涨跌停幅度（%）：北交所 30，创业板/科创板 20，ST 5，其余 10。code 可带 sh/sz/bj 前缀
+pub fn limit_pct(code: &str, name: &str) -> f64 {
+    let digits = code.trim_start_matches(|c: char| c.is_ascii_alphabetic());
+    match board_of(digits) {
+        _ if code.starts_with("bj") => 30.0,
+        "bse" => 30.0,
+        "star" | "chinext" => 20.0,
+        _ if name.to_uppercase().contains("ST") => 5.0,
+        _ => 10.0,
+    }
 }
 
 fn parse_eastmoney_item(item: &serde_json::Value) -> Option<MarketStockSnapshot> {
@@ -285,9 +690,18 @@ pub fn parse_eastmoney_item_public(item: &serde_json::Value) -> Option<MarketSto
             .and_then(|v| v.as_str())
             .unwrap_or("-")
             .to_string(),     // 上市日期 "YYYYMMDD" 或 "-"
+        ..Default::default()  // 股息率等补充财务字段见 enrich_fundamentals
     })
 }
 
+fn apply_fundamentals(stock: &mut MarketStockSnapshot, item: &serde_json::Value) {
+    stock.profit_yoy = get_f64(item, "f46");
+    stock.debt_ratio = get_f64(item, "f57");
+    let pcf = get_f64(item, "f131");
+    stock.fcf_yield = if pcf > 0.0 { 100.0 / pcf } else { 0.0 };
+    stock.dividend_yield = get_f64(item, "f133");
+}
+
 /// 解析腾讯行情接口返回的单行数据
 /// 格式: v_sz000002="51~万  科Ａ~000002~4.84~4.82~4.83~1132768~..."
 /// 字段以 ~ 分隔，索引含义：
@@ -321,7 +735,7 @@ fn parse_tencent_quote(line: &str) -> Option<MarketStockSnapshot> {
     let content = &line[start..end];
     let fields: Vec<&str> = content.split('~').collect();
 
-    if fields.len() < 50 {
+    if !schema_guard::check_field_count(schema_guard::TENCENT_QUOTE, fields.len(), 50) {
         return None;
     }
 
@@ -332,7 +746,7 @@ fn parse_tencent_quote(line: &str) -> Option<MarketStockSnapshot> {
     }
 
     let code = format!("{}{}", prefix, code_num);
-    let name = fields.get(1).unwrap_or(&"").to_string();
+    let name = clean_text(fields.get(1).unwrap_or(&""));
 
     let pre_close = parse_field(fields.get(4));
     let open = parse_field(fields.get(5));
@@ -341,11 +755,11 @@ fn parse_tencent_quote(line: &str) -> Option<MarketStockSnapshot> {
     let change_pct = parse_field(fields.get(33));
     let high = parse_field(fields.get(34));
     let low = parse_field(fields.get(35));
-    let amount = parse_field(fields.get(38)) * 10_000.0; // 万 → 元
+    let amount = units::wan_to_yuan(parse_field(fields.get(38)));
     let turnover_rate = parse_field(fields.get(39));
     let amplitude = parse_field(fields.get(44));
-    let total_market_cap = parse_field(fields.get(45)) * 100_000_000.0; // 亿 → 元
-    let float_market_cap = parse_field(fields.get(46)) * 100_000_000.0; // 亿 → 元
+    let total_market_cap = units::yi_to_yuan(parse_field(fields.get(45)));
+    let float_market_cap = units::yi_to_yuan(parse_field(fields.get(46)));
     let pe_ttm = parse_field(fields.get(47));
     let pb = parse_field(fields.get(49));
     let volume_ratio = parse_field(fields.get(50));
@@ -379,6 +793,7 @@ fn parse_tencent_quote(line: &str) -> Option<MarketStockSnapshot> {
         main_net_inflow: 0.0,  // 腾讯接口无此字段
         main_net_pct: 0.0,
         list_date: String::new(),
+        ..Default::default()
     })
 }
 
@@ -0,0 +1,193 @@
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager};
+
+use crate::AppState;
+use crate::services::stock_tools::{self, ToolContext};
+
+/// 未声明或不支持客户端版本时使用的协议版本
+const DEFAULT_PROTOCOL_VERSION: &str = "2025-03-26";
+const SUPPORTED_PROTOCOL_VERSIONS: [&str; 3] = ["2025-06-18", "2025-03-26", "2024-11-05"];
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// 单条 JSON-RPC 消息的处理结果
+#[derive(Debug, PartialEq)]
+pub enum Step {
+    /// 直接回复
+    Reply(Value),
+    /// 需要执行工具后回复
+    Call { id: Value, name: String, arguments: String },
+    /// 通知或客户端响应，无需回复
+    Ignore,
+}
+
+fn error(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+fn result(id: Value, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+/// 诊股与选股工具合并去重后转为 MCP 工具列表（OpenAI function 格式中的 parameters 即 inputSchema）
+pub fn to_mcp_tools(definitions: Vec<Value>) -> Vec<Value> {
+    let mut tools: Vec<Value> = Vec::new();
+    for def in definitions {
+        let function = &def["function"];
+        let Some(name) = function["name"].as_str() else { continue };
+        if tools.iter().any(|t| t["name"] == name) {
+            continue;
+        }
+        tools.push(json!({
+            "name": name,
+            "description": function["description"],
+            "inputSchema": function["parameters"],
+        }));
+    }
+    tools
+}
+
+pub fn tool_list() -> Vec<Value> {
+    let mut definitions = stock_tools::get_tool_definitions();
+    definitions.extend(stock_tools::get_pick_tool_definitions());
+    to_mcp_tools(definitions)
+}
+
+/// 处理单条 JSON-RPC 消息：initialize / ping / tools/list 直接回复，tools/call 交由调用方执行
+pub fn respond(message: &Value, tools: &[Value], server_version: &str) -> Step {
+    let Some(method) = message["method"].as_str() else {
+        // 客户端对服务端请求的响应，本服务不发起请求
+        return if message.get("result").is_some() || message.get("error").is_some() {
+            Step::Ignore
+        } else {
+            Step::Reply(error(message["id"].clone(), INVALID_REQUEST, "Invalid Request"))
+        };
+    };
+    let Some(id) = message.get("id").cloned() else {
+        return Step::Ignore;
+    };
+    let params = &message["params"];
+    match method {
+        "initialize" => {
+            let requested = params["protocolVersion"].as_str().unwrap_or_default();
+            let version = SUPPORTED_PROTOCOL_VERSIONS
+                .into_iter()
+                .find(|v| *v == requested)
+                .unwrap_or(DEFAULT_PROTOCOL_VERSION);
+            Step::Reply(result(id, json!({
+                "protocolVersion": version,
+                "capabilities": { "tools": { "listChanged": false } },
+                "serverInfo": { "name": "stock-helper", "version": server_version },
+                "instructions": "A 股行情、K线、技术指标、资金流向、新闻与选股工具。股票代码格式如 sh600519、sz000001。",
+            })))
+        }
+        "ping" => Step::Reply(result(id, json!({}))),
+        "tools/list" => Step::Reply(result(id, json!({ "tools": tools }))),
+        "tools/call" => {
+            let name = params["name"].as_str().unwrap_or_default();
+            if !tools.iter().any(|t| t["name"] == name) {
+                return Step::Reply(error(id, INVALID_PARAMS, &format!("未知工具: {}", name)));
+            }
+            let arguments = match &params["arguments"] {
+                Value::Null => "{}".to_string(),
+                args => args.to_string(),
+            };
+            Step::Call { id, name: name.to_string(), arguments }
+        }
+        _ => Step::Reply(error(id, METHOD_NOT_FOUND, &format!("Method not found: {}", method))),
+    }
+}
+
+async fn call_tool(id: Value, name: &str, arguments: &str, ctx: &ToolContext) -> Value {
+    log::info!("[mcp_server] tools/call name={}", name);
+    let (text, is_error) = match stock_tools::execute_pick_tool(name, arguments, ctx).await {
+        Ok(text) => (text, false),
+        Err(e) => {
+            log::warn!("[mcp_server] tool {} failed: {}", name, e);
+            (format!("工具执行失败: {}", e), true)
+        }
+    };
+    result(id, json!({ "content": [{ "type": "text", "text": text }], "isError": is_error }))
+}
+
+/// 处理 Streamable HTTP 传输的 POST 请求体（单条消息或批量数组），返回 (状态码, 响应正文)；
+/// 只包含通知时返回 202 且无正文
+pub async fn handle_http(app: &AppHandle, body: &[u8]) -> (u16, String) {
+    let message: Value = match serde_json::from_slice(body) {
+        Ok(v) => v,
+        Err(e) => return (400, error(Value::Null, PARSE_ERROR, &format!("Parse error: {}", e)).to_string()),
+    };
+    let (messages, batch) = match message {
+        Value::Array(items) => (items, true),
+        single => (vec![single], false),
+    };
+
+    let state = app.state::<AppState>();
+    let settings = state.db.load_settings().unwrap_or_default();
+    let mut ctx = ToolContext::from_settings(&settings);
+    ctx.load_market_history(&state.db);
+    let tools = tool_list();
+    let server_version = app.package_info().version.to_string();
+
+    let mut replies = Vec::new();
+    for message in &messages {
+        match respond(message, &tools, &server_version) {
+            Step::Reply(reply) => replies.push(reply),
+            Step::Call { id, name, arguments } => replies.push(call_tool(id, &name, &arguments, &ctx).await),
+            Step::Ignore => {}
+        }
+    }
+    match (replies.len(), batch) {
+        (0, _) => (202, String::new()),
+        (_, true) => (200, Value::Array(replies).to_string()),
+        _ => (200, replies.remove(0).to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn definition(name: &str) -> Value {
+        json!({
+            "type": "function",
+            "function": {
+                "name": name,
+                "description": "desc",
+                "parameters": { "type": "object", "properties": { "code": { "type": "string" } }, "required": ["code"] },
+            }
+        })
+    }
+
+    #[test]
+    fn test_mcp_respond() {
+        let tools = to_mcp_tools(vec![definition("get_stock_quote"), definition("get_stock_quote"), definition("get_fund_flow")]);
+        assert_eq!(tools.len(), 2);
+        assert_eq!(tools[0]["inputSchema"]["required"][0], "code");
+
+        let init = json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": { "protocolVersion": "2024-11-05" } });
+        let Step::Reply(reply) = respond(&init, &tools, "1.0.0") else { panic!("expected reply") };
+        assert_eq!(reply["result"]["protocolVersion"], "2024-11-05");
+        assert_eq!(reply["result"]["serverInfo"]["version"], "1.0.0");
+
+        let notification = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+        assert_eq!(respond(&notification, &tools, "1.0.0"), Step::Ignore);
+
+        let call = json!({ "jsonrpc": "2.0", "id": "a", "method": "tools/call", "params": { "name": "get_fund_flow", "arguments": { "code": "sh600519" } } });
+        assert_eq!(
+            respond(&call, &tools, "1.0.0"),
+            Step::Call { id: json!("a"), name: "get_fund_flow".to_string(), arguments: r#"{"code":"sh600519"}"#.to_string() }
+        );
+
+        let unknown = json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/call", "params": { "name": "rm_rf" } });
+        let Step::Reply(reply) = respond(&unknown, &tools, "1.0.0") else { panic!("expected reply") };
+        assert_eq!(reply["error"]["code"], INVALID_PARAMS);
+
+        let missing = json!({ "jsonrpc": "2.0", "id": 3, "method": "resources/list" });
+        let Step::Reply(reply) = respond(&missing, &tools, "1.0.0") else { panic!("expected reply") };
+        assert_eq!(reply["error"]["code"], METHOD_NOT_FOUND);
+    }
+}
@@ -9,3 +9,69 @@ pub mod technical_indicators;
 pub mod stock_tools;
 pub mod news_service;
 pub mod market_overview;
+pub mod watchlist_io;
+pub mod snapshot_archiver;
+pub mod signal_alert;
+pub mod signal_screener;
+pub mod technical_store;
+pub mod f10_service;
+pub mod valuation;
+pub mod peer_comparison;
+pub mod watchlist_diagnose;
+pub mod briefing;
+pub mod instruction_tracker;
+pub mod strategy_zone;
+pub mod model_capability;
+pub mod ai_postprocess;
+pub mod pick_store;
+pub mod pick_constraints;
+pub mod pick_verifier;
+pub mod symbol_table;
+pub mod stock_search;
+pub mod index_constituents;
+pub mod market_breadth;
+pub mod risk_metrics;
+pub mod rps;
+pub mod seasonality;
+pub mod history_sync;
+pub mod market_heatmap;
+pub mod board_rotation;
+pub mod anomaly_radar;
+pub mod job_scheduler;
+pub mod pick_checkpoint;
+pub mod shutdown;
+pub mod diagnostics;
+pub mod settings_io;
+pub mod sync_backend;
+pub mod sync;
+pub mod api_server;
+pub mod mcp_server;
+pub mod notifier;
+pub mod deep_link;
+pub mod window_manager;
+pub mod tray;
+pub mod hotkey;
+pub mod intraday_replay;
+pub mod risk_control;
+pub mod ai_router;
+pub mod chart_vision;
+pub mod tts;
+pub mod stock_schedule;
+pub mod filter_expr;
+pub mod scan_explain;
+pub mod factor_score;
+pub mod theme_exposure;
+pub mod board_members;
+pub mod theme_study;
+pub mod announcement_study;
+pub mod fund_flow;
+pub mod focus_monitor;
+pub mod auction_scanner;
+pub mod closing_auction;
+pub mod quote_provider;
+pub mod schema_guard;
+pub mod market_regime;
+pub mod strategy_performance;
+pub mod watchlist_prefetch;
+pub mod watchlist_enriched;
+pub mod stream_forwarder;
@@ -0,0 +1,358 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use crate::models::ai::{
+    AIConfig, AIStreamEvent, ChatCompletionRequest, ChatCompletionResponse, ChatMessage, FunctionCall,
+    ModelCapabilities, ToolCall,
+};
+
+/// 提示词注入模式下模型输出工具调用所用的标签
+const TOOL_CALL_OPEN: &str = "<tool_call>";
+const TOOL_CALL_CLOSE: &str = "</tool_call>";
+/// 部分服务把推理模型的思考链以内联标签放在 content 中
+const THINK_OPEN: &str = "<think>";
+const THINK_CLOSE: &str = "</think>";
+
+/// 进程内能力缓存，key 为 base_url + 模型名（同一模型在不同配置间共享探测结果）
+fn cache() -> &'static Mutex<HashMap<String, ModelCapabilities>> {
+    static CACHE: OnceLock<Mutex<HashMap<String, ModelCapabilities>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn cache_key(config: &AIConfig) -> String {
+    format!("{}|{}", config.base_url.trim_end_matches('/'), config.model_name)
+}
+
+/// 读取模型能力；未探测过时乐观地认为全部支持，调用失败后再降级
+pub fn get(config: &AIConfig) -> ModelCapabilities {
+    cache()
+        .lock()
+        .map(|c| c.get(&cache_key(config)).copied().unwrap_or_default())
+        .unwrap_or_default()
+}
+
+/// 记录探测结果（测试连接时写入）
+pub fn record(config: &AIConfig, caps: ModelCapabilities) {
+    log::info!("[model_capability] record model={} tools={} stream={}", config.model_name, caps.supports_tools, caps.supports_stream);
+    if let Ok(mut c) = cache().lock() {
+        c.insert(cache_key(config), caps);
+    }
+}
+
+pub fn mark_tools_unsupported(config: &AIConfig) {
+    record(config, ModelCapabilities { supports_tools: false, ..get(config) });
+}
+
+pub fn mark_stream_unsupported(config: &AIConfig) {
+    record(config, ModelCapabilities { supports_stream: false, ..get(config) });
+}
+
+/// 判断接口错误是否因为不支持 tools 参数（4xx 且错误信息提到 tool/function）
+pub fn is_tools_rejection(error: &str) -> bool {
+    let lower = error.to_lowercase();
+    is_client_error(&lower) && (lower.contains("tool") || lower.contains("function"))
+}
+
+/// 判断接口错误是否因为不支持流式输出
+pub fn is_stream_rejection(error: &str) -> bool {
+    let lower = error.to_lowercase();
+    is_client_error(&lower) && lower.contains("stream")
+}
+
+fn is_client_error(lower: &str) -> bool {
+    ["(400)", "(404)", "(422)", "400 bad request", "unsupported", "not support"]
+        .iter()
+        .any(|p| lower.contains(p))
+}
+
+/// 推送给前端的降级模式提示事件
+pub fn mode_event(message: &str) -> AIStreamEvent {
+    AIStreamEvent {
+        event_type: "mode".to_string(),
+        content: Some(message.to_string()),
+        done: false,
+        usage: None,
+        tool_name: None,
+    }
+}
+
+/// 提示词注入模式：把工具定义写进 system prompt，并把历史中的 tool_calls / tool 消息改写为纯文本，
+/// 使不支持 Function Calling 的模型也能按约定格式发起工具调用。tools 为空时只改写历史消息
+pub fn to_prompt_messages(messages: &[ChatMessage], tools: &[serde_json::Value]) -> Vec<ChatMessage> {
+    let protocol = tool_protocol_prompt(tools);
+    let mut out: Vec<ChatMessage> = Vec::with_capacity(messages.len() + 1);
+    let mut protocol_added = tools.is_empty();
+
+    for m in messages {
+        match m.role.as_str() {
+            "system" if !protocol_added => {
+                protocol_added = true;
+                let content = format!("{}\n\n{}", m.content.as_deref().unwrap_or(""), protocol);
+                out.push(ChatMessage::system(&content));
+            }
+            "assistant" if m.tool_calls.is_some() => {
+                let mut content = m.content.clone().unwrap_or_default();
+                for tc in m.tool_calls.iter().flatten() {
+                    let arguments = serde_json::from_str::<serde_json::Value>(&tc.function.arguments)
+                        .unwrap_or_else(|_| serde_json::json!({}));
+                    let call = serde_json::json!({ "name": tc.function.name, "arguments": arguments });
+                    content.push_str(&format!("\n{}{}{}", TOOL_CALL_OPEN, call, TOOL_CALL_CLOSE));
+                }
+                out.push(ChatMessage::assistant_text(content.trim()));
+            }
+            "tool" => {
+                let name = m.name.as_deref().unwrap_or("");
+                let content = format!("工具 {} 返回结果：\n{}", name, m.content.as_deref().unwrap_or(""));
+                out.push(ChatMessage::user(&content));
+            }
+            _ => out.push(m.clone()),
+        }
+    }
+
+    if !protocol_added {
+        out.insert(0, ChatMessage::system(&protocol));
+    }
+    out
+}
+
+fn tool_protocol_prompt(tools: &[serde_json::Value]) -> String {
+    let catalog: Vec<String> = tools
+        .iter()
+        .filter_map(|t| t.get("function"))
+        .map(|f| {
+            format!(
+                "- {}：{}\n  参数：{}",
+                f.get("name").and_then(|v| v.as_str()).unwrap_or(""),
+                f.get("description").and_then(|v| v.as_str()).unwrap_or(""),
+                f.get("parameters").map(|p| p.to_string()).unwrap_or_else(|| "{}".to_string()),
+            )
+        })
+        .collect();
+    format!(
+        "## 可用工具\n{}\n\n\
+        需要获取数据时，输出一个或多个工具调用，每个调用单独一行，格式严格为：\n\
+        {}{{\"name\": \"工具名\", \"arguments\": {{参数JSON}}}}{}\n\
+        输出工具调用后立即停止，等待工具结果；工具结果会以「工具 xxx 返回结果」的形式提供。\n\
+        数据足够后直接输出最终回答，不要再包含工具调用。",
+        catalog.join("\n"),
+        TOOL_CALL_OPEN,
+        TOOL_CALL_CLOSE,
+    )
+}
+
+/// 从提示词注入模式的回复中解析工具调用，返回去掉调用块后的文本与解析出的调用
+pub fn extract_prompt_tool_calls(content: &str) -> (String, Vec<ToolCall>) {
+    let mut text = String::new();
+    let mut calls = Vec::new();
+    let mut rest = content;
+
+    while let Some(start) = rest.find(TOOL_CALL_OPEN) {
+        text.push_str(&rest[..start]);
+        let after = &rest[start + TOOL_CALL_OPEN.len()..];
+        // 截断的调用块（缺少闭合标签）取到末尾
+        let (body, next) = match after.find(TOOL_CALL_CLOSE) {
+            Some(end) => (&after[..end], &after[end + TOOL_CALL_CLOSE.len()..]),
+            None => (after, ""),
+        };
+        match parse_call(body) {
+            Some((name, arguments)) => calls.push(ToolCall {
+                id: format!("prompt_call_{}", calls.len()),
+                call_type: "function".to_string(),
+                function: FunctionCall { name, arguments },
+            }),
+            None => log::warn!("[model_capability] malformed prompt tool call: {}", body.chars().take(200).collect::<String>()),
+        }
+        rest = next;
+    }
+    text.push_str(rest);
+    (text.trim().to_string(), calls)
+}
+
+fn parse_call(body: &str) -> Option<(String, String)> {
+    let body = body.trim().trim_start_matches("```json").trim_start_matches("```").trim_end_matches("```").trim();
+    let value: serde_json::Value = serde_json::from_str(body).ok()?;
+    let name = value.get("name")?.as_str()?.to_string();
+    let arguments = match value.get("arguments") {
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(v) => v.to_string(),
+        None => "{}".to_string(),
+    };
+    Some((name, arguments))
+}
+
+/// 非流式模式：把完整响应改写为一段 SSE 字节（单个 delta + [DONE]），供流式解析流程复用
+pub fn to_sse_bytes(response: &ChatCompletionResponse) -> Vec<u8> {
+    let message = response.choices.first().and_then(|c| c.message.as_ref());
+    let content = message.and_then(|m| m.content.clone()).unwrap_or_default();
+    let reasoning = message.and_then(|m| m.reasoning_content.clone());
+    let chunk = serde_json::json!({
+        "id": response.id,
+        "choices": [{
+            "index": 0,
+            "delta": { "content": content, "reasoning_content": reasoning },
+            "finish_reason": "stop",
+        }],
+        "usage": response.usage,
+    });
+    format!("data: {}\n\ndata: [DONE]\n\n", chunk).into_bytes()
+}
+
+/// 是否为推理模型（OpenAI o 系列、DeepSeek-R1 / reasoner、QwQ 等），这类模型不接受 temperature 等采样参数
+pub fn is_reasoning_model(model: &str) -> bool {
+    let name = base_model_name(model);
+    is_openai_o_series(model)
+        || name.contains("reasoner")
+        || name.contains("-r1")
+        || name.starts_with("r1")
+        || name.starts_with("qwq")
+}
+
+/// OpenAI o 系列（o1 / o3 / o4-mini 等）
+fn is_openai_o_series(model: &str) -> bool {
+    let name = base_model_name(model);
+    ["o1", "o3", "o4"].iter().any(|p| name == *p || name.starts_with(&format!("{}-", p)))
+}
+
+/// 去掉 "openai/"、"deepseek-ai/" 等路由前缀后的小写模型名
+fn base_model_name(model: &str) -> String {
+    model.rsplit('/').next().unwrap_or(model).to_lowercase()
+}
+
+/// 按模型类型剔除不支持的请求参数：推理模型不传 temperature，o 系列改用 max_completion_tokens
+pub fn adapt_request(req: &mut ChatCompletionRequest) {
+    if !is_reasoning_model(&req.model) {
+        return;
+    }
+    req.temperature = None;
+    if is_openai_o_series(&req.model) {
+        if let Some(max_tokens) = req.max_tokens.take() {
+            req.max_completion_tokens = Some(max_tokens);
+        }
+    }
+}
+
+/// 去掉正文中内联的 <think>…</think> 思考块，落库与解析只保留最终回答；
+/// 思考块未闭合（输出被截断）时其后内容全部视为思考链
+pub fn strip_reasoning(content: &str) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find(THINK_OPEN) {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + THINK_OPEN.len()..];
+        rest = match after.find(THINK_CLOSE) {
+            Some(end) => &after[end + THINK_CLOSE.len()..],
+            None => "",
+        };
+    }
+    // 部分服务省略开头的 <think>，只输出闭合标签
+    let rest = match rest.find(THINK_CLOSE) {
+        Some(end) if out.is_empty() => &rest[end + THINK_CLOSE.len()..],
+        _ => rest,
+    };
+    out.push_str(rest);
+    if out.len() == content.len() {
+        return content.to_string();
+    }
+    out.trim().to_string()
+}
+
+pub fn thinking_event(content: &str) -> AIStreamEvent {
+    AIStreamEvent {
+        event_type: "thinking".to_string(),
+        content: Some(content.to_string()),
+        done: false,
+        usage: None,
+        tool_name: None,
+    }
+}
+
+/// 流式思考链缓冲：按段落合并 reasoning_content 增量后再推送，避免每个 token 产生一条 thinking 事件
+#[derive(Debug, Default)]
+pub struct ReasoningBuffer {
+    buf: String,
+}
+
+impl ReasoningBuffer {
+    /// 追加增量，攒满完整段落时返回待推送的内容
+    pub fn push(&mut self, delta: &str) -> Option<String> {
+        self.buf.push_str(delta);
+        let pos = self.buf.rfind("\n\n")?;
+        let paragraph = self.buf[..pos].trim().to_string();
+        self.buf.drain(..pos + 2);
+        (!paragraph.is_empty()).then_some(paragraph)
+    }
+
+    /// 取出剩余未推送的内容（正文开始输出或流结束时调用）
+    pub fn flush(&mut self) -> Option<String> {
+        let rest = std::mem::take(&mut self.buf);
+        let rest = rest.trim();
+        (!rest.is_empty()).then(|| rest.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_prompt_tool_calls() {
+        let content = "先看看行情。\n<tool_call>{\"name\": \"get_stock_quote\", \"arguments\": {\"code\": \"sh600519\"}}</tool_call>\n<tool_call>{\"name\": \"get_kline_data\", \"arguments\": \"{\\\"code\\\":\\\"sh600519\\\"}\"";
+        let (text, calls) = extract_prompt_tool_calls(content);
+        assert_eq!(text, "先看看行情。");
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].function.name, "get_stock_quote");
+        assert_eq!(calls[0].function.arguments, r#"{"code":"sh600519"}"#);
+
+        let (text, calls) = extract_prompt_tool_calls("最终结论：持有");
+        assert_eq!(text, "最终结论：持有");
+        assert!(calls.is_empty());
+    }
+
+    #[test]
+    fn test_rejection_detection() {
+        assert!(is_tools_rejection("AI API error (400): {\"error\":\"tools is not supported\"}"));
+        assert!(!is_tools_rejection("AI API error (500): function crashed"));
+        assert!(is_stream_rejection("AI API error (400): stream mode unsupported"));
+    }
+
+    #[test]
+    fn test_reasoning_model_adaptation() {
+        assert!(is_reasoning_model("deepseek-reasoner"));
+        assert!(is_reasoning_model("deepseek-ai/DeepSeek-R1"));
+        assert!(is_reasoning_model("o3-mini"));
+        assert!(!is_reasoning_model("gpt-4o-mini"));
+        assert!(!is_reasoning_model("deepseek-chat"));
+
+        let mut req = ChatCompletionRequest {
+            model: "o4-mini".to_string(),
+            messages: vec![],
+            max_tokens: Some(2048),
+            max_completion_tokens: None,
+            temperature: Some(0.3),
+            stream: Some(true),
+            tools: None,
+            tool_choice: None,
+        };
+        adapt_request(&mut req);
+        assert_eq!(req.temperature, None);
+        assert_eq!(req.max_tokens, None);
+        assert_eq!(req.max_completion_tokens, Some(2048));
+    }
+
+    #[test]
+    fn test_strip_reasoning() {
+        assert_eq!(strip_reasoning("<think>先看均线\n再看量能</think>\n\n结论：持有"), "结论：持有");
+        assert_eq!(strip_reasoning("先看均线</think>结论：观望"), "结论：观望");
+        assert_eq!(strip_reasoning("结论：买入<think>被截断的思考"), "结论：买入");
+        assert_eq!(strip_reasoning("普通回答"), "普通回答");
+    }
+
+    #[test]
+    fn test_reasoning_buffer() {
+        let mut buf = ReasoningBuffer::default();
+        assert_eq!(buf.push("第一段"), None);
+        assert_eq!(buf.push("结束\n\n第二"), Some("第一段结束".to_string()));
+        assert_eq!(buf.flush(), Some("第二".to_string()));
+        assert_eq!(buf.flush(), None);
+    }
+}
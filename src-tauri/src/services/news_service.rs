@@ -5,6 +5,7 @@ use serde_json::Value;
 use std::time::Duration;
 
 use crate::models::news::{AnnouncementItem, NewsCategory, NewsItem, ReportItem};
+use crate::utils::http::SendLogged;
 
 /// 构建新闻请求客户端
 fn build_news_client(referer: &str) -> Result<reqwest::Client> {
@@ -37,7 +38,7 @@ pub async fn fetch_cls_telegraph(count: u32) -> Result<Vec<NewsItem>> {
         count
     );
 
-    let resp = client.get(&url).send().await?;
+    let resp = client.get(&url).send_logged().await?;
     let json: Value = resp.json().await?;
 
     let mut items = Vec::new();
@@ -125,7 +126,7 @@ pub async fn fetch_eastmoney_news(page: u32, page_size: u32) -> Result<Vec<NewsI
         page, page_size, ts
     );
 
-    let resp = client.get(&url).send().await?;
+    let resp = client.get(&url).send_logged().await?;
     let json: Value = resp.json().await?;
 
     let mut items = Vec::new();
@@ -205,7 +206,7 @@ pub async fn fetch_stock_news(keyword: &str, page: u32, page_size: u32) -> Resul
         param_str
     );
 
-    let resp = client.get(&url).send().await?;
+    let resp = client.get(&url).send_logged().await?;
     let text = resp.text().await?;
 
     // 去掉 JSONP 包装: jQuery(...)
@@ -294,7 +295,7 @@ pub async fn fetch_announcements(
         url.push_str(&format!("&stock={}", pure_code));
     }
 
-    let resp = client.get(&url).send().await?;
+    let resp = client.get(&url).send_logged().await?;
     let json: Value = resp.json().await?;
 
     let mut items = Vec::new();
@@ -383,7 +384,7 @@ pub async fn fetch_reports(
         url.push_str(&format!("&code={}", pure_code));
     }
 
-    let resp = client.get(&url).send().await?;
+    let resp = client.get(&url).send_logged().await?;
     let json: Value = resp.json().await?;
 
     let mut items = Vec::new();
@@ -462,7 +463,7 @@ pub async fn fetch_sina_roll_news(page: u32, count: u32) -> Result<Vec<NewsItem>
         count, page, ts
     );
 
-    let resp = client.get(&url).send().await?;
+    let resp = client.get(&url).send_logged().await?;
     let json: Value = resp.json().await?;
 
     let mut items = Vec::new();
@@ -537,7 +538,7 @@ pub async fn fetch_sina_7x24(count: u32) -> Result<Vec<NewsItem>> {
         count
     );
 
-    let resp = client.get(&url).send().await?;
+    let resp = client.get(&url).send_logged().await?;
     let json: Value = resp.json().await?;
 
     let mut items = Vec::new();
@@ -610,7 +611,7 @@ pub async fn fetch_wallstreetcn_lives(count: u32) -> Result<Vec<NewsItem>> {
         count
     );
 
-    let resp = client.get(&url).send().await?;
+    let resp = client.get(&url).send_logged().await?;
     let json: Value = resp.json().await?;
 
     let mut items = Vec::new();
@@ -0,0 +1,226 @@
+use anyhow::{anyhow, Result};
+use base64::Engine;
+use serde_json::{json, Value};
+
+use crate::db::database::Database;
+use crate::models::briefing::MarketBriefing;
+use crate::models::risk::RiskViolation;
+use crate::models::stock::AuctionGap;
+use crate::models::settings::NotifyTarget;
+use crate::models::watchlist::{AnomalyEvent, SignalAlert};
+use crate::services::deep_link::stock_url;
+use crate::utils::crypto::hmac_sha256;
+use crate::utils::http::{build_notify_client, SendLogged};
+
+/// 推送事件类型（NotifyTarget.events 中的取值）
+pub const EVENT_SIGNAL_ALERT: &str = "signal_alert";
+pub const EVENT_ANOMALY: &str = "anomaly";
+pub const EVENT_MORNING_BRIEFING: &str = "morning_briefing";
+pub const EVENT_RISK_ALERT: &str = "risk_alert";
+pub const EVENT_AUCTION_GAP: &str = "auction_gap";
+
+/// 企业微信 markdown 消息正文上限 4096 字节
+const WECOM_MAX_BYTES: usize = 4096;
+/// Telegram 单条消息上限 4096 字符，按字节截断留足余量
+const TELEGRAM_MAX_BYTES: usize = 4000;
+const DINGTALK_MAX_BYTES: usize = 18000;
+/// 单次推送列出的条目上限，其余以“等 N 条”省略
+const MAX_LIST_ITEMS: usize = 20;
+
+/// 按字节上限截断，保证不截断 UTF-8 字符
+fn truncate_bytes(text: &str, max: usize) -> String {
+    if text.len() <= max {
+        return text.to_string();
+    }
+    let mut end = max.saturating_sub("…".len());
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}…", &text[..end])
+}
+
+/// 钉钉机器人加签：timestamp + "\n" + secret 做 HMAC-SHA256，Base64 后 URL 编码
+fn dingtalk_sign(secret: &str, timestamp_ms: i64) -> String {
+    let digest = hmac_sha256(secret.as_bytes(), format!("{}\n{}", timestamp_ms, secret).as_bytes());
+    urlencoding::encode(&base64::engine::general_purpose::STANDARD.encode(digest)).into_owned()
+}
+
+/// 生成推送请求的 URL 与 JSON 请求体
+pub fn build_request(target: &NotifyTarget, event: &str, title: &str, content: &str, timestamp_ms: i64) -> Result<(String, Value)> {
+    let require = |value: &str, field: &str| -> Result<()> {
+        if value.trim().is_empty() {
+            return Err(anyhow!("推送目标「{}」未填写{}", target.name, field));
+        }
+        Ok(())
+    };
+    match target.kind.as_str() {
+        "dingtalk" => {
+            require(&target.url, "Webhook 地址")?;
+            let mut url = target.url.trim().to_string();
+            if !target.secret.trim().is_empty() {
+                let separator = if url.contains('?') { '&' } else { '?' };
+                url = format!("{}{}timestamp={}&sign={}", url, separator, timestamp_ms, dingtalk_sign(target.secret.trim(), timestamp_ms));
+            }
+            let text = truncate_bytes(&format!("### {}\n\n{}", title, content), DINGTALK_MAX_BYTES);
+            Ok((url, json!({ "msgtype": "markdown", "markdown": { "title": title, "text": text } })))
+        }
+        "wecom" => {
+            require(&target.url, "Webhook 地址")?;
+            let text = truncate_bytes(&format!("### {}\n{}", title, content), WECOM_MAX_BYTES);
+            Ok((target.url.trim().to_string(), json!({ "msgtype": "markdown", "markdown": { "content": text } })))
+        }
+        "telegram" => {
+            require(&target.key, " Bot Token")?;
+            require(&target.chat_id, " chat_id")?;
+            let url = format!("https://api.telegram.org/bot{}/sendMessage", target.key.trim());
+            let text = truncate_bytes(&format!("{}\n\n{}", title, content), TELEGRAM_MAX_BYTES);
+            Ok((url, json!({ "chat_id": target.chat_id.trim(), "text": text, "disable_web_page_preview": true })))
+        }
+        "serverchan" => {
+            require(&target.key, " SendKey")?;
+            let url = format!("https://sctapi.ftqq.com/{}.send", target.key.trim());
+            Ok((url, json!({ "title": title, "desp": content })))
+        }
+        "webhook" => {
+            require(&target.url, "Webhook 地址")?;
+            let sent_at = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+            Ok((target.url.trim().to_string(), json!({ "event": event, "title": title, "content": content, "sent_at": sent_at })))
+        }
+        other => Err(anyhow!("不支持的推送类型: {}", other)),
+    }
+}
+
+/// 各平台成功时 HTTP 状态也可能是 200 而业务码非零，需检查响应体
+fn check_response(kind: &str, body: &str) -> Result<()> {
+    let Ok(value) = serde_json::from_str::<Value>(body) else {
+        return Ok(());
+    };
+    let failed = match kind {
+        "dingtalk" | "wecom" => value["errcode"].as_i64().is_some_and(|c| c != 0).then(|| value["errmsg"].to_string()),
+        "telegram" => (value["ok"] == false).then(|| value["description"].to_string()),
+        "serverchan" => value["code"].as_i64().is_some_and(|c| c != 0).then(|| value["message"].to_string()),
+        _ => None,
+    };
+    match failed {
+        Some(message) => Err(anyhow!("推送失败: {}", message.trim_matches('"'))),
+        None => Ok(()),
+    }
+}
+
+/// 发送一条推送
+pub async fn send(target: &NotifyTarget, event: &str, title: &str, content: &str) -> Result<()> {
+    let (url, body) = build_request(target, event, title, content, chrono::Utc::now().timestamp_millis())?;
+    let response = build_notify_client()?.post(&url).json(&body).send_logged().await?;
+    let status = response.status();
+    let text = response.text().await.unwrap_or_default();
+    if !status.is_success() {
+        return Err(anyhow!("推送失败 ({}): {}", status.as_u16(), truncate_bytes(&text, 200)));
+    }
+    check_response(&target.kind, &text)
+}
+
+/// 推送到所有启用且订阅了该事件的目标，单个目标失败只记录日志，返回成功数
+pub async fn notify(db: &Database, event: &str, title: &str, content: &str) -> usize {
+    let targets = match db.load_settings() {
+        Ok(settings) => settings.notify_targets,
+        Err(e) => {
+            log::warn!("[notifier] load settings failed: {}", e);
+            return 0;
+        }
+    };
+    let mut sent = 0;
+    for target in targets.iter().filter(|t| t.enabled && (t.events.is_empty() || t.events.iter().any(|e| e == event))) {
+        match send(target, event, title, content).await {
+            Ok(()) => sent += 1,
+            Err(e) => log::warn!("[notifier] send {} to {} failed: {}", event, target.name, e),
+        }
+    }
+    if sent > 0 {
+        log::info!("[notifier] event={} sent={}", event, sent);
+    }
+    sent
+}
+
+fn list<T>(items: &[T], line: impl Fn(&T) -> String) -> String {
+    let mut lines: Vec<String> = items.iter().take(MAX_LIST_ITEMS).map(line).collect();
+    if items.len() > MAX_LIST_ITEMS {
+        lines.push(format!("- 等共 {} 条", items.len()));
+    }
+    lines.join("\n")
+}
+
+pub fn format_signal_alerts(alerts: &[SignalAlert]) -> (String, String) {
+    let content = list(alerts, |a| {
+        format!("- **{}({})** {} · {} · 强度 {} [查看]({})", a.name, a.code, a.description, a.direction, a.strength, stock_url(&a.code))
+    });
+    (format!("自选股新信号 {} 条", alerts.len()), content)
+}
+
+pub fn format_anomalies(events: &[AnomalyEvent]) -> (String, String) {
+    let content = list(events, |e| {
+        format!("- **{}({})** {} · 现价 {:.2} ({:+.2}%) · {} [查看]({})", e.name, e.code, e.description, e.price, e.change_pct, e.time, stock_url(&e.code))
+    });
+    (format!("盘中异动 {} 条", events.len()), content)
+}
+
+pub fn format_auction_gaps(gaps: &[AuctionGap]) -> (String, String) {
+    let content = list(gaps, |g| {
+        let zones = if g.zones.is_empty() { String::new() } else { format!(" · 区间：{}", g.zones.join("、")) };
+        format!(
+            "- **{}({})** 竞价 {:+.2}% · 竞价量占5日均量 {:.1}% · {}{} [查看]({})",
+            g.name, g.code, g.gap_pct, g.volume_pct, g.labels.join(" "), zones, stock_url(&g.code)
+        )
+    });
+    (format!("竞价异动 {} 只", gaps.len()), content)
+}
+
+pub fn format_risk_violations(violations: &[RiskViolation]) -> (String, String) {
+    let content = list(violations, |v| format!("- {}", v.message));
+    (format!("持仓风控违规 {} 项", violations.len()), content)
+}
+
+pub fn format_briefing(briefing: &MarketBriefing) -> (String, String) {
+    (format!("早盘备忘 {}", briefing.date), briefing.content.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(kind: &str) -> NotifyTarget {
+        NotifyTarget {
+            id: "t1".to_string(),
+            name: "手机".to_string(),
+            kind: kind.to_string(),
+            enabled: true,
+            url: "https://oapi.dingtalk.com/robot/send?access_token=abc".to_string(),
+            key: "123:bot".to_string(),
+            secret: "SECxyz".to_string(),
+            chat_id: "42".to_string(),
+            events: vec![],
+        }
+    }
+
+    #[test]
+    fn test_build_request() {
+        let (url, body) = build_request(&target("dingtalk"), EVENT_ANOMALY, "盘中异动 1 条", "- 内容", 1_700_000_000_000).unwrap();
+        assert!(url.starts_with("https://oapi.dingtalk.com/robot/send?access_token=abc&timestamp=1700000000000&sign="));
+        assert!(!url.ends_with("sign="));
+        assert_eq!(body["markdown"]["text"], "### 盘中异动 1 条\n\n- 内容");
+
+        let (url, body) = build_request(&target("telegram"), EVENT_ANOMALY, "标题", "正文", 0).unwrap();
+        assert_eq!(url, "https://api.telegram.org/bot123:bot/sendMessage");
+        assert_eq!(body["chat_id"], "42");
+
+        let long = "涨".repeat(3000);
+        let (_, body) = build_request(&target("wecom"), EVENT_ANOMALY, "标题", &long, 0).unwrap();
+        assert!(body["markdown"]["content"].as_str().unwrap().len() <= WECOM_MAX_BYTES);
+
+        let mut empty = target("serverchan");
+        empty.key.clear();
+        assert!(build_request(&empty, EVENT_ANOMALY, "标题", "正文", 0).is_err());
+
+        assert!(check_response("dingtalk", r#"{"errcode":310000,"errmsg":"sign not match"}"#).is_err());
+        assert!(check_response("telegram", r#"{"ok":true}"#).is_ok());
+    }
+}
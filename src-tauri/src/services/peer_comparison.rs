@@ -0,0 +1,75 @@
+use anyhow::{anyhow, Result};
+
+use crate::models::f10::{PeerComparison, PeerRow};
+use crate::models::stock::MarketStockSnapshot;
+use crate::services::market_scanner::MarketScanner;
+use crate::services::stock_data::format_stock_code;
+
+/// 默认返回的可比公司数量
+pub const DEFAULT_PEER_COUNT: usize = 10;
+
+/// 同行业可比公司对比：按市值取行业前 top_n（目标股不在其中时追加到末尾），并给出行业中位数
+pub async fn get_peer_comparison(code: &str, top_n: usize) -> Result<PeerComparison> {
+    let code = format_stock_code(code);
+    let scanner = MarketScanner::new()?;
+    let (board_code, industry) = scanner
+        .fetch_stock_industry(&code)
+        .await?
+        .ok_or_else(|| anyhow!("未找到 {} 所属行业", code))?;
+
+    let mut stocks = scanner.fetch_board_stocks(&board_code).await?;
+    stocks.sort_by(|a, b| b.total_market_cap.total_cmp(&a.total_market_cap));
+    let rank = stocks.iter().position(|s| s.code == code);
+
+    let median_pe = median(stocks.iter().map(|s| s.pe_ttm).filter(|v| *v > 0.0).collect());
+    let median_pb = median(stocks.iter().map(|s| s.pb).filter(|v| *v > 0.0).collect());
+    let median_roe = median(stocks.iter().map(|s| s.roe).filter(|v| *v != 0.0).collect());
+
+    let top_n = top_n.max(1);
+    let mut peers: Vec<PeerRow> = stocks.iter().take(top_n).map(|s| to_row(s, &code)).collect();
+    if let Some(i) = rank.filter(|i| *i >= top_n) {
+        peers.push(to_row(&stocks[i], &code));
+    }
+
+    Ok(PeerComparison {
+        name: rank.map(|i| stocks[i].name.clone()).unwrap_or_default(),
+        code,
+        industry,
+        board_code,
+        industry_size: stocks.len(),
+        market_cap_rank: rank.map(|i| i + 1).unwrap_or(0),
+        median_pe,
+        median_pb,
+        median_roe,
+        peers,
+    })
+}
+
+fn to_row(s: &MarketStockSnapshot, target: &str) -> PeerRow {
+    PeerRow {
+        code: s.code.clone(),
+        name: s.name.clone(),
+        price: s.price,
+        change_pct: s.change_pct,
+        total_market_cap: s.total_market_cap,
+        pe_ttm: s.pe_ttm,
+        pb: s.pb,
+        roe: s.roe,
+        revenue_yoy: s.revenue_yoy,
+        pct_20d: s.pct_20d,
+        is_target: s.code == target,
+    }
+}
+
+fn median(mut values: Vec<f64>) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+    let mid = values.len() / 2;
+    if values.len() % 2 == 0 {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    }
+}
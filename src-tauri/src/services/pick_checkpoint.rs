@@ -0,0 +1,72 @@
+use anyhow::Result;
+
+use crate::db::database::Database;
+use crate::models::agent_session::{InterruptedPick, PickCheckpoint};
+use crate::services::ai_postprocess;
+
+/// 应用退出导致中断时记录在会话上的错误
+pub const APP_EXIT_ERROR: &str = "中断：应用退出时选股未完成";
+
+/// 中断会话的报告正文：已生成的部分报告 + 中断标记
+pub fn interrupted_content(checkpoint: &PickCheckpoint) -> String {
+    let partial = ai_postprocess::clean(&checkpoint.session.raw_content);
+    let marker = format!(
+        "> **[中断]** 选股在第 {} 轮中断（已完成 {} 次工具调用），可在 AI 选股页继续运行。",
+        checkpoint.next_round + 1,
+        checkpoint.session.tool_calls.len()
+    );
+    if partial.trim().is_empty() {
+        marker
+    } else {
+        format!("{}\n\n---\n\n{}", partial, marker)
+    }
+}
+
+/// 启动时处理上次运行遗留的断点：只保留最近一个可续跑，并把对应会话标记为中断、写入部分报告
+pub fn recover_interrupted(db: &Database) -> Result<usize> {
+    let checkpoints = db.get_pick_checkpoints()?;
+    let Some((latest, stale)) = checkpoints.split_first() else {
+        return Ok(0);
+    };
+    for checkpoint in stale {
+        db.delete_pick_checkpoint(&checkpoint.session.id)?;
+    }
+
+    let mut checkpoint = latest.clone();
+    if checkpoint.session.error.is_none() {
+        checkpoint.session.error = Some(APP_EXIT_ERROR.to_string());
+        db.save_pick_checkpoint(&checkpoint)?;
+    }
+    let mut session = checkpoint.session.clone();
+    session.content = interrupted_content(&checkpoint);
+    db.save_agent_session(&session)?;
+    log::info!(
+        "[pick_checkpoint] recovered session={} next_round={} discarded={}",
+        session.id, checkpoint.next_round, stale.len()
+    );
+    Ok(checkpoints.len())
+}
+
+/// 运行出错时保留断点并记录原因，供用户续跑
+pub fn mark_failed(db: &Database, session_id: &str, error: &str) -> Result<Option<PickCheckpoint>> {
+    let Some(mut checkpoint) = db.get_pick_checkpoint(session_id)? else {
+        return Ok(None);
+    };
+    checkpoint.session.error = Some(error.to_string());
+    db.save_pick_checkpoint(&checkpoint)?;
+    Ok(Some(checkpoint))
+}
+
+/// 最近一个可续跑的中断选股
+pub fn latest_interrupted(db: &Database) -> Result<Option<InterruptedPick>> {
+    Ok(db.get_pick_checkpoints()?.into_iter().next().map(|c| InterruptedPick {
+        session_id: c.session.id.clone(),
+        model_name: c.session.model_name.clone(),
+        started_at: c.session.created_at.clone(),
+        updated_at: c.updated_at.clone(),
+        next_round: c.next_round,
+        final_phase: c.final_phase,
+        tool_calls: c.session.tool_calls.len(),
+        reason: c.session.error.clone().unwrap_or_else(|| APP_EXIT_ERROR.to_string()),
+    }))
+}
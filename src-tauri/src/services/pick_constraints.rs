@@ -0,0 +1,289 @@
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+
+use crate::models::ai::StockPick;
+use crate::models::settings::PickPreferences;
+use crate::models::stock::MarketStockSnapshot;
+use crate::services::ai_service;
+use crate::services::index_constituents;
+use crate::services::market_scanner::MarketScanner;
+use crate::services::rps;
+use crate::utils::units;
+
+const RISK_APPETITES: [(&str, &str); 3] = [
+    ("conservative", "稳健：优先低估值、业绩确定、波动小的标的，回避高位题材股"),
+    ("balanced", "均衡：兼顾成长与估值安全边际"),
+    ("aggressive", "激进：可接受高波动的题材与弹性标的"),
+];
+const HOLDING_HORIZONS: [(&str, &str); 3] = [
+    ("short", "短线（1~5 个交易日），侧重资金与情绪"),
+    ("swing", "波段（1~4 周），侧重趋势与板块轮动"),
+    ("medium", "中线（1~6 个月），侧重基本面与估值"),
+];
+
+/// 校验选股偏好的取值与区间
+pub fn validate(prefs: &PickPreferences) -> Result<(), String> {
+    if !prefs.risk_appetite.is_empty() && !RISK_APPETITES.iter().any(|(k, _)| *k == prefs.risk_appetite) {
+        return Err(format!("未知的风险偏好: {}", prefs.risk_appetite));
+    }
+    if !prefs.holding_horizon.is_empty() && !HOLDING_HORIZONS.iter().any(|(k, _)| *k == prefs.holding_horizon) {
+        return Err(format!("未知的持有周期: {}", prefs.holding_horizon));
+    }
+    if let (Some(min), Some(max)) = (prefs.min_market_cap, prefs.max_market_cap) {
+        if min > max {
+            return Err("市值下限大于上限".to_string());
+        }
+    }
+    if [prefs.min_market_cap, prefs.max_market_cap, prefs.max_price].iter().flatten().any(|v| *v < 0.0) {
+        return Err("市值与股价约束不能为负数".to_string());
+    }
+    if let Some(sector) = prefs.include_sectors.iter().find(|s| prefs.exclude_sectors.contains(s)) {
+        return Err(format!("板块「{}」同时出现在包含与排除列表中", sector));
+    }
+    if !prefs.universe.is_empty() {
+        index_constituents::resolve_index(&prefs.universe).map_err(|e| e.to_string())?;
+    }
+    if prefs.min_rps.is_some_and(|v| !(0.0..=100.0).contains(&v)) {
+        return Err("RPS 下限需在 0~100 之间".to_string());
+    }
+    if [prefs.min_dividend_yield, prefs.min_fcf_yield].iter().flatten().any(|v| *v < 0.0) {
+        return Err("股息率与现金流收益率下限不能为负数".to_string());
+    }
+    if prefs.max_debt_ratio.is_some_and(|v| !(0.0..=100.0).contains(&v)) {
+        return Err("资产负债率上限需在 0~100 之间".to_string());
+    }
+    Ok(())
+}
+
+/// 生成注入选股系统提示词的约束段落；未设置任何偏好时返回 None
+pub fn to_prompt(prefs: &PickPreferences) -> Option<String> {
+    let mut lines = Vec::new();
+    if let Some((_, desc)) = RISK_APPETITES.iter().find(|(k, _)| *k == prefs.risk_appetite) {
+        lines.push(format!("- 风险偏好：{}", desc));
+    }
+    if let Some((_, desc)) = HOLDING_HORIZONS.iter().find(|(k, _)| *k == prefs.holding_horizon) {
+        lines.push(format!("- 持有周期：{}", desc));
+    }
+    if let Ok((code, name, _)) = index_constituents::resolve_index(&prefs.universe) {
+        let label = if name.is_empty() { code } else { name };
+        lines.push(format!("- 只在{}成分股中选股（可调用 get_index_constituents 获取成分股列表）", label));
+    }
+    if !prefs.include_sectors.is_empty() {
+        lines.push(format!("- 只在以下板块中选股：{}", prefs.include_sectors.join("、")));
+    }
+    if !prefs.exclude_sectors.is_empty() {
+        lines.push(format!("- 不要推荐以下板块的股票：{}", prefs.exclude_sectors.join("、")));
+    }
+    match (prefs.min_market_cap, prefs.max_market_cap) {
+        (Some(min), Some(max)) => lines.push(format!("- 总市值在 {} 亿 ~ {} 亿之间", min, max)),
+        (Some(min), None) => lines.push(format!("- 总市值不低于 {} 亿", min)),
+        (None, Some(max)) => lines.push(format!("- 总市值不高于 {} 亿", max)),
+        (None, None) => {}
+    }
+    if let Some(max_price) = prefs.max_price {
+        lines.push(format!("- 股价不高于 {} 元", max_price));
+    }
+    if let Some(min_rps) = prefs.min_rps {
+        lines.push(format!("- 动量因子：只选 60 日 RPS（相对强度排名，行情工具的 rps 字段）不低于 {} 的强势股", min_rps));
+    }
+    if let Some(min) = prefs.min_dividend_yield {
+        lines.push(format!("- 价值因子：股息率（行情工具的 dividend_yield 字段）不低于 {}%", min));
+    }
+    if let Some(min) = prefs.min_fcf_yield {
+        lines.push(format!("- 价值因子：现金流收益率（fcf_yield 字段）不低于 {}%", min));
+    }
+    if let Some(max) = prefs.max_debt_ratio {
+        lines.push(format!("- 质量因子：资产负债率（debt_ratio 字段）不高于 {}%", max));
+    }
+    if let Some(min) = prefs.min_profit_yoy {
+        lines.push(format!("- 质量因子：净利润同比增长（profit_yoy 字段）不低于 {}%", min));
+    }
+    if lines.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "# 用户选股约束（必须遵守，违反约束的股票会被系统剔除）\n\n{}\n\n在 <PICKS> 的 sector 字段中如实填写所属板块。",
+        lines.join("\n")
+    ))
+}
+
+/// 按硬性约束（选股范围、板块、市值、股价、RPS、价值/质量因子）过滤报告中的 <PICKS>，返回改写后的报告与被剔除的说明。
+/// 行情、成分股获取失败或 RPS 尚未计算时跳过对应约束
+pub async fn enforce(content: &str, prefs: &PickPreferences) -> Result<(String, Vec<String>)> {
+    let picks = ai_service::parse_picks(content);
+    let needs_fundamentals = prefs.min_dividend_yield.is_some()
+        || prefs.min_fcf_yield.is_some()
+        || prefs.max_debt_ratio.is_some()
+        || prefs.min_profit_yoy.is_some();
+    let needs_quotes = needs_fundamentals || prefs.min_market_cap.is_some() || prefs.max_market_cap.is_some() || prefs.max_price.is_some();
+    let needs_sectors = !prefs.include_sectors.is_empty() || !prefs.exclude_sectors.is_empty();
+    let needs_universe = !prefs.universe.is_empty();
+    if picks.is_empty() || !(needs_quotes || needs_sectors || needs_universe || prefs.min_rps.is_some()) {
+        return Ok((content.to_string(), vec![]));
+    }
+
+    let universe: Option<HashSet<String>> = if needs_universe {
+        match index_constituents::constituent_codes(&prefs.universe).await {
+            Ok(codes) => Some(codes),
+            Err(e) => {
+                log::warn!("[pick_constraints] fetch constituents failed: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let quotes: HashMap<String, MarketStockSnapshot> = if needs_quotes {
+        let codes: Vec<String> = picks.iter().map(|p| p.code.clone()).collect();
+        let scanner = MarketScanner::new()?;
+        let quotes = if needs_fundamentals {
+            scanner.fetch_stocks_with_fundamentals(&codes).await
+        } else {
+            scanner.fetch_stocks_by_codes(&codes).await
+        };
+        match quotes {
+            Ok(quotes) => quotes.into_iter().map(|q| (q.code.clone(), q)).collect(),
+            Err(e) => {
+                log::warn!("[pick_constraints] fetch quotes failed: {}", e);
+                HashMap::new()
+            }
+        }
+    } else {
+        HashMap::new()
+    };
+
+    let mut kept = Vec::with_capacity(picks.len());
+    let mut removed = Vec::new();
+    for pick in picks {
+        let rps_60 = rps::cached(&pick.code).and_then(|r| r.rps_60);
+        match violation(&pick, quotes.get(&pick.code), rps_60, universe.as_ref(), prefs) {
+            Some(reason) => removed.push(format!("{}({})：{}", pick.name, pick.code, reason)),
+            None => kept.push(pick),
+        }
+    }
+    if removed.is_empty() {
+        return Ok((content.to_string(), removed));
+    }
+    log::info!("[pick_constraints] removed {} picks violating preferences", removed.len());
+    Ok((ai_service::replace_picks(content, &kept), removed))
+}
+
+fn violation(
+    pick: &StockPick,
+    quote: Option<&MarketStockSnapshot>,
+    rps_60: Option<f64>,
+    universe: Option<&HashSet<String>>,
+    prefs: &PickPreferences,
+) -> Option<String> {
+    if universe.is_some_and(|codes| !codes.contains(&pick.code)) {
+        return Some(format!("不在选股范围 {} 的成分股中", prefs.universe));
+    }
+    let matches_sector = |sector: &String| pick.sector.contains(sector.as_str()) || pick.reason.contains(sector.as_str());
+    if let Some(sector) = prefs.exclude_sectors.iter().find(|s| matches_sector(s)) {
+        return Some(format!("属于排除板块「{}」", sector));
+    }
+    if !prefs.include_sectors.is_empty() && !prefs.include_sectors.iter().any(matches_sector) {
+        return Some(format!("板块「{}」不在指定范围内", pick.sector));
+    }
+
+    if let (Some(min), Some(rps)) = (prefs.min_rps, rps_60) {
+        if rps < min {
+            return Some(format!("60日RPS {:.0} 低于下限 {}", rps, min));
+        }
+    }
+
+    let quote = quote?;
+    if let Some(max_price) = prefs.max_price {
+        if quote.price > max_price {
+            return Some(format!("股价 {:.2} 元高于上限 {} 元", quote.price, max_price));
+        }
+    }
+    let cap = units::yuan_to_yi(quote.total_market_cap);
+    if cap > 0.0 {
+        if let Some(min) = prefs.min_market_cap.filter(|min| cap < *min) {
+            return Some(format!("总市值 {:.0} 亿低于下限 {} 亿", cap, min));
+        }
+        if let Some(max) = prefs.max_market_cap.filter(|max| cap > *max) {
+            return Some(format!("总市值 {:.0} 亿高于上限 {} 亿", cap, max));
+        }
+    }
+    // 补充财务字段未获取到时（资产负债率为 0）跳过价值/质量因子
+    if quote.debt_ratio > 0.0 {
+        if let Some(min) = prefs.min_dividend_yield.filter(|min| quote.dividend_yield < *min) {
+            return Some(format!("股息率 {:.2}% 低于下限 {}%", quote.dividend_yield, min));
+        }
+        if let Some(min) = prefs.min_fcf_yield.filter(|min| quote.fcf_yield < *min) {
+            return Some(format!("现金流收益率 {:.2}% 低于下限 {}%", quote.fcf_yield, min));
+        }
+        if let Some(max) = prefs.max_debt_ratio.filter(|max| quote.debt_ratio > *max) {
+            return Some(format!("资产负债率 {:.1}% 高于上限 {}%", quote.debt_ratio, max));
+        }
+        if let Some(min) = prefs.min_profit_yoy.filter(|min| quote.profit_yoy < *min) {
+            return Some(format!("净利润同比 {:.1}% 低于下限 {}%", quote.profit_yoy, min));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sector_violation() {
+        let prefs = PickPreferences {
+            exclude_sectors: vec!["白酒".to_string()],
+            ..Default::default()
+        };
+        let pick = StockPick {
+            code: "sh600519".to_string(),
+            name: "贵州茅台".to_string(),
+            reason: String::new(),
+            rating: "buy".to_string(),
+            sector: "白酒".to_string(),
+            highlights: vec![],
+            fund_flow: String::new(),
+            valuation: String::new(),
+        };
+        assert!(violation(&pick, None, None, None, &prefs).is_some());
+        assert!(violation(&pick, None, None, None, &PickPreferences::default()).is_none());
+        let universe: HashSet<String> = ["sh600000".to_string()].into_iter().collect();
+        assert!(violation(&pick, None, None, Some(&universe), &PickPreferences::default()).is_some());
+        let momentum = PickPreferences { min_rps: Some(80.0), ..Default::default() };
+        assert!(violation(&pick, None, Some(65.0), None, &momentum).is_some());
+        assert!(violation(&pick, None, Some(92.0), None, &momentum).is_none());
+        assert!(validate(&PickPreferences { risk_appetite: "yolo".to_string(), ..Default::default() }).is_err());
+    }
+
+    #[test]
+    fn test_fundamental_violation() {
+        let pick = StockPick {
+            code: "sh601088".to_string(),
+            name: "中国神华".to_string(),
+            reason: String::new(),
+            rating: "buy".to_string(),
+            sector: "煤炭".to_string(),
+            highlights: vec![],
+            fund_flow: String::new(),
+            valuation: String::new(),
+        };
+        let quote = MarketStockSnapshot {
+            code: pick.code.clone(),
+            price: 40.0,
+            dividend_yield: 6.5,
+            fcf_yield: 9.0,
+            debt_ratio: 28.0,
+            profit_yoy: -5.0,
+            ..Default::default()
+        };
+        let dividend = PickPreferences { min_dividend_yield: Some(5.0), max_debt_ratio: Some(50.0), ..Default::default() };
+        assert!(violation(&pick, Some(&quote), None, None, &dividend).is_none());
+        let quality = PickPreferences { min_profit_yoy: Some(0.0), ..Default::default() };
+        assert!(violation(&pick, Some(&quote), None, None, &quality).is_some());
+        // 补充财务字段缺失时不剔除
+        let missing = MarketStockSnapshot { debt_ratio: 0.0, dividend_yield: 0.0, ..quote.clone() };
+        assert!(violation(&pick, Some(&missing), None, None, &dividend).is_none());
+        assert!(validate(&PickPreferences { max_debt_ratio: Some(120.0), ..Default::default() }).is_err());
+    }
+}
@@ -0,0 +1,87 @@
+use anyhow::Result;
+use chrono::Local;
+use std::collections::HashMap;
+
+use crate::db::database::Database;
+use crate::models::agent_session::PickSessionDetail;
+use crate::models::ai::{CachedPicks, PickRecord};
+use crate::services::ai_service;
+use crate::models::stock::MarketStockSnapshot;
+use crate::services::market_scanner::MarketScanner;
+use crate::services::pick_verifier;
+
+/// 解析选股报告中的 <PICKS> 并按会话落库：入库价取当前最新价，并用同一份行情
+/// 校验停牌/ST/涨停/近 5 日涨幅，结果随记录一起保存；返回落库的记录
+pub async fn record_picks(db: &Database, session_id: &str, content: &str) -> Result<Vec<PickRecord>> {
+    let picks = ai_service::parse_picks(content);
+    if picks.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let codes: Vec<String> = picks.iter().map(|p| p.code.clone()).collect();
+    let quotes: HashMap<String, MarketStockSnapshot> = match MarketScanner::new()?.fetch_stocks_by_codes(&codes).await {
+        Ok(quotes) => quotes.into_iter().map(|q| (q.code.clone(), q)).collect(),
+        Err(e) => {
+            log::warn!("[pick_store] fetch entry prices failed: {}", e);
+            HashMap::new()
+        }
+    };
+
+    let now = Local::now();
+    let date = now.format("%Y-%m-%d").to_string();
+    let created_at = now.format("%Y-%m-%d %H:%M:%S").to_string();
+    let records: Vec<PickRecord> = picks
+        .into_iter()
+        .map(|p| {
+            let quote = quotes.get(&p.code);
+            let verification = pick_verifier::verify(&p, quote);
+            PickRecord {
+                session_id: session_id.to_string(),
+                date: date.clone(),
+                entry_price: quote.map(|q| q.price).unwrap_or(0.0),
+                verify_status: verification.status.to_string(),
+                verify_note: verification.note,
+                code: p.code,
+                name: p.name,
+                rating: p.rating,
+                reason: p.reason,
+                sector: p.sector,
+                highlights: p.highlights,
+                fund_flow: p.fund_flow,
+                valuation: p.valuation,
+                created_at: created_at.clone(),
+            }
+        })
+        .collect();
+
+    db.save_pick_records(&records)?;
+    let rejected = records.iter().filter(|r| r.verify_status == pick_verifier::VERIFY_REJECTED).count();
+    log::info!("[pick_store] recorded {} picks for session {} rejected={}", records.len(), session_id, rejected);
+    Ok(records)
+}
+
+/// 当日最近一次成功的选股会话及其结构化结果
+pub fn get_latest_picks(db: &Database) -> Result<Option<CachedPicks>> {
+    let today = Local::now().format("%Y-%m-%d").to_string();
+    let session = match db.get_latest_agent_session("pick", &today)? {
+        Some(s) => s,
+        None => return Ok(None),
+    };
+    let picks = db.get_pick_records(&session.id)?;
+    Ok(Some(CachedPicks {
+        session_id: session.id,
+        content: session.content,
+        picks,
+        created_at: session.created_at,
+    }))
+}
+
+/// 选股会话详情；会话不存在或不是选股会话时返回 None
+pub fn get_pick_session(db: &Database, session_id: &str) -> Result<Option<PickSessionDetail>> {
+    let session = match db.get_agent_session(session_id)? {
+        Some(s) if s.kind == "pick" => s,
+        _ => return Ok(None),
+    };
+    let picks = db.get_pick_records(&session.id)?;
+    Ok(Some(PickSessionDetail { session, picks }))
+}
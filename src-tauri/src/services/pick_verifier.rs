@@ -0,0 +1,120 @@
+use crate::models::ai::{PickRecord, StockPick};
+use crate::models::stock::MarketStockSnapshot;
+use crate::services::market_scanner::limit_pct;
+
+/// 校验通过
+pub const VERIFY_PASSED: &str = "passed";
+/// 存在风险但仍保留（如近 5 日涨幅过大）
+pub const VERIFY_FLAGGED: &str = "flagged";
+/// 违反选股底线（停牌、ST、涨停买不进），不应加入自选
+pub const VERIFY_REJECTED: &str = "rejected";
+/// 未获取到行情，无法校验
+pub const VERIFY_UNVERIFIED: &str = "unverified";
+
+/// 与选股提示词「近 5 日涨幅超过 10% 需警惕」保持一致
+const MAX_PCT_5D: f64 = 10.0;
+/// 距涨停价不足该幅度即视为涨停（行情涨跌幅保留两位小数，存在舍入误差）
+const LIMIT_UP_TOLERANCE: f64 = 0.3;
+
+/// 单只推荐的行情校验结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct Verification {
+    pub status: &'static str,
+    pub note: String,
+}
+
+pub fn is_st(name: &str) -> bool {
+    name.to_uppercase().contains("ST")
+}
+
+/// 用实时行情校验一只推荐：停牌、ST、涨停直接驳回；近 5 日涨幅超限标记风险
+pub fn verify(pick: &StockPick, quote: Option<&MarketStockSnapshot>) -> Verification {
+    let quote = match quote {
+        Some(q) => q,
+        None => return Verification { status: VERIFY_UNVERIFIED, note: "未获取到行情".to_string() },
+    };
+
+    let mut rejected = Vec::new();
+    if quote.price <= 0.0 || quote.volume <= 0.0 {
+        rejected.push("停牌".to_string());
+    }
+    // 行情名称比模型输出的名称更可靠
+    let name = if quote.name.is_empty() { &pick.name } else { &quote.name };
+    if is_st(name) {
+        rejected.push("ST 股".to_string());
+    }
+    if quote.price > 0.0 && quote.change_pct >= limit_pct(&pick.code, name) - LIMIT_UP_TOLERANCE {
+        rejected.push(format!("涨停（{:+.2}%）", quote.change_pct));
+    }
+    if !rejected.is_empty() {
+        return Verification { status: VERIFY_REJECTED, note: rejected.join("，") };
+    }
+
+    if quote.pct_5d > MAX_PCT_5D {
+        return Verification {
+            status: VERIFY_FLAGGED,
+            note: format!("近5日涨幅 {:.2}% 超过 {}%", quote.pct_5d, MAX_PCT_5D),
+        };
+    }
+    Verification { status: VERIFY_PASSED, note: String::new() }
+}
+
+/// 汇总未通过校验的推荐，用于向前端发送告警；全部通过时返回 None
+pub fn summarize(records: &[PickRecord]) -> Option<String> {
+    let issues: Vec<String> = records
+        .iter()
+        .filter(|r| r.verify_status == VERIFY_REJECTED || r.verify_status == VERIFY_FLAGGED)
+        .map(|r| {
+            let label = if r.verify_status == VERIFY_REJECTED { "不建议买入" } else { "注意风险" };
+            format!("{}({}) {}：{}", r.name, r.code, label, r.verify_note)
+        })
+        .collect();
+    if issues.is_empty() {
+        return None;
+    }
+    Some(format!("行情校验发现 {} 只推荐存在问题：{}", issues.len(), issues.join("；")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pick(code: &str, name: &str) -> StockPick {
+        StockPick {
+            code: code.to_string(),
+            name: name.to_string(),
+            reason: String::new(),
+            rating: "buy".to_string(),
+            sector: String::new(),
+            highlights: vec![],
+            fund_flow: String::new(),
+            valuation: String::new(),
+        }
+    }
+
+    fn quote(name: &str, price: f64, change_pct: f64, pct_5d: f64) -> MarketStockSnapshot {
+        MarketStockSnapshot {
+            name: name.to_string(),
+            price,
+            change_pct,
+            volume: if price > 0.0 { 1000.0 } else { 0.0 },
+            pct_5d,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_verify_rules() {
+        let p = pick("sz300750", "宁德时代");
+        assert_eq!(verify(&p, Some(&quote("宁德时代", 200.0, 3.0, 2.0))).status, VERIFY_PASSED);
+        assert_eq!(verify(&p, Some(&quote("宁德时代", 200.0, 12.0, 2.0))).status, VERIFY_PASSED);
+        assert_eq!(verify(&p, Some(&quote("宁德时代", 200.0, 19.98, 2.0))).status, VERIFY_REJECTED);
+        assert_eq!(verify(&p, Some(&quote("宁德时代", 200.0, 3.0, 15.0))).status, VERIFY_FLAGGED);
+        assert_eq!(verify(&p, Some(&quote("宁德时代", 0.0, 0.0, 0.0))).status, VERIFY_REJECTED);
+        assert_eq!(verify(&p, None).status, VERIFY_UNVERIFIED);
+
+        let main = pick("sh600000", "浦发银行");
+        assert_eq!(verify(&main, Some(&quote("浦发银行", 10.0, 9.98, 0.0))).status, VERIFY_REJECTED);
+        assert_eq!(verify(&main, Some(&quote("*ST浦发", 10.0, 1.0, 0.0))).status, VERIFY_REJECTED);
+    }
+}
@@ -0,0 +1,346 @@
+use anyhow::{Result, anyhow};
+use chrono::Local;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::future::Future;
+use std::pin::Pin;
+
+use crate::models::settings::{AppSettings, CustomQuoteProvider, DataSource};
+use crate::models::stock::{MarketStockSnapshot, StockInfo};
+use crate::services::market_scanner::MarketScanner;
+use crate::services::stock_data::{StockDataService, code_to_pure, format_stock_code};
+use crate::utils::http::{build_stock_client, SendLogged};
+use crate::utils::units;
+
+pub const PROVIDER_SINA: &str = "sina";
+pub const PROVIDER_TENCENT: &str = "tencent";
+pub const PROVIDER_EASTMONEY: &str = "eastmoney";
+const BUILTIN_PROVIDERS: [&str; 3] = [PROVIDER_SINA, PROVIDER_TENCENT, PROVIDER_EASTMONEY];
+
+pub type QuoteFuture<'a> = Pin<Box<dyn Future<Output = Result<Vec<StockInfo>>> + Send + 'a>>;
+
+/// 实时行情源：按带交易所前缀的代码（sh600000）批量获取行情
+pub trait QuoteProvider: Send + Sync {
+    fn id(&self) -> &str;
+    fn fetch<'a>(&'a self, codes: &'a [String]) -> QuoteFuture<'a>;
+}
+
+/// 新浪行情
+pub struct SinaProvider(StockDataService);
+
+impl QuoteProvider for SinaProvider {
+    fn id(&self) -> &str {
+        PROVIDER_SINA
+    }
+
+    fn fetch<'a>(&'a self, codes: &'a [String]) -> QuoteFuture<'a> {
+        Box::pin(self.0.get_realtime_data_sina(codes))
+    }
+}
+
+/// 腾讯行情
+pub struct TencentProvider(StockDataService);
+
+impl QuoteProvider for TencentProvider {
+    fn id(&self) -> &str {
+        PROVIDER_TENCENT
+    }
+
+    fn fetch<'a>(&'a self, codes: &'a [String]) -> QuoteFuture<'a> {
+        Box::pin(self.0.get_realtime_data_tencent(codes))
+    }
+}
+
+/// 东方财富行情（无五档盘口）
+pub struct EastMoneyProvider(MarketScanner);
+
+impl QuoteProvider for EastMoneyProvider {
+    fn id(&self) -> &str {
+        PROVIDER_EASTMONEY
+    }
+
+    fn fetch<'a>(&'a self, codes: &'a [String]) -> QuoteFuture<'a> {
+        Box::pin(async move {
+            let stocks = self.0.fetch_stocks_by_codes_eastmoney(codes).await?;
+            Ok(stocks.iter().map(snapshot_to_info).collect())
+        })
+    }
+}
+
+fn snapshot_to_info(s: &MarketStockSnapshot) -> StockInfo {
+    let now = Local::now();
+    StockInfo {
+        code: s.code.clone(),
+        name: s.name.clone(),
+        open: s.open,
+        pre_close: s.pre_close,
+        price: s.price,
+        high: s.high,
+        low: s.low,
+        volume: units::lots_to_shares(s.volume),
+        amount: s.amount,
+        date: now.format("%Y-%m-%d").to_string(),
+        time: now.format("%H:%M:%S").to_string(),
+        ..Default::default()
+    }
+}
+
+/// 用户自定义的 JSON 行情接口
+pub struct CustomProvider {
+    config: CustomQuoteProvider,
+    client: reqwest::Client,
+}
+
+impl CustomProvider {
+    pub fn new(config: CustomQuoteProvider) -> Result<Self> {
+        Ok(Self { config, client: build_stock_client()? })
+    }
+}
+
+impl QuoteProvider for CustomProvider {
+    fn id(&self) -> &str {
+        &self.config.id
+    }
+
+    fn fetch<'a>(&'a self, codes: &'a [String]) -> QuoteFuture<'a> {
+        Box::pin(async move {
+            let codes: Vec<String> = match self.config.code_format.as_str() {
+                "pure" => codes.iter().map(|c| code_to_pure(c)).collect(),
+                _ => codes.to_vec(),
+            };
+            let url = self.config.url.replace("{codes}", &codes.join(","));
+            let mut request = self.client.get(&url);
+            for (key, value) in &self.config.headers {
+                request = request.header(key.as_str(), value.as_str());
+            }
+            let json: Value = request.send_logged().await?.json().await?;
+            map_quotes(&json, &self.config)
+        })
+    }
+}
+
+/// 按点分隔路径取 JSON 节点，数组节点支持数字下标；空路径返回根节点
+fn lookup<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').filter(|p| !p.is_empty()).try_fold(value, |node, key| match node {
+        Value::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get(i)),
+        _ => node.get(key),
+    })
+}
+
+fn as_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.trim().to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+fn as_number(value: &Value) -> f64 {
+    match value {
+        Value::Number(n) => n.as_f64().unwrap_or(0.0),
+        Value::String(s) => s.trim().parse().unwrap_or(0.0),
+        _ => 0.0,
+    }
+}
+
+/// 写入映射字段，未知字段名返回 false
+fn set_field(info: &mut StockInfo, field: &str, value: &Value) -> bool {
+    let text = || as_text(value).unwrap_or_default();
+    let number = as_number(value);
+    match field {
+        "code" => info.code = text(),
+        "name" => info.name = text(),
+        "date" => info.date = text(),
+        "time" => info.time = text(),
+        "open" => info.open = number,
+        "pre_close" => info.pre_close = number,
+        "price" => info.price = number,
+        "high" => info.high = number,
+        "low" => info.low = number,
+        "bid" => info.bid = number,
+        "ask" => info.ask = number,
+        "volume" => info.volume = number,
+        "amount" => info.amount = number,
+        _ => return false,
+    }
+    true
+}
+
+/// 按自定义接口的字段映射把响应 JSON 转为行情；列表为对象时键名可作为代码
+pub fn map_quotes(json: &Value, config: &CustomQuoteProvider) -> Result<Vec<StockInfo>> {
+    let list = lookup(json, &config.list_path)
+        .ok_or_else(|| anyhow!("响应中未找到行情列表: {}", config.list_path))?;
+    let items: Vec<(Option<&str>, &Value)> = match list {
+        Value::Array(items) => items.iter().map(|v| (None, v)).collect(),
+        Value::Object(map) => map.iter().map(|(k, v)| (Some(k.as_str()), v)).collect(),
+        _ => return Err(anyhow!("行情列表不是数组或对象: {}", config.list_path)),
+    };
+
+    let mut results = Vec::new();
+    for (key, item) in items {
+        let mut info = StockInfo { code: key.unwrap_or_default().to_string(), ..Default::default() };
+        for (field, path) in &config.fields {
+            if let Some(value) = lookup(item, path) {
+                set_field(&mut info, field, value);
+            }
+        }
+        if info.code.is_empty() {
+            continue;
+        }
+        info.code = format_stock_code(&info.code);
+        results.push(info);
+    }
+    Ok(results)
+}
+
+/// 字段映射中无法识别的字段名
+pub fn unknown_fields(config: &CustomQuoteProvider) -> Vec<String> {
+    let mut probe = StockInfo::default();
+    let mut unknown: Vec<String> = config
+        .fields
+        .keys()
+        .filter(|f| !set_field(&mut probe, f, &Value::Null))
+        .cloned()
+        .collect();
+    unknown.sort();
+    unknown
+}
+
+/// 按设置确定行情源的尝试顺序：显式配置的优先级中去掉重复与无效 id；未配置时主数据源在前、东财与自定义接口兜底
+pub fn resolve_order(settings: &AppSettings) -> Vec<String> {
+    let customs: Vec<&str> = settings
+        .custom_quote_providers
+        .iter()
+        .filter(|p| p.enabled)
+        .map(|p| p.id.as_str())
+        .collect();
+    let mut seen = HashSet::new();
+    let order: Vec<String> = settings
+        .quote_provider_order
+        .iter()
+        .map(|id| id.trim())
+        .filter(|id| BUILTIN_PROVIDERS.contains(id) || customs.contains(id))
+        .filter(|id| seen.insert(*id))
+        .map(str::to_string)
+        .collect();
+    if !order.is_empty() {
+        return order;
+    }
+
+    let (primary, secondary) = match settings.data_source_primary {
+        DataSource::Sina => (PROVIDER_SINA, PROVIDER_TENCENT),
+        DataSource::Tencent => (PROVIDER_TENCENT, PROVIDER_SINA),
+    };
+    [primary, secondary, PROVIDER_EASTMONEY]
+        .into_iter()
+        .chain(customs)
+        .map(str::to_string)
+        .collect()
+}
+
+/// 行情源注册表：按优先级依次尝试，出错或返回空时切换下一个
+pub struct QuoteRegistry {
+    providers: Vec<Box<dyn QuoteProvider>>,
+}
+
+impl QuoteRegistry {
+    pub fn from_settings(settings: &AppSettings) -> Result<Self> {
+        let mut providers: Vec<Box<dyn QuoteProvider>> = Vec::new();
+        for id in resolve_order(settings) {
+            let provider: Box<dyn QuoteProvider> = match id.as_str() {
+                PROVIDER_SINA => Box::new(SinaProvider(StockDataService::new()?)),
+                PROVIDER_TENCENT => Box::new(TencentProvider(StockDataService::new()?)),
+                PROVIDER_EASTMONEY => Box::new(EastMoneyProvider(MarketScanner::new()?)),
+                _ => match settings.custom_quote_providers.iter().find(|p| p.id == id) {
+                    Some(config) => Box::new(CustomProvider::new(config.clone())?),
+                    None => continue,
+                },
+            };
+            providers.push(provider);
+        }
+        Ok(Self { providers })
+    }
+
+    pub async fn fetch(&self, codes: &[String]) -> Result<Vec<StockInfo>> {
+        if codes.is_empty() {
+            return Ok(vec![]);
+        }
+        let mut last_err = None;
+        for provider in &self.providers {
+            match provider.fetch(codes).await {
+                Ok(quotes) if !quotes.is_empty() => return Ok(quotes),
+                Ok(_) => log::info!("[quote_provider] {} returned empty, trying next", provider.id()),
+                Err(e) => {
+                    log::warn!("[quote_provider] {} failed: {}, trying next", provider.id(), e);
+                    last_err = Some(e);
+                }
+            }
+        }
+        match last_err {
+            Some(e) => Err(anyhow!("所有行情源均获取失败: {}", e)),
+            None => Ok(vec![]),
+        }
+    }
+}
+
+/// 按设置中的行情源优先级获取实时行情
+pub async fn fetch_quotes(settings: &AppSettings, codes: &[String]) -> Result<Vec<StockInfo>> {
+    QuoteRegistry::from_settings(settings)?.fetch(codes).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn custom(list_path: &str, fields: &[(&str, &str)]) -> CustomQuoteProvider {
+        CustomQuoteProvider {
+            id: "my_api".to_string(),
+            name: "My API".to_string(),
+            enabled: true,
+            url: "https://example.com/q?codes={codes}".to_string(),
+            code_format: "prefixed".to_string(),
+            headers: HashMap::new(),
+            list_path: list_path.to_string(),
+            fields: fields.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+        }
+    }
+
+    #[test]
+    fn test_map_quotes() {
+        let config = custom("data.list", &[("code", "symbol"), ("name", "info.name"), ("price", "last"), ("pre_close", "prev")]);
+        let json = json!({"data": {"list": [
+            {"symbol": "600000", "info": {"name": "浦发银行"}, "last": 8.5, "prev": "8.40"},
+            {"info": {"name": "无代码"}, "last": 1.0}
+        ]}});
+        let quotes = map_quotes(&json, &config).unwrap();
+        assert_eq!(quotes.len(), 1);
+        assert_eq!(quotes[0].code, "sh600000");
+        assert_eq!(quotes[0].name, "浦发银行");
+        assert_eq!(quotes[0].price, 8.5);
+        assert_eq!(quotes[0].pre_close, 8.4);
+
+        // 以代码为键的对象列表
+        let config = custom("", &[("price", "0")]);
+        let quotes = map_quotes(&json!({"sz000001": [10.2, 10.0]}), &config).unwrap();
+        assert_eq!(quotes[0].code, "sz000001");
+        assert_eq!(quotes[0].price, 10.2);
+
+        assert!(map_quotes(&json!({"data": null}), &custom("data.list", &[])).is_err());
+        assert_eq!(unknown_fields(&custom("", &[("price", "p"), ("pe", "x")])), vec!["pe"]);
+    }
+
+    #[test]
+    fn test_resolve_order() {
+        let mut settings = AppSettings { data_source_primary: DataSource::Tencent, ..Default::default() };
+        assert_eq!(resolve_order(&settings), vec!["tencent", "sina", "eastmoney"]);
+
+        settings.custom_quote_providers = vec![custom("", &[])];
+        settings.quote_provider_order = vec!["my_api".into(), "unknown".into(), "eastmoney".into(), "my_api".into()];
+        assert_eq!(resolve_order(&settings), vec!["my_api", "eastmoney"]);
+
+        settings.custom_quote_providers[0].enabled = false;
+        assert_eq!(resolve_order(&settings), vec!["eastmoney"]);
+    }
+}
@@ -0,0 +1,416 @@
+use anyhow::Result;
+use chrono::Local;
+use std::collections::HashMap;
+use tauri::{Emitter, Manager};
+
+use crate::AppState;
+use crate::db::database::Database;
+use crate::models::risk::{CorrelatedCluster, PositionExposure, RiskReport, RiskViolation, SectorExposure};
+use crate::models::settings::RiskRules;
+use crate::models::stock::StockDailyHistory;
+use crate::models::tracking::AIPickTracking;
+use crate::services::history_kline::HistoryKlineService;
+use crate::services::job_scheduler::{JobSpec, JobTrigger};
+use crate::services::market_scanner::MarketScanner;
+use crate::services::notifier;
+use crate::services::pick_verifier::is_st;
+
+/// 前端监听的风控告警事件名
+pub const RISK_ALERT_EVENT: &str = "risk-alert";
+/// 收盘后检查
+const CHECK_AFTER: u32 = 1510;
+const CHECK_INTERVAL_SECS: u64 = 600;
+/// 相关性取最近 60 个交易日的日收益率
+const CORRELATION_DAYS: usize = 60;
+/// 两只股票重叠的收益率样本不足时不计算相关性
+const MIN_CORRELATION_SAMPLES: usize = 20;
+const HISTORY_DAYS: usize = 250;
+const UNCLASSIFIED_SECTOR: &str = "未分类";
+
+/// 同一股票的多条追踪记录合并为一个持仓
+struct Holding {
+    code: String,
+    name: String,
+    sector: String,
+    lots: usize,
+    entry_price: f64,
+    entry_date: String,
+}
+
+fn aggregate_holdings(tracking: &[AIPickTracking]) -> Vec<Holding> {
+    let mut holdings: Vec<Holding> = Vec::new();
+    for t in tracking.iter().filter(|t| t.added_price > 0.0) {
+        match holdings.iter_mut().find(|h| h.code == t.code) {
+            Some(h) => {
+                h.entry_price = (h.entry_price * h.lots as f64 + t.added_price) / (h.lots + 1) as f64;
+                h.lots += 1;
+                if t.added_date < h.entry_date {
+                    h.entry_date = t.added_date.clone();
+                }
+                if h.sector.is_empty() {
+                    h.sector = t.sector.clone();
+                }
+            }
+            None => holdings.push(Holding {
+                code: t.code.clone(),
+                name: t.name.clone(),
+                sector: t.sector.clone(),
+                lots: 1,
+                entry_price: t.added_price,
+                entry_date: t.added_date.clone(),
+            }),
+        }
+    }
+    holdings
+}
+
+/// 按日期索引的日收益率
+fn returns_by_date(history: &[StockDailyHistory]) -> HashMap<&str, f64> {
+    history
+        .windows(2)
+        .filter(|w| w[0].close > 0.0)
+        .map(|w| (w[1].date.as_str(), w[1].close / w[0].close - 1.0))
+        .collect()
+}
+
+/// 两只股票最近 CORRELATION_DAYS 个共同交易日的日收益率皮尔逊相关系数，样本不足返回 None
+pub fn correlation(a: &[StockDailyHistory], b: &[StockDailyHistory]) -> Option<f64> {
+    let returns_b = returns_by_date(b);
+    let returns_a = returns_by_date(a);
+    let mut pairs: Vec<(&str, f64, f64)> = returns_a
+        .iter()
+        .filter_map(|(date, ra)| returns_b.get(date).map(|rb| (*date, *ra, *rb)))
+        .collect();
+    pairs.sort_by(|x, y| x.0.cmp(y.0));
+    let pairs = &pairs[pairs.len().saturating_sub(CORRELATION_DAYS)..];
+    if pairs.len() < MIN_CORRELATION_SAMPLES {
+        return None;
+    }
+    let n = pairs.len() as f64;
+    let mean_a = pairs.iter().map(|p| p.1).sum::<f64>() / n;
+    let mean_b = pairs.iter().map(|p| p.2).sum::<f64>() / n;
+    let cov: f64 = pairs.iter().map(|p| (p.1 - mean_a) * (p.2 - mean_b)).sum();
+    let var_a: f64 = pairs.iter().map(|p| (p.1 - mean_a).powi(2)).sum();
+    let var_b: f64 = pairs.iter().map(|p| (p.2 - mean_b).powi(2)).sum();
+    if var_a <= 0.0 || var_b <= 0.0 {
+        return None;
+    }
+    Some(cov / (var_a * var_b).sqrt())
+}
+
+/// 相关系数不低于阈值的股票两两相连，连通的持仓（至少两只）组成一个股票群。
+/// 返回每个群的持仓下标与组内相连股票对的最低相关系数
+pub fn find_clusters(n: usize, pairs: &[(usize, usize, f64)], threshold: f64) -> Vec<(Vec<usize>, f64)> {
+    fn root(parent: &mut [usize], i: usize) -> usize {
+        let mut r = i;
+        while parent[r] != r {
+            r = parent[r];
+        }
+        parent[i] = r;
+        r
+    }
+    let mut parent: Vec<usize> = (0..n).collect();
+    let strong: Vec<&(usize, usize, f64)> = pairs.iter().filter(|p| p.2 >= threshold).collect();
+    for &&(i, j, _) in &strong {
+        let (ri, rj) = (root(&mut parent, i), root(&mut parent, j));
+        if ri != rj {
+            parent[ri] = rj;
+        }
+    }
+    let mut groups: HashMap<usize, (Vec<usize>, f64)> = HashMap::new();
+    for &&(i, _, corr) in &strong {
+        let r = root(&mut parent, i);
+        let group = groups.entry(r).or_insert((Vec::new(), f64::MAX));
+        group.1 = group.1.min(corr);
+    }
+    for i in 0..n {
+        let r = root(&mut parent, i);
+        if let Some(group) = groups.get_mut(&r) {
+            group.0.push(i);
+        }
+    }
+    let mut clusters: Vec<(Vec<usize>, f64)> = groups.into_values().collect();
+    clusters.sort_by_key(|c| c.0[0]);
+    clusters
+}
+
+/// 按行业汇总仓位，行业为空的归入“未分类”
+pub fn sector_exposures(positions: &[PositionExposure]) -> Vec<SectorExposure> {
+    let mut sectors: Vec<SectorExposure> = Vec::new();
+    for p in positions {
+        let name = if p.sector.trim().is_empty() { UNCLASSIFIED_SECTOR } else { p.sector.trim() };
+        match sectors.iter_mut().find(|s| s.sector == name) {
+            Some(s) => {
+                s.weight_pct += p.weight_pct;
+                s.codes.push(p.code.clone());
+            }
+            None => sectors.push(SectorExposure { sector: name.to_string(), weight_pct: p.weight_pct, codes: vec![p.code.clone()] }),
+        }
+    }
+    sectors.sort_by(|a, b| b.weight_pct.total_cmp(&a.weight_pct));
+    sectors
+}
+
+/// 按风控规则检查持仓，返回违规项（未分类行业不做集中度检查）
+pub fn evaluate(rules: &RiskRules, positions: &[PositionExposure], sectors: &[SectorExposure], clusters: &[CorrelatedCluster]) -> Vec<RiskViolation> {
+    let violation = |rule: &str, subject: String, message: String, value: f64, limit: f64| RiskViolation {
+        rule: rule.to_string(),
+        subject,
+        message,
+        value,
+        limit,
+    };
+    let mut violations = Vec::new();
+    for p in positions {
+        if p.weight_pct > rules.max_position_pct {
+            violations.push(violation(
+                "position",
+                p.name.clone(),
+                format!("{} 仓位 {:.1}% 超过单只上限 {:.0}%", p.name, p.weight_pct, rules.max_position_pct),
+                p.weight_pct,
+                rules.max_position_pct,
+            ));
+        }
+        if rules.forbid_st && p.is_st {
+            violations.push(violation("st", p.name.clone(), format!("{} 为 ST 股，规则禁止持有", p.name), 1.0, 0.0));
+        }
+        if p.drawdown_pct > rules.max_drawdown_pct {
+            violations.push(violation(
+                "drawdown",
+                p.name.clone(),
+                format!("{} 自建仓高点回撤 {:.1}% 超过 {:.0}%", p.name, p.drawdown_pct, rules.max_drawdown_pct),
+                p.drawdown_pct,
+                rules.max_drawdown_pct,
+            ));
+        }
+    }
+    for s in sectors.iter().filter(|s| s.sector != UNCLASSIFIED_SECTOR && s.weight_pct > rules.max_sector_pct) {
+        violations.push(violation(
+            "sector",
+            s.sector.clone(),
+            format!("{} 行业仓位 {:.1}% 超过上限 {:.0}%", s.sector, s.weight_pct, rules.max_sector_pct),
+            s.weight_pct,
+            rules.max_sector_pct,
+        ));
+    }
+    for c in clusters.iter().filter(|c| c.weight_pct > rules.max_cluster_pct) {
+        let subject = c.names.join("、");
+        violations.push(violation(
+            "cluster",
+            subject.clone(),
+            format!("{} 走势高度相关（相关系数≥{:.2}），合计仓位 {:.1}% 超过上限 {:.0}%", subject, c.min_correlation, c.weight_pct, rules.max_cluster_pct),
+            c.weight_pct,
+            rules.max_cluster_pct,
+        ));
+    }
+    violations
+}
+
+/// 自建仓日（含）以来最高收盘价到现价的回撤 %
+fn drawdown_since(history: &[StockDailyHistory], entry_date: &str, entry_price: f64, price: f64) -> f64 {
+    let peak = history
+        .iter()
+        .filter(|h| h.date.as_str() >= entry_date)
+        .map(|h| h.close)
+        .fold(entry_price.max(price), f64::max);
+    if peak <= 0.0 {
+        return 0.0;
+    }
+    ((1.0 - price / peak) * 100.0).max(0.0)
+}
+
+/// 对 AI 追踪中的股票生成风控报告：每条追踪记录视为一份等额建仓，按当前市值计算仓位
+pub async fn build_report(db: &Database) -> Result<RiskReport> {
+    let rules = db.load_settings()?.risk_rules;
+    let holdings = aggregate_holdings(&db.get_tracking_stocks()?);
+    let generated_at = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    if holdings.is_empty() {
+        return Ok(RiskReport { generated_at, positions: vec![], sectors: vec![], clusters: vec![], violations: vec![] });
+    }
+
+    let codes: Vec<String> = holdings.iter().map(|h| h.code.clone()).collect();
+    let quotes: HashMap<String, (String, f64)> = match MarketScanner::new()?.fetch_stocks_by_codes(&codes).await {
+        Ok(quotes) => quotes.into_iter().filter(|q| q.price > 0.0).map(|q| (q.code, (q.name, q.price))).collect(),
+        Err(e) => {
+            log::warn!("[risk_control] fetch quotes failed: {}", e);
+            HashMap::new()
+        }
+    };
+
+    let kline_service = HistoryKlineService::new()?;
+    let mut histories = Vec::with_capacity(holdings.len());
+    for h in &holdings {
+        if let Err(e) = kline_service.sync_daily_history(db, &h.code).await {
+            log::warn!("[risk_control] sync kline failed for {}: {}", h.code, e);
+        }
+        histories.push(db.get_daily_history_asc(&h.code, HISTORY_DAYS)?);
+    }
+
+    let mut positions: Vec<PositionExposure> = holdings
+        .iter()
+        .zip(&histories)
+        .map(|(h, history)| {
+            let (name, price) = quotes
+                .get(&h.code)
+                .cloned()
+                .unwrap_or_else(|| (h.name.clone(), history.last().map(|k| k.close).unwrap_or(h.entry_price)));
+            let name = if name.is_empty() { h.name.clone() } else { name };
+            PositionExposure {
+                code: h.code.clone(),
+                is_st: is_st(&name),
+                name,
+                sector: h.sector.clone(),
+                lots: h.lots,
+                entry_price: h.entry_price,
+                price,
+                return_pct: (price / h.entry_price - 1.0) * 100.0,
+                weight_pct: h.lots as f64 * price / h.entry_price,
+                drawdown_pct: drawdown_since(history, &h.entry_date, h.entry_price, price),
+            }
+        })
+        .collect();
+    let total: f64 = positions.iter().map(|p| p.weight_pct).sum();
+    for p in &mut positions {
+        p.weight_pct = if total > 0.0 { p.weight_pct / total * 100.0 } else { 0.0 };
+    }
+
+    let mut pairs = Vec::new();
+    for i in 0..histories.len() {
+        for j in i + 1..histories.len() {
+            if let Some(corr) = correlation(&histories[i], &histories[j]) {
+                pairs.push((i, j, corr));
+            }
+        }
+    }
+    let clusters: Vec<CorrelatedCluster> = find_clusters(positions.len(), &pairs, rules.correlation_threshold)
+        .into_iter()
+        .map(|(members, min_correlation)| CorrelatedCluster {
+            codes: members.iter().map(|&i| positions[i].code.clone()).collect(),
+            names: members.iter().map(|&i| positions[i].name.clone()).collect(),
+            weight_pct: members.iter().map(|&i| positions[i].weight_pct).sum(),
+            min_correlation,
+        })
+        .collect();
+
+    let sectors = sector_exposures(&positions);
+    let violations = evaluate(&rules, &positions, &sectors, &clusters);
+    positions.sort_by(|a, b| b.weight_pct.total_cmp(&a.weight_pct));
+    log::info!(
+        "[risk_control] build_report positions={} clusters={} violations={}",
+        positions.len(), clusters.len(), violations.len()
+    );
+    Ok(RiskReport { generated_at, positions, sectors, clusters, violations })
+}
+
+/// 后台任务：交易日收盘后检查持仓风控，违规项通过 risk-alert 事件通知前端并推送到已配置的消息目标
+pub fn risk_check_job() -> JobSpec {
+    JobSpec {
+        id: "risk_check",
+        name: "持仓风控检查",
+        trigger: JobTrigger::DailyAfter(CHECK_AFTER),
+        interval_secs: CHECK_INTERVAL_SECS,
+        run: |app| Box::pin(async move {
+            let db = &app.state::<AppState>().db;
+            if !db.load_settings()?.risk_rules.alert_enabled {
+                return Ok(None);
+            }
+            let report = build_report(db).await?;
+            if !report.violations.is_empty() {
+                let _ = app.emit(RISK_ALERT_EVENT, &report.violations);
+                let (title, content) = notifier::format_risk_violations(&report.violations);
+                notifier::notify(db, notifier::EVENT_RISK_ALERT, &title, &content).await;
+            }
+            Ok(Some(format!("持仓 {} 只，违规 {} 项", report.positions.len(), report.violations.len())))
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(code: &str, sector: &str, weight_pct: f64, drawdown_pct: f64) -> PositionExposure {
+        PositionExposure {
+            code: code.to_string(),
+            name: code.to_string(),
+            sector: sector.to_string(),
+            lots: 1,
+            entry_price: 10.0,
+            price: 10.0,
+            return_pct: 0.0,
+            weight_pct,
+            drawdown_pct,
+            is_st: code.contains("ST"),
+        }
+    }
+
+    fn history(closes: &[f64]) -> Vec<StockDailyHistory> {
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, &close)| StockDailyHistory {
+                code: String::new(),
+                date: format!("2024-{:02}-{:02}", 1 + i / 28, 1 + i % 28),
+                close,
+                high: close,
+                low: close,
+                open: close,
+                volume: 0.0,
+                amount: 0.0,
+                change_pct: 0.0,
+                is_limit_up: false,
+                turnover_rate: 0.0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_correlation_and_clusters() {
+        let a: Vec<f64> = (0..40).map(|i| 10.0 + if i % 2 == 0 { 0.5 } else { 0.0 } + i as f64 * 0.01).collect();
+        let b: Vec<f64> = a.iter().map(|c| c * 2.0).collect();
+        let c: Vec<f64> = a.iter().map(|c| 30.0 - c).collect();
+        assert!(correlation(&history(&a), &history(&b)).unwrap() > 0.99);
+        assert!(correlation(&history(&a), &history(&c)).unwrap() < 0.0);
+        assert_eq!(correlation(&history(&a[..10]), &history(&b[..10])), None);
+
+        let clusters = find_clusters(4, &[(0, 1, 0.9), (1, 3, 0.85), (0, 2, 0.3)], 0.8);
+        assert_eq!(clusters, vec![(vec![0, 1, 3], 0.85)]);
+        assert!(find_clusters(3, &[(0, 1, 0.5)], 0.8).is_empty());
+    }
+
+    #[test]
+    fn test_evaluate_rules() {
+        let rules = RiskRules::default();
+        let positions = vec![
+            position("A", "白酒", 30.0, 5.0),
+            position("B", "白酒", 15.0, 20.0),
+            position("*STC", "", 55.0, 0.0),
+        ];
+        let sectors = sector_exposures(&positions);
+        assert_eq!(sectors[0].sector, UNCLASSIFIED_SECTOR);
+        assert_eq!(sectors[1].codes, vec!["A", "B"]);
+        let clusters = vec![CorrelatedCluster {
+            codes: vec!["A".into(), "B".into()],
+            names: vec!["A".into(), "B".into()],
+            weight_pct: 45.0,
+            min_correlation: 0.9,
+        }];
+        let rules_hit: Vec<(String, String)> = evaluate(&rules, &positions, &sectors, &clusters)
+            .into_iter()
+            .map(|v| (v.rule, v.subject))
+            .collect();
+        assert_eq!(
+            rules_hit,
+            vec![
+                ("position".to_string(), "A".to_string()),
+                ("drawdown".to_string(), "B".to_string()),
+                ("position".to_string(), "*STC".to_string()),
+                ("st".to_string(), "*STC".to_string()),
+                ("sector".to_string(), "白酒".to_string()),
+            ]
+        );
+
+        let relaxed = RiskRules { forbid_st: false, max_position_pct: 60.0, ..RiskRules::default() };
+        assert_eq!(evaluate(&relaxed, &positions, &sectors, &clusters).len(), 2);
+    }
+}
@@ -0,0 +1,184 @@
+use anyhow::{Result, anyhow};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::models::watchlist::{KlineItem, RiskMetrics};
+use crate::services::history_kline::HistoryKlineService;
+
+/// Beta 基准：沪深300
+const BENCHMARK_CODE: &str = "sh000300";
+/// 一年约 250 个交易日
+const YEAR_BARS: usize = 250;
+/// 年化系数
+const TRADING_DAYS_PER_YEAR: f64 = 252.0;
+/// 计算 Beta 所需的最少同日收益率样本
+const MIN_BETA_SAMPLES: usize = 60;
+/// 基准日线缓存有效期
+const BENCHMARK_TTL: Duration = Duration::from_secs(3600);
+
+type BenchmarkCache = Mutex<Option<(Instant, Arc<Vec<KlineItem>>)>>;
+
+fn benchmark_cache() -> &'static BenchmarkCache {
+    static CACHE: OnceLock<BenchmarkCache> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(None))
+}
+
+/// 获取沪深300近一年日线（带 1 小时内存缓存）。指数不写入本地日线缓存，避免混入个股扫描
+pub async fn benchmark_klines() -> Result<Arc<Vec<KlineItem>>> {
+    if let Some((at, cached)) = benchmark_cache().lock().unwrap().as_ref() {
+        if at.elapsed() < BENCHMARK_TTL {
+            return Ok(Arc::clone(cached));
+        }
+    }
+    let today = chrono::Local::now().date_naive();
+    let start = (today - chrono::Duration::days(400)).format("%Y-%m-%d").to_string();
+    let klines = HistoryKlineService::new()?
+        .fetch_kline(BENCHMARK_CODE, "day", &start, &today.format("%Y-%m-%d").to_string(), 640)
+        .await?;
+    if klines.is_empty() {
+        return Err(anyhow!("未获取到沪深300日线"));
+    }
+    let klines = Arc::new(klines);
+    *benchmark_cache().lock().unwrap() = Some((Instant::now(), Arc::clone(&klines)));
+    Ok(klines)
+}
+
+/// 由个股日线（按日期升序）计算风险统计；benchmark 为空时不计算 Beta
+pub fn compute_risk_metrics(klines: &[KlineItem], benchmark: &[KlineItem]) -> RiskMetrics {
+    let year = &klines[klines.len().saturating_sub(YEAR_BARS + 1)..];
+    let returns = daily_returns(year);
+    let mut metrics = RiskMetrics {
+        volatility_20d: volatility(&returns, 20),
+        volatility_60d: volatility(&returns, 60),
+        beta: beta(year, benchmark),
+        ..Default::default()
+    };
+    if let Some((dd, peak, trough)) = max_drawdown(year) {
+        metrics.max_drawdown_1y = Some(dd);
+        metrics.drawdown_peak_date = peak;
+        metrics.drawdown_trough_date = trough;
+    }
+    metrics
+}
+
+/// 拉取基准后计算风险统计，基准获取失败时 Beta 为空
+pub async fn fetch_risk_metrics(klines: &[KlineItem]) -> RiskMetrics {
+    let benchmark = match benchmark_klines().await {
+        Ok(b) => b,
+        Err(e) => {
+            log::warn!("[risk_metrics] fetch benchmark failed: {}", e);
+            Arc::new(vec![])
+        }
+    };
+    compute_risk_metrics(klines, &benchmark)
+}
+
+pub fn daily_returns(klines: &[KlineItem]) -> Vec<f64> {
+    klines
+        .windows(2)
+        .filter(|w| w[0].close > 0.0)
+        .map(|w| w[1].close / w[0].close - 1.0)
+        .collect()
+}
+
+/// 最近 n 日收益率的年化标准差 %
+pub fn volatility(returns: &[f64], n: usize) -> Option<f64> {
+    if returns.len() < n {
+        return None;
+    }
+    let window = &returns[returns.len() - n..];
+    let mean = window.iter().sum::<f64>() / n as f64;
+    let var = window.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1) as f64;
+    Some(var.sqrt() * TRADING_DAYS_PER_YEAR.sqrt() * 100.0)
+}
+
+/// 按日期对齐个股与基准的日收益率，Beta = Cov(个股, 基准) / Var(基准)
+fn beta(klines: &[KlineItem], benchmark: &[KlineItem]) -> Option<f64> {
+    let bench_close: HashMap<&str, f64> = benchmark.iter().map(|k| (k.date.as_str(), k.close)).collect();
+    let pairs: Vec<(f64, f64)> = klines
+        .windows(2)
+        .filter_map(|w| {
+            let (b0, b1) = (bench_close.get(w[0].date.as_str())?, bench_close.get(w[1].date.as_str())?);
+            if w[0].close <= 0.0 || *b0 <= 0.0 {
+                return None;
+            }
+            Some((w[1].close / w[0].close - 1.0, b1 / b0 - 1.0))
+        })
+        .collect();
+    if pairs.len() < MIN_BETA_SAMPLES {
+        return None;
+    }
+    let n = pairs.len() as f64;
+    let mean_s = pairs.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_b = pairs.iter().map(|p| p.1).sum::<f64>() / n;
+    let cov = pairs.iter().map(|(s, b)| (s - mean_s) * (b - mean_b)).sum::<f64>();
+    let var = pairs.iter().map(|(_, b)| (b - mean_b).powi(2)).sum::<f64>();
+    if var <= 0.0 {
+        return None;
+    }
+    Some(cov / var)
+}
+
+/// 最大回撤 %（正数）及峰值、谷底日期；没有回撤时为 0
+fn max_drawdown(klines: &[KlineItem]) -> Option<(f64, String, String)> {
+    let first = klines.first()?;
+    let mut peak = first;
+    let mut worst = (0.0, first.date.clone(), first.date.clone());
+    for k in klines {
+        if k.close > peak.close {
+            peak = k;
+        }
+        if peak.close > 0.0 {
+            let dd = (1.0 - k.close / peak.close) * 100.0;
+            if dd > worst.0 {
+                worst = (dd, peak.date.clone(), k.date.clone());
+            }
+        }
+    }
+    Some(worst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn klines(closes: &[f64]) -> Vec<KlineItem> {
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| KlineItem {
+                date: format!("d{:03}", i),
+                open: c,
+                close: c,
+                high: c,
+                low: c,
+                volume: 0.0,
+                amount: 0.0,
+                change_pct: 0.0,
+                turnover_rate: 0.0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_risk_metrics() {
+        // 基准交替涨跌，个股走势为基准的两倍杠杆
+        let mut bench = vec![100.0];
+        let mut stock = vec![100.0];
+        for i in 0..100 {
+            let r = if i % 2 == 0 { 0.01 } else { -0.008 };
+            bench.push(bench.last().unwrap() * (1.0 + r));
+            stock.push(stock.last().unwrap() * (1.0 + 2.0 * r));
+        }
+        let m = compute_risk_metrics(&klines(&stock), &klines(&bench));
+        assert!((m.beta.unwrap() - 2.0).abs() < 1e-9);
+        assert!(m.volatility_20d.unwrap() > 0.0);
+
+        let m = compute_risk_metrics(&klines(&[100.0, 120.0, 90.0, 110.0, 60.0, 130.0]), &[]);
+        assert_eq!(m.beta, None);
+        assert_eq!(m.volatility_20d, None);
+        assert!((m.max_drawdown_1y.unwrap() - 50.0).abs() < 1e-9);
+        assert_eq!((m.drawdown_peak_date.as_str(), m.drawdown_trough_date.as_str()), ("d001", "d004"));
+    }
+}
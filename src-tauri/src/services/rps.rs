@@ -0,0 +1,181 @@
+use anyhow::{Result, anyhow};
+use chrono::Local;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+use tauri::Manager;
+
+use crate::AppState;
+use crate::db::database::Database;
+use crate::models::stock::{MarketStockSnapshot, StockRps};
+use crate::services::market_scanner::MarketScanner;
+use crate::services::job_scheduler::{JobSpec, JobTrigger};
+
+/// 支持的 RPS 周期（交易日）
+pub const RPS_PERIODS: [u32; 3] = [20, 60, 120];
+/// 保留的记录日数：推算 120 日涨幅需要 120 个交易日前的收盘价
+const KEEP_DAYS: usize = 130;
+/// 收盘后计算的时间（HHMM）
+const RECORD_AFTER: u32 = 1530;
+const CHECK_INTERVAL_SECS: u64 = 600;
+
+type RpsTable = HashMap<String, StockRps>;
+
+fn cache() -> &'static RwLock<Option<Arc<RpsTable>>> {
+    static CACHE: OnceLock<RwLock<Option<Arc<RpsTable>>>> = OnceLock::new();
+    CACHE.get_or_init(|| RwLock::new(None))
+}
+
+/// 取记录中指定周期的 RPS，周期不支持时返回 None
+pub fn rps_of(record: &StockRps, period: u32) -> Option<f64> {
+    match period {
+        20 => record.rps_20,
+        60 => record.rps_60,
+        120 => record.rps_120,
+        _ => None,
+    }
+}
+
+/// 百分位排名：比自身涨幅低的股票占比（0~100），并列取相同排名；None 不参与排名
+pub fn rank_percentiles(values: &[Option<f64>]) -> Vec<Option<f64>> {
+    let mut sorted: Vec<f64> = values.iter().flatten().copied().collect();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let m = sorted.len();
+    values
+        .iter()
+        .map(|v| {
+            let v = (*v)?;
+            if m <= 1 {
+                return Some(100.0);
+            }
+            let below = sorted.partition_point(|x| *x < v);
+            Some(below as f64 * 100.0 / (m - 1) as f64)
+        })
+        .collect()
+}
+
+/// 由全市场快照与 120 个交易日前的收盘价计算当日 RPS（停牌股不参与）
+pub fn compute_records(stocks: &[MarketStockSnapshot], closes_120: &HashMap<String, f64>, date: &str) -> Vec<StockRps> {
+    let mut records: Vec<StockRps> = stocks
+        .iter()
+        .filter(|s| s.price > 0.0)
+        .map(|s| StockRps {
+            code: s.code.clone(),
+            name: s.name.clone(),
+            date: date.to_string(),
+            close: s.price,
+            pct_20d: Some(s.pct_20d),
+            pct_60d: Some(s.pct_60d),
+            pct_120d: closes_120.get(&s.code).filter(|c| **c > 0.0).map(|c| (s.price / c - 1.0) * 100.0),
+            ..Default::default()
+        })
+        .collect();
+
+    let rps_20 = rank_percentiles(&records.iter().map(|r| r.pct_20d).collect::<Vec<_>>());
+    let rps_60 = rank_percentiles(&records.iter().map(|r| r.pct_60d).collect::<Vec<_>>());
+    let rps_120 = rank_percentiles(&records.iter().map(|r| r.pct_120d).collect::<Vec<_>>());
+    for (i, r) in records.iter_mut().enumerate() {
+        r.rps_20 = rps_20[i];
+        r.rps_60 = rps_60[i];
+        r.rps_120 = rps_120[i];
+    }
+    records
+}
+
+/// 读取最近一次计算的 RPS（首次访问时从数据库加载并缓存在内存中）
+pub fn load(db: &Database) -> Result<Arc<RpsTable>> {
+    if let Some(table) = cache().read().unwrap().as_ref() {
+        return Ok(Arc::clone(table));
+    }
+    let records = match db.get_stock_rps_dates(1)?.first() {
+        Some(date) => db.get_stock_rps_by_date(date)?,
+        None => vec![],
+    };
+    let table: Arc<RpsTable> = Arc::new(records.into_iter().map(|r| (r.code.clone(), r)).collect());
+    *cache().write().unwrap() = Some(Arc::clone(&table));
+    Ok(table)
+}
+
+/// 从内存缓存查询个股 RPS，缓存未加载时返回 None（供无数据库句柄的工具使用）
+pub fn cached(code: &str) -> Option<StockRps> {
+    cache().read().unwrap().as_ref().and_then(|t| t.get(code).cloned())
+}
+
+/// 扫描全市场计算当日 RPS 并保存，返回参与排名的股票数
+pub async fn refresh(db: &Database) -> Result<usize> {
+    let stocks = MarketScanner::new()?.scan_full_market().await?;
+    if stocks.is_empty() {
+        return Err(anyhow!("全市场扫描返回为空"));
+    }
+    let today = Local::now().format("%Y-%m-%d").to_string();
+    // 120 个交易日前的记录：今天之前的第 120 个记录日
+    let base_date = db
+        .get_stock_rps_dates(KEEP_DAYS)?
+        .into_iter()
+        .filter(|d| *d != today)
+        .nth(119);
+    let closes_120: HashMap<String, f64> = match &base_date {
+        Some(date) => db.get_stock_rps_by_date(date)?.into_iter().map(|r| (r.code, r.close)).collect(),
+        None => HashMap::new(),
+    };
+
+    let records = compute_records(&stocks, &closes_120, &today);
+    db.save_stock_rps(&records, KEEP_DAYS)?;
+    let count = records.len();
+    *cache().write().unwrap() = Some(Arc::new(records.into_iter().map(|r| (r.code.clone(), r)).collect()));
+    log::info!("[rps] refreshed {} stocks base_120={:?}", count, base_date);
+    Ok(count)
+}
+
+/// RPS 选股：按指定周期 RPS 降序返回不低于 min_rps 的股票
+pub fn top_rps(db: &Database, period: u32, min_rps: f64, limit: usize) -> Result<Vec<StockRps>> {
+    if !RPS_PERIODS.contains(&period) {
+        return Err(anyhow!("不支持的 RPS 周期: {}，可选 20/60/120", period));
+    }
+    let table = load(db)?;
+    let mut hits: Vec<&StockRps> = table
+        .values()
+        .filter(|r| rps_of(r, period).is_some_and(|v| v >= min_rps))
+        .collect();
+    hits.sort_by(|a, b| rps_of(b, period).unwrap_or(0.0).total_cmp(&rps_of(a, period).unwrap_or(0.0)));
+    Ok(hits.into_iter().take(limit).cloned().collect())
+}
+
+/// 启动任务：预加载最近一次 RPS 到内存缓存
+pub fn rps_preload_job() -> JobSpec {
+    JobSpec {
+        id: "rps_preload",
+        name: "RPS 预加载",
+        trigger: JobTrigger::Startup,
+        interval_secs: 0,
+        run: |app| Box::pin(async move {
+            let count = load(&app.state::<AppState>().db)?.len();
+            Ok(Some(format!("加载 {} 只股票", count)))
+        }),
+    }
+}
+
+/// 后台任务：交易日收盘后重新计算全市场 RPS
+pub fn rps_record_job() -> JobSpec {
+    JobSpec {
+        id: "rps_record",
+        name: "全市场 RPS 计算",
+        trigger: JobTrigger::DailyAfter(RECORD_AFTER),
+        interval_secs: CHECK_INTERVAL_SECS,
+        run: |app| Box::pin(async move {
+            let count = refresh(&app.state::<AppState>().db).await?;
+            Ok(Some(format!("计算 {} 只股票", count)))
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rank_percentiles() {
+        let ranks = rank_percentiles(&[Some(5.0), Some(-3.0), None, Some(12.0), Some(5.0), Some(0.0)]);
+        assert_eq!(ranks, vec![Some(50.0), Some(0.0), None, Some(100.0), Some(50.0), Some(25.0)]);
+        assert_eq!(rank_percentiles(&[Some(1.0)]), vec![Some(100.0)]);
+    }
+}
@@ -0,0 +1,181 @@
+use std::collections::HashMap;
+
+use crate::models::stock::MarketStockSnapshot;
+use crate::services::factor_score::BenchmarkReturns;
+use crate::services::filter_expr;
+use crate::services::rps;
+use crate::utils::units::{format_amount, yuan_to_yi};
+
+/// 参与全市场排名的因子：(字段, 名称, 是否越低越好)；0 值视为缺失不参与排名
+const RANKED_FACTORS: [(&str, &str, bool); 8] = [
+    ("roe", "ROE", false),
+    ("revenue_yoy", "营收同比", false),
+    ("profit_yoy", "净利润同比", false),
+    ("gross_margin", "毛利率", false),
+    ("pe_ttm", "PE", true),
+    ("dividend_yield", "股息率", false),
+    ("fcf_yield", "现金流收益率", false),
+    ("turnover_rate", "换手率", false),
+];
+/// 排名进入前多少百分比才算突出因子
+const TOP_PCT: f64 = 20.0;
+/// 每只股票最多列出的因子数
+const MAX_FACTORS: usize = 3;
+
+/// 全市场因子分布，用于把单只股票的数值换算成"前 x%"
+pub struct FactorRanks {
+    percentiles: HashMap<&'static str, HashMap<String, f64>>,
+}
+
+impl FactorRanks {
+    pub fn new(universe: &[MarketStockSnapshot]) -> Self {
+        let percentiles = RANKED_FACTORS
+            .iter()
+            .map(|(field, _, lower_better)| {
+                let values: Vec<Option<f64>> = universe
+                    .iter()
+                    .map(|s| {
+                        let v = filter_expr::numeric(s, field);
+                        // PE 为负（亏损）不参与排名
+                        let valid = v.is_finite() && v != 0.0 && !(*lower_better && v < 0.0);
+                        valid.then_some(if *lower_better { -v } else { v })
+                    })
+                    .collect();
+                let ranks = universe
+                    .iter()
+                    .zip(rps::rank_percentiles(&values))
+                    .filter_map(|(s, p)| Some((s.code.clone(), p?)))
+                    .collect();
+                (*field, ranks)
+            })
+            .collect();
+        Self { percentiles }
+    }
+
+    /// 排名前百分比（越小越靠前），未参与排名返回 None
+    fn top_pct(&self, field: &str, code: &str) -> Option<f64> {
+        self.percentiles.get(field)?.get(code).map(|p| 100.0 - p)
+    }
+}
+
+/// 20 日涨幅所处阶段
+fn trend_phase(pct_20d: f64) -> Option<&'static str> {
+    match pct_20d {
+        p if p <= -15.0 => Some("超跌"),
+        p if p < 3.0 => None,
+        p if p < 15.0 => Some("处于启动期"),
+        p if p < 40.0 => Some("处于主升段"),
+        _ => Some("高位加速"),
+    }
+}
+
+fn format_pct(v: f64) -> String {
+    if v.abs() >= 10.0 {
+        format!("{:.0}%", v)
+    } else {
+        format!("{:.1}%", v).replace(".0%", "%")
+    }
+}
+
+/// 生成单只股票的选股理由：按显著程度取最多 3 个因子，如
+/// "ROE前15% + 20日涨幅8%处于启动期 + 主力净流入1.2亿"；传入 benchmark 时动量描述为相对沪深300的超额收益
+pub fn explain(stock: &MarketStockSnapshot, ranks: &FactorRanks, benchmark: Option<&BenchmarkReturns>) -> String {
+    // (显著程度 0~100, 描述)
+    let mut factors: Vec<(f64, String)> = Vec::new();
+
+    for (field, label, _) in RANKED_FACTORS {
+        if let Some(top) = ranks.top_pct(field, &stock.code) {
+            if top <= TOP_PCT {
+                let top = top.ceil().max(1.0);
+                factors.push((100.0 - top, format!("{}前{}%", label, top)));
+            }
+        }
+    }
+
+    match benchmark {
+        Some(benchmark) => {
+            let excess = benchmark.excess(stock, 20);
+            if excess.abs() >= 3.0 {
+                let verb = if excess > 0.0 { "跑赢" } else { "跑输" };
+                factors.push((75.0 + excess.abs().min(20.0), format!("20日{}沪深300 {}", verb, format_pct(excess.abs()))));
+            }
+        }
+        None => {
+            if let Some(phase) = trend_phase(stock.pct_20d) {
+                factors.push((75.0 + stock.pct_20d.abs().min(20.0), format!("20日涨幅{}{}", format_pct(stock.pct_20d), phase)));
+            }
+        }
+    }
+
+    if stock.main_net_inflow >= 1e7 {
+        let strength = 70.0 + yuan_to_yi(stock.main_net_inflow).min(25.0);
+        factors.push((strength, format!("主力净流入{}", format_amount(stock.main_net_inflow))));
+    }
+
+    if stock.volume_ratio >= 2.0 {
+        factors.push((72.0 + stock.volume_ratio.min(10.0), format!("量比{:.1}放量", stock.volume_ratio)));
+    }
+
+    if let Some(rps20) = rps::cached(&stock.code).and_then(|r| rps::rps_of(&r, 20)) {
+        if rps20 >= 90.0 {
+            factors.push((rps20, format!("RPS20为{:.0}", rps20)));
+        }
+    }
+
+    if factors.is_empty() {
+        return "无突出因子".to_string();
+    }
+    factors.sort_by(|a, b| b.0.total_cmp(&a.0));
+    factors
+        .into_iter()
+        .take(MAX_FACTORS)
+        .map(|(_, text)| text)
+        .collect::<Vec<_>>()
+        .join(" + ")
+}
+
+/// 批量生成选股理由，按股票代码索引
+pub fn explain_all(
+    stocks: &[MarketStockSnapshot],
+    universe: &[MarketStockSnapshot],
+    benchmark: Option<&BenchmarkReturns>,
+) -> HashMap<String, String> {
+    let ranks = FactorRanks::new(universe);
+    stocks.iter().map(|s| (s.code.clone(), explain(s, &ranks, benchmark))).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stock(code: &str, roe: f64, pct_20d: f64, main_net_inflow: f64) -> MarketStockSnapshot {
+        MarketStockSnapshot {
+            code: code.to_string(),
+            name: code.to_string(),
+            roe,
+            pct_20d,
+            main_net_inflow,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_explain() {
+        let mut universe: Vec<MarketStockSnapshot> = (0..20).map(|i| stock(&format!("sz{:06}", i), i as f64, 0.0, 0.0)).collect();
+        universe.push(stock("sh600000", 30.0, 8.0, 1.2e8));
+        let reasons = explain_all(&universe[20..], &universe, None);
+        assert_eq!(reasons["sh600000"], "ROE前1% + 20日涨幅8%处于启动期 + 主力净流入1.20亿");
+        assert_eq!(explain(&universe[0], &FactorRanks::new(&universe), None), "无突出因子");
+
+        let benchmark = BenchmarkReturns { pct_20d: 12.0, ..Default::default() };
+        let reasons = explain_all(&universe[20..], &universe, Some(&benchmark));
+        assert_eq!(reasons["sh600000"], "ROE前1% + 20日跑输沪深300 4% + 主力净流入1.20亿");
+    }
+
+    #[test]
+    fn test_trend_phase() {
+        assert_eq!(trend_phase(1.0), None);
+        assert_eq!(trend_phase(8.0), Some("处于启动期"));
+        assert_eq!(trend_phase(-20.0), Some("超跌"));
+    }
+}
@@ -0,0 +1,86 @@
+use chrono::Local;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use crate::models::diagnostics::SchemaDrift;
+
+pub const SINA_QUOTE: &str = "新浪行情";
+pub const TENCENT_QUOTE: &str = "腾讯行情";
+pub const EASTMONEY_QUOTE: &str = "东方财富行情";
+pub const EASTMONEY_FUND_FLOW: &str = "东方财富资金流向";
+
+/// 东财行情条目的必需字段：价格、涨跌幅、代码、市场、名称、昨收
+pub const EASTMONEY_QUOTE_KEYS: &[&str] = &["f2", "f3", "f12", "f13", "f14", "f18"];
+/// 东财资金流向条目的必需字段：主力与超大单/大单/中单/小单净额
+pub const EASTMONEY_FUND_FLOW_KEYS: &[&str] = &["f12", "f13", "f62", "f66", "f72", "f78", "f84"];
+
+fn registry() -> &'static Mutex<HashMap<&'static str, SchemaDrift>> {
+    static DRIFTS: OnceLock<Mutex<HashMap<&'static str, SchemaDrift>>> = OnceLock::new();
+    DRIFTS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 记录一次格式偏差；详情变化时才打印告警，避免逐行解析时刷屏
+pub fn report(source: &'static str, detail: String) {
+    let mut drifts = registry().lock().unwrap();
+    let drift = drifts.entry(source).or_insert_with(|| SchemaDrift {
+        source: source.to_string(),
+        count: 0,
+        last_at: String::new(),
+        detail: String::new(),
+    });
+    drift.count += 1;
+    drift.last_at = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    if drift.detail != detail {
+        log::warn!("[schema_guard] drift source={} detail={} count={}", source, detail, drift.count);
+        drift.detail = detail;
+    }
+}
+
+/// 分隔格式（新浪/腾讯）的字段数不少于 min，否则记录偏差
+pub fn check_field_count(source: &'static str, count: usize, min: usize) -> bool {
+    if count >= min {
+        return true;
+    }
+    report(source, format!("字段数 {}，预期至少 {}", count, min));
+    false
+}
+
+/// JSON 条目包含全部必需键，否则记录缺失的键
+pub fn check_keys(source: &'static str, item: &Value, keys: &[&str]) -> bool {
+    let missing: Vec<&str> = keys.iter().copied().filter(|k| item.get(k).is_none()).collect();
+    if missing.is_empty() {
+        return true;
+    }
+    report(source, format!("缺少字段 {}", missing.join(",")));
+    false
+}
+
+/// 启动以来记录到的格式偏差，最近发生的在前
+pub fn list() -> Vec<SchemaDrift> {
+    let mut drifts: Vec<SchemaDrift> = registry().lock().unwrap().values().cloned().collect();
+    drifts.sort_by(|a, b| b.last_at.cmp(&a.last_at));
+    drifts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_checks() {
+        assert!(check_field_count("test_sina", 33, 32));
+        assert!(!check_field_count("test_sina", 30, 32));
+        assert!(!check_field_count("test_sina", 30, 32));
+        assert!(check_keys("test_em", &json!({"f2": 1, "f3": "-"}), &["f2", "f3"]));
+        assert!(!check_keys("test_em", &json!({"f2": 1}), &["f2", "f3", "f12"]));
+
+        let drifts = list();
+        let sina = drifts.iter().find(|d| d.source == "test_sina").unwrap();
+        assert_eq!(sina.count, 2);
+        assert_eq!(sina.detail, "字段数 30，预期至少 32");
+        let em = drifts.iter().find(|d| d.source == "test_em").unwrap();
+        assert_eq!(em.detail, "缺少字段 f3,f12");
+    }
+}
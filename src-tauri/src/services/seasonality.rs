@@ -0,0 +1,160 @@
+use anyhow::{Result, anyhow};
+use chrono::{Datelike, Local, NaiveDate};
+
+use crate::models::watchlist::{KlineItem, Seasonality, SeasonalityBucket};
+use crate::services::history_kline::HistoryKlineService;
+use crate::services::market_scanner::MarketScanner;
+use crate::services::stock_data::format_stock_code;
+
+/// 默认统计近 5 年
+pub const DEFAULT_YEARS: u32 = 5;
+/// 最多统计近 10 年
+pub const MAX_YEARS: u32 = 10;
+
+const WEEKDAY_LABELS: [&str; 5] = ["周一", "周二", "周三", "周四", "周五"];
+
+fn bucket(key: u32, label: String, returns: &[f64]) -> SeasonalityBucket {
+    let n = returns.len();
+    SeasonalityBucket {
+        key,
+        label,
+        avg_return_pct: (n > 0).then(|| returns.iter().sum::<f64>() / n as f64),
+        win_rate_pct: (n > 0).then(|| returns.iter().filter(|r| **r > 0.0).count() as f64 * 100.0 / n as f64),
+        samples: n,
+    }
+}
+
+/// 月度季节性：月K线（按日期升序）逐月涨跌幅按自然月分组。首根没有上月收盘不计入，
+/// current_month（"YYYY-MM"）尚未走完也不计入
+pub fn monthly_stats(klines: &[KlineItem], current_month: &str) -> Vec<SeasonalityBucket> {
+    let mut groups: Vec<Vec<f64>> = vec![Vec::new(); 12];
+    for w in klines.windows(2) {
+        let k = &w[1];
+        if w[0].close <= 0.0 || k.date.starts_with(current_month) {
+            continue;
+        }
+        if let Some(month) = k.date.get(5..7).and_then(|m| m.parse::<usize>().ok()).filter(|m| (1..=12).contains(m)) {
+            groups[month - 1].push((k.close / w[0].close - 1.0) * 100.0);
+        }
+    }
+    groups
+        .iter()
+        .enumerate()
+        .map(|(i, r)| bucket(i as u32 + 1, format!("{}月", i + 1), r))
+        .collect()
+}
+
+/// 星期效应：日K线（按日期升序）逐日涨跌幅按星期几分组
+pub fn weekday_stats(klines: &[KlineItem]) -> Vec<SeasonalityBucket> {
+    let mut groups: Vec<Vec<f64>> = vec![Vec::new(); 5];
+    for w in klines.windows(2) {
+        if w[0].close <= 0.0 {
+            continue;
+        }
+        let Ok(date) = NaiveDate::parse_from_str(&w[1].date, "%Y-%m-%d") else { continue };
+        let weekday = date.weekday().num_days_from_monday() as usize;
+        if weekday < 5 {
+            groups[weekday].push((w[1].close / w[0].close - 1.0) * 100.0);
+        }
+    }
+    groups
+        .iter()
+        .enumerate()
+        .map(|(i, r)| bucket(i as u32 + 1, WEEKDAY_LABELS[i].to_string(), r))
+        .collect()
+}
+
+/// 统计个股（或指数）近 years 年的月度与星期季节性，并附所属行业板块的月度季节性（获取失败时留空）
+pub async fn get_seasonality(code: &str, years: u32) -> Result<Seasonality> {
+    let code = format_stock_code(code);
+    let years = years.clamp(1, MAX_YEARS);
+    let today = Local::now().date_naive();
+    let end = today.format("%Y-%m-%d").to_string();
+    let start = (today - chrono::Duration::days(years as i64 * 365)).format("%Y-%m-%d").to_string();
+    // 月K线多取一个月，首月的涨跌幅需要上月收盘
+    let month_start = (today - chrono::Duration::days(years as i64 * 365 + 31)).format("%Y-%m-%d").to_string();
+    let current_month = today.format("%Y-%m").to_string();
+
+    let service = HistoryKlineService::new()?;
+    let (monthly, daily) = tokio::join!(
+        service.fetch_kline(&code, "month", &month_start, &end, MAX_YEARS * 12 + 2),
+        service.fetch_kline_full(&code, "day", &start, &end),
+    );
+    let (monthly, daily) = (monthly?, daily?);
+    if monthly.len() < 2 && daily.len() < 2 {
+        return Err(anyhow!("未获取到 {} 的历史K线", code));
+    }
+
+    let mut result = Seasonality {
+        years,
+        start_date: daily.first().map(|k| k.date.clone()).unwrap_or_default(),
+        end_date: daily.last().map(|k| k.date.clone()).unwrap_or_default(),
+        monthly: monthly_stats(&monthly, &current_month),
+        weekday: weekday_stats(&daily),
+        ..Default::default()
+    };
+
+    match MarketScanner::new()?.fetch_stock_industry(&code).await {
+        Ok(Some((board_code, sector_name))) => {
+            match service.fetch_board_kline(&board_code, "month", &month_start, &end).await {
+                Ok(klines) => result.sector_monthly = monthly_stats(&klines, &current_month),
+                Err(e) => log::warn!("[seasonality] fetch sector kline failed for {}: {}", board_code, e),
+            }
+            result.sector_code = board_code;
+            result.sector_name = sector_name;
+        }
+        Ok(None) => {}
+        Err(e) => log::warn!("[seasonality] fetch industry failed for {}: {}", code, e),
+    }
+    result.code = code;
+    log::info!(
+        "[seasonality] get_seasonality code={} years={} months={} days={} sector={}",
+        result.code, years, monthly.len(), daily.len(), result.sector_code
+    );
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kline(date: &str, close: f64) -> KlineItem {
+        KlineItem {
+            date: date.to_string(),
+            open: close,
+            close,
+            high: close,
+            low: close,
+            volume: 0.0,
+            amount: 0.0,
+            change_pct: 0.0,
+            turnover_rate: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_seasonality_stats() {
+        let months = vec![
+            kline("2023-05-31", 10.0),
+            kline("2023-06-30", 11.0),
+            kline("2024-05-31", 10.0),
+            kline("2024-06-28", 9.5),
+            kline("2024-07-31", 9.5),
+        ];
+        let m = monthly_stats(&months, "2024-07");
+        assert_eq!(m.len(), 12);
+        // 2023-06 +10%、2024-06 -5%；2024-05 相对 2023-06 下跌；当月 7 月不计入
+        assert_eq!(m[5].samples, 2);
+        assert!((m[5].avg_return_pct.unwrap() - 2.5).abs() < 1e-9);
+        assert_eq!(m[5].win_rate_pct, Some(50.0));
+        assert_eq!((m[4].samples, m[6].samples), (1, 0));
+        assert_eq!(m[6].avg_return_pct, None);
+
+        // 2024-06-07 为周五，2024-06-10 为周一
+        let days = vec![kline("2024-06-06", 10.0), kline("2024-06-07", 10.2), kline("2024-06-10", 9.9)];
+        let w = weekday_stats(&days);
+        assert_eq!((w[4].samples, w[0].samples, w[1].samples), (1, 1, 0));
+        assert!((w[4].avg_return_pct.unwrap() - 2.0).abs() < 1e-9);
+        assert_eq!(w[0].win_rate_pct, Some(0.0));
+    }
+}
@@ -0,0 +1,230 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::models::settings::{AppSettings, SettingsProfile};
+use crate::services::{pick_constraints, strategy_zone};
+
+/// 导出文件格式版本
+const EXPORT_VERSION: u32 = 1;
+
+/// 设置导出文件
+#[derive(Debug, Serialize, Deserialize)]
+struct SettingsExport {
+    version: u32,
+    exported_at: String,
+    /// 是否包含 API Key 与东财用户标识
+    include_secrets: bool,
+    settings: AppSettings,
+}
+
+/// 导出设置为 JSON；不含密钥时清空各模型的 API Key 与东财用户标识
+pub fn export_json(settings: &AppSettings, include_secrets: bool) -> Result<String> {
+    let mut settings = settings.clone();
+    if !include_secrets {
+        for config in &mut settings.ai_configs {
+            config.api_key.clear();
+        }
+        settings.qgqp_b_id.clear();
+        settings.sync.password.clear();
+        settings.api_server.token.clear();
+        settings.tts.api_key.clear();
+        for provider in &mut settings.custom_quote_providers {
+            provider.headers.clear();
+        }
+        for target in &mut settings.notify_targets {
+            target.url.clear();
+            target.key.clear();
+            target.secret.clear();
+        }
+    }
+    settings.token_usage_today = 0;
+    let export = SettingsExport {
+        version: EXPORT_VERSION,
+        exported_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        include_secrets,
+        settings,
+    };
+    Ok(serde_json::to_string_pretty(&export)?)
+}
+
+/// 解析导入的设置文件（兼容直接保存的 AppSettings JSON），并与当前设置合并：
+/// 导入文件中为空的 API Key / 东财用户标识沿用当前值，今日 Token 用量不被覆盖
+pub fn import_json(current: &AppSettings, json: &str) -> Result<AppSettings> {
+    let value: serde_json::Value = serde_json::from_str(json).map_err(|e| anyhow!("设置文件不是有效的 JSON: {}", e))?;
+    let mut imported: AppSettings = match value.get("settings") {
+        Some(settings) if value.get("version").is_some() => serde_json::from_value(settings.clone()),
+        _ => serde_json::from_value(value),
+    }
+    .map_err(|e| anyhow!("设置文件格式不正确: {}", e))?;
+
+    for config in &mut imported.ai_configs {
+        if config.api_key.is_empty() {
+            if let Some(existing) = current.ai_configs.iter().find(|c| c.id == config.id) {
+                config.api_key = existing.api_key.clone();
+            }
+        }
+    }
+    if imported.qgqp_b_id.is_empty() {
+        imported.qgqp_b_id = current.qgqp_b_id.clone();
+    }
+    if imported.sync.password.is_empty() {
+        imported.sync.password = current.sync.password.clone();
+    }
+    if imported.api_server.token.is_empty() {
+        imported.api_server.token = current.api_server.token.clone();
+    }
+    if imported.tts.api_key.is_empty() {
+        imported.tts.api_key = current.tts.api_key.clone();
+    }
+    for provider in &mut imported.custom_quote_providers {
+        if provider.headers.is_empty() {
+            if let Some(existing) = current.custom_quote_providers.iter().find(|p| p.id == provider.id) {
+                provider.headers = existing.headers.clone();
+            }
+        }
+    }
+    for target in &mut imported.notify_targets {
+        if let Some(existing) = current.notify_targets.iter().find(|t| t.id == target.id) {
+            if target.url.is_empty() {
+                target.url = existing.url.clone();
+            }
+            if target.key.is_empty() {
+                target.key = existing.key.clone();
+            }
+            if target.secret.is_empty() {
+                target.secret = existing.secret.clone();
+            }
+        }
+    }
+    imported.token_usage_today = current.token_usage_today;
+    if imported.active_ai_config_id.as_ref().is_some_and(|id| !imported.ai_configs.iter().any(|c| &c.id == id)) {
+        imported.active_ai_config_id = imported.ai_configs.first().map(|c| c.id.clone());
+    }
+
+    strategy_zone::validate_zones(&imported.strategy_zones).map_err(|e| anyhow!(e))?;
+    pick_constraints::validate(&imported.pick_preferences).map_err(|e| anyhow!(e))?;
+    Ok(imported)
+}
+
+/// 多设备共享的设置：去掉本机专属的 API Key、东财用户标识、Token 用量、同步配置、本机 API、推送目标与调试开关。
+/// 推送目标只保留在本机，避免多台设备重复推送同一条消息
+pub fn shareable(settings: &AppSettings) -> AppSettings {
+    let mut settings = settings.clone();
+    for config in &mut settings.ai_configs {
+        config.api_key.clear();
+    }
+    settings.qgqp_b_id.clear();
+    settings.token_usage_today = 0;
+    settings.sync = Default::default();
+    settings.api_server = Default::default();
+    settings.notify_targets.clear();
+    settings.tts.api_key.clear();
+    for provider in &mut settings.custom_quote_providers {
+        provider.headers.clear();
+    }
+    settings.debug_logging = false;
+    settings
+}
+
+/// 将当前参数保存为方案；同名方案覆盖，返回方案 id
+pub fn save_profile(settings: &mut AppSettings, name: &str) -> Result<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(anyhow!("方案名称不能为空"));
+    }
+    let updated_at = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    let params = settings.profile_params();
+    let id = match settings.profiles.iter_mut().find(|p| p.name == name) {
+        Some(profile) => {
+            profile.params = params;
+            profile.updated_at = updated_at;
+            profile.id.clone()
+        }
+        None => {
+            let id = uuid::Uuid::new_v4().to_string();
+            settings.profiles.push(SettingsProfile { id: id.clone(), name: name.to_string(), updated_at, params });
+            id
+        }
+    };
+    settings.active_profile_id = Some(id.clone());
+    Ok(id)
+}
+
+/// 切换到指定方案
+pub fn apply_profile(settings: &mut AppSettings, id: &str) -> Result<()> {
+    let params = settings
+        .profiles
+        .iter()
+        .find(|p| p.id == id)
+        .map(|p| p.params.clone())
+        .ok_or_else(|| anyhow!("设置方案不存在: {}", id))?;
+    strategy_zone::validate_zones(&params.strategy_zones).map_err(|e| anyhow!(e))?;
+    pick_constraints::validate(&params.pick_preferences).map_err(|e| anyhow!(e))?;
+    settings.apply_profile_params(&params);
+    settings.active_profile_id = Some(id.to_string());
+    Ok(())
+}
+
+pub fn delete_profile(settings: &mut AppSettings, id: &str) {
+    settings.profiles.retain(|p| p.id != id);
+    if settings.active_profile_id.as_deref() == Some(id) {
+        settings.active_profile_id = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ai::AIConfig;
+    use crate::models::settings::CustomQuoteProvider;
+
+    #[test]
+    fn test_export_import_and_profiles() {
+        let mut current = AppSettings {
+            ai_configs: vec![AIConfig { id: "m1".to_string(), api_key: "sk-secret".to_string(), ..Default::default() }],
+            active_ai_config_id: Some("m1".to_string()),
+            qgqp_b_id: "fp".to_string(),
+            token_usage_today: 42,
+            ..Default::default()
+        };
+        current.tts.api_key = "sk-tts".to_string();
+        current.custom_quote_providers = vec![CustomQuoteProvider {
+            id: "my_api".to_string(),
+            name: "My API".to_string(),
+            enabled: true,
+            url: "https://example.com/q?codes={codes}".to_string(),
+            code_format: "prefixed".to_string(),
+            headers: [("Authorization".to_string(), "Bearer quote-token".to_string())].into_iter().collect(),
+            list_path: String::new(),
+            fields: Default::default(),
+        }];
+        let json = export_json(&current, false).unwrap();
+        assert!(!json.contains("sk-secret"));
+        assert!(!json.contains("sk-tts"));
+        assert!(!json.contains("quote-token"));
+        let full = export_json(&current, true).unwrap();
+        assert!(full.contains("sk-tts") && full.contains("quote-token"));
+        assert!(shareable(&current).custom_quote_providers[0].headers.is_empty());
+
+        let imported = import_json(&current, &json).unwrap();
+        assert_eq!(imported.ai_configs[0].api_key, "sk-secret");
+        assert_eq!(imported.qgqp_b_id, "fp");
+        assert_eq!(imported.tts.api_key, "sk-tts");
+        assert_eq!(imported.custom_quote_providers[0].headers, current.custom_quote_providers[0].headers);
+        assert_eq!(imported.token_usage_today, 42);
+
+        current.max_pick_tool_rounds = 20;
+        let aggressive = save_profile(&mut current, "激进短线").unwrap();
+        current.max_pick_tool_rounds = 6;
+        let steady = save_profile(&mut current, "稳健中线").unwrap();
+        apply_profile(&mut current, &aggressive).unwrap();
+        assert_eq!(current.max_pick_tool_rounds, 20);
+        assert_eq!(current.active_profile_id.as_deref(), Some(aggressive.as_str()));
+
+        current.max_pick_tool_rounds = 8;
+        assert_eq!(save_profile(&mut current, "稳健中线").unwrap(), steady);
+        assert_eq!(current.profiles.len(), 2);
+        delete_profile(&mut current, &steady);
+        assert!(current.active_profile_id.is_none());
+    }
+}
@@ -0,0 +1,121 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, ExitRequestApi, Manager};
+use tokio::sync::watch;
+
+use crate::AppState;
+
+/// 退出时等待进行中任务结束的最长时间，超时后直接退出
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(5);
+const IDLE_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 应用退出控制：通知后台任务停止，并登记进行中的任务，退出前等待其写完数据
+pub struct ShutdownController {
+    signal: watch::Sender<bool>,
+    active: AtomicUsize,
+}
+
+/// 进行中任务的登记凭据，drop 时注销
+pub struct TaskGuard<'a> {
+    controller: &'a ShutdownController,
+}
+
+impl Drop for TaskGuard<'_> {
+    fn drop(&mut self) {
+        self.controller.active.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl Default for ShutdownController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ShutdownController {
+    pub fn new() -> Self {
+        let (signal, _) = watch::channel(false);
+        Self { signal, active: AtomicUsize::new(0) }
+    }
+
+    pub fn is_requested(&self) -> bool {
+        *self.signal.borrow()
+    }
+
+    /// 发出退出信号，已在等待 cancelled() 的任务会立即返回
+    pub fn request(&self) {
+        self.signal.send_replace(true);
+    }
+
+    /// 等待退出信号
+    pub async fn cancelled(&self) {
+        let mut receiver = self.signal.subscribe();
+        let _ = receiver.wait_for(|requested| *requested).await;
+    }
+
+    /// 登记一个进行中的任务，返回的凭据存活期间退出流程会等待
+    pub fn track(&self) -> TaskGuard<'_> {
+        self.active.fetch_add(1, Ordering::SeqCst);
+        TaskGuard { controller: self }
+    }
+
+    pub fn active_tasks(&self) -> usize {
+        self.active.load(Ordering::SeqCst)
+    }
+
+    /// 等待全部登记任务结束，超时返回 false
+    pub async fn wait_idle(&self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        while self.active_tasks() > 0 {
+            if Instant::now() >= deadline {
+                return false;
+            }
+            tokio::time::sleep(IDLE_POLL_INTERVAL).await;
+        }
+        true
+    }
+}
+
+/// 处理应用退出请求：首次请求时阻止退出，通知后台任务与 AI 选股停止，等待进行中的任务写完数据后再退出。
+/// 清理完成后由 app.exit 触发的第二次请求直接放行
+pub fn on_exit_requested(app: &AppHandle, api: &ExitRequestApi) {
+    let state = app.state::<AppState>();
+    if state.shutdown.is_requested() {
+        return;
+    }
+    api.prevent_exit();
+    log::info!("[shutdown] exit requested, active_tasks={}", state.shutdown.active_tasks());
+    state.shutdown.request();
+    state.ai_pick_cancel.store(true, Ordering::SeqCst);
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        if !state.shutdown.wait_idle(SHUTDOWN_GRACE).await {
+            log::warn!("[shutdown] {} tasks still running after grace period", state.shutdown.active_tasks());
+        }
+        if let Err(e) = state.db.flush() {
+            log::warn!("[shutdown] flush database failed: {}", e);
+        }
+        log::info!("[shutdown] exiting");
+        app.exit(0);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_shutdown_controller() {
+        let controller = ShutdownController::new();
+        let guard = controller.track();
+        assert_eq!(controller.active_tasks(), 1);
+        assert!(!controller.wait_idle(Duration::from_millis(100)).await);
+
+        controller.request();
+        controller.cancelled().await;
+        drop(guard);
+        assert!(controller.wait_idle(Duration::from_millis(100)).await);
+    }
+}
@@ -0,0 +1,83 @@
+use anyhow::Result;
+use chrono::Local;
+use tauri::{Emitter, Manager};
+
+use crate::db::database::Database;
+use crate::models::watchlist::SignalAlert;
+use crate::services::history_kline::HistoryKlineService;
+use crate::services::technical_indicators;
+use crate::services::job_scheduler::{JobSpec, JobTrigger};
+use crate::services::notifier;
+use crate::AppState;
+
+/// 收盘后开始扫描的时间（HHMM）
+const SCAN_AFTER: u32 = 1510;
+const CHECK_INTERVAL_SECS: u64 = 600;
+/// 前端监听的预警事件名
+pub const SIGNAL_ALERT_EVENT: &str = "signal-alert";
+
+/// 扫描全部自选股的最新交易日信号，写入 signals_history，返回本次新增的强信号（强度不低于 alert_min_strength）
+pub async fn scan_watchlist_signals(db: &Database) -> Result<Vec<SignalAlert>> {
+    let stocks = db.get_watchlist_stocks()?;
+    if stocks.is_empty() {
+        return Ok(vec![]);
+    }
+    let config = db.load_settings()?.signal_config;
+    let kline_service = HistoryKlineService::new()?;
+    let created_at = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    let mut alerts = Vec::new();
+
+    for stock in &stocks {
+        if let Err(e) = kline_service.sync_daily_history(db, &stock.code).await {
+            log::warn!("[signal_alert] sync kline failed for {}: {}", stock.code, e);
+        }
+        let history = db.get_daily_history_asc(&stock.code, 250)?;
+        let last_date = match history.last() {
+            Some(h) => h.date.clone(),
+            None => continue,
+        };
+        let klines = technical_indicators::klines_from_history(&history);
+        let indicators = technical_indicators::compute_indicators(&klines);
+        let signals = technical_indicators::detect_signals(&klines, &indicators, &config);
+
+        alerts.extend(
+            signals
+                .into_iter()
+                .filter(|s| s.strength >= config.alert_min_strength && s.date == last_date)
+                .map(|s| SignalAlert {
+                    code: stock.code.clone(),
+                    name: stock.name.clone(),
+                    signal_type: s.signal_type,
+                    direction: s.direction,
+                    description: s.description,
+                    strength: s.strength,
+                    date: s.date,
+                    created_at: created_at.clone(),
+                }),
+        );
+    }
+
+    let inserted = db.save_signal_alerts(&alerts)?;
+    log::info!("[signal_alert] scanned {} stocks, {} new alerts", stocks.len(), inserted.len());
+    Ok(inserted)
+}
+
+/// 后台任务：交易日收盘后扫描一次自选股，新信号通过 signal-alert 事件推送给前端，并推送到已配置的消息目标
+pub fn signal_scan_job() -> JobSpec {
+    JobSpec {
+        id: "signal_scan",
+        name: "自选股信号扫描",
+        trigger: JobTrigger::DailyAfter(SCAN_AFTER),
+        interval_secs: CHECK_INTERVAL_SECS,
+        run: |app| Box::pin(async move {
+            let db = &app.state::<AppState>().db;
+            let alerts = scan_watchlist_signals(db).await?;
+            if !alerts.is_empty() {
+                let _ = app.emit(SIGNAL_ALERT_EVENT, &alerts);
+                let (title, content) = notifier::format_signal_alerts(&alerts);
+                notifier::notify(db, notifier::EVENT_SIGNAL_ALERT, &title, &content).await;
+            }
+            Ok(Some(format!("新信号 {} 条", alerts.len())))
+        }),
+    }
+}
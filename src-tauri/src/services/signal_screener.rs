@@ -0,0 +1,94 @@
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+
+use crate::db::database::Database;
+use crate::models::watchlist::SignalScreenHit;
+use crate::services::history_kline::HistoryKlineService;
+use crate::services::market_scanner::MarketScanner;
+use crate::services::technical_store::{self, STORE_SIGNAL_DAYS};
+
+/// 形态选股：基于本地日线预计算的 technical_daily，返回最近 within_days 个交易日内触发指定信号的股票。
+///
+/// signal 可以是信号类型（如 macd_bottom_divergence），也可以是信号描述中的关键词（如 "MACD底背离"）。
+/// 指定 board_code（如 BK0477）时只扫描该板块成分股，并先增量同步成分股日线；
+/// 否则扫描本地已缓存日线的全部股票，不发起额外的K线请求。within_days 最多 STORE_SIGNAL_DAYS 日。
+pub async fn screen_by_signal(
+    db: &Database,
+    signal: &str,
+    board_code: Option<&str>,
+    within_days: usize,
+) -> Result<Vec<SignalScreenHit>> {
+    let signal = signal.trim();
+    if signal.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mut names: HashMap<String, String> = HashMap::new();
+    let board_codes: Option<Vec<String>> = match board_code {
+        Some(board) => {
+            let scanner = MarketScanner::new()?;
+            let stocks = scanner.fetch_board_stocks(board).await?;
+            let kline_service = HistoryKlineService::new()?;
+            for stock in &stocks {
+                if let Err(e) = kline_service.sync_daily_history(db, &stock.code).await {
+                    log::warn!("[signal_screener] sync kline failed for {}: {}", stock.code, e);
+                }
+            }
+            Some(stocks.into_iter().map(|s| {
+                names.insert(s.code.clone(), s.name);
+                s.code
+            }).collect())
+        }
+        None => None,
+    };
+
+    // 指标已是最新的股票不会重算，夜间任务跑过之后这里基本是空操作
+    technical_store::refresh_technical_daily(db, board_codes.as_deref(), false)?;
+
+    let within_days = within_days.clamp(1, STORE_SIGNAL_DAYS);
+    let cutoff = match db.get_recent_trade_dates(within_days)?.pop() {
+        Some(date) => date,
+        None => return Ok(vec![]),
+    };
+    let universe: Option<HashSet<&String>> = board_codes.as_ref().map(|codes| codes.iter().collect());
+
+    let records = db.get_all_technical_daily()?;
+    let scanned = records.len();
+    let mut hits = Vec::new();
+    for record in records {
+        if universe.as_ref().is_some_and(|u| !u.contains(&record.code)) {
+            continue;
+        }
+        let matched = record.signals.iter().filter(|s| {
+            s.date >= cutoff && (s.signal_type == signal || s.description.contains(signal))
+        });
+        for s in matched {
+            hits.push(SignalScreenHit {
+                code: record.code.clone(),
+                name: names.get(&record.code).cloned().unwrap_or_default(),
+                signal_type: s.signal_type.clone(),
+                direction: s.direction.clone(),
+                description: s.description.clone(),
+                strength: s.strength,
+                date: s.date.clone(),
+                close: record.close,
+                change_pct: record.change_pct,
+            });
+        }
+    }
+
+    // 全市场模式下本地缓存没有名称，只为命中的股票补全
+    let missing: Vec<String> = hits.iter().filter(|h| h.name.is_empty()).map(|h| h.code.clone()).collect();
+    if !missing.is_empty() {
+        if let Ok(quotes) = MarketScanner::new()?.fetch_stocks_by_codes(&missing).await {
+            names.extend(quotes.into_iter().map(|q| (q.code, q.name)));
+            for hit in hits.iter_mut().filter(|h| h.name.is_empty()) {
+                hit.name = names.get(&hit.code).cloned().unwrap_or_default();
+            }
+        }
+    }
+
+    hits.sort_by(|a, b| b.date.cmp(&a.date).then(b.strength.cmp(&a.strength)));
+    log::info!("[signal_screener] screen_by_signal signal={} scanned={} hits={}", signal, scanned, hits.len());
+    Ok(hits)
+}
@@ -3,6 +3,17 @@ use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE, ORIGIN, REFERER, USE
 use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value;
 use std::time::Duration;
+use tauri::Manager;
+
+use crate::db::database::Database;
+use crate::AppState;
+use crate::services::job_scheduler::{JobSpec, JobTrigger};
+use crate::utils::http::SendLogged;
+
+/// 自动获取 qgqp_b_id 时最多尝试的候选数
+const FINGERPRINT_ATTEMPTS: usize = 3;
+/// 用于校验 qgqp_b_id 的探测条件
+const FINGERPRINT_PROBE: &str = "今日涨停";
 
 /// 东财 API 的 code 字段可能是字符串 "100" 或数字 100，统一反序列化为 i32
 fn deserialize_string_or_i32<'de, D>(deserializer: D) -> std::result::Result<i32, D::Error>
@@ -128,7 +139,7 @@ impl SmartStockService {
     pub async fn search_stock(keyword: &str, page_size: usize, qgqp_b_id: &str) -> Result<SmartStockResponse> {
         if qgqp_b_id.is_empty() {
             return Err(anyhow!(
-                "请先配置东财用户标识（qgqp_b_id）：\n\
+                "请先配置东财用户标识（qgqp_b_id）：可在设置中点击「自动获取」，或手动获取：\n\
                  1. 打开浏览器访问 https://xuangu.eastmoney.com\n\
                  2. 按 F12 打开开发者工具 → 网络面板\n\
                  3. 随便点开一个请求，复制 Cookie 中 qgqp_b_id 的值\n\
@@ -140,7 +151,7 @@ impl SmartStockService {
         let body = Self::build_body(keyword, page_size, qgqp_b_id);
 
         let url = "https://np-tjxg-g.eastmoney.com/api/smart-tag/stock/v3/pw/search-code";
-        let resp = client.post(url).json(&body).send().await?;
+        let resp = client.post(url).json(&body).send_logged().await?;
         let status = resp.status();
         let text = resp.text().await?;
 
@@ -166,7 +177,7 @@ impl SmartStockService {
         let body = Self::build_body(keyword, page_size, qgqp_b_id);
 
         let url = "https://np-tjxg-b.eastmoney.com/api/smart-tag/bkc/v3/pw/search-code";
-        let resp = client.post(url).json(&body).send().await?;
+        let resp = client.post(url).json(&body).send_logged().await?;
         let status = resp.status();
         let text = resp.text().await?;
 
@@ -202,7 +213,7 @@ impl SmartStockService {
             .gzip(true)
             .build()?;
 
-        let resp = client.get(&url).send().await?;
+        let resp = client.get(&url).send_logged().await?;
         let text = resp.text().await?;
 
         let response: HotStrategyResponse = serde_json::from_str(&text)
@@ -214,4 +225,59 @@ impl SmartStockService {
             Ok(vec![])
         }
     }
+
+    /// 生成浏览器同格式的设备指纹（32 位十六进制）
+    pub fn generate_fingerprint() -> String {
+        uuid::Uuid::new_v4().simple().to_string()
+    }
+
+    /// 用一次最小的选股请求校验 qgqp_b_id 是否被东财接受
+    pub async fn validate_fingerprint(qgqp_b_id: &str) -> Result<bool> {
+        if qgqp_b_id.is_empty() {
+            return Ok(false);
+        }
+        let resp = Self::search_stock(FINGERPRINT_PROBE, 1, qgqp_b_id).await?;
+        Ok(resp.code == 100)
+    }
+}
+
+/// 获取可用的 qgqp_b_id 并写入设置。force 为 false 且现有值校验通过时直接返回现有值
+pub async fn acquire_fingerprint(db: &Database, force: bool) -> Result<String> {
+    let mut settings = db.load_settings()?;
+    if !force && SmartStockService::validate_fingerprint(&settings.qgqp_b_id).await.unwrap_or(false) {
+        return Ok(settings.qgqp_b_id);
+    }
+
+    for attempt in 1..=FINGERPRINT_ATTEMPTS {
+        let candidate = SmartStockService::generate_fingerprint();
+        match SmartStockService::validate_fingerprint(&candidate).await {
+            Ok(true) => {
+                settings.qgqp_b_id = candidate.clone();
+                db.save_settings(&settings)?;
+                log::info!("[smart_stock] acquired qgqp_b_id on attempt {}", attempt);
+                return Ok(candidate);
+            }
+            Ok(false) => log::warn!("[smart_stock] candidate qgqp_b_id rejected, attempt {}", attempt),
+            Err(e) => log::warn!("[smart_stock] validate qgqp_b_id failed, attempt {}: {}", attempt, e),
+        }
+    }
+    Err(anyhow!("自动获取东财用户标识失败，请稍后重试或在设置中手动填写 qgqp_b_id"))
+}
+
+/// 启动任务：尚未配置 qgqp_b_id 时在后台自动获取
+pub fn fingerprint_bootstrap_job() -> JobSpec {
+    JobSpec {
+        id: "fingerprint_bootstrap",
+        name: "获取东财用户标识",
+        trigger: JobTrigger::Startup,
+        interval_secs: 0,
+        run: |app| Box::pin(async move {
+            let state = app.state::<AppState>();
+            if !state.db.load_settings()?.qgqp_b_id.is_empty() {
+                return Ok(None);
+            }
+            acquire_fingerprint(&state.db, false).await?;
+            Ok(Some("已获取 qgqp_b_id".to_string()))
+        }),
+    }
 }
@@ -0,0 +1,97 @@
+use anyhow::Result;
+use chrono::Local;
+use tauri::Manager;
+
+use crate::db::database::Database;
+use crate::models::settings::SignalConfig;
+use crate::models::watchlist::WatchlistSnapshot;
+use crate::services::market_scanner::MarketScanner;
+use crate::services::technical_indicators;
+use crate::services::job_scheduler::{JobSpec, JobTrigger};
+use crate::AppState;
+
+/// 收盘后多久开始归档（HHMM），留出行情源结算时间
+const ARCHIVE_AFTER: u32 = 1505;
+/// 后台检查间隔
+const CHECK_INTERVAL_SECS: u64 = 600;
+
+/// 归档当前自选股的收盘快照（行情 + 资金 + 本地日线计算的技术面摘要），返回归档条数
+pub async fn archive_watchlist(db: &Database) -> Result<usize> {
+    let stocks = db.get_watchlist_stocks()?;
+    if stocks.is_empty() {
+        return Ok(0);
+    }
+    let codes: Vec<String> = stocks.iter().map(|s| s.code.clone()).collect();
+    let scanner = MarketScanner::new()?;
+    let quotes = scanner.fetch_stocks_by_codes(&codes).await?;
+
+    let date = Local::now().format("%Y-%m-%d").to_string();
+    let created_at = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    let config = db.load_settings()?.signal_config;
+    let mut snapshots = Vec::with_capacity(quotes.len());
+    for q in &quotes {
+        let (ma_alignment, tech_summary) = tech_summary_from_cache(db, &q.code, &config);
+        snapshots.push(WatchlistSnapshot {
+            code: q.code.clone(),
+            name: q.name.clone(),
+            date: date.clone(),
+            price: q.price,
+            change_pct: q.change_pct,
+            turnover_rate: q.turnover_rate,
+            volume_ratio: q.volume_ratio,
+            amount: q.amount,
+            pe_ttm: q.pe_ttm,
+            pb: q.pb,
+            total_market_cap: q.total_market_cap,
+            main_net_inflow: q.main_net_inflow,
+            main_net_pct: q.main_net_pct,
+            pct_5d: q.pct_5d,
+            pct_20d: q.pct_20d,
+            ma_alignment,
+            tech_summary,
+            created_at: created_at.clone(),
+        });
+    }
+
+    db.save_watchlist_snapshots(&snapshots)?;
+    log::info!("[snapshot_archiver] archived {} watchlist snapshots for {}", snapshots.len(), date);
+    Ok(snapshots.len())
+}
+
+/// 基于本地日线缓存计算均线排列与技术摘要；缓存不足时返回空
+fn tech_summary_from_cache(db: &Database, code: &str, config: &SignalConfig) -> (String, String) {
+    let history = match db.get_daily_history_asc(code, 120) {
+        Ok(h) if h.len() >= 20 => h,
+        _ => return (String::new(), String::new()),
+    };
+    let klines = technical_indicators::klines_from_history(&history);
+    let indicators = technical_indicators::compute_indicators(&klines);
+    let signals = technical_indicators::detect_signals(&klines, &indicators, config);
+    let ma_alignment = technical_indicators::determine_ma_alignment(&indicators);
+    let volume_price = technical_indicators::determine_volume_price_relation(&klines);
+    let summary = technical_indicators::generate_summary(&ma_alignment, &volume_price, &signals);
+    let alignment = serde_json::to_value(&ma_alignment)
+        .ok()
+        .and_then(|v| v.as_str().map(|s| s.to_string()))
+        .unwrap_or_default();
+    (alignment, summary)
+}
+
+/// 后台任务：交易日 15:05 之后，当日尚未归档时执行一次收盘归档
+pub fn eod_archive_job() -> JobSpec {
+    JobSpec {
+        id: "eod_archive",
+        name: "收盘快照归档",
+        trigger: JobTrigger::DailyAfter(ARCHIVE_AFTER),
+        interval_secs: CHECK_INTERVAL_SECS,
+        run: |app| Box::pin(async move {
+            let state = app.state::<AppState>();
+            let today = Local::now().format("%Y-%m-%d").to_string();
+            if state.db.get_latest_snapshot_date()?.as_deref() == Some(today.as_str()) {
+                return Ok(None);
+            }
+            let count = archive_watchlist(&state.db).await?;
+            Ok(Some(format!("归档 {} 只自选股", count)))
+        }),
+    }
+}
@@ -1,7 +1,8 @@
 use anyhow::{Result, anyhow};
 use crate::models::stock::{StockInfo, KLineData};
-use crate::utils::encoding::gb18030_to_utf8;
-use crate::utils::http::build_stock_client;
+use crate::services::schema_guard;
+use crate::utils::encoding::{clean_text, response_text};
+use crate::utils::http::{build_stock_client, SendLogged};
 
 #[allow(dead_code)]
 const SINA_STOCK_URL: &str = "http://hq.sinajs.cn/rn={}&list={}";
@@ -27,9 +28,8 @@ impl StockDataService {
         let ts = chrono::Utc::now().timestamp();
         let url = format!("http://hq.sinajs.cn/rn={}&list={}", ts, code_list);
 
-        let resp = self.client.get(&url).send().await?;
-        let bytes = resp.bytes().await?;
-        let text = gb18030_to_utf8(&bytes);
+        let resp = self.client.get(&url).send_logged().await?;
+        let text = response_text(resp).await?;
 
         let mut results = Vec::new();
         for line in text.lines() {
@@ -52,8 +52,8 @@ impl StockDataService {
         let ts = chrono::Utc::now().timestamp();
         let url = format!("http://qt.gtimg.cn/?_={}&q={}", ts, code_list);
 
-        let resp = self.client.get(&url).send().await?;
-        let text = resp.text().await?;
+        let resp = self.client.get(&url).send_logged().await?;
+        let text = response_text(resp).await?;
 
         let mut results = Vec::new();
         for line in text.lines() {
@@ -73,7 +73,7 @@ impl StockDataService {
             "{}?symbol={}&scale={}&ma=yes&datalen={}",
             SINA_KLINE_URL, code, scale, days
         );
-        let resp = self.client.get(&url).send().await?;
+        let resp = self.client.get(&url).send_logged().await?;
         let text = resp.text().await?;
 
         let data: Vec<SinaKLineItem> = serde_json::from_str(&text)
@@ -92,14 +92,6 @@ impl StockDataService {
             })
             .collect())
     }
-
-    pub async fn get_realtime_batch(&self, codes: &[String], use_sina: bool) -> Result<Vec<StockInfo>> {
-        if use_sina {
-            self.get_realtime_data_sina(codes).await
-        } else {
-            self.get_realtime_data_tencent(codes).await
-        }
-    }
 }
 
 #[derive(serde::Deserialize)]
@@ -138,13 +130,13 @@ fn parse_sina_shsz_line(line: &str) -> Option<StockInfo> {
     }
 
     let parts: Vec<&str> = data_str.split(',').collect();
-    if parts.len() < 32 {
+    if !schema_guard::check_field_count(schema_guard::SINA_QUOTE, parts.len(), 32) {
         return None;
     }
 
     Some(StockInfo {
         code,
-        name: parts[0].to_string(),
+        name: clean_text(parts[0]),
         open: parse_float(parts[1]),
         pre_close: parse_float(parts[2]),
         price: parse_float(parts[3]),
@@ -200,7 +192,7 @@ fn parse_tencent_shsz_line(line: &str) -> Option<StockInfo> {
     }
 
     let parts: Vec<&str> = data_str.split('~').collect();
-    if parts.len() < 34 {
+    if !schema_guard::check_field_count(schema_guard::TENCENT_QUOTE, parts.len(), 34) {
         return None;
     }
 
@@ -228,7 +220,7 @@ fn parse_tencent_shsz_line(line: &str) -> Option<StockInfo> {
 
     Some(StockInfo {
         code: code_raw,
-        name: parts[1].to_string(),
+        name: clean_text(parts[1]),
         price: parse_float(parts[3]),
         pre_close: parse_float(parts[4]),
         open: parse_float(parts[5]),
@@ -0,0 +1,224 @@
+use anyhow::{anyhow, Result};
+use std::collections::HashSet;
+use tauri::{Emitter, Manager};
+
+use crate::AppState;
+use crate::db::database::Database;
+use crate::models::ai::{AIAnalysisResult, AIConfig};
+use crate::models::schedule::{ScheduleRun, StockSchedule};
+use crate::services::ai_service::AIService;
+use crate::services::chart_vision;
+use crate::services::job_scheduler::{self, JobSpec, JobTrigger};
+use crate::services::stock_tools::ToolContext;
+
+/// 前端监听的快检结果事件名
+pub const SCHEDULE_RESULT_EVENT: &str = "stock-schedule-result";
+/// ai_analysis 表中快检记录的 question 前缀，后接用户设定的问题
+const QUESTION_PREFIX: &str = "盯盘：";
+/// 到点后多少分钟内仍补跑（应用晚启动或机器休眠），超过则当日跳过，避免收盘后才给出尾盘建议
+const GRACE_MINUTES: u32 = 30;
+const CHECK_INTERVAL_SECS: u64 = 60;
+
+/// 快检记录在 ai_analysis 中的 question，同一股票同一问题的记录构成该任务的历史
+pub fn analysis_question(question: &str) -> String {
+    format!("{}{}", QUESTION_PREFIX, question.trim())
+}
+
+/// 规范化执行时间：接受 "9:30"、"09:30"、"0930"，返回 "HH:MM"
+pub fn normalize_time(input: &str) -> Result<String> {
+    let input = input.trim();
+    let (h, m) = match input.split_once(':') {
+        Some((h, m)) => (h, m),
+        None if input.len() == 4 => input.split_at(2),
+        None => return Err(anyhow!("时间格式应为 HH:MM")),
+    };
+    let h: u32 = h.trim().parse().map_err(|_| anyhow!("时间格式应为 HH:MM"))?;
+    let m: u32 = m.trim().parse().map_err(|_| anyhow!("时间格式应为 HH:MM"))?;
+    if h > 23 || m > 59 {
+        return Err(anyhow!("时间超出范围: {}", input));
+    }
+    Ok(format!("{:02}:{:02}", h, m))
+}
+
+fn minutes_of(time: &str) -> Option<u32> {
+    let (h, m) = time.split_once(':')?;
+    Some(h.parse::<u32>().ok()? * 60 + m.parse::<u32>().ok()?)
+}
+
+/// 当日是否应执行：已启用、今天未执行过、当前时间在 [设定时间, 设定时间 + 宽限) 内
+pub fn is_due(schedule: &StockSchedule, today: &str, now: &str) -> bool {
+    if !schedule.enabled || schedule.last_run_date == today {
+        return false;
+    }
+    match (minutes_of(&schedule.time), minutes_of(now)) {
+        (Some(at), Some(now)) => now >= at && now < at + GRACE_MINUTES,
+        _ => false,
+    }
+}
+
+/// 忽略空白与列表符号后比较，返回 current 中新增或变化的非空行号
+pub fn changed_lines(previous: &str, current: &str) -> Vec<usize> {
+    let normalize = |line: &str| -> String {
+        line.trim()
+            .trim_start_matches(['-', '*', '+', '#', '>'])
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != '*')
+            .collect()
+    };
+    let seen: HashSet<String> = previous.lines().map(normalize).filter(|l| !l.is_empty()).collect();
+    current
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| {
+            let line = normalize(line);
+            !line.is_empty() && !seen.contains(&line)
+        })
+        .map(|(i, _)| i)
+        .collect()
+}
+
+fn to_run(schedule_id: &str, analysis: AIAnalysisResult, previous: Option<&AIAnalysisResult>) -> ScheduleRun {
+    let changed_lines = previous.map(|p| changed_lines(&p.content, &analysis.content)).unwrap_or_default();
+    ScheduleRun {
+        schedule_id: schedule_id.to_string(),
+        analysis,
+        previous_id: previous.map(|p| p.id.clone()),
+        changed_lines,
+    }
+}
+
+/// 执行一次快检：获取真实行情与指标，连同上一次结论发给模型，结果追加到该股分析历史
+pub async fn run_check_in(db: &Database, config: &AIConfig, tool_ctx: &ToolContext, schedule: &StockSchedule) -> Result<ScheduleRun> {
+    let question = analysis_question(&schedule.question);
+    let previous = db.get_ai_analysis_by_question(&schedule.code, &question, 1)?.into_iter().next();
+    let context = chart_vision::stock_context(&schedule.code, tool_ctx).await;
+    let (content, usage) = AIService::stock_check_in(
+        config,
+        &schedule.code,
+        &schedule.name,
+        &schedule.question,
+        &context,
+        previous.as_ref().map(|p| p.content.as_str()),
+    ).await?;
+    if let Some(usage) = usage {
+        let _ = db.record_token_usage(&config.model_name, usage.prompt_tokens, usage.completion_tokens);
+    }
+
+    let analysis = AIAnalysisResult {
+        id: uuid::Uuid::new_v4().to_string(),
+        code: schedule.code.clone(),
+        name: schedule.name.clone(),
+        model_name: config.model_name.clone(),
+        question,
+        content,
+        created_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+    };
+    db.save_ai_analysis(&analysis)?;
+    log::info!("[stock_schedule] run_check_in code={} id={}", schedule.code, schedule.id);
+    Ok(to_run(&schedule.id, analysis, previous.as_ref()))
+}
+
+/// 某个快检任务的历史结果（倒序），每条均标注相对上一次的变化行
+pub fn get_runs(db: &Database, schedule_id: &str, limit: usize) -> Result<Vec<ScheduleRun>> {
+    let schedule = db
+        .get_stock_schedules()?
+        .into_iter()
+        .find(|s| s.id == schedule_id)
+        .ok_or_else(|| anyhow!("盯盘任务不存在"))?;
+    let history = db.get_ai_analysis_by_question(&schedule.code, &analysis_question(&schedule.question), limit + 1)?;
+    let runs = (0..history.len().min(limit))
+        .map(|i| to_run(schedule_id, history[i].clone(), history.get(i + 1)))
+        .collect();
+    Ok(runs)
+}
+
+/// 盯盘助手定时任务：每分钟检查到点的快检，交易日才执行，每个任务每日一次
+pub fn stock_schedule_job() -> JobSpec {
+    JobSpec {
+        id: "stock_schedule",
+        name: "盯盘助手",
+        trigger: JobTrigger::Interval,
+        interval_secs: CHECK_INTERVAL_SECS,
+        run: |app| Box::pin(async move {
+            let db = &app.state::<AppState>().db;
+            let now = chrono::Local::now();
+            let today = now.format("%Y-%m-%d").to_string();
+            let time = now.format("%H:%M").to_string();
+            let due: Vec<StockSchedule> = db
+                .get_stock_schedules()?
+                .into_iter()
+                .filter(|s| is_due(s, &today, &time))
+                .collect();
+            if due.is_empty() || !job_scheduler::is_trading_day().await {
+                return Ok(None);
+            }
+            let settings = db.load_settings()?;
+            let Some(config) = settings.active_ai_config() else {
+                log::warn!("[stock_schedule] {} check-ins due but no AI config", due.len());
+                return Ok(None);
+            };
+            let tool_ctx = ToolContext::from_settings(&settings);
+
+            let mut done = 0;
+            for schedule in &due {
+                // 先标记当日已执行，失败也不在宽限期内反复重试
+                db.set_schedule_last_run(&schedule.id, &today)?;
+                match run_check_in(db, &config, &tool_ctx, schedule).await {
+                    Ok(run) => {
+                        done += 1;
+                        let _ = app.emit(SCHEDULE_RESULT_EVENT, &run);
+                    }
+                    Err(e) => log::warn!("[stock_schedule] check-in {} failed: {}", schedule.code, e),
+                }
+            }
+            Ok(Some(format!("快检 {}/{} 只", done, due.len())))
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(time: &str, last_run_date: &str) -> StockSchedule {
+        StockSchedule {
+            id: "1".to_string(),
+            code: "sh600519".to_string(),
+            name: "贵州茅台".to_string(),
+            time: time.to_string(),
+            question: "分析尾盘该不该走".to_string(),
+            enabled: true,
+            last_run_date: last_run_date.to_string(),
+            created_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_normalize_time() {
+        assert_eq!(normalize_time("9:30").unwrap(), "09:30");
+        assert_eq!(normalize_time("1430").unwrap(), "14:30");
+        assert!(normalize_time("24:00").is_err());
+        assert!(normalize_time("abc").is_err());
+    }
+
+    #[test]
+    fn test_is_due() {
+        let s = schedule("14:30", "2024-06-05");
+        assert!(!is_due(&s, "2024-06-06", "14:29"));
+        assert!(is_due(&s, "2024-06-06", "14:30"));
+        assert!(is_due(&s, "2024-06-06", "14:59"));
+        assert!(!is_due(&s, "2024-06-06", "15:00"));
+        // 当日已执行
+        assert!(!is_due(&s, "2024-06-05", "14:30"));
+        let mut disabled = schedule("14:30", "");
+        disabled.enabled = false;
+        assert!(!is_due(&disabled, "2024-06-06", "14:30"));
+    }
+
+    #[test]
+    fn test_changed_lines() {
+        let previous = "较上次：首次快检\n- 结论：持有\n支撑 10.5";
+        let current = "较上次：结论转为减仓\n\n* 结论：减仓\n支撑  10.5";
+        assert_eq!(changed_lines(previous, current), vec![0, 2]);
+    }
+}
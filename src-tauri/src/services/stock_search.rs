@@ -0,0 +1,194 @@
+use crate::models::stock::StockSymbol;
+use crate::services::stock_data::code_to_pure;
+use crate::services::symbol_table::normalize_name;
+
+/// 多音字词组的读音修正（deunicode 按单字取默认读音，股票名称中常见的词组需要单独处理）
+const PHRASE_PINYIN: [(&str, [&str; 2]); 8] = [
+    ("银行", ["YIN", "HANG"]),
+    ("重庆", ["CHONG", "QING"]),
+    ("厦门", ["XIA", "MEN"]),
+    ("西藏", ["XI", "ZANG"]),
+    ("蚌埠", ["BENG", "BU"]),
+    ("长春", ["CHANG", "CHUN"]),
+    ("长江", ["CHANG", "JIANG"]),
+    ("长城", ["CHANG", "CHENG"]),
+];
+/// 单字读音修正
+const CHAR_PINYIN: [(char, &str); 3] = [('万', "WAN"), ('乐', "LE"), ('朝', "CHAO")];
+
+/// 单只股票的检索键
+struct Entry {
+    code: String,
+    name: String,
+    initials: String,
+    pinyin: String,
+}
+
+/// 本地股票检索索引：支持 6 位代码、名称子串、拼音首字母（gzmt → 贵州茅台）与全拼
+pub struct StockSearchIndex {
+    entries: Vec<Entry>,
+}
+
+impl StockSearchIndex {
+    pub fn new(symbols: &[StockSymbol]) -> Self {
+        let entries = symbols
+            .iter()
+            .map(|s| {
+                let name = normalize_name(&s.name);
+                let syllables = to_pinyin(&name);
+                Entry {
+                    code: code_to_pure(&s.code),
+                    initials: syllables.iter().filter_map(|p| p.chars().next()).collect(),
+                    pinyin: syllables.concat(),
+                    name,
+                }
+            })
+            .collect();
+        Self { entries }
+    }
+
+    /// 检索并按匹配程度排序，返回 symbols 中的下标。同分时名称短的在前
+    pub fn search(&self, keyword: &str, limit: usize) -> Vec<usize> {
+        let keyword = normalize_keyword(keyword);
+        if keyword.is_empty() {
+            return vec![];
+        }
+        let mut hits: Vec<(u32, usize)> = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(i, e)| score(e, &keyword).map(|s| (s, i)))
+            .collect();
+        hits.sort_by_key(|&(s, i)| (std::cmp::Reverse(s), self.entries[i].name.chars().count(), i));
+        hits.into_iter().take(limit).map(|(_, i)| i).collect()
+    }
+}
+
+/// 关键词规范化：去掉 sh/sz/bj 前缀（后面跟数字时），其余同名称规范化
+fn normalize_keyword(keyword: &str) -> String {
+    let keyword = normalize_name(keyword);
+    let lower = keyword.to_lowercase();
+    for prefix in ["sh", "sz", "bj"] {
+        if let Some(rest) = lower.strip_prefix(prefix) {
+            if !rest.is_empty() && rest.chars().all(|c| c.is_ascii_digit()) {
+                return rest.to_string();
+            }
+        }
+    }
+    keyword
+}
+
+/// 匹配得分，未命中返回 None
+fn score(e: &Entry, keyword: &str) -> Option<u32> {
+    if e.code == keyword || e.name == keyword {
+        return Some(100);
+    }
+    if keyword.chars().all(|c| c.is_ascii_digit()) {
+        return if e.code.starts_with(keyword) {
+            Some(90)
+        } else if e.code.contains(keyword) {
+            Some(40)
+        } else {
+            None
+        };
+    }
+    if e.initials == keyword {
+        Some(95)
+    } else if e.initials.starts_with(keyword) {
+        Some(80)
+    } else if e.name.starts_with(keyword) {
+        Some(75)
+    } else if e.pinyin.starts_with(keyword) {
+        Some(70)
+    } else if e.name.contains(keyword) {
+        Some(60)
+    } else if e.initials.contains(keyword) {
+        Some(50)
+    } else if e.pinyin.contains(keyword) {
+        Some(30)
+    } else {
+        None
+    }
+}
+
+/// 名称转拼音音节（大写），字母数字原样保留为单独音节，其余符号忽略
+fn to_pinyin(name: &str) -> Vec<String> {
+    let chars: Vec<char> = name.chars().collect();
+    let mut out = Vec::with_capacity(chars.len());
+    let mut i = 0;
+    while i < chars.len() {
+        if let Some((_, syllables)) = PHRASE_PINYIN
+            .iter()
+            .find(|(p, _)| chars[i..].iter().take(2).copied().eq(p.chars()))
+        {
+            out.extend(syllables.iter().map(|s| s.to_string()));
+            i += 2;
+            continue;
+        }
+        let c = chars[i];
+        if c.is_ascii_alphanumeric() {
+            out.push(c.to_ascii_uppercase().to_string());
+        } else {
+            let p = char_pinyin(c);
+            if !p.is_empty() {
+                out.push(p);
+            }
+        }
+        i += 1;
+    }
+    out
+}
+
+fn char_pinyin(c: char) -> String {
+    if let Some((_, p)) = CHAR_PINYIN.iter().find(|(ch, _)| *ch == c) {
+        return p.to_string();
+    }
+    deunicode::deunicode_char(c)
+        .unwrap_or("")
+        .chars()
+        .filter(|c| c.is_ascii_alphabetic())
+        .collect::<String>()
+        .to_uppercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index() -> (Vec<StockSymbol>, StockSearchIndex) {
+        let symbols: Vec<StockSymbol> = [
+            ("sh600519", "贵州茅台"),
+            ("sz000001", "平安银行"),
+            ("sh601318", "中国平安"),
+            ("sz000002", "万科Ａ"),
+            ("sh600132", "重庆啤酒"),
+        ]
+        .iter()
+        .map(|(code, name)| StockSymbol { code: code.to_string(), name: name.to_string() })
+        .collect();
+        let index = StockSearchIndex::new(&symbols);
+        (symbols, index)
+    }
+
+    fn first(keyword: &str) -> Option<String> {
+        let (symbols, index) = index();
+        index.search(keyword, 5).first().map(|&i| symbols[i].name.clone())
+    }
+
+    #[test]
+    fn test_search() {
+        assert_eq!(first("gzmt").as_deref(), Some("贵州茅台"));
+        assert_eq!(first("GZMT").as_deref(), Some("贵州茅台"));
+        assert_eq!(first("600519").as_deref(), Some("贵州茅台"));
+        assert_eq!(first("sh6005").as_deref(), Some("贵州茅台"));
+        assert_eq!(first("payh").as_deref(), Some("平安银行"));
+        assert_eq!(first("wk").as_deref(), Some("万科Ａ"));
+        assert_eq!(first("cqpj").as_deref(), Some("重庆啤酒"));
+        assert_eq!(first("maotai").as_deref(), Some("贵州茅台"));
+        // 名称子串：平安银行与中国平安都命中，名称以关键词开头的在前
+        let (symbols, index) = index();
+        let names: Vec<&str> = index.search("平安", 5).iter().map(|&i| symbols[i].name.as_str()).collect();
+        assert_eq!(names, vec!["平安银行", "中国平安"]);
+        assert!(index.search("xyz", 5).is_empty());
+    }
+}
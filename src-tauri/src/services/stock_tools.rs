@@ -1,13 +1,81 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
 use anyhow::Result;
+use chrono::Datelike;
+use futures::stream::{self, StreamExt};
 use serde_json::Value;
 
-use crate::services::history_kline::HistoryKlineService;
+use crate::db::database::Database;
+use crate::services::announcement_study;
+use crate::services::closing_auction;
+use crate::services::history_kline::{self, HistoryKlineService};
+use crate::services::index_constituents;
+use crate::services::board_rotation;
+use crate::services::market_breadth;
+use crate::services::market_heatmap;
 use crate::services::market_scanner::MarketScanner;
 use crate::services::technical_indicators;
+use crate::services::f10_service;
+use crate::services::fund_flow;
 use crate::services::news_service;
+use crate::services::peer_comparison;
+use crate::services::risk_metrics;
+use crate::services::rps;
+use crate::services::seasonality;
 use crate::services::smart_stock::SmartStockService;
-use crate::models::watchlist::KlineItem;
+use crate::services::theme_exposure;
+use crate::services::valuation;
+use crate::models::settings::{AppSettings, SignalConfig};
+use crate::models::stock::{BoardQuote, BoardRankRecord, BoardRotationItem, ClosingAuction, CumulativeFlow, FundFlowBreakdown, MarketBreadth, MarketRegime};
+use crate::models::watchlist::{GapAnalysis, KlineItem, SeasonalityBucket};
 use crate::utils::http;
+use crate::utils::units::{format_amount, format_lots};
+use crate::utils::http::SendLogged;
+
+/// 提供给 get_market_breadth 工具的历史宽度天数
+const BREADTH_HISTORY_DAYS: usize = 10;
+
+/// 工具执行上下文：来自用户设置、工具实现需要的参数
+#[derive(Debug, Clone, Default)]
+pub struct ToolContext {
+    /// 东财用户标识，用于 NLP 选股 API 的 fingerprint 字段
+    pub qgqp_b_id: String,
+    /// 技术信号检测参数
+    pub signal_config: SignalConfig,
+    /// 近期市场宽度记录（按日期升序），由选股命令从数据库读取后填入
+    pub breadth_history: Vec<MarketBreadth>,
+    /// 近期行业与概念板块的收盘排名记录，由选股命令从数据库读取后填入
+    pub board_rank_history: Vec<BoardRankRecord>,
+    /// 当日市场状态，由选股命令计算后填入并写入系统提示词
+    pub market_regime: Option<MarketRegime>,
+}
+
+impl ToolContext {
+    pub fn from_settings(settings: &AppSettings) -> Self {
+        Self {
+            qgqp_b_id: settings.qgqp_b_id.clone(),
+            signal_config: settings.signal_config.clone(),
+            breadth_history: Vec::new(),
+            board_rank_history: Vec::new(),
+            market_regime: None,
+        }
+    }
+
+    /// 从数据库读取近期市场宽度与板块排名记录（选股工具 get_market_breadth / get_board_rotation 使用）
+    pub fn load_market_history(&mut self, db: &Database) {
+        self.breadth_history = db.get_market_breadth_history(BREADTH_HISTORY_DAYS).unwrap_or_else(|e| {
+            log::warn!("[stock_tools] get_market_breadth_history failed: {}", e);
+            vec![]
+        });
+        for kind in ["industry", "concept"] {
+            match db.get_board_rank_history(kind, board_rotation::ROTATION_HISTORY_DAYS) {
+                Ok(records) => self.board_rank_history.extend(records),
+                Err(e) => log::warn!("[stock_tools] get_board_rank_history failed: {}", e),
+            }
+        }
+    }
+}
 
 /// AI 可调用的工具定义（OpenAI function calling 格式）— 诊股专用
 pub fn get_tool_definitions() -> Vec<Value> {
@@ -16,7 +84,7 @@ pub fn get_tool_definitions() -> Vec<Value> {
             "type": "function",
             "function": {
                 "name": "get_stock_quote",
-                "description": "获取股票实时行情快照，包括最新价、涨跌幅、PE/PB/ROE、市值、换手率、量比、主力净流入、5日/20日涨幅等多维度数据",
+                "description": "获取股票实时行情快照，包括最新价、涨跌幅、PE/PB/ROE（含近5年估值分位）、市值、换手率、量比、主力净流入、5日/20日涨幅、RPS相对强度排名，以及股息率、现金流收益率、资产负债率、净利润增速等价值/质量因子",
                 "parameters": {
                     "type": "object",
                     "properties": {
@@ -33,18 +101,18 @@ pub fn get_tool_definitions() -> Vec<Value> {
             "type": "function",
             "function": {
                 "name": "get_kline_data",
-                "description": "获取股票历史K线数据（前复权），包括日期、开高低收、成交量、涨跌幅。可选日K或周K，可指定获取最近N根K线",
+                "description": "获取股票或指数的历史K线数据（个股前复权），包括日期、开高低收、成交量、涨跌幅。支持日/周/月K与5/15/30/60分钟K，可指定获取最近N根K线",
                 "parameters": {
                     "type": "object",
                     "properties": {
                         "code": {
                             "type": "string",
-                            "description": "股票代码，如 sh600519、sz000001"
+                            "description": "股票或指数代码，如 sh600519、sz000001、sh000001(上证指数)、sz399006(创业板指)，指数也可直接传名称如 中证500"
                         },
                         "period": {
                             "type": "string",
-                            "enum": ["day", "week"],
-                            "description": "K线周期，day=日K线，week=周K线，默认day"
+                            "enum": ["day", "week", "month", "m5", "m15", "m30", "m60"],
+                            "description": "K线周期，day=日K线，week=周K线，month=月K线，m5~m60=分钟K线，默认day"
                         },
                         "count": {
                             "type": "integer",
@@ -59,17 +127,17 @@ pub fn get_tool_definitions() -> Vec<Value> {
             "type": "function",
             "function": {
                 "name": "get_technical_indicators",
-                "description": "获取股票技术分析指标，包括：MA均线(5/10/20/60)、MACD(DIF/DEA/柱)、KDJ、RSI(6/12/24)、布林带(上/中/下轨)，以及技术信号（金叉/死叉/超买超卖/背离等）、均线排列状态、量价关系",
+                "description": "获取股票或指数的技术分析指标，包括：MA均线(5/10/20/60)、MACD(DIF/DEA/柱)、KDJ、RSI(6/12/24)、布林带(上/中/下轨)、唐奇安通道(20日高低点)、肯特纳通道(EMA20±2ATR)，以及技术信号（金叉/死叉/超买超卖/背离/通道突破等）、均线排列状态、量价关系；未回补跳空缺口与历史回补统计、近60根K线成交量分布（成交密集价位/价值区/高量节点）；日线周期另含20/60日年化波动率、相对沪深300的Beta与近一年最大回撤",
                 "parameters": {
                     "type": "object",
                     "properties": {
                         "code": {
                             "type": "string",
-                            "description": "股票代码，如 sh600519、sz000001"
+                            "description": "股票或指数代码，如 sh600519、sh000300(沪深300)，指数也可直接传名称如 中证500"
                         },
                         "period": {
                             "type": "string",
-                            "enum": ["day", "week"],
+                            "enum": ["day", "week", "month", "m5", "m15", "m30", "m60"],
                             "description": "K线周期，默认day"
                         }
                     },
@@ -81,7 +149,7 @@ pub fn get_tool_definitions() -> Vec<Value> {
             "type": "function",
             "function": {
                 "name": "get_fund_flow",
-                "description": "获取股票资金流向数据，包括主力净流入金额和主力净占比",
+                "description": "获取股票资金流向数据：当日主力净流入与净占比、超大单/大单/中单/小单净额及形态(如超大单流入但大单流出)、盘中累计净额走势，近5/10/20日累计主力与超大单净流入，以及最近一个交易日尾盘集合竞价偏离、竞价成交占比、科创板盘后成交与尾盘抢筹/砸盘标记",
                 "parameters": {
                     "type": "object",
                     "properties": {
@@ -94,11 +162,83 @@ pub fn get_tool_definitions() -> Vec<Value> {
                 }
             }
         }),
+        serde_json::json!({
+            "type": "function",
+            "function": {
+                "name": "get_stock_profile",
+                "description": "获取公司简介、主营业务及最新报告期主营构成（按行业/产品/地区的收入占比与毛利率），用于确认公司实际做什么业务、收入来自哪里",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "code": { "type": "string", "description": "股票代码，如 sh600519、sz000001" }
+                    },
+                    "required": ["code"]
+                }
+            }
+        }),
+        serde_json::json!({
+            "type": "function",
+            "function": {
+                "name": "get_earnings_forecast",
+                "description": "获取机构一致预期：近半年研报汇总的今明两年预测EPS/净利润/预测PE，以及买入/增持/中性/减持/卖出评级分布，用于判断业绩预期与估值匹配度",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "code": { "type": "string", "description": "股票代码，如 sh600519、sz000001" }
+                    },
+                    "required": ["code"]
+                }
+            }
+        }),
+        serde_json::json!({
+            "type": "function",
+            "function": {
+                "name": "get_peer_comparison",
+                "description": "获取同行业可比公司对比表：按市值排序的行业龙头及目标股的PE/PB/ROE/营收增速/市值/20日涨幅，附行业中位数和目标股市值排名。需要横向比较估值或行业地位时一次调用即可，无需逐只查询同行",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "code": { "type": "string", "description": "股票代码，如 sh600519、sz000001" },
+                        "count": { "type": "integer", "description": "返回的可比公司数量，默认10，最多20" }
+                    },
+                    "required": ["code"]
+                }
+            }
+        }),
+        serde_json::json!({
+            "type": "function",
+            "function": {
+                "name": "get_seasonality",
+                "description": "获取个股或指数的历史季节性：近N年各月份的平均涨跌幅与上涨概率、周一至周五的平均涨跌幅，并附所属行业板块的月度统计（如\"该股历史上6月平均上涨2.3%\"），仅作为择时的参考背景",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "code": { "type": "string", "description": "股票/指数代码，如 sh600519、sh000300" },
+                        "years": { "type": "integer", "description": "统计近N年，默认5，最多10" }
+                    },
+                    "required": ["code"]
+                }
+            }
+        }),
+        serde_json::json!({
+            "type": "function",
+            "function": {
+                "name": "get_stock_theme_exposure",
+                "description": "获取个股的题材暴露：所属题材板块按近期快讯提及次数与板块今日涨幅加权（权重之和为1），附驱动各题材的快讯标题，用于判断该股当前主要受哪些题材驱动",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "code": { "type": "string", "description": "股票代码，如 sh600519" }
+                    },
+                    "required": ["code"]
+                }
+            }
+        }),
     ]
 }
 
 /// 执行工具调用，返回 JSON 字符串结果
-pub async fn execute_tool(name: &str, arguments: &str) -> Result<String> {
+pub async fn execute_tool(name: &str, arguments: &str, ctx: &ToolContext) -> Result<String> {
     let args: Value = serde_json::from_str(arguments).unwrap_or(Value::Object(Default::default()));
 
     match name {
@@ -115,12 +255,34 @@ pub async fn execute_tool(name: &str, arguments: &str) -> Result<String> {
         "get_technical_indicators" => {
             let code = args["code"].as_str().unwrap_or("").to_string();
             let period = args["period"].as_str().unwrap_or("day").to_string();
-            get_technical_indicators(&code, &period).await
+            get_technical_indicators(&code, &period, &ctx.signal_config).await
         }
         "get_fund_flow" => {
             let code = args["code"].as_str().unwrap_or("").to_string();
             get_fund_flow(&code).await
         }
+        "get_stock_profile" => {
+            let code = args["code"].as_str().unwrap_or("").to_string();
+            get_stock_profile(&code).await
+        }
+        "get_earnings_forecast" => {
+            let code = args["code"].as_str().unwrap_or("").to_string();
+            get_earnings_forecast(&code).await
+        }
+        "get_peer_comparison" => {
+            let code = args["code"].as_str().unwrap_or("").to_string();
+            let count = args["count"].as_u64().unwrap_or(peer_comparison::DEFAULT_PEER_COUNT as u64).min(20) as usize;
+            get_peer_comparison(&code, count).await
+        }
+        "get_seasonality" => {
+            let code = args["code"].as_str().unwrap_or("").to_string();
+            let years = args["years"].as_u64().unwrap_or(seasonality::DEFAULT_YEARS as u64).min(seasonality::MAX_YEARS as u64) as u32;
+            get_seasonality_tool(&code, years).await
+        }
+        "get_stock_theme_exposure" => {
+            let code = args["code"].as_str().unwrap_or("").to_string();
+            get_theme_exposure_tool(&code).await
+        }
         _ => Ok(format!("未知工具: {}", name)),
     }
 }
@@ -129,7 +291,21 @@ pub async fn execute_tool(name: &str, arguments: &str) -> Result<String> {
 async fn get_stock_quote(code: &str) -> Result<String> {
     let scanner = MarketScanner::new()?;
     let codes = vec![code.to_string()];
-    let snapshots = scanner.fetch_stocks_by_codes(&codes).await?;
+    let (snapshots, band, exposure) = tokio::join!(
+        scanner.fetch_stocks_with_fundamentals(&codes),
+        valuation::fetch_valuation_band(code),
+        theme_exposure::get_exposure(code),
+    );
+    let snapshots = snapshots?;
+    // 估值分位、题材获取失败不影响行情
+    let band = band.ok();
+    let top_themes: Vec<String> = exposure
+        .map(|e| e.themes.into_iter().take(3).map(|t| t.name).collect())
+        .unwrap_or_default();
+    let percentile_text = |p: Option<f64>| match p {
+        Some(p) => format!("近{}年 {:.1}% 分位", valuation::VALUATION_YEARS, p),
+        None => "N/A".to_string(),
+    };
 
     if let Some(s) = snapshots.first() {
         let result = serde_json::json!({
@@ -142,12 +318,14 @@ async fn get_stock_quote(code: &str) -> Result<String> {
             "high": s.high,
             "low": s.low,
             "pre_close": s.pre_close,
-            "volume": format!("{:.0}手", s.volume),
+            "volume": format_lots(s.volume),
             "amount": format_amount(s.amount),
             "amplitude": format!("{:.2}%", s.amplitude),
             "turnover_rate": format!("{:.2}%", s.turnover_rate),
             "pe_ttm": if s.pe_ttm > 0.0 { format!("{:.2}", s.pe_ttm) } else { "N/A".to_string() },
             "pb": if s.pb > 0.0 { format!("{:.2}", s.pb) } else { "N/A".to_string() },
+            "pe_percentile": percentile_text(band.as_ref().and_then(|b| b.pe_percentile)),
+            "pb_percentile": percentile_text(band.as_ref().and_then(|b| b.pb_percentile)),
             "roe": if s.roe != 0.0 { format!("{:.2}%", s.roe) } else { "N/A".to_string() },
             "total_market_cap": format_amount(s.total_market_cap),
             "float_market_cap": format_amount(s.float_market_cap),
@@ -155,7 +333,13 @@ async fn get_stock_quote(code: &str) -> Result<String> {
             "main_net_inflow": format_amount(s.main_net_inflow),
             "pct_5d": format!("{:.2}%", s.pct_5d),
             "pct_20d": format!("{:.2}%", s.pct_20d),
+            "rps": rps_text(&s.code),
             "revenue_yoy": if s.revenue_yoy != 0.0 { format!("{:.2}%", s.revenue_yoy) } else { "N/A".to_string() },
+            "profit_yoy": if s.profit_yoy != 0.0 { format!("{:.2}%", s.profit_yoy) } else { "N/A".to_string() },
+            "dividend_yield": if s.debt_ratio > 0.0 { format!("{:.2}%", s.dividend_yield) } else { "N/A".to_string() },
+            "fcf_yield": if s.fcf_yield != 0.0 { format!("{:.2}%", s.fcf_yield) } else { "N/A".to_string() },
+            "debt_ratio": if s.debt_ratio > 0.0 { format!("{:.2}%", s.debt_ratio) } else { "N/A".to_string() },
+            "top_themes": top_themes,
         });
         Ok(serde_json::to_string_pretty(&result)?)
     } else {
@@ -166,18 +350,8 @@ async fn get_stock_quote(code: &str) -> Result<String> {
 /// 获取K线数据
 async fn get_kline_data(code: &str, period: &str, count: usize) -> Result<String> {
     let count = count.min(120);
-    let kline_service = HistoryKlineService::new()?;
-    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
-    let start = "2023-01-01";
-
-    let all_items = kline_service.fetch_kline(code, period, start, &today, 640).await?;
-
-    // 取最近 count 根
-    let items: Vec<&KlineItem> = if all_items.len() > count {
-        all_items[all_items.len() - count..].iter().collect()
-    } else {
-        all_items.iter().collect()
-    };
+    let code = &history_kline::resolve_kline_code(code);
+    let items = HistoryKlineService::new()?.fetch_recent(code, period, count as u32).await?;
 
     let klines: Vec<Value> = items.iter().map(|k| {
         serde_json::json!({
@@ -202,19 +376,16 @@ async fn get_kline_data(code: &str, period: &str, count: usize) -> Result<String
 }
 
 /// 获取技术指标
-async fn get_technical_indicators(code: &str, period: &str) -> Result<String> {
-    let kline_service = HistoryKlineService::new()?;
-    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
-    let start = "2023-01-01";
-
-    let klines = kline_service.fetch_kline(code, period, start, &today, 640).await?;
+async fn get_technical_indicators(code: &str, period: &str, signal_config: &SignalConfig) -> Result<String> {
+    let code = &history_kline::resolve_kline_code(code);
+    let klines = HistoryKlineService::new()?.fetch_recent(code, period, 640).await?;
 
     if klines.is_empty() {
         return Ok(format!("未找到股票 {} 的K线数据，无法计算技术指标", code));
     }
 
     let indicators = technical_indicators::compute_indicators(&klines);
-    let signals = technical_indicators::detect_signals(&klines, &indicators);
+    let signals = technical_indicators::detect_signals(&klines, &indicators, signal_config);
     let ma_alignment = technical_indicators::determine_ma_alignment(&indicators);
     let volume_price = technical_indicators::determine_volume_price_relation(&klines);
     let summary = technical_indicators::generate_summary(&ma_alignment, &volume_price, &signals);
@@ -247,6 +418,10 @@ async fn get_technical_indicators(code: &str, period: &str) -> Result<String> {
         if let Some(v) = indicators.boll_upper[i] { ind.insert("BOLL_UPPER".to_string(), serde_json::json!(format!("{:.2}", v))); }
         if let Some(v) = indicators.boll_middle[i] { ind.insert("BOLL_MIDDLE".to_string(), serde_json::json!(format!("{:.2}", v))); }
         if let Some(v) = indicators.boll_lower[i] { ind.insert("BOLL_LOWER".to_string(), serde_json::json!(format!("{:.2}", v))); }
+        if let Some(v) = indicators.donchian_upper[i] { ind.insert("DC_UPPER".to_string(), serde_json::json!(format!("{:.2}", v))); }
+        if let Some(v) = indicators.donchian_lower[i] { ind.insert("DC_LOWER".to_string(), serde_json::json!(format!("{:.2}", v))); }
+        if let Some(v) = indicators.keltner_upper[i] { ind.insert("KC_UPPER".to_string(), serde_json::json!(format!("{:.2}", v))); }
+        if let Some(v) = indicators.keltner_lower[i] { ind.insert("KC_LOWER".to_string(), serde_json::json!(format!("{:.2}", v))); }
 
         recent_indicators.push(Value::Object(ind));
     }
@@ -261,7 +436,7 @@ async fn get_technical_indicators(code: &str, period: &str) -> Result<String> {
         })
     }).collect();
 
-    let result = serde_json::json!({
+    let mut result = serde_json::json!({
         "code": code,
         "period": period,
         "total_klines": n,
@@ -270,28 +445,111 @@ async fn get_technical_indicators(code: &str, period: &str) -> Result<String> {
         "summary": summary,
         "signals": signals_json,
         "recent_indicators": recent_indicators,
+        "gaps": gap_summary(&technical_indicators::detect_gaps(&klines)),
+        "volume_profile": volume_profile_summary(&klines),
     });
+    if period == "day" {
+        let risk = risk_metrics::fetch_risk_metrics(&klines).await;
+        let pct = |v: Option<f64>| v.map(|p| format!("{:.2}%", p)).unwrap_or_else(|| "N/A".to_string());
+        result["risk"] = serde_json::json!({
+            "volatility_20d": pct(risk.volatility_20d),
+            "volatility_60d": pct(risk.volatility_60d),
+            "beta_vs_csi300": risk.beta.map(|b| format!("{:.2}", b)).unwrap_or_else(|| "N/A".to_string()),
+            "max_drawdown_1y": pct(risk.max_drawdown_1y),
+            "drawdown_period": format!("{} ~ {}", risk.drawdown_peak_date, risk.drawdown_trough_date),
+        });
+    }
 
     Ok(serde_json::to_string(&result)?)
 }
 
+/// 并发拉取多只股票近 20 日资金流向并计算累计净流入，单只失败时跳过
+async fn fetch_cumulative_flows(codes: &[String]) -> HashMap<String, CumulativeFlow> {
+    stream::iter(codes.iter().cloned())
+        .map(|code| async move {
+            let flows = fund_flow::fetch_history(&code, fund_flow::WINDOWS[2] as u32).await.ok()?;
+            Some((code.clone(), fund_flow::cumulative(&code, &flows)))
+        })
+        .buffer_unordered(5)
+        .filter_map(|r| async move { r })
+        .collect()
+        .await
+}
+
+/// 附加 5/10/20 日累计主力与超大单净流入
+fn append_cumulative_flow(value: &mut Value, cumulative: &CumulativeFlow) {
+    let amount = |v: Option<f64>| v.map(format_amount);
+    value["main_net_inflow_5d"] = serde_json::json!(amount(cumulative.main_5d));
+    value["main_net_inflow_10d"] = serde_json::json!(amount(cumulative.main_10d));
+    value["main_net_inflow_20d"] = serde_json::json!(amount(cumulative.main_20d));
+    value["super_large_net_5d"] = serde_json::json!(amount(cumulative.super_large_5d));
+    value["super_large_net_20d"] = serde_json::json!(amount(cumulative.super_large_20d));
+}
+
+/// 当日分单资金流向的 JSON：主力、超大单、大单、中单、小单净额与形态
+fn fund_flow_json(flow: &FundFlowBreakdown) -> Value {
+    serde_json::json!({
+        "code": flow.code,
+        "main_net_inflow": format_amount(flow.main_net),
+        "main_net_inflow_raw": flow.main_net,
+        "main_net_pct": format!("{:.2}%", flow.main_pct),
+        "super_large_net": format_amount(flow.super_large_net),
+        "large_net": format_amount(flow.large_net),
+        "medium_net": format_amount(flow.medium_net),
+        "small_net": format_amount(flow.small_net),
+        "pattern": flow.pattern,
+    })
+}
+
+/// 盘中主力与超大单累计净额在几个时点的取值，观察资金是早盘流入还是尾盘流出
+fn intraday_flow_json(flow: &FundFlowBreakdown) -> Option<Value> {
+    let last = flow.intraday.last()?;
+    let checkpoints: Vec<Value> = ["10:00", "11:30", "14:00"]
+        .iter()
+        .filter_map(|t| flow.intraday.iter().find(|p| p.time.as_str() == *t))
+        .chain(std::iter::once(last))
+        .map(|p| serde_json::json!({
+            "time": p.time,
+            "main_net": format_amount(p.main_net),
+            "super_large_net": format_amount(p.super_large_net),
+            "large_net": format_amount(p.large_net),
+        }))
+        .collect();
+    Some(Value::Array(checkpoints))
+}
+
+/// 最近一个交易日尾盘集合竞价与盘后固定价格交易的 JSON
+fn closing_auction_json(auction: &ClosingAuction) -> Value {
+    serde_json::json!({
+        "date": auction.date,
+        "price_before_auction": format!("{:.2}", auction.pre_price),
+        "close": format!("{:.2}", auction.close),
+        "auction_deviation": format!("{:+.2}%", auction.deviation_pct),
+        "auction_amount": format_amount(auction.auction_amount),
+        "auction_volume_share": format!("{:.2}%", auction.volume_share),
+        "after_hours_amount": auction.after_hours_amount.map(format_amount),
+        "tail_flag": auction.flag.as_deref().unwrap_or("无"),
+    })
+}
+
 /// 获取资金流向
 async fn get_fund_flow(code: &str) -> Result<String> {
-    let scanner = MarketScanner::new()?;
-    let codes = vec![code.to_string()];
-    let flows = scanner.fetch_fund_flow(&codes).await?;
-
-    if let Some((c, net_inflow, net_pct)) = flows.first() {
-        let result = serde_json::json!({
-            "code": c,
-            "main_net_inflow": format_amount(*net_inflow),
-            "main_net_inflow_raw": net_inflow,
-            "main_net_pct": format!("{:.2}%", net_pct),
-        });
-        Ok(serde_json::to_string_pretty(&result)?)
-    } else {
-        Ok(format!("未找到股票 {} 的资金流向数据", code))
+    let Some(flow) = fund_flow::get_breakdown(code).await? else {
+        return Ok(format!("未找到股票 {} 的资金流向数据", code));
+    };
+    let mut result = fund_flow_json(&flow);
+    if let Some(intraday) = intraday_flow_json(&flow) {
+        result["intraday"] = intraday;
+    }
+    if let Some(cumulative) = fetch_cumulative_flows(std::slice::from_ref(&flow.code)).await.get(&flow.code) {
+        append_cumulative_flow(&mut result, cumulative);
+    }
+    match closing_auction::fetch(&flow.code).await {
+        Ok(Some(auction)) => result["closing_auction"] = closing_auction_json(&auction),
+        Ok(None) => {}
+        Err(e) => log::warn!("[stock_tools] fetch closing auction {} failed: {}", flow.code, e),
     }
+    Ok(serde_json::to_string_pretty(&result)?)
 }
 
 /// 批量获取资金流向（最多20只）
@@ -300,16 +558,15 @@ async fn batch_get_fund_flow(codes: &[String]) -> Result<String> {
         return Ok(r#"{"error":"未提供股票代码"}"#.to_string());
     }
     let codes: Vec<String> = codes.iter().take(20).cloned().collect();
-    let scanner = MarketScanner::new()?;
-    let flows = scanner.fetch_fund_flow(&codes).await?;
+    let flows = fund_flow::fetch_breakdowns(&codes).await?;
 
-    let stocks: Vec<Value> = flows.iter().map(|(c, net_inflow, net_pct)| {
-        serde_json::json!({
-            "code": c,
-            "main_net_inflow": format_amount(*net_inflow),
-            "main_net_inflow_raw": net_inflow,
-            "main_net_pct": format!("{:.2}%", net_pct),
-        })
+    let cumulative = fetch_cumulative_flows(&codes).await;
+    let stocks: Vec<Value> = flows.iter().map(|flow| {
+        let mut stock = fund_flow_json(flow);
+        if let Some(cumulative) = cumulative.get(&flow.code) {
+            append_cumulative_flow(&mut stock, cumulative);
+        }
+        stock
     }).collect();
 
     let result = serde_json::json!({
@@ -363,6 +620,14 @@ pub fn get_pick_tool_definitions() -> Vec<Value> {
                 "parameters": { "type": "object", "properties": {}, "required": [] }
             }
         }),
+        serde_json::json!({
+            "type": "function",
+            "function": {
+                "name": "get_market_breadth",
+                "description": "获取A股市场宽度：今日上涨/下跌/平盘家数、涨停/跌停家数，以及近期每日的涨跌家数、沪深300成分股站上20日/60日均线比例和52周新高/新低数量，用于判断市场情绪与赚钱效应",
+                "parameters": { "type": "object", "properties": {}, "required": [] }
+            }
+        }),
         serde_json::json!({
             "type": "function",
             "function": {
@@ -376,12 +641,12 @@ pub fn get_pick_tool_definitions() -> Vec<Value> {
             "type": "function",
             "function": {
                 "name": "get_kline_data",
-                "description": "获取股票或指数的历史K线数据（前复权），包括日期、开高低收、成交量、涨跌幅。支持指数代码：sh000001(上证指数)、sz399001(深证成指)、sz399006(创业板指)",
+                "description": "获取股票或指数的历史K线数据（个股前复权），包括日期、开高低收、成交量、涨跌幅。支持指数代码：sh000001(上证指数)、sz399001(深证成指)、sz399006(创业板指)、sh000300(沪深300)、sh000905(中证500)，也可直接传指数名称",
                 "parameters": {
                     "type": "object",
                     "properties": {
-                        "code": { "type": "string", "description": "股票/指数代码，如sh600519、sh000001(上证指数)" },
-                        "period": { "type": "string", "enum": ["day", "week"], "description": "K线周期，默认day" },
+                        "code": { "type": "string", "description": "股票/指数代码或指数名称，如sh600519、sh000001(上证指数)、中证500" },
+                        "period": { "type": "string", "enum": ["day", "week", "month", "m5", "m15", "m30", "m60"], "description": "K线周期，默认day，m5~m60为分钟K线" },
                         "count": { "type": "integer", "description": "最近N根K线，默认30，最多120" }
                     },
                     "required": ["code"]
@@ -392,12 +657,12 @@ pub fn get_pick_tool_definitions() -> Vec<Value> {
             "type": "function",
             "function": {
                 "name": "get_technical_indicators",
-                "description": "获取股票或指数的技术分析指标：MA均线、MACD、KDJ、RSI、布林带，以及技术信号（金叉/死叉/背离等）。支持指数代码：sh000001(上证)、sz399001(深证)、sz399006(创业板)",
+                "description": "获取股票或指数的技术分析指标：MA均线、MACD、KDJ、RSI、布林带，以及技术信号（金叉/死叉/背离等）。支持指数代码：sh000001(上证)、sz399001(深证)、sz399006(创业板)、sh000300(沪深300)、sh000905(中证500)，也可直接传指数名称",
                 "parameters": {
                     "type": "object",
                     "properties": {
-                        "code": { "type": "string", "description": "股票/指数代码" },
-                        "period": { "type": "string", "enum": ["day", "week"], "description": "K线周期，默认day" }
+                        "code": { "type": "string", "description": "股票/指数代码或指数名称" },
+                        "period": { "type": "string", "enum": ["day", "week", "month", "m5", "m15", "m30", "m60"], "description": "K线周期，默认day，m5~m60为分钟K线" }
                     },
                     "required": ["code"]
                 }
@@ -423,7 +688,7 @@ pub fn get_pick_tool_definitions() -> Vec<Value> {
             "type": "function",
             "function": {
                 "name": "search_concept_boards",
-                "description": "通过自然语言搜索概念板块/行业板块数据，查询板块涨幅排行或特定概念板块情况",
+                "description": "通过自然语言搜索概念板块/行业板块数据，查询板块涨幅排行或特定概念板块情况；已缓存成分股的板块附成分股数量与近期新纳入成分股(new_members)",
                 "parameters": {
                     "type": "object",
                     "properties": {
@@ -434,12 +699,41 @@ pub fn get_pick_tool_definitions() -> Vec<Value> {
                 }
             }
         }),
+        serde_json::json!({
+            "type": "function",
+            "function": {
+                "name": "get_market_heatmap",
+                "description": "一次获取全部行业板块与概念板块的今日行情：按涨幅排序的领涨/领跌板块（含换手率、成交额、主力净流入、涨跌家数、领涨股）以及主力净流入最多的板块。需要了解板块全貌时优先调用，无需多次调用 search_concept_boards",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "top": { "type": "integer", "description": "每个榜单返回的板块数，默认10，最多30" }
+                    },
+                    "required": []
+                }
+            }
+        }),
+        serde_json::json!({
+            "type": "function",
+            "function": {
+                "name": "get_board_rotation",
+                "description": "获取板块轮动：基于每日收盘的板块涨幅排名，返回近3/5/10个交易日排名上升最多（资金轮入、升温）和下降最多（轮出、降温）的行业或概念板块，附累计涨幅。用于判断主线切换、避免追退潮板块",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "kind": { "type": "string", "enum": ["industry", "concept"], "description": "板块类型，默认concept" },
+                        "window": { "type": "integer", "enum": [3, 5, 10], "description": "排名变化的比较窗口（交易日），默认5" }
+                    },
+                    "required": []
+                }
+            }
+        }),
         // ===== 验证层 =====
         serde_json::json!({
             "type": "function",
             "function": {
                 "name": "batch_get_stock_quotes",
-                "description": "批量获取多只股票详细行情（最新价、涨跌幅、PE/PB/ROE、市值、换手率、量比、主力净流入、5日/20日涨幅等），一次最多20只",
+                "description": "批量获取多只股票详细行情（最新价、涨跌幅、PE/PB/ROE、市值、换手率、量比、主力净流入、5日/20日涨幅、RPS相对强度排名、股息率、现金流收益率、资产负债率、净利润增速等），一次最多20只",
                 "parameters": {
                     "type": "object",
                     "properties": {
@@ -449,6 +743,21 @@ pub fn get_pick_tool_definitions() -> Vec<Value> {
                 }
             }
         }),
+        serde_json::json!({
+            "type": "function",
+            "function": {
+                "name": "get_index_constituents",
+                "description": "获取指数成分股及行情（按总市值降序），用于在指定范围内选股，如\"只在沪深300里选\"。支持沪深300(000300)、上证50(000016)、中证500(000905)以及行业板块代码BKxxxx",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "index_code": { "type": "string", "description": "指数代码或名称，如\"000300\"、\"沪深300\"、\"BK0477\"" },
+                        "limit": { "type": "integer", "description": "返回条数，默认50，最多300" }
+                    },
+                    "required": ["index_code"]
+                }
+            }
+        }),
         serde_json::json!({
             "type": "function",
             "function": {
@@ -467,7 +776,7 @@ pub fn get_pick_tool_definitions() -> Vec<Value> {
             "type": "function",
             "function": {
                 "name": "batch_get_fund_flow",
-                "description": "批量获取多只股票的资金流向数据（主力净流入金额和占比），用于验证候选股资金面。一次最多20只，推荐优先使用此工具而非逐只调用get_fund_flow",
+                "description": "批量获取多只股票的资金流向数据（当日主力净流入金额和占比、超大单/大单/中单/小单净额及形态、近5/10/20日累计主力净流入），用于验证候选股资金面。一次最多20只，推荐优先使用此工具而非逐只调用get_fund_flow",
                 "parameters": {
                     "type": "object",
                     "properties": {
@@ -481,7 +790,7 @@ pub fn get_pick_tool_definitions() -> Vec<Value> {
             "type": "function",
             "function": {
                 "name": "get_fund_flow",
-                "description": "获取单只股票资金流向数据，包括主力净流入金额和占比",
+                "description": "获取单只股票资金流向数据，包括当日主力净流入金额和占比、分单净额与形态、盘中走势、近5/10/20日累计净流入及尾盘抢筹/砸盘标记",
                 "parameters": {
                     "type": "object",
                     "properties": {
@@ -510,7 +819,7 @@ pub fn get_pick_tool_definitions() -> Vec<Value> {
             "type": "function",
             "function": {
                 "name": "get_stock_notices",
-                "description": "获取上市公司最新公告(业绩预告/重大合同/定增/减持等)，比新闻更权威。只对最终候选股使用",
+                "description": "获取上市公司最新公告(业绩预告/重大合同/定增/减持等)，比新闻更权威。只对最终候选股使用。回购、业绩预增、减持类公告附 event_study：历史同类公告发布后的平均异常收益(相对沪深300)，分析时请引用",
                 "parameters": {
                     "type": "object",
                     "properties": {
@@ -534,12 +843,83 @@ pub fn get_pick_tool_definitions() -> Vec<Value> {
                 }
             }
         }),
+        serde_json::json!({
+            "type": "function",
+            "function": {
+                "name": "get_stock_profile",
+                "description": "获取公司简介、主营业务及最新报告期主营构成（按行业/产品/地区的收入占比与毛利率），用于确认公司实际做什么业务、收入来自哪里",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "code": { "type": "string", "description": "股票代码，如 sh600519、sz000001" }
+                    },
+                    "required": ["code"]
+                }
+            }
+        }),
+        serde_json::json!({
+            "type": "function",
+            "function": {
+                "name": "get_earnings_forecast",
+                "description": "获取机构一致预期：近半年研报汇总的今明两年预测EPS/净利润/预测PE，以及买入/增持/中性/减持/卖出评级分布，用于判断业绩预期与估值匹配度",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "code": { "type": "string", "description": "股票代码，如 sh600519、sz000001" }
+                    },
+                    "required": ["code"]
+                }
+            }
+        }),
+        serde_json::json!({
+            "type": "function",
+            "function": {
+                "name": "get_peer_comparison",
+                "description": "获取同行业可比公司对比表：按市值排序的行业龙头及目标股的PE/PB/ROE/营收增速/市值/20日涨幅，附行业中位数和目标股市值排名。需要横向比较估值或行业地位时一次调用即可，无需逐只查询同行",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "code": { "type": "string", "description": "股票代码，如 sh600519、sz000001" },
+                        "count": { "type": "integer", "description": "返回的可比公司数量，默认10，最多20" }
+                    },
+                    "required": ["code"]
+                }
+            }
+        }),
+        serde_json::json!({
+            "type": "function",
+            "function": {
+                "name": "get_seasonality",
+                "description": "获取个股或指数的历史季节性：近N年各月份的平均涨跌幅与上涨概率、周一至周五的平均涨跌幅，并附所属行业板块的月度统计（如\"该股历史上6月平均上涨2.3%\"），仅作为择时的参考背景",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "code": { "type": "string", "description": "股票/指数代码，如 sh600519、sh000300" },
+                        "years": { "type": "integer", "description": "统计近N年，默认5，最多10" }
+                    },
+                    "required": ["code"]
+                }
+            }
+        }),
+        serde_json::json!({
+            "type": "function",
+            "function": {
+                "name": "get_stock_theme_exposure",
+                "description": "获取个股的题材暴露：所属题材板块按近期快讯提及次数与板块今日涨幅加权（权重之和为1），附驱动各题材的快讯标题，用于判断该股当前主要受哪些题材驱动",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "code": { "type": "string", "description": "股票代码，如 sh600519" }
+                    },
+                    "required": ["code"]
+                }
+            }
+        }),
     ]
 }
 
 /// 执行选股工具调用
-/// qgqp_b_id: 东财用户标识，用于 NLP 选股 API 的 fingerprint 字段
-pub async fn execute_pick_tool(name: &str, arguments: &str, qgqp_b_id: &str) -> Result<String> {
+pub async fn execute_pick_tool(name: &str, arguments: &str, ctx: &ToolContext) -> Result<String> {
     let args: Value = serde_json::from_str(arguments).unwrap_or(Value::Object(Default::default()));
 
     match name {
@@ -554,18 +934,30 @@ pub async fn execute_pick_tool(name: &str, arguments: &str, qgqp_b_id: &str) ->
         "get_global_indexes" => {
             get_global_indexes().await
         }
+        "get_market_breadth" => {
+            get_market_breadth_tool(&ctx.breadth_history).await
+        }
+        "get_market_heatmap" => {
+            let top = args["top"].as_u64().unwrap_or(10).clamp(1, 30) as usize;
+            get_market_heatmap_tool(top).await
+        }
+        "get_board_rotation" => {
+            let kind = args["kind"].as_str().unwrap_or("concept").to_string();
+            let window = args["window"].as_u64().unwrap_or(5) as usize;
+            get_board_rotation_tool(&ctx.board_rank_history, &kind, window)
+        }
         "get_financial_calendar" => {
             get_financial_calendar().await
         }
         "search_stocks_by_condition" => {
             let keyword = args["keyword"].as_str().unwrap_or("").to_string();
             let page_size = args["page_size"].as_u64().unwrap_or(20).min(50) as u32;
-            search_stocks_by_condition(&keyword, page_size, qgqp_b_id).await
+            search_stocks_by_condition(&keyword, page_size, &ctx.qgqp_b_id).await
         }
         "search_concept_boards" => {
             let keyword = args["keyword"].as_str().unwrap_or("").to_string();
             let page_size = args["page_size"].as_u64().unwrap_or(20).min(50) as u32;
-            search_concept_boards(&keyword, page_size, qgqp_b_id).await
+            search_concept_boards(&keyword, page_size, &ctx.qgqp_b_id).await
         }
         "batch_get_stock_quotes" => {
             let codes: Vec<String> = args["codes"]
@@ -581,6 +973,11 @@ pub async fn execute_pick_tool(name: &str, arguments: &str, qgqp_b_id: &str) ->
                 .unwrap_or_default();
             batch_get_fund_flow(&codes).await
         }
+        "get_index_constituents" => {
+            let index_code = args["index_code"].as_str().unwrap_or("").to_string();
+            let limit = args["limit"].as_u64().unwrap_or(50).min(300) as usize;
+            get_index_constituents_tool(&index_code, limit).await
+        }
         "search_stock_news" => {
             let keyword = args["keyword"].as_str().unwrap_or("").to_string();
             search_stock_news_tool(&keyword).await
@@ -594,13 +991,60 @@ pub async fn execute_pick_tool(name: &str, arguments: &str, qgqp_b_id: &str) ->
             get_industry_report(code.as_deref()).await
         }
         // 诊股工具也支持
-        "get_stock_quote" | "get_kline_data" | "get_technical_indicators" | "get_fund_flow" => {
-            execute_tool(name, arguments).await
+        "get_stock_quote" | "get_kline_data" | "get_technical_indicators" | "get_fund_flow" | "get_stock_profile"
+        | "get_earnings_forecast" | "get_peer_comparison" | "get_seasonality" | "get_stock_theme_exposure" => {
+            execute_tool(name, arguments, ctx).await
         }
         _ => Ok(format!("未知工具: {}", name)),
     }
 }
 
+/// 会话内工具结果缓存有效期（秒）
+pub const TOOL_CACHE_TTL_SECS: u64 = 120;
+
+/// 单次 Agent 会话内的工具结果缓存
+/// 以 (工具名, 规范化参数) 为键，模型重复请求相同工具调用时直接复用结果，减少等待和上游请求
+pub struct ToolResultCache {
+    ttl: Duration,
+    entries: HashMap<String, (Instant, String)>,
+}
+
+impl Default for ToolResultCache {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(TOOL_CACHE_TTL_SECS))
+    }
+}
+
+impl ToolResultCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self { ttl, entries: HashMap::new() }
+    }
+
+    /// 参数 JSON 重新序列化（键有序、去空白），字段顺序不同的相同调用视为同一键
+    fn cache_key(name: &str, arguments: &str) -> String {
+        let normalized = serde_json::from_str::<Value>(arguments)
+            .map(|v| v.to_string())
+            .unwrap_or_else(|_| arguments.trim().to_string());
+        format!("{}:{}", name, normalized)
+    }
+
+    pub fn get(&self, name: &str, arguments: &str) -> Option<String> {
+        self.entries
+            .get(&Self::cache_key(name, arguments))
+            .filter(|(at, _)| at.elapsed() < self.ttl)
+            .map(|(_, result)| result.clone())
+    }
+
+    /// 写入缓存；调用失败的结果不缓存，以便模型重试时重新请求
+    pub fn insert(&mut self, name: &str, arguments: &str, result: &str) {
+        if result.starts_with("工具调用失败") {
+            return;
+        }
+        self.entries
+            .insert(Self::cache_key(name, arguments), (Instant::now(), result.to_string()));
+    }
+}
+
 /// 获取市场最新新闻摘要（每个源独立超时8秒，任一失败不影响其他）
 async fn get_market_news(count: u32) -> Result<String> {
     use tokio::time::{timeout, Duration};
@@ -761,6 +1205,28 @@ async fn search_stocks_by_condition(keyword: &str, page_size: u32, qgqp_b_id: &s
 }
 
 /// NLP 板块搜索：复用 SmartStockService::search_board（使用正确的Host header）
+/// 附加成分股缓存中的成分股数量与近期新纳入成分股（缓存未建立时不附加）
+fn append_cached_members(board_info: &mut Value, sec_code: &str) {
+    let Some(index) = crate::services::board_members::cached() else {
+        return;
+    };
+    let board_code = if sec_code.starts_with("BK") { sec_code.to_string() } else { format!("BK{}", sec_code) };
+    let members = index.members_of(&board_code);
+    if members.is_empty() {
+        return;
+    }
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let new_members: Vec<Value> = members
+        .iter()
+        .filter(|m| crate::services::board_members::is_recent_addition(m, &today))
+        .map(|m| serde_json::json!({ "code": m.code, "name": m.name, "first_seen": m.first_seen }))
+        .collect();
+    board_info["member_count"] = serde_json::json!(members.len());
+    if !new_members.is_empty() {
+        board_info["new_members"] = Value::Array(new_members);
+    }
+}
+
 async fn search_concept_boards(keyword: &str, page_size: u32, qgqp_b_id: &str) -> Result<String> {
     if keyword.is_empty() {
         return Ok(r#"{"error":"请提供板块查询条件"}"#.to_string());
@@ -801,6 +1267,7 @@ async fn search_concept_boards(keyword: &str, page_size: u32, qgqp_b_id: &str) -
                             }
                         }
                     }
+                    append_cached_members(&mut board_info, sec_code);
                     boards.push(board_info);
                 }
             }
@@ -824,6 +1291,22 @@ async fn search_concept_boards(keyword: &str, page_size: u32, qgqp_b_id: &str) -
     }
 }
 
+/// 个股 RPS 文本（20/60/120 日），未计算时为 N/A
+fn rps_text(code: &str) -> String {
+    let record = match rps::cached(code) {
+        Some(r) => r,
+        None => return "N/A".to_string(),
+    };
+    let parts: Vec<String> = rps::RPS_PERIODS
+        .iter()
+        .map(|&p| match rps::rps_of(&record, p) {
+            Some(v) => format!("RPS{}={:.0}", p, v),
+            None => format!("RPS{}=N/A", p),
+        })
+        .collect();
+    parts.join(" ")
+}
+
 /// 批量获取股票行情
 async fn batch_get_stock_quotes(codes: &[String]) -> Result<String> {
     if codes.is_empty() {
@@ -831,7 +1314,7 @@ async fn batch_get_stock_quotes(codes: &[String]) -> Result<String> {
     }
     let codes: Vec<String> = codes.iter().take(20).cloned().collect();
     let scanner = MarketScanner::new()?;
-    let snapshots = scanner.fetch_stocks_by_codes(&codes).await?;
+    let snapshots = scanner.fetch_stocks_with_fundamentals(&codes).await?;
 
     let stocks: Vec<Value> = snapshots.iter().map(|s| {
         serde_json::json!({
@@ -848,7 +1331,12 @@ async fn batch_get_stock_quotes(codes: &[String]) -> Result<String> {
             "main_net_inflow": format_amount(s.main_net_inflow),
             "pct_5d": format!("{:.2}%", s.pct_5d),
             "pct_20d": format!("{:.2}%", s.pct_20d),
+            "rps": rps_text(&s.code),
             "revenue_yoy": if s.revenue_yoy != 0.0 { format!("{:.2}%", s.revenue_yoy) } else { "N/A".to_string() },
+            "profit_yoy": if s.profit_yoy != 0.0 { format!("{:.2}%", s.profit_yoy) } else { "N/A".to_string() },
+            "dividend_yield": if s.debt_ratio > 0.0 { format!("{:.2}%", s.dividend_yield) } else { "N/A".to_string() },
+            "fcf_yield": if s.fcf_yield != 0.0 { format!("{:.2}%", s.fcf_yield) } else { "N/A".to_string() },
+            "debt_ratio": if s.debt_ratio > 0.0 { format!("{:.2}%", s.debt_ratio) } else { "N/A".to_string() },
             "amount": format_amount(s.amount),
         })
     }).collect();
@@ -860,6 +1348,174 @@ async fn batch_get_stock_quotes(codes: &[String]) -> Result<String> {
     Ok(serde_json::to_string(&result)?)
 }
 
+/// 近 60 根K线的成交量分布要点：成交密集价位（POC）、70% 价值区与高量节点
+fn volume_profile_summary(klines: &[KlineItem]) -> Value {
+    let window = &klines[klines.len().saturating_sub(60)..];
+    match technical_indicators::compute_volume_profile(window, 40, "day", window.len()) {
+        Some(p) => serde_json::json!({
+            "bars": p.days,
+            "poc_price": format!("{:.2}", p.poc_price),
+            "value_area": format!("{:.2}-{:.2}", p.value_area_low, p.value_area_high),
+            "high_volume_nodes": p.high_volume_nodes.iter().map(|v| format!("{:.2}", v)).collect::<Vec<_>>(),
+        }),
+        None => Value::Null,
+    }
+}
+
+/// 缺口信息（最近 5 个未回补缺口 + 历史回补统计），供技术指标工具输出
+fn gap_summary(gaps: &GapAnalysis) -> Value {
+    let open_gaps: Vec<Value> = gaps.open_gaps.iter().take(5).map(|g| {
+        serde_json::json!({
+            "direction": if g.direction == "up" { "向上" } else { "向下" },
+            "date": g.date,
+            "range": format!("{:.2}-{:.2}", g.lower, g.upper),
+            "size_pct": format!("{:.2}%", g.size_pct),
+            "days_open": g.days_open,
+        })
+    }).collect();
+    serde_json::json!({
+        "open_gaps": open_gaps,
+        "total": gaps.total,
+        "filled": gaps.filled,
+        "fill_rate": gaps.fill_rate_pct.map(|p| format!("{:.1}%", p)).unwrap_or_else(|| "N/A".to_string()),
+        "median_fill_days": gaps.median_fill_days,
+    })
+}
+
+/// 获取市场宽度：今日实时涨跌家数 + 近期收盘后记录的宽度指标
+async fn get_market_breadth_tool(history: &[MarketBreadth]) -> Result<String> {
+    let today = match MarketScanner::new()?.scan_full_market().await {
+        Ok(stocks) if !stocks.is_empty() => {
+            let b = market_breadth::snapshot_stats(&stocks);
+            serde_json::json!({
+                "total": b.total,
+                "advancers": b.advancers,
+                "decliners": b.decliners,
+                "flat": b.flat,
+                "limit_up": b.limit_up,
+                "limit_down": b.limit_down,
+            })
+        }
+        Ok(_) => serde_json::json!({ "error": "全市场扫描返回为空" }),
+        Err(e) => serde_json::json!({ "error": e.to_string() }),
+    };
+    let pct = |v: Option<f64>| v.map(|p| format!("{:.1}%", p)).unwrap_or_else(|| "N/A".to_string());
+    let recent: Vec<Value> = history.iter().map(|b| {
+        serde_json::json!({
+            "date": b.date,
+            "advancers": b.advancers,
+            "decliners": b.decliners,
+            "limit_up": b.limit_up,
+            "limit_down": b.limit_down,
+            "above_ma20": pct(b.above_ma20_pct),
+            "above_ma60": pct(b.above_ma60_pct),
+            "new_high_52w": b.new_high_52w,
+            "new_low_52w": b.new_low_52w,
+        })
+    }).collect();
+    let result = serde_json::json!({
+        "today": today,
+        "history": recent,
+    });
+    Ok(serde_json::to_string(&result)?)
+}
+
+/// 板块热力图：行业/概念板块涨幅榜、跌幅榜与主力净流入榜
+async fn get_market_heatmap_tool(top: usize) -> Result<String> {
+    let heatmap = match market_heatmap::get_market_heatmap().await {
+        Ok(h) => h,
+        Err(e) => return Ok(serde_json::json!({ "error": format!("获取板块行情失败: {}", e) }).to_string()),
+    };
+    let row = |b: &BoardQuote| serde_json::json!({
+        "name": b.name,
+        "code": b.code,
+        "change": format!("{:+.2}%", b.change_pct),
+        "turnover": format!("{:.2}%", b.turnover_rate),
+        "amount": format_amount(b.amount),
+        "main_net_inflow": format_amount(b.main_net_inflow),
+        "up_down": format!("{}/{}", b.up_count, b.down_count),
+        "leader": format!("{}({}) {:+.2}%", b.leader_name, b.leader_code, b.leader_change_pct),
+    });
+    // 板块列表已按涨跌幅降序
+    let ranking = |boards: &[BoardQuote]| serde_json::json!({
+        "count": boards.len(),
+        "top": boards.iter().take(top).map(row).collect::<Vec<_>>(),
+        "bottom": boards.iter().rev().take(top.min(5)).map(row).collect::<Vec<_>>(),
+    });
+    let mut by_inflow: Vec<&BoardQuote> = heatmap.industries.iter().chain(heatmap.concepts.iter()).collect();
+    by_inflow.sort_by(|a, b| b.main_net_inflow.total_cmp(&a.main_net_inflow));
+
+    let result = serde_json::json!({
+        "updated_at": heatmap.updated_at,
+        "industries": ranking(&heatmap.industries),
+        "concepts": ranking(&heatmap.concepts),
+        "top_inflow": by_inflow.into_iter().take(top).map(row).collect::<Vec<_>>(),
+    });
+    Ok(serde_json::to_string(&result)?)
+}
+
+/// 板块轮动：由选股命令预读的排名记录计算升温/降温板块
+fn get_board_rotation_tool(history: &[BoardRankRecord], kind: &str, window: usize) -> Result<String> {
+    if !board_rotation::ROTATION_WINDOWS.contains(&window) {
+        return Ok(serde_json::json!({ "error": format!("不支持的轮动窗口: {}，可选 3/5/10", window) }).to_string());
+    }
+    let records: Vec<BoardRankRecord> = history.iter().filter(|r| r.kind == kind).cloned().collect();
+    let rotation = board_rotation::compute_rotation(&records, kind, window, 10);
+    if rotation.history_days <= window {
+        return Ok(serde_json::json!({
+            "kind": kind,
+            "history_days": rotation.history_days,
+            "error": format!("板块排名记录仅 {} 个交易日，不足以计算 {} 日轮动（收盘后自动记录）", rotation.history_days, window),
+        }).to_string());
+    }
+    let change = |v: Option<i32>| v.map(|c| format!("{:+}", c)).unwrap_or_else(|| "N/A".to_string());
+    let pct = |v: Option<f64>| v.map(|p| format!("{:+.2}%", p)).unwrap_or_else(|| "N/A".to_string());
+    let row = |i: &BoardRotationItem| serde_json::json!({
+        "name": i.name,
+        "code": i.code,
+        "rank": i.rank,
+        "rank_change_3d": change(i.rank_change_3d),
+        "rank_change_5d": change(i.rank_change_5d),
+        "rank_change_10d": change(i.rank_change_10d),
+        "pct_5d": pct(i.pct_5d),
+    });
+    let result = serde_json::json!({
+        "kind": kind,
+        "date": rotation.date,
+        "window": window,
+        "gaining": rotation.gaining.iter().map(row).collect::<Vec<_>>(),
+        "losing": rotation.losing.iter().map(row).collect::<Vec<_>>(),
+    });
+    Ok(serde_json::to_string(&result)?)
+}
+
+/// 获取指数成分股（精简字段，避免占用过多上下文）
+async fn get_index_constituents_tool(index_code: &str, limit: usize) -> Result<String> {
+    let data = match index_constituents::get_index_constituents(index_code).await {
+        Ok(d) => d,
+        Err(e) => return Ok(serde_json::json!({ "index_code": index_code, "error": e.to_string() }).to_string()),
+    };
+    let stocks: Vec<Value> = data.stocks.iter().take(limit).map(|s| {
+        serde_json::json!({
+            "code": s.code,
+            "name": s.name,
+            "price": s.price,
+            "change_pct": format!("{:.2}%", s.change_pct),
+            "pe_ttm": if s.pe_ttm > 0.0 { format!("{:.2}", s.pe_ttm) } else { "N/A".to_string() },
+            "total_market_cap": format_amount(s.total_market_cap),
+            "pct_5d": format!("{:.2}%", s.pct_5d),
+        })
+    }).collect();
+    let result = serde_json::json!({
+        "index_code": data.index_code,
+        "index_name": data.index_name,
+        "total_count": data.stocks.len(),
+        "returned": stocks.len(),
+        "stocks": stocks,
+    });
+    Ok(serde_json::to_string(&result)?)
+}
+
 // ============================================================
 // 新增工具实现：宏观经济 / 全球指数 / 财经日历 / 个股新闻 / 公告 / 研报
 // ============================================================
@@ -892,7 +1548,7 @@ async fn get_economic_data(indicator: &str) -> Result<String> {
             report_name, columns, ts
         );
 
-        match client.get(&url).send().await {
+        match client.get(&url).send_logged().await {
             Ok(resp) => {
                 if let Ok(text) = resp.text().await {
                     // JSONP 解包: datatable...({...})
@@ -945,7 +1601,7 @@ pub async fn get_global_indexes() -> Result<String> {
     let client = http::build_qq_finance_client()?;
     let url = "https://proxy.finance.qq.com/ifzqgtimg/appstock/app/rank/indexRankDetail2";
 
-    let resp = client.get(url).send().await?;
+    let resp = client.get(url).send_logged().await?;
     let json: Value = resp.json().await?;
 
     let data = &json["data"];
@@ -989,11 +1645,11 @@ pub async fn get_global_indexes() -> Result<String> {
 }
 
 /// 获取财经日历
-async fn get_financial_calendar() -> Result<String> {
+pub async fn get_financial_calendar() -> Result<String> {
     let client = http::build_cls_client()?;
     let url = "https://www.cls.cn/api/calendar/web/list?app=CailianpressWeb&flag=0&os=web&sv=8.4.6&type=0&sign=4b839750dc2f6b803d1c8ca00d2b40be";
 
-    let resp = client.get(url).send().await?;
+    let resp = client.get(url).send_logged().await?;
     let json: Value = resp.json().await?;
 
     let mut events = Vec::new();
@@ -1082,12 +1738,26 @@ async fn get_stock_notices(code: &str) -> Result<String> {
     match news_service::fetch_announcements(Some(code), 1, 5).await {
         Ok(items) => {
             let notices: Vec<Value> = items.iter().take(5).map(|a| {
-                serde_json::json!({
+                let mut notice = serde_json::json!({
                     "title": a.title,
                     "date": a.notice_date,
                     "category": a.category,
                     "stock_name": a.stock_name,
-                })
+                });
+                // 回购、业绩预增、减持类公告附带历史同类公告的平均异常收益
+                if let Some(stats) = announcement_study::classify(&a.title).and_then(announcement_study::cached_stats) {
+                    let pct = |v: Option<f64>| v.map(|v| format!("{:+.2}%", v)).unwrap_or_else(|| "-".to_string());
+                    notice["event_study"] = serde_json::json!({
+                        "category": stats.category,
+                        "events": stats.events,
+                        "avg_abnormal_return_announce_day": pct(stats.avg_ar_0d),
+                        "avg_abnormal_return_1d": pct(stats.avg_ar_1d),
+                        "avg_abnormal_return_5d": pct(stats.avg_ar_5d),
+                        "avg_abnormal_return_20d": pct(stats.avg_ar_20d),
+                        "win_rate_5d": stats.win_rate_5d.map(|v| format!("{:.0}%", v)),
+                    });
+                }
+                notice
             }).collect();
 
             let result = serde_json::json!({
@@ -1108,6 +1778,214 @@ async fn get_stock_notices(code: &str) -> Result<String> {
     }
 }
 
+/// 获取公司简介与主营构成（东方财富 F10）
+async fn get_stock_profile(code: &str) -> Result<String> {
+    if code.is_empty() {
+        return Ok(r#"{"error":"请提供股票代码"}"#.to_string());
+    }
+
+    match f10_service::fetch_stock_profile(code).await {
+        Ok(p) => {
+            let mut by_category: Vec<(String, Vec<Value>)> = Vec::new();
+            for seg in &p.segments {
+                let item = serde_json::json!({
+                    "name": seg.name,
+                    "revenue": format_amount(seg.revenue),
+                    "revenue_ratio": format!("{:.1}%", seg.revenue_ratio),
+                    "gross_margin": format!("{:.1}%", seg.gross_margin),
+                });
+                match by_category.iter_mut().find(|(c, _)| c == &seg.category) {
+                    Some((_, items)) if items.len() < 6 => items.push(item),
+                    Some(_) => {}
+                    None => by_category.push((seg.category.clone(), vec![item])),
+                }
+            }
+            let segments: serde_json::Map<String, Value> = by_category
+                .into_iter()
+                .map(|(c, items)| (format!("按{}", c), Value::Array(items)))
+                .collect();
+
+            let result = serde_json::json!({
+                "code": p.code,
+                "name": p.name,
+                "full_name": p.full_name,
+                "industry": p.industry,
+                "listing_date": p.listing_date,
+                "province": p.province,
+                "main_business": truncate_str(&p.main_business, 300),
+                "profile": truncate_str(&p.profile, 300),
+                "report_date": p.report_date,
+                "segments": segments,
+            });
+            Ok(serde_json::to_string(&result)?)
+        }
+        Err(e) => {
+            Ok(serde_json::json!({
+                "code": code,
+                "error": format!("获取公司简介失败: {}", e),
+            }).to_string())
+        }
+    }
+}
+
+/// 获取机构一致预期与评级分布
+async fn get_earnings_forecast(code: &str) -> Result<String> {
+    if code.is_empty() {
+        return Ok(r#"{"error":"请提供股票代码"}"#.to_string());
+    }
+
+    match f10_service::fetch_earnings_forecast(code).await {
+        Ok(f) => {
+            let years: Vec<Value> = f.years.iter().map(|y| {
+                serde_json::json!({
+                    "year": y.year,
+                    "eps": format!("{:.3}", y.eps),
+                    "eps_range": format!("{:.3}~{:.3}", y.eps_min, y.eps_max),
+                    "net_profit": if y.net_profit != 0.0 { format_amount(y.net_profit) } else { "N/A".to_string() },
+                    "forward_pe": if y.forward_pe > 0.0 { format!("{:.1}", y.forward_pe) } else { "N/A".to_string() },
+                    "samples": y.sample_count,
+                })
+            }).collect();
+
+            let result = serde_json::json!({
+                "code": f.code,
+                "name": f.name,
+                "report_count": f.report_count,
+                "org_count": f.org_count,
+                "ratings": {
+                    "买入": f.ratings.buy,
+                    "增持": f.ratings.overweight,
+                    "中性": f.ratings.neutral,
+                    "减持": f.ratings.underweight,
+                    "卖出": f.ratings.sell,
+                },
+                "forecast": years,
+                "note": if f.report_count == 0 { "近半年无机构研报覆盖" } else { "" },
+            });
+            Ok(serde_json::to_string(&result)?)
+        }
+        Err(e) => {
+            Ok(serde_json::json!({
+                "code": code,
+                "error": format!("获取盈利预测失败: {}", e),
+            }).to_string())
+        }
+    }
+}
+
+/// 获取同行业可比公司对比表
+async fn get_peer_comparison(code: &str, count: usize) -> Result<String> {
+    if code.is_empty() {
+        return Ok(r#"{"error":"请提供股票代码"}"#.to_string());
+    }
+
+    match peer_comparison::get_peer_comparison(code, count).await {
+        Ok(c) => {
+            let fmt_ratio = |v: f64| if v > 0.0 { format!("{:.2}", v) } else { "N/A".to_string() };
+            let peers: Vec<Value> = c.peers.iter().map(|p| {
+                serde_json::json!({
+                    "code": p.code,
+                    "name": if p.is_target { format!("{}(目标)", p.name) } else { p.name.clone() },
+                    "market_cap": format_amount(p.total_market_cap),
+                    "pe_ttm": fmt_ratio(p.pe_ttm),
+                    "pb": fmt_ratio(p.pb),
+                    "roe": if p.roe != 0.0 { format!("{:.2}%", p.roe) } else { "N/A".to_string() },
+                    "revenue_yoy": format!("{:.2}%", p.revenue_yoy),
+                    "pct_20d": format!("{:.2}%", p.pct_20d),
+                })
+            }).collect();
+
+            let result = serde_json::json!({
+                "code": c.code,
+                "name": c.name,
+                "industry": c.industry,
+                "industry_size": c.industry_size,
+                "market_cap_rank": c.market_cap_rank,
+                "industry_median": {
+                    "pe_ttm": fmt_ratio(c.median_pe),
+                    "pb": fmt_ratio(c.median_pb),
+                    "roe": format!("{:.2}%", c.median_roe),
+                },
+                "peers": peers,
+            });
+            Ok(serde_json::to_string(&result)?)
+        }
+        Err(e) => {
+            Ok(serde_json::json!({
+                "code": code,
+                "error": format!("获取同业对比失败: {}", e),
+            }).to_string())
+        }
+    }
+}
+
+/// 获取历史季节性（月度/星期效应，附行业板块月度统计），仅保留有样本的分组
+async fn get_seasonality_tool(code: &str, years: u32) -> Result<String> {
+    if code.is_empty() {
+        return Ok(r#"{"error":"请提供股票代码"}"#.to_string());
+    }
+
+    match seasonality::get_seasonality(code, years).await {
+        Ok(s) => {
+            let buckets = |items: &[SeasonalityBucket]| -> Vec<Value> {
+                items.iter().filter(|b| b.samples > 0).map(|b| {
+                    serde_json::json!({
+                        "period": b.label,
+                        "avg_return": b.avg_return_pct.map(|v| format!("{:+.2}%", v)),
+                        "win_rate": b.win_rate_pct.map(|v| format!("{:.0}%", v)),
+                        "samples": b.samples,
+                    })
+                }).collect()
+            };
+            let result = serde_json::json!({
+                "code": s.code,
+                "years": s.years,
+                "range": format!("{} ~ {}", s.start_date, s.end_date),
+                "current_month": chrono::Local::now().month(),
+                "monthly": buckets(&s.monthly),
+                "weekday": buckets(&s.weekday),
+                "sector": s.sector_name,
+                "sector_monthly": buckets(&s.sector_monthly),
+            });
+            Ok(serde_json::to_string(&result)?)
+        }
+        Err(e) => {
+            Ok(serde_json::json!({
+                "code": code,
+                "error": format!("获取季节性统计失败: {}", e),
+            }).to_string())
+        }
+    }
+}
+
+/// 获取个股题材暴露向量（权重、板块涨幅、驱动快讯）
+async fn get_theme_exposure_tool(code: &str) -> Result<String> {
+    if code.is_empty() {
+        return Ok(r#"{"error":"请提供股票代码"}"#.to_string());
+    }
+
+    match theme_exposure::get_exposure(code).await {
+        Ok(e) => {
+            let themes: Vec<Value> = e.themes.iter().map(|t| {
+                serde_json::json!({
+                    "theme": t.name,
+                    "weight": format!("{:.1}%", t.weight * 100.0),
+                    "board_change_pct": format!("{:+.2}%", t.change_pct),
+                    "news_mentions": t.mentions,
+                    "headlines": t.headlines,
+                })
+            }).collect();
+            Ok(serde_json::json!({ "code": e.code, "themes": themes }).to_string())
+        }
+        Err(e) => {
+            Ok(serde_json::json!({
+                "code": code,
+                "error": format!("获取题材暴露失败: {}", e),
+            }).to_string())
+        }
+    }
+}
+
 /// 获取行业/个股研报摘要（复用 news_service::fetch_reports）
 async fn get_industry_report(code: Option<&str>) -> Result<String> {
     match news_service::fetch_reports(code, 1, 8).await {
@@ -1145,10 +2023,14 @@ pub fn pick_tool_name_to_chinese(name: &str) -> &str {
         "get_market_news" => "市场新闻",
         "get_economic_data" => "宏观经济",
         "get_global_indexes" => "全球指数",
+        "get_market_breadth" => "市场宽度",
+        "get_market_heatmap" => "板块热力图",
+        "get_board_rotation" => "板块轮动",
         "get_financial_calendar" => "财经日历",
         "search_stocks_by_condition" => "NLP智能选股",
         "search_concept_boards" => "NLP板块搜索",
         "batch_get_stock_quotes" => "批量行情",
+        "get_index_constituents" => "指数成分股",
         "get_stock_quote" => "实时行情",
         "get_fund_flow" => "资金流向",
         "batch_get_fund_flow" => "批量资金流向",
@@ -1157,6 +2039,11 @@ pub fn pick_tool_name_to_chinese(name: &str) -> &str {
         "search_stock_news" => "个股新闻",
         "get_stock_notices" => "公司公告",
         "get_industry_report" => "研报摘要",
+        "get_stock_profile" => "公司简介",
+        "get_earnings_forecast" => "盈利预测",
+        "get_peer_comparison" => "同业对比",
+        "get_seasonality" => "季节性统计",
+        "get_stock_theme_exposure" => "题材暴露",
         _ => name,
     }
 }
@@ -1204,6 +2091,37 @@ pub fn summarize_tool_result(tool_name: &str, result: &str) -> String {
             }
             lines.join("\n")
         }
+        "get_board_rotation" => {
+            if let Some(err) = json["error"].as_str() {
+                return err.to_string();
+            }
+            let names = |list: &Value| -> String {
+                list.as_array()
+                    .map(|arr| arr.iter().take(5).map(|b| {
+                        format!("{}({})", b["name"].as_str().unwrap_or(""), b[format!("rank_change_{}d", json["window"])].as_str().unwrap_or(""))
+                    }).collect::<Vec<_>>().join("、"))
+                    .unwrap_or_default()
+            };
+            format!("近{}日排名升温：{}\n排名降温：{}", json["window"], names(&json["gaining"]), names(&json["losing"]))
+        }
+        "get_market_heatmap" => {
+            if let Some(err) = json["error"].as_str() {
+                return format!("板块行情获取失败: {}", err);
+            }
+            let names = |list: &Value| -> String {
+                list.as_array()
+                    .map(|arr| arr.iter().take(3).map(|b| {
+                        format!("{} {}", b["name"].as_str().unwrap_or(""), b["change"].as_str().unwrap_or(""))
+                    }).collect::<Vec<_>>().join("、"))
+                    .unwrap_or_default()
+            };
+            format!(
+                "行业领涨：{}\n概念领涨：{}\n主力净流入居前：{}",
+                names(&json["industries"]["top"]),
+                names(&json["concepts"]["top"]),
+                names(&json["top_inflow"]),
+            )
+        }
         "search_concept_boards" => {
             let keyword = json["keyword"].as_str().unwrap_or("");
             let total = json["total_count"].as_u64().unwrap_or(0);
@@ -1248,6 +2166,30 @@ pub fn summarize_tool_result(tool_name: &str, result: &str) -> String {
             }
             lines.join("\n")
         }
+        "get_market_breadth" => {
+            let today = &json["today"];
+            let mut lines = vec![match today["error"].as_str() {
+                Some(err) => format!("今日涨跌家数获取失败: {}", err),
+                None => format!(
+                    "今日上涨 {} 家 / 下跌 {} 家，涨停 {} 家 / 跌停 {} 家",
+                    today["advancers"].as_u64().unwrap_or(0),
+                    today["decliners"].as_u64().unwrap_or(0),
+                    today["limit_up"].as_u64().unwrap_or(0),
+                    today["limit_down"].as_u64().unwrap_or(0),
+                ),
+            }];
+            if let Some(last) = json["history"].as_array().and_then(|h| h.last()) {
+                lines.push(format!(
+                    "{} 沪深300站上MA20 {} / MA60 {}，52周新高 {} / 新低 {}",
+                    last["date"].as_str().unwrap_or(""),
+                    last["above_ma20"].as_str().unwrap_or("N/A"),
+                    last["above_ma60"].as_str().unwrap_or("N/A"),
+                    last["new_high_52w"].as_u64().unwrap_or(0),
+                    last["new_low_52w"].as_u64().unwrap_or(0),
+                ));
+            }
+            lines.join("\n")
+        }
         "get_financial_calendar" => {
             let total = json["total"].as_u64().unwrap_or(0);
             format!("获取到 {} 条财经日历事件", total)
@@ -1272,6 +2214,14 @@ pub fn summarize_tool_result(tool_name: &str, result: &str) -> String {
                 for (i, n) in notices.iter().take(5).enumerate() {
                     let title = n["title"].as_str().unwrap_or("");
                     lines.push(format!("{}. {}", i + 1, title));
+                    if let Some(study) = n.get("event_study") {
+                        lines.push(format!(
+                            "   历史同类公告({} {} 例) 5日平均超额 {}",
+                            study["category"].as_str().unwrap_or(""),
+                            study["events"].as_u64().unwrap_or(0),
+                            study["avg_abnormal_return_5d"].as_str().unwrap_or("-"),
+                        ));
+                    }
                 }
             }
             lines.join("\n")
@@ -1304,12 +2254,34 @@ pub fn summarize_tool_result(tool_name: &str, result: &str) -> String {
             }
             lines.join("\n")
         }
+        "get_index_constituents" => {
+            if let Some(err) = json["error"].as_str() {
+                return format!("获取成分股失败: {}", err);
+            }
+            let name = json["index_name"].as_str().filter(|n| !n.is_empty()).or(json["index_code"].as_str()).unwrap_or("");
+            let total = json["total_count"].as_u64().unwrap_or(0);
+            let returned = json["returned"].as_u64().unwrap_or(0);
+            let mut lines = vec![format!("{} 共 {} 只成分股（返回市值前 {} 只）", name, total, returned)];
+            if let Some(stocks) = json["stocks"].as_array() {
+                for s in stocks.iter().take(10) {
+                    let name = s["name"].as_str().unwrap_or("");
+                    let code = s["code"].as_str().unwrap_or("");
+                    let pct = s["change_pct"].as_str().unwrap_or("0%");
+                    lines.push(format!("· {}({}) {}", name, code, pct));
+                }
+            }
+            lines.join("\n")
+        }
         "get_stock_quote" => {
             let name = json["name"].as_str().unwrap_or("");
             let code = json["code"].as_str().unwrap_or("");
             let price = json["price"].as_f64().map(|v| format!("{:.2}", v)).unwrap_or_default();
             let pct = json["change_pct"].as_str().unwrap_or("");
             let pe = json["pe_ttm"].as_str().unwrap_or("N/A");
+            let pe = match json["pe_percentile"].as_str() {
+                Some(p) if p != "N/A" => format!("{}({})", pe, p),
+                _ => pe.to_string(),
+            };
             let roe = json["roe"].as_str().unwrap_or("N/A");
             let cap = json["total_market_cap"].as_str().unwrap_or("");
             let main = json["main_net_inflow"].as_str().unwrap_or("");
@@ -1319,7 +2291,13 @@ pub fn summarize_tool_result(tool_name: &str, result: &str) -> String {
             let code = json["code"].as_str().unwrap_or("");
             let inflow = json["main_net_inflow"].as_str().unwrap_or("0");
             let pct = json["main_net_pct"].as_str().unwrap_or("0%");
-            format!("{} 主力净流入:{} 占比:{}", code, inflow, pct)
+            let flow_5d = json["main_net_inflow_5d"].as_str().unwrap_or("-");
+            let flow_20d = json["main_net_inflow_20d"].as_str().unwrap_or("-");
+            let mut summary = format!("{} 主力净流入:{} 占比:{} 5日:{} 20日:{}", code, inflow, pct, flow_5d, flow_20d);
+            if let Some(pattern) = json["pattern"].as_str() {
+                summary.push_str(&format!(" | {}", pattern));
+            }
+            summary
         }
         "batch_get_fund_flow" => {
             let count = json["total_count"].as_u64().unwrap_or(0);
@@ -1338,14 +2316,89 @@ pub fn summarize_tool_result(tool_name: &str, result: &str) -> String {
             let code = json["code"].as_str().unwrap_or("");
             let count = json["count"].as_u64().unwrap_or(0);
             let period = json["period"].as_str().unwrap_or("day");
-            format!("{} {}K线 {} 根", code, if period == "week" { "周" } else { "日" }, count)
+            let label = match period {
+                "week" => "周",
+                "month" => "月",
+                "day" => "日",
+                minute => minute,
+            };
+            format!("{} {}K线 {} 根", code, label, count)
+        }
+        "get_peer_comparison" => {
+            let name = json["name"].as_str().unwrap_or("");
+            let industry = json["industry"].as_str().unwrap_or("");
+            let rank = json["market_cap_rank"].as_u64().unwrap_or(0);
+            let size = json["industry_size"].as_u64().unwrap_or(0);
+            let pe = json["industry_median"]["pe_ttm"].as_str().unwrap_or("N/A");
+            format!("{} 所属「{}」共 {} 只，市值排名第 {}，行业PE中位数 {}", name, industry, size, rank, pe)
+        }
+        "get_stock_theme_exposure" => {
+            let code = json["code"].as_str().unwrap_or("");
+            let themes: Vec<String> = json["themes"]
+                .as_array()
+                .map(|arr| arr.iter().take(5).map(|t| {
+                    format!("{}({})", t["theme"].as_str().unwrap_or(""), t["weight"].as_str().unwrap_or(""))
+                }).collect())
+                .unwrap_or_default();
+            if themes.is_empty() {
+                format!("{} 未识别到题材", code)
+            } else {
+                format!("{} 题材暴露: {}", code, themes.join(" "))
+            }
+        }
+        "get_seasonality" => {
+            let code = json["code"].as_str().unwrap_or("");
+            let month = json["current_month"].as_u64().unwrap_or(0);
+            let find = |key: &str| {
+                json[key].as_array().and_then(|arr| {
+                    arr.iter().find(|b| b["period"].as_str() == Some(format!("{}月", month).as_str()))
+                }).map(|b| format!("{}（上涨概率 {}）", b["avg_return"].as_str().unwrap_or("N/A"), b["win_rate"].as_str().unwrap_or("N/A")))
+            };
+            let mut text = format!("{} 近{}年{}月平均涨跌 {}", code, json["years"], month, find("monthly").unwrap_or_else(|| "N/A".to_string()));
+            if let Some(sector) = find("sector_monthly") {
+                text.push_str(&format!("，所属「{}」板块同月 {}", json["sector"].as_str().unwrap_or(""), sector));
+            }
+            text
+        }
+        "get_earnings_forecast" => {
+            let name = json["name"].as_str().unwrap_or("");
+            let count = json["report_count"].as_u64().unwrap_or(0);
+            let years: Vec<String> = json["forecast"]
+                .as_array()
+                .map(|arr| arr.iter().map(|y| {
+                    format!("{}年EPS {} PE {}", y["year"], y["eps"].as_str().unwrap_or(""), y["forward_pe"].as_str().unwrap_or(""))
+                }).collect())
+                .unwrap_or_default();
+            format!("{} 近半年研报 {} 篇（买入{} 增持{}）{}", name, count, json["ratings"]["买入"], json["ratings"]["增持"], years.join("；"))
+        }
+        "get_stock_profile" => {
+            let name = json["name"].as_str().unwrap_or("");
+            let industry = json["industry"].as_str().unwrap_or("");
+            let top: Vec<String> = json["segments"]
+                .as_object()
+                .and_then(|m| m.get("按产品").or_else(|| m.get("按行业")))
+                .and_then(|v| v.as_array())
+                .map(|arr| arr.iter().take(3).map(|s| {
+                    format!("{} {}", s["name"].as_str().unwrap_or(""), s["revenue_ratio"].as_str().unwrap_or(""))
+                }).collect())
+                .unwrap_or_default();
+            format!("{} [{}] 主营构成: {}", name, industry, if top.is_empty() { "暂无".to_string() } else { top.join(" / ") })
         }
         "get_technical_indicators" => {
             let code = json["code"].as_str().unwrap_or("");
             let ma = json["ma_alignment"].as_str().unwrap_or("");
             let vp = json["volume_price_relation"].as_str().unwrap_or("");
             let summary = json["summary"].as_str().unwrap_or("");
-            format!("{} 均线:{} 量价:{}\n{}", code, ma, vp, summary)
+            let mut text = format!("{} 均线:{} 量价:{}\n{}", code, ma, vp, summary);
+            if let Some(risk) = json["risk"].as_object() {
+                text.push_str(&format!(
+                    "\n波动率(20日):{} Beta:{} 近一年最大回撤:{}",
+                    risk["volatility_20d"].as_str().unwrap_or("N/A"),
+                    risk["beta_vs_csi300"].as_str().unwrap_or("N/A"),
+                    risk["max_drawdown_1y"].as_str().unwrap_or("N/A"),
+                ));
+            }
+            text
         }
         _ => {
             format!("工具 {} 返回 {} 字节数据", tool_name, result.len())
@@ -1363,13 +2416,3 @@ fn truncate_str(s: &str, max_chars: usize) -> String {
     }
 }
 
-fn format_amount(v: f64) -> String {
-    let abs = v.abs();
-    if abs >= 1e8 {
-        format!("{:.2}亿", v / 1e8)
-    } else if abs >= 1e4 {
-        format!("{:.0}万", v / 1e4)
-    } else {
-        format!("{:.0}", v)
-    }
-}
@@ -0,0 +1,226 @@
+use anyhow::Result;
+use chrono::Local;
+use futures::stream::{self, StreamExt};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::db::database::Database;
+use crate::models::tracking::{PerformancePoint, StrategyPerformance};
+use crate::models::watchlist::KlineItem;
+use crate::services::history_kline::HistoryKlineService;
+
+pub const DEFAULT_HOLD_DAYS: usize = 5;
+/// 拉取日线的并发数
+const FETCH_CONCURRENCY: usize = 4;
+/// 单次统计最多评估的股票数，避免逐只拉取日线过慢
+const MAX_CODES: usize = 300;
+
+/// 模拟建仓价
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EntryPrice {
+    /// 入选日开盘价（盘前/竞价生成的买入指令）
+    Open,
+    /// 入选日收盘价（收盘后检测的技术信号）
+    Close,
+    /// 入选时记录的价格（选股完成时的最新价），为 0 时按收盘价
+    Fixed(f64),
+}
+
+/// 一次入选：某策略在 date 选出 code
+#[derive(Debug, Clone)]
+pub struct StrategyEntry {
+    pub date: String,
+    pub code: String,
+    pub entry: EntryPrice,
+}
+
+/// 在 date 当日（非交易日顺延至下一交易日）按 entry 建仓，持有 hold_days 个交易日后按收盘价卖出的收益 %。
+/// klines 按日期升序，持有期未满或缺少日线时返回 None
+pub fn simulate_return(klines: &[KlineItem], date: &str, entry: EntryPrice, hold_days: usize) -> Option<f64> {
+    let idx = klines.iter().position(|k| k.date.as_str() >= date)?;
+    let bar = &klines[idx];
+    let entry_price = match entry {
+        EntryPrice::Open => bar.open,
+        EntryPrice::Fixed(p) if p > 0.0 => p,
+        EntryPrice::Close | EntryPrice::Fixed(_) => bar.close,
+    };
+    let exit = klines.get(idx + hold_days)?;
+    (entry_price > 0.0).then(|| (exit.close / entry_price - 1.0) * 100.0)
+}
+
+fn round2(v: f64) -> f64 {
+    (v * 100.0).round() / 100.0
+}
+
+/// 汇总一个策略的逐次模拟收益：(入选日, 收益)，收益为 None 表示持有期未满
+pub fn summarize(strategy: &str, name: &str, days: i64, hold_days: usize, results: &[(String, Option<f64>)]) -> StrategyPerformance {
+    let returns: Vec<f64> = results.iter().filter_map(|(_, r)| *r).collect();
+    let mut by_date: BTreeMap<&str, Vec<f64>> = BTreeMap::new();
+    for (date, r) in results {
+        if let Some(r) = r {
+            by_date.entry(date.as_str()).or_default().push(*r);
+        }
+    }
+    let avg = |v: &[f64]| if v.is_empty() { 0.0 } else { v.iter().sum::<f64>() / v.len() as f64 };
+    StrategyPerformance {
+        strategy: strategy.to_string(),
+        name: name.to_string(),
+        days,
+        hold_days,
+        total: results.len(),
+        evaluated: returns.len(),
+        avg_return: round2(avg(&returns)),
+        win_rate: if returns.is_empty() {
+            0.0
+        } else {
+            round2(returns.iter().filter(|r| **r > 0.0).count() as f64 * 100.0 / returns.len() as f64)
+        },
+        best_return: returns.iter().copied().reduce(f64::max).map(round2),
+        worst_return: returns.iter().copied().reduce(f64::min).map(round2),
+        points: by_date
+            .into_iter()
+            .map(|(date, v)| PerformancePoint { date: date.to_string(), samples: v.len(), avg_return: round2(avg(&v)) })
+            .collect(),
+        updated_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+    }
+}
+
+/// 同一天重复入选的股票只计一次
+fn dedup(entries: Vec<StrategyEntry>) -> Vec<StrategyEntry> {
+    let mut seen = HashSet::new();
+    entries.into_iter().filter(|e| seen.insert((e.date.clone(), e.code.clone()))).collect()
+}
+
+/// 近 days 天各策略的入选记录：看多技术信号（收盘建仓）、AI 买入指令（开盘建仓）、AI 选股（按选股时价格建仓）
+fn collect_entries(db: &Database, since: &str) -> Result<Vec<(&'static str, &'static str, Vec<StrategyEntry>)>> {
+    let signals = db
+        .get_signals_since(since)?
+        .into_iter()
+        .filter(|s| s.direction == "bullish")
+        .map(|s| StrategyEntry { date: s.date, code: s.code, entry: EntryPrice::Close })
+        .collect();
+    let instructions = db
+        .get_instructions_since(since)?
+        .into_iter()
+        .filter(|i| i.action == "buy")
+        .map(|i| StrategyEntry { date: i.date, code: i.code, entry: EntryPrice::Open })
+        .collect();
+    let picks = db
+        .get_picks_since(since)?
+        .into_iter()
+        .map(|p| StrategyEntry { date: p.date, code: p.code, entry: EntryPrice::Fixed(p.entry_price) })
+        .collect();
+    Ok(vec![
+        ("technical_signal", "技术信号", dedup(signals)),
+        ("ai_instruction", "AI 买入指令", dedup(instructions)),
+        ("ai_pick", "AI 选股", dedup(picks)),
+    ])
+}
+
+/// 统计近 days 天扫描信号、AI 指令与 AI 选股的模拟持有收益与胜率
+pub async fn compute(db: &Database, days: i64, hold_days: usize) -> Result<Vec<StrategyPerformance>> {
+    let today = Local::now().format("%Y-%m-%d").to_string();
+    let since = (Local::now() - chrono::Duration::days(days)).format("%Y-%m-%d").to_string();
+    let strategies = collect_entries(db, &since)?;
+
+    let mut codes: Vec<String> = strategies
+        .iter()
+        .flat_map(|(_, _, entries)| entries.iter().map(|e| e.code.clone()))
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    if codes.len() > MAX_CODES {
+        log::warn!("[strategy_performance] {} codes exceed limit, only first {} evaluated", codes.len(), MAX_CODES);
+        codes.sort();
+        codes.truncate(MAX_CODES);
+    }
+
+    let service = HistoryKlineService::new()?;
+    let klines: HashMap<String, Vec<KlineItem>> = stream::iter(codes)
+        .map(|code| {
+            let (service, since, today) = (&service, &since, &today);
+            async move {
+                match service.fetch_kline(&code, "day", since, today, 640).await {
+                    Ok(k) => Some((code, k)),
+                    Err(e) => {
+                        log::warn!("[strategy_performance] fetch kline failed for {}: {}", code, e);
+                        None
+                    }
+                }
+            }
+        })
+        .buffer_unordered(FETCH_CONCURRENCY)
+        .filter_map(|r| async move { r })
+        .collect()
+        .await;
+
+    let performances: Vec<StrategyPerformance> = strategies
+        .iter()
+        .map(|(strategy, name, entries)| {
+            let results: Vec<(String, Option<f64>)> = entries
+                .iter()
+                .map(|e| {
+                    let r = klines.get(&e.code).and_then(|k| simulate_return(k, &e.date, e.entry, hold_days));
+                    (e.date.clone(), r)
+                })
+                .collect();
+            summarize(strategy, name, days, hold_days, &results)
+        })
+        .collect();
+    log::info!(
+        "[strategy_performance] compute days={} hold_days={} evaluated={:?}",
+        days, hold_days, performances.iter().map(|p| p.evaluated).collect::<Vec<_>>()
+    );
+    Ok(performances)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(date: &str, open: f64, close: f64) -> KlineItem {
+        KlineItem {
+            date: date.to_string(),
+            open,
+            close,
+            high: close,
+            low: open,
+            volume: 0.0,
+            amount: 0.0,
+            change_pct: 0.0,
+            turnover_rate: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_simulate_return() {
+        let klines = vec![
+            bar("2024-06-03", 10.0, 10.0),
+            bar("2024-06-04", 9.0, 12.0),
+            bar("2024-06-05", 12.0, 11.0),
+            bar("2024-06-06", 11.0, 13.2),
+        ];
+        assert_eq!(simulate_return(&klines, "2024-06-03", EntryPrice::Close, 2).map(round2), Some(10.0));
+        assert_eq!(simulate_return(&klines, "2024-06-04", EntryPrice::Open, 2).map(round2), Some(46.67));
+        assert_eq!(simulate_return(&klines, "2024-06-04", EntryPrice::Fixed(11.0), 1), Some(0.0));
+        // 周末入选顺延到下一交易日，持有期未满返回 None
+        assert_eq!(simulate_return(&klines, "2024-06-02", EntryPrice::Fixed(0.0), 3).map(round2), Some(32.0));
+        assert_eq!(simulate_return(&klines, "2024-06-05", EntryPrice::Close, 2), None);
+    }
+
+    #[test]
+    fn test_summarize() {
+        let results = vec![
+            ("2024-06-03".to_string(), Some(4.0)),
+            ("2024-06-03".to_string(), Some(-2.0)),
+            ("2024-06-04".to_string(), Some(1.0)),
+            ("2024-06-06".to_string(), None),
+        ];
+        let p = summarize("ai_pick", "AI 选股", 30, 5, &results);
+        assert_eq!((p.total, p.evaluated), (4, 3));
+        assert_eq!(p.avg_return, 1.0);
+        assert_eq!(p.win_rate, 66.67);
+        assert_eq!((p.best_return, p.worst_return), (Some(4.0), Some(-2.0)));
+        assert_eq!(p.points.len(), 2);
+        assert_eq!((p.points[0].samples, p.points[0].avg_return), (2, 1.0));
+    }
+}
@@ -0,0 +1,127 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::ai::StockSummaryForAI;
+use crate::models::settings::{StrategyZone, ZoneRule};
+
+/// 可用于区间规则的快照字段
+pub const ZONE_FIELDS: [&str; 6] = ["open_pct", "current_pct", "score", "bid_amount", "streak_days", "turnover"];
+
+/// 单个区间的分类结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZoneMembers {
+    pub zone_id: String,
+    pub zone_name: String,
+    pub stocks: Vec<StockSummaryForAI>,
+}
+
+fn field_value(stock: &StockSummaryForAI, field: &str) -> Option<f64> {
+    match field {
+        "open_pct" => Some(stock.open_pct),
+        "current_pct" => Some(stock.current_pct),
+        "score" => Some(stock.score as f64),
+        "bid_amount" => Some(stock.bid_amount),
+        "streak_days" => Some(stock.streak_days as f64),
+        "turnover" => Some(stock.turnover),
+        _ => None,
+    }
+}
+
+/// 未知字段的规则视为不满足
+fn rule_matches(rule: &ZoneRule, stock: &StockSummaryForAI) -> bool {
+    match field_value(stock, &rule.field) {
+        Some(v) => rule.min.map_or(true, |min| v >= min) && rule.max.map_or(true, |max| v <= max),
+        None => false,
+    }
+}
+
+/// 校验区间定义：名称非空、至少一条规则、字段合法、min 不大于 max
+pub fn validate_zones(zones: &[StrategyZone]) -> Result<(), String> {
+    for zone in zones {
+        if zone.name.trim().is_empty() {
+            return Err("区间名称不能为空".to_string());
+        }
+        if zone.rules.is_empty() {
+            return Err(format!("区间「{}」至少需要一条规则", zone.name));
+        }
+        for rule in &zone.rules {
+            if !ZONE_FIELDS.contains(&rule.field.as_str()) {
+                return Err(format!("区间「{}」包含未知字段: {}", zone.name, rule.field));
+            }
+            if let (Some(min), Some(max)) = (rule.min, rule.max) {
+                if min > max {
+                    return Err(format!("区间「{}」的 {} 下限大于上限", zone.name, rule.field));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 按启用的区间依次评估，一只股票可同时落入多个区间；空区间也会返回以便前端展示
+pub fn classify(zones: &[StrategyZone], stocks: &[StockSummaryForAI]) -> Vec<ZoneMembers> {
+    zones
+        .iter()
+        .filter(|z| z.enabled)
+        .map(|zone| ZoneMembers {
+            zone_id: zone.id.clone(),
+            zone_name: zone.name.clone(),
+            stocks: stocks
+                .iter()
+                .filter(|s| zone.rules.iter().all(|r| rule_matches(r, s)))
+                .cloned()
+                .collect(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stock(code: &str, open_pct: f64, bid_amount: f64, streak_days: u32) -> StockSummaryForAI {
+        StockSummaryForAI {
+            code: code.to_string(),
+            name: code.to_string(),
+            open_pct,
+            current_pct: open_pct,
+            score: 70,
+            bid_amount,
+            streak_days,
+            turnover: 5.0,
+            labels: vec![],
+        }
+    }
+
+    fn rule(field: &str, min: Option<f64>, max: Option<f64>) -> ZoneRule {
+        ZoneRule { field: field.to_string(), min, max }
+    }
+
+    #[test]
+    fn test_classify_by_rules() {
+        let zones = vec![
+            StrategyZone {
+                id: "a".into(),
+                name: "高开".into(),
+                enabled: true,
+                rules: vec![rule("open_pct", Some(3.0), None), rule("bid_amount", Some(1e7), None)],
+            },
+            StrategyZone {
+                id: "b".into(),
+                name: "连板".into(),
+                enabled: true,
+                rules: vec![rule("streak_days", Some(2.0), None)],
+            },
+            StrategyZone { id: "c".into(), name: "停用".into(), enabled: false, rules: vec![] },
+        ];
+        let stocks = vec![stock("s1", 5.0, 2e7, 3), stock("s2", 5.0, 5e6, 1), stock("s3", -1.0, 0.0, 2)];
+        let result = classify(&zones, &stocks);
+        assert_eq!(result.len(), 2);
+        let codes = |i: usize| result[i].stocks.iter().map(|s| s.code.as_str()).collect::<Vec<_>>();
+        assert_eq!(codes(0), vec!["s1"]);
+        assert_eq!(codes(1), vec!["s1", "s3"]);
+
+        let bad = vec![StrategyZone { id: "x".into(), name: "x".into(), enabled: true, rules: vec![rule("foo", None, None)] }];
+        assert!(validate_zones(&bad).is_err());
+        assert!(validate_zones(&zones[..2]).is_ok());
+    }
+}
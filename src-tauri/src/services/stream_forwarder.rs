@@ -0,0 +1,148 @@
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::mpsc::Receiver;
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+
+use crate::AppState;
+use crate::models::ai::AIStreamEvent;
+use crate::models::settings::StreamBatchConfig;
+
+/// 定时刷新的最短间隔，避免配置过小时空转
+const MIN_FLUSH_INTERVAL_MS: u64 = 10;
+
+/// 流式正文合并缓冲：content 增量攒到 max_chars 个字符或定时刷新时合并为一条事件，
+/// 其他事件推送前先刷出已缓冲的正文以保持顺序；done 未带内容时补上完整正文
+#[derive(Debug)]
+pub struct ContentBatcher {
+    enabled: bool,
+    max_chars: usize,
+    buf: String,
+    buf_chars: usize,
+    full: String,
+}
+
+impl ContentBatcher {
+    pub fn new(config: &StreamBatchConfig) -> Self {
+        Self {
+            enabled: config.enabled,
+            max_chars: config.max_chars.max(1),
+            buf: String::new(),
+            buf_chars: 0,
+            full: String::new(),
+        }
+    }
+
+    /// 处理一条事件，返回需要立即推送的事件（按顺序）
+    pub fn push(&mut self, mut event: AIStreamEvent) -> Vec<AIStreamEvent> {
+        if !self.enabled {
+            return vec![event];
+        }
+        if event.event_type == "content" {
+            let Some(delta) = event.content.as_deref() else {
+                return vec![];
+            };
+            self.full.push_str(delta);
+            self.buf.push_str(delta);
+            self.buf_chars += delta.chars().count();
+            return if self.buf_chars >= self.max_chars { self.flush().into_iter().collect() } else { vec![] };
+        }
+        let mut out: Vec<AIStreamEvent> = self.flush().into_iter().collect();
+        if event.event_type == "done" {
+            let full = std::mem::take(&mut self.full);
+            if event.content.is_none() && !full.is_empty() {
+                event.content = Some(full);
+            }
+        }
+        out.push(event);
+        out
+    }
+
+    /// 取出已缓冲的正文（定时刷新或流结束时调用）
+    pub fn flush(&mut self) -> Option<AIStreamEvent> {
+        if self.buf.is_empty() {
+            return None;
+        }
+        self.buf_chars = 0;
+        Some(AIStreamEvent {
+            event_type: "content".to_string(),
+            content: Some(std::mem::take(&mut self.buf)),
+            done: false,
+            usage: None,
+            tool_name: None,
+        })
+    }
+}
+
+/// 将 AI 流式事件转发到前端 event_name 事件，正文按设置中的 stream_batch 合并推送；
+/// 发送端全部释放后刷出剩余正文并结束
+pub fn spawn(app: &AppHandle, event_name: String, mut rx: Receiver<AIStreamEvent>) -> JoinHandle<()> {
+    let config = app
+        .state::<AppState>()
+        .db
+        .load_settings()
+        .map(|s| s.stream_batch)
+        .unwrap_or_default();
+    let app = app.clone();
+    tokio::spawn(async move {
+        let mut batcher = ContentBatcher::new(&config);
+        let mut ticker = tokio::time::interval(Duration::from_millis(config.flush_interval_ms.max(MIN_FLUSH_INTERVAL_MS)));
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let (mut received, mut emitted) = (0usize, 0usize);
+        let mut emit = |event: AIStreamEvent| {
+            emitted += 1;
+            let _ = app.emit(&event_name, &event);
+        };
+        loop {
+            tokio::select! {
+                event = rx.recv() => match event {
+                    Some(event) => {
+                        received += 1;
+                        batcher.push(event).into_iter().for_each(&mut emit);
+                    }
+                    None => break,
+                },
+                _ = ticker.tick() => batcher.flush().into_iter().for_each(&mut emit),
+            }
+        }
+        batcher.flush().into_iter().for_each(&mut emit);
+        log::debug!("[stream_forwarder] {} done received={} emitted={}", event_name, received, emitted);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(event_type: &str, content: Option<&str>) -> AIStreamEvent {
+        AIStreamEvent {
+            event_type: event_type.to_string(),
+            content: content.map(str::to_string),
+            done: event_type == "done",
+            usage: None,
+            tool_name: None,
+        }
+    }
+
+    #[test]
+    fn test_content_batcher() {
+        let config = StreamBatchConfig { enabled: true, flush_interval_ms: 50, max_chars: 4 };
+        let mut batcher = ContentBatcher::new(&config);
+        assert!(batcher.push(event("content", Some("买入"))).is_empty());
+        let out = batcher.push(event("content", Some("信号确认")));
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].content.as_deref(), Some("买入信号确认"));
+
+        // 其他事件先刷出缓冲正文，done 附带完整正文
+        batcher.push(event("content", Some("。")));
+        let out = batcher.push(event("done", None));
+        assert_eq!(out.iter().map(|e| e.event_type.as_str()).collect::<Vec<_>>(), ["content", "done"]);
+        assert_eq!(out[0].content.as_deref(), Some("。"));
+        assert_eq!(out[1].content.as_deref(), Some("买入信号确认。"));
+        assert!(batcher.flush().is_none());
+
+        let mut passthrough = ContentBatcher::new(&StreamBatchConfig { enabled: false, ..config });
+        assert_eq!(passthrough.push(event("content", Some("买"))).len(), 1);
+        assert_eq!(passthrough.push(event("done", None))[0].content, None);
+    }
+}
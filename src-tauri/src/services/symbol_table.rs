@@ -0,0 +1,310 @@
+use anyhow::{Result, anyhow};
+use chrono::{Duration as ChronoDuration, Local, NaiveDateTime};
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+use tauri::Manager;
+
+use crate::AppState;
+use crate::db::database::Database;
+use crate::models::ai::StockPick;
+use crate::models::stock::StockSymbol;
+use crate::services::ai_service;
+use crate::services::market_scanner::MarketScanner;
+use crate::services::stock_data::code_to_pure;
+use crate::services::stock_search::StockSearchIndex;
+use crate::services::job_scheduler::{JobSpec, JobTrigger};
+
+/// 代码表刷新周期
+const REFRESH_INTERVAL_DAYS: i64 = 7;
+/// 连续多久未在扫描中出现即视为退市并移除
+const STALE_DAYS: i64 = 30;
+/// 后台检查间隔：1 小时
+const CHECK_INTERVAL_SECS: u64 = 3600;
+/// 名称模糊匹配的最低相似度
+const MIN_SIMILARITY: f64 = 0.6;
+
+/// 代码/名称的校验结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    /// 代码与名称一致
+    Exact,
+    /// 代码或名称与代码表不一致，已按代码表纠正
+    Corrected,
+    /// 仅凭名称模糊匹配得到
+    Fuzzy,
+    /// 代码表中找不到，保持原样
+    Unknown,
+}
+
+/// 内存中的代码表索引（按 6 位纯数字代码与规范化名称，另含拼音检索索引）
+pub struct SymbolTable {
+    symbols: Vec<StockSymbol>,
+    by_code: HashMap<String, usize>,
+    by_name: HashMap<String, usize>,
+    search_index: StockSearchIndex,
+}
+
+impl SymbolTable {
+    pub fn new(symbols: Vec<StockSymbol>) -> Self {
+        let mut by_code = HashMap::with_capacity(symbols.len());
+        let mut by_name = HashMap::with_capacity(symbols.len());
+        for (i, s) in symbols.iter().enumerate() {
+            by_code.insert(code_to_pure(&s.code), i);
+            by_name.insert(normalize_name(&s.name), i);
+        }
+        let search_index = StockSearchIndex::new(&symbols);
+        Self { symbols, by_code, by_name, search_index }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    pub fn symbols(&self) -> &[StockSymbol] {
+        &self.symbols
+    }
+
+    /// 本地模糊检索：代码、名称子串、拼音首字母与全拼
+    pub fn search(&self, keyword: &str, limit: usize) -> Vec<&StockSymbol> {
+        self.search_index.search(keyword, limit).into_iter().map(|i| &self.symbols[i]).collect()
+    }
+
+    pub fn get(&self, code: &str) -> Option<&StockSymbol> {
+        self.by_code.get(&code_to_pure(code)).map(|&i| &self.symbols[i])
+    }
+
+    pub fn find_by_name(&self, name: &str) -> Option<&StockSymbol> {
+        self.by_name.get(&normalize_name(name)).map(|&i| &self.symbols[i])
+    }
+
+    /// 名称模糊匹配：先找唯一的包含关系，再按字符重合度取最高且不并列的一项
+    pub fn fuzzy_by_name(&self, name: &str) -> Option<&StockSymbol> {
+        let target = normalize_name(name);
+        if target.is_empty() {
+            return None;
+        }
+        if let Some(s) = self.find_by_name(name) {
+            return Some(s);
+        }
+
+        let contains: Vec<&StockSymbol> = self
+            .symbols
+            .iter()
+            .filter(|s| {
+                let n = normalize_name(&s.name);
+                n.contains(&target) || target.contains(&n)
+            })
+            .collect();
+        if contains.len() == 1 {
+            return Some(contains[0]);
+        }
+
+        let mut best: Option<(&StockSymbol, f64)> = None;
+        let mut tied = false;
+        for s in &self.symbols {
+            let score = similarity(&target, &normalize_name(&s.name));
+            match best {
+                Some((_, b)) if (score - b).abs() < f64::EPSILON => tied = true,
+                Some((_, b)) if score < b => {}
+                _ => {
+                    best = Some((s, score));
+                    tied = false;
+                }
+            }
+        }
+        match best {
+            Some((s, score)) if score >= MIN_SIMILARITY && !tied => Some(s),
+            _ => None,
+        }
+    }
+
+    /// 校验一组代码/名称。模型给出的名称能精确对应另一只股票时以名称为准（代码更容易被编错），
+    /// 否则以代码为准纠正名称；代码缺失或不存在时按名称模糊匹配
+    pub fn resolve(&self, code: &str, name: &str) -> (String, String, Resolution) {
+        let by_name = if name.trim().is_empty() { None } else { self.find_by_name(name) };
+        if let Some(s) = self.get(code) {
+            if normalize_name(&s.name) == normalize_name(name) {
+                return (s.code.clone(), s.name.clone(), Resolution::Exact);
+            }
+            let s = by_name.unwrap_or(s);
+            return (s.code.clone(), s.name.clone(), Resolution::Corrected);
+        }
+        if let Some(s) = by_name {
+            return (s.code.clone(), s.name.clone(), Resolution::Corrected);
+        }
+        match self.fuzzy_by_name(name) {
+            Some(s) => (s.code.clone(), s.name.clone(), Resolution::Fuzzy),
+            None => (code.to_string(), name.to_string(), Resolution::Unknown),
+        }
+    }
+}
+
+/// 名称规范化：去空白与 * 号，字母统一大写，全角字母数字转半角
+pub(crate) fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| !c.is_whitespace() && *c != '*' && *c != '＊')
+        .map(|c| match c {
+            '\u{ff01}'..='\u{ff5e}' => char::from_u32(c as u32 - 0xfee0).unwrap_or(c),
+            _ => c,
+        })
+        .collect::<String>()
+        .to_uppercase()
+}
+
+/// 字符级 Dice 相似度
+fn similarity(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let mut b: Vec<char> = b.chars().collect();
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let total = a.len() + b.len();
+    let mut common = 0;
+    for c in &a {
+        if let Some(pos) = b.iter().position(|x| x == c) {
+            b.swap_remove(pos);
+            common += 1;
+        }
+    }
+    2.0 * common as f64 / total as f64
+}
+
+fn cache() -> &'static RwLock<Option<Arc<SymbolTable>>> {
+    static CACHE: OnceLock<RwLock<Option<Arc<SymbolTable>>>> = OnceLock::new();
+    CACHE.get_or_init(|| RwLock::new(None))
+}
+
+/// 读取代码表（首次访问时从数据库加载并缓存在内存中）
+pub fn load(db: &Database) -> Result<Arc<SymbolTable>> {
+    if let Some(table) = cache().read().unwrap().as_ref() {
+        return Ok(Arc::clone(table));
+    }
+    let table = Arc::new(SymbolTable::new(db.get_stock_symbols()?));
+    *cache().write().unwrap() = Some(Arc::clone(&table));
+    Ok(table)
+}
+
+/// 从全市场扫描刷新代码表，返回本次写入条数
+pub async fn refresh(db: &Database) -> Result<usize> {
+    let stocks = MarketScanner::new()?.scan_full_market().await?;
+    if stocks.is_empty() {
+        return Err(anyhow!("全市场扫描返回为空"));
+    }
+    let symbols: Vec<StockSymbol> = stocks.into_iter().map(|s| StockSymbol { code: s.code, name: s.name }).collect();
+    let stale_before = (Local::now() - ChronoDuration::days(STALE_DAYS)).format("%Y-%m-%d %H:%M:%S").to_string();
+    db.save_stock_symbols(&symbols, &stale_before)?;
+    *cache().write().unwrap() = None;
+    log::info!("[symbol_table] refreshed {} symbols", symbols.len());
+    Ok(symbols.len())
+}
+
+fn needs_refresh(db: &Database) -> bool {
+    let updated_at = match db.get_stock_symbols_updated_at() {
+        Ok(Some(t)) => t,
+        _ => return true,
+    };
+    match NaiveDateTime::parse_from_str(&updated_at, "%Y-%m-%d %H:%M:%S") {
+        Ok(t) => Local::now().naive_local() - t > ChronoDuration::days(REFRESH_INTERVAL_DAYS),
+        Err(_) => true,
+    }
+}
+
+/// 后台任务：代码表为空或超过一周未刷新时从全市场扫描更新
+pub fn symbol_refresh_job() -> JobSpec {
+    JobSpec {
+        id: "symbol_refresh",
+        name: "股票代码表刷新",
+        trigger: JobTrigger::Interval,
+        interval_secs: CHECK_INTERVAL_SECS,
+        run: |app| Box::pin(async move {
+            let state = app.state::<AppState>();
+            if !needs_refresh(&state.db) {
+                return Ok(None);
+            }
+            let count = refresh(&state.db).await?;
+            Ok(Some(format!("更新 {} 只股票", count)))
+        }),
+    }
+}
+
+/// 校验诊断等命令的输入代码/名称。输入代码来自自选列表，代码存在时保持原样（前端按代码监听事件），
+/// 只按代码表纠正名称；代码无效时再按名称匹配。代码表不可用时原样返回
+pub fn resolve_input(db: &Database, code: &str, name: &str) -> (String, String) {
+    let table = match load(db) {
+        Ok(t) if !t.is_empty() => t,
+        Ok(_) => return (code.to_string(), name.to_string()),
+        Err(e) => {
+            log::warn!("[symbol_table] load failed: {}", e);
+            return (code.to_string(), name.to_string());
+        }
+    };
+    let (resolved_code, resolved_name) = match table.get(code) {
+        Some(s) => (code.to_string(), s.name.clone()),
+        None => {
+            let (c, n, _) = table.resolve(code, name);
+            (c, n)
+        }
+    };
+    if resolved_code != code || resolved_name != name {
+        log::info!("[symbol_table] resolve_input {} {} -> {} {}", code, name, resolved_code, resolved_name);
+    }
+    (resolved_code, resolved_name)
+}
+
+/// 校验报告 <PICKS> 中的代码/名称并改写报告，返回改写后的报告与纠正说明。
+/// 纠正后重复的股票只保留第一条
+pub fn resolve_picks(db: &Database, content: &str) -> Result<(String, Vec<String>)> {
+    let picks = ai_service::parse_picks(content);
+    let table = load(db)?;
+    if picks.is_empty() || table.is_empty() {
+        return Ok((content.to_string(), vec![]));
+    }
+
+    let mut corrections = Vec::new();
+    let mut resolved: Vec<StockPick> = Vec::with_capacity(picks.len());
+    for mut pick in picks {
+        let (code, name, resolution) = table.resolve(&pick.code, &pick.name);
+        if matches!(resolution, Resolution::Corrected | Resolution::Fuzzy) {
+            corrections.push(format!("{}({}) → {}({})", pick.name, pick.code, name, code));
+            pick.code = code;
+            pick.name = name;
+        }
+        if !resolved.iter().any(|p| p.code == pick.code) {
+            resolved.push(pick);
+        }
+    }
+    if corrections.is_empty() {
+        return Ok((content.to_string(), corrections));
+    }
+    log::info!("[symbol_table] resolve_picks corrected {} picks", corrections.len());
+    Ok((ai_service::replace_picks(content, &resolved), corrections))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> SymbolTable {
+        SymbolTable::new(
+            [("sh600519", "贵州茅台"), ("sz000858", "五粮液"), ("sz300750", "宁德时代"), ("sh600000", "浦发银行"), ("sz000001", "平安银行")]
+                .iter()
+                .map(|(code, name)| StockSymbol { code: code.to_string(), name: name.to_string() })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_resolve() {
+        let t = table();
+        assert_eq!(t.resolve("600519", "贵州茅台"), ("sh600519".to_string(), "贵州茅台".to_string(), Resolution::Exact));
+        // 代码错误但名称精确：以名称为准
+        assert_eq!(t.resolve("sh600518", "五粮液").0, "sz000858");
+        assert_eq!(t.resolve("sz000858", "贵州茅台").0, "sh600519");
+        // 名称不精确：以代码为准纠正名称
+        assert_eq!(t.resolve("sz300750", "宁德").1, "宁德时代");
+        // 只有名称：模糊匹配
+        assert_eq!(t.resolve("", "茅台"), ("sh600519".to_string(), "贵州茅台".to_string(), Resolution::Fuzzy));
+        // 并列时放弃
+        assert_eq!(t.resolve("", "银行").2, Resolution::Unknown);
+    }
+}
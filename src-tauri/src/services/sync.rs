@@ -0,0 +1,320 @@
+use anyhow::{anyhow, Result};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use tauri::Manager;
+
+use crate::AppState;
+use crate::db::database::Database;
+use crate::models::ai::AIAnalysisResult;
+use crate::models::settings::AppSettings;
+use crate::models::sync::{SyncResult, SyncSnapshot, SyncStatus};
+use crate::models::watchlist::WatchlistStock;
+use crate::services::job_scheduler::{JobSpec, JobTrigger};
+use crate::services::settings_io;
+use crate::services::sync_backend::{RemoteChanged, SyncTarget};
+use crate::utils::http;
+
+const SNAPSHOT_VERSION: u32 = 1;
+/// 同步最近多少天的 AI 分析记录，避免同步文件无限增长
+const ANALYSIS_SYNC_DAYS: i64 = 90;
+/// 远端在读取后被其他设备修改时的重试次数
+const MAX_ATTEMPTS: usize = 3;
+const SYNC_INTERVAL_SECS: u64 = 1800;
+
+const STATE_DEVICE_ID: &str = "device_id";
+const STATE_BASE: &str = "base_snapshot";
+const STATE_LAST_RESULT: &str = "last_result";
+
+/// 三方合并结果
+pub struct MergeOutcome {
+    pub snapshot: SyncSnapshot,
+    /// 合并后的自选股与本地不同时需要写回
+    pub watchlist_changed: bool,
+    /// 远端设置需要应用到本地时为 Some
+    pub settings_from_remote: Option<AppSettings>,
+    /// 本地缺少的分析记录
+    pub new_analyses: Vec<AIAnalysisResult>,
+    pub pulled: usize,
+    pub conflicts: usize,
+}
+
+fn now() -> String {
+    chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// 三方取值合并：一侧未改动则取另一侧；两侧都改动且不同视为冲突，按本地优先（本地已删除而远端仍在时保留远端，避免误删）
+fn merge_value<T: PartialEq + Clone>(base: Option<&T>, local: Option<&T>, remote: Option<&T>) -> (Option<T>, bool) {
+    if local == remote || remote == base {
+        return (local.cloned(), false);
+    }
+    if local == base {
+        return (remote.cloned(), false);
+    }
+    (local.or(remote).cloned(), true)
+}
+
+/// 自选股、设置、分析记录三方合并。base 为上次同步完成时的快照，首次同步时为空
+pub fn merge_snapshots(base: Option<&SyncSnapshot>, local: &SyncSnapshot, remote: Option<&SyncSnapshot>) -> MergeOutcome {
+    let index = |s: Option<&SyncSnapshot>| -> HashMap<String, WatchlistStock> {
+        s.map(|s| s.watchlist.iter().map(|w| (w.code.clone(), w.clone())).collect()).unwrap_or_default()
+    };
+    let (base_wl, local_wl, remote_wl) = (index(base), index(Some(local)), index(remote));
+    let codes: BTreeSet<&String> = base_wl.keys().chain(local_wl.keys()).chain(remote_wl.keys()).collect();
+
+    let mut conflicts = 0;
+    let mut pulled = 0;
+    let mut watchlist = Vec::new();
+    for code in codes {
+        let local_item = local_wl.get(code);
+        let (merged, conflict) = merge_value(base_wl.get(code), local_item, remote_wl.get(code));
+        conflicts += conflict as usize;
+        if merged.as_ref() != local_item {
+            pulled += 1;
+        }
+        watchlist.extend(merged);
+    }
+    watchlist.sort_by(|a, b| a.sort_order.cmp(&b.sort_order).then_with(|| a.created_at.cmp(&b.created_at)));
+
+    let base_settings = base.and_then(|b| b.settings.as_ref()).map(settings_value);
+    let local_settings = local.settings.as_ref().map(settings_value);
+    let remote_settings = remote.and_then(|r| r.settings.as_ref()).map(settings_value);
+    let (merged_settings, settings_conflict) = merge_value(base_settings.as_ref(), local_settings.as_ref(), remote_settings.as_ref());
+    conflicts += settings_conflict as usize;
+    let settings_from_remote = if merged_settings != local_settings {
+        pulled += 1;
+        remote.and_then(|r| r.settings.clone())
+    } else {
+        None
+    };
+
+    let local_ids: HashSet<&str> = local.analyses.iter().map(|a| a.id.as_str()).collect();
+    let new_analyses: Vec<AIAnalysisResult> = remote
+        .map(|r| r.analyses.iter().filter(|a| !local_ids.contains(a.id.as_str())).cloned().collect())
+        .unwrap_or_default();
+    pulled += new_analyses.len();
+    let mut analyses: Vec<AIAnalysisResult> = local.analyses.iter().chain(new_analyses.iter()).cloned().collect();
+    analyses.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+
+    let watchlist_changed = watchlist != local.watchlist;
+    MergeOutcome {
+        snapshot: SyncSnapshot {
+            version: SNAPSHOT_VERSION,
+            device_id: local.device_id.clone(),
+            updated_at: local.updated_at.clone(),
+            watchlist,
+            settings: settings_from_remote.clone().or_else(|| local.settings.clone()),
+            analyses,
+        },
+        watchlist_changed,
+        settings_from_remote,
+        new_analyses,
+        pulled,
+        conflicts,
+    }
+}
+
+/// 设置比较用的 JSON 值（字段顺序无关）
+fn settings_value(settings: &AppSettings) -> serde_json::Value {
+    serde_json::to_value(settings).unwrap_or_default()
+}
+
+/// 快照内容是否相同（忽略写入设备与时间）
+fn same_content(a: &SyncSnapshot, b: &SyncSnapshot) -> bool {
+    a.watchlist == b.watchlist
+        && a.settings.as_ref().map(settings_value) == b.settings.as_ref().map(settings_value)
+        && a.analyses.iter().map(|x| &x.id).eq(b.analyses.iter().map(|x| &x.id))
+}
+
+fn device_id(db: &Database) -> Result<String> {
+    if let Some(id) = db.get_sync_state(STATE_DEVICE_ID)? {
+        return Ok(id);
+    }
+    let id = uuid::Uuid::new_v4().to_string();
+    db.set_sync_state(STATE_DEVICE_ID, &id)?;
+    Ok(id)
+}
+
+fn local_snapshot(db: &Database, settings: &AppSettings) -> Result<SyncSnapshot> {
+    let since = (chrono::Local::now() - chrono::Duration::days(ANALYSIS_SYNC_DAYS)).format("%Y-%m-%d").to_string();
+    Ok(SyncSnapshot {
+        version: SNAPSHOT_VERSION,
+        device_id: device_id(db)?,
+        updated_at: now(),
+        watchlist: db.get_watchlist_stocks()?,
+        settings: Some(settings_io::shareable(settings)),
+        analyses: db.get_ai_analyses_since(&since)?,
+    })
+}
+
+/// 应用远端设置：保留本机的 API Key、设备标识、同步配置、本机 API、推送目标与调试开关
+fn apply_remote_settings(db: &Database, current: &AppSettings, remote: &AppSettings) -> Result<()> {
+    let json = serde_json::to_string(remote)?;
+    let mut settings = settings_io::import_json(current, &json)?;
+    settings.sync = current.sync.clone();
+    settings.api_server = current.api_server.clone();
+    settings.notify_targets = current.notify_targets.clone();
+    settings.tts = current.tts.clone();
+    settings.debug_logging = current.debug_logging;
+    settings.quick_search_hotkey = current.quick_search_hotkey.clone();
+    db.save_settings(&settings)?;
+    http::set_request_logging(settings.debug_logging);
+    Ok(())
+}
+
+/// 执行一次同步：读取远端快照，与本地及上次同步基准三方合并，写回本地并在有变化时条件写入远端
+pub async fn sync_now(db: &Database) -> Result<SyncResult> {
+    let settings = db.load_settings()?;
+    let target = SyncTarget::new(&settings.sync)?;
+    let base: Option<SyncSnapshot> = db
+        .get_sync_state(STATE_BASE)?
+        .and_then(|json| serde_json::from_str(&json).ok());
+
+    for attempt in 1..=MAX_ATTEMPTS {
+        let remote_file = target.fetch().await?;
+        let remote: Option<SyncSnapshot> = match &remote_file {
+            Some(file) => Some(serde_json::from_slice(&file.data).map_err(|e| anyhow!("远端同步文件格式不正确: {}", e))?),
+            None => None,
+        };
+        if let Some(r) = &remote {
+            if r.version > SNAPSHOT_VERSION {
+                return Err(anyhow!("远端同步文件版本 {} 高于当前应用支持的版本，请先升级应用", r.version));
+            }
+        }
+
+        let current = db.load_settings()?;
+        let local = local_snapshot(db, &current)?;
+        let outcome = merge_snapshots(base.as_ref(), &local, remote.as_ref());
+        let pushed = !remote.as_ref().is_some_and(|r| same_content(r, &outcome.snapshot));
+        if pushed {
+            let data = serde_json::to_vec(&outcome.snapshot)?;
+            let etag = remote_file.as_ref().and_then(|f| f.etag.as_deref());
+            match target.store(&data, etag).await {
+                Ok(()) => {}
+                Err(e) if e.downcast_ref::<RemoteChanged>().is_some() && attempt < MAX_ATTEMPTS => {
+                    log::warn!("[sync] remote changed during sync, retry attempt={}", attempt);
+                    continue;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        if outcome.watchlist_changed {
+            db.replace_watchlist(&outcome.snapshot.watchlist)?;
+        }
+        if let Some(remote_settings) = &outcome.settings_from_remote {
+            apply_remote_settings(db, &current, remote_settings)?;
+        }
+        for analysis in &outcome.new_analyses {
+            db.save_ai_analysis(analysis)?;
+        }
+        db.set_sync_state(STATE_BASE, &serde_json::to_string(&outcome.snapshot)?)?;
+
+        let result = SyncResult {
+            synced_at: now(),
+            pulled: outcome.pulled,
+            pushed,
+            conflicts: outcome.conflicts,
+            message: format!(
+                "拉取 {} 项变更{}{}",
+                outcome.pulled,
+                if pushed { "，已上传本地变更" } else { "" },
+                if outcome.conflicts > 0 { format!("，{} 处冲突按本机优先", outcome.conflicts) } else { String::new() }
+            ),
+        };
+        db.set_sync_state(STATE_LAST_RESULT, &serde_json::to_string(&result)?)?;
+        log::info!(
+            "[sync] sync_now pulled={} pushed={} conflicts={} attempt={}",
+            result.pulled, result.pushed, result.conflicts, attempt
+        );
+        return Ok(result);
+    }
+    Err(anyhow!("远端同步文件持续被其他设备修改，请稍后重试"))
+}
+
+/// 测试同步服务连通性与鉴权（读取一次远端文件）
+pub async fn test_connection(config: &crate::models::settings::SyncConfig) -> Result<String> {
+    let target = SyncTarget::new(config)?;
+    Ok(match target.fetch().await? {
+        Some(file) => format!("连接成功，远端已有同步数据（{} 字节）", file.data.len()),
+        None => "连接成功，远端暂无同步数据".to_string(),
+    })
+}
+
+pub fn get_status(db: &Database) -> Result<SyncStatus> {
+    let last_result: Option<SyncResult> = db
+        .get_sync_state(STATE_LAST_RESULT)?
+        .and_then(|json| serde_json::from_str(&json).ok());
+    Ok(SyncStatus {
+        device_id: device_id(db)?,
+        last_synced_at: last_result.as_ref().map(|r| r.synced_at.clone()),
+        last_result,
+    })
+}
+
+/// 后台任务：启用多设备同步后定时同步
+pub fn sync_job() -> JobSpec {
+    JobSpec {
+        id: "multi_device_sync",
+        name: "多设备同步",
+        trigger: JobTrigger::Interval,
+        interval_secs: SYNC_INTERVAL_SECS,
+        run: |app| Box::pin(async move {
+            let db = &app.state::<AppState>().db;
+            let settings = db.load_settings()?;
+            if !settings.sync.enabled || settings.sync.endpoint.trim().is_empty() {
+                return Ok(None);
+            }
+            Ok(Some(sync_now(db).await?.message))
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stock(code: &str, group: &str) -> WatchlistStock {
+        WatchlistStock {
+            code: code.to_string(),
+            name: code.to_string(),
+            sort_order: 0,
+            group_name: group.to_string(),
+            created_at: "2024-06-01 10:00:00".to_string(),
+        }
+    }
+
+    fn snapshot(watchlist: Vec<WatchlistStock>) -> SyncSnapshot {
+        SyncSnapshot {
+            version: SNAPSHOT_VERSION,
+            device_id: "local".to_string(),
+            updated_at: String::new(),
+            watchlist,
+            settings: None,
+            analyses: vec![],
+        }
+    }
+
+    #[test]
+    fn test_merge_snapshots() {
+        let base = snapshot(vec![stock("sh600000", ""), stock("sz000001", ""), stock("sz000002", "")]);
+        // 本地删除 600000、把 000001 移到“银行”组；远端新增 300750、把 000001 移到“观察”组、删除 000002
+        let local = snapshot(vec![stock("sz000001", "银行"), stock("sz000002", "")]);
+        let remote = snapshot(vec![stock("sh600000", ""), stock("sz000001", "观察"), stock("sz300750", "")]);
+
+        let outcome = merge_snapshots(Some(&base), &local, Some(&remote));
+        let merged: Vec<(&str, &str)> = outcome
+            .snapshot
+            .watchlist
+            .iter()
+            .map(|s| (s.code.as_str(), s.group_name.as_str()))
+            .collect();
+        assert_eq!(merged, vec![("sz000001", "银行"), ("sz300750", "")]);
+        assert_eq!(outcome.conflicts, 1);
+        assert_eq!(outcome.pulled, 2);
+        assert!(outcome.watchlist_changed);
+
+        // 首次同步：两端并集
+        let first = merge_snapshots(None, &snapshot(vec![stock("sh600000", "")]), Some(&snapshot(vec![stock("sz000001", "")])));
+        assert_eq!(first.snapshot.watchlist.len(), 2);
+        assert_eq!(first.conflicts, 0);
+    }
+}
@@ -1,52 +1,101 @@
+use std::collections::VecDeque;
+
+use crate::models::settings::SignalConfig;
+use crate::models::stock::StockDailyHistory;
 use crate::models::watchlist::{
-    KlineItem, MaAlignment, TechnicalIndicators, TechnicalSignal, VolumePriceRelation,
+    GapAnalysis, KlineItem, MaAlignment, PriceGap, TechnicalIndicators, TechnicalSignal, VolumeBin, VolumePriceRelation,
+    VolumeProfile,
 };
 
+/// 缺口最小幅度 %（相对前一日收盘价），过滤一分钱级别的跳空
+const MIN_GAP_PCT: f64 = 0.3;
+/// 价值区覆盖的成交量比例
+const VALUE_AREA_RATIO: f64 = 0.7;
+/// 返回的高量节点数
+const MAX_VOLUME_NODES: usize = 3;
+
+/// 本地日线缓存（升序）转换为指标计算所需的K线序列
+pub fn klines_from_history(history: &[StockDailyHistory]) -> Vec<KlineItem> {
+    history.iter().map(|h| KlineItem {
+        date: h.date.clone(),
+        open: h.open,
+        close: h.close,
+        high: h.high,
+        low: h.low,
+        volume: h.volume,
+        amount: h.amount,
+        change_pct: h.change_pct,
+        turnover_rate: h.turnover_rate,
+    }).collect()
+}
+
 /// 计算所有技术指标
 pub fn compute_indicators(klines: &[KlineItem]) -> TechnicalIndicators {
     let closes: Vec<f64> = klines.iter().map(|k| k.close).collect();
     let highs: Vec<f64> = klines.iter().map(|k| k.high).collect();
     let lows: Vec<f64> = klines.iter().map(|k| k.low).collect();
-    let volumes: Vec<f64> = klines.iter().map(|k| k.volume).collect();
     let dates: Vec<String> = klines.iter().map(|k| k.date.clone()).collect();
 
-    let ma5 = calc_ma(&closes, 5);
-    let ma10 = calc_ma(&closes, 10);
-    let ma20 = calc_ma(&closes, 20);
-    let ma60 = calc_ma(&closes, 60);
-
-    let ema12 = calc_ema(&closes, 12);
-    let ema26 = calc_ema(&closes, 26);
-    let (macd_dif, macd_dea, macd_hist) = calc_macd(&closes, 12, 26, 9);
-    let (kdj_k, kdj_d, kdj_j) = calc_kdj(&highs, &lows, &closes, 9, 3, 3);
-    let rsi6 = calc_rsi(&closes, 6);
-    let rsi12 = calc_rsi(&closes, 12);
-    let rsi24 = calc_rsi(&closes, 24);
-    let (boll_upper, boll_middle, boll_lower) = calc_boll(&closes, 20, 2.0);
-
-    let _ = volumes; // used in signal detection
+    let mut m = SeriesMatrix::new(COL_COUNT, closes.len());
+    calc_ma(&closes, 5, m.col_mut(COL_MA5));
+    calc_ma(&closes, 10, m.col_mut(COL_MA10));
+    calc_ma(&closes, 20, m.col_mut(COL_MA20));
+    calc_ma(&closes, 60, m.col_mut(COL_MA60));
+
+    calc_ema(&closes, 12, m.col_mut(COL_EMA12));
+    calc_ema(&closes, 26, m.col_mut(COL_EMA26));
+    let [ema12, ema26, dif, dea, hist] = m.cols_mut([COL_EMA12, COL_EMA26, COL_DIF, COL_DEA, COL_HIST]);
+    calc_macd(ema12, ema26, 9, dif, dea, hist);
+    let [k, d, j] = m.cols_mut([COL_K, COL_D, COL_J]);
+    calc_kdj(&highs, &lows, &closes, 9, 3, 3, k, d, j);
+    calc_rsi(&closes, 6, m.col_mut(COL_RSI6));
+    calc_rsi(&closes, 12, m.col_mut(COL_RSI12));
+    calc_rsi(&closes, 24, m.col_mut(COL_RSI24));
+    let [upper, middle, lower] = m.cols_mut([COL_BOLL_UPPER, COL_BOLL_MIDDLE, COL_BOLL_LOWER]);
+    calc_boll(&closes, 20, 2.0, upper, middle, lower);
+    let [dc_upper, dc_lower] = m.cols_mut([COL_DC_UPPER, COL_DC_LOWER]);
+    calc_donchian(&highs, &lows, 20, dc_upper, dc_lower);
+    let [kc_upper, kc_middle, kc_lower] = m.cols_mut([COL_KC_UPPER, COL_KC_MIDDLE, COL_KC_LOWER]);
+    calc_keltner(&highs, &lows, &closes, 20, 10, 2.0, kc_upper, kc_middle, kc_lower);
 
     TechnicalIndicators {
         dates,
-        ma5, ma10, ma20, ma60,
-        ema12, ema26,
-        macd_dif, macd_dea, macd_hist,
-        kdj_k, kdj_d, kdj_j,
-        rsi6, rsi12, rsi24,
-        boll_upper, boll_middle, boll_lower,
+        ma5: m.to_options(COL_MA5),
+        ma10: m.to_options(COL_MA10),
+        ma20: m.to_options(COL_MA20),
+        ma60: m.to_options(COL_MA60),
+        ema12: m.to_options(COL_EMA12),
+        ema26: m.to_options(COL_EMA26),
+        macd_dif: m.to_options(COL_DIF),
+        macd_dea: m.to_options(COL_DEA),
+        macd_hist: m.to_options(COL_HIST),
+        kdj_k: m.to_options(COL_K),
+        kdj_d: m.to_options(COL_D),
+        kdj_j: m.to_options(COL_J),
+        rsi6: m.to_options(COL_RSI6),
+        rsi12: m.to_options(COL_RSI12),
+        rsi24: m.to_options(COL_RSI24),
+        boll_upper: m.to_options(COL_BOLL_UPPER),
+        boll_middle: m.to_options(COL_BOLL_MIDDLE),
+        boll_lower: m.to_options(COL_BOLL_LOWER),
+        donchian_upper: m.to_options(COL_DC_UPPER),
+        donchian_lower: m.to_options(COL_DC_LOWER),
+        keltner_upper: m.to_options(COL_KC_UPPER),
+        keltner_middle: m.to_options(COL_KC_MIDDLE),
+        keltner_lower: m.to_options(COL_KC_LOWER),
     }
 }
 
-/// 检测技术信号
-pub fn detect_signals(klines: &[KlineItem], indicators: &TechnicalIndicators) -> Vec<TechnicalSignal> {
+/// 检测技术信号（阈值、回看窗口与启用的信号类型由 SignalConfig 控制）
+pub fn detect_signals(klines: &[KlineItem], indicators: &TechnicalIndicators, config: &SignalConfig) -> Vec<TechnicalSignal> {
     let mut signals = Vec::new();
     let n = klines.len();
     if n < 3 {
         return signals;
     }
 
-    // 只检测最近5个交易日的信号
-    let check_start = if n > 5 { n - 5 } else { 0 };
+    // 只检测最近 lookback_days 个交易日的信号
+    let check_start = n.saturating_sub(config.lookback_days.max(1));
 
     for i in check_start..n {
         if i < 1 { continue; }
@@ -61,20 +110,20 @@ pub fn detect_signals(klines: &[KlineItem], indicators: &TechnicalIndicators) ->
 
         // KDJ 超买超卖
         if let (Some(k), Some(d)) = (indicators.kdj_k[i], indicators.kdj_d[i]) {
-            if k > 80.0 && d > 80.0 {
+            if k > config.kdj_overbought && d > config.kdj_overbought {
                 signals.push(TechnicalSignal {
                     signal_type: "kdj_overbought".into(),
                     direction: "bearish".into(),
                     description: format!("KDJ超买 K={:.1} D={:.1}", k, d),
-                    strength: if k > 90.0 { 4 } else { 3 },
+                    strength: if k > config.kdj_overbought + 10.0 { 4 } else { 3 },
                     date: klines[i].date.clone(),
                 });
-            } else if k < 20.0 && d < 20.0 {
+            } else if k < config.kdj_oversold && d < config.kdj_oversold {
                 signals.push(TechnicalSignal {
                     signal_type: "kdj_oversold".into(),
                     direction: "bullish".into(),
                     description: format!("KDJ超卖 K={:.1} D={:.1}", k, d),
-                    strength: if k < 10.0 { 4 } else { 3 },
+                    strength: if k < config.kdj_oversold - 10.0 { 4 } else { 3 },
                     date: klines[i].date.clone(),
                 });
             }
@@ -82,20 +131,20 @@ pub fn detect_signals(klines: &[KlineItem], indicators: &TechnicalIndicators) ->
 
         // RSI 超买超卖
         if let Some(rsi) = indicators.rsi6[i] {
-            if rsi > 80.0 {
+            if rsi > config.rsi_overbought {
                 signals.push(TechnicalSignal {
                     signal_type: "rsi_overbought".into(),
                     direction: "bearish".into(),
                     description: format!("RSI6超买 {:.1}", rsi),
-                    strength: if rsi > 90.0 { 4 } else { 3 },
+                    strength: if rsi > config.rsi_overbought + 10.0 { 4 } else { 3 },
                     date: klines[i].date.clone(),
                 });
-            } else if rsi < 20.0 {
+            } else if rsi < config.rsi_oversold {
                 signals.push(TechnicalSignal {
                     signal_type: "rsi_oversold".into(),
                     direction: "bullish".into(),
                     description: format!("RSI6超卖 {:.1}", rsi),
-                    strength: if rsi < 10.0 { 4 } else { 3 },
+                    strength: if rsi < config.rsi_oversold - 10.0 { 4 } else { 3 },
                     date: klines[i].date.clone(),
                 });
             }
@@ -122,25 +171,40 @@ pub fn detect_signals(klines: &[KlineItem], indicators: &TechnicalIndicators) ->
             }
         }
 
+        // 唐奇安通道突破（海龟入场：收盘价首次突破前 20 日最高/最低价）
+        detect_channel_break(
+            klines, &indicators.donchian_upper, &indicators.donchian_lower, i,
+            ("donchian_breakout_up", "donchian_breakout_down", "20日唐奇安通道", 4),
+            &mut signals,
+        );
+
+        // 肯特纳通道突破（趋势跟随：收盘价首次站上上轨 / 跌破下轨）
+        detect_channel_break(
+            klines, &indicators.keltner_upper, &indicators.keltner_lower, i,
+            ("keltner_break_upper", "keltner_break_lower", "肯特纳通道", 3),
+            &mut signals,
+        );
+
         // 放量信号
         if i >= 5 {
             let avg_vol: f64 = klines[i-5..i].iter().map(|k| k.volume).sum::<f64>() / 5.0;
             if avg_vol > 0.0 {
                 let vol_ratio = klines[i].volume / avg_vol;
-                if vol_ratio > 2.0 && klines[i].change_pct > 0.0 {
+                let strong_ratio = config.volume_surge_ratio * 1.5;
+                if vol_ratio > config.volume_surge_ratio && klines[i].change_pct > 0.0 {
                     signals.push(TechnicalSignal {
                         signal_type: "volume_surge_up".into(),
                         direction: "bullish".into(),
                         description: format!("放量上攻 量比{:.1}", vol_ratio),
-                        strength: if vol_ratio > 3.0 { 4 } else { 3 },
+                        strength: if vol_ratio > strong_ratio { 4 } else { 3 },
                         date: klines[i].date.clone(),
                     });
-                } else if vol_ratio > 2.0 && klines[i].change_pct < -1.0 {
+                } else if vol_ratio > config.volume_surge_ratio && klines[i].change_pct < -1.0 {
                     signals.push(TechnicalSignal {
                         signal_type: "volume_surge_down".into(),
                         direction: "bearish".into(),
                         description: format!("放量下跌 量比{:.1}", vol_ratio),
-                        strength: if vol_ratio > 3.0 { 4 } else { 3 },
+                        strength: if vol_ratio > strong_ratio { 4 } else { 3 },
                         date: klines[i].date.clone(),
                     });
                 }
@@ -148,12 +212,164 @@ pub fn detect_signals(klines: &[KlineItem], indicators: &TechnicalIndicators) ->
         }
     }
 
-    // MACD 顶背离/底背离检测（最近30个交易日）
-    detect_macd_divergence(klines, indicators, &mut signals);
+    // MACD 顶背离/底背离检测（最近 divergence_window 个交易日）
+    detect_macd_divergence(klines, indicators, config.divergence_window, &mut signals);
+
+    // 回看窗口内出现且尚未回补的跳空缺口
+    for gap in detect_gaps(klines).open_gaps.iter().filter(|g| g.days_open < n - check_start) {
+        let up = gap.direction == "up";
+        signals.push(TechnicalSignal {
+            signal_type: if up { "gap_up" } else { "gap_down" }.into(),
+            direction: if up { "bullish" } else { "bearish" }.into(),
+            description: format!(
+                "{}跳空缺口 {:.2}-{:.2} 未回补",
+                if up { "向上" } else { "向下" },
+                gap.lower,
+                gap.upper
+            ),
+            strength: if gap.size_pct >= 2.0 { 4 } else { 3 },
+            date: gap.date.clone(),
+        });
+    }
 
+    signals.retain(|s| config.is_enabled(&s.signal_type));
     signals
 }
 
+/// 检测日线跳空缺口：向上缺口为今日最低价高于昨日最高价，向下缺口为今日最高价低于昨日最低价。
+/// 之后的K线进入缺口区间即视为部分回补（区间收窄），完全穿越则视为回补，记录回补所用交易日数
+pub fn detect_gaps(klines: &[KlineItem]) -> GapAnalysis {
+    // (缺口, 出现位置, 回补位置)
+    let mut gaps: Vec<(PriceGap, usize, Option<usize>)> = Vec::new();
+    for i in 1..klines.len() {
+        let (prev, cur) = (&klines[i - 1], &klines[i]);
+        for (gap, _, filled_at) in gaps.iter_mut().filter(|g| g.2.is_none()) {
+            if gap.direction == "up" {
+                if cur.low <= gap.lower {
+                    *filled_at = Some(i);
+                } else {
+                    gap.upper = gap.upper.min(cur.low);
+                }
+            } else if cur.high >= gap.upper {
+                *filled_at = Some(i);
+            } else {
+                gap.lower = gap.lower.max(cur.high);
+            }
+        }
+
+        if prev.close <= 0.0 {
+            continue;
+        }
+        let (direction, lower, upper) = if cur.low > prev.high {
+            ("up", prev.high, cur.low)
+        } else if cur.high < prev.low {
+            ("down", cur.high, prev.low)
+        } else {
+            continue;
+        };
+        let size_pct = (upper - lower) / prev.close * 100.0;
+        if size_pct < MIN_GAP_PCT {
+            continue;
+        }
+        gaps.push((
+            PriceGap { direction: direction.into(), date: cur.date.clone(), upper, lower, size_pct, days_open: 0 },
+            i,
+            None,
+        ));
+    }
+
+    let mut fill_days: Vec<usize> = gaps.iter().filter_map(|(_, at, filled)| filled.map(|f| f - at)).collect();
+    fill_days.sort_unstable();
+    let total = gaps.len();
+    let filled = fill_days.len();
+    let last = klines.len().saturating_sub(1);
+    let mut open_gaps: Vec<PriceGap> = gaps
+        .into_iter()
+        .filter(|(_, _, filled)| filled.is_none())
+        .map(|(mut gap, at, _)| {
+            gap.days_open = last - at;
+            gap
+        })
+        .collect();
+    open_gaps.reverse();
+
+    GapAnalysis {
+        open_gaps,
+        total,
+        filled,
+        fill_rate_pct: (total > 0).then(|| filled as f64 * 100.0 / total as f64),
+        median_fill_days: (filled > 0).then(|| {
+            if filled % 2 == 1 {
+                fill_days[filled / 2] as f64
+            } else {
+                (fill_days[filled / 2 - 1] + fill_days[filled / 2]) as f64 / 2.0
+            }
+        }),
+        avg_fill_days: (filled > 0).then(|| fill_days.iter().sum::<usize>() as f64 / filled as f64),
+    }
+}
+
+/// 计算成交量价格分布：每根K线的成交量按价格均匀分摊到其最高/最低价覆盖的区间。
+/// source/days 仅用于标注数据来源；K线为空或价格无波动时返回 None
+pub fn compute_volume_profile(klines: &[KlineItem], bin_count: usize, source: &str, days: usize) -> Option<VolumeProfile> {
+    let bin_count = bin_count.max(1);
+    let min = klines.iter().map(|k| k.low).filter(|v| *v > 0.0).fold(f64::INFINITY, f64::min);
+    let max = klines.iter().map(|k| k.high).fold(f64::NEG_INFINITY, f64::max);
+    if !min.is_finite() || max <= min {
+        return None;
+    }
+    let step = (max - min) / bin_count as f64;
+    let mut volumes = vec![0.0; bin_count];
+    for k in klines.iter().filter(|k| k.low > 0.0 && k.volume > 0.0) {
+        let first = (((k.low - min) / step) as usize).min(bin_count - 1);
+        let last = (((k.high - min) / step) as usize).min(bin_count - 1);
+        let share = k.volume / (last - first + 1) as f64;
+        for v in &mut volumes[first..=last] {
+            *v += share;
+        }
+    }
+    let mid = |i: usize| min + step * (i as f64 + 0.5);
+
+    let poc = (0..bin_count).max_by(|&a, &b| volumes[a].total_cmp(&volumes[b]))?;
+    // 价值区：从 POC 向两侧扩展，每次并入成交量较大的一侧，直到覆盖 70% 成交量
+    let total: f64 = volumes.iter().sum();
+    let (mut lo, mut hi, mut covered) = (poc, poc, volumes[poc]);
+    while covered < total * VALUE_AREA_RATIO && (lo > 0 || hi < bin_count - 1) {
+        let below = if lo > 0 { volumes[lo - 1] } else { -1.0 };
+        let above = if hi < bin_count - 1 { volumes[hi + 1] } else { -1.0 };
+        if above >= below {
+            hi += 1;
+            covered += above;
+        } else {
+            lo -= 1;
+            covered += below;
+        }
+    }
+
+    let mut peaks: Vec<usize> = (0..bin_count)
+        .filter(|&i| {
+            volumes[i] > 0.0
+                && (i == 0 || volumes[i] >= volumes[i - 1])
+                && (i == bin_count - 1 || volumes[i] > volumes[i + 1])
+        })
+        .collect();
+    peaks.sort_by(|&a, &b| volumes[b].total_cmp(&volumes[a]));
+
+    Some(VolumeProfile {
+        source: source.to_string(),
+        days,
+        bins: volumes
+            .iter()
+            .enumerate()
+            .map(|(i, &volume)| VolumeBin { price_low: min + step * i as f64, price_high: min + step * (i + 1) as f64, volume })
+            .collect(),
+        poc_price: mid(poc),
+        value_area_low: min + step * lo as f64,
+        value_area_high: min + step * (hi + 1) as f64,
+        high_volume_nodes: peaks.into_iter().take(MAX_VOLUME_NODES).map(mid).collect(),
+    })
+}
+
 /// 判断均线排列状态
 pub fn determine_ma_alignment(indicators: &TechnicalIndicators) -> MaAlignment {
     let n = indicators.ma5.len();
@@ -243,87 +459,169 @@ pub fn generate_summary(
 }
 
 // ====== 指标计算函数 ======
+//
+// 中间结果写入按列连续存放的 f64 矩阵（NaN 表示数据不足），最后统一转换为 Option 序列。
+// 所有指标均为单遍 O(n)：均线滑动求和，BOLL 滑动 Welford 方差，KDJ 区间高低点用单调队列。
+
+const COL_MA5: usize = 0;
+const COL_MA10: usize = 1;
+const COL_MA20: usize = 2;
+const COL_MA60: usize = 3;
+const COL_EMA12: usize = 4;
+const COL_EMA26: usize = 5;
+const COL_DIF: usize = 6;
+const COL_DEA: usize = 7;
+const COL_HIST: usize = 8;
+const COL_K: usize = 9;
+const COL_D: usize = 10;
+const COL_J: usize = 11;
+const COL_RSI6: usize = 12;
+const COL_RSI12: usize = 13;
+const COL_RSI24: usize = 14;
+const COL_BOLL_UPPER: usize = 15;
+const COL_BOLL_MIDDLE: usize = 16;
+const COL_BOLL_LOWER: usize = 17;
+const COL_DC_UPPER: usize = 18;
+const COL_DC_LOWER: usize = 19;
+const COL_KC_UPPER: usize = 20;
+const COL_KC_MIDDLE: usize = 21;
+const COL_KC_LOWER: usize = 22;
+const COL_COUNT: usize = 23;
+
+/// 列优先的连续 f64 矩阵，一次分配容纳全部指标序列
+struct SeriesMatrix {
+    len: usize,
+    data: Vec<f64>,
+}
+
+impl SeriesMatrix {
+    fn new(cols: usize, len: usize) -> Self {
+        Self { len, data: vec![f64::NAN; cols * len] }
+    }
+
+    fn col_mut(&mut self, col: usize) -> &mut [f64] {
+        &mut self.data[col * self.len..(col + 1) * self.len]
+    }
 
-fn calc_ma(data: &[f64], period: usize) -> Vec<Option<f64>> {
-    let mut result = vec![None; data.len()];
-    if data.len() < period { return result; }
+    /// 同时借出多个互不相同的列
+    fn cols_mut<const N: usize>(&mut self, cols: [usize; N]) -> [&mut [f64]; N] {
+        let mut chunks: Vec<Option<&mut [f64]>> = if self.len == 0 {
+            (0..COL_COUNT).map(|_| Some(&mut [][..])).collect()
+        } else {
+            self.data.chunks_mut(self.len).map(Some).collect()
+        };
+        cols.map(|c| chunks[c].take().expect("列索引重复"))
+    }
+
+    fn to_options(&self, col: usize) -> Vec<Option<f64>> {
+        self.data[col * self.len..(col + 1) * self.len]
+            .iter()
+            .map(|v| if v.is_nan() { None } else { Some(*v) })
+            .collect()
+    }
+}
+
+fn calc_ma(data: &[f64], period: usize, out: &mut [f64]) {
+    if period == 0 || data.len() < period { return; }
 
     let mut sum: f64 = data[..period].iter().sum();
-    result[period - 1] = Some(sum / period as f64);
+    out[period - 1] = sum / period as f64;
 
     for i in period..data.len() {
         sum += data[i] - data[i - period];
-        result[i] = Some(sum / period as f64);
+        out[i] = sum / period as f64;
     }
-    result
 }
 
-fn calc_ema(data: &[f64], period: usize) -> Vec<Option<f64>> {
-    let mut result = vec![None; data.len()];
-    if data.is_empty() || period == 0 { return result; }
+fn calc_ema(data: &[f64], period: usize, out: &mut [f64]) {
+    if data.is_empty() || period == 0 { return; }
 
     let multiplier = 2.0 / (period as f64 + 1.0);
-    result[0] = Some(data[0]);
+    out[0] = data[0];
 
     for i in 1..data.len() {
-        let prev = result[i - 1].unwrap_or(data[i]);
-        result[i] = Some(data[i] * multiplier + prev * (1.0 - multiplier));
+        out[i] = data[i] * multiplier + out[i - 1] * (1.0 - multiplier);
     }
-    result
 }
 
-fn calc_macd(data: &[f64], fast: usize, slow: usize, signal: usize) -> (Vec<Option<f64>>, Vec<Option<f64>>, Vec<Option<f64>>) {
-    let ema_fast = calc_ema(data, fast);
-    let ema_slow = calc_ema(data, slow);
-    let n = data.len();
-
-    let mut dif = vec![None; n];
-    let mut dif_values = Vec::new();
-
-    for i in 0..n {
-        if let (Some(f), Some(s)) = (ema_fast[i], ema_slow[i]) {
-            let d = f - s;
-            dif[i] = Some(d);
-            dif_values.push(d);
-        }
+/// DIF = EMA(fast) - EMA(slow)，DEA = EMA(DIF, signal)，HIST = (DIF - DEA) * 2
+fn calc_macd(ema_fast: &[f64], ema_slow: &[f64], signal: usize, dif: &mut [f64], dea: &mut [f64], hist: &mut [f64]) {
+    for i in 0..dif.len() {
+        dif[i] = ema_fast[i] - ema_slow[i];
     }
+    calc_ema(dif, signal, dea);
+    for i in 0..hist.len() {
+        hist[i] = (dif[i] - dea[i]) * 2.0;
+    }
+}
 
-    // DEA = EMA(DIF, signal)
-    let dea_raw = calc_ema(&dif_values, signal);
-    let mut dea = vec![None; n];
-    let mut hist = vec![None; n];
-    let mut dea_idx = 0;
-
-    for i in 0..n {
-        if dif[i].is_some() {
-            if dea_idx < dea_raw.len() {
-                dea[i] = dea_raw[dea_idx];
-                if let (Some(d), Some(de)) = (dif[i], dea[i]) {
-                    hist[i] = Some((d - de) * 2.0);
-                }
-                dea_idx += 1;
-            }
+/// 唐奇安通道：第 i 根为前 n 根（不含当根）的最高价/最低价，收盘价突破即为海龟入场信号
+fn calc_donchian(highs: &[f64], lows: &[f64], n: usize, upper: &mut [f64], lower: &mut [f64]) {
+    if n == 0 { return; }
+    let mut max_q: VecDeque<usize> = VecDeque::with_capacity(n);
+    let mut min_q: VecDeque<usize> = VecDeque::with_capacity(n);
+
+    for i in 0..highs.len() {
+        if i >= n {
+            while max_q.front().is_some_and(|&f| f < i - n) { max_q.pop_front(); }
+            while min_q.front().is_some_and(|&f| f < i - n) { min_q.pop_front(); }
+            upper[i] = highs[max_q[0]];
+            lower[i] = lows[min_q[0]];
         }
+        while max_q.back().is_some_and(|&b| highs[b] <= highs[i]) { max_q.pop_back(); }
+        max_q.push_back(i);
+        while min_q.back().is_some_and(|&b| lows[b] >= lows[i]) { min_q.pop_back(); }
+        min_q.push_back(i);
     }
-
-    (dif, dea, hist)
 }
 
-fn calc_kdj(highs: &[f64], lows: &[f64], closes: &[f64], n: usize, m1: usize, m2: usize) -> (Vec<Option<f64>>, Vec<Option<f64>>, Vec<Option<f64>>) {
+/// 肯特纳通道：中轨 EMA(close, ema_period)，上下轨为中轨 ± multiplier × ATR(atr_period)，
+/// ATR 为真实波幅的 EMA。前 ema_period - 1 根数据不足，保持 NaN
+#[allow(clippy::too_many_arguments)]
+fn calc_keltner(highs: &[f64], lows: &[f64], closes: &[f64], ema_period: usize, atr_period: usize, multiplier: f64, upper: &mut [f64], middle: &mut [f64], lower: &mut [f64]) {
     let len = closes.len();
-    let mut k_vals = vec![None; len];
-    let mut d_vals = vec![None; len];
-    let mut j_vals = vec![None; len];
+    if ema_period == 0 || len < ema_period { return; }
+
+    let true_range: Vec<f64> = (0..len).map(|i| {
+        let range = highs[i] - lows[i];
+        if i == 0 { return range; }
+        range.max((highs[i] - closes[i - 1]).abs()).max((lows[i] - closes[i - 1]).abs())
+    }).collect();
+    let mut ema = vec![f64::NAN; len];
+    let mut atr = vec![f64::NAN; len];
+    calc_ema(closes, ema_period, &mut ema);
+    calc_ema(&true_range, atr_period, &mut atr);
+
+    for i in ema_period - 1..len {
+        middle[i] = ema[i];
+        upper[i] = ema[i] + multiplier * atr[i];
+        lower[i] = ema[i] - multiplier * atr[i];
+    }
+}
 
-    if len < n { return (k_vals, d_vals, j_vals); }
+#[allow(clippy::too_many_arguments)]
+fn calc_kdj(highs: &[f64], lows: &[f64], closes: &[f64], n: usize, m1: usize, m2: usize, k_out: &mut [f64], d_out: &mut [f64], j_out: &mut [f64]) {
+    let len = closes.len();
+    if n == 0 || len < n { return; }
 
     let mut prev_k = 50.0_f64;
     let mut prev_d = 50.0_f64;
+    // 单调队列：队首分别为窗口内最高价/最低价的下标
+    let mut max_q: VecDeque<usize> = VecDeque::with_capacity(n);
+    let mut min_q: VecDeque<usize> = VecDeque::with_capacity(n);
+
+    for i in 0..len {
+        while max_q.back().is_some_and(|&b| highs[b] <= highs[i]) { max_q.pop_back(); }
+        max_q.push_back(i);
+        while min_q.back().is_some_and(|&b| lows[b] >= lows[i]) { min_q.pop_back(); }
+        min_q.push_back(i);
+        if i + 1 < n { continue; }
 
-    for i in (n - 1)..len {
         let start = i + 1 - n;
-        let highest = highs[start..=i].iter().cloned().fold(f64::NEG_INFINITY, f64::max);
-        let lowest = lows[start..=i].iter().cloned().fold(f64::INFINITY, f64::min);
+        while max_q.front().is_some_and(|&f| f < start) { max_q.pop_front(); }
+        while min_q.front().is_some_and(|&f| f < start) { min_q.pop_front(); }
+        let highest = highs[max_q[0]];
+        let lowest = lows[min_q[0]];
 
         let rsv = if (highest - lowest).abs() < 1e-10 {
             50.0
@@ -333,22 +631,22 @@ fn calc_kdj(highs: &[f64], lows: &[f64], closes: &[f64], n: usize, m1: usize, m2
 
         let k = prev_k * (m1 as f64 - 1.0) / m1 as f64 + rsv / m1 as f64;
         let d = prev_d * (m2 as f64 - 1.0) / m2 as f64 + k / m2 as f64;
-        let j = 3.0 * k - 2.0 * d;
 
-        k_vals[i] = Some(k);
-        d_vals[i] = Some(d);
-        j_vals[i] = Some(j);
+        k_out[i] = k;
+        d_out[i] = d;
+        j_out[i] = 3.0 * k - 2.0 * d;
 
         prev_k = k;
         prev_d = d;
     }
-
-    (k_vals, d_vals, j_vals)
 }
 
-fn calc_rsi(data: &[f64], period: usize) -> Vec<Option<f64>> {
-    let mut result = vec![None; data.len()];
-    if data.len() < period + 1 { return result; }
+fn calc_rsi(data: &[f64], period: usize, out: &mut [f64]) {
+    if period == 0 || data.len() < period + 1 { return; }
+
+    let rsi = |avg_gain: f64, avg_loss: f64| {
+        if avg_loss.abs() < 1e-10 { 100.0 } else { 100.0 - 100.0 / (1.0 + avg_gain / avg_loss) }
+    };
 
     let mut avg_gain = 0.0;
     let mut avg_loss = 0.0;
@@ -361,13 +659,7 @@ fn calc_rsi(data: &[f64], period: usize) -> Vec<Option<f64>> {
 
     avg_gain /= period as f64;
     avg_loss /= period as f64;
-
-    if avg_loss.abs() < 1e-10 {
-        result[period] = Some(100.0);
-    } else {
-        let rs = avg_gain / avg_loss;
-        result[period] = Some(100.0 - 100.0 / (1.0 + rs));
-    }
+    out[period] = rsi(avg_gain, avg_loss);
 
     for i in (period + 1)..data.len() {
         let change = data[i] - data[i - 1];
@@ -375,39 +667,37 @@ fn calc_rsi(data: &[f64], period: usize) -> Vec<Option<f64>> {
 
         avg_gain = (avg_gain * (period as f64 - 1.0) + gain) / period as f64;
         avg_loss = (avg_loss * (period as f64 - 1.0) + loss) / period as f64;
-
-        if avg_loss.abs() < 1e-10 {
-            result[i] = Some(100.0);
-        } else {
-            let rs = avg_gain / avg_loss;
-            result[i] = Some(100.0 - 100.0 / (1.0 + rs));
-        }
+        out[i] = rsi(avg_gain, avg_loss);
     }
-
-    result
 }
 
-fn calc_boll(data: &[f64], period: usize, multiplier: f64) -> (Vec<Option<f64>>, Vec<Option<f64>>, Vec<Option<f64>>) {
+/// 布林带：窗口滑动时用 Welford 增量更新均值与平方差和，避免逐窗口重算方差
+fn calc_boll(data: &[f64], period: usize, multiplier: f64, upper: &mut [f64], middle: &mut [f64], lower: &mut [f64]) {
     let n = data.len();
-    let mut upper = vec![None; n];
-    let mut middle = vec![None; n];
-    let mut lower = vec![None; n];
-
-    if n < period { return (upper, middle, lower); }
+    if period == 0 || n < period { return; }
+
+    let p = period as f64;
+    let mut mean = 0.0;
+    let mut m2 = 0.0;
+    for (count, x) in data[..period].iter().enumerate() {
+        let delta = x - mean;
+        mean += delta / (count + 1) as f64;
+        m2 += delta * (x - mean);
+    }
 
     for i in (period - 1)..n {
-        let start = i + 1 - period;
-        let slice = &data[start..=i];
-        let mean: f64 = slice.iter().sum::<f64>() / period as f64;
-        let variance: f64 = slice.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / period as f64;
-        let std_dev = variance.sqrt();
-
-        middle[i] = Some(mean);
-        upper[i] = Some(mean + multiplier * std_dev);
-        lower[i] = Some(mean - multiplier * std_dev);
+        if i >= period {
+            // 移出 data[i - period]、移入 data[i]，窗口大小不变
+            let (old, new) = (data[i - period], data[i]);
+            let new_mean = mean + (new - old) / p;
+            m2 += (new - old) * (new - new_mean + old - mean);
+            mean = new_mean;
+        }
+        let std_dev = (m2.max(0.0) / p).sqrt();
+        middle[i] = mean;
+        upper[i] = mean + multiplier * std_dev;
+        lower[i] = mean - multiplier * std_dev;
     }
-
-    (upper, middle, lower)
 }
 
 fn detect_ma_cross(fast: &[Option<f64>], slow: &[Option<f64>], i: usize, label: &str, date: &str, signals: &mut Vec<TechnicalSignal>) {
@@ -433,13 +723,50 @@ fn detect_ma_cross(fast: &[Option<f64>], slow: &[Option<f64>], i: usize, label:
     }
 }
 
-fn detect_macd_divergence(klines: &[KlineItem], indicators: &TechnicalIndicators, signals: &mut Vec<TechnicalSignal>) {
+/// 通道突破：第 i 根收盘价突破上轨（或跌破下轨）且前一根尚未突破时产生信号。
+/// labels 为 (向上信号类型, 向下信号类型, 通道名称, 信号强度)
+fn detect_channel_break(
+    klines: &[KlineItem],
+    upper: &[Option<f64>],
+    lower: &[Option<f64>],
+    i: usize,
+    labels: (&str, &str, &str, u8),
+    signals: &mut Vec<TechnicalSignal>,
+) {
+    let (up_type, down_type, channel, strength) = labels;
+    let (close, prev_close) = (klines[i].close, klines[i - 1].close);
+    if let (Some(u), Some(prev_u)) = (upper[i], upper[i - 1]) {
+        if close > u && prev_close <= prev_u {
+            signals.push(TechnicalSignal {
+                signal_type: up_type.into(),
+                direction: "bullish".into(),
+                description: format!("向上突破{}上轨 {:.2}", channel, u),
+                strength,
+                date: klines[i].date.clone(),
+            });
+            return;
+        }
+    }
+    if let (Some(l), Some(prev_l)) = (lower[i], lower[i - 1]) {
+        if close < l && prev_close >= prev_l {
+            signals.push(TechnicalSignal {
+                signal_type: down_type.into(),
+                direction: "bearish".into(),
+                description: format!("向下跌破{}下轨 {:.2}", channel, l),
+                strength,
+                date: klines[i].date.clone(),
+            });
+        }
+    }
+}
+
+fn detect_macd_divergence(klines: &[KlineItem], indicators: &TechnicalIndicators, window: usize, signals: &mut Vec<TechnicalSignal>) {
     let n = klines.len();
-    if n < 30 { return; }
+    if window < 3 || n < window { return; }
 
-    let check_range = n.saturating_sub(30)..n;
+    let check_range = n.saturating_sub(window)..n;
 
-    // 找最近30日内的两个价格高点和对应的MACD DIF
+    // 找窗口内的两个价格高点和对应的MACD DIF
     let mut highs_points: Vec<(usize, f64, f64)> = Vec::new(); // (index, price_high, dif)
     for i in check_range.clone() {
         if i < 1 || i >= n - 1 { continue; }
@@ -491,3 +818,125 @@ fn detect_macd_divergence(klines: &[KlineItem], indicators: &TechnicalIndicators
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_series(n: usize) -> Vec<f64> {
+        let mut seed = 42u64;
+        let mut price = 10.0;
+        (0..n).map(|_| {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            let r = (seed >> 33) as f64 / (1u64 << 31) as f64 - 0.5;
+            price *= 1.0 + r * 0.08;
+            price
+        }).collect()
+    }
+
+    #[test]
+    fn test_boll_matches_naive_variance() {
+        let data = sample_series(200);
+        let (mut upper, mut middle, mut lower) = (vec![f64::NAN; 200], vec![f64::NAN; 200], vec![f64::NAN; 200]);
+        calc_boll(&data, 20, 2.0, &mut upper, &mut middle, &mut lower);
+        assert!(middle[18].is_nan());
+        for i in 19..200 {
+            let slice = &data[i - 19..=i];
+            let mean = slice.iter().sum::<f64>() / 20.0;
+            let std_dev = (slice.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / 20.0).sqrt();
+            assert!((middle[i] - mean).abs() < 1e-9);
+            assert!((upper[i] - (mean + 2.0 * std_dev)).abs() < 1e-9);
+            assert!((lower[i] - (mean - 2.0 * std_dev)).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_kdj_window_extremes() {
+        let closes = sample_series(120);
+        let highs: Vec<f64> = closes.iter().map(|c| c * 1.02).collect();
+        let lows: Vec<f64> = closes.iter().map(|c| c * 0.98).collect();
+        let (mut k, mut d, mut j) = (vec![f64::NAN; 120], vec![f64::NAN; 120], vec![f64::NAN; 120]);
+        calc_kdj(&highs, &lows, &closes, 9, 3, 3, &mut k, &mut d, &mut j);
+
+        let (mut prev_k, mut prev_d) = (50.0, 50.0);
+        for i in 8..120 {
+            let highest = highs[i - 8..=i].iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+            let lowest = lows[i - 8..=i].iter().cloned().fold(f64::INFINITY, f64::min);
+            let rsv = (closes[i] - lowest) / (highest - lowest) * 100.0;
+            prev_k = prev_k * 2.0 / 3.0 + rsv / 3.0;
+            prev_d = prev_d * 2.0 / 3.0 + prev_k / 3.0;
+            assert!((k[i] - prev_k).abs() < 1e-9);
+            assert!((d[i] - prev_d).abs() < 1e-9);
+            assert!((j[i] - (3.0 * prev_k - 2.0 * prev_d)).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_detect_gaps() {
+        // (high, low)：第 1 根向上跳空 10.0-10.5，第 3 根回补；第 4 根向下跳空 9.5-9.8 部分回补至 9.6
+        let bars = [(10.0, 9.5), (11.0, 10.5), (11.2, 10.6), (10.7, 9.8), (9.5, 9.0), (9.6, 9.1)];
+        let klines: Vec<KlineItem> = bars.iter().enumerate().map(|(i, &(h, l))| KlineItem {
+            date: format!("d{}", i), open: l, close: (h + l) / 2.0, high: h, low: l, volume: 1.0,
+            amount: 0.0, change_pct: 0.0, turnover_rate: 0.0,
+        }).collect();
+        let gaps = detect_gaps(&klines);
+        assert_eq!((gaps.total, gaps.filled), (2, 1));
+        assert_eq!(gaps.median_fill_days, Some(2.0));
+        assert_eq!(gaps.open_gaps.len(), 1);
+        let open = &gaps.open_gaps[0];
+        assert_eq!((open.direction.as_str(), open.date.as_str(), open.days_open), ("down", "d4", 1));
+        assert!((open.lower - 9.6).abs() < 1e-9 && (open.upper - 9.8).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_volume_profile() {
+        // 价格 10~20，成交集中在 12 附近，次峰在 18 附近
+        let bars = [(10.0, 11.0, 100.0), (11.5, 12.5, 900.0), (11.5, 12.5, 800.0), (17.5, 18.5, 500.0), (19.0, 20.0, 50.0)];
+        let klines: Vec<KlineItem> = bars.iter().map(|&(l, h, v)| KlineItem {
+            date: String::new(), open: l, close: h, high: h, low: l, volume: v,
+            amount: 0.0, change_pct: 0.0, turnover_rate: 0.0,
+        }).collect();
+        let profile = compute_volume_profile(&klines, 10, "day", 5).unwrap();
+        assert_eq!(profile.bins.len(), 10);
+        assert!((profile.bins.iter().map(|b| b.volume).sum::<f64>() - 2350.0).abs() < 1e-6);
+        assert!(profile.poc_price > 11.0 && profile.poc_price < 13.0);
+        assert!(profile.value_area_low <= 11.5 && profile.value_area_high >= 12.5);
+        assert_eq!(profile.high_volume_nodes.len(), 2);
+        assert!(profile.high_volume_nodes[1] > 17.0 && profile.high_volume_nodes[1] < 19.0);
+        assert!(compute_volume_profile(&[], 10, "day", 0).is_none());
+    }
+
+    #[test]
+    fn test_channels_and_breakout() {
+        // 30 根横盘后第 31 根放量突破
+        let mut closes = vec![10.0; 30];
+        closes.push(11.0);
+        let klines: Vec<KlineItem> = closes.iter().enumerate().map(|(i, &c)| KlineItem {
+            date: format!("d{}", i), open: c, close: c, high: c + 0.1, low: c - 0.1, volume: 1.0,
+            amount: 0.0, change_pct: 0.0, turnover_rate: 0.0,
+        }).collect();
+        let ind = compute_indicators(&klines);
+        assert_eq!(ind.donchian_upper[19], None);
+        assert!((ind.donchian_upper[30].unwrap() - 10.1).abs() < 1e-9);
+        assert!((ind.donchian_lower[30].unwrap() - 9.9).abs() < 1e-9);
+        assert_eq!(ind.keltner_middle[18], None);
+        assert!(ind.keltner_upper[29].unwrap() > ind.keltner_middle[29].unwrap());
+
+        let config = SignalConfig { lookback_days: 1, ..Default::default() };
+        let signals = detect_signals(&klines, &ind, &config);
+        assert!(signals.iter().any(|s| s.signal_type == "donchian_breakout_up"));
+        assert!(signals.iter().any(|s| s.signal_type == "keltner_break_upper"));
+    }
+
+    #[test]
+    fn test_compute_indicators_empty_and_short() {
+        assert!(compute_indicators(&[]).ma5.is_empty());
+        let klines: Vec<KlineItem> = sample_series(3).into_iter().map(|c| KlineItem {
+            date: String::new(), open: c, close: c, high: c, low: c, volume: 1.0,
+            amount: 0.0, change_pct: 0.0, turnover_rate: 0.0,
+        }).collect();
+        let ind = compute_indicators(&klines);
+        assert_eq!(ind.ma5, vec![None, None, None]);
+        assert!(ind.ema12.iter().all(|v| v.is_some()));
+    }
+}
@@ -0,0 +1,113 @@
+use anyhow::{Result, anyhow};
+use chrono::Local;
+use tauri::Manager;
+
+use crate::db::database::Database;
+use crate::models::settings::SignalConfig;
+use crate::models::stock::StockDailyHistory;
+use crate::models::watchlist::TechnicalDaily;
+use crate::services::technical_indicators;
+use crate::services::job_scheduler::{JobSpec, JobTrigger};
+use crate::AppState;
+
+/// 参与预计算所需的最少日线条数（MA60 / 背离检测需要足够的历史）
+pub const MIN_HISTORY_DAYS: usize = 60;
+/// technical_daily 中保存的信号覆盖最近 N 个交易日
+pub const STORE_SIGNAL_DAYS: usize = 10;
+/// 每只股票参与计算的日线条数，足够 EMA 收敛与 60 日均线
+const COMPUTE_BARS: usize = 250;
+/// 收盘后开始预计算的时间（HHMM），排在快照归档与信号扫描之后
+const REFRESH_AFTER: u32 = 1530;
+const CHECK_INTERVAL_SECS: u64 = 600;
+
+/// 由日线计算单只股票最新交易日的指标快照；数据不足时返回 None
+pub fn compute_technical_daily(code: &str, history: &[StockDailyHistory], config: &SignalConfig) -> Option<TechnicalDaily> {
+    if history.len() < MIN_HISTORY_DAYS {
+        return None;
+    }
+    let klines = technical_indicators::klines_from_history(history);
+    let indicators = technical_indicators::compute_indicators(&klines);
+    let signals = technical_indicators::detect_signals(&klines, &indicators, config);
+    let ma_alignment = serde_json::to_value(technical_indicators::determine_ma_alignment(&indicators))
+        .ok()
+        .and_then(|v| v.as_str().map(|s| s.to_string()))
+        .unwrap_or_default();
+
+    let i = klines.len() - 1;
+    let last = &klines[i];
+    Some(TechnicalDaily {
+        code: code.to_string(),
+        date: last.date.clone(),
+        close: last.close,
+        change_pct: last.change_pct,
+        ma5: indicators.ma5[i],
+        ma10: indicators.ma10[i],
+        ma20: indicators.ma20[i],
+        ma60: indicators.ma60[i],
+        macd_dif: indicators.macd_dif[i],
+        macd_dea: indicators.macd_dea[i],
+        macd_hist: indicators.macd_hist[i],
+        kdj_k: indicators.kdj_k[i],
+        kdj_d: indicators.kdj_d[i],
+        kdj_j: indicators.kdj_j[i],
+        rsi6: indicators.rsi6[i],
+        boll_upper: indicators.boll_upper[i],
+        boll_middle: indicators.boll_middle[i],
+        boll_lower: indicators.boll_lower[i],
+        ma_alignment,
+        signals,
+        updated_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+    })
+}
+
+/// 增量刷新 technical_daily：只重算本地日线比已存指标更新的股票，返回重算条数。
+/// codes 为 None 时覆盖本地日线缓存中的全部股票；force 为 true 时忽略已存日期全部重算（如修改了信号参数后）
+pub fn refresh_technical_daily(db: &Database, codes: Option<&[String]>, force: bool) -> Result<usize> {
+    let latest_dates = db.get_latest_history_dates()?;
+    let stored_dates = if force { Default::default() } else { db.get_technical_daily_dates()? };
+
+    // 预计算覆盖全部信号类型，阈值沿用用户设置
+    let mut config = db.load_settings()?.signal_config;
+    config.lookback_days = STORE_SIGNAL_DAYS;
+    config.enabled_signals.clear();
+
+    let targets: Vec<&String> = match codes {
+        Some(codes) => codes.iter().filter(|c| latest_dates.contains_key(*c)).collect(),
+        None => latest_dates.keys().collect(),
+    };
+
+    let mut records = Vec::new();
+    for code in targets {
+        if stored_dates.get(code) == latest_dates.get(code) {
+            continue;
+        }
+        let history = db.get_daily_history_asc(code, COMPUTE_BARS)?;
+        if let Some(record) = compute_technical_daily(code, &history, &config) {
+            records.push(record);
+        }
+    }
+
+    if !records.is_empty() {
+        db.save_technical_daily(&records)?;
+    }
+    log::info!("[technical_store] refresh_technical_daily force={} updated={}", force, records.len());
+    Ok(records.len())
+}
+
+/// 后台任务：交易日收盘后对全部本地日线做一次增量刷新
+pub fn technical_refresh_job() -> JobSpec {
+    JobSpec {
+        id: "technical_refresh",
+        name: "技术指标预计算",
+        trigger: JobTrigger::DailyAfter(REFRESH_AFTER),
+        interval_secs: CHECK_INTERVAL_SECS,
+        run: |app| Box::pin(async move {
+            let count = tauri::async_runtime::spawn_blocking(move || {
+                refresh_technical_daily(&app.state::<AppState>().db, None, false)
+            })
+            .await
+            .map_err(|e| anyhow!("刷新任务异常退出: {}", e))??;
+            Ok(Some(format!("刷新 {} 条", count)))
+        }),
+    }
+}
@@ -0,0 +1,225 @@
+use anyhow::Result;
+use futures::stream::{self, StreamExt};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::models::news::NewsItem;
+use crate::models::stock::{BoardQuote, ThemeExposure, ThemeWeight};
+use crate::services::board_members;
+use crate::services::market_scanner::MarketScanner;
+use crate::services::news_service;
+
+/// 参与题材热度统计的快讯条数
+const HEADLINE_COUNT: u32 = 100;
+/// 快讯缓存有效期，批量计算时共用一份
+const HEADLINE_TTL: Duration = Duration::from_secs(600);
+/// 每个题材保留的驱动快讯条数
+const MAX_HEADLINES: usize = 3;
+/// 快讯提及次数计入权重的上限，避免单一热点压过全部题材
+const MAX_MENTIONS: u32 = 6;
+/// 概念板块行情缓存有效期，配合成分股缓存替代逐只查询所属板块
+const BOARD_QUOTE_TTL: Duration = Duration::from_secs(60);
+/// 近期新纳入成分股的权重加成
+const NEW_MEMBER_BOOST: f64 = 0.5;
+/// 扫描结果批量查询题材的并发数
+const FETCH_CONCURRENCY: usize = 8;
+/// 不代表题材的板块（指数成分、交易通道、财务状态等）
+const NON_THEME_KEYWORDS: [&str; 14] = [
+    "融资融券", "沪股通", "深股通", "MSCI", "标准普尔", "富时罗素", "HS300", "上证", "深证", "中证",
+    "预盈预增", "预亏预减", "昨日", "机构重仓",
+];
+
+type HeadlineCache = Mutex<Option<(Instant, Arc<Vec<NewsItem>>)>>;
+type BoardQuoteCache = Mutex<Option<(Instant, Arc<HashMap<String, BoardQuote>>)>>;
+
+fn headline_cache() -> &'static HeadlineCache {
+    static CACHE: OnceLock<HeadlineCache> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(None))
+}
+
+/// 近期财联社快讯（带 10 分钟内存缓存），获取失败时返回空列表，只影响热度加权
+async fn recent_headlines() -> Arc<Vec<NewsItem>> {
+    if let Some((at, cached)) = headline_cache().lock().unwrap().as_ref() {
+        if at.elapsed() < HEADLINE_TTL {
+            return Arc::clone(cached);
+        }
+    }
+    match news_service::fetch_cls_telegraph(HEADLINE_COUNT).await {
+        Ok(items) => {
+            let items = Arc::new(items);
+            *headline_cache().lock().unwrap() = Some((Instant::now(), Arc::clone(&items)));
+            items
+        }
+        Err(e) => {
+            log::warn!("[theme_exposure] fetch headlines failed: {}", e);
+            Arc::new(vec![])
+        }
+    }
+}
+
+fn board_quote_cache() -> &'static BoardQuoteCache {
+    static CACHE: OnceLock<BoardQuoteCache> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(None))
+}
+
+/// 全部概念板块行情（带 1 分钟内存缓存），按板块代码索引
+async fn concept_board_quotes() -> Result<Arc<HashMap<String, BoardQuote>>> {
+    if let Some((at, cached)) = board_quote_cache().lock().unwrap().as_ref() {
+        if at.elapsed() < BOARD_QUOTE_TTL {
+            return Ok(Arc::clone(cached));
+        }
+    }
+    let boards = MarketScanner::new()?.fetch_boards("concept").await?;
+    let quotes = Arc::new(boards.into_iter().map(|b| (b.code.clone(), b)).collect::<HashMap<_, _>>());
+    *board_quote_cache().lock().unwrap() = Some((Instant::now(), Arc::clone(&quotes)));
+    Ok(quotes)
+}
+
+/// 个股所属概念板块行情与其中近期新纳入的板块代码：
+/// 成分股缓存已建立时由缓存 + 板块行情得出，否则逐只查询
+async fn stock_boards(code: &str) -> Result<(Vec<BoardQuote>, HashSet<String>)> {
+    if let Some(index) = board_members::cached() {
+        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+        let quotes = concept_board_quotes().await?;
+        let memberships = index.boards_of(code);
+        let boards = memberships.iter().filter_map(|m| quotes.get(&m.board_code).cloned()).collect();
+        let new_boards = memberships
+            .iter()
+            .filter(|m| board_members::is_recent_addition(m, &today))
+            .map(|m| m.board_code.clone())
+            .collect();
+        return Ok((boards, new_boards));
+    }
+    let boards = MarketScanner::new()?.fetch_stock_boards(code).await?;
+    Ok((boards, HashSet::new()))
+}
+
+/// 是否为题材板块：排除地域板块（"xx板块"）与指数、通道类板块
+pub(crate) fn is_theme(name: &str) -> bool {
+    !name.ends_with("板块") && !NON_THEME_KEYWORDS.iter().any(|k| name.contains(k))
+}
+
+/// 题材在快讯中的匹配关键词：去掉"概念"等后缀，过短的名称不匹配
+pub(crate) fn keyword(name: &str) -> Option<&str> {
+    let key = name.trim_end_matches("概念").trim_end_matches("板块");
+    (key.chars().count() >= 2).then_some(key)
+}
+
+/// 由所属板块与近期快讯计算题材暴露：
+/// 原始权重 = 1 + 0.5 × 提及次数（最多 6 次） + 今日涨幅 / 5（下跌不扣分） + 近期新纳入 0.5，归一化后按权重降序
+pub fn compute_exposure(boards: &[BoardQuote], headlines: &[NewsItem], new_boards: &HashSet<String>) -> Vec<ThemeWeight> {
+    let mut themes: Vec<ThemeWeight> = boards
+        .iter()
+        .filter(|b| is_theme(&b.name))
+        .map(|b| {
+            let matched: Vec<&NewsItem> = match keyword(&b.name) {
+                Some(key) => headlines
+                    .iter()
+                    .filter(|n| n.title.contains(key) || n.summary.contains(key))
+                    .collect(),
+                None => vec![],
+            };
+            let newly_added = new_boards.contains(&b.code);
+            ThemeWeight {
+                board_code: b.code.clone(),
+                name: b.name.clone(),
+                weight: 1.0
+                    + 0.5 * (matched.len() as u32).min(MAX_MENTIONS) as f64
+                    + b.change_pct.max(0.0) / 5.0
+                    + if newly_added { NEW_MEMBER_BOOST } else { 0.0 },
+                change_pct: b.change_pct,
+                mentions: matched.len() as u32,
+                headlines: matched.iter().take(MAX_HEADLINES).map(|n| n.title.clone()).collect(),
+                newly_added,
+            }
+        })
+        .collect();
+
+    let total: f64 = themes.iter().map(|t| t.weight).sum();
+    if total > 0.0 {
+        for t in &mut themes {
+            t.weight = (t.weight / total * 1000.0).round() / 1000.0;
+        }
+    }
+    themes.sort_by(|a, b| b.weight.total_cmp(&a.weight).then(b.change_pct.total_cmp(&a.change_pct)));
+    themes
+}
+
+/// 个股题材暴露向量
+pub async fn get_exposure(code: &str) -> Result<ThemeExposure> {
+    let (boards, new_boards) = stock_boards(code).await?;
+    let headlines = recent_headlines().await;
+    Ok(ThemeExposure {
+        code: code.to_string(),
+        themes: compute_exposure(&boards, &headlines, &new_boards),
+        updated_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+    })
+}
+
+/// 批量查询主要题材名称（每只最多 top_n 个），单只失败时跳过
+pub async fn top_themes(codes: &[String], top_n: usize) -> HashMap<String, Vec<String>> {
+    stream::iter(codes.iter().cloned())
+        .map(|code| async move {
+            match get_exposure(&code).await {
+                Ok(e) => Some((code, e.themes.into_iter().take(top_n).map(|t| t.name).collect())),
+                Err(e) => {
+                    log::warn!("[theme_exposure] get_exposure {} failed: {}", code, e);
+                    None
+                }
+            }
+        })
+        .buffer_unordered(FETCH_CONCURRENCY)
+        .filter_map(|r| async move { r })
+        .collect()
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::news::NewsCategory;
+
+    fn board(code: &str, name: &str, change_pct: f64) -> BoardQuote {
+        BoardQuote { code: code.to_string(), name: name.to_string(), change_pct, ..Default::default() }
+    }
+
+    fn news(title: &str) -> NewsItem {
+        NewsItem {
+            id: String::new(),
+            category: NewsCategory::ClsTelegraph,
+            title: title.to_string(),
+            summary: String::new(),
+            source: "财联社".to_string(),
+            publish_time: String::new(),
+            url: String::new(),
+            importance: 0,
+            related_stocks: vec![],
+        }
+    }
+
+    #[test]
+    fn test_compute_exposure() {
+        let boards = vec![
+            board("BK1", "白酒", 1.0),
+            board("BK2", "贵州板块", 5.0),
+            board("BK3", "融资融券", 0.5),
+            board("BK4", "消费电子概念", -2.0),
+        ];
+        let headlines = vec![news("白酒龙头提价"), news("消费电子新品发布"), news("白酒批价企稳")];
+        let themes = compute_exposure(&boards, &headlines, &HashSet::new());
+        assert_eq!(themes.len(), 2);
+        assert_eq!(themes[0].name, "白酒");
+        assert_eq!(themes[0].mentions, 2);
+        assert_eq!(themes[0].headlines, vec!["白酒龙头提价", "白酒批价企稳"]);
+        // 白酒 1 + 1.0 + 0.2 = 2.2，消费电子 1 + 0.5 = 1.5
+        assert!((themes[0].weight - 0.595).abs() < 1e-9);
+        assert!((themes.iter().map(|t| t.weight).sum::<f64>() - 1.0).abs() < 0.01);
+
+        // 近期新纳入消费电子：1.5 + 0.5 = 2.0
+        let new_boards = HashSet::from(["BK4".to_string()]);
+        let themes = compute_exposure(&boards, &headlines, &new_boards);
+        assert!(themes[1].newly_added);
+        assert!((themes[1].weight - 2.0 / 4.2).abs() < 1e-3);
+    }
+}
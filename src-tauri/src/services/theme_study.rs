@@ -0,0 +1,255 @@
+use anyhow::{anyhow, Result};
+use chrono::Local;
+use std::collections::HashMap;
+use tauri::Manager;
+
+use crate::AppState;
+use crate::db::database::Database;
+use crate::models::news::NewsItem;
+use crate::models::stock::{StockDailyHistory, ThemeEventStats, ThemeStudyResult};
+use crate::models::watchlist::KlineItem;
+use crate::services::board_members;
+use crate::services::job_scheduler::{JobSpec, JobTrigger};
+use crate::services::news_service;
+use crate::services::risk_metrics;
+use crate::services::theme_exposure;
+
+/// 远期收益的持有期（交易日）
+const HORIZONS: [usize; 3] = [1, 5, 10];
+/// 判断显著性所需的最少 5 日样本数
+const MIN_SAMPLES: usize = 30;
+/// 快讯归档保留天数
+const ARCHIVE_DAYS: i64 = 180;
+/// 每次归档拉取的快讯条数
+const ARCHIVE_COUNT: u32 = 100;
+const ARCHIVE_INTERVAL_SECS: u64 = 600;
+
+/// 按日期正序的收盘价序列
+pub(crate) struct CloseSeries {
+    pub dates: Vec<String>,
+    pub closes: Vec<f64>,
+}
+
+/// 单个事件样本在各持有期的收益与超额收益（%）
+#[derive(Default)]
+struct Sample {
+    returns: [Option<f64>; 3],
+    excess: [Option<f64>; 3],
+}
+
+impl CloseSeries {
+    pub fn from_history(rows: &[StockDailyHistory]) -> Self {
+        Self {
+            dates: rows.iter().map(|r| r.date.clone()).collect(),
+            closes: rows.iter().map(|r| r.close).collect(),
+        }
+    }
+
+    pub fn from_klines(klines: &[KlineItem]) -> Self {
+        Self {
+            dates: klines.iter().map(|k| k.date.clone()).collect(),
+            closes: klines.iter().map(|k| k.close).collect(),
+        }
+    }
+
+    /// 事件的建仓日下标：15:00 前发布按当日收盘价建仓，收盘后发布按下一交易日收盘价
+    pub fn entry_index(&self, publish_time: &str) -> Option<usize> {
+        let (date, time) = publish_time.split_once(' ').unwrap_or((publish_time, ""));
+        let idx = if time >= "15:00" {
+            self.dates.partition_point(|d| d.as_str() <= date)
+        } else {
+            self.dates.partition_point(|d| d.as_str() < date)
+        };
+        (idx < self.dates.len()).then_some(idx)
+    }
+
+    /// 自 entry 起持有 days 个交易日的收益（%）
+    pub fn forward_return(&self, entry: usize, days: usize) -> Option<f64> {
+        let start = self.closes[entry];
+        let end = *self.closes.get(entry + days)?;
+        (start > 0.0).then(|| (end / start - 1.0) * 100.0)
+    }
+}
+
+/// 计算单个事件样本；个股与基准建仓日不一致（停牌等）时不计超额收益
+fn sample(series: &CloseSeries, benchmark: &CloseSeries, publish_time: &str) -> Option<Sample> {
+    let entry = series.entry_index(publish_time)?;
+    let bench_entry = benchmark
+        .entry_index(publish_time)
+        .filter(|&i| benchmark.dates[i] == series.dates[entry]);
+    let mut sample = Sample::default();
+    for (i, &days) in HORIZONS.iter().enumerate() {
+        sample.returns[i] = series.forward_return(entry, days);
+        let bench = bench_entry.and_then(|b| benchmark.forward_return(b, days));
+        sample.excess[i] = sample.returns[i].zip(bench).map(|(r, b)| r - b);
+    }
+    Some(sample)
+}
+
+/// 每个题材被快讯提及的事件：同一自然日多条快讯只取最早一条的发布时间
+fn tag_events(news: &[NewsItem], themes: &[(String, String)]) -> HashMap<String, Vec<String>> {
+    themes
+        .iter()
+        .filter_map(|(board_code, key)| {
+            let mut events: Vec<String> = Vec::new();
+            for n in news.iter().filter(|n| n.title.contains(key.as_str()) || n.summary.contains(key.as_str())) {
+                let date = n.publish_time.get(..10).unwrap_or(&n.publish_time);
+                if !events.iter().any(|e| e.starts_with(date)) {
+                    events.push(n.publish_time.clone());
+                }
+            }
+            (!events.is_empty()).then(|| (board_code.clone(), events))
+        })
+        .collect()
+}
+
+pub(crate) fn round2(v: f64) -> f64 {
+    (v * 100.0).round() / 100.0
+}
+
+pub(crate) fn mean(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
+
+/// 均值的 t 统计量，样本不足或方差为 0 时为 None
+pub(crate) fn t_stat(values: &[f64]) -> Option<f64> {
+    let m = mean(values).filter(|_| values.len() >= 2)?;
+    let var = values.iter().map(|x| (x - m).powi(2)).sum::<f64>() / (values.len() - 1) as f64;
+    (var > 0.0).then(|| m / (var.sqrt() / (values.len() as f64).sqrt()))
+}
+
+/// 大于 0 的样本占比（%）
+pub(crate) fn win_rate(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().filter(|&&x| x > 0.0).count() as f64 / values.len() as f64 * 100.0)
+}
+
+/// 汇总题材的事件样本
+fn summarize(board_code: &str, theme: &str, events: usize, samples: &[Sample]) -> ThemeEventStats {
+    let collect = |f: &dyn Fn(&Sample) -> Option<f64>| samples.iter().filter_map(f).collect::<Vec<f64>>();
+    let excess_5d = collect(&|s| s.excess[1]);
+    let t_stat_5d = t_stat(&excess_5d);
+
+    ThemeEventStats {
+        board_code: board_code.to_string(),
+        theme: theme.to_string(),
+        events,
+        samples: samples.len(),
+        avg_return_1d: mean(&collect(&|s| s.returns[0])).map(round2),
+        avg_return_5d: mean(&collect(&|s| s.returns[1])).map(round2),
+        avg_return_10d: mean(&collect(&|s| s.returns[2])).map(round2),
+        avg_excess_5d: mean(&excess_5d).map(round2),
+        avg_excess_10d: mean(&collect(&|s| s.excess[2])).map(round2),
+        win_rate_5d: win_rate(&excess_5d).map(round2),
+        t_stat_5d: t_stat_5d.map(round2),
+        significant: excess_5d.len() >= MIN_SAMPLES && t_stat_5d.is_some_and(|t| t.abs() >= 2.0),
+    }
+}
+
+/// 题材事件研究：用近 days 天的归档快讯给概念板块打标，统计成分股此后 1/5/10 日的平均收益与相对沪深300的超额收益。
+/// 只统计本地已同步日线的成分股
+pub async fn run_study(db: &Database, days: i64) -> Result<ThemeStudyResult> {
+    let today = Local::now().date_naive();
+    let start = (today - chrono::Duration::days(days)).format("%Y-%m-%d").to_string();
+    let end = today.format("%Y-%m-%d").to_string();
+
+    let news = db.get_news_archive(&start)?;
+    if news.is_empty() {
+        return Err(anyhow!("暂无归档快讯，应用运行一段时间积累快讯后再研究"));
+    }
+    let index = board_members::cached().ok_or_else(|| anyhow!("概念板块成分股缓存尚未建立"))?;
+    let benchmark = CloseSeries::from_klines(&risk_metrics::benchmark_klines().await?);
+
+    let themes: Vec<(String, String)> = index
+        .boards()
+        .filter_map(|(code, members)| {
+            let name = &members.first()?.board_name;
+            let key = theme_exposure::keyword(name).filter(|_| theme_exposure::is_theme(name))?;
+            Some((code.clone(), key.to_string()))
+        })
+        .collect();
+    let events = tag_events(&news, &themes);
+
+    let mut histories: HashMap<String, Option<CloseSeries>> = HashMap::new();
+    let mut stats = Vec::new();
+    for (board_code, times) in &events {
+        let members = index.members_of(board_code);
+        let mut samples = Vec::new();
+        for m in members {
+            if !histories.contains_key(&m.code) {
+                let rows = db.get_daily_history_range(&m.code, &start, &end)?;
+                let series = (!rows.is_empty()).then(|| CloseSeries::from_history(&rows));
+                histories.insert(m.code.clone(), series);
+            }
+            if let Some(series) = &histories[&m.code] {
+                samples.extend(times.iter().filter_map(|t| sample(series, &benchmark, t)));
+            }
+        }
+        if !samples.is_empty() {
+            stats.push(summarize(board_code, &members[0].board_name, times.len(), &samples));
+        }
+    }
+    stats.sort_by(|a, b| {
+        b.avg_excess_5d
+            .unwrap_or(f64::MIN)
+            .total_cmp(&a.avg_excess_5d.unwrap_or(f64::MIN))
+    });
+    log::info!("[theme_study] {} news, {} themes with samples", news.len(), stats.len());
+    Ok(ThemeStudyResult { start, end, news_count: news.len(), themes: stats })
+}
+
+/// 后台任务：定时归档财联社快讯，供题材事件研究回看
+pub fn news_archive_job() -> JobSpec {
+    JobSpec {
+        id: "news_archive",
+        name: "快讯归档",
+        trigger: JobTrigger::Interval,
+        interval_secs: ARCHIVE_INTERVAL_SECS,
+        run: |app| Box::pin(async move {
+            let db = &app.state::<AppState>().db;
+            let items = news_service::fetch_cls_telegraph(ARCHIVE_COUNT).await?;
+            let inserted = db.save_news_archive(&items)?;
+            let before = (Local::now() - chrono::Duration::days(ARCHIVE_DAYS)).format("%Y-%m-%d").to_string();
+            db.prune_news_archive(&before)?;
+            Ok(Some(format!("归档 {} 条快讯", inserted)))
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series(closes: &[f64]) -> CloseSeries {
+        CloseSeries {
+            dates: (0..closes.len()).map(|i| format!("2024-06-{:02}", i + 1)).collect(),
+            closes: closes.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_sample() {
+        let stock = series(&[10.0, 11.0, 11.0, 11.0, 11.0, 11.0, 12.0, 12.0, 12.0, 12.0, 12.0]);
+        let bench = series(&[100.0; 11]);
+        // 盘中发布按当日收盘建仓
+        let s = sample(&stock, &bench, "2024-06-01 10:30:00").unwrap();
+        assert!((s.returns[0].unwrap() - 10.0).abs() < 1e-9);
+        assert!((s.excess[1].unwrap() - 10.0).abs() < 1e-9);
+        // 收盘后发布按次日收盘建仓
+        let s = sample(&stock, &bench, "2024-06-01 20:00:00").unwrap();
+        assert_eq!(s.returns[0], Some(0.0));
+        assert!((s.returns[1].unwrap() - 100.0 / 11.0).abs() < 1e-9);
+        assert_eq!(s.returns[2], None);
+    }
+
+    #[test]
+    fn test_summarize() {
+        let samples: Vec<Sample> = (0..40)
+            .map(|i| Sample { excess: [None, Some(1.0 + (i % 3) as f64), None], ..Default::default() })
+            .collect();
+        let stats = summarize("BK1", "白酒", 5, &samples);
+        assert_eq!(stats.samples, 40);
+        assert_eq!(stats.win_rate_5d, Some(100.0));
+        assert!(stats.significant);
+        assert_eq!(stats.avg_return_5d, None);
+    }
+}
@@ -0,0 +1,162 @@
+use anyhow::{anyhow, Result};
+use tauri::menu::{IsMenuItem, Menu, MenuItem, PredefinedMenuItem};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Emitter, Manager, Wry};
+
+use crate::AppState;
+use crate::models::stock::MarketStockSnapshot;
+use crate::services::job_scheduler::{JobSpec, JobTrigger};
+use crate::services::market_scanner::MarketScanner;
+use crate::services::window_manager;
+use crate::models::window::{WINDOW_KIND_KLINE, WINDOW_KIND_MINI_QUOTE};
+
+/// 托盘快捷操作事件，载荷为操作名（watchlist / ai_pick），由主窗口前端处理
+pub const TRAY_ACTION_EVENT: &str = "tray-action";
+pub const ACTION_WATCHLIST: &str = "watchlist";
+pub const ACTION_AI_PICK: &str = "ai_pick";
+
+pub const TRAY_ID: &str = "main";
+const MENU_SHOW: &str = "show";
+const MENU_MINI_QUOTE: &str = "mini_quote";
+const MENU_QUIT: &str = "quit";
+const MOVER_ID_PREFIX: &str = "mover:";
+/// 托盘菜单与提示中展示的自选股异动数
+const TOP_MOVERS: usize = 5;
+const TICKER_INTERVAL_SECS: u64 = 60;
+const TOOLTIP_TITLE: &str = "Stock Helper";
+
+/// 按涨跌幅绝对值取前 N 只（剔除停牌、无报价的股票）
+pub fn top_movers(quotes: &[MarketStockSnapshot], n: usize) -> Vec<&MarketStockSnapshot> {
+    let mut movers: Vec<&MarketStockSnapshot> = quotes.iter().filter(|q| q.price > 0.0).collect();
+    movers.sort_by(|a, b| b.change_pct.abs().total_cmp(&a.change_pct.abs()));
+    movers.truncate(n);
+    movers
+}
+
+pub fn mover_line(quote: &MarketStockSnapshot) -> String {
+    format!("{} {:.2} {:+.2}%", quote.name, quote.price, quote.change_pct)
+}
+
+fn build_menu(app: &AppHandle, movers: &[&MarketStockSnapshot]) -> tauri::Result<Menu<Wry>> {
+    let mut items: Vec<Box<dyn IsMenuItem<Wry>>> = Vec::new();
+    for quote in movers {
+        let id = format!("{}{}", MOVER_ID_PREFIX, quote.code);
+        items.push(Box::new(MenuItem::with_id(app, id, mover_line(quote), true, None::<&str>)?));
+    }
+    if !movers.is_empty() {
+        items.push(Box::new(PredefinedMenuItem::separator(app)?));
+    }
+    items.push(Box::new(MenuItem::with_id(app, MENU_SHOW, "显示主窗口", true, None::<&str>)?));
+    items.push(Box::new(MenuItem::with_id(app, ACTION_WATCHLIST, "打开自选盯盘", true, None::<&str>)?));
+    items.push(Box::new(MenuItem::with_id(app, ACTION_AI_PICK, "运行 AI 选股", true, None::<&str>)?));
+    items.push(Box::new(MenuItem::with_id(app, MENU_MINI_QUOTE, "迷你行情条", true, None::<&str>)?));
+    items.push(Box::new(PredefinedMenuItem::separator(app)?));
+    items.push(Box::new(MenuItem::with_id(app, MENU_QUIT, "退出", true, None::<&str>)?));
+    let refs: Vec<&dyn IsMenuItem<Wry>> = items.iter().map(|i| i.as_ref()).collect();
+    Menu::with_items(app, &refs)
+}
+
+/// 显示并聚焦主窗口
+pub fn show_main(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+fn on_menu(app: &AppHandle, id: &str) {
+    log::info!("[tray] menu id={}", id);
+    match id {
+        MENU_SHOW => show_main(app),
+        MENU_MINI_QUOTE => {
+            if let Err(e) = window_manager::open(app, WINDOW_KIND_MINI_QUOTE, None, None) {
+                log::warn!("[tray] open mini quote failed: {}", e);
+            }
+        }
+        MENU_QUIT => app.exit(0),
+        ACTION_WATCHLIST | ACTION_AI_PICK => {
+            show_main(app);
+            let _ = app.emit(TRAY_ACTION_EVENT, id);
+        }
+        other => {
+            // 点击异动股：打开对应的独立 K 线窗口
+            if let Some(code) = other.strip_prefix(MOVER_ID_PREFIX) {
+                if let Err(e) = window_manager::open(app, WINDOW_KIND_KLINE, Some(code.to_string()), None) {
+                    log::warn!("[tray] open kline {} failed: {}", code, e);
+                }
+            }
+        }
+    }
+}
+
+/// 创建托盘图标：左键单击显示主窗口，右键菜单提供快捷操作
+pub fn setup(app: &AppHandle) -> Result<()> {
+    let icon = app.default_window_icon().cloned().ok_or_else(|| anyhow!("缺少应用图标"))?;
+    TrayIconBuilder::with_id(TRAY_ID)
+        .icon(icon)
+        .tooltip(TOOLTIP_TITLE)
+        .menu(&build_menu(app, &[])?)
+        .show_menu_on_left_click(false)
+        .on_menu_event(|app, event| on_menu(app, event.id().as_ref()))
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click { button: MouseButton::Left, button_state: MouseButtonState::Up, .. } = event {
+                show_main(tray.app_handle());
+            }
+        })
+        .build(app)?;
+    Ok(())
+}
+
+/// 拉取自选股行情，更新托盘提示与菜单中的异动股
+pub async fn refresh(app: &AppHandle) -> Result<usize> {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return Ok(0);
+    };
+    let codes: Vec<String> = app.state::<AppState>().db.get_watchlist_stocks()?.into_iter().map(|s| s.code).collect();
+    let quotes = if codes.is_empty() {
+        vec![]
+    } else {
+        MarketScanner::new()?.fetch_stocks_by_codes(&codes).await?
+    };
+    let movers = top_movers(&quotes, TOP_MOVERS);
+    let tooltip = std::iter::once(TOOLTIP_TITLE.to_string())
+        .chain(movers.iter().map(|q| mover_line(q)))
+        .collect::<Vec<_>>()
+        .join("\n");
+    tray.set_tooltip(Some(tooltip))?;
+    tray.set_menu(Some(build_menu(app, &movers)?))?;
+    Ok(movers.len())
+}
+
+/// 盘中定时刷新托盘行情，主窗口关闭到托盘后仍可查看自选股异动
+pub fn tray_ticker_job() -> JobSpec {
+    JobSpec {
+        id: "tray_ticker",
+        name: "托盘行情",
+        trigger: JobTrigger::TradingHours,
+        interval_secs: TICKER_INTERVAL_SECS,
+        run: |app| Box::pin(async move {
+            let count = refresh(&app).await?;
+            Ok(Some(format!("异动股 {} 只", count)))
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quote(code: &str, price: f64, change_pct: f64) -> MarketStockSnapshot {
+        MarketStockSnapshot { code: code.to_string(), name: code.to_string(), price, change_pct, ..Default::default() }
+    }
+
+    #[test]
+    fn test_top_movers() {
+        let quotes = vec![quote("a", 10.0, 1.0), quote("b", 10.0, -5.5), quote("c", 0.0, 9.0), quote("d", 8.0, 3.2)];
+        let codes: Vec<&str> = top_movers(&quotes, 2).iter().map(|q| q.code.as_str()).collect();
+        assert_eq!(codes, vec!["b", "d"]);
+        assert_eq!(mover_line(&quotes[1]), "b 10.00 -5.50%");
+        assert!(top_movers(&[], 5).is_empty());
+    }
+}
@@ -0,0 +1,201 @@
+use anyhow::{anyhow, Result};
+use std::path::Path;
+
+use crate::db::database::Database;
+use crate::models::audio::AudioExport;
+use crate::models::settings::TtsConfig;
+use crate::services::briefing;
+use crate::utils::http::{build_ai_client, SendLogged};
+
+/// audio_exports.source_kind 取值
+pub const SOURCE_BRIEFING: &str = "briefing";
+pub const SOURCE_ANALYSIS: &str = "analysis";
+/// 单次请求的最大字符数，OpenAI 接口上限 4096，留出余量兼容其他服务商
+const MAX_CHUNK_CHARS: usize = 1500;
+/// 合成请求超时（秒）
+const TTS_TIMEOUT_SECS: u64 = 120;
+
+/// 把 Markdown 正文转为适合朗读的纯文本：去掉标题符号、强调、代码块、链接地址与表格分隔线
+pub fn speech_text(markdown: &str) -> String {
+    let mut lines = Vec::new();
+    let mut in_code = false;
+    for line in markdown.lines() {
+        let line = line.trim();
+        if line.starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code || line.is_empty() {
+            continue;
+        }
+        // 表格分隔行 |---|---|
+        if line.chars().all(|c| matches!(c, '|' | '-' | ':' | ' ')) {
+            continue;
+        }
+        let line = line.trim_start_matches(['#', '>', ' ']);
+        let line = line
+            .strip_prefix("- ")
+            .or_else(|| line.strip_prefix("* "))
+            .or_else(|| line.strip_prefix("+ "))
+            .unwrap_or(line);
+        let text = strip_links(line)
+            .replace(['*', '`', '~'], "")
+            .split('|')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join("，");
+        if !text.is_empty() {
+            lines.push(text);
+        }
+    }
+    lines.join("\n")
+}
+
+/// [文字](链接) 只保留文字
+fn strip_links(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(start) = rest.find('[') {
+        let Some(mid) = rest[start..].find("](").map(|i| start + i) else { break };
+        let Some(end) = rest[mid..].find(')').map(|i| mid + i) else { break };
+        out.push_str(&rest[..start]);
+        out.push_str(&rest[start + 1..mid]);
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// 按句切分为不超过 max_chars 字符的片段，单句超长时硬切
+pub fn split_chunks(text: &str, max_chars: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    let mut current_len = 0;
+    let mut sentence = String::new();
+    let mut sentence_len = 0;
+
+    let mut flush_sentence = |sentence: &mut String, sentence_len: &mut usize, current: &mut String, current_len: &mut usize| {
+        if *current_len + *sentence_len > max_chars && !current.is_empty() {
+            chunks.push(std::mem::take(current));
+            *current_len = 0;
+        }
+        current.push_str(sentence);
+        *current_len += *sentence_len;
+        sentence.clear();
+        *sentence_len = 0;
+    };
+
+    for c in text.chars() {
+        sentence.push(c);
+        sentence_len += 1;
+        if matches!(c, '。' | '！' | '？' | '；' | '\n' | '!' | '?' | ';') || sentence_len >= max_chars {
+            flush_sentence(&mut sentence, &mut sentence_len, &mut current, &mut current_len);
+        }
+    }
+    if !sentence.is_empty() {
+        flush_sentence(&mut sentence, &mut sentence_len, &mut current, &mut current_len);
+    }
+    if !current.trim().is_empty() {
+        chunks.push(current);
+    }
+    chunks.retain(|c| !c.trim().is_empty());
+    chunks
+}
+
+/// 调用 OpenAI 兼容的 /audio/speech 接口合成 mp3，长文本分段合成后顺序拼接
+pub async fn synthesize(config: &TtsConfig, text: &str) -> Result<Vec<u8>> {
+    if config.base_url.trim().is_empty() {
+        return Err(anyhow!("未配置语音播报接口，请在设置中填写 TTS 地址"));
+    }
+    let chunks = split_chunks(text, MAX_CHUNK_CHARS);
+    if chunks.is_empty() {
+        return Err(anyhow!("没有可朗读的内容"));
+    }
+    let url = format!("{}/audio/speech", config.base_url.trim().trim_end_matches('/'));
+    let client = build_ai_client(TTS_TIMEOUT_SECS)?;
+    let mut audio = Vec::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let body = serde_json::json!({
+            "model": config.model,
+            "input": chunk,
+            "voice": config.voice,
+            "speed": config.speed.clamp(0.25, 4.0),
+            "response_format": "mp3",
+        });
+        let mut request = client.post(&url).json(&body);
+        if !config.api_key.is_empty() {
+            request = request.bearer_auth(&config.api_key);
+        }
+        let response = request.send_logged().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            return Err(anyhow!("语音合成失败 ({}): {}", status.as_u16(), text.chars().take(200).collect::<String>()));
+        }
+        audio.extend_from_slice(&response.bytes().await?);
+        log::info!("[tts] synthesize chunk {}/{} chars={}", i + 1, chunks.len(), chunk.chars().count());
+    }
+    Ok(audio)
+}
+
+/// 合成并写入 {dir}/audio/{file_stem}.mp3，记录到 audio_exports
+async fn export(db: &Database, dir: &Path, config: &TtsConfig, source_kind: &str, source_id: &str, file_stem: &str, text: &str) -> Result<AudioExport> {
+    let audio = synthesize(config, text).await?;
+    let audio_dir = dir.join("audio");
+    std::fs::create_dir_all(&audio_dir)?;
+    let path = audio_dir.join(format!("{}.mp3", file_stem));
+    std::fs::write(&path, &audio)?;
+
+    let export = AudioExport {
+        source_kind: source_kind.to_string(),
+        source_id: source_id.to_string(),
+        path: path.to_string_lossy().to_string(),
+        created_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+    };
+    db.save_audio_export(&export)?;
+    log::info!("[tts] export {}:{} bytes={} path={}", source_kind, source_id, audio.len(), export.path);
+    Ok(export)
+}
+
+/// 市场日志（早盘备忘 / 收盘复盘）转语音
+pub async fn export_briefing(db: &Database, dir: &Path, date: &str, kind: &str) -> Result<AudioExport> {
+    let config = db.load_settings()?.tts;
+    let briefing = db
+        .get_market_briefing(date, kind)?
+        .ok_or_else(|| anyhow!("{} 尚未生成{}", date, briefing_title(kind)))?;
+    let text = format!("{}{}。\n{}", date, briefing_title(kind), speech_text(&briefing.content));
+    export(db, dir, &config, SOURCE_BRIEFING, &format!("{}/{}", date, kind), &format!("{}-{}", kind, date), &text).await
+}
+
+/// AI 分析记录（如个股诊断）转语音
+pub async fn export_analysis(db: &Database, dir: &Path, id: &str) -> Result<AudioExport> {
+    let config = db.load_settings()?.tts;
+    let analysis = db.get_ai_analysis(id)?.ok_or_else(|| anyhow!("分析记录不存在"))?;
+    let text = format!("{}，{}。\n{}", analysis.name, analysis.question, speech_text(&analysis.content));
+    export(db, dir, &config, SOURCE_ANALYSIS, id, &format!("analysis-{}", id), &text).await
+}
+
+fn briefing_title(kind: &str) -> &'static str {
+    if kind == briefing::REVIEW_KIND { "收盘复盘" } else { "早盘备忘" }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_speech_text() {
+        let md = "## 今日要点\n\n- **大盘**：缩量震荡\n> 注意[公告](https://x.com)风险\n\n| 代码 | 名称 |\n|---|---|\n| 600519 | 贵州茅台 |\n```\ncode\n```";
+        assert_eq!(speech_text(md), "今日要点\n大盘：缩量震荡\n注意公告风险\n代码，名称\n600519，贵州茅台");
+    }
+
+    #[test]
+    fn test_split_chunks() {
+        let chunks = split_chunks("第一句。第二句！第三句", 8);
+        assert_eq!(chunks, vec!["第一句。第二句！", "第三句"]);
+        // 单句超长时硬切
+        assert_eq!(split_chunks("一二三四五", 2), vec!["一二", "三四", "五"]);
+        assert!(split_chunks("  \n", 10).is_empty());
+    }
+}
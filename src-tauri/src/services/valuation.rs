@@ -0,0 +1,163 @@
+use anyhow::{anyhow, Result};
+use chrono::Datelike;
+use serde_json::Value;
+
+use crate::db::database::Database;
+use crate::models::f10::{ValuationBand, ValuationPoint};
+use crate::services::stock_data::{code_to_pure, format_stock_code};
+use crate::utils::http::{build_datacenter_client, SendLogged};
+
+/// 估值分位统计的历史年数
+pub const VALUATION_YEARS: i32 = 5;
+/// 样本少于该交易日数时不给出分位（次新股等）
+const MIN_SAMPLE_DAYS: usize = 120;
+const PAGE_SIZE: usize = 500;
+
+/// 历史估值统计起始日期（今天往前 VALUATION_YEARS 年）
+fn history_start() -> String {
+    let today = chrono::Local::now().date_naive();
+    today
+        .with_year(today.year() - VALUATION_YEARS)
+        .unwrap_or(today)
+        .format("%Y-%m-%d")
+        .to_string()
+}
+
+/// 从东财数据中心拉取 since 之后（不含）的每日 PE/PB，按日期升序返回
+pub async fn fetch_valuation_history(code: &str, since: &str) -> Result<Vec<ValuationPoint>> {
+    let client = build_datacenter_client()?;
+    let pure = code_to_pure(code);
+    let mut points = Vec::new();
+    let mut page = 1;
+    loop {
+        let url = format!(
+            "https://datacenter-web.eastmoney.com/api/data/v1/get?reportName=RPT_VALUEANALYSIS_DET&columns=TRADE_DATE,CLOSE_PRICE,PE_TTM,PB_MRQ&filter=(SECURITY_CODE=\"{}\")(TRADE_DATE>'{}')&pageNumber={}&pageSize={}&sortColumns=TRADE_DATE&sortTypes=-1&source=WEB&client=WEB",
+            pure, since, page, PAGE_SIZE
+        );
+        let json: Value = client.get(&url).send_logged().await?.json().await?;
+        let data = match json["result"]["data"].as_array() {
+            Some(d) => d,
+            // 无新数据时 result 为 null
+            None => break,
+        };
+        points.extend(data.iter().filter_map(|item| {
+            Some(ValuationPoint {
+                date: item["TRADE_DATE"].as_str()?.chars().take(10).collect(),
+                close: item["CLOSE_PRICE"].as_f64().unwrap_or(0.0),
+                pe_ttm: item["PE_TTM"].as_f64().unwrap_or(0.0),
+                pb: item["PB_MRQ"].as_f64().unwrap_or(0.0),
+            })
+        }));
+        let pages = json["result"]["pages"].as_u64().unwrap_or(1) as usize;
+        if page >= pages || data.len() < PAGE_SIZE {
+            break;
+        }
+        page += 1;
+    }
+    points.reverse();
+    Ok(points)
+}
+
+/// 增量同步本地估值历史（首次拉取近 VALUATION_YEARS 年），返回新增条数
+pub async fn sync_valuation_history(db: &Database, code: &str) -> Result<usize> {
+    let code = format_stock_code(code);
+    let since = db.get_latest_valuation_date(&code)?.unwrap_or_else(history_start);
+    let points = fetch_valuation_history(&code, &since).await?;
+    if !points.is_empty() {
+        db.save_valuation_history(&code, &points)?;
+    }
+    Ok(points.len())
+}
+
+/// 基于本地估值缓存计算估值分位（先增量同步，同步失败时使用已有缓存）
+pub async fn get_valuation_band(db: &Database, code: &str) -> Result<ValuationBand> {
+    let code = format_stock_code(code);
+    if let Err(e) = sync_valuation_history(db, &code).await {
+        log::warn!("[valuation] sync valuation history failed for {}: {}", code, e);
+    }
+    let history = db.get_valuation_history(&code, &history_start())?;
+    compute_valuation_band(&code, &history).ok_or_else(|| anyhow!("{} 暂无估值历史数据", code))
+}
+
+/// 不落库、直接拉取近 VALUATION_YEARS 年数据计算估值分位（供无数据库上下文的 AI 工具使用）
+pub async fn fetch_valuation_band(code: &str) -> Result<ValuationBand> {
+    let code = format_stock_code(code);
+    let history = fetch_valuation_history(&code, &history_start()).await?;
+    compute_valuation_band(&code, &history).ok_or_else(|| anyhow!("{} 暂无估值历史数据", code))
+}
+
+/// 计算最新一日 PE/PB 在历史中的分位；PE 只统计正值（亏损期的 PE 没有可比性）
+pub fn compute_valuation_band(code: &str, history: &[ValuationPoint]) -> Option<ValuationBand> {
+    let latest = history.last()?;
+    let mut pes: Vec<f64> = history.iter().map(|p| p.pe_ttm).filter(|v| *v > 0.0).collect();
+    let mut pbs: Vec<f64> = history.iter().map(|p| p.pb).filter(|v| *v > 0.0).collect();
+    pes.sort_by(|a, b| a.total_cmp(b));
+    pbs.sort_by(|a, b| a.total_cmp(b));
+
+    let enough = history.len() >= MIN_SAMPLE_DAYS;
+    let (pe_min, pe_median, pe_max) = min_median_max(&pes);
+    let (pb_min, pb_median, pb_max) = min_median_max(&pbs);
+    Some(ValuationBand {
+        code: code.to_string(),
+        date: latest.date.clone(),
+        pe_ttm: latest.pe_ttm,
+        pb: latest.pb,
+        pe_percentile: if enough && latest.pe_ttm > 0.0 { percentile_of(&pes, latest.pe_ttm) } else { None },
+        pb_percentile: if enough && latest.pb > 0.0 { percentile_of(&pbs, latest.pb) } else { None },
+        pe_min,
+        pe_median,
+        pe_max,
+        pb_min,
+        pb_median,
+        pb_max,
+        sample_days: history.len(),
+        start_date: history[0].date.clone(),
+    })
+}
+
+/// 有序样本中不大于 value 的占比（0-100）
+fn percentile_of(sorted: &[f64], value: f64) -> Option<f64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let below = sorted.partition_point(|v| *v <= value);
+    Some(below as f64 / sorted.len() as f64 * 100.0)
+}
+
+fn min_median_max(sorted: &[f64]) -> (f64, f64, f64) {
+    match (sorted.first(), sorted.last()) {
+        (Some(min), Some(max)) => (*min, sorted[sorted.len() / 2], *max),
+        _ => (0.0, 0.0, 0.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(date: &str, pe_ttm: f64, pb: f64) -> ValuationPoint {
+        ValuationPoint { date: date.to_string(), close: 10.0, pe_ttm, pb }
+    }
+
+    #[test]
+    fn test_percentile_excludes_negative_pe() {
+        let mut history: Vec<ValuationPoint> = (0..200)
+            .map(|i| point(&format!("d{:03}", i), if i < 50 { -5.0 } else { i as f64 }, i as f64 / 10.0 + 0.1))
+            .collect();
+        history.push(point("d200", 60.0, 20.1));
+        let band = compute_valuation_band("sh600000", &history).unwrap();
+        // 正值 PE 样本为 50..=199 与 60，<= 60 的共 12 个
+        assert_eq!(band.pe_percentile.map(|p| (p * 151.0 / 100.0).round()), Some(12.0));
+        assert_eq!(band.pb_percentile, Some(100.0));
+        assert_eq!(band.pe_min, 50.0);
+    }
+
+    #[test]
+    fn test_no_percentile_when_loss_making_or_short_history() {
+        let history: Vec<ValuationPoint> = (0..200).map(|i| point(&format!("d{:03}", i), -1.0, 1.0)).collect();
+        assert!(compute_valuation_band("sz000001", &history).unwrap().pe_percentile.is_none());
+        let short: Vec<ValuationPoint> = (0..10).map(|i| point(&format!("d{:03}", i), 10.0, 1.0)).collect();
+        assert!(compute_valuation_band("sz000001", &short).unwrap().pe_percentile.is_none());
+        assert!(compute_valuation_band("sz000001", &[]).is_none());
+    }
+}
@@ -0,0 +1,201 @@
+use anyhow::Result;
+use futures::stream::{self, StreamExt};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+use crate::db::database::Database;
+use crate::models::agent_session::AgentSession;
+use crate::models::ai::{AIAnalysisResult, AIConfig, AIStreamEvent};
+use crate::models::watchlist::{WatchlistDiagnoseDigest, WatchlistDiagnoseItem, WatchlistDiagnoseProgress};
+use crate::services::ai_service::AIService;
+use crate::services::stock_tools::ToolContext;
+use crate::services::stream_forwarder;
+use crate::services::symbol_table;
+
+/// ai_analysis 表中 Agent 诊断记录的 question 标识
+pub const DIAGNOSE_QUESTION: &str = "AI技术诊断(Agent)";
+pub const DEFAULT_CONCURRENCY: usize = 2;
+pub const MAX_CONCURRENCY: usize = 4;
+/// 相邻两只股票开始诊断的最小间隔，避免触发模型接口限流
+const START_INTERVAL_MS: u64 = 2000;
+/// 前端监听的批量诊断进度事件名
+pub const DIAGNOSE_PROGRESS_EVENT: &str = "watchlist-diagnose-progress";
+
+/// 操作建议关键词及排序分值（同一位置优先匹配靠前的词）
+const ACTIONS: [(&str, i32); 6] = [
+    ("买入", 4),
+    ("增持", 4),
+    ("持有", 3),
+    ("观望", 2),
+    ("减仓", 1),
+    ("清仓", 0),
+];
+
+/// 诊断单只股票：流式事件推送到 ai-diagnose-{code}，并保存会话审计、诊断结果与 token 用量
+pub async fn diagnose_stock(
+    db: &Database,
+    app: &AppHandle,
+    config: &AIConfig,
+    tool_ctx: &ToolContext,
+    code: &str,
+    name: &str,
+) -> Result<AIAnalysisResult> {
+    let (tx, rx) = tokio::sync::mpsc::channel::<AIStreamEvent>(100);
+    stream_forwarder::spawn(app, format!("ai-diagnose-{}", code), rx);
+
+    // 名称与代码不一致时模型容易分析错股票，先按本地代码表校验
+    let (code, name) = symbol_table::resolve_input(db, code, name);
+    let (code, name) = (code.as_str(), name.as_str());
+
+    let mut session = AgentSession::new("diagnose", code, &config.model_name);
+    let result = AIService::diagnose_stock_with_tools(config, code, name, tool_ctx, tx, &mut session).await;
+    session.finish(&result);
+    let _ = db.save_agent_session(&session);
+    let (content, usage) = result?;
+
+    let analysis = AIAnalysisResult {
+        id: uuid::Uuid::new_v4().to_string(),
+        code: code.to_string(),
+        name: name.to_string(),
+        model_name: config.model_name.clone(),
+        question: DIAGNOSE_QUESTION.to_string(),
+        content,
+        created_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+    };
+    let _ = db.save_ai_analysis(&analysis);
+
+    if let Some(usage) = usage {
+        let _ = db.record_token_usage(&config.model_name, usage.prompt_tokens, usage.completion_tokens);
+    }
+    Ok(analysis)
+}
+
+/// 批量诊断全部自选股：限制并发并控制启动间隔，每完成一只推送进度事件，
+/// 当日已诊断过的股票直接复用（force 时重新诊断），最后按操作建议排序汇总
+pub async fn diagnose_watchlist(
+    db: &Database,
+    app: &AppHandle,
+    config: &AIConfig,
+    tool_ctx: &ToolContext,
+    concurrency: usize,
+    force: bool,
+) -> Result<WatchlistDiagnoseDigest> {
+    let stocks = db.get_watchlist_stocks()?;
+    let total = stocks.len();
+    let concurrency = concurrency.clamp(1, MAX_CONCURRENCY);
+    log::info!("[watchlist_diagnose] diagnose_watchlist total={} concurrency={} force={}", total, concurrency, force);
+
+    let next_start = Mutex::new(Instant::now());
+    let mut results = stream::iter(stocks)
+        .map(|stock| {
+            let next_start = &next_start;
+            async move {
+                if !force {
+                    if let Ok(Some(cached)) = db.get_today_ai_analysis_by_question(&stock.code, DIAGNOSE_QUESTION) {
+                        return to_item(&stock.code, &stock.name, Ok(&cached.content), true);
+                    }
+                }
+                wait_turn(next_start).await;
+                let result = diagnose_stock(db, app, config, tool_ctx, &stock.code, &stock.name).await;
+                if let Err(e) = &result {
+                    log::warn!("[watchlist_diagnose] diagnose {} failed: {}", stock.code, e);
+                }
+                to_item(&stock.code, &stock.name, result.as_ref().map(|a| a.content.as_str()), false)
+            }
+        })
+        .buffer_unordered(concurrency);
+
+    let mut items = Vec::with_capacity(total);
+    while let Some(item) = results.next().await {
+        let progress = WatchlistDiagnoseProgress {
+            completed: items.len() + 1,
+            total,
+            item: item.clone(),
+        };
+        let _ = app.emit(DIAGNOSE_PROGRESS_EVENT, &progress);
+        items.push(item);
+    }
+
+    items.sort_by_key(|i| std::cmp::Reverse(i.score));
+    let failed = items.iter().filter(|i| i.error.is_some()).count();
+    log::info!("[watchlist_diagnose] diagnose_watchlist done total={} failed={}", total, failed);
+    Ok(WatchlistDiagnoseDigest {
+        date: chrono::Local::now().format("%Y-%m-%d").to_string(),
+        total,
+        succeeded: total - failed,
+        failed,
+        items,
+    })
+}
+
+/// 按固定间隔依次放行，持锁等待以保证启动顺序
+async fn wait_turn(next_start: &Mutex<Instant>) {
+    let mut next = next_start.lock().await;
+    tokio::time::sleep_until(*next).await;
+    *next = Instant::now() + Duration::from_millis(START_INTERVAL_MS);
+}
+
+fn to_item(code: &str, name: &str, content: Result<&str, &anyhow::Error>, cached: bool) -> WatchlistDiagnoseItem {
+    let (action, score, summary, error) = match content {
+        Ok(content) => {
+            let (action, score, summary) = extract_action(content);
+            (action, score, summary, None)
+        }
+        Err(e) => (String::new(), -1, String::new(), Some(e.to_string())),
+    };
+    WatchlistDiagnoseItem {
+        code: code.to_string(),
+        name: name.to_string(),
+        action,
+        score,
+        summary,
+        cached,
+        error,
+    }
+}
+
+/// 从诊断正文的「操作建议」段落提取建议动作、分值与首句摘要；未识别时分值取中性的观望
+fn extract_action(content: &str) -> (String, i32, String) {
+    let section = match content.find("操作建议") {
+        Some(pos) => &content[pos + "操作建议".len()..],
+        None => content,
+    };
+    let window: String = section.chars().take(300).collect();
+
+    let action = ACTIONS
+        .iter()
+        .filter_map(|(word, score)| window.find(word).map(|pos| (pos, *word, *score)))
+        .min_by_key(|(pos, _, _)| *pos);
+
+    let summary = window
+        .split(['\n', '。'])
+        .map(|s| s.trim_matches(|c: char| c.is_whitespace() || matches!(c, '*' | '#' | ':' | '：' | '-')))
+        .find(|s| !s.is_empty())
+        .map(|s| s.chars().take(80).collect())
+        .unwrap_or_default();
+
+    match action {
+        Some((_, word, score)) => (word.to_string(), score, summary),
+        None => (String::new(), 2, summary),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_action_from_section() {
+        let content = "1. **行情概览**：可买入区间不明确\n4. **操作建议**：\n- 建议减仓，反弹至 12.5 附近止盈。\n5. **风险提示**";
+        let (action, score, summary) = extract_action(content);
+        assert_eq!(action, "减仓");
+        assert_eq!(score, 1);
+        assert_eq!(summary, "建议减仓，反弹至 12.5 附近止盈");
+
+        let (action, score, _) = extract_action("没有明确结论");
+        assert_eq!(action, "");
+        assert_eq!(score, 2);
+    }
+}
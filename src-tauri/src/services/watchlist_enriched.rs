@@ -0,0 +1,156 @@
+use anyhow::{anyhow, Result};
+use chrono::Local;
+use futures::stream::{self, StreamExt};
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use tokio::time::Instant;
+
+use crate::db::database::Database;
+use crate::models::stock::{MarketStockSnapshot, StockDailyHistory};
+use crate::services::closing_auction;
+use crate::services::fund_flow;
+use crate::services::market_scanner::MarketScanner;
+
+/// 每批 ulist 请求的股票数
+const QUOTE_CHUNK: usize = 80;
+/// 同时进行的行情批次数
+const QUOTE_CONCURRENCY: usize = 3;
+/// 整体耗时上限，超时返回已到达的部分结果
+const LATENCY_BUDGET: Duration = Duration::from_secs(6);
+/// 本地日线回看的交易日数（覆盖 60 日涨幅）
+const HISTORY_DAYS: usize = 61;
+
+/// 以本地日线计算 n 个交易日涨幅 %。history 按日期倒序；最新一条为今天时以再往前 n 条的收盘价为基准
+pub fn period_pct(history: &[StockDailyHistory], today: &str, price: f64, n: usize) -> Option<f64> {
+    let offset = if history.first().is_some_and(|h| h.date == today) { n } else { n - 1 };
+    let base = history.get(offset)?.close;
+    (base > 0.0 && price > 0.0).then(|| ((price / base - 1.0) * 10000.0).round() / 100.0)
+}
+
+/// 用本地日线补齐行情中缺失的 5/20/60 日涨幅（腾讯行情无该字段，东财 ulist 无 60 日涨幅）
+fn fill_period_pct(db: &Database, stocks: &mut [MarketStockSnapshot]) {
+    let today = Local::now().format("%Y-%m-%d").to_string();
+    for stock in stocks.iter_mut().filter(|s| s.pct_5d == 0.0 || s.pct_20d == 0.0 || s.pct_60d == 0.0) {
+        let history = match db.get_daily_history(&stock.code, HISTORY_DAYS) {
+            Ok(h) if !h.is_empty() => h,
+            Ok(_) => continue,
+            Err(e) => {
+                log::warn!("[watchlist_enriched] load daily history failed for {}: {}", stock.code, e);
+                continue;
+            }
+        };
+        for (field, n) in [(&mut stock.pct_5d, 5), (&mut stock.pct_20d, 20), (&mut stock.pct_60d, 60)] {
+            if *field == 0.0 {
+                *field = period_pct(&history, &today, stock.price, n).unwrap_or(0.0);
+            }
+        }
+    }
+}
+
+/// 自选股增强行情：ulist 分批并发拉取快照、一次批量资金流向补主力净额与净占比、本地日线补区间涨幅。
+/// 总耗时受 LATENCY_BUDGET 约束，超时或部分批次失败时返回已获取的部分结果，按传入顺序排列
+pub async fn fetch(db: &Database, codes: &[String]) -> Result<Vec<MarketStockSnapshot>> {
+    let codes: Vec<String> = {
+        let mut seen = HashSet::new();
+        codes.iter().filter(|c| seen.insert(c.to_string())).cloned().collect()
+    };
+    if codes.is_empty() {
+        return Ok(vec![]);
+    }
+    let deadline = Instant::now() + LATENCY_BUDGET;
+    let scanner = MarketScanner::new()?;
+
+    let quotes = async {
+        let mut batches = stream::iter(codes.chunks(QUOTE_CHUNK).map(|c| c.to_vec()).collect::<Vec<_>>())
+            .map(|chunk| {
+                let scanner = &scanner;
+                async move { scanner.fetch_stocks_by_codes(&chunk).await }
+            })
+            .buffer_unordered(QUOTE_CONCURRENCY);
+        let (mut stocks, mut last_err) = (Vec::new(), None);
+        loop {
+            match tokio::time::timeout_at(deadline, batches.next()).await {
+                Ok(Some(Ok(batch))) => stocks.extend(batch),
+                Ok(Some(Err(e))) => {
+                    log::warn!("[watchlist_enriched] quote batch failed: {}", e);
+                    last_err = Some(e);
+                }
+                Ok(None) => break,
+                Err(_) => {
+                    log::warn!("[watchlist_enriched] quotes exceeded {:?}, got {}/{}", LATENCY_BUDGET, stocks.len(), codes.len());
+                    break;
+                }
+            }
+        }
+        (stocks, last_err)
+    };
+    let flows = async {
+        match tokio::time::timeout_at(deadline, fund_flow::fetch_breakdowns(&codes)).await {
+            Ok(Ok(flows)) => flows,
+            Ok(Err(e)) => {
+                log::warn!("[watchlist_enriched] fetch fund flow failed: {}", e);
+                vec![]
+            }
+            Err(_) => {
+                log::warn!("[watchlist_enriched] fund flow exceeded {:?}, skipped", LATENCY_BUDGET);
+                vec![]
+            }
+        }
+    };
+    let ((mut stocks, last_err), flows) = tokio::join!(quotes, flows);
+    if stocks.is_empty() {
+        if let Some(e) = last_err {
+            return Err(e);
+        }
+        if Instant::now() >= deadline {
+            return Err(anyhow!("获取自选股行情超时"));
+        }
+    }
+
+    let flows: HashMap<String, _> = flows.into_iter().map(|f| (f.code.clone(), f)).collect();
+    for stock in &mut stocks {
+        if let Some(flow) = flows.get(&stock.code) {
+            stock.main_net_inflow = flow.main_net;
+            stock.main_net_pct = flow.main_pct;
+        }
+    }
+    fill_period_pct(db, &mut stocks);
+    closing_auction::attach_flags(db, &mut stocks);
+
+    let order: HashMap<&str, usize> = codes.iter().enumerate().map(|(i, c)| (c.as_str(), i)).collect();
+    stocks.sort_by_key(|s| order.get(s.code.as_str()).copied().unwrap_or(usize::MAX));
+    log::info!("[watchlist_enriched] fetch done {}/{} flows={}", stocks.len(), codes.len(), flows.len());
+    Ok(stocks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(date: &str, close: f64) -> StockDailyHistory {
+        StockDailyHistory {
+            code: "sh600000".to_string(),
+            date: date.to_string(),
+            close,
+            high: close,
+            low: close,
+            open: close,
+            volume: 0.0,
+            amount: 0.0,
+            change_pct: 0.0,
+            is_limit_up: false,
+            turnover_rate: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_period_pct() {
+        let history = vec![day("2024-06-07", 11.0), day("2024-06-06", 10.0), day("2024-06-05", 8.0)];
+        // 最新日线为今天：以往前 n 条为基准
+        assert_eq!(period_pct(&history, "2024-06-07", 12.0, 1), Some(20.0));
+        assert_eq!(period_pct(&history, "2024-06-07", 12.0, 2), Some(50.0));
+        // 今天尚未落库：最新一条即为 1 日前
+        assert_eq!(period_pct(&history, "2024-06-10", 12.0, 1), Some(9.09));
+        assert_eq!(period_pct(&history, "2024-06-07", 12.0, 5), None);
+    }
+}
@@ -0,0 +1,295 @@
+use anyhow::{anyhow, Result};
+use encoding_rs::GBK;
+
+use crate::models::watchlist::WatchlistStock;
+use crate::services::stock_data::format_stock_code;
+
+/// 自选股导入/导出文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchlistFormat {
+    /// 通用 CSV：代码,名称,分组
+    Csv,
+    /// 同花顺自选股导出（制表符分隔，首列代码、次列名称）
+    Ths,
+    /// 通达信板块文件（.blk/.ebk，每行 市场位+6位代码，0=深 1=沪 2=北）
+    Tdx,
+}
+
+impl WatchlistFormat {
+    pub fn from_name(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "csv" => Ok(Self::Csv),
+            "ths" | "txt" => Ok(Self::Ths),
+            "tdx" | "blk" | "ebk" => Ok(Self::Tdx),
+            other => Err(anyhow!("不支持的自选股格式: {}", other)),
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Ths => "txt",
+            Self::Tdx => "blk",
+        }
+    }
+}
+
+/// 解码导入文件：优先 UTF-8（去 BOM），否则按 GBK 处理（同花顺/通达信默认编码）
+pub fn decode_file(bytes: &[u8]) -> String {
+    crate::utils::encoding::decode_auto(bytes, None)
+}
+
+/// 解析导入内容，返回 (有效条目, 无法识别的行数)。
+/// 条目的 group_name 为文件内的分组（CSV），未指定时为空；名称缺失时为空
+pub fn parse_watchlist(content: &str, format: WatchlistFormat) -> (Vec<WatchlistStock>, usize) {
+    let mut stocks = Vec::new();
+    let mut invalid = 0;
+    let mut lines = content.lines().map(|l| l.trim()).filter(|l| !l.is_empty()).peekable();
+
+    // CSV 表头：按列名定位代码/名称/分组列
+    let mut columns = (0usize, Some(1usize), Some(2usize));
+    if format == WatchlistFormat::Csv {
+        if let Some(header) = lines.peek() {
+            let fields = split_csv_line(header);
+            let find = |names: &[&str]| {
+                fields.iter().position(|f| names.iter().any(|n| f.eq_ignore_ascii_case(n)))
+            };
+            if let Some(code_col) = find(&["代码", "股票代码", "code"]) {
+                columns = (code_col, find(&["名称", "股票名称", "name"]), find(&["分组", "group", "group_name"]));
+                lines.next();
+            }
+        }
+    }
+
+    // 同花顺导出首行为中文表头
+    if format == WatchlistFormat::Ths && lines.peek().is_some_and(|l| l.contains("代码")) {
+        lines.next();
+    }
+
+    for line in lines {
+        let parsed = match format {
+            WatchlistFormat::Csv => {
+                let fields = split_csv_line(line);
+                normalize_code(fields.get(columns.0).map(|s| s.as_str()).unwrap_or("")).map(|code| {
+                    let pick = |col: Option<usize>| col.and_then(|c| fields.get(c)).cloned().unwrap_or_default();
+                    (code, pick(columns.1), pick(columns.2))
+                })
+            }
+            WatchlistFormat::Ths => {
+                // 制表符分隔时名称中可能含逗号，只在无制表符时按逗号拆分
+                let sep = if line.contains('\t') { '\t' } else { ',' };
+                let fields: Vec<&str> = line.split(sep).map(|f| f.trim()).collect();
+                fields.iter().enumerate().find_map(|(i, f)| {
+                    normalize_code(f).map(|code| {
+                        let name = fields.get(i + 1).filter(|n| normalize_code(n).is_none()).map(|n| n.to_string());
+                        (code, name.unwrap_or_default(), String::new())
+                    })
+                })
+            }
+            WatchlistFormat::Tdx => parse_tdx_code(line).map(|code| (code, String::new(), String::new())),
+        };
+        match parsed {
+            Some((code, name, group_name)) => stocks.push(WatchlistStock {
+                code,
+                name,
+                sort_order: 0,
+                group_name,
+                created_at: String::new(),
+            }),
+            None => invalid += 1,
+        }
+    }
+    (stocks, invalid)
+}
+
+/// 导出自选股为指定格式的文件内容（字节）。同花顺/通达信按 GBK 编码，CSV 带 BOM 以便 Excel 识别
+pub fn export_watchlist(stocks: &[WatchlistStock], format: WatchlistFormat) -> Vec<u8> {
+    match format {
+        WatchlistFormat::Csv => {
+            let mut out = String::from("\u{feff}代码,名称,分组\r\n");
+            for s in stocks {
+                out.push_str(&format!("{},{},{}\r\n", s.code, escape_csv(&s.name), escape_csv(&s.group_name)));
+            }
+            out.into_bytes()
+        }
+        WatchlistFormat::Ths => {
+            let mut out = String::from("代码\t名称\r\n");
+            for s in stocks {
+                out.push_str(&format!("{}\t{}\r\n", s.code.to_uppercase(), s.name));
+            }
+            GBK.encode(&out).0.into_owned()
+        }
+        WatchlistFormat::Tdx => {
+            let mut out = String::new();
+            for s in stocks {
+                let market = match s.code.get(..2) {
+                    Some("sh") => '1',
+                    Some("bj") => '2',
+                    _ => '0',
+                };
+                out.push_str(&format!("{}{}\r\n", market, s.code.get(2..).unwrap_or("")));
+            }
+            out.into_bytes()
+        }
+    }
+}
+
+/// 将 600519 / SH600519 / 600519.SH / sh600519 统一为 sh600519；无法识别时返回 None
+fn normalize_code(raw: &str) -> Option<String> {
+    let raw = raw.trim().trim_matches('"').to_lowercase();
+    let (digits, suffix) = match raw.split_once('.') {
+        Some((d, s)) => (d.to_string(), s.to_string()),
+        None => (raw.clone(), String::new()),
+    };
+    let (prefix, digits) = match digits.get(..2) {
+        Some(p) if digits.len() == 8 && ["sh", "sz", "bj"].contains(&p) => (p.to_string(), digits[2..].to_string()),
+        _ => (suffix, digits),
+    };
+    if digits.len() != 6 || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    match prefix.as_str() {
+        "sh" | "sz" | "bj" => Some(format!("{}{}", prefix, digits)),
+        _ => Some(format_stock_code(&digits)),
+    }
+}
+
+fn parse_tdx_code(line: &str) -> Option<String> {
+    if line.len() != 7 || !line.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let prefix = match &line[..1] {
+        "1" => "sh",
+        "0" => "sz",
+        "2" => "bj",
+        _ => return None,
+    };
+    Some(format!("{}{}", prefix, &line[1..]))
+}
+
+/// 简易 CSV 行拆分（支持双引号包裹的字段）
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                current.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut current).trim().to_string()),
+            _ => current.push(c),
+        }
+    }
+    fields.push(current.trim().to_string());
+    fields
+}
+
+fn escape_csv(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stock(code: &str, name: &str, group_name: &str) -> WatchlistStock {
+        WatchlistStock {
+            code: code.to_string(),
+            name: name.to_string(),
+            sort_order: 0,
+            group_name: group_name.to_string(),
+            created_at: String::new(),
+        }
+    }
+
+    fn summary(stocks: &[WatchlistStock]) -> Vec<(&str, &str, &str)> {
+        stocks.iter().map(|s| (s.code.as_str(), s.name.as_str(), s.group_name.as_str())).collect()
+    }
+
+    #[test]
+    fn test_normalize_code() {
+        assert_eq!(normalize_code("600519.SH").as_deref(), Some("sh600519"));
+        assert_eq!(normalize_code("SH600519").as_deref(), Some("sh600519"));
+        assert_eq!(normalize_code("\"sz000001\"").as_deref(), Some("sz000001"));
+        assert_eq!(normalize_code("600519").as_deref(), Some("sh600519"));
+        assert_eq!(normalize_code("300750").as_deref(), Some("sz300750"));
+        assert_eq!(normalize_code("830799.BJ").as_deref(), Some("bj830799"));
+        assert_eq!(normalize_code("60051"), None);
+        assert_eq!(normalize_code("sx600519"), None);
+        assert_eq!(normalize_code("中600519"), None);
+        assert_eq!(normalize_code("贵州茅台"), None);
+    }
+
+    #[test]
+    fn test_parse_tdx_code() {
+        assert_eq!(parse_tdx_code("1600519").as_deref(), Some("sh600519"));
+        assert_eq!(parse_tdx_code("0000001").as_deref(), Some("sz000001"));
+        assert_eq!(parse_tdx_code("2830799").as_deref(), Some("bj830799"));
+        assert_eq!(parse_tdx_code("3600519"), None);
+        assert_eq!(parse_tdx_code("160051"), None);
+        assert_eq!(parse_tdx_code("1中0051"), None);
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let content = "代码,名称,分组\n600519.SH,贵州茅台,白酒\nSZ000001,平安银行,\n\n300750,\"宁德,时代\",新能源\nbad,坏行,\n";
+        let (stocks, invalid) = parse_watchlist(content, WatchlistFormat::Csv);
+        assert_eq!(
+            summary(&stocks),
+            [("sh600519", "贵州茅台", "白酒"), ("sz000001", "平安银行", ""), ("sz300750", "宁德,时代", "新能源")]
+        );
+        assert_eq!(invalid, 1);
+
+        // 表头列顺序不同时按列名定位；无表头时按 代码,名称,分组 读取
+        let (stocks, _) = parse_watchlist("name,code\n贵州茅台,sh600519\n", WatchlistFormat::Csv);
+        assert_eq!(summary(&stocks), [("sh600519", "贵州茅台", "")]);
+        let (stocks, invalid) = parse_watchlist("sh600519,贵州茅台\n", WatchlistFormat::Csv);
+        assert_eq!((summary(&stocks), invalid), (vec![("sh600519", "贵州茅台", "")], 0));
+    }
+
+    #[test]
+    fn test_parse_ths_and_tdx() {
+        let content = "代码\t名称\tzf\nSH600519\t贵州茅台\t1.2\n000001.SZ\t平安银行\n合计\t2\n";
+        let (stocks, invalid) = parse_watchlist(content, WatchlistFormat::Ths);
+        assert_eq!(summary(&stocks), [("sh600519", "贵州茅台", ""), ("sz000001", "平安银行", "")]);
+        assert_eq!(invalid, 1);
+
+        let (stocks, invalid) = parse_watchlist("1600519\r\n0000001\r\n2830799\r\n3600519\r\n160051\r\n", WatchlistFormat::Tdx);
+        assert_eq!(stocks.iter().map(|s| s.code.as_str()).collect::<Vec<_>>(), ["sh600519", "sz000001", "bj830799"]);
+        assert_eq!(invalid, 2);
+    }
+
+    #[test]
+    fn test_export_round_trip() {
+        let stocks = vec![stock("sh600519", "贵州茅台", "白酒"), stock("sz000001", "平安,银行", ""), stock("bj830799", "艾融软件", "北交所")];
+
+        let csv = export_watchlist(&stocks, WatchlistFormat::Csv);
+        assert!(csv.starts_with(b"\xEF\xBB\xBF"));
+        let (parsed, invalid) = parse_watchlist(&decode_file(&csv), WatchlistFormat::Csv);
+        assert_eq!((summary(&parsed), invalid), (summary(&stocks), 0));
+
+        // 同花顺按 GBK 编码，导入时自动识别
+        let ths = export_watchlist(&stocks, WatchlistFormat::Ths);
+        assert!(std::str::from_utf8(&ths).is_err());
+        let (parsed, invalid) = parse_watchlist(&decode_file(&ths), WatchlistFormat::Ths);
+        assert_eq!(invalid, 0);
+        assert_eq!(
+            summary(&parsed),
+            [("sh600519", "贵州茅台", ""), ("sz000001", "平安,银行", ""), ("bj830799", "艾融软件", "")]
+        );
+
+        let tdx = export_watchlist(&stocks, WatchlistFormat::Tdx);
+        assert_eq!(tdx, b"1600519\r\n0000001\r\n2830799\r\n");
+        let (parsed, invalid) = parse_watchlist(&decode_file(&tdx), WatchlistFormat::Tdx);
+        assert_eq!(parsed.iter().map(|s| s.code.as_str()).collect::<Vec<_>>(), ["sh600519", "sz000001", "bj830799"]);
+        assert_eq!(invalid, 0);
+    }
+}
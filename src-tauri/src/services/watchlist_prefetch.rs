@@ -0,0 +1,73 @@
+use anyhow::Result;
+use futures::stream::{self, StreamExt};
+use std::collections::HashSet;
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Manager};
+
+use crate::AppState;
+use crate::db::database::Database;
+use crate::services::f10_service;
+use crate::services::history_kline::HistoryKlineService;
+use crate::services::stock_data::format_stock_code;
+use crate::services::valuation;
+
+/// 同时预取的股票数（批量导入时避免占满行情接口）
+const PREFETCH_CONCURRENCY: usize = 2;
+
+/// 正在预取的代码，重复加入时跳过
+fn in_flight() -> &'static Mutex<HashSet<String>> {
+    static IN_FLIGHT: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+    IN_FLIGHT.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+/// 预取单只股票：日线写入本地缓存（首次自 HISTORY_START_DATE 全量拉取，覆盖两年以上）、估值历史写入本地缓存，
+/// F10 公司资料与一致预期写入内存缓存。日线失败返回错误，其余失败只记录日志
+pub async fn prefetch_stock(db: &Database, code: &str) -> Result<()> {
+    let added = HistoryKlineService::new()?.sync_daily_history(db, code).await?;
+    let valuation = valuation::sync_valuation_history(db, code).await.unwrap_or_else(|e| {
+        log::warn!("[watchlist_prefetch] sync valuation failed for {}: {}", code, e);
+        0
+    });
+    let (profile, forecast) = tokio::join!(
+        f10_service::fetch_stock_profile(code),
+        f10_service::fetch_earnings_forecast(code),
+    );
+    if let Err(e) = profile {
+        log::warn!("[watchlist_prefetch] fetch profile failed for {}: {}", code, e);
+    }
+    if let Err(e) = forecast {
+        log::warn!("[watchlist_prefetch] fetch forecast failed for {}: {}", code, e);
+    }
+    log::info!("[watchlist_prefetch] prefetched {} klines={} valuation={}", code, added, valuation);
+    Ok(())
+}
+
+/// 在后台预取新加入自选股的日线与基本面，命令立即返回；首次技术分析与 AI 诊断直接命中缓存
+pub fn spawn_prefetch(app: &AppHandle, codes: Vec<String>) {
+    let codes: Vec<String> = {
+        let mut running = in_flight().lock().unwrap();
+        codes
+            .iter()
+            .map(|c| format_stock_code(c))
+            .filter(|c| !c.is_empty() && running.insert(c.clone()))
+            .collect()
+    };
+    if codes.is_empty() {
+        return;
+    }
+    log::info!("[watchlist_prefetch] spawn_prefetch count={}", codes.len());
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        let _guard = state.shutdown.track();
+        let db = &state.db;
+        stream::iter(codes)
+            .for_each_concurrent(PREFETCH_CONCURRENCY, |code| async move {
+                if let Err(e) = prefetch_stock(db, &code).await {
+                    log::warn!("[watchlist_prefetch] prefetch {} failed: {}", code, e);
+                }
+                in_flight().lock().unwrap().remove(&code);
+            })
+            .await;
+    });
+}
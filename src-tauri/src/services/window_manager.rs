@@ -0,0 +1,172 @@
+use anyhow::{anyhow, Result};
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder, WindowEvent};
+
+use crate::AppState;
+use crate::models::window::{DetachedWindow, WINDOW_KIND_KLINE, WINDOW_KIND_MINI_QUOTE, WINDOW_KIND_QUICK_SEARCH};
+use crate::services::stock_data::format_stock_code;
+
+/// 独立窗口打开或关闭后通知各窗口刷新列表
+pub const WINDOWS_CHANGED_EVENT: &str = "detached-windows-changed";
+const MINI_QUOTE_LABEL: &str = "mini-quote";
+const QUICK_SEARCH_LABEL: &str = "quick-search";
+const KLINE_LABEL_PREFIX: &str = "kline-";
+
+/// 窗口标签：迷你行情条与快速查股全局唯一，K 线窗口每只股票一个
+pub fn window_label(kind: &str, code: Option<&str>) -> Result<String> {
+    match kind {
+        WINDOW_KIND_MINI_QUOTE => Ok(MINI_QUOTE_LABEL.to_string()),
+        WINDOW_KIND_QUICK_SEARCH => Ok(QUICK_SEARCH_LABEL.to_string()),
+        WINDOW_KIND_KLINE => {
+            let code = format_stock_code(code.ok_or_else(|| anyhow!("K 线窗口需要股票代码"))?);
+            if code.len() != 8 || !code.is_ascii() || !code[2..].chars().all(|c| c.is_ascii_digit()) {
+                return Err(anyhow!("无效的股票代码: {}", code));
+            }
+            Ok(format!("{}{}", KLINE_LABEL_PREFIX, code))
+        }
+        other => Err(anyhow!("不支持的窗口类型: {}", other)),
+    }
+}
+
+/// 独立窗口加载的前端地址，前端按 window 参数渲染对应页面
+pub fn window_url(window: &DetachedWindow) -> String {
+    let mut url = format!("index.html?window={}", window.kind);
+    if let Some(code) = &window.code {
+        url.push_str(&format!("&code={}", urlencoding::encode(code)));
+    }
+    if let Some(name) = &window.name {
+        url.push_str(&format!("&name={}", urlencoding::encode(name)));
+    }
+    url
+}
+
+fn notify_changed(app: &AppHandle) {
+    let _ = app.emit(WINDOWS_CHANGED_EVENT, list(app));
+}
+
+/// 打开独立窗口，已打开时切到前台
+pub fn open(app: &AppHandle, kind: &str, code: Option<String>, name: Option<String>) -> Result<DetachedWindow> {
+    let label = window_label(kind, code.as_deref())?;
+    let state = app.state::<AppState>();
+    if let Some(existing) = app.get_webview_window(&label) {
+        let _ = existing.unminimize();
+        let _ = existing.show();
+        let _ = existing.set_focus();
+        if let Some(window) = state.windows.lock().unwrap().get(&label) {
+            return Ok(window.clone());
+        }
+    }
+
+    let code = code.map(|c| format_stock_code(&c));
+    let window = DetachedWindow {
+        label: label.clone(),
+        kind: kind.to_string(),
+        code: code.clone(),
+        name: name.filter(|n| !n.trim().is_empty()),
+        always_on_top: kind == WINDOW_KIND_MINI_QUOTE || kind == WINDOW_KIND_QUICK_SEARCH,
+    };
+    let builder = WebviewWindowBuilder::new(app, &label, WebviewUrl::App(window_url(&window).into()));
+    let builder = match kind {
+        WINDOW_KIND_MINI_QUOTE => builder.title("迷你行情").inner_size(320.0, 420.0).min_inner_size(240.0, 160.0),
+        WINDOW_KIND_QUICK_SEARCH => builder
+            .title("快速查股")
+            .inner_size(480.0, 360.0)
+            .resizable(false)
+            .decorations(false)
+            .skip_taskbar(true)
+            .center(),
+        _ => {
+            let title = format!("{} {}", window.name.as_deref().unwrap_or_default(), code.as_deref().unwrap_or_default());
+            builder.title(title.trim()).inner_size(1000.0, 640.0).min_inner_size(600.0, 400.0)
+        }
+    };
+    let webview = builder.always_on_top(window.always_on_top).build()?;
+
+    let handle = app.clone();
+    let closed_label = label.clone();
+    webview.on_window_event(move |event| {
+        if let WindowEvent::Destroyed = event {
+            handle.state::<AppState>().windows.lock().unwrap().remove(&closed_label);
+            log::info!("[window_manager] closed label={}", closed_label);
+            notify_changed(&handle);
+        }
+    });
+    state.windows.lock().unwrap().insert(label.clone(), window.clone());
+    log::info!("[window_manager] open label={}", label);
+    notify_changed(app);
+    Ok(window)
+}
+
+/// 窗口已打开时关闭，否则打开（用于全局快捷键唤起/收起）
+pub fn toggle(app: &AppHandle, kind: &str) -> Result<()> {
+    let label = window_label(kind, None)?;
+    match app.get_webview_window(&label) {
+        Some(window) => window.close()?,
+        None => {
+            open(app, kind, None, None)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn close(app: &AppHandle, label: &str) -> Result<()> {
+    let window = app.get_webview_window(label).ok_or_else(|| anyhow!("窗口不存在: {}", label))?;
+    window.close()?;
+    Ok(())
+}
+
+/// 关闭全部独立窗口
+pub fn close_all(app: &AppHandle) {
+    let labels: Vec<String> = app.state::<AppState>().windows.lock().unwrap().keys().cloned().collect();
+    for label in labels {
+        if let Some(window) = app.get_webview_window(&label) {
+            let _ = window.close();
+        }
+    }
+}
+
+/// 切换窗口置顶
+pub fn set_always_on_top(app: &AppHandle, label: &str, on_top: bool) -> Result<DetachedWindow> {
+    let webview = app.get_webview_window(label).ok_or_else(|| anyhow!("窗口不存在: {}", label))?;
+    webview.set_always_on_top(on_top)?;
+    let state = app.state::<AppState>();
+    let mut windows = state.windows.lock().unwrap();
+    let window = windows.get_mut(label).ok_or_else(|| anyhow!("窗口不存在: {}", label))?;
+    window.always_on_top = on_top;
+    Ok(window.clone())
+}
+
+/// 当前打开的独立窗口，按标签排序
+pub fn list(app: &AppHandle) -> Vec<DetachedWindow> {
+    let state = app.state::<AppState>();
+    let mut windows: Vec<DetachedWindow> = state.windows.lock().unwrap().values().cloned().collect();
+    windows.sort_by(|a, b| a.label.cmp(&b.label));
+    windows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_label_and_url() {
+        assert_eq!(window_label(WINDOW_KIND_MINI_QUOTE, None).unwrap(), "mini-quote");
+        assert_eq!(window_label(WINDOW_KIND_QUICK_SEARCH, None).unwrap(), "quick-search");
+        assert_eq!(window_label(WINDOW_KIND_KLINE, Some("600519")).unwrap(), "kline-sh600519");
+        assert!(window_label(WINDOW_KIND_KLINE, None).is_err());
+        assert!(window_label(WINDOW_KIND_KLINE, Some("abc")).is_err());
+        assert!(window_label(WINDOW_KIND_KLINE, Some("中abcde")).is_err());
+        assert!(window_label("settings", None).is_err());
+
+        let window = DetachedWindow {
+            label: "kline-sh600519".to_string(),
+            kind: WINDOW_KIND_KLINE.to_string(),
+            code: Some("sh600519".to_string()),
+            name: Some("贵州茅台".to_string()),
+            always_on_top: false,
+        };
+        assert_eq!(
+            window_url(&window),
+            "index.html?window=kline&code=sh600519&name=%E8%B4%B5%E5%B7%9E%E8%8C%85%E5%8F%B0"
+        );
+    }
+}
@@ -1,6 +1,71 @@
-use encoding_rs::GB18030;
+use encoding_rs::{Encoding, GB18030, UTF_8};
+use reqwest::header::CONTENT_TYPE;
 
+/// GBK / GB2312 统一按其超集 GB18030 解码
 pub fn gb18030_to_utf8(bytes: &[u8]) -> String {
     let (cow, _, _) = GB18030.decode(bytes);
     cow.into_owned()
 }
+
+/// Content-Type 中声明的字符集，如 `text/html; charset=GBK`
+fn declared_charset(content_type: &str) -> Option<&'static Encoding> {
+    content_type.split(';').find_map(|part| {
+        let (key, value) = part.split_once('=')?;
+        key.trim()
+            .eq_ignore_ascii_case("charset")
+            .then(|| Encoding::for_label(value.trim().trim_matches('"').as_bytes()))
+            .flatten()
+    })
+}
+
+/// 自动识别字符集并解码：UTF-8 BOM → 声明的非 UTF-8 字符集 → 合法 UTF-8 → GB18030。
+/// 声明为 UTF-8 但实际是 GBK 的响应（新浪/腾讯部分接口）同样按 GB18030 兜底
+pub fn decode_auto(bytes: &[u8], content_type: Option<&str>) -> String {
+    if let Some(rest) = bytes.strip_prefix(b"\xEF\xBB\xBF") {
+        return String::from_utf8_lossy(rest).into_owned();
+    }
+    if let Some(encoding) = content_type.and_then(declared_charset).filter(|e| *e != UTF_8) {
+        let (cow, _) = encoding.decode_without_bom_handling(bytes);
+        return cow.into_owned();
+    }
+    match std::str::from_utf8(bytes) {
+        Ok(s) => s.to_string(),
+        Err(_) => gb18030_to_utf8(bytes),
+    }
+}
+
+/// 读取响应体并按 decode_auto 解码，用于可能返回 GBK 的行情接口
+pub async fn response_text(resp: reqwest::Response) -> reqwest::Result<String> {
+    let content_type = resp
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    let bytes = resp.bytes().await?;
+    Ok(decode_auto(&bytes, content_type.as_deref()))
+}
+
+/// 去掉解码失败留下的替换字符与控制字符，避免乱码名称写入行情快照
+pub fn clean_text(s: &str) -> String {
+    s.chars()
+        .filter(|c| *c != char::REPLACEMENT_CHARACTER && !c.is_control())
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_auto() {
+        let gbk = encoding_rs::GBK.encode("平安银行").0.into_owned();
+        assert_eq!(decode_auto(&gbk, None), "平安银行");
+        assert_eq!(decode_auto(&gbk, Some("text/html; charset=GBK")), "平安银行");
+        assert_eq!(decode_auto(&gbk, Some("application/json; charset=utf-8")), "平安银行");
+        assert_eq!(decode_auto("平安银行".as_bytes(), Some("text/plain")), "平安银行");
+        assert_eq!(decode_auto(b"\xEF\xBB\xBFcode", None), "code");
+        assert_eq!(clean_text(" 平安\u{FFFD}银行\r"), "平安银行");
+    }
+}
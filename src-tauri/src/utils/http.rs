@@ -1,6 +1,69 @@
 use anyhow::Result;
 use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, CONTENT_TYPE, ORIGIN, USER_AGENT, REFERER, HOST};
-use std::time::Duration;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// 调试日志开关：开启后记录每个上游请求的 URL、状态码、耗时与请求体大小（不记录内容）
+static REQUEST_LOGGING: AtomicBool = AtomicBool::new(false);
+/// 日志中需要隐去取值的查询参数
+const SENSITIVE_PARAMS: &[&str] = &["key", "apikey", "api_key", "token", "access_token", "secret", "sign", "qgqp_b_id", "fingerprint"];
+
+pub fn set_request_logging(enabled: bool) {
+    if REQUEST_LOGGING.swap(enabled, Ordering::Relaxed) != enabled {
+        log::info!("[http] request logging {}", if enabled { "enabled" } else { "disabled" });
+    }
+}
+
+/// 隐去 URL 中敏感查询参数的取值
+pub fn redact_url(url: &reqwest::Url) -> String {
+    if url.query().is_none() {
+        return url.to_string();
+    }
+    let mut redacted = url.clone();
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(k, v)| {
+            let sensitive = SENSITIVE_PARAMS.contains(&k.to_lowercase().as_str());
+            (k.into_owned(), if sensitive { "***".to_string() } else { v.into_owned() })
+        })
+        .collect();
+    redacted.query_pairs_mut().clear().extend_pairs(pairs);
+    redacted.to_string()
+}
+
+/// 发送请求并在调试日志开启时记录请求摘要，未开启时与 send() 相同
+pub trait SendLogged {
+    fn send_logged(self) -> impl Future<Output = reqwest::Result<reqwest::Response>> + Send;
+}
+
+impl SendLogged for reqwest::RequestBuilder {
+    async fn send_logged(self) -> reqwest::Result<reqwest::Response> {
+        if !REQUEST_LOGGING.load(Ordering::Relaxed) {
+            return self.send().await;
+        }
+        let (client, request) = self.build_split();
+        let request = request?;
+        let method = request.method().clone();
+        let raw_url = request.url().to_string();
+        let url = redact_url(request.url());
+        let body_bytes = request.body().and_then(|b| b.as_bytes()).map_or(0, |b| b.len());
+        let start = Instant::now();
+        let result = client.execute(request).await;
+        match &result {
+            Ok(resp) => log::info!(
+                "[http] {} {} status={} elapsed={}ms req_bytes={} resp_bytes={}",
+                method, url, resp.status().as_u16(), start.elapsed().as_millis(), body_bytes,
+                resp.content_length().map_or("-".to_string(), |n| n.to_string())
+            ),
+            Err(e) => log::warn!(
+                "[http] {} {} failed elapsed={}ms req_bytes={}: {}",
+                method, url, start.elapsed().as_millis(), body_bytes, e.to_string().replace(&raw_url, &url)
+            ),
+        }
+        result
+    }
+}
 
 pub fn build_stock_client() -> Result<reqwest::Client> {
     let mut headers = HeaderMap::new();
@@ -89,3 +152,47 @@ pub fn build_cls_client() -> Result<reqwest::Client> {
         .build()?;
     Ok(client)
 }
+
+/// 多设备同步（WebDAV / S3）HTTP client，超时30秒
+pub fn build_sync_client() -> Result<reqwest::Client> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()?;
+    Ok(client)
+}
+
+/// 消息推送（钉钉/企业微信/Telegram/Server酱/Webhook）HTTP client，超时15秒
+pub fn build_notify_client() -> Result<reqwest::Client> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(15))
+        .build()?;
+    Ok(client)
+}
+
+/// 东方财富 F10（公司资料/经营分析/盈利预测）HTTP client
+pub fn build_f10_client() -> Result<reqwest::Client> {
+    let mut headers = HeaderMap::new();
+    headers.insert(USER_AGENT, HeaderValue::from_static("Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36"));
+    headers.insert(ACCEPT, HeaderValue::from_static("application/json, text/plain, */*"));
+    headers.insert(REFERER, HeaderValue::from_static("https://emweb.securities.eastmoney.com/"));
+
+    let client = reqwest::Client::builder()
+        .default_headers(headers)
+        .timeout(Duration::from_secs(10))
+        .gzip(true)
+        .build()?;
+    Ok(client)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_url() {
+        let url = reqwest::Url::parse("https://example.com/api?secid=1.000001&token=abc&qgqp_b_id=xyz").unwrap();
+        assert_eq!(redact_url(&url), "https://example.com/api?secid=1.000001&token=***&qgqp_b_id=***");
+        let plain = reqwest::Url::parse("https://example.com/api").unwrap();
+        assert_eq!(redact_url(&plain), "https://example.com/api");
+    }
+}
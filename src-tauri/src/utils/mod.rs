@@ -1,3 +1,6 @@
 pub mod encoding;
 pub mod http;
 pub mod retry;
+pub mod sse;
+pub mod crypto;
+pub mod units;
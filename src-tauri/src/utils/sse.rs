@@ -0,0 +1,213 @@
+use futures::{Stream, StreamExt};
+use std::collections::VecDeque;
+
+/// 一条完整的 SSE 事件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SseEvent {
+    /// event 字段，未指定时为 None（即默认的 "message"）
+    pub event: Option<String>,
+    /// 多个 data 行按规范以 '\n' 拼接
+    pub data: String,
+}
+
+impl SseEvent {
+    /// OpenAI 兼容接口的流结束标记
+    pub fn is_done(&self) -> bool {
+        self.data.trim() == "[DONE]"
+    }
+}
+
+/// Server-Sent Events 增量解码器
+///
+/// 按字节缓冲、按行切分后再解码，因此被 chunk 边界截断的多字节 UTF-8 字符不会变成乱码。
+/// 支持 LF / CRLF / CR 行尾、多行 data 字段、`:` 开头的注释行；空行触发事件派发。
+#[derive(Debug, Default)]
+pub struct SseDecoder {
+    buffer: Vec<u8>,
+    data_lines: Vec<String>,
+    event: Option<String>,
+    /// 上一个 chunk 以 '\r' 结尾，下一个 chunk 开头的 '\n' 属于同一个行尾
+    skip_next_lf: bool,
+}
+
+impl SseDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 喂入一个网络 chunk，返回其中已完整的事件
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        let mut chunk = chunk;
+        if self.skip_next_lf {
+            if let Some(rest) = chunk.strip_prefix(b"\n") {
+                chunk = rest;
+            }
+            self.skip_next_lf = false;
+        }
+        self.buffer.extend_from_slice(chunk);
+
+        let mut events = Vec::new();
+        let mut start = 0;
+        let mut i = 0;
+        while i < self.buffer.len() {
+            let b = self.buffer[i];
+            if b == b'\n' || b == b'\r' {
+                let line = String::from_utf8_lossy(&self.buffer[start..i]).into_owned();
+                if b == b'\r' {
+                    match self.buffer.get(i + 1) {
+                        Some(b'\n') => i += 1,
+                        Some(_) => {}
+                        None => self.skip_next_lf = true,
+                    }
+                }
+                if let Some(event) = self.process_line(&line) {
+                    events.push(event);
+                }
+                start = i + 1;
+            }
+            i += 1;
+        }
+        self.buffer.drain(..start);
+        events
+    }
+
+    /// 流结束时调用：处理末尾未换行的数据，并派发缺少结尾空行的最后一个事件
+    pub fn finish(&mut self) -> Option<SseEvent> {
+        if !self.buffer.is_empty() {
+            let line = String::from_utf8_lossy(&self.buffer).into_owned();
+            self.buffer.clear();
+            if let Some(event) = self.process_line(&line) {
+                return Some(event);
+            }
+        }
+        self.dispatch()
+    }
+
+    fn process_line(&mut self, line: &str) -> Option<SseEvent> {
+        if line.is_empty() {
+            return self.dispatch();
+        }
+        if line.starts_with(':') {
+            return None;
+        }
+        let (field, value) = match line.find(':') {
+            Some(pos) => {
+                let value = &line[pos + 1..];
+                (&line[..pos], value.strip_prefix(' ').unwrap_or(value))
+            }
+            None => (line, ""),
+        };
+        match field {
+            "data" => self.data_lines.push(value.to_string()),
+            "event" => self.event = Some(value.to_string()),
+            // id / retry 以及未知字段对本项目无意义，按规范忽略
+            _ => {}
+        }
+        None
+    }
+
+    fn dispatch(&mut self) -> Option<SseEvent> {
+        let event = self.event.take();
+        if self.data_lines.is_empty() {
+            return None;
+        }
+        let data = std::mem::take(&mut self.data_lines).join("\n");
+        Some(SseEvent { event, data })
+    }
+}
+
+/// 将字节流（如 reqwest 的 bytes_stream）包装为 SSE 事件流
+pub struct SseStream<S> {
+    inner: S,
+    decoder: SseDecoder,
+    pending: VecDeque<SseEvent>,
+    finished: bool,
+}
+
+impl<S, B, E> SseStream<S>
+where
+    S: Stream<Item = Result<B, E>> + Unpin,
+    B: AsRef<[u8]>,
+{
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            decoder: SseDecoder::new(),
+            pending: VecDeque::new(),
+            finished: false,
+        }
+    }
+
+    /// 取下一个事件；底层流出错时原样返回错误，流结束后返回 None
+    pub async fn next_event(&mut self) -> Option<Result<SseEvent, E>> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some(Ok(event));
+            }
+            if self.finished {
+                return None;
+            }
+            match self.inner.next().await {
+                Some(Ok(chunk)) => self.pending.extend(self.decoder.push(chunk.as_ref())),
+                Some(Err(e)) => return Some(Err(e)),
+                None => {
+                    self.finished = true;
+                    self.pending.extend(self.decoder.finish());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data_of(events: &[SseEvent]) -> Vec<&str> {
+        events.iter().map(|e| e.data.as_str()).collect()
+    }
+
+    #[test]
+    fn test_lf_and_crlf_line_endings() {
+        let mut decoder = SseDecoder::new();
+        let events = decoder.push(b"data: {\"a\":1}\n\ndata: {\"b\":2}\r\n\r\n");
+        assert_eq!(data_of(&events), vec!["{\"a\":1}", "{\"b\":2}"]);
+    }
+
+    #[test]
+    fn test_cr_split_across_chunks() {
+        let mut decoder = SseDecoder::new();
+        assert!(decoder.push(b"data: x\r").is_empty());
+        let events = decoder.push(b"\n\r\n");
+        assert_eq!(data_of(&events), vec!["x"]);
+    }
+
+    #[test]
+    fn test_multiline_data_and_comments() {
+        let mut decoder = SseDecoder::new();
+        let events = decoder.push(b": keep-alive\nevent: delta\ndata: line1\ndata:line2\n\n");
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event.as_deref(), Some("delta"));
+        assert_eq!(events[0].data, "line1\nline2");
+    }
+
+    #[test]
+    fn test_utf8_split_across_chunks() {
+        let bytes = "data: 涨停\n\n".as_bytes();
+        // "涨" 占 3 字节，从其中间切开
+        let (a, b) = bytes.split_at(8);
+        let mut decoder = SseDecoder::new();
+        assert!(decoder.push(a).is_empty());
+        let events = decoder.push(b);
+        assert_eq!(data_of(&events), vec!["涨停"]);
+    }
+
+    #[test]
+    fn test_finish_flushes_trailing_event() {
+        let mut decoder = SseDecoder::new();
+        assert!(decoder.push(b"data: [DONE]").is_empty());
+        let event = decoder.finish().expect("末尾事件应被派发");
+        assert!(event.is_done());
+        assert!(decoder.finish().is_none());
+    }
+}
@@ -0,0 +1,69 @@
+// 行情数值的统一单位：解析时即换算为标准单位，展示时统一经由本模块格式化。
+// 金额、市值、资金流向为元；成交量在快照（MarketStockSnapshot / KlineItem / TickPrint）中为手、
+// 在盘口行情（StockInfo）中为股；涨跌幅、换手率、占比为百分数（1.5 表示 1.5%）
+
+pub const YUAN_PER_WAN: f64 = 1e4;
+pub const YUAN_PER_YI: f64 = 1e8;
+pub const SHARES_PER_LOT: f64 = 100.0;
+
+/// 万元 → 元（腾讯成交额）
+pub fn wan_to_yuan(v: f64) -> f64 {
+    v * YUAN_PER_WAN
+}
+
+/// 亿元 → 元（腾讯市值）
+pub fn yi_to_yuan(v: f64) -> f64 {
+    v * YUAN_PER_YI
+}
+
+/// 元 → 亿元，用于与按亿填写的阈值比较
+pub fn yuan_to_yi(v: f64) -> f64 {
+    v / YUAN_PER_YI
+}
+
+/// 手 → 股
+pub fn lots_to_shares(v: f64) -> f64 {
+    v * SHARES_PER_LOT
+}
+
+/// 按成交价与成交手数估算成交额（元）
+pub fn lot_amount(price: f64, lots: f64) -> f64 {
+    price * lots * SHARES_PER_LOT
+}
+
+/// 金额（元）格式化为“1.23亿 / 4567万 / 890”
+pub fn format_amount(v: f64) -> String {
+    let abs = v.abs();
+    if abs >= YUAN_PER_YI {
+        format!("{:.2}亿", v / YUAN_PER_YI)
+    } else if abs >= YUAN_PER_WAN {
+        format!("{:.0}万", v / YUAN_PER_WAN)
+    } else {
+        format!("{:.0}", v)
+    }
+}
+
+/// 成交量（手）格式化为“12.3万手 / 4567手”
+pub fn format_lots(v: f64) -> String {
+    if v.abs() >= 1e4 {
+        format!("{:.1}万手", v / 1e4)
+    } else {
+        format!("{:.0}手", v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_units() {
+        assert_eq!(format_amount(1.2e8), "1.20亿");
+        assert_eq!(format_amount(-3.5e6), "-350万");
+        assert_eq!(format_amount(800.0), "800");
+        assert_eq!(format_lots(123_456.0), "12.3万手");
+        assert_eq!(wan_to_yuan(1.5), 15_000.0);
+        assert_eq!(yuan_to_yi(yi_to_yuan(12.0)), 12.0);
+        assert_eq!(lot_amount(10.0, 5.0), 5_000.0);
+    }
+}
@@ -6,7 +6,7 @@
 //! 如果没有设置 QGQP_B_ID 环境变量，NLP选股/板块搜索测试会跳过。
 
 use app_lib::services::smart_stock::SmartStockService;
-use app_lib::services::stock_tools;
+use app_lib::services::stock_tools::{self, ToolContext};
 
 fn get_qgqp_b_id() -> Option<String> {
     std::env::var("QGQP_B_ID").ok().filter(|s| !s.is_empty())
@@ -225,7 +225,7 @@ fn test_summarize_error_case() {
 
 #[tokio::test]
 async fn test_execute_pick_tool_unknown_returns_msg() {
-    let result = stock_tools::execute_pick_tool("nonexistent_tool", "{}", "").await
+    let result = stock_tools::execute_pick_tool("nonexistent_tool", "{}", &ToolContext::default()).await
         .expect("未知工具应返回Ok");
     assert!(result.contains("未知工具"), "应返回未知工具消息: {}", result);
 }
@@ -233,16 +233,38 @@ async fn test_execute_pick_tool_unknown_returns_msg() {
 #[tokio::test]
 async fn test_execute_pick_tool_hot_strategies_is_unknown() {
     // get_hot_strategies 已从 pick tools 移除，调用应返回"未知工具"
-    let result = stock_tools::execute_pick_tool("get_hot_strategies", r#"{"count":10}"#, "").await
+    let result = stock_tools::execute_pick_tool("get_hot_strategies", r#"{"count":10}"#, &ToolContext::default()).await
         .expect("应返回Ok");
     assert!(result.contains("未知工具"), "get_hot_strategies 应已从pick tools移除: {}", result);
 }
 
+// ==================== 工具结果缓存测试 ====================
+
+#[test]
+fn test_tool_cache_normalizes_argument_order() {
+    let mut cache = stock_tools::ToolResultCache::default();
+    cache.insert("get_kline_data", r#"{"code":"sh600519","count":60}"#, "kline");
+    let hit = cache.get("get_kline_data", r#"{ "count": 60, "code": "sh600519" }"#);
+    assert_eq!(hit.as_deref(), Some("kline"), "参数顺序不同应命中同一缓存");
+    assert!(cache.get("get_stock_quote", r#"{"code":"sh600519","count":60}"#).is_none(), "不同工具不应命中");
+}
+
+#[test]
+fn test_tool_cache_skips_failures_and_expires() {
+    let mut cache = stock_tools::ToolResultCache::default();
+    cache.insert("get_fund_flow", r#"{"code":"sz000001"}"#, "工具调用失败: timeout");
+    assert!(cache.get("get_fund_flow", r#"{"code":"sz000001"}"#).is_none(), "失败结果不应缓存");
+
+    let mut expired = stock_tools::ToolResultCache::new(std::time::Duration::ZERO);
+    expired.insert("get_fund_flow", r#"{"code":"sz000001"}"#, "ok");
+    assert!(expired.get("get_fund_flow", r#"{"code":"sz000001"}"#).is_none(), "过期结果不应命中");
+}
+
 // ==================== 全球指数 / 财经日历 集成测试 ====================
 
 #[tokio::test]
 async fn test_get_global_indexes_real_api() {
-    let result = stock_tools::execute_pick_tool("get_global_indexes", "{}", "").await
+    let result = stock_tools::execute_pick_tool("get_global_indexes", "{}", &ToolContext::default()).await
         .expect("全球指数API不应返回Err");
     let json: serde_json::Value = serde_json::from_str(&result).expect("应返回有效JSON");
     let total = json["total"].as_u64().unwrap_or(0);
@@ -259,7 +281,7 @@ async fn test_get_global_indexes_real_api() {
 
 #[tokio::test]
 async fn test_get_financial_calendar_real_api() {
-    let result = stock_tools::execute_pick_tool("get_financial_calendar", "{}", "").await
+    let result = stock_tools::execute_pick_tool("get_financial_calendar", "{}", &ToolContext::default()).await
         .expect("财经日历API不应返回Err");
     let json: serde_json::Value = serde_json::from_str(&result).expect("应返回有效JSON");
     let total = json["total"].as_u64().unwrap_or(0);
@@ -342,7 +364,7 @@ async fn test_execute_pick_tool_search_stocks() {
     let result = stock_tools::execute_pick_tool(
         "search_stocks_by_condition",
         r#"{"keyword": "人工智能", "page_size": 5}"#,
-        &qgqp_b_id,
+        &ToolContext { qgqp_b_id, ..Default::default() },
     ).await.expect("不应返回Err");
 
     let json: serde_json::Value = serde_json::from_str(&result).expect("应返回有效JSON");
@@ -356,7 +378,7 @@ async fn test_execute_pick_tool_empty_qgqp_returns_error_json() {
     let result = stock_tools::execute_pick_tool(
         "search_stocks_by_condition",
         r#"{"keyword": "人工智能"}"#,
-        "",
+        &ToolContext::default(),
     ).await.expect("应返回Ok(错误JSON)");
 
     let json: serde_json::Value = serde_json::from_str(&result).expect("应返回有效JSON");
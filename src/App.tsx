@@ -5,16 +5,23 @@ import Watchlist from './pages/Watchlist';
 import NewsCenter from './pages/NewsCenter';
 import MarketOverviewPage from './pages/MarketOverview';
 import UpdateModal from './components/UpdateModal';
+import AIRouterPanel from './components/AIRouterPanel';
+import ExpressionScreenerPanel from './components/ExpressionScreenerPanel';
 import type { UpdateInfo } from './components/UpdateModal';
-import { safeInvoke as invoke } from './hooks/useTauri';
+import { safeInvoke as invoke, safeListen } from './hooks/useTauri';
+import { useWatchlistStore } from './stores/watchlistStore';
+import { useAIPickStore } from './stores/aiPickStore';
+import type { DeepLink } from './types';
 import logger from './utils/logger';
-import { Settings as SettingsIcon, TrendingUp, ChevronLeft, Brain, Eye, Newspaper, BarChart3 } from 'lucide-react';
+import { Settings as SettingsIcon, TrendingUp, ChevronLeft, Brain, Eye, Newspaper, BarChart3, MessageSquare, Filter } from 'lucide-react';
 
 type Page = 'market' | 'board' | 'settings' | 'watchlist' | 'news';
 
 export default function App() {
   const [currentPage, setCurrentPage] = useState<Page>('market');
   const [updateInfo, setUpdateInfo] = useState<UpdateInfo | null>(null);
+  const [showRouter, setShowRouter] = useState(false);
+  const [showScreener, setShowScreener] = useState(false);
 
   // 启动时静默检查更新
   useEffect(() => {
@@ -32,6 +39,38 @@ export default function App() {
     return () => clearTimeout(timer);
   }, []);
 
+  // stockhelper:// 链接：冷启动时取走待处理目标，运行中收到事件后同样取走，避免重复打开
+  useEffect(() => {
+    const open = (link: DeepLink | null) => {
+      if (!link) return;
+      useWatchlistStore.getState().openDeepLink(link);
+      setCurrentPage('watchlist');
+    };
+    const take = () => invoke<DeepLink | null>('take_pending_deep_link').then(open).catch(() => {});
+    take();
+    let unlisten: (() => void) | undefined;
+    safeListen<DeepLink>('deep-link', () => take()).then(fn => { unlisten = fn; });
+    return () => unlisten?.();
+  }, []);
+
+  // 托盘菜单快捷操作
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    safeListen<string>('tray-action', event => {
+      if (event.payload === 'watchlist') {
+        setCurrentPage('watchlist');
+      } else if (event.payload === 'ai_pick') {
+        setCurrentPage('board');
+        const { picking, reset, startPick } = useAIPickStore.getState();
+        if (!picking) {
+          reset();
+          startPick();
+        }
+      }
+    }).then(fn => { unlisten = fn; });
+    return () => unlisten?.();
+  }, []);
+
   return (
     <div className="w-full h-full flex flex-col bg-bg-base">
       {/* 更新弹窗 */}
@@ -108,6 +147,30 @@ export default function App() {
         )}
 
         <div className="flex-1" />
+        <button
+          onClick={() => { setShowScreener(v => !v); setShowRouter(false); }}
+          title="用条件表达式扫描全市场"
+          className={`flex items-center gap-1.5 px-3 py-1.5 mr-2 text-xs font-medium rounded-md transition-all cursor-pointer ${
+            showScreener
+              ? 'bg-primary-gold/20 text-primary-gold border border-primary-gold/30'
+              : 'text-txt-secondary hover:text-txt-primary hover:bg-bg-elevated'
+          }`}
+        >
+          <Filter size={13} />
+          条件选股
+        </button>
+        <button
+          onClick={() => { setShowRouter(v => !v); setShowScreener(false); }}
+          title="用一句话提问：诊断个股、选股、资讯、对比、大盘"
+          className={`flex items-center gap-1.5 px-3 py-1.5 mr-2 text-xs font-medium rounded-md transition-all cursor-pointer ${
+            showRouter
+              ? 'bg-primary-gold/20 text-primary-gold border border-primary-gold/30'
+              : 'text-txt-secondary hover:text-txt-primary hover:bg-bg-elevated'
+          }`}
+        >
+          <MessageSquare size={13} />
+          问AI
+        </button>
         {currentPage !== 'settings' && (
           <button
             onClick={() => setCurrentPage('settings')}
@@ -132,6 +195,9 @@ export default function App() {
           <Settings />
         )}
       </main>
+
+      {showRouter && <AIRouterPanel onClose={() => setShowRouter(false)} />}
+      {showScreener && <ExpressionScreenerPanel onClose={() => setShowScreener(false)} />}
     </div>
   );
 }
@@ -0,0 +1,146 @@
+import { useEffect, useRef, useState } from 'react';
+import ReactMarkdown from 'react-markdown';
+import remarkGfm from 'remark-gfm';
+import { X, Send, Loader2, MessageSquare, Check, RefreshCw } from 'lucide-react';
+import { safeInvoke as invoke, safeListen } from '../hooks/useTauri';
+import { AIStreamEvent, RouterAnswer } from '../types';
+import logger from '../utils/logger';
+
+interface Props {
+  onClose: () => void;
+}
+
+interface ChatTurn {
+  question: string;
+  /** 识别出的意图说明，如“个股诊断 贵州茅台” */
+  mode: string;
+  tools: { name: string; label: string; done: boolean }[];
+  content: string;
+  done: boolean;
+}
+
+const EXAMPLES = ['帮我看看最近军工板块', '贵州茅台和五粮液哪个好', '今天大盘情绪怎么样', '宁德时代最近有什么公告'];
+
+/** 自然语言统一入口：自动识别诊断、选股、资讯、对比、大盘等意图并流式回答 */
+export default function AIRouterPanel({ onClose }: Props) {
+  const [input, setInput] = useState('');
+  const [turns, setTurns] = useState<ChatTurn[]>([]);
+  const [running, setRunning] = useState(false);
+  const contentRef = useRef<HTMLDivElement>(null);
+
+  const updateLast = (fn: (turn: ChatTurn) => ChatTurn) =>
+    setTurns(prev => (prev.length ? [...prev.slice(0, -1), fn(prev[prev.length - 1])] : prev));
+
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    safeListen<AIStreamEvent>('ai-router-stream', event => {
+      const { event_type, content, done, tool_name } = event.payload;
+      if (event_type === 'mode' && content) updateLast(t => ({ ...t, mode: content }));
+      if (event_type === 'tool_call' && tool_name) {
+        updateLast(t => ({ ...t, tools: [...t.tools, { name: tool_name, label: content || tool_name, done: false }] }));
+      }
+      if (event_type === 'tool_result' && tool_name) {
+        updateLast(t => ({
+          ...t,
+          tools: t.tools.map(x => (x.name === tool_name && !x.done ? { ...x, label: content || x.label, done: true } : x)),
+        }));
+      }
+      if (event_type === 'content' && content) updateLast(t => ({ ...t, content: t.content + content }));
+      if (event_type === 'error') updateLast(t => ({ ...t, done: true, content: `${t.content}\n\n[出错: ${content || '未知错误'}]` }));
+      if (event_type === 'done' || done) updateLast(t => ({ ...t, done: true }));
+    }).then(fn => { unlisten = fn; });
+    return () => unlisten?.();
+  }, []);
+
+  useEffect(() => {
+    contentRef.current?.scrollTo({ top: contentRef.current.scrollHeight });
+  }, [turns]);
+
+  const ask = async (question: string) => {
+    const text = question.trim();
+    if (!text || running) return;
+    setInput('');
+    setRunning(true);
+    setTurns(prev => [...prev, { question: text, mode: '正在识别意图...', tools: [], content: '', done: false }]);
+    try {
+      const answer = await invoke<RouterAnswer>('ai_router', { input: text });
+      updateLast(t => ({ ...t, done: true, content: t.content || answer?.content || '' }));
+    } catch (e) {
+      logger.error(`AI router failed: ${e}`);
+      updateLast(t => ({ ...t, done: true, content: t.content || `[出错: ${e}]` }));
+    } finally {
+      setRunning(false);
+    }
+  };
+
+  return (
+    <div className="fixed right-0 top-12 bottom-0 w-[480px] bg-bg-card border-l border-[#30363D] flex flex-col z-50 shadow-2xl shadow-black/50 animate-in slide-in-from-right">
+      <div className="flex items-center justify-between px-4 py-3 border-b border-[#30363D]">
+        <div className="flex items-center gap-2">
+          <MessageSquare size={16} className="text-primary-gold" />
+          <span className="font-bold text-txt-primary text-sm">问 AI</span>
+        </div>
+        <button onClick={onClose} className="p-1 rounded hover:bg-bg-elevated transition-colors cursor-pointer">
+          <X size={18} className="text-txt-secondary" />
+        </button>
+      </div>
+
+      <div ref={contentRef} className="flex-1 overflow-auto px-4 py-3 space-y-4">
+        {turns.length === 0 && (
+          <div className="space-y-2 pt-6">
+            <p className="text-xs text-txt-muted text-center">用一句话提问，自动识别个股诊断、选股、资讯、对比与大盘研判</p>
+            {EXAMPLES.map(example => (
+              <button
+                key={example}
+                onClick={() => ask(example)}
+                className="block w-full px-3 py-2 rounded-lg border border-[#30363D] text-left text-xs text-txt-secondary hover:text-txt-primary hover:border-[#484F58] transition-colors cursor-pointer"
+              >
+                {example}
+              </button>
+            ))}
+          </div>
+        )}
+        {turns.map((turn, i) => (
+          <div key={i} className="space-y-2">
+            <div className="ml-auto w-fit max-w-[85%] px-3 py-2 rounded-lg bg-primary-gold/10 text-xs text-txt-primary">{turn.question}</div>
+            <div className="text-[10px] text-primary-gold">{turn.mode}</div>
+            {turn.tools.length > 0 && (
+              <div className="flex flex-col gap-1">
+                {turn.tools.map((tool, j) => (
+                  <div key={j} className="flex items-center gap-1.5 text-[11px] text-txt-muted">
+                    {tool.done ? <Check size={10} className="text-functional-down" /> : <RefreshCw size={10} className="animate-spin text-primary-gold" />}
+                    {tool.label}
+                  </div>
+                ))}
+              </div>
+            )}
+            {turn.content && (
+              <div className="prose prose-invert prose-sm max-w-none">
+                <ReactMarkdown remarkPlugins={[remarkGfm]}>{turn.content}</ReactMarkdown>
+              </div>
+            )}
+            {!turn.done && !turn.content && <Loader2 size={14} className="animate-spin text-txt-muted" />}
+          </div>
+        ))}
+      </div>
+
+      <div className="flex items-center gap-2 px-4 py-3 border-t border-[#30363D]">
+        <input
+          value={input}
+          onChange={e => setInput(e.target.value)}
+          onKeyDown={e => { if (e.key === 'Enter' && !e.nativeEvent.isComposing) ask(input); }}
+          placeholder="例如：帮我看看最近军工板块"
+          disabled={running}
+          className="flex-1 px-3 py-2 rounded-lg bg-bg-base border border-[#30363D] text-xs text-txt-primary placeholder:text-txt-muted outline-none focus:border-primary-gold/50 transition-colors disabled:opacity-50"
+        />
+        <button
+          onClick={() => ask(input)}
+          disabled={running || !input.trim()}
+          className="p-2 rounded-lg bg-primary-gold/10 text-primary-gold hover:bg-primary-gold/20 transition-colors cursor-pointer disabled:opacity-40"
+        >
+          {running ? <Loader2 size={14} className="animate-spin" /> : <Send size={14} />}
+        </button>
+      </div>
+    </div>
+  );
+}
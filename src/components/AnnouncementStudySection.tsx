@@ -0,0 +1,91 @@
+import { useEffect, useState } from 'react';
+import { Play, Loader2, DownloadCloud } from 'lucide-react';
+import { safeInvoke as invoke } from '../hooks/useTauri';
+import { AnnouncementStudyResult } from '../types';
+import logger from '../utils/logger';
+import { Pct } from './ThemeStudySection';
+
+/** 公告回测：回购、业绩预增、减持公告发布后的平均异常收益，AI 分析候选股公告时会引用 */
+export default function AnnouncementStudySection() {
+  const [result, setResult] = useState<AnnouncementStudyResult | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  const [loading, setLoading] = useState(false);
+
+  const handleRun = async (backfill = false) => {
+    setLoading(true);
+    setError(null);
+    try {
+      setResult(await invoke<AnnouncementStudyResult>('run_announcement_study', { backfill }));
+    } catch (e) {
+      logger.error(`Announcement study failed: ${e}`);
+      setError(`${e}`);
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  useEffect(() => { handleRun(); }, []);
+
+  return (
+    <div className="px-4 py-3 space-y-3 border-b border-[#30363D]">
+      <div className="flex items-center gap-2">
+        <span className="text-xs font-medium text-txt-primary">公告事件</span>
+        <div className="flex-1" />
+        <button
+          onClick={() => handleRun(true)}
+          disabled={loading}
+          title="为本地已同步日线的股票拉取历史公告，耗时较长"
+          className="flex items-center gap-1 px-3 py-1.5 rounded-lg text-xs font-medium bg-bg-card text-txt-secondary border border-[#30363D] hover:border-[#484F58] hover:text-txt-primary transition-all cursor-pointer disabled:opacity-40"
+        >
+          <DownloadCloud size={12} />
+          回补公告
+        </button>
+        <button
+          onClick={() => handleRun()}
+          disabled={loading}
+          className="flex items-center gap-1 px-3 py-1.5 rounded-lg text-xs font-medium bg-primary-gold/10 text-primary-gold hover:bg-primary-gold/20 transition-colors cursor-pointer disabled:opacity-40"
+        >
+          {loading ? <Loader2 size={12} className="animate-spin" /> : <Play size={12} />}
+          重新计算
+        </button>
+      </div>
+      <p className="text-[10px] text-txt-muted">
+        异常收益 = 个股收益 − 沪深300同期收益；公告日为相对前一交易日，1/5/20 日自公告日收盘起算。只统计本地已同步日线的股票
+      </p>
+      {error && <p className="text-[11px] text-functional-up">{error}</p>}
+      {result && (
+        <p className="text-[11px] text-txt-muted">
+          {result.start} ~ {result.end}，归档公告 {result.announcements} 条
+        </p>
+      )}
+      {result && result.stats.length > 0 && (
+        <table className="w-full text-xs">
+          <thead>
+            <tr className="text-txt-muted text-[10px] text-right">
+              <th className="text-left font-normal pb-1">类别</th>
+              <th className="font-normal pb-1">事件</th>
+              <th className="font-normal pb-1">公告日</th>
+              <th className="font-normal pb-1">1日</th>
+              <th className="font-normal pb-1">5日</th>
+              <th className="font-normal pb-1">20日</th>
+              <th className="font-normal pb-1">胜率</th>
+            </tr>
+          </thead>
+          <tbody>
+            {result.stats.map(s => (
+              <tr key={s.category} className="text-right border-t border-[#30363D]/50 hover:bg-bg-elevated">
+                <td className="text-left py-1.5 text-txt-primary">{s.category}</td>
+                <td className="font-din text-txt-secondary">{s.events}</td>
+                <td className="font-din"><Pct value={s.avg_ar_0d} /></td>
+                <td className="font-din"><Pct value={s.avg_ar_1d} /></td>
+                <td className="font-din"><Pct value={s.avg_ar_5d} /></td>
+                <td className="font-din"><Pct value={s.avg_ar_20d} /></td>
+                <td className="font-din text-txt-secondary">{s.win_rate_5d == null ? '-' : `${s.win_rate_5d.toFixed(0)}%`}</td>
+              </tr>
+            ))}
+          </tbody>
+        </table>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,148 @@
+import { useEffect, useState } from 'react';
+import { Input, InputNumber, Switch, App } from 'antd';
+import { Save, KeyRound, Copy } from 'lucide-react';
+import { useSettingsStore } from '../stores/settingsStore';
+import { safeInvoke as invoke } from '../hooks/useTauri';
+import { ApiServerConfig, ApiServerStatus, AppSettings } from '../types';
+
+interface Props {
+  settings: AppSettings;
+}
+
+const DEFAULT_CONFIG: ApiServerConfig = { enabled: false, port: 18520, token: '', mcp_enabled: false };
+
+function randomToken() {
+  const bytes = new Uint8Array(16);
+  crypto.getRandomValues(bytes);
+  return Array.from(bytes, b => b.toString(16).padStart(2, '0')).join('');
+}
+
+/** 本机 HTTP API：开关、端口、访问令牌、MCP 服务与调用示例 */
+export default function ApiServerPanel({ settings }: Props) {
+  const { message } = App.useApp();
+  const { saveSettings } = useSettingsStore();
+  const [draft, setDraft] = useState<ApiServerConfig>(settings.api_server ?? DEFAULT_CONFIG);
+  const [status, setStatus] = useState<ApiServerStatus | null>(null);
+
+  const refreshStatus = () => invoke<ApiServerStatus>('get_api_server_status').then(setStatus).catch(() => {});
+  useEffect(() => {
+    refreshStatus();
+  }, []);
+
+  const dirty = JSON.stringify(draft) !== JSON.stringify(settings.api_server ?? DEFAULT_CONFIG);
+  const update = (patch: Partial<ApiServerConfig>) => setDraft(prev => ({ ...prev, ...patch }));
+
+  const handleSave = async () => {
+    await saveSettings({ ...settings, api_server: draft });
+    await refreshStatus();
+    message.success('本机 API 配置已保存');
+  };
+
+  const base = status?.address ?? `http://127.0.0.1:${draft.port}`;
+  const auth = draft.token ? ` -H "Authorization: Bearer ${draft.token}"` : '';
+  const example = [
+    `curl${auth} "${base}/api/quotes?codes=sh600519,sz000001"`,
+    `curl${auth} "${base}/api/watchlist?enriched=1"`,
+    `curl${auth} "${base}/api/screen?signal=MACD底背离&within_days=3"`,
+    `curl${auth} -X POST "${base}/api/diagnose?code=sh600519"`,
+  ].join('\n');
+  const mcpConfig = JSON.stringify(
+    {
+      mcpServers: {
+        'stock-helper': {
+          url: `${base}/mcp`,
+          ...(draft.token ? { headers: { Authorization: `Bearer ${draft.token}` } } : {}),
+        },
+      },
+    },
+    null,
+    2,
+  );
+
+  const buttonClass =
+    'flex items-center gap-1.5 px-3 py-1.5 rounded-lg text-sm text-txt-secondary hover:text-txt-primary hover:bg-bg-elevated transition-colors cursor-pointer disabled:opacity-50 disabled:cursor-not-allowed';
+  const labelClass = 'text-xs text-txt-muted w-24 shrink-0';
+
+  return (
+    <div className="p-4 rounded-lg border border-[#30363D] bg-bg-card space-y-4">
+      <div className="flex items-center justify-between">
+        <div>
+          <span className="text-sm text-txt-primary">启用本机 API</span>
+          <p className="text-xs text-txt-muted mt-1">
+            仅监听 127.0.0.1，供 Python / Excel 等脚本查询行情、自选股、形态选股与发起 AI 诊断
+          </p>
+        </div>
+        <Switch checked={draft.enabled} onChange={enabled => update({ enabled })} />
+      </div>
+
+      <div className="space-y-3">
+        <div className="flex items-center gap-3">
+          <span className={labelClass}>端口</span>
+          <InputNumber min={1024} max={65535} value={draft.port} onChange={v => v && update({ port: v })} />
+        </div>
+        <div className="flex items-center gap-3">
+          <span className={labelClass}>访问令牌</span>
+          <Input.Password
+            placeholder="留空则不校验"
+            value={draft.token}
+            onChange={e => update({ token: e.target.value })}
+          />
+          <button className={buttonClass} onClick={() => update({ token: randomToken() })}>
+            <KeyRound size={14} />
+            生成
+          </button>
+        </div>
+      </div>
+
+      <div className="flex items-center justify-between">
+        <div>
+          <span className="text-sm text-txt-primary">MCP 服务</span>
+          <p className="text-xs text-txt-muted mt-1">
+            在 /mcp 提供诊股与选股工具（行情、K线、指标、新闻、资金流向、条件选股），供支持 Streamable HTTP 的 AI 客户端调用
+          </p>
+        </div>
+        <Switch checked={draft.mcp_enabled} onChange={mcp_enabled => update({ mcp_enabled })} />
+      </div>
+
+      <div className="space-y-2">
+        <div className="flex items-center justify-between">
+          <span className="text-xs text-txt-muted">调用示例</span>
+          <button
+            className={buttonClass}
+            onClick={() => navigator.clipboard.writeText(example).then(() => message.success('已复制'))}
+          >
+            <Copy size={14} />
+            复制
+          </button>
+        </div>
+        <pre className="text-xs text-txt-secondary bg-bg-elevated rounded-lg p-3 overflow-x-auto whitespace-pre">{example}</pre>
+      </div>
+
+      {draft.mcp_enabled && (
+        <div className="space-y-2">
+          <div className="flex items-center justify-between">
+            <span className="text-xs text-txt-muted">MCP 客户端配置</span>
+            <button
+              className={buttonClass}
+              onClick={() => navigator.clipboard.writeText(mcpConfig).then(() => message.success('已复制'))}
+            >
+              <Copy size={14} />
+              复制
+            </button>
+          </div>
+          <pre className="text-xs text-txt-secondary bg-bg-elevated rounded-lg p-3 overflow-x-auto whitespace-pre">{mcpConfig}</pre>
+        </div>
+      )}
+
+      <div className="flex items-center justify-between pt-3 border-t border-[#30363D]">
+        <span className={`text-xs ${status?.error ? 'text-red-400' : 'text-txt-muted'}`}>
+          {status?.error ?? (status?.running ? `运行中：${status.address}` : '未运行')}
+        </span>
+        <button className={buttonClass} disabled={!dirty} onClick={handleSave}>
+          <Save size={14} />
+          保存
+        </button>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,158 @@
+import { useEffect, useState } from 'react';
+import { App } from 'antd';
+import { X, Zap, Play, Loader2, Plus } from 'lucide-react';
+import { safeInvoke as invoke, safeListen } from '../hooks/useTauri';
+import { useSettingsStore } from '../stores/settingsStore';
+import { useWatchlistStore } from '../stores/watchlistStore';
+import { AuctionScanResult } from '../types';
+import logger from '../utils/logger';
+
+interface Props {
+  onClose: () => void;
+}
+
+function formatAmount(val: number): string {
+  if (val >= 1e8) return `${(val / 1e8).toFixed(2)}亿`;
+  return `${(val / 1e4).toFixed(0)}万`;
+}
+
+/** 竞价异动：09:22 后自动扫描竞价跳空与竞价爆量，按强度排序并标注落入的策略区间 */
+export default function AuctionGapPanel({ onClose }: Props) {
+  const { message } = App.useApp();
+  const { settings, loadSettings } = useSettingsStore();
+  const { addStock } = useWatchlistStore();
+  const [result, setResult] = useState<AuctionScanResult | null>(null);
+  const [watchlistOnly, setWatchlistOnly] = useState(false);
+  const [loading, setLoading] = useState(false);
+
+  useEffect(() => {
+    if (!settings) loadSettings();
+    invoke<AuctionScanResult | null>('get_auction_gaps')
+      .then(r => setResult(r ?? null))
+      .catch(e => logger.error(`Failed to load auction gaps: ${e}`));
+  }, []);
+
+  useEffect(() => {
+    if (settings) setWatchlistOnly(settings.auction_scan_watchlist_only ?? false);
+  }, [settings?.auction_scan_watchlist_only]);
+
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    safeListen<AuctionScanResult>('auction-gaps', event => setResult(event.payload)).then(fn => { unlisten = fn; });
+    return () => unlisten?.();
+  }, []);
+
+  const handleScan = async () => {
+    setLoading(true);
+    try {
+      setResult(await invoke<AuctionScanResult>('scan_auction_gaps', { watchlistOnly }));
+    } catch (e) {
+      logger.error(`Auction gap scan failed: ${e}`);
+      message.error(`${e}`);
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const handleAddWatch = async (code: string, name: string) => {
+    try {
+      await addStock(code, name);
+      message.success(`已加入自选：${name}`);
+    } catch (e) {
+      message.error(`${e}`);
+    }
+  };
+
+  return (
+    <div className="fixed right-0 top-12 bottom-0 w-[480px] bg-bg-card border-l border-[#30363D] flex flex-col z-50 shadow-2xl shadow-black/50 animate-in slide-in-from-right">
+      <div className="flex items-center justify-between px-4 py-3 border-b border-[#30363D]">
+        <div className="flex items-center gap-2">
+          <Zap size={16} className="text-primary-gold" />
+          <span className="font-bold text-txt-primary text-sm">竞价异动</span>
+        </div>
+        <button onClick={onClose} className="p-1 rounded hover:bg-bg-elevated transition-colors cursor-pointer">
+          <X size={18} className="text-txt-secondary" />
+        </button>
+      </div>
+
+      <div className="px-4 py-3 border-b border-[#30363D] space-y-2">
+        <div className="flex items-center gap-2">
+          <button
+            onClick={handleScan}
+            disabled={loading}
+            className="flex items-center gap-1 px-3 py-1.5 rounded-lg text-xs font-medium bg-primary-gold/10 text-primary-gold hover:bg-primary-gold/20 transition-colors cursor-pointer disabled:opacity-40"
+          >
+            {loading ? <Loader2 size={12} className="animate-spin" /> : <Play size={12} />}
+            立即扫描
+          </button>
+          <label className="flex items-center gap-1.5 text-xs text-txt-muted cursor-pointer select-none">
+            <input
+              type="checkbox"
+              checked={watchlistOnly}
+              onChange={e => setWatchlistOnly(e.target.checked)}
+              className="accent-primary-gold w-3 h-3"
+            />
+            只扫自选股
+          </label>
+        </div>
+        <p className="text-[10px] text-txt-muted">交易日 09:22 后自动扫描一次：竞价跳空 ≥3% 或竞价量达 5 日均量 10% 视为异动，结果按设置推送</p>
+      </div>
+
+      <div className="flex-1 overflow-auto px-4 py-3">
+        {result && (
+          <p className="text-[11px] text-txt-muted mb-2">
+            {result.time} · {result.scope === 'watchlist' ? '自选股' : '全市场'} {result.scanned} 只，异动 {result.gaps.length} 只
+          </p>
+        )}
+        {result && result.gaps.length > 0 && (
+          <table className="w-full text-xs">
+            <thead>
+              <tr className="text-txt-muted text-[10px] text-right">
+                <th className="text-left font-normal pb-1">名称</th>
+                <th className="font-normal pb-1">竞价</th>
+                <th className="font-normal pb-1">量/5日均</th>
+                <th className="font-normal pb-1">竞价额</th>
+                <th className="font-normal pb-1">强度</th>
+                <th className="pb-1" />
+              </tr>
+            </thead>
+            <tbody>
+              {result.gaps.map(g => (
+                <tr key={g.code} className="text-right border-t border-[#30363D]/50 hover:bg-bg-elevated">
+                  <td className="text-left py-1.5">
+                    <div className="text-txt-primary">{g.name}</div>
+                    <div className="text-[10px] text-txt-muted font-mono">{g.code}</div>
+                    {g.labels.length > 0 && (
+                      <div className="text-[10px] text-functional-info whitespace-nowrap">{g.labels.join(' · ')}</div>
+                    )}
+                    {g.zones.length > 0 && (
+                      <div className="text-[10px] text-primary-gold/80 whitespace-nowrap">{g.zones.join(' · ')}</div>
+                    )}
+                  </td>
+                  <td className={`font-din ${g.gap_pct >= 0 ? 'text-functional-up' : 'text-functional-down'}`}>
+                    {g.gap_pct >= 0 ? '+' : ''}{g.gap_pct.toFixed(2)}%
+                  </td>
+                  <td className="font-din text-txt-secondary">{g.volume_pct > 0 ? `${g.volume_pct.toFixed(1)}%` : '-'}</td>
+                  <td className="font-din text-txt-secondary">{formatAmount(g.amount)}</td>
+                  <td className="font-din text-txt-primary">{g.score}</td>
+                  <td className="pl-2">
+                    <button
+                      onClick={() => handleAddWatch(g.code, g.name)}
+                      title="加入自选"
+                      className="p-1 rounded text-txt-muted hover:text-primary-gold cursor-pointer"
+                    >
+                      <Plus size={12} />
+                    </button>
+                  </td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+        )}
+        {!result && !loading && (
+          <p className="text-xs text-txt-muted text-center pt-6">今日尚未扫描，集合竞价期间可手动扫描</p>
+        )}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,77 @@
+import { useEffect, useState } from 'react';
+import { App } from 'antd';
+import { Headphones, Play, RefreshCw, Loader2 } from 'lucide-react';
+import { safeInvoke as invoke } from '../hooks/useTauri';
+import { AudioExport } from '../types';
+import logger from '../utils/logger';
+
+interface Props {
+  /** audio_exports 来源：briefing | analysis */
+  sourceKind: 'briefing' | 'analysis';
+  /** 已知来源 id 时先查询是否已有语音；分析记录在生成时才确定 id，可为空 */
+  sourceId?: string;
+  /** 生成语音并返回导出记录 */
+  onExport: () => Promise<AudioExport>;
+  label?: string;
+}
+
+/** 语音播报按钮：未生成时调用 TTS 生成 mp3，已生成时用系统播放器打开，可重新生成 */
+export default function AudioExportButton({ sourceKind, sourceId, onExport, label = '生成语音' }: Props) {
+  const { message } = App.useApp();
+  const [exported, setExported] = useState<AudioExport | null>(null);
+  const [loading, setLoading] = useState(false);
+
+  useEffect(() => {
+    setExported(null);
+    if (!sourceId) return;
+    invoke<AudioExport | null>('get_audio_export', { sourceKind, sourceId })
+      .then(result => setExported(result ?? null))
+      .catch(e => logger.error(`Failed to get audio export: ${e}`));
+  }, [sourceKind, sourceId]);
+
+  const handleExport = async () => {
+    setLoading(true);
+    try {
+      const result = await onExport();
+      setExported(result);
+      message.success('语音已生成');
+    } catch (e) {
+      logger.error(`Audio export failed: ${e}`);
+      message.error(`${e}`);
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const handlePlay = async () => {
+    if (!exported) return;
+    try {
+      await invoke('open_audio_export', { sourceKind: exported.source_kind, sourceId: exported.source_id });
+    } catch (e) {
+      message.error(`${e}`);
+    }
+  };
+
+  const buttonClass = 'flex items-center gap-1 px-3 py-1.5 rounded-lg text-xs font-medium bg-bg-card text-txt-secondary border border-[#30363D] hover:border-[#484F58] hover:text-txt-primary transition-all cursor-pointer disabled:opacity-40';
+
+  if (!exported) {
+    return (
+      <button onClick={handleExport} disabled={loading} className={buttonClass}>
+        {loading ? <Loader2 size={12} className="animate-spin" /> : <Headphones size={12} />}
+        {label}
+      </button>
+    );
+  }
+
+  return (
+    <div className="flex items-center gap-1">
+      <button onClick={handlePlay} title={exported.path} className={buttonClass}>
+        <Play size={12} />
+        播放语音
+      </button>
+      <button onClick={handleExport} disabled={loading} title="重新生成" className="p-1.5 rounded-lg text-txt-muted hover:text-txt-primary hover:bg-bg-elevated transition-colors cursor-pointer disabled:opacity-40">
+        <RefreshCw size={12} className={loading ? 'animate-spin' : ''} />
+      </button>
+    </div>
+  );
+}
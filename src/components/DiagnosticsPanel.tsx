@@ -0,0 +1,133 @@
+import { useState } from 'react';
+import { App } from 'antd';
+import { Stethoscope, Loader2, Copy, CheckCircle, XCircle, AlertTriangle } from 'lucide-react';
+import { safeInvoke as invoke } from '../hooks/useTauri';
+import { DiagnosticsReport } from '../types';
+
+function formatSize(bytes: number) {
+  if (bytes >= 1024 * 1024) return `${(bytes / 1024 / 1024).toFixed(1)} MB`;
+  return `${(bytes / 1024).toFixed(0)} KB`;
+}
+
+/** 诊断报告的纯文本版本，便于反馈问题时粘贴 */
+function reportText(report: DiagnosticsReport) {
+  const lines = [
+    `诊断时间: ${report.generated_at}`,
+    `版本: ${report.app_version} (${report.os})`,
+    `数据库: ${report.db_path} (${formatSize(report.db_size_bytes)})`,
+    '',
+    '[数据源]',
+    ...report.sources.map(s =>
+      s.reachable ? `${s.name}: 可达 ${s.latency_ms}ms HTTP ${s.status}` : `${s.name}: 不可达 ${s.error ?? ''}`,
+    ),
+    ...(report.schema_drifts?.length
+      ? ['', '[数据源格式变更]', ...report.schema_drifts.map(d => `${d.source}: ${d.detail}（${d.count} 次，最近 ${d.last_at}）`)]
+      : []),
+    '',
+    '[缓存]',
+    ...report.caches.map(c => `${c.label}(${c.table}): ${c.rows} 行，最新 ${c.latest ?? '-'}`),
+    '',
+    '[后台任务]',
+    ...report.jobs.map(j => {
+      const last = j.recent_runs[0];
+      const lastRun = last ? `${last.started_at} ${last.success ? '成功' : '失败'} ${last.message}` : '尚未运行';
+      return `${j.name}: ${j.enabled ? '启用' : '停用'}${j.running ? ' 运行中' : ''}，最近成功 ${report.last_successes[j.id] ?? '-'}，最近运行 ${lastRun}`;
+    }),
+    '',
+    `进行中任务: ${report.active_tasks}，AI 选股${report.ai_picking ? '进行中' : '空闲'}`,
+  ];
+  return lines.join('\n');
+}
+
+/** 诊断报告：数据源连通性、数据库与缓存、后台任务状态，可复制后附在问题反馈中 */
+export default function DiagnosticsPanel() {
+  const { message } = App.useApp();
+  const [report, setReport] = useState<DiagnosticsReport | null>(null);
+  const [loading, setLoading] = useState(false);
+
+  const runDiagnostics = async () => {
+    setLoading(true);
+    try {
+      setReport(await invoke<DiagnosticsReport>('get_diagnostics'));
+    } catch (e) {
+      message.error(`诊断失败: ${e}`);
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const handleCopy = () => {
+    if (!report) return;
+    navigator.clipboard.writeText(reportText(report));
+    message.success('诊断报告已复制');
+  };
+
+  return (
+    <div className="p-4 rounded-lg border border-[#30363D] bg-bg-card">
+      <div className="flex items-center justify-between">
+        <div>
+          <span className="text-sm text-txt-primary">诊断报告</span>
+          <p className="text-xs text-txt-muted mt-1">检测各数据源连通性、本地缓存与后台任务状态，遇到“数据不更新”时可复制附在反馈中</p>
+        </div>
+        <div className="flex items-center gap-2 shrink-0">
+          {report && (
+            <button
+              onClick={handleCopy}
+              className="flex items-center gap-2 px-3 py-2 rounded-lg text-txt-secondary hover:text-txt-primary hover:bg-bg-elevated transition-colors cursor-pointer text-sm"
+            >
+              <Copy size={14} />
+              复制
+            </button>
+          )}
+          <button
+            onClick={runDiagnostics}
+            disabled={loading}
+            className="flex items-center gap-2 px-4 py-2 rounded-lg bg-functional-info/20 text-functional-info hover:bg-functional-info/30 transition-colors cursor-pointer disabled:opacity-50 disabled:cursor-not-allowed text-sm"
+          >
+            {loading ? <Loader2 size={14} className="animate-spin" /> : <Stethoscope size={14} />}
+            {loading ? '诊断中...' : '运行诊断'}
+          </button>
+        </div>
+      </div>
+
+      {report && (
+        <div className="mt-4 space-y-3 text-xs">
+          <div className="grid grid-cols-2 gap-x-6 gap-y-1.5">
+            {report.sources.map(s => (
+              <div key={s.name} className="flex items-center gap-1.5 min-w-0" title={s.error ?? s.url}>
+                {s.reachable ? <CheckCircle size={12} className="text-green-400 shrink-0" /> : <XCircle size={12} className="text-red-400 shrink-0" />}
+                <span className="text-txt-primary">{s.name}</span>
+                <span className="text-txt-muted truncate">{s.reachable ? `${s.latency_ms}ms` : '不可达'}</span>
+              </div>
+            ))}
+          </div>
+          {report.schema_drifts?.length > 0 && (
+            <div className="p-2 rounded-lg bg-functional-warn/10 space-y-1">
+              <div className="flex items-center gap-1.5 text-functional-warn">
+                <AlertTriangle size={12} />
+                数据源格式变更
+              </div>
+              {report.schema_drifts.map(d => (
+                <div key={d.source} className="text-txt-secondary">
+                  {d.source}：{d.detail}
+                  <span className="text-txt-muted">（{d.count} 次，最近 {d.last_at}）</span>
+                </div>
+              ))}
+            </div>
+          )}
+          <div className="text-txt-muted">
+            数据库 {formatSize(report.db_size_bytes)} · <span className="select-all">{report.db_path}</span>
+          </div>
+          <div className="grid grid-cols-2 gap-x-6 gap-y-1 text-txt-muted">
+            {report.caches.map(c => (
+              <div key={c.table} className="flex justify-between gap-2">
+                <span>{c.label}</span>
+                <span>{c.rows} 行 · {c.latest ?? '-'}</span>
+              </div>
+            ))}
+          </div>
+        </div>
+      )}
+    </div>
+  );
+}
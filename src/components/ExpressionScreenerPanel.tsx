@@ -0,0 +1,267 @@
+import { useEffect, useState } from 'react';
+import { App } from 'antd';
+import { X, Filter, Play, Save, Trash2, Loader2, Plus, FlaskConical } from 'lucide-react';
+import { safeInvoke as invoke } from '../hooks/useTauri';
+import { useSettingsStore } from '../stores/settingsStore';
+import { useWatchlistStore } from '../stores/watchlistStore';
+import { ExpressionScreenResult, ScreenerPreset } from '../types';
+import logger from '../utils/logger';
+import ThemeStudySection from './ThemeStudySection';
+import AnnouncementStudySection from './AnnouncementStudySection';
+
+interface Props {
+  onClose: () => void;
+}
+
+const EXAMPLE = "pe_ttm < 30 && roe > 10 && turnover_rate between 3 and 15 && !name.contains('ST')";
+
+const FIELD_HELP = [
+  ['price / change_pct', '最新价 / 涨跌幅%'],
+  ['pe_ttm / pb', '市盈率TTM / 市净率'],
+  ['roe / gross_margin', 'ROE% / 毛利率%'],
+  ['revenue_yoy / profit_yoy', '营收 / 净利润同比%'],
+  ['turnover_rate / volume_ratio', '换手率% / 量比'],
+  ['pct_5d / pct_20d / pct_60d', '区间涨幅%'],
+  ['total_market_cap / float_market_cap', '总 / 流通市值（元）'],
+  ['main_net_inflow / amount', '主力净流入 / 成交额（元）'],
+  ['dividend_yield / fcf_yield / debt_ratio', '股息率% / 现金流收益率% / 负债率%'],
+  ['code / name / list_date', "文本：== != 及 .contains('x') .starts_with .ends_with"],
+];
+
+function formatCap(val: number): string {
+  if (val >= 1e8) return `${(val / 1e8).toFixed(0)}亿`;
+  return `${(val / 1e4).toFixed(0)}万`;
+}
+
+/** 条件选股：编写筛选表达式扫描全市场，常用表达式保存为方案 */
+export default function ExpressionScreenerPanel({ onClose }: Props) {
+  const { message } = App.useApp();
+  const { settings, loadSettings, saveSettings } = useSettingsStore();
+  const { addStock } = useWatchlistStore();
+  const [expression, setExpression] = useState(EXAMPLE);
+  const [presetName, setPresetName] = useState('');
+  const [result, setResult] = useState<ExpressionScreenResult | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  const [loading, setLoading] = useState(false);
+  const [showStudy, setShowStudy] = useState(false);
+
+  useEffect(() => {
+    if (!settings) loadSettings();
+  }, []);
+
+  const presets = settings?.screener_presets ?? [];
+
+  const handleScreen = async (expr = expression) => {
+    if (!expr.trim()) return;
+    setLoading(true);
+    setError(null);
+    try {
+      setResult(await invoke<ExpressionScreenResult>('screen_by_expression', { expression: expr, limit: 200 }));
+    } catch (e) {
+      logger.error(`Expression screen failed: ${e}`);
+      setError(`${e}`);
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const handleSavePreset = async () => {
+    if (!settings || !presetName.trim()) return;
+    try {
+      await invoke('validate_filter_expression', { expression });
+    } catch (e) {
+      setError(`${e}`);
+      return;
+    }
+    const now = new Date().toLocaleString('sv-SE');
+    const existing = presets.find(p => p.name === presetName.trim());
+    const preset: ScreenerPreset = {
+      id: existing?.id ?? crypto.randomUUID(),
+      name: presetName.trim(),
+      expression: expression.trim(),
+      updated_at: now,
+    };
+    const next = existing ? presets.map(p => (p.id === existing.id ? preset : p)) : [...presets, preset];
+    await saveSettings({ ...settings, screener_presets: next });
+    setPresetName('');
+    message.success(`方案「${preset.name}」已保存`);
+  };
+
+  const handleDeletePreset = async (id: string) => {
+    if (!settings) return;
+    await saveSettings({ ...settings, screener_presets: presets.filter(p => p.id !== id) });
+  };
+
+  const handleApplyPreset = (preset: ScreenerPreset) => {
+    setExpression(preset.expression);
+    setPresetName(preset.name);
+    handleScreen(preset.expression);
+  };
+
+  const handleAddWatch = async (code: string, name: string) => {
+    try {
+      await addStock(code, name);
+      message.success(`已加入自选：${name}`);
+    } catch (e) {
+      message.error(`${e}`);
+    }
+  };
+
+  return (
+    <div className="fixed right-0 top-12 bottom-0 w-[480px] bg-bg-card border-l border-[#30363D] flex flex-col z-50 shadow-2xl shadow-black/50 animate-in slide-in-from-right">
+      <div className="flex items-center justify-between px-4 py-3 border-b border-[#30363D]">
+        <div className="flex items-center gap-2">
+          <Filter size={16} className="text-primary-gold" />
+          <span className="font-bold text-txt-primary text-sm">条件选股</span>
+        </div>
+        <div className="flex-1" />
+        <button
+          onClick={() => setShowStudy(v => !v)}
+          title="回测公告与题材快讯的预测力"
+          className={`flex items-center gap-1 px-2 py-1 mr-1 rounded text-[11px] transition-colors cursor-pointer ${
+            showStudy ? 'bg-primary-gold/20 text-primary-gold' : 'text-txt-secondary hover:text-txt-primary hover:bg-bg-elevated'
+          }`}
+        >
+          <FlaskConical size={12} />
+          事件回测
+        </button>
+        <button onClick={onClose} className="p-1 rounded hover:bg-bg-elevated transition-colors cursor-pointer">
+          <X size={18} className="text-txt-secondary" />
+        </button>
+      </div>
+
+      {showStudy ? (
+        <div className="flex-1 overflow-auto">
+          <AnnouncementStudySection />
+          <ThemeStudySection />
+        </div>
+      ) : (
+        <>
+          <div className="px-4 py-3 border-b border-[#30363D] space-y-2">
+            {presets.length > 0 && (
+              <div className="flex flex-wrap gap-1.5">
+                {presets.map(p => (
+                  <div
+                    key={p.id}
+                    title={p.expression}
+                    className="flex items-center gap-1 pl-2 pr-1 py-0.5 rounded border border-[#30363D] text-[11px] text-txt-secondary hover:text-txt-primary hover:border-[#484F58] transition-colors"
+                  >
+                    <span onClick={() => handleApplyPreset(p)} className="cursor-pointer">{p.name}</span>
+                    <button onClick={() => handleDeletePreset(p.id)} className="p-0.5 rounded text-txt-muted hover:text-functional-up cursor-pointer">
+                      <Trash2 size={10} />
+                    </button>
+                  </div>
+                ))}
+              </div>
+            )}
+            <textarea
+              value={expression}
+              onChange={e => { setExpression(e.target.value); setError(null); }}
+              onKeyDown={e => { if (e.key === 'Enter' && (e.metaKey || e.ctrlKey)) handleScreen(); }}
+              rows={3}
+              spellCheck={false}
+              placeholder={EXAMPLE}
+              className="w-full px-3 py-2 rounded-lg bg-bg-base border border-[#30363D] text-xs font-mono text-txt-primary placeholder:text-txt-muted outline-none focus:border-primary-gold/50 resize-none"
+            />
+            {error && <p className="text-[11px] text-functional-up">{error}</p>}
+            <div className="flex items-center gap-2">
+              <button
+                onClick={() => handleScreen()}
+                disabled={loading || !expression.trim()}
+                className="flex items-center gap-1 px-3 py-1.5 rounded-lg text-xs font-medium bg-primary-gold/10 text-primary-gold hover:bg-primary-gold/20 transition-colors cursor-pointer disabled:opacity-40"
+              >
+                {loading ? <Loader2 size={12} className="animate-spin" /> : <Play size={12} />}
+                扫描全市场
+              </button>
+              <input
+                value={presetName}
+                onChange={e => setPresetName(e.target.value)}
+                placeholder="方案名称"
+                className="flex-1 px-3 py-1.5 rounded-lg bg-bg-base border border-[#30363D] text-xs text-txt-primary placeholder:text-txt-muted outline-none focus:border-primary-gold/50"
+              />
+              <button
+                onClick={handleSavePreset}
+                disabled={!presetName.trim() || !expression.trim()}
+                className="flex items-center gap-1 px-3 py-1.5 rounded-lg text-xs font-medium bg-bg-card text-txt-secondary border border-[#30363D] hover:border-[#484F58] hover:text-txt-primary transition-all cursor-pointer disabled:opacity-40"
+              >
+                <Save size={12} />
+                保存方案
+              </button>
+            </div>
+            <details className="text-[10px] text-txt-muted">
+              <summary className="cursor-pointer hover:text-txt-secondary">可用字段与语法</summary>
+              <div className="mt-1.5 space-y-0.5">
+                {FIELD_HELP.map(([fields, desc]) => (
+                  <div key={fields} className="flex gap-2">
+                    <span className="font-mono text-txt-secondary w-56 shrink-0">{fields}</span>
+                    <span>{desc}</span>
+                  </div>
+                ))}
+                <p className="pt-1">运算：+ - * / 比较 &lt; &lt;= &gt; &gt;= == !=，x between a and b，逻辑 &amp;&amp; || ! 或 and or not</p>
+                <p>使用股息率、现金流收益率、负债率、净利润同比时需额外拉取财务数据，扫描会慢一些</p>
+              </div>
+            </details>
+          </div>
+
+          <div className="flex-1 overflow-auto px-4 py-3">
+            {result && (
+              <p className="text-[11px] text-txt-muted mb-2">
+                扫描 {result.scanned} 只，命中 {result.matched} 只{result.matched > result.stocks.length ? `，按涨幅显示前 ${result.stocks.length} 只` : ''}
+              </p>
+            )}
+            {result && result.stocks.length > 0 && (
+              <table className="w-full text-xs">
+                <thead>
+                  <tr className="text-txt-muted text-[10px] text-right">
+                    <th className="text-left font-normal pb-1">名称</th>
+                    <th className="font-normal pb-1">现价</th>
+                    <th className="font-normal pb-1">涨跌幅</th>
+                    <th className="font-normal pb-1">PE</th>
+                    <th className="font-normal pb-1">ROE</th>
+                    <th className="font-normal pb-1">市值</th>
+                    <th className="pb-1" />
+                  </tr>
+                </thead>
+                <tbody>
+                  {result.stocks.map(s => (
+                    <tr key={s.code} className="text-right border-t border-[#30363D]/50 hover:bg-bg-elevated">
+                      <td className="text-left py-1.5">
+                        <div className="text-txt-primary">{s.name}</div>
+                        <div className="text-[10px] text-txt-muted font-mono">{s.code}</div>
+                        {result.themes[s.code]?.length > 0 && (
+                          <div className="text-[10px] text-functional-info whitespace-nowrap">{result.themes[s.code].join(' · ')}</div>
+                        )}
+                        {result.explanations[s.code] && (
+                          <div className="text-[10px] text-primary-gold/80 whitespace-nowrap">{result.explanations[s.code]}</div>
+                        )}
+                      </td>
+                      <td className="font-din text-txt-primary">{s.price.toFixed(2)}</td>
+                      <td className={`font-din ${s.change_pct >= 0 ? 'text-functional-up' : 'text-functional-down'}`}>
+                        {s.change_pct >= 0 ? '+' : ''}{s.change_pct.toFixed(2)}%
+                      </td>
+                      <td className="font-din text-txt-secondary">{s.pe_ttm.toFixed(1)}</td>
+                      <td className="font-din text-txt-secondary">{s.roe.toFixed(1)}</td>
+                      <td className="font-din text-txt-secondary">{formatCap(s.total_market_cap)}</td>
+                      <td className="pl-2">
+                        <button
+                          onClick={() => handleAddWatch(s.code, s.name)}
+                          title="加入自选"
+                          className="p-1 rounded text-txt-muted hover:text-primary-gold cursor-pointer"
+                        >
+                          <Plus size={12} />
+                        </button>
+                      </td>
+                    </tr>
+                  ))}
+                </tbody>
+              </table>
+            )}
+            {!result && !loading && (
+              <p className="text-xs text-txt-muted text-center pt-6">编写条件后扫描全市场，Ctrl/⌘ + Enter 快速执行</p>
+            )}
+          </div>
+        </>
+      )}
+    </div>
+  );
+}
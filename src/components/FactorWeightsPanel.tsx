@@ -0,0 +1,112 @@
+import { useState } from 'react';
+import { InputNumber, Select, App } from 'antd';
+import { Save, Plus, Trash2 } from 'lucide-react';
+import { useSettingsStore } from '../stores/settingsStore';
+import { AppSettings, FactorWeights, RegimeFactorWeights } from '../types';
+
+interface Props {
+  settings: AppSettings;
+}
+
+const FACTOR_FIELDS: { key: keyof FactorWeights; label: string }[] = [
+  { key: 'quality', label: '质量' },
+  { key: 'growth', label: '成长' },
+  { key: 'value', label: '估值' },
+  { key: 'momentum', label: '动量' },
+  { key: 'flow', label: '资金' },
+];
+
+const TREND_OPTIONS = [
+  { value: '', label: '任意趋势' },
+  { value: 'up', label: '上升趋势' },
+  { value: 'down', label: '下跌趋势' },
+  { value: 'range', label: '震荡市' },
+];
+
+const VOLATILITY_OPTIONS = [
+  { value: '', label: '任意波动' },
+  { value: 'high', label: '高波动' },
+  { value: 'normal', label: '波动正常' },
+  { value: 'low', label: '低波动' },
+];
+
+const EQUAL_WEIGHTS: FactorWeights = { quality: 1, growth: 1, value: 1, momentum: 1, flow: 1 };
+
+/** 因子权重：按市场状态（趋势、波动）切换因子评分各分项的权重 */
+export default function FactorWeightsPanel({ settings }: Props) {
+  const { message } = App.useApp();
+  const { saveSettings } = useSettingsStore();
+  const [rows, setRows] = useState<RegimeFactorWeights[]>(settings.regime_factor_weights ?? []);
+
+  const dirty = JSON.stringify(rows) !== JSON.stringify(settings.regime_factor_weights ?? []);
+  const update = (index: number, patch: Partial<RegimeFactorWeights>) =>
+    setRows(prev => prev.map((r, i) => (i === index ? { ...r, ...patch } : r)));
+
+  const handleSave = async () => {
+    await saveSettings({ ...settings, regime_factor_weights: rows });
+    message.success('因子权重已保存');
+  };
+
+  const buttonClass =
+    'flex items-center gap-1.5 px-3 py-1.5 rounded-lg text-sm text-txt-secondary hover:text-txt-primary hover:bg-bg-elevated transition-colors cursor-pointer disabled:opacity-50 disabled:cursor-not-allowed';
+
+  return (
+    <div className="p-4 rounded-lg border border-[#30363D] bg-bg-card space-y-4">
+      <div className="flex items-center justify-between">
+        <p className="text-xs text-txt-muted">
+          收盘后计算因子评分时按当日市场状态取第一条匹配的权重，综合分为各分项的加权均值；无匹配时各分项等权
+        </p>
+        <button className={buttonClass} onClick={() => setRows(prev => [...prev, { trend: null, volatility: null, weights: EQUAL_WEIGHTS }])}>
+          <Plus size={14} />
+          添加
+        </button>
+      </div>
+
+      {rows.map((row, index) => (
+        <div key={index} className="p-3 rounded-lg bg-bg-elevated space-y-2">
+          <div className="flex items-center gap-2">
+            <Select
+              style={{ width: 120 }}
+              value={row.trend ?? ''}
+              options={TREND_OPTIONS}
+              onChange={v => update(index, { trend: (v || null) as RegimeFactorWeights['trend'] })}
+            />
+            <Select
+              style={{ width: 120 }}
+              value={row.volatility ?? ''}
+              options={VOLATILITY_OPTIONS}
+              onChange={v => update(index, { volatility: (v || null) as RegimeFactorWeights['volatility'] })}
+            />
+            <span className="flex-1" />
+            <button onClick={() => setRows(prev => prev.filter((_, i) => i !== index))} className="p-1 rounded text-txt-muted hover:text-red-400 cursor-pointer">
+              <Trash2 size={14} />
+            </button>
+          </div>
+          <div className="flex items-center gap-3 flex-wrap">
+            {FACTOR_FIELDS.map(({ key, label }) => (
+              <div key={key} className="flex items-center gap-1.5">
+                <span className="text-xs text-txt-muted">{label}</span>
+                <InputNumber
+                  size="small"
+                  min={0}
+                  max={10}
+                  step={0.1}
+                  value={row.weights[key]}
+                  onChange={v => v !== null && update(index, { weights: { ...row.weights, [key]: v } })}
+                  style={{ width: 72 }}
+                />
+              </div>
+            ))}
+          </div>
+        </div>
+      ))}
+
+      <div className="flex justify-end">
+        <button className={buttonClass} disabled={!dirty} onClick={handleSave}>
+          <Save size={14} />
+          保存
+        </button>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,146 @@
+import { useEffect, useState } from 'react';
+import { App } from 'antd';
+import { X, Radar, Play, Square, Loader2 } from 'lucide-react';
+import { safeInvoke as invoke, safeListen } from '../hooks/useTauri';
+import { useSettingsStore } from '../stores/settingsStore';
+import { FocusMonitorStatus, TickPrint } from '../types';
+import logger from '../utils/logger';
+
+interface Props {
+  code: string;
+  name: string;
+  onClose: () => void;
+}
+
+const SIDE_LABEL: Record<TickPrint['side'], string> = { buy: '主买', sell: '主卖', neutral: '中性' };
+const SIDE_COLOR: Record<TickPrint['side'], string> = {
+  buy: 'text-functional-up',
+  sell: 'text-functional-down',
+  neutral: 'text-txt-secondary',
+};
+
+function formatAmount(val: number): string {
+  if (val >= 1e8) return `${(val / 1e8).toFixed(2)}亿`;
+  return `${(val / 1e4).toFixed(0)}万`;
+}
+
+/** 焦点盯盘：盘中每 3 秒轮询该股逐笔成交，滚动显示超过阈值的大单 */
+export default function FocusTapePanel({ code, name, onClose }: Props) {
+  const { message } = App.useApp();
+  const { settings, loadSettings } = useSettingsStore();
+  const [status, setStatus] = useState<FocusMonitorStatus | null>(null);
+  const [prints, setPrints] = useState<TickPrint[]>([]);
+  const [busy, setBusy] = useState(false);
+
+  useEffect(() => {
+    if (!settings) loadSettings();
+    invoke<FocusMonitorStatus>('get_focus_monitor_status')
+      .then(s => {
+        setStatus(s);
+        if (s?.code === code) setPrints([...s.prints].reverse());
+      })
+      .catch(e => logger.error(`Failed to load focus monitor status: ${e}`));
+  }, [code]);
+
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    safeListen<TickPrint>('focus-large-order', event => {
+      if (event.payload.code === code) setPrints(prev => [event.payload, ...prev].slice(0, 200));
+    }).then(fn => { unlisten = fn; });
+    return () => unlisten?.();
+  }, [code]);
+
+  const watching = status?.running && status.code === code;
+  const threshold = settings?.large_order_threshold ?? 1000000;
+  const buyTotal = prints.filter(p => p.side === 'buy').reduce((sum, p) => sum + p.amount, 0);
+  const sellTotal = prints.filter(p => p.side === 'sell').reduce((sum, p) => sum + p.amount, 0);
+
+  const handleToggle = async () => {
+    setBusy(true);
+    try {
+      if (watching) {
+        setStatus(await invoke<FocusMonitorStatus>('stop_focus_monitor'));
+      } else {
+        setPrints([]);
+        setStatus(await invoke<FocusMonitorStatus>('start_focus_monitor', { code }));
+      }
+    } catch (e) {
+      logger.error(`Focus monitor toggle failed: ${e}`);
+      message.error(`${e}`);
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  return (
+    <div className="fixed right-0 top-12 bottom-0 w-[480px] bg-bg-card border-l border-[#30363D] flex flex-col z-50 shadow-2xl shadow-black/50 animate-in slide-in-from-right">
+      <div className="flex items-center justify-between px-4 py-3 border-b border-[#30363D]">
+        <div className="flex items-center gap-2">
+          <Radar size={16} className="text-primary-gold" />
+          <span className="font-bold text-txt-primary text-sm">逐笔大单</span>
+          <span className="text-xs text-txt-muted">{name} {code}</span>
+        </div>
+        <button onClick={onClose} className="p-1 rounded hover:bg-bg-elevated transition-colors cursor-pointer">
+          <X size={18} className="text-txt-secondary" />
+        </button>
+      </div>
+
+      <div className="px-4 py-3 border-b border-[#30363D] space-y-2">
+        <div className="flex items-center gap-2">
+          <button
+            onClick={handleToggle}
+            disabled={busy}
+            className={`flex items-center gap-1 px-3 py-1.5 rounded-lg text-xs font-medium transition-colors cursor-pointer disabled:opacity-40 ${
+              watching ? 'bg-functional-up/10 text-functional-up hover:bg-functional-up/20' : 'bg-primary-gold/10 text-primary-gold hover:bg-primary-gold/20'
+            }`}
+          >
+            {busy ? <Loader2 size={12} className="animate-spin" /> : watching ? <Square size={12} /> : <Play size={12} />}
+            {watching ? '停止盯盘' : '开始盯盘'}
+          </button>
+          <span className="text-[11px] text-txt-muted">单笔 ≥ {formatAmount(threshold)}</span>
+          <div className="flex-1" />
+          {prints.length > 0 && (
+            <span className="text-[11px] font-din">
+              <span className="text-functional-up">买 {formatAmount(buyTotal)}</span>
+              <span className="text-txt-muted mx-1">/</span>
+              <span className="text-functional-down">卖 {formatAmount(sellTotal)}</span>
+            </span>
+          )}
+        </div>
+        {status?.running && status.code !== code && (
+          <p className="text-[10px] text-primary-gold/80">正在盯 {status.code}，开始后将切换到本股</p>
+        )}
+        <p className="text-[10px] text-txt-muted">同一时间只盯一只股票，仅交易时段轮询；阈值可在设置中调整</p>
+      </div>
+
+      <div className="flex-1 overflow-auto px-4 py-3">
+        {prints.length > 0 ? (
+          <table className="w-full text-xs">
+            <thead>
+              <tr className="text-txt-muted text-[10px] text-right">
+                <th className="text-left font-normal pb-1">时间</th>
+                <th className="font-normal pb-1">价格</th>
+                <th className="font-normal pb-1">手数</th>
+                <th className="font-normal pb-1">金额</th>
+                <th className="font-normal pb-1">方向</th>
+              </tr>
+            </thead>
+            <tbody>
+              {prints.map((p, i) => (
+                <tr key={`${p.time}-${p.price}-${p.volume}-${i}`} className="text-right border-t border-[#30363D]/50">
+                  <td className="text-left py-1 font-mono text-txt-secondary">{p.time}</td>
+                  <td className="font-din text-txt-primary">{p.price.toFixed(2)}</td>
+                  <td className="font-din text-txt-secondary">{p.volume.toFixed(0)}</td>
+                  <td className={`font-din ${SIDE_COLOR[p.side]}`}>{formatAmount(p.amount)}</td>
+                  <td className={SIDE_COLOR[p.side]}>{SIDE_LABEL[p.side]}</td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+        ) : (
+          <p className="text-xs text-txt-muted text-center pt-6">{watching ? '等待大单成交…' : '开始盯盘后，盘中大单会实时出现在这里'}</p>
+        )}
+      </div>
+    </div>
+  );
+}
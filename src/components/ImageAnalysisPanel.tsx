@@ -0,0 +1,159 @@
+import { useEffect, useState } from 'react';
+import ReactMarkdown from 'react-markdown';
+import remarkGfm from 'remark-gfm';
+import { Select } from 'antd';
+import { X, ImagePlus, Loader2, ScanEye } from 'lucide-react';
+import { safeInvoke as invoke, safeListen } from '../hooks/useTauri';
+import { useSettingsStore } from '../stores/settingsStore';
+import { AIAnalysisResult, AIStreamEvent } from '../types';
+import logger from '../utils/logger';
+
+interface Props {
+  code: string;
+  name: string;
+  onClose: () => void;
+}
+
+/** 图片大小上限，与后端一致 */
+const MAX_IMAGE_MB = 8;
+
+function readAsDataUrl(file: File): Promise<string> {
+  return new Promise((resolve, reject) => {
+    const reader = new FileReader();
+    reader.onload = () => resolve(reader.result as string);
+    reader.onerror = () => reject(reader.error);
+    reader.readAsDataURL(file);
+  });
+}
+
+/** 识图分析：粘贴或拖入其他软件的图表截图，由视觉模型结合该股真实数据交叉核对后解读 */
+export default function ImageAnalysisPanel({ code, name, onClose }: Props) {
+  const { settings } = useSettingsStore();
+  const configs = (settings?.ai_configs ?? []).filter(c => c.enabled);
+  const [configId, setConfigId] = useState<string | null>(settings?.active_ai_config_id ?? null);
+  const [image, setImage] = useState<string | null>(null);
+  const [question, setQuestion] = useState('');
+  const [content, setContent] = useState('');
+  const [running, setRunning] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const loadFile = async (file: File | null | undefined) => {
+    if (!file || !file.type.startsWith('image/')) return;
+    if (file.size > MAX_IMAGE_MB * 1024 * 1024) {
+      setError(`图片过大，请裁剪到 ${MAX_IMAGE_MB}MB 以内`);
+      return;
+    }
+    setError(null);
+    setImage(await readAsDataUrl(file));
+  };
+
+  useEffect(() => {
+    const onPaste = (e: ClipboardEvent) => {
+      const item = Array.from(e.clipboardData?.items ?? []).find(i => i.type.startsWith('image/'));
+      if (item) loadFile(item.getAsFile());
+    };
+    window.addEventListener('paste', onPaste);
+    return () => window.removeEventListener('paste', onPaste);
+  }, []);
+
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    safeListen<AIStreamEvent>('ai-image-analysis', event => {
+      const { event_type, content: chunk } = event.payload;
+      if (event_type === 'content' && chunk) setContent(c => c + chunk);
+    }).then(fn => { unlisten = fn; });
+    return () => unlisten?.();
+  }, []);
+
+  const handleAnalyze = async () => {
+    if (!image) return;
+    setRunning(true);
+    setContent('');
+    setError(null);
+    try {
+      const result = await invoke<AIAnalysisResult>('ai_analyze_image', {
+        image,
+        code: code || null,
+        question: question.trim() || null,
+        configId,
+      });
+      if (result?.content) setContent(result.content);
+    } catch (e) {
+      logger.error(`Image analysis failed: ${e}`);
+      setError(`${e}`);
+    } finally {
+      setRunning(false);
+    }
+  };
+
+  return (
+    <div className="fixed right-0 top-12 bottom-0 w-[480px] bg-bg-card border-l border-[#30363D] flex flex-col z-50 shadow-2xl shadow-black/50 animate-in slide-in-from-right">
+      <div className="flex items-center justify-between px-4 py-3 border-b border-[#30363D]">
+        <div className="flex items-center gap-2">
+          <ScanEye size={16} className="text-primary-gold" />
+          <span className="font-bold text-txt-primary text-sm">识图分析</span>
+          {code && <span className="text-xs text-txt-muted">{name} {code}</span>}
+        </div>
+        <button onClick={onClose} className="p-1 rounded hover:bg-bg-elevated transition-colors cursor-pointer">
+          <X size={18} className="text-txt-secondary" />
+        </button>
+      </div>
+
+      <div className="px-4 py-3 border-b border-[#30363D] space-y-2">
+        <label
+          onDragOver={e => e.preventDefault()}
+          onDrop={e => { e.preventDefault(); loadFile(e.dataTransfer.files[0]); }}
+          className="flex items-center justify-center h-40 rounded-lg border border-dashed border-[#30363D] hover:border-[#484F58] bg-bg-base cursor-pointer overflow-hidden"
+        >
+          {image ? (
+            <img src={image} alt="chart" className="max-h-full max-w-full object-contain" />
+          ) : (
+            <div className="flex flex-col items-center gap-1 text-txt-muted">
+              <ImagePlus size={20} />
+              <span className="text-xs">Ctrl+V 粘贴截图，或拖入 / 点击选择图片</span>
+            </div>
+          )}
+          <input type="file" accept="image/*" className="hidden" onChange={e => loadFile(e.target.files?.[0])} />
+        </label>
+        <input
+          value={question}
+          onChange={e => setQuestion(e.target.value)}
+          placeholder="想问什么？例如：这个形态是不是头肩底"
+          className="w-full px-3 py-1.5 rounded-lg bg-bg-base border border-[#30363D] text-xs text-txt-primary placeholder:text-txt-muted outline-none focus:border-primary-gold/50"
+        />
+        <div className="flex items-center gap-2">
+          <Select
+            className="flex-1"
+            size="small"
+            value={configId}
+            onChange={setConfigId}
+            placeholder="选择支持视觉的模型"
+            options={configs.map(c => ({ value: c.id, label: `${c.name} · ${c.model_name}` }))}
+          />
+          <button
+            onClick={handleAnalyze}
+            disabled={!image || running}
+            className="flex items-center gap-1.5 px-3 py-1.5 rounded-lg text-xs font-medium text-primary-gold bg-primary-gold/10 border border-primary-gold/20 hover:bg-primary-gold/20 transition-all cursor-pointer disabled:opacity-40"
+          >
+            {running ? <Loader2 size={12} className="animate-spin" /> : <ScanEye size={12} />}
+            开始分析
+          </button>
+        </div>
+        <p className="text-[10px] text-txt-muted">需要支持图片输入的模型（如 gpt-4o、qwen-vl、glm-4v）。指定股票时会用真实行情核对图片内容</p>
+      </div>
+
+      <div className="flex-1 overflow-auto px-4 py-3">
+        {error && <p className="text-xs text-red-400 mb-2">{error}</p>}
+        {content ? (
+          <div className="prose prose-invert prose-sm max-w-none">
+            <ReactMarkdown remarkPlugins={[remarkGfm]}>{content}</ReactMarkdown>
+          </div>
+        ) : running ? (
+          <div className="flex items-center justify-center h-24">
+            <Loader2 size={18} className="animate-spin text-primary-gold" />
+          </div>
+        ) : null}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,106 @@
+import { useEffect, useState } from 'react';
+import { Switch, InputNumber, Tooltip, App } from 'antd';
+import { Play, Loader2, CheckCircle, XCircle } from 'lucide-react';
+import { safeInvoke as invoke } from '../hooks/useTauri';
+import { AppSettings, JobRun, JobSetting, ScheduledJob } from '../types';
+
+interface Props {
+  settings: AppSettings;
+  saveSettings: (settings: AppSettings) => Promise<void>;
+}
+
+/** 后台任务列表：启用开关、间隔覆盖、最近运行记录与立即执行 */
+export default function JobSchedulerPanel({ settings, saveSettings }: Props) {
+  const { message } = App.useApp();
+  const [jobs, setJobs] = useState<ScheduledJob[]>([]);
+  const [runningId, setRunningId] = useState<string | null>(null);
+
+  const loadJobs = async () => {
+    try {
+      setJobs((await invoke<ScheduledJob[]>('get_scheduled_jobs')) ?? []);
+    } catch (e) {
+      message.error(`加载后台任务失败: ${e}`);
+    }
+  };
+
+  useEffect(() => {
+    loadJobs();
+  }, []);
+
+  const updateJob = async (id: string, patch: Partial<JobSetting>) => {
+    const current = settings.job_settings?.[id] ?? { enabled: true, interval_secs: null };
+    await saveSettings({ ...settings, job_settings: { ...settings.job_settings, [id]: { ...current, ...patch } } });
+    await loadJobs();
+  };
+
+  const handleRun = async (job: ScheduledJob) => {
+    setRunningId(job.id);
+    try {
+      const run = await invoke<JobRun | null>('run_scheduled_job', { id: job.id });
+      if (!run) message.info(`${job.name}：当前无需执行`);
+      else if (run.success) message.success(`${job.name}：${run.message}`);
+      else message.error(`${job.name} 失败：${run.message}`);
+    } catch (e) {
+      message.error(`${e}`);
+    } finally {
+      setRunningId(null);
+      loadJobs();
+    }
+  };
+
+  return (
+    <div className="p-4 rounded-lg border border-[#30363D] bg-bg-card divide-y divide-[#30363D]">
+      {jobs.length === 0 && <div className="text-xs text-txt-muted">暂无后台任务</div>}
+      {jobs.map(job => {
+        const last = job.recent_runs[0];
+        const intervalFixed = job.default_interval_secs === 0;
+        return (
+          <div key={job.id} className="flex items-center justify-between gap-4 py-2.5 first:pt-0 last:pb-0">
+            <div className="min-w-0">
+              <div className="flex items-center gap-2">
+                <span className="text-sm text-txt-primary">{job.name}</span>
+                <span className="text-[11px] text-txt-muted">{job.schedule}</span>
+              </div>
+              {last ? (
+                <Tooltip
+                  title={job.recent_runs.map(r => `${r.started_at} ${r.success ? '成功' : '失败'} ${r.message}`).join('\n')}
+                  overlayStyle={{ whiteSpace: 'pre-line', maxWidth: 480 }}
+                >
+                  <div className="flex items-center gap-1 text-[11px] text-txt-muted mt-0.5 truncate cursor-help">
+                    {last.success ? <CheckCircle size={11} className="text-green-400 shrink-0" /> : <XCircle size={11} className="text-red-400 shrink-0" />}
+                    <span>{last.started_at}</span>
+                    <span className="truncate">{last.message}</span>
+                  </div>
+                </Tooltip>
+              ) : (
+                <div className="text-[11px] text-txt-muted mt-0.5">尚未运行</div>
+              )}
+            </div>
+            <div className="flex items-center gap-3 shrink-0">
+              {!intervalFixed && (
+                <InputNumber
+                  size="small"
+                  min={10}
+                  step={60}
+                  value={job.interval_secs}
+                  addonAfter="秒"
+                  style={{ width: 120 }}
+                  onChange={v => updateJob(job.id, { interval_secs: v === job.default_interval_secs ? null : v })}
+                />
+              )}
+              <Switch size="small" checked={job.enabled} onChange={v => updateJob(job.id, { enabled: v })} />
+              <button
+                onClick={() => handleRun(job)}
+                disabled={job.running || runningId !== null}
+                className="p-1.5 rounded hover:bg-bg-elevated text-txt-secondary hover:text-txt-primary transition-colors cursor-pointer disabled:opacity-50 disabled:cursor-not-allowed"
+                title="立即执行"
+              >
+                {job.running || runningId === job.id ? <Loader2 size={14} className="animate-spin" /> : <Play size={14} />}
+              </button>
+            </div>
+          </div>
+        );
+      })}
+    </div>
+  );
+}
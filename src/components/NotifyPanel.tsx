@@ -0,0 +1,151 @@
+import { useState } from 'react';
+import { Select, Input, Switch, Checkbox, App } from 'antd';
+import { Plus, Trash2, Save, Send, Loader2 } from 'lucide-react';
+import { useSettingsStore } from '../stores/settingsStore';
+import { safeInvoke as invoke } from '../hooks/useTauri';
+import { AppSettings, NotifyEvent, NotifyKind, NotifyTarget } from '../types';
+
+interface Props {
+  settings: AppSettings;
+}
+
+const KIND_OPTIONS: { value: NotifyKind; label: string }[] = [
+  { value: 'dingtalk', label: '钉钉机器人' },
+  { value: 'wecom', label: '企业微信机器人' },
+  { value: 'telegram', label: 'Telegram Bot' },
+  { value: 'serverchan', label: 'Server酱' },
+  { value: 'webhook', label: '通用 Webhook（POST JSON）' },
+];
+
+const EVENT_OPTIONS: { value: NotifyEvent; label: string }[] = [
+  { value: 'signal_alert', label: '自选股信号' },
+  { value: 'anomaly', label: '盘中异动' },
+  { value: 'morning_briefing', label: '早盘备忘' },
+  { value: 'risk_alert', label: '持仓风控' },
+  { value: 'auction_gap', label: '竞价异动' },
+];
+
+function newTarget(index: number): NotifyTarget {
+  return {
+    id: crypto.randomUUID(),
+    name: `推送 ${index}`,
+    kind: 'dingtalk',
+    enabled: true,
+    url: '',
+    key: '',
+    secret: '',
+    chat_id: '',
+    events: [],
+  };
+}
+
+/** 消息推送目标：钉钉 / 企业微信 / Telegram / Server酱 / Webhook，按事件订阅 */
+export default function NotifyPanel({ settings }: Props) {
+  const { message } = App.useApp();
+  const { saveSettings } = useSettingsStore();
+  const [targets, setTargets] = useState<NotifyTarget[]>(settings.notify_targets ?? []);
+  const [testingId, setTestingId] = useState<string | null>(null);
+
+  const dirty = JSON.stringify(targets) !== JSON.stringify(settings.notify_targets ?? []);
+  const update = (id: string, patch: Partial<NotifyTarget>) =>
+    setTargets(prev => prev.map(t => (t.id === id ? { ...t, ...patch } : t)));
+
+  const handleSave = async () => {
+    await saveSettings({ ...settings, notify_targets: targets });
+    message.success('推送配置已保存');
+  };
+
+  const handleTest = async (target: NotifyTarget) => {
+    setTestingId(target.id);
+    try {
+      await invoke('test_notify_target', { target });
+      message.success(`已发送测试消息到「${target.name}」`);
+    } catch (e) {
+      message.error(`${e}`);
+    } finally {
+      setTestingId(null);
+    }
+  };
+
+  const buttonClass =
+    'flex items-center gap-1.5 px-3 py-1.5 rounded-lg text-sm text-txt-secondary hover:text-txt-primary hover:bg-bg-elevated transition-colors cursor-pointer disabled:opacity-50 disabled:cursor-not-allowed';
+  const labelClass = 'text-xs text-txt-muted w-24 shrink-0';
+
+  return (
+    <div className="p-4 rounded-lg border border-[#30363D] bg-bg-card space-y-4">
+      <p className="text-xs text-txt-muted">
+        自选股信号、盘中异动、早盘备忘与持仓风控告警生成后推送到手机，应用最小化或不在电脑前时也能及时收到。推送目标仅保存在本机
+      </p>
+
+      {targets.map(target => (
+        <div key={target.id} className="p-3 rounded-lg border border-[#30363D] space-y-3">
+          <div className="flex items-center gap-2">
+            <Input className="flex-1" value={target.name} onChange={e => update(target.id, { name: e.target.value })} />
+            <Select
+              className="w-48"
+              value={target.kind}
+              onChange={kind => update(target.id, { kind })}
+              options={KIND_OPTIONS}
+            />
+            <Switch checked={target.enabled} onChange={enabled => update(target.id, { enabled })} />
+            <button className={buttonClass} disabled={testingId === target.id} onClick={() => handleTest(target)}>
+              {testingId === target.id ? <Loader2 size={14} className="animate-spin" /> : <Send size={14} />}
+              测试
+            </button>
+            <button className={buttonClass} onClick={() => setTargets(prev => prev.filter(t => t.id !== target.id))}>
+              <Trash2 size={14} />
+            </button>
+          </div>
+
+          {(target.kind === 'dingtalk' || target.kind === 'wecom' || target.kind === 'webhook') && (
+            <div className="flex items-center gap-3">
+              <span className={labelClass}>Webhook 地址</span>
+              <Input.Password value={target.url} onChange={e => update(target.id, { url: e.target.value })} />
+            </div>
+          )}
+          {target.kind === 'dingtalk' && (
+            <div className="flex items-center gap-3">
+              <span className={labelClass}>加签密钥</span>
+              <Input.Password
+                placeholder="SEC 开头，未开启加签可留空"
+                value={target.secret}
+                onChange={e => update(target.id, { secret: e.target.value })}
+              />
+            </div>
+          )}
+          {(target.kind === 'telegram' || target.kind === 'serverchan') && (
+            <div className="flex items-center gap-3">
+              <span className={labelClass}>{target.kind === 'telegram' ? 'Bot Token' : 'SendKey'}</span>
+              <Input.Password value={target.key} onChange={e => update(target.id, { key: e.target.value })} />
+            </div>
+          )}
+          {target.kind === 'telegram' && (
+            <div className="flex items-center gap-3">
+              <span className={labelClass}>chat_id</span>
+              <Input value={target.chat_id} onChange={e => update(target.id, { chat_id: e.target.value })} />
+            </div>
+          )}
+          <div className="flex items-center gap-3">
+            <span className={labelClass}>推送内容</span>
+            <Checkbox.Group
+              value={target.events.length ? target.events : EVENT_OPTIONS.map(o => o.value)}
+              onChange={values => update(target.id, { events: values as NotifyEvent[] })}
+              options={EVENT_OPTIONS}
+            />
+          </div>
+        </div>
+      ))}
+
+      <div className="flex items-center justify-between">
+        <button className={buttonClass} onClick={() => setTargets(prev => [...prev, newTarget(prev.length + 1)])}>
+          <Plus size={14} />
+          添加推送目标
+        </button>
+        <button className={buttonClass} disabled={!dirty} onClick={handleSave}>
+          <Save size={14} />
+          保存
+        </button>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,208 @@
+import { useState } from 'react';
+import { Input, Select, Switch, App } from 'antd';
+import { Save, Plus, Trash2, FlaskConical, Loader2 } from 'lucide-react';
+import { useSettingsStore } from '../stores/settingsStore';
+import { safeInvoke as invoke } from '../hooks/useTauri';
+import { AppSettings, CustomQuoteProvider, StockInfo } from '../types';
+
+interface Props {
+  settings: AppSettings;
+}
+
+const BUILTIN_PROVIDERS = [
+  { value: 'sina', label: '新浪' },
+  { value: 'tencent', label: '腾讯' },
+  { value: 'eastmoney', label: '东方财富' },
+];
+
+const EXAMPLE_FIELDS = { code: 'symbol', name: 'name', price: 'last', pre_close: 'prev_close' };
+
+function newProvider(index: number): CustomQuoteProvider {
+  return {
+    id: `custom_${Date.now().toString(36)}`,
+    name: `自定义接口 ${index}`,
+    enabled: true,
+    url: 'https://example.com/quote?codes={codes}',
+    code_format: 'prefixed',
+    headers: {},
+    list_path: 'data',
+    fields: EXAMPLE_FIELDS,
+  };
+}
+
+function parseMap(text: string): Record<string, string> | null {
+  try {
+    const value = JSON.parse(text || '{}');
+    return value && typeof value === 'object' && !Array.isArray(value) ? value : null;
+  } catch {
+    return null;
+  }
+}
+
+/** 行情源：内置与自定义接口的优先级，以及通过字段映射接入的自定义 JSON 接口 */
+export default function QuoteProviderPanel({ settings }: Props) {
+  const { message } = App.useApp();
+  const { saveSettings } = useSettingsStore();
+  const [order, setOrder] = useState<string[]>(settings.quote_provider_order ?? []);
+  const [providers, setProviders] = useState<CustomQuoteProvider[]>(settings.custom_quote_providers ?? []);
+  const [drafts, setDrafts] = useState<Record<string, { fields: string; headers: string }>>({});
+  const [testCodes, setTestCodes] = useState('sh600519,sz000001');
+  const [testingId, setTestingId] = useState<string | null>(null);
+  const [testResults, setTestResults] = useState<Record<string, { ok: boolean; msg: string }>>({});
+
+  const dirty =
+    JSON.stringify({ order, providers }) !==
+    JSON.stringify({ order: settings.quote_provider_order ?? [], providers: settings.custom_quote_providers ?? [] });
+
+  const update = (id: string, patch: Partial<CustomQuoteProvider>) =>
+    setProviders(prev => prev.map(p => (p.id === id ? { ...p, ...patch } : p)));
+
+  const updateMap = (id: string, key: 'fields' | 'headers', text: string) => {
+    const provider = providers.find(p => p.id === id);
+    if (!provider) return;
+    setDrafts(prev => ({
+      ...prev,
+      [id]: { fields: JSON.stringify(provider.fields, null, 2), headers: JSON.stringify(provider.headers), ...prev[id], [key]: text },
+    }));
+    const parsed = parseMap(text);
+    if (parsed) update(id, { [key]: parsed });
+  };
+
+  const mapText = (p: CustomQuoteProvider, key: 'fields' | 'headers') =>
+    drafts[p.id]?.[key] ?? (key === 'fields' ? JSON.stringify(p.fields, null, 2) : JSON.stringify(p.headers));
+
+  const handleRemove = (id: string) => {
+    setProviders(prev => prev.filter(p => p.id !== id));
+    setOrder(prev => prev.filter(o => o !== id));
+  };
+
+  const handleTest = async (provider: CustomQuoteProvider) => {
+    setTestingId(provider.id);
+    try {
+      const codes = testCodes.split(/[,，\s]+/).filter(Boolean);
+      const quotes = await invoke<StockInfo[]>('test_custom_quote_provider', { provider, codes });
+      const msg = quotes.length
+        ? quotes.slice(0, 3).map(q => `${q.name || q.code} ${q.price}`).join('，') + (quotes.length > 3 ? ` 等 ${quotes.length} 只` : '')
+        : '接口返回成功，但未解析出行情，请检查列表路径与字段映射';
+      setTestResults(prev => ({ ...prev, [provider.id]: { ok: quotes.length > 0, msg } }));
+    } catch (e) {
+      setTestResults(prev => ({ ...prev, [provider.id]: { ok: false, msg: `${e}` } }));
+    } finally {
+      setTestingId(null);
+    }
+  };
+
+  const handleSave = async () => {
+    await saveSettings({ ...settings, quote_provider_order: order, custom_quote_providers: providers });
+    message.success('行情源配置已保存');
+  };
+
+  const buttonClass =
+    'flex items-center gap-1.5 px-3 py-1.5 rounded-lg text-sm text-txt-secondary hover:text-txt-primary hover:bg-bg-elevated transition-colors cursor-pointer disabled:opacity-50 disabled:cursor-not-allowed';
+  const labelClass = 'text-xs text-txt-muted w-20 shrink-0';
+  const providerOptions = [
+    ...BUILTIN_PROVIDERS,
+    ...providers.filter(p => p.enabled).map(p => ({ value: p.id, label: p.name })),
+  ];
+
+  return (
+    <div className="p-4 rounded-lg border border-[#30363D] bg-bg-card space-y-4">
+      <div className="space-y-2">
+        <span className="text-sm text-txt-primary">行情源优先级</span>
+        <p className="text-xs text-txt-muted">
+          按选择顺序依次尝试，出错或返回空时切换下一个；留空则按主数据源（新浪/腾讯）优先，东财与已启用的自定义接口兜底
+        </p>
+        <Select
+          mode="multiple"
+          className="w-full"
+          placeholder="默认顺序"
+          value={order}
+          options={providerOptions}
+          onChange={setOrder}
+        />
+      </div>
+
+      <div className="flex items-center justify-between">
+        <div>
+          <span className="text-sm text-txt-primary">自定义 JSON 接口</span>
+          <p className="text-xs text-txt-muted mt-1">
+            字段映射的键为 code/name/price/pre_close/open/high/low/volume/amount/bid/ask/date/time，值为条目内的字段路径（点分隔，数组可用下标）
+          </p>
+        </div>
+        <button className={buttonClass} onClick={() => setProviders(prev => [...prev, newProvider(prev.length + 1)])}>
+          <Plus size={14} />
+          添加
+        </button>
+      </div>
+
+      {providers.map(p => (
+        <div key={p.id} className="p-3 rounded-lg bg-bg-elevated space-y-2">
+          <div className="flex items-center gap-2">
+            <Input value={p.name} onChange={e => update(p.id, { name: e.target.value })} style={{ width: 180 }} />
+            <span className="text-xs text-txt-muted font-mono flex-1 truncate">{p.id}</span>
+            <Switch size="small" checked={p.enabled} onChange={enabled => update(p.id, { enabled })} />
+            <button onClick={() => handleRemove(p.id)} className="p-1 rounded text-txt-muted hover:text-red-400 cursor-pointer">
+              <Trash2 size={14} />
+            </button>
+          </div>
+          <div className="flex items-center gap-3">
+            <span className={labelClass}>请求地址</span>
+            <Input value={p.url} placeholder="需包含 {codes}，密钥请放在请求头中" onChange={e => update(p.id, { url: e.target.value })} />
+          </div>
+          <div className="flex items-center gap-3">
+            <span className={labelClass}>代码格式</span>
+            <Select
+              value={p.code_format}
+              style={{ width: 160 }}
+              options={[
+                { value: 'prefixed', label: 'sh600000' },
+                { value: 'pure', label: '600000' },
+              ]}
+              onChange={code_format => update(p.id, { code_format })}
+            />
+            <span className="text-xs text-txt-muted shrink-0">列表路径</span>
+            <Input value={p.list_path} placeholder="如 data.list" onChange={e => update(p.id, { list_path: e.target.value })} />
+          </div>
+          <div className="flex items-start gap-3">
+            <span className={labelClass}>字段映射</span>
+            <Input.TextArea
+              rows={4}
+              className="font-mono"
+              value={mapText(p, 'fields')}
+              status={parseMap(mapText(p, 'fields')) ? undefined : 'error'}
+              onChange={e => updateMap(p.id, 'fields', e.target.value)}
+            />
+          </div>
+          <div className="flex items-center gap-3">
+            <span className={labelClass}>请求头</span>
+            <Input
+              className="font-mono"
+              placeholder='{"Referer": "https://example.com/"}'
+              value={mapText(p, 'headers')}
+              status={parseMap(mapText(p, 'headers')) ? undefined : 'error'}
+              onChange={e => updateMap(p.id, 'headers', e.target.value)}
+            />
+          </div>
+          <div className="flex items-center gap-3">
+            <span className={labelClass}>测试代码</span>
+            <Input value={testCodes} onChange={e => setTestCodes(e.target.value)} style={{ width: 200 }} />
+            <button className={buttonClass} disabled={testingId !== null} onClick={() => handleTest(p)}>
+              {testingId === p.id ? <Loader2 size={14} className="animate-spin" /> : <FlaskConical size={14} />}
+              测试
+            </button>
+          </div>
+          {testResults[p.id] && (
+            <p className={`text-xs ${testResults[p.id].ok ? 'text-functional-info' : 'text-red-400'}`}>{testResults[p.id].msg}</p>
+          )}
+        </div>
+      ))}
+
+      <div className="flex items-center justify-end pt-3 border-t border-[#30363D]">
+        <button className={buttonClass} disabled={!dirty} onClick={handleSave}>
+          <Save size={14} />
+          保存
+        </button>
+      </div>
+    </div>
+  );
+}
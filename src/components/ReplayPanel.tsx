@@ -0,0 +1,137 @@
+import { useEffect, useState } from 'react';
+import { Select, Slider } from 'antd';
+import { X, Play, Pause, History, Loader2 } from 'lucide-react';
+import { safeInvoke as invoke } from '../hooks/useTauri';
+import { ReplayDay, ReplayFrame } from '../types';
+import logger from '../utils/logger';
+
+interface Props {
+  onClose: () => void;
+}
+
+/** 每秒播放的帧数（1 帧 = 1 分钟） */
+const SPEED_OPTIONS = [1, 2, 5, 10].map(v => ({ value: v, label: `${v}x` }));
+
+/** 盘后复盘回放：按分钟重放集合竞价与盘中行情 */
+export default function ReplayPanel({ onClose }: Props) {
+  const [days, setDays] = useState<ReplayDay[]>([]);
+  const [date, setDate] = useState<string | null>(null);
+  const [frames, setFrames] = useState<ReplayFrame[]>([]);
+  const [index, setIndex] = useState(0);
+  const [playing, setPlaying] = useState(false);
+  const [speed, setSpeed] = useState(2);
+  const [loading, setLoading] = useState(false);
+
+  useEffect(() => {
+    invoke<ReplayDay[]>('get_replay_days')
+      .then(list => {
+        setDays(list || []);
+        if (list?.length) setDate(list[0].date);
+      })
+      .catch(e => logger.error(`Load replay days failed: ${e}`));
+  }, []);
+
+  useEffect(() => {
+    if (!date) return;
+    setPlaying(false);
+    setLoading(true);
+    invoke<ReplayFrame[]>('get_intraday_replay', { date })
+      .then(list => {
+        setFrames(list || []);
+        setIndex(0);
+      })
+      .catch(e => logger.error(`Load replay failed: ${e}`))
+      .finally(() => setLoading(false));
+  }, [date]);
+
+  useEffect(() => {
+    if (!playing) return;
+    const timer = setInterval(() => {
+      setIndex(i => {
+        if (i >= frames.length - 1) {
+          setPlaying(false);
+          return i;
+        }
+        return i + 1;
+      });
+    }, 1000 / speed);
+    return () => clearInterval(timer);
+  }, [playing, speed, frames.length]);
+
+  const frame = frames[index];
+  const quotes = frame ? [...frame.quotes].sort((a, b) => b.change_pct - a.change_pct) : [];
+
+  const togglePlay = () => {
+    if (!playing && index >= frames.length - 1) setIndex(0);
+    setPlaying(p => !p);
+  };
+
+  return (
+    <div className="fixed right-0 top-12 bottom-0 w-[480px] bg-bg-card border-l border-[#30363D] flex flex-col z-50 shadow-2xl shadow-black/50 animate-in slide-in-from-right">
+      <div className="flex items-center justify-between px-4 py-3 border-b border-[#30363D]">
+        <div className="flex items-center gap-2">
+          <History size={16} className="text-cyan-400" />
+          <span className="font-bold text-txt-primary text-sm">复盘回放</span>
+          {frame && <span className="text-xs text-txt-muted font-mono">{frame.time}</span>}
+        </div>
+        <button onClick={onClose} className="p-1 rounded hover:bg-bg-elevated transition-colors cursor-pointer">
+          <X size={18} className="text-txt-secondary" />
+        </button>
+      </div>
+
+      <div className="px-4 py-3 border-b border-[#30363D] space-y-2">
+        <div className="flex items-center gap-2">
+          <Select
+            className="flex-1"
+            value={date}
+            onChange={setDate}
+            placeholder="暂无盘中快照"
+            options={days.map(d => ({ value: d.date, label: `${d.date}（${d.stock_count} 只）` }))}
+          />
+          <Select className="w-20" value={speed} onChange={setSpeed} options={SPEED_OPTIONS} />
+          <button
+            onClick={togglePlay}
+            disabled={frames.length === 0}
+            className="p-1.5 rounded-lg bg-bg-elevated text-txt-secondary hover:text-txt-primary transition-colors cursor-pointer disabled:opacity-40"
+          >
+            {playing ? <Pause size={14} /> : <Play size={14} />}
+          </button>
+        </div>
+        <Slider
+          min={0}
+          max={Math.max(frames.length - 1, 0)}
+          value={index}
+          onChange={v => { setPlaying(false); setIndex(v); }}
+          tooltip={{ formatter: v => frames[v ?? 0]?.time }}
+          disabled={frames.length === 0}
+        />
+      </div>
+
+      <div className="flex-1 overflow-auto">
+        {loading ? (
+          <div className="flex items-center justify-center h-32">
+            <Loader2 size={18} className="animate-spin text-primary-gold" />
+          </div>
+        ) : frames.length === 0 ? (
+          <p className="p-6 text-center text-xs text-txt-muted leading-relaxed">
+            交易日 09:15 起会自动采集自选股与 AI 追踪股票的分时快照，收盘后即可在此回放
+          </p>
+        ) : (
+          quotes.map(q => {
+            const color = q.change_pct > 0 ? 'text-functional-up' : q.change_pct < 0 ? 'text-functional-down' : 'text-txt-primary';
+            return (
+              <div key={q.code} className="flex items-center gap-3 px-4 py-1.5 text-xs border-b border-[#30363D]/50">
+                <span className="flex-1 truncate text-txt-primary">{q.name}</span>
+                <span className="font-mono text-txt-muted w-20">{q.code}</span>
+                <span className={`font-mono w-16 text-right ${color}`}>{q.price.toFixed(2)}</span>
+                <span className={`font-mono w-16 text-right ${color}`}>
+                  {q.change_pct > 0 ? '+' : ''}{q.change_pct.toFixed(2)}%
+                </span>
+              </div>
+            );
+          })
+        )}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,130 @@
+import { useEffect, useState } from 'react';
+import { X, ShieldAlert, ShieldCheck, RefreshCw, Loader2 } from 'lucide-react';
+import { safeInvoke as invoke } from '../hooks/useTauri';
+import { RiskReport } from '../types';
+import logger from '../utils/logger';
+
+interface Props {
+  onClose: () => void;
+}
+
+const RULE_LABELS: Record<string, string> = {
+  position: '单只仓位',
+  sector: '行业集中',
+  cluster: '相关性集中',
+  st: 'ST 股',
+  drawdown: '回撤',
+};
+
+/** 持仓风控：AI 追踪股票的仓位、行业与相关性集中度，以及违规项 */
+export default function RiskPanel({ onClose }: Props) {
+  const [report, setReport] = useState<RiskReport | null>(null);
+  const [loading, setLoading] = useState(false);
+
+  const load = () => {
+    setLoading(true);
+    invoke<RiskReport>('get_risk_report')
+      .then(setReport)
+      .catch(e => logger.error(`Load risk report failed: ${e}`))
+      .finally(() => setLoading(false));
+  };
+
+  useEffect(load, []);
+
+  const sectionTitle = 'px-4 pt-3 pb-1 text-xs font-medium text-txt-secondary';
+  const pct = (v: number) => `${v.toFixed(1)}%`;
+
+  return (
+    <div className="fixed right-0 top-12 bottom-0 w-[480px] bg-bg-card border-l border-[#30363D] flex flex-col z-50 shadow-2xl shadow-black/50 animate-in slide-in-from-right">
+      <div className="flex items-center justify-between px-4 py-3 border-b border-[#30363D]">
+        <div className="flex items-center gap-2">
+          <ShieldAlert size={16} className="text-amber-400" />
+          <span className="font-bold text-txt-primary text-sm">持仓风控</span>
+          {report && <span className="text-xs text-txt-muted font-mono">{report.generated_at}</span>}
+        </div>
+        <div className="flex items-center gap-1">
+          <button onClick={load} disabled={loading} className="p-1 rounded hover:bg-bg-elevated transition-colors cursor-pointer disabled:opacity-40">
+            <RefreshCw size={15} className={`text-txt-secondary ${loading ? 'animate-spin' : ''}`} />
+          </button>
+          <button onClick={onClose} className="p-1 rounded hover:bg-bg-elevated transition-colors cursor-pointer">
+            <X size={18} className="text-txt-secondary" />
+          </button>
+        </div>
+      </div>
+
+      <div className="flex-1 overflow-auto pb-4">
+        {loading && !report ? (
+          <div className="flex items-center justify-center h-32">
+            <Loader2 size={18} className="animate-spin text-primary-gold" />
+          </div>
+        ) : !report || report.positions.length === 0 ? (
+          <p className="p-6 text-center text-xs text-txt-muted leading-relaxed">
+            暂无 AI 追踪股票。每条追踪记录视为一份等额建仓，按当前市值计算仓位
+          </p>
+        ) : (
+          <>
+            <div className={sectionTitle}>违规项</div>
+            {report.violations.length === 0 ? (
+              <div className="flex items-center gap-2 px-4 py-2 text-xs text-emerald-400">
+                <ShieldCheck size={14} />
+                持仓符合全部风控规则
+              </div>
+            ) : (
+              report.violations.map((v, i) => (
+                <div key={i} className="flex items-start gap-2 px-4 py-1.5 text-xs">
+                  <span className="shrink-0 px-1.5 py-0.5 rounded bg-red-500/10 text-red-400 text-[10px]">
+                    {RULE_LABELS[v.rule] ?? v.rule}
+                  </span>
+                  <span className="text-txt-primary leading-relaxed">{v.message}</span>
+                </div>
+              ))
+            )}
+
+            <div className={sectionTitle}>持仓</div>
+            {report.positions.map(p => {
+              const color = p.return_pct > 0 ? 'text-functional-up' : p.return_pct < 0 ? 'text-functional-down' : 'text-txt-primary';
+              return (
+                <div key={p.code} className="flex items-center gap-3 px-4 py-1.5 text-xs border-b border-[#30363D]/50">
+                  <span className="flex-1 truncate text-txt-primary">
+                    {p.name}
+                    {p.lots > 1 && <span className="ml-1 text-txt-muted">×{p.lots}</span>}
+                  </span>
+                  <span className="w-16 truncate text-txt-muted">{p.sector || '未分类'}</span>
+                  <span className="font-mono w-14 text-right text-txt-secondary">{pct(p.weight_pct)}</span>
+                  <span className={`font-mono w-16 text-right ${color}`}>
+                    {p.return_pct > 0 ? '+' : ''}{pct(p.return_pct)}
+                  </span>
+                  <span className="font-mono w-14 text-right text-txt-muted" title="自建仓高点回撤">-{pct(p.drawdown_pct)}</span>
+                </div>
+              );
+            })}
+
+            <div className={sectionTitle}>行业分布</div>
+            {report.sectors.map(s => (
+              <div key={s.sector} className="flex items-center gap-3 px-4 py-1 text-xs">
+                <span className="w-20 truncate text-txt-primary">{s.sector}</span>
+                <div className="flex-1 h-1.5 rounded bg-bg-elevated overflow-hidden">
+                  <div className="h-full bg-cyan-500/60" style={{ width: `${Math.min(s.weight_pct, 100)}%` }} />
+                </div>
+                <span className="font-mono w-14 text-right text-txt-secondary">{pct(s.weight_pct)}</span>
+              </div>
+            ))}
+
+            {report.clusters.length > 0 && (
+              <>
+                <div className={sectionTitle}>高相关股票群</div>
+                {report.clusters.map(c => (
+                  <div key={c.codes.join(',')} className="flex items-center gap-3 px-4 py-1 text-xs">
+                    <span className="flex-1 text-txt-primary">{c.names.join('、')}</span>
+                    <span className="font-mono text-txt-muted">ρ≥{c.min_correlation.toFixed(2)}</span>
+                    <span className="font-mono w-14 text-right text-txt-secondary">{pct(c.weight_pct)}</span>
+                  </div>
+                ))}
+              </>
+            )}
+          </>
+        )}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,83 @@
+import { useState } from 'react';
+import { InputNumber, Switch, App } from 'antd';
+import { Save } from 'lucide-react';
+import { useSettingsStore } from '../stores/settingsStore';
+import { AppSettings, RiskRules } from '../types';
+
+interface Props {
+  settings: AppSettings;
+}
+
+const PCT_FIELDS: { key: keyof RiskRules; label: string }[] = [
+  { key: 'max_position_pct', label: '单只仓位上限' },
+  { key: 'max_sector_pct', label: '单行业仓位上限' },
+  { key: 'max_cluster_pct', label: '高相关股票群合计上限' },
+  { key: 'max_drawdown_pct', label: '自建仓高点回撤告警' },
+];
+
+/** 持仓风控规则：仓位、行业与相关性集中度上限，ST 与回撤告警 */
+export default function RiskRulesPanel({ settings }: Props) {
+  const { message } = App.useApp();
+  const { saveSettings } = useSettingsStore();
+  const [rules, setRules] = useState<RiskRules>(settings.risk_rules);
+
+  const dirty = JSON.stringify(rules) !== JSON.stringify(settings.risk_rules);
+  const update = (patch: Partial<RiskRules>) => setRules(prev => ({ ...prev, ...patch }));
+
+  const handleSave = async () => {
+    await saveSettings({ ...settings, risk_rules: rules });
+    message.success('风控规则已保存');
+  };
+
+  const labelClass = 'text-xs text-txt-muted w-40 shrink-0';
+
+  return (
+    <div className="p-4 rounded-lg border border-[#30363D] bg-bg-card space-y-4">
+      <p className="text-xs text-txt-muted">
+        以 AI 追踪股票为持仓，每条追踪记录视为一份等额建仓、按当前市值计算仓位。可在「盯盘 → 风控检查」查看报告
+      </p>
+
+      {PCT_FIELDS.map(({ key, label }) => (
+        <div key={key} className="flex items-center gap-3">
+          <span className={labelClass}>{label}</span>
+          <InputNumber
+            min={1}
+            max={100}
+            addonAfter="%"
+            value={rules[key] as number}
+            onChange={v => v !== null && update({ [key]: v } as Partial<RiskRules>)}
+          />
+        </div>
+      ))}
+      <div className="flex items-center gap-3">
+        <span className={labelClass}>高相关阈值（日收益率）</span>
+        <InputNumber
+          min={0.5}
+          max={0.99}
+          step={0.05}
+          value={rules.correlation_threshold}
+          onChange={v => v !== null && update({ correlation_threshold: v })}
+        />
+      </div>
+      <div className="flex items-center gap-3">
+        <span className={labelClass}>禁止持有 ST 股</span>
+        <Switch checked={rules.forbid_st} onChange={forbid_st => update({ forbid_st })} />
+      </div>
+      <div className="flex items-center gap-3">
+        <span className={labelClass}>收盘后自动检查并推送</span>
+        <Switch checked={rules.alert_enabled} onChange={alert_enabled => update({ alert_enabled })} />
+      </div>
+
+      <div className="flex justify-end">
+        <button
+          className="flex items-center gap-1.5 px-3 py-1.5 rounded-lg text-sm text-txt-secondary hover:text-txt-primary hover:bg-bg-elevated transition-colors cursor-pointer disabled:opacity-50 disabled:cursor-not-allowed"
+          disabled={!dirty}
+          onClick={handleSave}
+        >
+          <Save size={14} />
+          保存
+        </button>
+      </div>
+    </div>
+  );
+}
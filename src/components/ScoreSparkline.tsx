@@ -0,0 +1,64 @@
+import { useEffect, useState } from 'react';
+import { safeInvoke as invoke } from '../hooks/useTauri';
+import { FactorScore } from '../types';
+import logger from '../utils/logger';
+
+interface Props {
+  code: string;
+}
+
+const WIDTH = 72;
+const HEIGHT = 20;
+
+const PARTS: [keyof FactorScore, string][] = [
+  ['quality', '质量'],
+  ['growth', '成长'],
+  ['value', '估值'],
+  ['momentum', '动量'],
+  ['flow', '资金'],
+];
+
+/** 综合因子评分近一月走势：折线 + 最新分与区间变化，悬停查看分项 */
+export default function ScoreSparkline({ code }: Props) {
+  const [history, setHistory] = useState<FactorScore[]>([]);
+
+  useEffect(() => {
+    setHistory([]);
+    invoke<FactorScore[]>('get_score_history', { code, days: 20 })
+      .then(list => setHistory(list ?? []))
+      .catch(e => logger.error(`Failed to load score history: ${e}`));
+  }, [code]);
+
+  if (history.length === 0) return null;
+
+  const latest = history[history.length - 1];
+  const delta = latest.score - history[0].score;
+  const color = delta > 0 ? '#E74C3C' : delta < 0 ? '#2ECC71' : '#8B949E';
+  const points = history
+    .map((h, i) => {
+      const x = history.length > 1 ? (i / (history.length - 1)) * WIDTH : WIDTH / 2;
+      const y = HEIGHT - (h.score / 100) * HEIGHT;
+      return `${x.toFixed(1)},${y.toFixed(1)}`;
+    })
+    .join(' ');
+  const title = [
+    `综合评分 ${latest.score.toFixed(1)}（${latest.date}）`,
+    ...PARTS.map(([key, label]) => `${label} ${latest[key] == null ? '-' : (latest[key] as number).toFixed(0)}`),
+    `近 ${history.length} 个交易日 ${delta >= 0 ? '+' : ''}${delta.toFixed(1)}`,
+  ].join('\n');
+
+  return (
+    <div title={title} className="flex items-center gap-1.5 px-2 py-0.5 rounded bg-bg-card border border-[#30363D] cursor-default">
+      <span className="text-[10px] text-txt-muted">评分</span>
+      <svg width={WIDTH} height={HEIGHT} className="overflow-visible">
+        <polyline points={points} fill="none" stroke={color} strokeWidth={1.5} strokeLinejoin="round" />
+      </svg>
+      <span className="font-mono text-xs text-txt-primary">{latest.score.toFixed(0)}</span>
+      {history.length > 1 && (
+        <span className="font-mono text-[10px]" style={{ color }}>
+          {delta >= 0 ? '+' : ''}{delta.toFixed(1)}
+        </span>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,129 @@
+import { useState } from 'react';
+import { Select, Input, Checkbox, Popconfirm, App } from 'antd';
+import { Save, Trash2, Upload, Download, Check } from 'lucide-react';
+import { useSettingsStore } from '../stores/settingsStore';
+import { CommandError } from '../hooks/useTauri';
+import { AppSettings } from '../types';
+
+interface Props {
+  settings: AppSettings;
+}
+
+/** 设置方案切换与设置文件导入导出 */
+export default function SettingsProfilePanel({ settings }: Props) {
+  const { message } = App.useApp();
+  const { exportSettings, importSettings, saveProfile, applyProfile, deleteProfile } = useSettingsStore();
+  const [selectedId, setSelectedId] = useState<string | null>(settings.active_profile_id);
+  const [profileName, setProfileName] = useState('');
+  const [includeSecrets, setIncludeSecrets] = useState(false);
+
+  const profiles = settings.profiles ?? [];
+  const selected = profiles.find(p => p.id === selectedId) ?? null;
+
+  const run = async (action: () => Promise<unknown>, success: string) => {
+    try {
+      await action();
+      message.success(success);
+    } catch (e) {
+      if (e instanceof CommandError && e.code === 'cancelled') return;
+      message.error(`${e}`);
+    }
+  };
+
+  const handleSave = () => {
+    const name = profileName.trim();
+    if (!name) {
+      message.warning('请输入方案名称');
+      return;
+    }
+    run(async () => {
+      await saveProfile(name);
+      setProfileName('');
+      setSelectedId(useSettingsStore.getState().settings?.active_profile_id ?? null);
+    }, `已保存方案「${name}」`);
+  };
+
+  const buttonClass =
+    'flex items-center gap-1.5 px-3 py-1.5 rounded-lg text-sm text-txt-secondary hover:text-txt-primary hover:bg-bg-elevated transition-colors cursor-pointer disabled:opacity-50 disabled:cursor-not-allowed';
+
+  return (
+    <div className="p-4 rounded-lg border border-[#30363D] bg-bg-card space-y-4">
+      <div>
+        <span className="text-sm text-txt-primary">设置方案</span>
+        <p className="text-xs text-txt-muted mt-1">保存当前模型、选股提示词、Agent 轮次与预算、信号参数、策略区间与选股偏好，一键切换</p>
+      </div>
+
+      <div className="flex items-center gap-2">
+        <Select
+          className="flex-1"
+          placeholder={profiles.length ? '选择方案' : '暂无方案'}
+          value={selectedId ?? undefined}
+          onChange={setSelectedId}
+          options={profiles.map(p => ({
+            value: p.id,
+            label: `${p.name}${p.id === settings.active_profile_id ? '（当前）' : ''}`,
+          }))}
+        />
+        <button
+          className={buttonClass}
+          disabled={!selected}
+          onClick={() => selected && run(() => applyProfile(selected.id), `已切换到「${selected.name}」`)}
+        >
+          <Check size={14} />
+          应用
+        </button>
+        <Popconfirm
+          title={`删除方案「${selected?.name ?? ''}」？`}
+          disabled={!selected}
+          onConfirm={() => selected && run(async () => {
+            await deleteProfile(selected.id);
+            setSelectedId(null);
+          }, '方案已删除')}
+        >
+          <button className={buttonClass} disabled={!selected}>
+            <Trash2 size={14} />
+          </button>
+        </Popconfirm>
+      </div>
+
+      <div className="flex items-center gap-2">
+        <Input
+          className="flex-1"
+          placeholder="方案名称，如 激进短线 / 稳健中线（同名覆盖）"
+          value={profileName}
+          onChange={e => setProfileName(e.target.value)}
+          onPressEnter={handleSave}
+        />
+        <button className={buttonClass} onClick={handleSave}>
+          <Save size={14} />
+          保存当前为方案
+        </button>
+      </div>
+
+      <div className="flex items-center justify-between pt-3 border-t border-[#30363D]">
+        <Checkbox checked={includeSecrets} onChange={e => setIncludeSecrets(e.target.checked)}>
+          <span className="text-xs text-txt-muted">导出时包含 API Key</span>
+        </Checkbox>
+        <div className="flex items-center gap-2">
+          <button className={buttonClass} onClick={() => run(importSettings, '设置已导入')}>
+            <Upload size={14} />
+            导入设置
+          </button>
+          <button
+            className={buttonClass}
+            onClick={async () => {
+              try {
+                message.success(await exportSettings(includeSecrets));
+              } catch (e) {
+                if (!(e instanceof CommandError && e.code === 'cancelled')) message.error(`${e}`);
+              }
+            }}
+          >
+            <Download size={14} />
+            导出设置
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,229 @@
+import { useEffect, useState } from 'react';
+import ReactMarkdown from 'react-markdown';
+import remarkGfm from 'remark-gfm';
+import { Switch, App } from 'antd';
+import { X, AlarmClock, Plus, Trash2, Play, Loader2 } from 'lucide-react';
+import { safeInvoke as invoke, safeListen } from '../hooks/useTauri';
+import { ScheduleRun, StockSchedule } from '../types';
+import logger from '../utils/logger';
+
+interface Props {
+  code: string;
+  name: string;
+  onClose: () => void;
+}
+
+const PRESETS = [
+  { time: '09:35', question: '开盘后走势是否符合预期，要不要调整' },
+  { time: '14:30', question: '分析尾盘该不该走' },
+];
+
+/** 按行渲染快检结论，与上一次相比新增或变化的行高亮 */
+function RunContent({ run }: { run: ScheduleRun }) {
+  const changed = new Set(run.changed_lines);
+  return (
+    <div className="prose prose-invert prose-sm max-w-none">
+      {run.analysis.content.split('\n').map((line, i) =>
+        line.trim() ? (
+          <div key={i} className={changed.has(i) ? 'bg-primary-gold/10 border-l-2 border-primary-gold pl-2 -ml-2.5' : ''}>
+            <ReactMarkdown remarkPlugins={[remarkGfm]}>{line}</ReactMarkdown>
+          </div>
+        ) : null
+      )}
+    </div>
+  );
+}
+
+/** 盯盘助手：为单只股票设定每个交易日定时的 AI 快检，结果追加到分析历史并高亮与上次的差异 */
+export default function StockSchedulePanel({ code, name, onClose }: Props) {
+  const { message } = App.useApp();
+  const [schedules, setSchedules] = useState<StockSchedule[]>([]);
+  const [selectedId, setSelectedId] = useState<string | null>(null);
+  const [runs, setRuns] = useState<ScheduleRun[]>([]);
+  const [time, setTime] = useState('14:30');
+  const [question, setQuestion] = useState('');
+  const [running, setRunning] = useState(false);
+
+  const loadSchedules = async () => {
+    try {
+      const list = await invoke<StockSchedule[]>('get_stock_schedules', { code });
+      setSchedules(list ?? []);
+      setSelectedId(prev => (prev && list?.some(s => s.id === prev) ? prev : list?.[0]?.id ?? null));
+    } catch (e) {
+      logger.error(`Failed to load stock schedules: ${e}`);
+    }
+  };
+
+  const loadRuns = async (id: string) => {
+    try {
+      setRuns((await invoke<ScheduleRun[]>('get_stock_schedule_runs', { id, limit: 10 })) ?? []);
+    } catch (e) {
+      logger.error(`Failed to load schedule runs: ${e}`);
+    }
+  };
+
+  useEffect(() => { loadSchedules(); }, [code]);
+
+  useEffect(() => {
+    if (selectedId) loadRuns(selectedId);
+    else setRuns([]);
+  }, [selectedId]);
+
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    safeListen<ScheduleRun>('stock-schedule-result', event => {
+      if (event.payload.schedule_id === selectedId) setRuns(prev => [event.payload, ...prev]);
+    }).then(fn => { unlisten = fn; });
+    return () => unlisten?.();
+  }, [selectedId]);
+
+  const save = async (schedule: StockSchedule) => {
+    try {
+      const saved = await invoke<StockSchedule>('save_stock_schedule', { schedule });
+      await loadSchedules();
+      return saved;
+    } catch (e) {
+      message.error(`${e}`);
+      return null;
+    }
+  };
+
+  const handleAdd = async (preset?: { time: string; question: string }) => {
+    const q = (preset?.question ?? question).trim();
+    if (!q) return;
+    const saved = await save({
+      id: '',
+      code,
+      name,
+      time: preset?.time ?? time,
+      question: q,
+      enabled: true,
+      last_run_date: '',
+      created_at: '',
+    });
+    if (saved) {
+      setQuestion('');
+      setSelectedId(saved.id);
+    }
+  };
+
+  const handleDelete = async (id: string) => {
+    await invoke('delete_stock_schedule', { id });
+    loadSchedules();
+  };
+
+  const handleRunNow = async () => {
+    if (!selectedId) return;
+    setRunning(true);
+    try {
+      const run = await invoke<ScheduleRun>('run_stock_schedule', { id: selectedId });
+      if (run) setRuns(prev => [run, ...prev]);
+    } catch (e) {
+      logger.error(`Stock schedule run failed: ${e}`);
+      message.error(`${e}`);
+    } finally {
+      setRunning(false);
+    }
+  };
+
+  return (
+    <div className="fixed right-0 top-12 bottom-0 w-[480px] bg-bg-card border-l border-[#30363D] flex flex-col z-50 shadow-2xl shadow-black/50 animate-in slide-in-from-right">
+      <div className="flex items-center justify-between px-4 py-3 border-b border-[#30363D]">
+        <div className="flex items-center gap-2">
+          <AlarmClock size={16} className="text-primary-gold" />
+          <span className="font-bold text-txt-primary text-sm">盯盘助手</span>
+          <span className="text-xs text-txt-muted">{name} {code}</span>
+        </div>
+        <button onClick={onClose} className="p-1 rounded hover:bg-bg-elevated transition-colors cursor-pointer">
+          <X size={18} className="text-txt-secondary" />
+        </button>
+      </div>
+
+      <div className="px-4 py-3 border-b border-[#30363D] space-y-2">
+        {schedules.map(s => (
+          <div
+            key={s.id}
+            onClick={() => setSelectedId(s.id)}
+            className={`flex items-center gap-2 px-2 py-1.5 rounded-lg border text-xs cursor-pointer transition-colors ${
+              s.id === selectedId ? 'border-primary-gold/40 bg-primary-gold/5' : 'border-[#30363D] hover:border-[#484F58]'
+            }`}
+          >
+            <span className="font-mono text-primary-gold">{s.time}</span>
+            <span className="flex-1 truncate text-txt-primary">{s.question}</span>
+            <Switch size="small" checked={s.enabled} onClick={(_, e) => e.stopPropagation()} onChange={enabled => save({ ...s, enabled })} />
+            <button
+              onClick={e => { e.stopPropagation(); handleDelete(s.id); }}
+              className="p-1 rounded text-txt-muted hover:text-functional-up cursor-pointer"
+            >
+              <Trash2 size={12} />
+            </button>
+          </div>
+        ))}
+        <div className="flex items-center gap-2">
+          <input
+            type="time"
+            value={time}
+            onChange={e => setTime(e.target.value)}
+            className="px-2 py-1.5 rounded-lg bg-bg-base border border-[#30363D] text-xs text-txt-primary outline-none focus:border-primary-gold/50"
+          />
+          <input
+            value={question}
+            onChange={e => setQuestion(e.target.value)}
+            onKeyDown={e => { if (e.key === 'Enter' && !e.nativeEvent.isComposing) handleAdd(); }}
+            placeholder="到点问 AI 什么？例如：分析尾盘该不该走"
+            className="flex-1 px-3 py-1.5 rounded-lg bg-bg-base border border-[#30363D] text-xs text-txt-primary placeholder:text-txt-muted outline-none focus:border-primary-gold/50"
+          />
+          <button
+            onClick={() => handleAdd()}
+            disabled={!question.trim()}
+            className="p-1.5 rounded-lg bg-primary-gold/10 text-primary-gold hover:bg-primary-gold/20 transition-colors cursor-pointer disabled:opacity-40"
+          >
+            <Plus size={14} />
+          </button>
+        </div>
+        {schedules.length === 0 && (
+          <div className="flex items-center gap-2">
+            {PRESETS.map(p => (
+              <button
+                key={p.time}
+                onClick={() => handleAdd(p)}
+                className="px-2 py-1 rounded border border-[#30363D] text-[11px] text-txt-secondary hover:text-txt-primary hover:border-[#484F58] transition-colors cursor-pointer"
+              >
+                {p.time} {p.question}
+              </button>
+            ))}
+          </div>
+        )}
+        <p className="text-[10px] text-txt-muted">每个交易日到点后自动执行一次（应用需在运行），结果追加到该股分析历史，与上次相比变化的内容会高亮</p>
+      </div>
+
+      <div className="flex-1 overflow-auto px-4 py-3 space-y-4">
+        {selectedId && (
+          <button
+            onClick={handleRunNow}
+            disabled={running}
+            className="flex items-center gap-1 px-3 py-1.5 rounded-lg text-xs font-medium bg-bg-card text-txt-secondary border border-[#30363D] hover:border-[#484F58] hover:text-txt-primary transition-all cursor-pointer disabled:opacity-40"
+          >
+            {running ? <Loader2 size={12} className="animate-spin" /> : <Play size={12} />}
+            立即快检
+          </button>
+        )}
+        {runs.map(run => (
+          <div key={run.analysis.id} className="space-y-1.5">
+            <div className="flex items-center gap-2 text-[10px] text-txt-muted">
+              <span className="font-mono">{run.analysis.created_at}</span>
+              <span>{run.analysis.model_name}</span>
+              {run.previous_id && run.changed_lines.length > 0 && (
+                <span className="text-primary-gold">{run.changed_lines.length} 处变化</span>
+              )}
+            </div>
+            <RunContent run={run} />
+          </div>
+        ))}
+        {selectedId && runs.length === 0 && !running && (
+          <p className="text-xs text-txt-muted text-center pt-6">暂无快检结果</p>
+        )}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,128 @@
+import { useEffect, useState } from 'react';
+import { Select } from 'antd';
+import { X, BarChart3, RefreshCw, Loader2 } from 'lucide-react';
+import { safeInvoke as invoke } from '../hooks/useTauri';
+import { StrategyPerformance } from '../types';
+import logger from '../utils/logger';
+
+interface Props {
+  onClose: () => void;
+}
+
+const DAY_OPTIONS = [
+  { value: 30, label: '近30日' },
+  { value: 60, label: '近60日' },
+  { value: 90, label: '近90日' },
+];
+
+const HOLD_OPTIONS = [
+  { value: 1, label: '持有1日' },
+  { value: 3, label: '持有3日' },
+  { value: 5, label: '持有5日' },
+  { value: 10, label: '持有10日' },
+];
+
+/** 策略表现：技术信号、AI 买入指令与 AI 选股入选后的模拟持有收益与胜率 */
+export default function StrategyPerformancePanel({ onClose }: Props) {
+  const [items, setItems] = useState<StrategyPerformance[]>([]);
+  const [days, setDays] = useState(30);
+  const [holdDays, setHoldDays] = useState(5);
+  const [loading, setLoading] = useState(false);
+
+  const load = () => {
+    setLoading(true);
+    invoke<StrategyPerformance[]>('get_strategy_performance', { days, holdDays })
+      .then(setItems)
+      .catch(e => logger.error(`Load strategy performance failed: ${e}`))
+      .finally(() => setLoading(false));
+  };
+
+  useEffect(load, [days, holdDays]);
+
+  const signed = (v: number) => `${v > 0 ? '+' : ''}${v.toFixed(2)}%`;
+  const color = (v: number) => (v > 0 ? 'text-functional-up' : v < 0 ? 'text-functional-down' : 'text-txt-primary');
+
+  return (
+    <div className="fixed right-0 top-12 bottom-0 w-[480px] bg-bg-card border-l border-[#30363D] flex flex-col z-50 shadow-2xl shadow-black/50 animate-in slide-in-from-right">
+      <div className="flex items-center justify-between px-4 py-3 border-b border-[#30363D]">
+        <div className="flex items-center gap-2">
+          <BarChart3 size={16} className="text-functional-info" />
+          <span className="font-bold text-txt-primary text-sm">策略表现</span>
+        </div>
+        <div className="flex items-center gap-1">
+          <Select size="small" value={days} options={DAY_OPTIONS} onChange={setDays} style={{ width: 90 }} />
+          <Select size="small" value={holdDays} options={HOLD_OPTIONS} onChange={setHoldDays} style={{ width: 96 }} />
+          <button onClick={load} disabled={loading} className="p-1 rounded hover:bg-bg-elevated transition-colors cursor-pointer disabled:opacity-40">
+            <RefreshCw size={15} className={`text-txt-secondary ${loading ? 'animate-spin' : ''}`} />
+          </button>
+          <button onClick={onClose} className="p-1 rounded hover:bg-bg-elevated transition-colors cursor-pointer">
+            <X size={18} className="text-txt-secondary" />
+          </button>
+        </div>
+      </div>
+
+      <div className="flex-1 overflow-auto p-4 space-y-3">
+        <p className="text-xs text-txt-muted leading-relaxed">
+          入选后按固定天数模拟持有：技术信号按当日收盘、AI 买入指令按当日开盘、AI 选股按选股时价格建仓，到期按收盘价卖出
+        </p>
+        {loading && items.length === 0 ? (
+          <div className="flex items-center justify-center h-32">
+            <Loader2 size={18} className="animate-spin text-primary-gold" />
+          </div>
+        ) : (
+          items.map(p => {
+            const maxAbs = Math.max(...p.points.map(pt => Math.abs(pt.avg_return)), 1);
+            return (
+              <div key={p.strategy} className="p-3 rounded-lg bg-bg-elevated space-y-2">
+                <div className="flex items-center justify-between">
+                  <span className="text-sm text-txt-primary">{p.name}</span>
+                  <span className="text-xs text-txt-muted">
+                    入选 {p.total} 次，已到期 {p.evaluated} 次
+                  </span>
+                </div>
+                {p.evaluated === 0 ? (
+                  <p className="text-xs text-txt-muted">暂无持有期已满的记录</p>
+                ) : (
+                  <>
+                    <div className="grid grid-cols-4 gap-2 text-xs">
+                      <div>
+                        <div className="text-txt-muted">平均收益</div>
+                        <div className={`font-mono ${color(p.avg_return)}`}>{signed(p.avg_return)}</div>
+                      </div>
+                      <div>
+                        <div className="text-txt-muted">胜率</div>
+                        <div className="font-mono text-txt-primary">{p.win_rate.toFixed(1)}%</div>
+                      </div>
+                      <div>
+                        <div className="text-txt-muted">最好</div>
+                        <div className={`font-mono ${color(p.best_return ?? 0)}`}>{signed(p.best_return ?? 0)}</div>
+                      </div>
+                      <div>
+                        <div className="text-txt-muted">最差</div>
+                        <div className={`font-mono ${color(p.worst_return ?? 0)}`}>{signed(p.worst_return ?? 0)}</div>
+                      </div>
+                    </div>
+                    <div className="flex items-center gap-px h-10">
+                      {p.points.map(pt => (
+                        <div
+                          key={pt.date}
+                          title={`${pt.date} ${pt.samples} 只 ${signed(pt.avg_return)}`}
+                          className="flex-1 flex flex-col justify-center h-full"
+                        >
+                          <div
+                            className={pt.avg_return >= 0 ? 'bg-functional-up/70 self-stretch' : 'bg-functional-down/70 self-stretch'}
+                            style={{ height: `${(Math.abs(pt.avg_return) / maxAbs) * 50}%`, marginTop: pt.avg_return >= 0 ? 'auto' : '50%', marginBottom: pt.avg_return >= 0 ? '50%' : 'auto' }}
+                          />
+                        </div>
+                      ))}
+                    </div>
+                  </>
+                )}
+              </div>
+            );
+          })
+        )}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,151 @@
+import { useEffect, useState } from 'react';
+import { Select, Input, Switch, App } from 'antd';
+import { Save, PlugZap, RefreshCw, Loader2 } from 'lucide-react';
+import { useSettingsStore } from '../stores/settingsStore';
+import { safeInvoke as invoke } from '../hooks/useTauri';
+import { AppSettings, SyncConfig, SyncStatus } from '../types';
+
+interface Props {
+  settings: AppSettings;
+}
+
+const DEFAULT_SYNC: SyncConfig = {
+  enabled: false,
+  backend: 'webdav',
+  endpoint: '',
+  username: '',
+  password: '',
+  bucket: '',
+  region: 'us-east-1',
+  remote_path: 'stock-helper',
+};
+
+/** 多设备同步：WebDAV / S3 配置、测试连接与手动同步 */
+export default function SyncPanel({ settings }: Props) {
+  const { message } = App.useApp();
+  const { saveSettings, syncNow } = useSettingsStore();
+  const [draft, setDraft] = useState<SyncConfig>(settings.sync ?? DEFAULT_SYNC);
+  const [status, setStatus] = useState<SyncStatus | null>(null);
+  const [testing, setTesting] = useState(false);
+  const [syncing, setSyncing] = useState(false);
+
+  useEffect(() => {
+    invoke<SyncStatus>('get_sync_status').then(setStatus).catch(() => {});
+  }, []);
+
+  const isS3 = draft.backend === 's3';
+  const dirty = JSON.stringify(draft) !== JSON.stringify(settings.sync ?? DEFAULT_SYNC);
+  const update = (patch: Partial<SyncConfig>) => setDraft(prev => ({ ...prev, ...patch }));
+
+  const handleSave = async () => {
+    await saveSettings({ ...settings, sync: draft });
+    message.success('同步配置已保存');
+  };
+
+  const handleTest = async () => {
+    setTesting(true);
+    try {
+      message.success(await invoke<string>('test_sync_connection', { config: draft }));
+    } catch (e) {
+      message.error(`${e}`);
+    } finally {
+      setTesting(false);
+    }
+  };
+
+  const handleSync = async () => {
+    setSyncing(true);
+    try {
+      if (dirty) await saveSettings({ ...settings, sync: draft });
+      const result = await syncNow();
+      message.success(result.message);
+      setStatus(await invoke<SyncStatus>('get_sync_status'));
+    } catch (e) {
+      message.error(`${e}`);
+    } finally {
+      setSyncing(false);
+    }
+  };
+
+  const buttonClass =
+    'flex items-center gap-1.5 px-3 py-1.5 rounded-lg text-sm text-txt-secondary hover:text-txt-primary hover:bg-bg-elevated transition-colors cursor-pointer disabled:opacity-50 disabled:cursor-not-allowed';
+  const labelClass = 'text-xs text-txt-muted w-24 shrink-0';
+
+  return (
+    <div className="p-4 rounded-lg border border-[#30363D] bg-bg-card space-y-4">
+      <div className="flex items-center justify-between">
+        <div>
+          <span className="text-sm text-txt-primary">自动同步</span>
+          <p className="text-xs text-txt-muted mt-1">
+            在多台设备间同步自选股、设置（不含 API Key）与近 90 天的 AI 分析记录；两端同时修改时以本机为准
+          </p>
+        </div>
+        <Switch checked={draft.enabled} onChange={enabled => update({ enabled })} />
+      </div>
+
+      <div className="space-y-3">
+        <div className="flex items-center gap-3">
+          <span className={labelClass}>同步方式</span>
+          <Select
+            className="flex-1"
+            value={draft.backend}
+            onChange={backend => update({ backend })}
+            options={[
+              { value: 'webdav', label: 'WebDAV（坚果云、Nextcloud 等）' },
+              { value: 's3', label: 'S3 兼容对象存储' },
+            ]}
+          />
+        </div>
+        <div className="flex items-center gap-3">
+          <span className={labelClass}>服务地址</span>
+          <Input
+            placeholder={isS3 ? 'https://s3.amazonaws.com' : 'https://dav.jianguoyun.com/dav'}
+            value={draft.endpoint}
+            onChange={e => update({ endpoint: e.target.value })}
+          />
+        </div>
+        {isS3 && (
+          <div className="flex items-center gap-3">
+            <span className={labelClass}>Bucket / 区域</span>
+            <Input placeholder="bucket" value={draft.bucket} onChange={e => update({ bucket: e.target.value })} />
+            <Input placeholder="us-east-1" value={draft.region} onChange={e => update({ region: e.target.value })} />
+          </div>
+        )}
+        <div className="flex items-center gap-3">
+          <span className={labelClass}>{isS3 ? 'Access Key' : '用户名'}</span>
+          <Input value={draft.username} onChange={e => update({ username: e.target.value })} />
+        </div>
+        <div className="flex items-center gap-3">
+          <span className={labelClass}>{isS3 ? 'Secret Key' : '密码'}</span>
+          <Input.Password value={draft.password} onChange={e => update({ password: e.target.value })} />
+        </div>
+        <div className="flex items-center gap-3">
+          <span className={labelClass}>{isS3 ? '对象前缀' : '远端目录'}</span>
+          <Input placeholder="stock-helper" value={draft.remote_path} onChange={e => update({ remote_path: e.target.value })} />
+        </div>
+      </div>
+
+      <div className="flex items-center justify-between pt-3 border-t border-[#30363D]">
+        <span className="text-xs text-txt-muted">
+          {status?.last_result
+            ? `上次同步 ${status.last_result.synced_at}：${status.last_result.message}`
+            : '尚未同步'}
+        </span>
+        <div className="flex items-center gap-2">
+          <button className={buttonClass} disabled={!dirty} onClick={handleSave}>
+            <Save size={14} />
+            保存
+          </button>
+          <button className={buttonClass} disabled={testing || !draft.endpoint.trim()} onClick={handleTest}>
+            {testing ? <Loader2 size={14} className="animate-spin" /> : <PlugZap size={14} />}
+            测试连接
+          </button>
+          <button className={buttonClass} disabled={syncing || !draft.endpoint.trim()} onClick={handleSync}>
+            {syncing ? <Loader2 size={14} className="animate-spin" /> : <RefreshCw size={14} />}
+            立即同步
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -1,4 +1,4 @@
-import { TechnicalSignal, StockTechnicalAnalysis } from '../types';
+import { TechnicalSignal, StockTechnicalAnalysis, VolumeProfile } from '../types';
 import { TrendingUp, TrendingDown, Minus, Activity, BarChart2, Layers } from 'lucide-react';
 
 interface TechnicalPanelProps {
@@ -75,6 +75,44 @@ function SignalCard({ signal }: { signal: TechnicalSignal }) {
   );
 }
 
+function VolumeProfileChart({ profile }: { profile: VolumeProfile }) {
+  const maxVolume = Math.max(...profile.bins.map((b) => b.volume), 1);
+  const bins = [...profile.bins].reverse();
+  return (
+    <div className="px-3 pb-3">
+      <div className="flex items-center justify-between mb-2">
+        <span className="text-xs text-txt-muted font-medium">
+          成交量分布（近 {profile.days} 日{profile.source === 'm5' ? '，5分钟线' : ''}）
+        </span>
+        <span className="text-[10px] text-txt-muted">
+          密集成交 {profile.poc_price.toFixed(2)}，价值区 {profile.value_area_low.toFixed(2)}-{profile.value_area_high.toFixed(2)}
+        </span>
+      </div>
+      <div className="space-y-px">
+        {bins.map((bin) => {
+          const inValueArea = bin.price_high > profile.value_area_low && bin.price_low < profile.value_area_high;
+          const isPoc = profile.poc_price >= bin.price_low && profile.poc_price < bin.price_high;
+          return (
+            <div key={bin.price_low} className="flex items-center gap-2 h-1.5">
+              <span className="w-12 text-[9px] text-txt-muted text-right leading-none">{bin.price_low.toFixed(2)}</span>
+              <div className="flex-1 h-full">
+                <div
+                  className={`h-full rounded-sm ${isPoc ? 'bg-primary-gold' : inValueArea ? 'bg-primary-gold/50' : 'bg-[#30363D]'}`}
+                  style={{ width: `${(bin.volume / maxVolume) * 100}%` }}
+                />
+              </div>
+            </div>
+          );
+        })}
+      </div>
+    </div>
+  );
+}
+
+function formatRiskValue(value: number | null, suffix = '%'): string {
+  return value === null ? '--' : `${value.toFixed(2)}${suffix}`;
+}
+
 export default function TechnicalPanel({ analysis, onDiagnose }: TechnicalPanelProps) {
   const maInfo = getAlignmentInfo(analysis.ma_alignment);
   const MaIcon = maInfo.icon;
@@ -146,7 +184,56 @@ export default function TechnicalPanel({ analysis, onDiagnose }: TechnicalPanelP
         </div>
       </div>
 
-      {/* Signal details */}
+      {/* Risk metrics */}
+      {analysis.risk && (
+        <div className="grid grid-cols-4 gap-2 px-3 pb-3">
+          {[
+            { label: '20日波动率', value: formatRiskValue(analysis.risk.volatility_20d) },
+            { label: '60日波动率', value: formatRiskValue(analysis.risk.volatility_60d) },
+            { label: 'Beta(沪深300)', value: formatRiskValue(analysis.risk.beta, '') },
+            { label: '近一年最大回撤', value: formatRiskValue(analysis.risk.max_drawdown_1y) },
+          ].map((item) => (
+            <div key={item.label} className="rounded-lg border border-[#30363D]/50 bg-bg-elevated/30 px-2.5 py-2">
+              <p className="text-[10px] text-txt-muted">{item.label}</p>
+              <span className="text-xs font-bold text-txt-secondary">{item.value}</span>
+            </div>
+          ))}
+        </div>
+      )}
+
+            {/* Volume profile */}
+      {analysis.volume_profile && (
+        <VolumeProfileChart profile={analysis.volume_profile} />
+      )}
+
+      {/* Open gaps */}
+      {analysis.gaps.open_gaps.length > 0 && (
+        <div className="px-3 pb-3">
+          <div className="flex items-center justify-between mb-2">
+            <span className="text-xs text-txt-muted font-medium">未回补缺口</span>
+            {analysis.gaps.fill_rate_pct !== null && (
+              <span className="text-[10px] text-txt-muted">
+                历史回补率 {analysis.gaps.fill_rate_pct.toFixed(0)}%
+                {analysis.gaps.median_fill_days !== null && `，中位回补 ${analysis.gaps.median_fill_days} 个交易日`}
+              </span>
+            )}
+          </div>
+          <div className="flex flex-wrap gap-2">
+            {analysis.gaps.open_gaps.slice(0, 6).map((gap) => (
+              <span
+                key={gap.date}
+                className={`text-[10px] px-2 py-1 rounded border border-[#30363D]/50 bg-bg-base/50 ${
+                  gap.direction === 'up' ? 'text-functional-down' : 'text-functional-up'
+                }`}
+              >
+                {gap.direction === 'up' ? '↑' : '↓'} {gap.lower.toFixed(2)}-{gap.upper.toFixed(2)}（{gap.date}）
+              </span>
+            ))}
+          </div>
+        </div>
+      )}
+
+            {/* Signal details */}
       {analysis.signals.length > 0 && (
         <div className="px-3 pb-2">
           <div className="flex items-center justify-between mb-2">
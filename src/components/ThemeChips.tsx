@@ -0,0 +1,44 @@
+import { useEffect, useState } from 'react';
+import { safeInvoke as invoke } from '../hooks/useTauri';
+import { ThemeExposure } from '../types';
+import logger from '../utils/logger';
+
+interface Props {
+  code: string;
+  /** 显示的题材数 */
+  limit?: number;
+}
+
+/** 个股主要题材标签：按暴露权重取前几个，悬停查看板块涨幅与驱动快讯 */
+export default function ThemeChips({ code, limit = 3 }: Props) {
+  const [exposure, setExposure] = useState<ThemeExposure | null>(null);
+
+  useEffect(() => {
+    setExposure(null);
+    invoke<ThemeExposure>('get_stock_theme_exposure', { code })
+      .then(result => setExposure(result ?? null))
+      .catch(e => logger.error(`Failed to load theme exposure: ${e}`));
+  }, [code]);
+
+  if (!exposure || exposure.themes.length === 0) return null;
+
+  return (
+    <div className="flex items-center gap-1">
+      {exposure.themes.slice(0, limit).map(t => (
+        <span
+          key={t.board_code}
+          title={[
+            `${t.name} 权重 ${(t.weight * 100).toFixed(1)}% · 板块今日 ${t.change_pct >= 0 ? '+' : ''}${t.change_pct.toFixed(2)}%`,
+            ...(t.newly_added ? ['近期新纳入该板块'] : []),
+            ...(t.headlines.length > 0 ? ['驱动快讯：', ...t.headlines] : ['近期快讯未提及']),
+          ].join('\n')}
+          className="px-1.5 py-0.5 rounded text-[10px] bg-functional-info/10 text-functional-info border border-functional-info/20 cursor-default"
+        >
+          {t.name}
+          {t.newly_added && <span className="ml-0.5 text-primary-gold">新</span>}
+          {t.mentions > 0 && <span className="ml-0.5 text-txt-muted">·{t.mentions}</span>}
+        </span>
+      ))}
+    </div>
+  );
+}
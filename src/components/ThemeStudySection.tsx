@@ -0,0 +1,102 @@
+import { useState } from 'react';
+import { Play, Loader2 } from 'lucide-react';
+import { safeInvoke as invoke } from '../hooks/useTauri';
+import { ThemeStudyResult } from '../types';
+import logger from '../utils/logger';
+
+const PERIODS = [30, 90, 180];
+
+export function Pct({ value }: { value: number | null }) {
+  if (value == null) return <span className="text-txt-muted">-</span>;
+  const color = value > 0 ? 'text-functional-up' : value < 0 ? 'text-functional-down' : 'text-txt-secondary';
+  return <span className={color}>{value > 0 ? '+' : ''}{value.toFixed(2)}%</span>;
+}
+
+/** 题材回测：快讯提及各题材后成分股的平均远期收益，判断哪些题材快讯真正有预测力 */
+export default function ThemeStudySection() {
+  const [days, setDays] = useState(90);
+  const [result, setResult] = useState<ThemeStudyResult | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  const [loading, setLoading] = useState(false);
+
+  const handleRun = async () => {
+    setLoading(true);
+    setError(null);
+    try {
+      setResult(await invoke<ThemeStudyResult>('run_theme_event_study', { days }));
+    } catch (e) {
+      logger.error(`Theme event study failed: ${e}`);
+      setError(`${e}`);
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  return (
+    <div className="px-4 py-3 space-y-3">
+      <p className="text-xs font-medium text-txt-primary">题材快讯</p>
+      <div className="flex items-center gap-2">
+        {PERIODS.map(p => (
+          <button
+            key={p}
+            onClick={() => setDays(p)}
+            className={`px-2 py-1 rounded text-[11px] border transition-colors cursor-pointer ${
+              days === p ? 'border-primary-gold/40 text-primary-gold bg-primary-gold/5' : 'border-[#30363D] text-txt-secondary hover:text-txt-primary'
+            }`}
+          >
+            近{p}天
+          </button>
+        ))}
+        <div className="flex-1" />
+        <button
+          onClick={handleRun}
+          disabled={loading}
+          className="flex items-center gap-1 px-3 py-1.5 rounded-lg text-xs font-medium bg-primary-gold/10 text-primary-gold hover:bg-primary-gold/20 transition-colors cursor-pointer disabled:opacity-40"
+        >
+          {loading ? <Loader2 size={12} className="animate-spin" /> : <Play size={12} />}
+          开始回测
+        </button>
+      </div>
+      <p className="text-[10px] text-txt-muted">
+        用归档的财联社快讯给概念板块打标，统计成分股此后 1/5/10 日平均收益与相对沪深300的超额收益；只统计本地已同步日线的股票，样本 ≥ 30 且 |t| ≥ 2 标记为显著
+      </p>
+      {error && <p className="text-[11px] text-functional-up">{error}</p>}
+      {result && (
+        <p className="text-[11px] text-txt-muted">
+          {result.start} ~ {result.end}，快讯 {result.news_count} 条，{result.themes.length} 个题材有样本
+        </p>
+      )}
+      {result && result.themes.length > 0 && (
+        <table className="w-full text-xs">
+          <thead>
+            <tr className="text-txt-muted text-[10px] text-right">
+              <th className="text-left font-normal pb-1">题材</th>
+              <th className="font-normal pb-1">事件/样本</th>
+              <th className="font-normal pb-1">1日</th>
+              <th className="font-normal pb-1">5日</th>
+              <th className="font-normal pb-1">5日超额</th>
+              <th className="font-normal pb-1">胜率</th>
+              <th className="font-normal pb-1">t</th>
+            </tr>
+          </thead>
+          <tbody>
+            {result.themes.map(t => (
+              <tr key={t.board_code} className="text-right border-t border-[#30363D]/50 hover:bg-bg-elevated">
+                <td className="text-left py-1.5">
+                  <span className="text-txt-primary">{t.theme}</span>
+                  {t.significant && <span className="ml-1 text-[10px] text-primary-gold">显著</span>}
+                </td>
+                <td className="font-din text-txt-secondary">{t.events}/{t.samples}</td>
+                <td className="font-din"><Pct value={t.avg_return_1d} /></td>
+                <td className="font-din"><Pct value={t.avg_return_5d} /></td>
+                <td className="font-din"><Pct value={t.avg_excess_5d} /></td>
+                <td className="font-din text-txt-secondary">{t.win_rate_5d == null ? '-' : `${t.win_rate_5d.toFixed(0)}%`}</td>
+                <td className="font-din text-txt-secondary">{t.t_stat_5d == null ? '-' : t.t_stat_5d.toFixed(1)}</td>
+              </tr>
+            ))}
+          </tbody>
+        </table>
+      )}
+    </div>
+  );
+}
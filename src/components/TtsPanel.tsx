@@ -0,0 +1,66 @@
+import { useState } from 'react';
+import { Input, InputNumber, App } from 'antd';
+import { Save } from 'lucide-react';
+import { useSettingsStore } from '../stores/settingsStore';
+import { AppSettings, TtsConfig } from '../types';
+
+interface Props {
+  settings: AppSettings;
+}
+
+/** 语音播报配置：OpenAI 兼容的 TTS 接口，用于把早盘备忘、诊断结论转为 mp3 */
+export default function TtsPanel({ settings }: Props) {
+  const { message } = App.useApp();
+  const { saveSettings } = useSettingsStore();
+  const [config, setConfig] = useState<TtsConfig>(settings.tts);
+
+  const dirty = JSON.stringify(config) !== JSON.stringify(settings.tts);
+  const update = (patch: Partial<TtsConfig>) => setConfig(prev => ({ ...prev, ...patch }));
+
+  const handleSave = async () => {
+    await saveSettings({ ...settings, tts: config });
+    message.success('语音播报配置已保存');
+  };
+
+  const labelClass = 'text-xs text-txt-muted w-24 shrink-0';
+
+  return (
+    <div className="p-4 rounded-lg border border-[#30363D] bg-bg-card space-y-4">
+      <p className="text-xs text-txt-muted">
+        兼容 OpenAI /audio/speech 接口。生成后可在「大盘 → 早盘语音」与诊断面板中播放，文件保存在应用数据目录的 audio 文件夹
+      </p>
+
+      <div className="flex items-center gap-3">
+        <span className={labelClass}>接口地址</span>
+        <Input value={config.base_url} onChange={e => update({ base_url: e.target.value })} placeholder="https://api.openai.com/v1" />
+      </div>
+      <div className="flex items-center gap-3">
+        <span className={labelClass}>API Key</span>
+        <Input.Password value={config.api_key} onChange={e => update({ api_key: e.target.value })} placeholder="sk-..." />
+      </div>
+      <div className="flex items-center gap-3">
+        <span className={labelClass}>模型</span>
+        <Input value={config.model} onChange={e => update({ model: e.target.value })} placeholder="tts-1" />
+      </div>
+      <div className="flex items-center gap-3">
+        <span className={labelClass}>音色</span>
+        <Input value={config.voice} onChange={e => update({ voice: e.target.value })} placeholder="alloy" />
+      </div>
+      <div className="flex items-center gap-3">
+        <span className={labelClass}>语速</span>
+        <InputNumber min={0.25} max={4} step={0.25} value={config.speed} onChange={v => v !== null && update({ speed: v })} />
+      </div>
+
+      <div className="flex justify-end">
+        <button
+          className="flex items-center gap-1.5 px-3 py-1.5 rounded-lg text-sm text-txt-secondary hover:text-txt-primary hover:bg-bg-elevated transition-colors cursor-pointer disabled:opacity-50 disabled:cursor-not-allowed"
+          disabled={!dirty}
+          onClick={handleSave}
+        >
+          <Save size={14} />
+          保存
+        </button>
+      </div>
+    </div>
+  );
+}
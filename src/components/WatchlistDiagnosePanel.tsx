@@ -4,6 +4,9 @@ import remarkGfm from 'remark-gfm';
 import { X, Copy, Check, RefreshCw, Database, BarChart3, TrendingUp, DollarSign } from 'lucide-react';
 import { useState } from 'react';
 import { useWatchlistStore } from '../stores/watchlistStore';
+import { safeInvoke as invoke } from '../hooks/useTauri';
+import { AIAnalysisResult, AudioExport } from '../types';
+import AudioExportButton from './AudioExportButton';
 
 interface Props {
   code: string;
@@ -42,6 +45,13 @@ export default function WatchlistDiagnosePanel({ code, name, onClose }: Props) {
     setTimeout(() => setCopied(false), 2000);
   };
 
+  /** 诊断完成后已落库，取该股最新一条分析记录转语音 */
+  const exportAudio = async () => {
+    const [latest] = await invoke<AIAnalysisResult[]>('get_analysis_history', { code, limit: 1 });
+    if (!latest) throw new Error('未找到诊断记录');
+    return invoke<AudioExport>('export_analysis_audio', { id: latest.id });
+  };
+
   const showToolProgress = diagnosing && diagnoseToolCalls.length > 0 && !diagnoseContent;
 
   return (
@@ -123,6 +133,9 @@ export default function WatchlistDiagnosePanel({ code, name, onClose }: Props) {
             {copied ? '已复制' : '复制'}
           </button>
         )}
+        {diagnoseDone && diagnoseContent && (
+          <AudioExportButton key={diagnoseContent.length} sourceKind="analysis" onExport={exportAudio} />
+        )}
         {diagnosing && (
           <span className="text-xs text-txt-muted ml-auto">
             {diagnoseToolCalls.length > 0 && !diagnoseContent
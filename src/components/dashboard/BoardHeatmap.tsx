@@ -0,0 +1,114 @@
+import { useState } from 'react';
+import { Treemap, ResponsiveContainer, Tooltip } from 'recharts';
+import { LayoutGrid } from 'lucide-react';
+import { BoardQuote, MarketHeatmap } from '../../types';
+
+interface Props {
+  heatmap: MarketHeatmap;
+}
+
+type Kind = 'industry' | 'concept';
+
+/** 概念板块数量较多，只展示成交额最大的部分，避免色块过小 */
+const MAX_TILES = 80;
+
+/** A股配色：涨红跌绿，涨跌幅越大颜色越深（±5% 封顶） */
+function tileColor(pct: number): string {
+  const t = Math.min(Math.abs(pct) / 5, 1);
+  if (pct > 0) return `rgba(231, 76, 60, ${0.25 + t * 0.75})`;
+  if (pct < 0) return `rgba(46, 204, 113, ${0.25 + t * 0.75})`;
+  return '#30363D';
+}
+
+function formatAmount(v: number): string {
+  return v >= 1e8 ? `${(v / 1e8).toFixed(1)}亿` : `${(v / 1e4).toFixed(0)}万`;
+}
+
+interface TileProps {
+  x?: number;
+  y?: number;
+  width?: number;
+  height?: number;
+  name?: string;
+  change_pct?: number;
+}
+
+function Tile({ x = 0, y = 0, width = 0, height = 0, name = '', change_pct = 0 }: TileProps) {
+  const showText = width > 48 && height > 28;
+  return (
+    <g>
+      <rect x={x} y={y} width={width} height={height} fill={tileColor(change_pct)} stroke="#0D1117" strokeWidth={1} />
+      {showText && (
+        <>
+          <text x={x + width / 2} y={y + height / 2 - 3} textAnchor="middle" fill="#E6EDF3" fontSize={11}>
+            {name}
+          </text>
+          <text x={x + width / 2} y={y + height / 2 + 11} textAnchor="middle" fill="#E6EDF3" fontSize={10}>
+            {change_pct >= 0 ? '+' : ''}{change_pct.toFixed(2)}%
+          </text>
+        </>
+      )}
+    </g>
+  );
+}
+
+function TileTooltip({ active, payload }: { active?: boolean; payload?: { payload: BoardQuote }[] }) {
+  if (!active || !payload?.length) return null;
+  const b = payload[0].payload;
+  return (
+    <div className="rounded-md bg-bg-elevated border border-[#30363D] px-2.5 py-2 text-[11px] text-txt-secondary space-y-0.5">
+      <div className="text-xs font-semibold text-txt-primary">{b.name}</div>
+      <div>涨跌幅 {b.change_pct >= 0 ? '+' : ''}{b.change_pct.toFixed(2)}% · 换手 {b.turnover_rate.toFixed(2)}%</div>
+      <div>成交额 {formatAmount(b.amount)} · 主力净流入 {formatAmount(b.main_net_inflow)}</div>
+      <div>上涨 {b.up_count} / 下跌 {b.down_count}</div>
+      {b.leader_name && <div>领涨 {b.leader_name} {b.leader_change_pct >= 0 ? '+' : ''}{b.leader_change_pct.toFixed(2)}%</div>}
+    </div>
+  );
+}
+
+export default function BoardHeatmap({ heatmap }: Props) {
+  const [kind, setKind] = useState<Kind>('industry');
+  const boards = kind === 'industry' ? heatmap.industries : heatmap.concepts;
+  // 色块面积按成交额
+  const data = [...boards]
+    .filter(b => b.amount > 0)
+    .sort((a, b) => b.amount - a.amount)
+    .slice(0, MAX_TILES)
+    .map(b => ({ ...b, size: b.amount }));
+
+  return (
+    <div className="rounded-xl bg-bg-card border border-[#30363D] p-3">
+      <div className="flex items-center justify-between mb-2.5">
+        <div className="flex items-center gap-1.5">
+          <LayoutGrid size={13} className="text-functional-info" />
+          <span className="text-xs font-semibold text-txt-primary">板块热力图</span>
+          <span className="text-[10px] text-txt-muted">面积=成交额 · {heatmap.updated_at}</span>
+        </div>
+        <div className="flex gap-1">
+          {(['industry', 'concept'] as Kind[]).map(k => (
+            <button
+              key={k}
+              onClick={() => setKind(k)}
+              className={`px-2 py-0.5 text-[11px] rounded cursor-pointer transition-colors ${
+                kind === k ? 'bg-functional-info/20 text-functional-info' : 'text-txt-muted hover:text-txt-secondary'
+              }`}
+            >
+              {k === 'industry' ? '行业' : '概念'}
+            </button>
+          ))}
+        </div>
+      </div>
+      {data.length === 0 ? (
+        <div className="h-[320px] flex items-center justify-center text-xs text-txt-muted">暂无板块数据</div>
+      ) : (
+        <div className="h-[320px]">
+          <ResponsiveContainer width="100%" height="100%">
+            <Treemap data={data} dataKey="size" nameKey="name" isAnimationActive={false} content={<Tile />}>
+              <Tooltip content={<TileTooltip />} />
+            </Treemap>
+          </ResponsiveContainer>
+        </div>
+      )}
+    </div>
+  );
+}
@@ -1,5 +1,7 @@
 import { ArrowUp, ArrowDown, Sparkles, RefreshCw } from 'lucide-react';
-import { MarketOverview } from '../../types';
+import { AudioExport, MarketOverview } from '../../types';
+import { safeInvoke as invoke } from '../../hooks/useTauri';
+import AudioExportButton from '../AudioExportButton';
 
 interface Props {
   overview: MarketOverview | null;
@@ -25,12 +27,18 @@ const statusColor: Record<string, string> = {
   '集合竞价结束': 'bg-functional-warn',
 };
 
+function localDate(): string {
+  const d = new Date();
+  return `${d.getFullYear()}-${String(d.getMonth() + 1).padStart(2, '0')}-${String(d.getDate()).padStart(2, '0')}`;
+}
+
 export default function MarketHeader({ overview, aiComment, aiCommentLoading, onRefreshAi }: Props) {
   if (!overview) return null;
 
   const isTrading = overview.market_status.includes('交易中');
   const dotColor = statusColor[overview.market_status] || 'bg-txt-muted';
   const vc = overview.volume_compare;
+  const today = localDate();
   const isUp = vc.diff > 0;
 
   return (
@@ -68,7 +76,16 @@ export default function MarketHeader({ overview, aiComment, aiCommentLoading, on
 
         </div>
 
-        <span className="text-xs text-txt-muted font-din">{overview.update_time}</span>
+        <div className="flex items-center gap-3">
+          {/* 早盘备忘语音，通勤时收听 */}
+          <AudioExportButton
+            sourceKind="briefing"
+            sourceId={`${today}/morning`}
+            label="早盘语音"
+            onExport={() => invoke<AudioExport>('export_briefing_audio', { date: today, kind: 'morning' })}
+          />
+          <span className="text-xs text-txt-muted font-din">{overview.update_time}</span>
+        </div>
       </div>
 
       {/* AI 解说卡片 */}
@@ -1,15 +1,43 @@
 import { invoke as tauriInvoke } from '@tauri-apps/api/core';
 import { listen as tauriListen, type EventCallback, type UnlistenFn } from '@tauri-apps/api/event';
 import logger from '../utils/logger';
+import type { AppErrorCode, AppErrorPayload } from '../types';
 
 export const isTauri = typeof window !== 'undefined' && !!(window as any).__TAURI_INTERNALS__;
 
+/** 命令错误：message 为中文提示，code 用于区分网络、鉴权、限流等情况；toString 只返回提示，兼容 `${e}` 的用法 */
+export class CommandError extends Error {
+  code: AppErrorCode;
+
+  constructor(code: AppErrorCode, message: string) {
+    super(message);
+    this.name = 'CommandError';
+    this.code = code;
+  }
+
+  toString() {
+    return this.message;
+  }
+}
+
+function toCommandError(e: unknown): CommandError {
+  if (e && typeof e === 'object' && 'code' in e && 'message' in e) {
+    const payload = e as AppErrorPayload;
+    return new CommandError(payload.code, payload.message);
+  }
+  return new CommandError('internal', e instanceof Error ? e.message : String(e));
+}
+
 export async function safeInvoke<T>(cmd: string, args?: Record<string, unknown>): Promise<T> {
   if (!isTauri) {
     logger.warn(`[mock] invoke("${cmd}") — not in Tauri runtime`);
     return getMockData(cmd) as T;
   }
-  return tauriInvoke<T>(cmd, args);
+  try {
+    return await tauriInvoke<T>(cmd, args);
+  } catch (e) {
+    throw toCommandError(e);
+  }
 }
 
 export async function safeListen<T>(event: string, handler: EventCallback<T>): Promise<UnlistenFn> {
@@ -36,6 +64,73 @@ function getMockData(cmd: string): unknown {
         max_pick_token_budget: 100000,
         agent_prompts: [],
         active_pick_prompt_id: null,
+        signal_config: {
+          lookback_days: 5,
+          kdj_overbought: 80,
+          kdj_oversold: 20,
+          rsi_overbought: 80,
+          rsi_oversold: 20,
+          volume_surge_ratio: 2,
+          divergence_window: 30,
+          alert_min_strength: 4,
+          enabled_signals: [],
+        },
+        morning_briefing_enabled: true,
+        strategy_zones: [],
+        pick_preferences: {
+          risk_appetite: '',
+          include_sectors: [],
+          exclude_sectors: [],
+          min_market_cap: null,
+          max_market_cap: null,
+          holding_horizon: '',
+          max_price: null,
+          universe: '',
+          min_rps: null,
+          min_dividend_yield: null,
+          min_fcf_yield: null,
+          max_debt_ratio: null,
+          min_profit_yoy: null,
+        },
+        job_settings: {},
+        debug_logging: false,
+        profiles: [],
+        active_profile_id: null,
+        sync: {
+          enabled: false,
+          backend: 'webdav',
+          endpoint: '',
+          username: '',
+          password: '',
+          bucket: '',
+          region: 'us-east-1',
+          remote_path: 'stock-helper',
+        },
+        api_server: { enabled: false, port: 18520, token: '', mcp_enabled: false },
+        notify_targets: [],
+        close_to_tray: true,
+        quick_search_hotkey: 'CommandOrControl+Alt+K',
+        risk_rules: {
+          alert_enabled: true,
+          max_position_pct: 20,
+          max_sector_pct: 40,
+          max_cluster_pct: 50,
+          correlation_threshold: 0.8,
+          forbid_st: true,
+          max_drawdown_pct: 15,
+        },
+        tts: { base_url: '', api_key: '', model: 'tts-1', voice: 'alloy', speed: 1 },
+        screener_presets: [],
+        momentum_vs_benchmark: false,
+        large_order_threshold: 1000000,
+        auction_scan_watchlist_only: false,
+        quote_provider_order: [],
+        custom_quote_providers: [],
+        regime_factor_weights: [
+          { trend: 'up', volatility: null, weights: { quality: 1, growth: 1.2, value: 0.6, momentum: 2, flow: 1.5 } },
+          { trend: 'down', volatility: null, weights: { quality: 2, growth: 1, value: 2, momentum: 0.5, flow: 0.8 } },
+        ],
+        stream_batch: { enabled: true, flush_interval_ms: 50, max_chars: 200 },
       };
     case 'search_stocks':
       return [];
@@ -58,11 +153,16 @@ function getMockData(cmd: string): unknown {
           kdj_k: [], kdj_d: [], kdj_j: [],
           rsi6: [], rsi12: [], rsi24: [],
           boll_upper: [], boll_middle: [], boll_lower: [],
+          donchian_upper: [], donchian_lower: [],
+          keltner_upper: [], keltner_middle: [], keltner_lower: [],
         },
         signals: [],
         ma_alignment: 'tangled',
         volume_price_relation: '正常',
         summary: '非 Tauri 环境，无法获取技术分析数据',
+        risk: null,
+        volume_profile: null,
+        gaps: { open_gaps: [], total: 0, filled: 0, fill_rate_pct: null, median_fill_days: null, avg_fill_days: null },
       };
     case 'ai_diagnose_stock':
       return null;
@@ -91,12 +191,103 @@ function getMockData(cmd: string): unknown {
       return null;
     case 'get_cached_picks':
       return null;
+    case 'list_pick_sessions':
+      return [];
+    case 'get_pick_session':
+      return null;
     case 'stop_ai_pick':
       return null;
     case 'analyze_loss_reasons':
       return null;
     case 'test_ai_config':
-      return '模型连接正常（Mock 环境）';
+      return { model: 'mock-model', reply: '连接成功', latency_ms: 320, supports_tools: true, tool_probe_message: null };
+    case 'get_scheduled_jobs':
+      return [];
+    case 'get_diagnostics':
+      return {
+        generated_at: '2024-06-06 15:30:00',
+        app_version: '0.0.0',
+        os: 'mock',
+        db_path: 'stock_helper.db',
+        db_size_bytes: 0,
+        sources: [],
+        schema_drifts: [],
+        caches: [],
+        jobs: [],
+        last_successes: {},
+        active_tasks: 0,
+        ai_picking: false,
+      };
+    case 'test_notify_target':
+    case 'take_pending_deep_link':
+    case 'open_deep_link':
+    case 'get_audio_export':
+    case 'open_audio_export':
+      return null;
+    case 'export_briefing_audio':
+    case 'export_analysis_audio':
+      return { source_kind: 'briefing', source_id: 'mock', path: '/tmp/mock.mp3', created_at: '2024-06-06 08:30:00' };
+    case 'validate_filter_expression':
+      return null;
+    case 'screen_by_expression':
+      return { expression: '', scanned: 0, matched: 0, stocks: [], explanations: {}, themes: {} };
+    case 'get_fund_flow_breakdown':
+      return null;
+    case 'start_focus_monitor':
+    case 'stop_focus_monitor':
+    case 'get_focus_monitor_status':
+      return { running: false, code: null, prints: [] };
+    case 'scan_auction_gaps':
+      return { time: '2024-06-06 09:23:00', scope: 'market', scanned: 0, gaps: [] };
+    case 'get_auction_gaps':
+      return null;
+    case 'test_custom_quote_provider':
+      return [];
+    case 'get_index_kline':
+      return { code: 'sh000001', name: '上证指数', period: 'day', klines: [] };
+    case 'get_strategy_performance':
+      return [
+        { strategy: 'technical_signal', name: '技术信号', days: 30, hold_days: 5, total: 0, evaluated: 0, avg_return: 0, win_rate: 0, best_return: null, worst_return: null, points: [], updated_at: '' },
+        { strategy: 'ai_instruction', name: 'AI 买入指令', days: 30, hold_days: 5, total: 0, evaluated: 0, avg_return: 0, win_rate: 0, best_return: null, worst_return: null, points: [], updated_at: '' },
+        { strategy: 'ai_pick', name: 'AI 选股', days: 30, hold_days: 5, total: 0, evaluated: 0, avg_return: 0, win_rate: 0, best_return: null, worst_return: null, points: [], updated_at: '' },
+      ];
+    case 'get_market_regime':
+      return {
+        date: '2024-06-06', trend: 'range', volatility: 'normal', style: 'balanced',
+        index_close: 3550.2, ma20: 3560.8, ma60: 3520.4, return_20d: -1.2,
+        volatility_20d: 14.5, volatility_1y: 16.2, large_cap_return_20d: 0.8, small_cap_return_20d: -1.5,
+        above_ma20_pct: 48, volume_ratio: 0.92, summary: '震荡市 · 波动正常 · 大小盘均衡', created_at: '2024-06-06 10:00:00',
+      };
+    case 'run_announcement_study':
+      return { start: '2023-06-06', end: '2024-06-06', announcements: 0, stats: [], updated_at: '' };
+    case 'run_theme_event_study':
+      return { start: '2024-03-08', end: '2024-06-06', news_count: 0, themes: [] };
+    case 'get_stock_theme_exposure':
+      return { code: '', themes: [], updated_at: '' };
+    case 'get_api_server_status':
+      return { running: false, address: null, error: null };
+    case 'list_detached_windows':
+    case 'get_replay_days':
+    case 'get_intraday_replay':
+    case 'get_analysis_history':
+    case 'get_stock_schedules':
+    case 'get_stock_schedule_runs':
+    case 'get_score_history':
+    case 'get_board_member_additions':
+    case 'get_fund_flow_history':
+      return [];
+    case 'ai_analyze_image':
+      return { id: 'mock', code: '', name: '', model_name: 'mock', question: 'AI识图分析', content: '浏览器预览模式下无法调用 AI，请在桌面应用中使用', created_at: '' };
+    case 'ai_router':
+      return { intent: { intent: 'macro', stocks: [], keyword: '' }, content: '浏览器预览模式下无法调用 AI，请在桌面应用中使用' };
+    case 'get_risk_report':
+      return { generated_at: '2024-06-06 15:10:00', positions: [], sectors: [], clusters: [], violations: [] };
+    case 'get_sync_status':
+      return { device_id: 'mock-device', last_synced_at: null, last_result: null };
+    case 'test_sync_connection':
+      return '连接成功，远端暂无同步数据';
+    case 'sync_now':
+      return { synced_at: '2024-06-06 15:30:00', pulled: 0, pushed: true, conflicts: 0, message: '拉取 0 项变更，已上传本地变更' };
     case 'get_market_overview':
       return {
         market_status: '已收盘',
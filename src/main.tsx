@@ -2,8 +2,26 @@ import React from 'react';
 import ReactDOM from 'react-dom/client';
 import { ConfigProvider, App as AntdApp, theme } from 'antd';
 import App from './App';
+import MiniQuote from './pages/MiniQuote';
+import DetachedKline from './pages/DetachedKline';
+import QuickSearch from './pages/QuickSearch';
 import './styles/global.css';
 
+// 独立窗口（迷你行情条、独立 K 线、快速查股）与主窗口共用同一入口，按 window 参数渲染
+function Root() {
+  const params = new URLSearchParams(window.location.search);
+  switch (params.get('window')) {
+    case 'mini_quote':
+      return <MiniQuote />;
+    case 'quick_search':
+      return <QuickSearch />;
+    case 'kline':
+      return <DetachedKline code={params.get('code') || ''} name={params.get('name') || ''} />;
+    default:
+      return <App />;
+  }
+}
+
 ReactDOM.createRoot(document.getElementById('root')!).render(
   <React.StrictMode>
     <ConfigProvider
@@ -23,7 +41,7 @@ ReactDOM.createRoot(document.getElementById('root')!).render(
       }}
     >
       <AntdApp>
-        <App />
+        <Root />
       </AntdApp>
     </ConfigProvider>
   </React.StrictMode>
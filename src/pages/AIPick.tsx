@@ -1,5 +1,5 @@
 import { useState, useEffect, useRef, useCallback } from 'react';
-import { Sparkles, ArrowLeft, Activity, Loader2, Check, RefreshCw, Brain, Zap, ChevronRight, ChevronDown, ChevronUp, Star, TrendingUp, X, Search, Users, MessageSquare, Eye, Square, Settings } from 'lucide-react';
+import { Sparkles, ArrowLeft, Activity, Loader2, Check, RefreshCw, Brain, Zap, ChevronRight, ChevronDown, ChevronUp, Star, TrendingUp, X, Search, Users, MessageSquare, Eye, Square, Settings, RotateCcw } from 'lucide-react';
 import ReactMarkdown from 'react-markdown';
 import remarkGfm from 'remark-gfm';
 import KlineChart from '../components/KlineChart';
@@ -24,8 +24,8 @@ const RATING_CONFIG: Record<string, { label: string; color: string; bg: string;
 
 export default function AIPick() {
   const {
-    picking, aiContent, recommendations, toolCalls, thinkingSteps, error, tokenUsage,
-    startPick, stopPick, loadCachedPicks, reset,
+    picking, aiContent, recommendations, toolCalls, thinkingSteps, error, tokenUsage, interrupted,
+    startPick, stopPick, loadCachedPicks, loadInterrupted, discardInterrupted, reset,
     similarLoading, similarTarget, similarContent, similarPicks, similarToolCalls, similarThinkingSteps, similarError,
     findSimilarStocks, closeSimilar,
   } = useAIPickStore();
@@ -48,6 +48,7 @@ export default function AIPick() {
 
   useEffect(() => {
     loadCachedPicks();
+    loadInterrupted();
     // 确保 settings 加载后同步 prompts
     const initPrompts = async () => {
       const settings = useSettingsStore.getState().settings;
@@ -60,7 +61,7 @@ export default function AIPick() {
     return () => {
       if (unlistenRef.current) unlistenRef.current();
     };
-  }, [loadCachedPicks, loadPrompts]);
+  }, [loadCachedPicks, loadInterrupted, loadPrompts]);
 
   useEffect(() => {
     if (contentRef.current && picking && autoScrollRef.current) {
@@ -87,6 +88,12 @@ export default function AIPick() {
     await startPick();
   }, [picking, reset, startPick]);
 
+  const handleResumePick = useCallback(async () => {
+    if (picking || !interrupted) return;
+    reset();
+    await startPick(interrupted.session_id);
+  }, [picking, interrupted, reset, startPick]);
+
   const handleStopPick = useCallback(async () => {
     await stopPick();
   }, [stopPick]);
@@ -292,6 +299,30 @@ export default function AIPick() {
         )}
       </div>
 
+      {/* 中断的选股：可从断点续跑 */}
+      {interrupted && !picking && (
+        <div className="flex items-center gap-3 px-4 py-2 border-b border-[#30363D] bg-amber-500/10 text-xs">
+          <span className="text-amber-400 font-medium">选股已中断</span>
+          <span className="text-txt-muted truncate">
+            {interrupted.started_at} · {interrupted.final_phase ? '报告生成阶段' : `第 ${interrupted.next_round + 1} 轮`} · 已完成 {interrupted.tool_calls} 次工具调用 · {interrupted.reason}
+          </span>
+          <div className="flex-1" />
+          <button
+            onClick={handleResumePick}
+            className="flex items-center gap-1 px-2.5 py-1 rounded-md bg-cyan-600 text-white hover:bg-cyan-500 transition-colors cursor-pointer"
+          >
+            <RotateCcw size={11} />
+            继续运行
+          </button>
+          <button
+            onClick={discardInterrupted}
+            className="px-2.5 py-1 rounded-md text-txt-muted hover:text-txt-primary hover:bg-bg-elevated transition-colors cursor-pointer"
+          >
+            放弃
+          </button>
+        </div>
+      )}
+
       {/* ===== LIST VIEW ===== */}
       {viewMode === 'list' && (
         <div className="flex-1 flex overflow-hidden">
@@ -700,6 +731,16 @@ function RecommendationCard({
           <span className={`inline-flex items-center px-1.5 py-0.5 rounded text-[10px] font-medium ${ratingCfg.color} ${ratingCfg.bg} border ${ratingCfg.border}`}>
             {ratingCfg.label}
           </span>
+          {rec.verify_status === 'rejected' && (
+            <span title={rec.verify_note} className="text-[10px] px-1.5 py-0.5 rounded bg-red-500/10 text-red-400 border border-red-500/20">
+              校验未通过：{rec.verify_note}
+            </span>
+          )}
+          {rec.verify_status === 'flagged' && (
+            <span title={rec.verify_note} className="text-[10px] px-1.5 py-0.5 rounded bg-amber-500/10 text-amber-400 border border-amber-500/20">
+              {rec.verify_note}
+            </span>
+          )}
           {rec.sector && (
             <span className="text-[10px] px-1.5 py-0.5 rounded bg-[#282E36] text-[#9CA3AF] border border-[#3B424D]">
               {rec.sector}
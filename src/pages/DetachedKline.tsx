@@ -0,0 +1,87 @@
+import { useCallback, useEffect, useState } from 'react';
+import { Loader2, RefreshCw } from 'lucide-react';
+import KlineChart from '../components/KlineChart';
+import { safeInvoke as invoke } from '../hooks/useTauri';
+import { StockTechnicalAnalysis } from '../types';
+import logger from '../utils/logger';
+
+interface Props {
+  code: string;
+  name: string;
+}
+
+/** 刷新间隔（秒） */
+const REFRESH_SECS = 30;
+
+/** 独立 K 线窗口：可拖到副屏单独盯一只股票 */
+export default function DetachedKline({ code, name }: Props) {
+  const [analysis, setAnalysis] = useState<StockTechnicalAnalysis | null>(null);
+  const [loading, setLoading] = useState(false);
+  const [period, setPeriod] = useState<'day' | 'week'>('day');
+  const [activeIndicators, setActiveIndicators] = useState<string[]>(['MA']);
+
+  const load = useCallback(async () => {
+    setLoading(true);
+    try {
+      const result = await invoke<StockTechnicalAnalysis>('get_stock_technical_analysis', { code, name, period });
+      setAnalysis(result);
+    } catch (e) {
+      logger.error(`Load kline for ${code} failed: ${e}`);
+    } finally {
+      setLoading(false);
+    }
+  }, [code, name, period]);
+
+  useEffect(() => {
+    load();
+    const timer = setInterval(load, REFRESH_SECS * 1000);
+    return () => clearInterval(timer);
+  }, [load]);
+
+  const last = analysis?.kline_data[analysis.kline_data.length - 1];
+  const prev = analysis && analysis.kline_data.length > 1 ? analysis.kline_data[analysis.kline_data.length - 2] : null;
+  const changePct = last && prev ? ((last.close - prev.close) / prev.close) * 100 : 0;
+  const changeColor = changePct > 0 ? 'text-functional-up' : changePct < 0 ? 'text-functional-down' : 'text-txt-primary';
+
+  return (
+    <div className="w-full h-full flex flex-col bg-bg-base">
+      <div className="flex items-center gap-2 px-4 py-2 bg-bg-elevated/50 border-b border-[#30363D] flex-shrink-0">
+        <span className="text-sm font-bold text-txt-primary">{analysis?.name || name}</span>
+        <span className="text-xs text-txt-muted font-mono">{code}</span>
+        {last && (
+          <>
+            <span className={`font-mono font-bold text-sm ${changeColor}`}>{last.close.toFixed(2)}</span>
+            <span className={`font-mono text-xs ${changeColor}`}>
+              {changePct > 0 ? '+' : ''}{changePct.toFixed(2)}%
+            </span>
+          </>
+        )}
+        <div className="flex-1" />
+        <button
+          onClick={load}
+          disabled={loading}
+          className="p-1.5 rounded hover:bg-bg-elevated transition-colors cursor-pointer text-txt-secondary hover:text-txt-primary disabled:opacity-50"
+        >
+          <RefreshCw size={13} className={loading ? 'animate-spin' : ''} />
+        </button>
+      </div>
+      <div className="flex-1 min-h-0 overflow-hidden">
+        {analysis ? (
+          <KlineChart
+            klineData={analysis.kline_data}
+            indicators={analysis.indicators}
+            period={period}
+            onPeriodChange={setPeriod}
+            activeIndicators={activeIndicators}
+            onIndicatorsChange={setActiveIndicators}
+          />
+        ) : (
+          <div className="h-full flex items-center justify-center">
+            <Loader2 size={24} className="animate-spin text-primary-gold" />
+            <span className="ml-2 text-xs text-txt-muted">加载K线和技术指标中...</span>
+          </div>
+        )}
+      </div>
+    </div>
+  );
+}
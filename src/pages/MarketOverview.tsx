@@ -5,13 +5,14 @@ import MarketHeader from '../components/dashboard/MarketHeader';
 import IndexCard from '../components/dashboard/IndexCard';
 import SentimentMeter from '../components/dashboard/SentimentMeter';
 import SectorRank from '../components/dashboard/SectorRank';
+import BoardHeatmap from '../components/dashboard/BoardHeatmap';
 import { Loader2 } from 'lucide-react';
 
 export default function MarketOverview() {
   const {
     overview, aiComment, aiCommentLoading, loading, error,
-    indexKlines,
-    fetchOverview, generateAiComment, fetchIndexKlines,
+    indexKlines, heatmap,
+    fetchOverview, generateAiComment, fetchIndexKlines, fetchHeatmap,
     startAutoRefresh, stopAutoRefresh,
   } = useMarketStore();
 
@@ -25,6 +26,7 @@ export default function MarketOverview() {
       loadSettings();
       fetchOverview();
       fetchIndexKlines();
+      fetchHeatmap();
     }
   }, []);
 
@@ -112,6 +114,9 @@ export default function MarketOverview() {
           globalIndexes={overview.global_indexes}
         />
       )}
+
+      {/* 第三行：板块热力图 */}
+      {heatmap && <BoardHeatmap heatmap={heatmap} />}
     </div>
   );
 }
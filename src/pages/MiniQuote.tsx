@@ -0,0 +1,79 @@
+import { useEffect, useState } from 'react';
+import { Pin, PinOff, Loader2 } from 'lucide-react';
+import { getCurrentWindow } from '@tauri-apps/api/window';
+import { useWatchlistStore } from '../stores/watchlistStore';
+import { safeInvoke as invoke, isTauri } from '../hooks/useTauri';
+import { DetachedWindow } from '../types';
+import logger from '../utils/logger';
+
+/** 刷新间隔（秒） */
+const REFRESH_SECS = 10;
+
+/** 迷你行情条：置顶的小窗口，展示自选股现价与涨跌幅，点击打开独立 K 线窗口 */
+export default function MiniQuote() {
+  const { stocks, quotes, quotesLoading, loadStocks, startAutoRefresh, stopAutoRefresh } = useWatchlistStore();
+  const [onTop, setOnTop] = useState(true);
+
+  useEffect(() => {
+    loadStocks().then(() => startAutoRefresh(REFRESH_SECS));
+    return () => stopAutoRefresh();
+  }, []);
+
+  const label = isTauri ? getCurrentWindow().label : 'mini-quote';
+
+  const toggleOnTop = async () => {
+    try {
+      const detached = await invoke<DetachedWindow>('set_detached_window_on_top', { label, onTop: !onTop });
+      setOnTop(detached.always_on_top);
+    } catch (e) {
+      logger.error(`Toggle always on top failed: ${e}`);
+    }
+  };
+
+  const openKline = (code: string, name: string) => {
+    invoke('open_detached_window', { kind: 'kline', code, name }).catch(e =>
+      logger.error(`Open kline window failed: ${e}`)
+    );
+  };
+
+  return (
+    <div className="w-full h-full flex flex-col bg-bg-base select-none">
+      <div className="flex items-center justify-between px-3 py-1.5 border-b border-[#30363D] bg-bg-card">
+        <span className="text-xs text-txt-muted">自选 {stocks.length}</span>
+        <button
+          onClick={toggleOnTop}
+          title={onTop ? '取消置顶' : '置顶'}
+          className="p-1 rounded hover:bg-bg-elevated transition-colors cursor-pointer text-txt-secondary hover:text-txt-primary"
+        >
+          {onTop ? <Pin size={12} /> : <PinOff size={12} />}
+        </button>
+      </div>
+      <div className="flex-1 overflow-auto">
+        {quotesLoading && quotes.length === 0 ? (
+          <div className="flex items-center justify-center h-full">
+            <Loader2 size={16} className="animate-spin text-primary-gold" />
+          </div>
+        ) : quotes.length === 0 ? (
+          <div className="flex items-center justify-center h-full text-xs text-txt-muted">暂无自选股</div>
+        ) : (
+          quotes.map(q => {
+            const color = q.change_pct > 0 ? 'text-functional-up' : q.change_pct < 0 ? 'text-functional-down' : 'text-txt-primary';
+            return (
+              <div
+                key={q.code}
+                onClick={() => openKline(q.code, q.name)}
+                className="flex items-center gap-2 px-3 py-1.5 text-xs hover:bg-bg-elevated cursor-pointer"
+              >
+                <span className="flex-1 truncate text-txt-primary">{q.name}</span>
+                <span className={`font-mono w-16 text-right ${color}`}>{q.price.toFixed(2)}</span>
+                <span className={`font-mono w-16 text-right ${color}`}>
+                  {q.change_pct > 0 ? '+' : ''}{q.change_pct.toFixed(2)}%
+                </span>
+              </div>
+            );
+          })
+        )}
+      </div>
+    </div>
+  );
+}
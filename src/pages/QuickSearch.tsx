@@ -0,0 +1,114 @@
+import { useEffect, useRef, useState } from 'react';
+import { Search, Loader2 } from 'lucide-react';
+import { getCurrentWindow } from '@tauri-apps/api/window';
+import { safeInvoke as invoke, isTauri } from '../hooks/useTauri';
+import logger from '../utils/logger';
+
+interface SearchResult {
+  code: string;
+  name: string;
+  market: string;
+}
+
+/** 全局快捷键唤起的快速查股窗口：本地模糊搜索，回车后在主窗口打开个股诊断 */
+export default function QuickSearch() {
+  const [keyword, setKeyword] = useState('');
+  const [results, setResults] = useState<SearchResult[]>([]);
+  const [active, setActive] = useState(0);
+  const [searching, setSearching] = useState(false);
+  const inputRef = useRef<HTMLInputElement>(null);
+
+  const close = () => {
+    if (isTauri) getCurrentWindow().close();
+  };
+
+  useEffect(() => {
+    inputRef.current?.focus();
+    if (!isTauri) return;
+    // 失去焦点即收起，与系统启动器的交互一致
+    let unlisten: (() => void) | undefined;
+    getCurrentWindow().onFocusChanged(({ payload: focused }) => {
+      if (!focused) close();
+    }).then(fn => { unlisten = fn; });
+    return () => unlisten?.();
+  }, []);
+
+  useEffect(() => {
+    if (!keyword.trim()) {
+      setResults([]);
+      return;
+    }
+    const timer = setTimeout(async () => {
+      setSearching(true);
+      try {
+        const list = await invoke<SearchResult[]>('search_stocks', { keyword: keyword.trim() });
+        setResults(list || []);
+        setActive(0);
+      } catch (e) {
+        logger.error(`Quick search failed: ${e}`);
+        setResults([]);
+      } finally {
+        setSearching(false);
+      }
+    }, 150);
+    return () => clearTimeout(timer);
+  }, [keyword]);
+
+  const open = async (result: SearchResult) => {
+    try {
+      await invoke('open_deep_link', { url: `stockhelper://analysis/${result.code}` });
+      close();
+    } catch (e) {
+      logger.error(`Open stock from quick search failed: ${e}`);
+    }
+  };
+
+  const handleKeyDown = (e: React.KeyboardEvent) => {
+    if (e.key === 'Escape') {
+      close();
+    } else if (e.key === 'ArrowDown') {
+      e.preventDefault();
+      setActive(i => Math.min(i + 1, results.length - 1));
+    } else if (e.key === 'ArrowUp') {
+      e.preventDefault();
+      setActive(i => Math.max(i - 1, 0));
+    } else if (e.key === 'Enter' && results[active]) {
+      open(results[active]);
+    }
+  };
+
+  return (
+    <div className="w-full h-full flex flex-col bg-bg-card border border-[#30363D] rounded-lg overflow-hidden">
+      <div className="flex items-center gap-2 px-4 py-3 border-b border-[#30363D]" data-tauri-drag-region>
+        <Search size={16} className="text-txt-muted" />
+        <input
+          ref={inputRef}
+          value={keyword}
+          onChange={e => setKeyword(e.target.value)}
+          onKeyDown={handleKeyDown}
+          placeholder="代码 / 名称 / 拼音首字母，回车查看诊断"
+          className="flex-1 bg-transparent outline-none text-sm text-txt-primary placeholder:text-txt-muted"
+        />
+        {searching && <Loader2 size={14} className="animate-spin text-primary-gold" />}
+      </div>
+      <div className="flex-1 overflow-auto">
+        {results.map((result, index) => (
+          <div
+            key={result.code}
+            onMouseEnter={() => setActive(index)}
+            onClick={() => open(result)}
+            className={`flex items-center gap-3 px-4 py-2 text-sm cursor-pointer ${
+              index === active ? 'bg-bg-elevated text-txt-primary' : 'text-txt-secondary'
+            }`}
+          >
+            <span className="flex-1 truncate">{result.name}</span>
+            <span className="font-mono text-xs text-txt-muted">{result.code}</span>
+          </div>
+        ))}
+        {keyword.trim() && !searching && results.length === 0 && (
+          <div className="py-6 text-center text-xs text-txt-muted">未找到匹配的A股</div>
+        )}
+      </div>
+    </div>
+  );
+}
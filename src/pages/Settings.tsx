@@ -1,13 +1,32 @@
 import { useEffect, useState } from 'react';
 import { Slider, Switch, Select, Input, InputNumber, App } from 'antd';
-import { Plus, Trash2, Bot, Database, Fingerprint, Loader2, CheckCircle, XCircle, Zap, FileDown, RefreshCw, Info } from 'lucide-react';
+import { Plus, Trash2, Bot, Database, Fingerprint, Loader2, CheckCircle, XCircle, Zap, FileDown, FolderOpen, RefreshCw, Info, Timer, Layers, Cloud, Server, Bell, ShieldAlert, Headphones, Activity, SlidersHorizontal } from 'lucide-react';
 import { getVersion } from '@tauri-apps/api/app';
-import { safeInvoke as invoke, isTauri } from '../hooks/useTauri';
+import { safeInvoke as invoke, isTauri, CommandError } from '../hooks/useTauri';
 import { useSettingsStore } from '../stores/settingsStore';
-import { AIConfig } from '../types';
+import { AIConfig, AppErrorCode } from '../types';
 import UpdateModal from '../components/UpdateModal';
+import JobSchedulerPanel from '../components/JobSchedulerPanel';
+import DiagnosticsPanel from '../components/DiagnosticsPanel';
+import SettingsProfilePanel from '../components/SettingsProfilePanel';
+import SyncPanel from '../components/SyncPanel';
+import NotifyPanel from '../components/NotifyPanel';
+import RiskRulesPanel from '../components/RiskRulesPanel';
+import TtsPanel from '../components/TtsPanel';
+import ApiServerPanel from '../components/ApiServerPanel';
+import QuoteProviderPanel from '../components/QuoteProviderPanel';
+import FactorWeightsPanel from '../components/FactorWeightsPanel';
 import type { UpdateInfo } from '../components/UpdateModal';
 
+/** 连接测试失败时按错误类别给出的排查建议 */
+const TEST_ERROR_HINTS: Partial<Record<AppErrorCode, string>> = {
+  unauthorized: '请检查 API Key 是否正确、是否有该模型的权限',
+  rate_limited: '请求被限流，请稍后重试或更换模型',
+  network: '无法连接服务器，请检查网络、代理或 API 地址',
+  timeout: '请求超时，请检查网络或 API 地址',
+  upstream: '服务端返回错误，请检查 API 地址与模型名称',
+};
+
 export default function Settings() {
   const { message } = App.useApp();
   const { settings, loadSettings, saveSettings, addAIConfig, removeAIConfig, updateAIConfig, setActiveAIConfig, testAIConfig, testingConfigId, exportLogs, exportingLogs } = useSettingsStore();
@@ -16,6 +35,7 @@ export default function Settings() {
   const [appVersion, setAppVersion] = useState('');
   const [checkingUpdate, setCheckingUpdate] = useState(false);
   const [updateInfo, setUpdateInfo] = useState<UpdateInfo | null>(null);
+  const [hotkeyDraft, setHotkeyDraft] = useState<string | null>(null);
 
   useEffect(() => {
     loadSettings();
@@ -59,12 +79,29 @@ export default function Settings() {
     setTestResults(prev => ({ ...prev, [config.id]: { ok: false, msg: '' } }));
     try {
       const result = await testAIConfig(config);
-      setTestResults(prev => ({ ...prev, [config.id]: { ok: true, msg: result } }));
+      const toolInfo = result.supports_tools ? '支持工具调用' : (result.tool_probe_message || '不支持工具调用');
+      const msg = `模型 ${result.model} 连接正常，耗时 ${result.latency_ms}ms，${toolInfo}。回复: ${result.reply}`;
+      setTestResults(prev => ({ ...prev, [config.id]: { ok: true, msg } }));
       message.success('连接测试成功');
     } catch (e: unknown) {
       const errMsg = e instanceof Error ? e.message : String(e);
-      setTestResults(prev => ({ ...prev, [config.id]: { ok: false, msg: errMsg } }));
-      message.error(`测试失败: ${errMsg}`);
+      const hint = e instanceof CommandError ? TEST_ERROR_HINTS[e.code] : undefined;
+      setTestResults(prev => ({ ...prev, [config.id]: { ok: false, msg: hint ? `${hint}（${errMsg}）` : errMsg } }));
+      message.error(`测试失败: ${hint ?? errMsg}`);
+    }
+  };
+
+  // 快捷键单独保存：注册失败（被其他程序占用）时需要提示
+  const handleSaveHotkey = async () => {
+    if (hotkeyDraft === null || hotkeyDraft.trim() === settings.quick_search_hotkey) return;
+    try {
+      await invoke('save_settings', { settings: { ...settings, quick_search_hotkey: hotkeyDraft.trim() } });
+      message.success(hotkeyDraft.trim() ? '快捷键已更新' : '已关闭快速查股快捷键');
+      setHotkeyDraft(null);
+    } catch (e) {
+      message.error(`${e}`);
+    } finally {
+      await loadSettings();
     }
   };
 
@@ -299,6 +336,85 @@ export default function Settings() {
             </div>
           </div>
 
+          <div className="flex items-center justify-between">
+            <div>
+              <span className="text-sm text-txt-primary">关闭时最小化到托盘</span>
+              <p className="text-xs text-txt-muted mt-1">关闭主窗口后保留托盘图标，后台扫描与推送继续运行，托盘菜单显示自选股异动</p>
+            </div>
+            <Switch
+              checked={settings.close_to_tray ?? true}
+              onChange={v => saveSettings({ ...settings, close_to_tray: v })}
+            />
+          </div>
+
+          <div className="flex items-center justify-between">
+            <div>
+              <span className="text-sm text-txt-primary">快速查股快捷键</span>
+              <p className="text-xs text-txt-muted mt-1">任意界面按下即弹出查股窗口，回车在主窗口打开个股诊断；留空关闭</p>
+            </div>
+            <Input
+              className="w-56"
+              value={hotkeyDraft ?? settings.quick_search_hotkey ?? ''}
+              onChange={e => setHotkeyDraft(e.target.value)}
+              onBlur={handleSaveHotkey}
+              onPressEnter={handleSaveHotkey}
+              placeholder="如 CommandOrControl+Alt+K"
+              style={inputStyle}
+            />
+          </div>
+
+          <div className="flex items-center justify-between">
+            <div>
+              <span className="text-sm text-txt-primary">动量相对沪深300</span>
+              <p className="text-xs text-txt-muted mt-1">因子评分与选股理由中的动量按跑赢沪深300的幅度计算，普涨行情不再整体抬高评分</p>
+            </div>
+            <Switch
+              checked={settings.momentum_vs_benchmark ?? false}
+              onChange={v => saveSettings({ ...settings, momentum_vs_benchmark: v })}
+            />
+          </div>
+
+          <div className="flex items-center justify-between">
+            <div>
+              <span className="text-sm text-txt-primary">焦点盯盘大单阈值</span>
+              <p className="text-xs text-txt-muted mt-1">单笔成交额达到该金额（万元）即推送到逐笔大单面板</p>
+            </div>
+            <InputNumber
+              size="small"
+              min={10}
+              max={100000}
+              step={50}
+              value={(settings.large_order_threshold ?? 1000000) / 10000}
+              onChange={v => v && saveSettings({ ...settings, large_order_threshold: v * 10000 })}
+              style={{ ...inputStyle, width: 120 }}
+            />
+          </div>
+
+          <div className="flex items-center justify-between">
+            <div>
+              <span className="text-sm text-txt-primary">竞价异动只扫自选股</span>
+              <p className="text-xs text-txt-muted mt-1">交易日 09:22 自动扫描竞价跳空与竞价爆量，关闭时扫描全市场</p>
+            </div>
+            <Switch
+              checked={settings.auction_scan_watchlist_only ?? false}
+              onChange={v => saveSettings({ ...settings, auction_scan_watchlist_only: v })}
+            />
+          </div>
+
+          <div className="flex items-center justify-between">
+            <div>
+              <span className="text-sm text-txt-primary">AI 输出合并推送</span>
+              <p className="text-xs text-txt-muted mt-1">流式正文每 50 毫秒或每 200 字推送一次，长报告输出更流畅；关闭时逐字推送</p>
+            </div>
+            <Switch
+              checked={settings.stream_batch?.enabled ?? true}
+              onChange={v => saveSettings({
+                ...settings,
+                stream_batch: { flush_interval_ms: 50, max_chars: 200, ...settings.stream_batch, enabled: v },
+              })}
+            />
+          </div>
+
           <div className="flex items-center justify-between">
             <span className="text-sm text-txt-primary">AI 指令自动生成</span>
             <Switch
@@ -345,6 +461,87 @@ export default function Settings() {
         </div>
       </section>
 
+      {/* 设置方案与导入导出 */}
+      <section>
+        <div className="flex items-center gap-2 mb-4">
+          <Layers size={18} className="text-functional-info" />
+          <h2 className="text-base font-bold text-txt-primary">设置方案</h2>
+        </div>
+        <SettingsProfilePanel settings={settings} />
+      </section>
+
+      {/* 风控规则 */}
+      <section>
+        <div className="flex items-center gap-2 mb-4">
+          <ShieldAlert size={18} className="text-functional-info" />
+          <h2 className="text-base font-bold text-txt-primary">风控规则</h2>
+        </div>
+        <RiskRulesPanel settings={settings} />
+      </section>
+
+      {/* 因子权重 */}
+      <section>
+        <div className="flex items-center gap-2 mb-4">
+          <SlidersHorizontal size={18} className="text-functional-info" />
+          <h2 className="text-base font-bold text-txt-primary">因子权重</h2>
+        </div>
+        <FactorWeightsPanel settings={settings} />
+      </section>
+
+      {/* 语音播报 */}
+      <section>
+        <div className="flex items-center gap-2 mb-4">
+          <Headphones size={18} className="text-functional-info" />
+          <h2 className="text-base font-bold text-txt-primary">语音播报</h2>
+        </div>
+        <TtsPanel settings={settings} />
+      </section>
+
+      {/* 消息推送 */}
+      <section>
+        <div className="flex items-center gap-2 mb-4">
+          <Bell size={18} className="text-functional-info" />
+          <h2 className="text-base font-bold text-txt-primary">消息推送</h2>
+        </div>
+        <NotifyPanel settings={settings} />
+      </section>
+
+      {/* 多设备同步 */}
+      <section>
+        <div className="flex items-center gap-2 mb-4">
+          <Cloud size={18} className="text-functional-info" />
+          <h2 className="text-base font-bold text-txt-primary">多设备同步</h2>
+        </div>
+        <SyncPanel settings={settings} />
+      </section>
+
+      {/* 行情源 */}
+      <section>
+        <div className="flex items-center gap-2 mb-4">
+          <Activity size={18} className="text-functional-info" />
+          <h2 className="text-base font-bold text-txt-primary">行情源</h2>
+        </div>
+        <QuoteProviderPanel settings={settings} />
+      </section>
+
+      {/* 本机 API */}
+      <section>
+        <div className="flex items-center gap-2 mb-4">
+          <Server size={18} className="text-functional-info" />
+          <h2 className="text-base font-bold text-txt-primary">本机 API</h2>
+        </div>
+        <ApiServerPanel settings={settings} />
+      </section>
+
+      {/* 后台任务 */}
+      <section>
+        <div className="flex items-center gap-2 mb-4">
+          <Timer size={18} className="text-functional-info" />
+          <h2 className="text-base font-bold text-txt-primary">后台任务</h2>
+        </div>
+        <JobSchedulerPanel settings={settings} saveSettings={saveSettings} />
+      </section>
+
       {/* 日志导出 */}
       <section>
         <div className="flex items-center gap-2 mb-4">
@@ -352,33 +549,63 @@ export default function Settings() {
           <h2 className="text-base font-bold text-txt-primary">日志管理</h2>
         </div>
 
-        <div className="p-4 rounded-lg border border-[#30363D] bg-bg-card flex items-center justify-between">
-          <div>
-            <span className="text-sm text-txt-primary">导出日志文件</span>
-            <p className="text-xs text-txt-muted mt-1">将应用日志打包为 ZIP 文件，便于发送给开发者诊断问题</p>
+        <div className="p-4 rounded-lg border border-[#30363D] bg-bg-card space-y-4">
+          <div className="flex items-center justify-between">
+            <div>
+              <span className="text-sm text-txt-primary">导出日志文件</span>
+              <p className="text-xs text-txt-muted mt-1">将应用日志打包为 ZIP 文件，便于发送给开发者诊断问题</p>
+            </div>
+            <div className="flex items-center gap-2 shrink-0">
+              <button
+                onClick={async () => {
+                  try {
+                    await invoke<string>('open_log_dir');
+                  } catch (e) {
+                    message.error(`${e}`);
+                  }
+                }}
+                className="flex items-center gap-2 px-3 py-2 rounded-lg text-txt-secondary hover:text-txt-primary hover:bg-bg-elevated transition-colors cursor-pointer text-sm"
+              >
+                <FolderOpen size={14} />
+                打开目录
+              </button>
+              <button
+                onClick={async () => {
+                  try {
+                    const result = await exportLogs();
+                    message.success(result);
+                  } catch (e: unknown) {
+                    const errMsg = e instanceof Error ? e.message : String(e);
+                    if (!errMsg.includes('取消')) {
+                      message.error(`导出失败: ${errMsg}`);
+                    }
+                  }
+                }}
+                disabled={exportingLogs}
+                className="flex items-center gap-2 px-4 py-2 rounded-lg bg-functional-info/20 text-functional-info hover:bg-functional-info/30 transition-colors cursor-pointer disabled:opacity-50 disabled:cursor-not-allowed text-sm"
+              >
+                {exportingLogs ? (
+                  <Loader2 size={14} className="animate-spin" />
+                ) : (
+                  <FileDown size={14} />
+                )}
+                {exportingLogs ? '导出中...' : '导出日志'}
+              </button>
+            </div>
           </div>
-          <button
-            onClick={async () => {
-              try {
-                const result = await exportLogs();
-                message.success(result);
-              } catch (e: unknown) {
-                const errMsg = e instanceof Error ? e.message : String(e);
-                if (!errMsg.includes('取消')) {
-                  message.error(`导出失败: ${errMsg}`);
-                }
-              }
-            }}
-            disabled={exportingLogs}
-            className="flex items-center gap-2 px-4 py-2 rounded-lg bg-functional-info/20 text-functional-info hover:bg-functional-info/30 transition-colors cursor-pointer disabled:opacity-50 disabled:cursor-not-allowed text-sm"
-          >
-            {exportingLogs ? (
-              <Loader2 size={14} className="animate-spin" />
-            ) : (
-              <FileDown size={14} />
-            )}
-            {exportingLogs ? '导出中...' : '导出日志'}
-          </button>
+          <div className="flex items-center justify-between">
+            <div>
+              <span className="text-sm text-txt-primary">调试日志</span>
+              <p className="text-xs text-txt-muted mt-1">记录数据源与 AI 请求的地址、状态码、耗时和请求体大小（不含内容，敏感参数已隐去）</p>
+            </div>
+            <Switch
+              checked={settings.debug_logging ?? false}
+              onChange={v => saveSettings({ ...settings, debug_logging: v })}
+            />
+          </div>
+        </div>
+        <div className="mt-3">
+          <DiagnosticsPanel />
         </div>
       </section>
 
@@ -1,5 +1,5 @@
 import { useState, useEffect, useRef, useCallback, useMemo } from 'react';
-import { Search, Plus, Loader2, Activity, X, RefreshCw, Eye, ArrowLeft, Brain, TrendingUp, TrendingDown, Trash2, Calendar, Target, ChevronDown, ChevronUp, LayoutList, Table2, AlertTriangle } from 'lucide-react';
+import { Search, Plus, Loader2, Activity, X, RefreshCw, Eye, ArrowLeft, Brain, TrendingUp, TrendingDown, Trash2, Calendar, Target, ChevronDown, ChevronUp, LayoutList, Table2, AlertTriangle, ExternalLink, PanelTop, History, ShieldAlert, ScanEye, AlarmClock, Radar, Zap, BarChart3 } from 'lucide-react';
 import { useWatchlistStore } from '../stores/watchlistStore';
 import { useTrackingStore, DateGroup, TrackingStockWithQuote } from '../stores/trackingStore';
 import { safeInvoke } from '../hooks/useTauri';
@@ -9,6 +9,16 @@ import KlineChart from '../components/KlineChart';
 import TechnicalPanel from '../components/TechnicalPanel';
 import WatchlistDiagnosePanel from '../components/WatchlistDiagnosePanel';
 import LossAnalysisPanel from '../components/LossAnalysisPanel';
+import ReplayPanel from '../components/ReplayPanel';
+import AuctionGapPanel from '../components/AuctionGapPanel';
+import RiskPanel from '../components/RiskPanel';
+import StrategyPerformancePanel from '../components/StrategyPerformancePanel';
+import ImageAnalysisPanel from '../components/ImageAnalysisPanel';
+import ScoreSparkline from '../components/ScoreSparkline';
+import ThemeChips from '../components/ThemeChips';
+import StockSchedulePanel from '../components/StockSchedulePanel';
+import FocusTapePanel from '../components/FocusTapePanel';
+import logger from '../utils/logger';
 
 interface SearchResult {
   code: string;
@@ -29,9 +39,9 @@ const RATING_CONFIG: Record<string, { label: string; color: string; bg: string;
 export default function Watchlist() {
   const {
     stocks, quotes, quotesLoading, loading, selectedCode, analysis, analysisLoading, analysisPeriod,
-    showDiagnosePanel,
+    showDiagnosePanel, pendingDeepLink,
     loadStocks, addStock, removeStock, selectStock, setPeriod,
-    startDiagnosis, setShowDiagnosePanel, resetDiagnosis,
+    startDiagnosis, setShowDiagnosePanel, resetDiagnosis, clearDeepLink,
     loadQuotes, startAutoRefresh, stopAutoRefresh,
   } = useWatchlistStore();
 
@@ -54,6 +64,13 @@ export default function Watchlist() {
   const [activeIndicators, setActiveIndicators] = useState<string[]>(['MA']);
   const [autoRefresh, setAutoRefresh] = useState(false);
   const [activeTab, setActiveTab] = useState<ViewTab>('table');
+  const [showReplay, setShowReplay] = useState(false);
+  const [showAuctionGaps, setShowAuctionGaps] = useState(false);
+  const [showRisk, setShowRisk] = useState(false);
+  const [showPerformance, setShowPerformance] = useState(false);
+  const [showImageAnalysis, setShowImageAnalysis] = useState(false);
+  const [showSchedule, setShowSchedule] = useState(false);
+  const [showFocusTape, setShowFocusTape] = useState(false);
   const unlistenRef = useRef<(() => void) | null>(null);
   const searchTimerRef = useRef<ReturnType<typeof setTimeout> | null>(null);
   const addInputRef = useRef<HTMLInputElement>(null);
@@ -129,6 +146,33 @@ export default function Watchlist() {
     setActiveTab('detail');
   }, [selectStock]);
 
+  // stockhelper:// 链接：打开个股详情，analysis 目标随后发起 AI 诊断
+  useEffect(() => {
+    if (!pendingDeepLink) return;
+    const { code, target } = pendingDeepLink;
+    clearDeepLink();
+    const name = stocks.find(s => s.code === code)?.name || '';
+    setMainTab('watchlist');
+    setActiveTab('detail');
+    const ws = useWatchlistStore.getState();
+    const loaded = ws.loadAnalysis(code, name);
+    selectStock(code);
+    if (target === 'analysis') {
+      loaded.then(async () => {
+        const current = useWatchlistStore.getState().analysis;
+        if (unlistenRef.current) unlistenRef.current();
+        resetDiagnosis();
+        unlistenRef.current = await startDiagnosis(code, current?.name || name);
+      }).catch(e => logger.error(`Deep link analysis failed: ${e}`));
+    }
+  }, [pendingDeepLink]);
+
+  const openDetachedWindow = useCallback((kind: 'mini_quote' | 'kline', code?: string, name?: string) => {
+    safeInvoke('open_detached_window', { kind, code, name }).catch(e =>
+      logger.error(`Open detached window failed: ${e}`)
+    );
+  }, []);
+
   const handleBackToTable = useCallback(() => {
     setActiveTab('table');
   }, []);
@@ -161,6 +205,7 @@ export default function Watchlist() {
     const labels: StockLabel[] = [];
     if (q.change_pct >= 9.8) labels.push({ text: '涨停', color: '#F43F5E', icon: null });
     else if (q.change_pct <= -9.8) labels.push({ text: '跌停', color: '#22C55E', icon: null });
+    if (q.tail_flag) labels.push({ text: q.tail_flag, color: q.tail_flag === '尾盘抢筹' ? '#F43F5E' : '#22C55E', icon: null });
     if (q.volume_ratio >= 3) labels.push({ text: '放量', color: '#F59E0B', icon: null });
     if (q.main_net_inflow >= 5e7) labels.push({ text: '主力流入', color: '#F43F5E', icon: null });
     else if (q.main_net_inflow <= -5e7) labels.push({ text: '主力流出', color: '#22C55E', icon: null });
@@ -268,9 +313,51 @@ export default function Watchlist() {
                     </>
                   );
                 })()}
+                <ScoreSparkline code={analysis.code} />
+                <ThemeChips code={analysis.code} />
               </div>
             )}
             <div className="flex-1" />
+            {analysis && (
+              <button
+                onClick={() => openDetachedWindow('kline', analysis.code, analysis.name)}
+                title="在独立窗口中打开 K 线，可拖到副屏"
+                className="flex items-center gap-1 px-3 py-1.5 rounded-lg text-xs font-medium bg-bg-card text-txt-secondary border border-[#30363D] hover:border-[#484F58] hover:text-txt-primary transition-all cursor-pointer"
+              >
+                <ExternalLink size={12} />
+                独立窗口
+              </button>
+            )}
+            {analysis && (
+              <button
+                onClick={() => setShowImageAnalysis(true)}
+                title="粘贴其他软件的图表截图，由视觉模型结合真实行情解读"
+                className="flex items-center gap-1 px-3 py-1.5 rounded-lg text-xs font-medium bg-bg-card text-txt-secondary border border-[#30363D] hover:border-[#484F58] hover:text-txt-primary transition-all cursor-pointer"
+              >
+                <ScanEye size={12} />
+                识图
+              </button>
+            )}
+            {analysis && (
+              <button
+                onClick={() => setShowSchedule(true)}
+                title="每个交易日定时让 AI 快检该股，如 14:30 分析尾盘该不该走"
+                className="flex items-center gap-1 px-3 py-1.5 rounded-lg text-xs font-medium bg-bg-card text-txt-secondary border border-[#30363D] hover:border-[#484F58] hover:text-txt-primary transition-all cursor-pointer"
+              >
+                <AlarmClock size={12} />
+                盯盘助手
+              </button>
+            )}
+            {analysis && (
+              <button
+                onClick={() => setShowFocusTape(true)}
+                title="盘中实时监控该股逐笔成交中的大单"
+                className="flex items-center gap-1 px-3 py-1.5 rounded-lg text-xs font-medium bg-bg-card text-txt-secondary border border-[#30363D] hover:border-[#484F58] hover:text-txt-primary transition-all cursor-pointer"
+              >
+                <Radar size={12} />
+                逐笔大单
+              </button>
+            )}
             {analysis && (
               <button
                 onClick={handleDiagnose}
@@ -416,6 +503,29 @@ export default function Watchlist() {
               </>
             )}
 
+            {mainTab === 'tracking' && (
+              <button
+                onClick={() => setShowRisk(true)}
+                disabled={trackingStocks.length === 0}
+                title="检查追踪持仓的仓位、行业与相关性集中度"
+                className="flex items-center gap-1 px-3 py-1.5 rounded-lg text-xs font-medium bg-bg-card text-txt-secondary border border-[#30363D] hover:border-[#484F58] hover:text-txt-primary transition-all cursor-pointer disabled:opacity-40"
+              >
+                <ShieldAlert size={12} />
+                风控检查
+              </button>
+            )}
+
+            {mainTab === 'tracking' && (
+              <button
+                onClick={() => setShowPerformance(true)}
+                title="技术信号、AI 买入指令与 AI 选股的近期模拟收益与胜率"
+                className="flex items-center gap-1 px-3 py-1.5 rounded-lg text-xs font-medium bg-bg-card text-txt-secondary border border-[#30363D] hover:border-[#484F58] hover:text-txt-primary transition-all cursor-pointer"
+              >
+                <BarChart3 size={12} />
+                策略表现
+              </button>
+            )}
+
             <button
               onClick={() => {
                 if (mainTab === 'tracking') loadTrackingQuotes();
@@ -437,6 +547,33 @@ export default function Watchlist() {
               />
               自动(15s)
             </label>
+
+            <button
+              onClick={() => openDetachedWindow('mini_quote')}
+              title="打开置顶的迷你行情条"
+              className="flex items-center gap-1 px-3 py-1.5 rounded-lg text-xs font-medium bg-bg-card text-txt-secondary border border-[#30363D] hover:border-[#484F58] hover:text-txt-primary transition-all cursor-pointer"
+            >
+              <PanelTop size={12} />
+              迷你行情
+            </button>
+
+            <button
+              onClick={() => setShowReplay(true)}
+              title="回放当日集合竞价与盘中走势"
+              className="flex items-center gap-1 px-3 py-1.5 rounded-lg text-xs font-medium bg-bg-card text-txt-secondary border border-[#30363D] hover:border-[#484F58] hover:text-txt-primary transition-all cursor-pointer"
+            >
+              <History size={12} />
+              复盘回放
+            </button>
+
+            <button
+              onClick={() => setShowAuctionGaps(true)}
+              title="集合竞价跳空与竞价爆量扫描"
+              className="flex items-center gap-1 px-3 py-1.5 rounded-lg text-xs font-medium bg-bg-card text-txt-secondary border border-[#30363D] hover:border-[#484F58] hover:text-txt-primary transition-all cursor-pointer"
+            >
+              <Zap size={12} />
+              竞价异动
+            </button>
           </>
         )}
       </div>
@@ -560,6 +697,31 @@ export default function Watchlist() {
       {lossAnalysisDate && (
         <LossAnalysisPanel />
       )}
+
+      {/* Intraday Replay Panel */}
+      {showReplay && <ReplayPanel onClose={() => setShowReplay(false)} />}
+      {showAuctionGaps && <AuctionGapPanel onClose={() => setShowAuctionGaps(false)} />}
+
+      {/* Risk Control Panel */}
+      {showRisk && <RiskPanel onClose={() => setShowRisk(false)} />}
+
+      {/* Strategy Performance Panel */}
+      {showPerformance && <StrategyPerformancePanel onClose={() => setShowPerformance(false)} />}
+
+      {/* Chart Screenshot Analysis Panel */}
+      {showImageAnalysis && analysis && (
+        <ImageAnalysisPanel code={analysis.code} name={analysis.name} onClose={() => setShowImageAnalysis(false)} />
+      )}
+
+      {/* Per-stock Scheduled Check-in Panel */}
+      {showSchedule && analysis && (
+        <StockSchedulePanel code={analysis.code} name={analysis.name} onClose={() => setShowSchedule(false)} />
+      )}
+
+      {/* Tick-level Large Order Panel */}
+      {showFocusTape && analysis && (
+        <FocusTapePanel code={analysis.code} name={analysis.name} onClose={() => setShowFocusTape(false)} />
+      )}
     </div>
   );
 }
@@ -1,6 +1,6 @@
 import { create } from 'zustand';
 import { safeInvoke, safeListen } from '../hooks/useTauri';
-import { AIPickRecommendation, AIStreamEvent } from '../types';
+import { AIPickRecommendation, AIStreamEvent, CachedPicks, InterruptedPick } from '../types';
 import logger from '../utils/logger';
 
 interface ToolCallStatus {
@@ -25,6 +25,8 @@ interface AIPickState {
   error: string | null;
   cachedContent: string | null;
   tokenUsage: number | null;
+  /** 上次异常退出或出错、可续跑的选股 */
+  interrupted: InterruptedPick | null;
 
   // Similar stocks state
   similarLoading: boolean;
@@ -36,9 +38,12 @@ interface AIPickState {
   similarError: string | null;
 
   // Actions
-  startPick: () => Promise<void>;
+  /** 传入 resumeSessionId 时从该会话的断点续跑 */
+  startPick: (resumeSessionId?: string) => Promise<void>;
   stopPick: () => Promise<void>;
   loadCachedPicks: () => Promise<void>;
+  loadInterrupted: () => Promise<void>;
+  discardInterrupted: () => Promise<void>;
   reset: () => void;
   findSimilarStocks: (code: string, name: string, sector: string) => Promise<void>;
   closeSimilar: () => void;
@@ -91,6 +96,10 @@ const TOOL_LABELS: Record<string, string> = {
   get_market_news: '获取市场新闻',
   get_economic_data: '宏观经济数据',
   get_global_indexes: '全球指数',
+  get_market_breadth: '市场宽度',
+  get_market_heatmap: '板块热力图',
+  get_board_rotation: '板块轮动',
+  get_seasonality: '季节性统计',
   get_financial_calendar: '财经日历',
   search_stocks_by_condition: 'NLP智能选股',
   search_concept_boards: 'NLP板块搜索',
@@ -98,6 +107,7 @@ const TOOL_LABELS: Record<string, string> = {
   get_stock_quote: '查看个股行情',
   get_fund_flow: '查看资金流向',
   batch_get_fund_flow: '批量查看资金流向',
+  get_index_constituents: '获取指数成分股',
   get_kline_data: '获取K线数据',
   get_technical_indicators: '获取技术指标',
   search_stock_news: '个股新闻搜索',
@@ -114,6 +124,7 @@ export const useAIPickStore = create<AIPickState>((set, get) => ({
   error: null,
   cachedContent: null,
   tokenUsage: null,
+  interrupted: null,
 
   // Similar stocks
   similarLoading: false,
@@ -124,7 +135,7 @@ export const useAIPickStore = create<AIPickState>((set, get) => ({
   similarThinkingSteps: [],
   similarError: null,
 
-  startPick: async () => {
+  startPick: async (resumeSessionId?: string) => {
     set({
       picking: true,
       aiContent: '',
@@ -133,13 +144,14 @@ export const useAIPickStore = create<AIPickState>((set, get) => ({
       thinkingSteps: [],
       error: null,
       tokenUsage: null,
+      interrupted: null,
     });
 
     const unlisten = await safeListen<AIStreamEvent>('ai-pick-stream', (event) => {
       const data = event.payload;
       const state = get();
 
-      if (data.event_type === 'thinking') {
+      if (data.event_type === 'thinking' || data.event_type === 'mode' || data.event_type === 'warning') {
         set({
           thinkingSteps: [
             ...state.thinkingSteps,
@@ -184,17 +196,33 @@ export const useAIPickStore = create<AIPickState>((set, get) => ({
           tokenUsage: data.usage?.total_tokens || null,
           error: parseWarning,
         });
+        // 行情校验结果随选股记录落库，完成后回读并标注到每只推荐上
+        safeInvoke<CachedPicks | null>('get_cached_picks').then((cached) => {
+          if (!cached) return;
+          const stored = new Map(cached.picks.map((p) => [p.code, p]));
+          set({
+            recommendations: get().recommendations.map((r) => {
+              const p = stored.get(r.code);
+              return p ? { ...r, verify_status: p.verify_status, verify_note: p.verify_note } : r;
+            }),
+          });
+        }).catch(() => { /* ignore */ });
         unlisten();
       } else if (data.event_type === 'error') {
         set({
           picking: false,
           error: data.content || 'AI 选股失败',
         });
+        // 出错的选股保留了断点，可直接续跑
+        get().loadInterrupted();
         unlisten();
       }
     });
 
-    await safeInvoke('ai_pick_stocks').catch((e: Error) => {
+    const request = resumeSessionId
+      ? safeInvoke('resume_ai_pick', { sessionId: resumeSessionId })
+      : safeInvoke('ai_pick_stocks');
+    await request.catch((e: Error) => {
       set({ picking: false, error: e.message });
       unlisten();
     });
@@ -209,10 +237,25 @@ export const useAIPickStore = create<AIPickState>((set, get) => ({
   },
 
   loadCachedPicks: async () => {
-    const content = await safeInvoke<string | null>('get_cached_picks');
-    if (content) {
-      let picks: AIPickRecommendation[] = [];
-      try { picks = parseRecommendations(content); } catch { /* ignore */ }
+    const cached = await safeInvoke<CachedPicks | null>('get_cached_picks');
+    if (cached) {
+      const content = cached.content;
+      let picks: AIPickRecommendation[] = cached.picks.map(p => ({
+        code: p.code,
+        name: p.name,
+        price: p.entry_price || undefined,
+        reason: p.reason,
+        rating: (['strong_buy', 'buy', 'watch'].includes(p.rating) ? p.rating : 'watch') as AIPickRecommendation['rating'],
+        sector: p.sector,
+        highlights: p.highlights,
+        fund_flow: p.fund_flow,
+        valuation: p.valuation,
+        verify_status: p.verify_status,
+        verify_note: p.verify_note,
+      }));
+      if (picks.length === 0) {
+        try { picks = parseRecommendations(content); } catch { /* ignore */ }
+      }
       const parseWarning = picks.length === 0 && content.includes('<PICKS')
         ? 'AI 推荐结果解析异常，请查看原始分析报告'
         : null;
@@ -225,6 +268,24 @@ export const useAIPickStore = create<AIPickState>((set, get) => ({
     }
   },
 
+  loadInterrupted: async () => {
+    try {
+      const interrupted = await safeInvoke<InterruptedPick | null>('get_interrupted_pick');
+      set({ interrupted: interrupted ?? null });
+    } catch (e) {
+      logger.error(`Failed to load interrupted pick: ${e}`);
+    }
+  },
+
+  discardInterrupted: async () => {
+    const interrupted = get().interrupted;
+    if (!interrupted) return;
+    set({ interrupted: null });
+    await safeInvoke('discard_interrupted_pick', { sessionId: interrupted.session_id }).catch((e) => {
+      logger.error(`Failed to discard interrupted pick: ${e}`);
+    });
+  },
+
   reset: () => {
     set({
       picking: false,
@@ -253,7 +314,7 @@ export const useAIPickStore = create<AIPickState>((set, get) => ({
       const data = event.payload;
       const state = get();
 
-      if (data.event_type === 'thinking') {
+      if (data.event_type === 'thinking' || data.event_type === 'mode') {
         set({
           similarThinkingSteps: [
             ...state.similarThinkingSteps,
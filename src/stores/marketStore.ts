@@ -1,6 +1,6 @@
 import { create } from 'zustand';
 import { safeInvoke as invoke } from '../hooks/useTauri';
-import { MarketOverview, KlineItem } from '../types';
+import { MarketOverview, MarketHeatmap, KlineItem } from '../types';
 import logger from '../utils/logger';
 
 interface MarketStore {
@@ -10,11 +10,13 @@ interface MarketStore {
   loading: boolean;
   error: string | null;
   indexKlines: Record<string, KlineItem[]>;
+  heatmap: MarketHeatmap | null;
   refreshTimer: ReturnType<typeof setInterval> | null;
 
   fetchOverview: () => Promise<void>;
   generateAiComment: () => Promise<void>;
   fetchIndexKlines: () => Promise<void>;
+  fetchHeatmap: () => Promise<void>;
   startAutoRefresh: () => void;
   stopAutoRefresh: () => void;
 }
@@ -26,6 +28,7 @@ export const useMarketStore = create<MarketStore>((set, get) => ({
   loading: false,
   error: null,
   indexKlines: {},
+  heatmap: null,
   refreshTimer: null,
 
   fetchOverview: async () => {
@@ -66,12 +69,24 @@ export const useMarketStore = create<MarketStore>((set, get) => ({
     set({ indexKlines: klines });
   },
 
+  fetchHeatmap: async () => {
+    const result = await invoke<MarketHeatmap>('get_market_heatmap').catch((e: unknown) => {
+      const msg = e instanceof Error ? e.message : String(e);
+      logger.warn(`[marketStore] fetchHeatmap failed: ${msg}`);
+      return null;
+    });
+    if (result) {
+      set({ heatmap: result });
+    }
+  },
+
   startAutoRefresh: () => {
     const existing = get().refreshTimer;
     if (existing) clearInterval(existing);
 
     const timer = setInterval(() => {
       get().fetchOverview();
+      get().fetchHeatmap();
     }, 30000);
     set({ refreshTimer: timer });
   },
@@ -1,6 +1,6 @@
 import { create } from 'zustand';
 import { safeInvoke as invoke } from '../hooks/useTauri';
-import { AppSettings, AIConfig } from '../types';
+import { AppSettings, AIConfig, AIConnectionTest, SyncResult } from '../types';
 import logger from '../utils/logger';
 
 const defaultSettings: AppSettings = {
@@ -30,8 +30,14 @@ interface SettingsStore {
   removeAIConfig: (configId: string) => Promise<void>;
   updateAIConfig: (config: AIConfig) => Promise<void>;
   setActiveAIConfig: (configId: string) => Promise<void>;
-  testAIConfig: (config: AIConfig) => Promise<string>;
+  testAIConfig: (config: AIConfig) => Promise<AIConnectionTest>;
   exportLogs: () => Promise<string>;
+  exportSettings: (includeSecrets: boolean) => Promise<string>;
+  importSettings: () => Promise<void>;
+  saveProfile: (name: string) => Promise<void>;
+  applyProfile: (profileId: string) => Promise<void>;
+  deleteProfile: (profileId: string) => Promise<void>;
+  syncNow: () => Promise<SyncResult>;
 }
 
 export const useSettingsStore = create<SettingsStore>((set) => ({
@@ -99,7 +105,7 @@ export const useSettingsStore = create<SettingsStore>((set) => ({
   testAIConfig: async (config: AIConfig) => {
     set({ testingConfigId: config.id });
     try {
-      const result = await invoke<string>('test_ai_config', { config });
+      const result = await invoke<AIConnectionTest>('test_ai_config', { config });
       return result;
     } finally {
       set({ testingConfigId: null });
@@ -115,4 +121,36 @@ export const useSettingsStore = create<SettingsStore>((set) => ({
       set({ exportingLogs: false });
     }
   },
+
+  exportSettings: async (includeSecrets: boolean) => {
+    return invoke<string>('export_settings', { includeSecrets });
+  },
+
+  importSettings: async () => {
+    const settings = await invoke<AppSettings>('import_settings');
+    set({ settings });
+  },
+
+  saveProfile: async (name: string) => {
+    const settings = await invoke<AppSettings>('save_settings_profile', { name });
+    set({ settings });
+  },
+
+  applyProfile: async (profileId: string) => {
+    const settings = await invoke<AppSettings>('apply_settings_profile', { profileId });
+    set({ settings });
+  },
+
+  deleteProfile: async (profileId: string) => {
+    const settings = await invoke<AppSettings>('delete_settings_profile', { profileId });
+    set({ settings });
+  },
+
+  /** 同步可能拉取到其他设备的设置，完成后重新加载 */
+  syncNow: async () => {
+    const result = await invoke<SyncResult>('sync_now');
+    const settings = await invoke<AppSettings>('get_settings');
+    set({ settings });
+    return result;
+  },
 }));
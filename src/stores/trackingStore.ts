@@ -235,7 +235,7 @@ export const useTrackingStore = create<TrackingStore>((set, get) => ({
       // 忽略非当前日期的事件
       if (state.lossAnalysisDate !== date) return;
 
-      if (data.event_type === 'thinking') {
+      if (data.event_type === 'thinking' || data.event_type === 'mode') {
         set({
           lossThinkingSteps: [
             ...state.lossThinkingSteps,
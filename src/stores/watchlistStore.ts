@@ -5,6 +5,7 @@ import {
   WatchlistQuote,
   StockTechnicalAnalysis,
   AIStreamEvent,
+  DeepLink,
 } from '../types';
 import logger from '../utils/logger';
 
@@ -33,6 +34,7 @@ interface MarketSnapshotRaw {
   revenue_yoy: number;
   main_net_inflow: number;
   main_net_pct: number;
+  tail_flag: string | null;
 }
 
 interface WatchlistStore {
@@ -52,6 +54,9 @@ interface WatchlistStore {
   showDiagnosePanel: boolean;
   diagnoseToolCalls: { name: string; label: string; done: boolean }[];  // 工具调用进度列表
 
+  // 由 stockhelper:// 链接打开、等待盯盘页处理的目标
+  pendingDeepLink: DeepLink | null;
+
   // Auto refresh
   _refreshTimer: ReturnType<typeof setInterval> | null;
 
@@ -71,6 +76,8 @@ interface WatchlistStore {
   startDiagnosis: (code: string, name: string) => Promise<() => void>;
   setShowDiagnosePanel: (show: boolean) => void;
   resetDiagnosis: () => void;
+  openDeepLink: (link: DeepLink) => void;
+  clearDeepLink: () => void;
 }
 
 export const useWatchlistStore = create<WatchlistStore>((set, get) => ({
@@ -88,6 +95,7 @@ export const useWatchlistStore = create<WatchlistStore>((set, get) => ({
   diagnoseDone: false,
   showDiagnosePanel: false,
   diagnoseToolCalls: [],
+  pendingDeepLink: null,
 
   _refreshTimer: null,
 
@@ -135,6 +143,7 @@ export const useWatchlistStore = create<WatchlistStore>((set, get) => ({
         pct_20d: s.pct_20d,
         revenue_yoy: s.revenue_yoy,
         amplitude: s.amplitude,
+        tail_flag: s.tail_flag ?? null,
         date: '',
         time: '',
       }));
@@ -277,4 +286,12 @@ export const useWatchlistStore = create<WatchlistStore>((set, get) => ({
       diagnoseToolCalls: [],
     });
   },
+
+  openDeepLink: (link: DeepLink) => {
+    set({ pendingDeepLink: link });
+  },
+
+  clearDeepLink: () => {
+    set({ pendingDeepLink: null });
+  },
 }));
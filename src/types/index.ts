@@ -41,6 +41,428 @@ export interface AppSettings {
   max_pick_token_budget: number;
   agent_prompts: AgentPrompt[];
   active_pick_prompt_id: string | null;
+  signal_config: SignalConfig;
+  morning_briefing_enabled: boolean;
+  strategy_zones: StrategyZone[];
+  pick_preferences: PickPreferences;
+  /** 后台任务配置，按任务 id 索引；未配置的任务启用并使用默认间隔 */
+  job_settings: Record<string, JobSetting>;
+  debug_logging: boolean;
+  /** 命名的设置方案（策略与 AI 参数组合） */
+  profiles: SettingsProfile[];
+  active_profile_id: string | null;
+  /** 多设备同步配置 */
+  sync: SyncConfig;
+  /** 本机 HTTP API 配置 */
+  api_server: ApiServerConfig;
+  /** 消息推送目标 */
+  notify_targets: NotifyTarget[];
+  /** 关闭主窗口时最小化到托盘 */
+  close_to_tray: boolean;
+  /** 快速查股全局快捷键，为空表示不启用 */
+  quick_search_hotkey: string;
+  /** 持仓风控规则 */
+  risk_rules: RiskRules;
+  /** 语音播报（TTS）接口配置 */
+  tts: TtsConfig;
+  /** 条件选股方案 */
+  screener_presets: ScreenerPreset[];
+  /** 动量因子按相对沪深300的超额收益计算 */
+  momentum_vs_benchmark: boolean;
+  /** 焦点盯盘：单笔成交额达到该值（元）视为大单 */
+  large_order_threshold: number;
+  /** 竞价异动扫描只扫自选股 */
+  auction_scan_watchlist_only: boolean;
+  /** 实时行情源优先级（sina / tencent / eastmoney / 自定义接口 id），为空时按 data_source_primary */
+  quote_provider_order: string[];
+  /** 自定义 JSON 行情接口 */
+  custom_quote_providers: CustomQuoteProvider[];
+  /** 市场状态 → 因子权重映射，按顺序取第一条匹配，为空时等权 */
+  regime_factor_weights: RegimeFactorWeights[];
+  /** AI 流式正文合并推送 */
+  stream_batch: StreamBatchConfig;
+}
+
+/** AI 流式正文合并推送：攒够 max_chars 个字符或满 flush_interval_ms 毫秒推送一次，done 附带完整正文 */
+export interface StreamBatchConfig {
+  enabled: boolean;
+  flush_interval_ms: number;
+  max_chars: number;
+}
+
+/** 因子评分各分项的相对权重 */
+export interface FactorWeights {
+  quality: number;
+  growth: number;
+  value: number;
+  momentum: number;
+  flow: number;
+}
+
+/** 市场状态到因子权重的映射，trend / volatility 为 null 表示任意 */
+export interface RegimeFactorWeights {
+  trend: MarketRegime['trend'] | null;
+  volatility: MarketRegime['volatility'] | null;
+  weights: FactorWeights;
+}
+
+/** 自定义 JSON 行情接口：通过字段映射接入任意返回 JSON 的行情接口 */
+export interface CustomQuoteProvider {
+  id: string;
+  name: string;
+  enabled: boolean;
+  /** 请求地址，{codes} 替换为逗号分隔的股票代码 */
+  url: string;
+  /** 代码格式：prefixed（sh600000）或 pure（600000） */
+  code_format: 'prefixed' | 'pure';
+  headers: Record<string, string>;
+  /** 行情列表在响应中的路径（点分隔），为空表示根节点 */
+  list_path: string;
+  /** StockInfo 字段名 → 条目内的字段路径 */
+  fields: Record<string, string>;
+}
+
+/** 实时行情（五档盘口字段略） */
+export interface StockInfo {
+  code: string;
+  name: string;
+  open: number;
+  pre_close: number;
+  price: number;
+  high: number;
+  low: number;
+  volume: number;
+  amount: number;
+  date: string;
+  time: string;
+}
+
+/** 条件选股方案，expression 如 pe_ttm < 30 && roe > 10 && !name.contains('ST') */
+export interface ScreenerPreset {
+  id: string;
+  name: string;
+  expression: string;
+  updated_at: string;
+}
+
+/** OpenAI 兼容的 /audio/speech 接口配置 */
+export interface TtsConfig {
+  /** 接口地址，为空表示未配置 */
+  base_url: string;
+  api_key: string;
+  model: string;
+  /** 音色，如 alloy、nova */
+  voice: string;
+  /** 语速 0.25 ~ 4.0 */
+  speed: number;
+}
+
+/** 语音播报导出记录 */
+export interface AudioExport {
+  source_kind: 'briefing' | 'analysis';
+  /** 市场日志为 "日期/类型"，分析记录为分析 id */
+  source_id: string;
+  /** mp3 文件绝对路径 */
+  path: string;
+  created_at: string;
+}
+
+export interface RiskRules {
+  /** 收盘后自动检查并推送违规项 */
+  alert_enabled: boolean;
+  max_position_pct: number;
+  max_sector_pct: number;
+  max_cluster_pct: number;
+  /** 日收益率相关系数不低于该值视为高相关 */
+  correlation_threshold: number;
+  forbid_st: boolean;
+  max_drawdown_pct: number;
+}
+
+/** 单只持仓的敞口（同一股票多次追踪合并） */
+export interface PositionExposure {
+  code: string;
+  name: string;
+  sector: string;
+  lots: number;
+  entry_price: number;
+  price: number;
+  return_pct: number;
+  weight_pct: number;
+  drawdown_pct: number;
+  is_st: boolean;
+}
+
+export interface SectorExposure {
+  sector: string;
+  weight_pct: number;
+  codes: string[];
+}
+
+export interface CorrelatedCluster {
+  codes: string[];
+  names: string[];
+  weight_pct: number;
+  min_correlation: number;
+}
+
+export interface RiskViolation {
+  rule: 'position' | 'sector' | 'cluster' | 'st' | 'drawdown';
+  subject: string;
+  message: string;
+  value: number;
+  limit: number;
+}
+
+export interface RiskReport {
+  generated_at: string;
+  positions: PositionExposure[];
+  sectors: SectorExposure[];
+  clusters: CorrelatedCluster[];
+  violations: RiskViolation[];
+}
+
+/** stockhelper:// 链接解析结果 */
+export interface DeepLink {
+  target: 'stock' | 'analysis';
+  code: string;
+}
+
+/** 盘中分时快照 */
+export interface IntradaySnapshot {
+  code: string;
+  name: string;
+  date: string;
+  time: string;
+  price: number;
+  pre_close: number;
+  change_pct: number;
+  volume: number;
+  amount: number;
+  bid1: number;
+  ask1: number;
+  bid1_vol: number;
+  ask1_vol: number;
+}
+
+/** 复盘回放的一帧（HH:MM） */
+export interface ReplayFrame {
+  time: string;
+  quotes: IntradaySnapshot[];
+}
+
+export interface ReplayDay {
+  date: string;
+  stock_count: number;
+  snapshot_count: number;
+}
+
+export type DetachedWindowKind = 'mini_quote' | 'kline' | 'quick_search';
+
+/** 主窗口之外打开的独立窗口 */
+export interface DetachedWindow {
+  label: string;
+  kind: DetachedWindowKind;
+  code: string | null;
+  name: string | null;
+  always_on_top: boolean;
+}
+
+export type NotifyKind = 'dingtalk' | 'wecom' | 'telegram' | 'serverchan' | 'webhook';
+export type NotifyEvent = 'signal_alert' | 'anomaly' | 'morning_briefing' | 'risk_alert' | 'auction_gap';
+
+export interface NotifyTarget {
+  id: string;
+  name: string;
+  kind: NotifyKind;
+  enabled: boolean;
+  /** 钉钉、企业微信机器人或通用 Webhook 地址 */
+  url: string;
+  /** Server酱 SendKey 或 Telegram Bot Token */
+  key: string;
+  /** 钉钉加签密钥 */
+  secret: string;
+  /** Telegram chat_id */
+  chat_id: string;
+  /** 订阅的事件，为空时推送全部 */
+  events: NotifyEvent[];
+}
+
+export interface ApiServerConfig {
+  enabled: boolean;
+  port: number;
+  /** 访问令牌，为空时不校验 */
+  token: string;
+  /** 在 /mcp 提供 MCP 服务 */
+  mcp_enabled: boolean;
+}
+
+export interface ApiServerStatus {
+  running: boolean;
+  address: string | null;
+  error: string | null;
+}
+
+export interface SyncConfig {
+  enabled: boolean;
+  backend: 'webdav' | 's3';
+  endpoint: string;
+  /** WebDAV 用户名 / S3 Access Key */
+  username: string;
+  /** WebDAV 密码 / S3 Secret Key */
+  password: string;
+  bucket: string;
+  region: string;
+  /** 远端目录（WebDAV）或对象前缀（S3） */
+  remote_path: string;
+}
+
+export interface SyncResult {
+  synced_at: string;
+  /** 从远端拉取的变更数 */
+  pulled: number;
+  pushed: boolean;
+  /** 两端都修改的条目数，按本机优先 */
+  conflicts: number;
+  message: string;
+}
+
+export interface SyncStatus {
+  device_id: string;
+  last_synced_at: string | null;
+  last_result: SyncResult | null;
+}
+
+/** 设置方案包含的参数 */
+export interface ProfileParams {
+  active_ai_config_id: string | null;
+  active_pick_prompt_id: string | null;
+  max_pick_tool_rounds: number;
+  max_pick_token_budget: number;
+  signal_config: SignalConfig;
+  strategy_zones: StrategyZone[];
+  pick_preferences: PickPreferences;
+}
+
+export interface SettingsProfile {
+  id: string;
+  name: string;
+  updated_at: string;
+  params: ProfileParams;
+}
+
+export interface JobSetting {
+  enabled: boolean;
+  /** 检查/执行间隔（秒），null 使用任务默认值 */
+  interval_secs: number | null;
+}
+
+export interface JobRun {
+  id: number;
+  job_id: string;
+  started_at: string;
+  finished_at: string;
+  success: boolean;
+  message: string;
+}
+
+export interface ScheduledJob {
+  id: string;
+  name: string;
+  schedule: string;
+  enabled: boolean;
+  interval_secs: number;
+  default_interval_secs: number;
+  running: boolean;
+  /** 最近运行记录，最新在前 */
+  recent_runs: JobRun[];
+}
+
+export interface SourceHealth {
+  name: string;
+  url: string;
+  reachable: boolean;
+  latency_ms: number | null;
+  status: number | null;
+  error: string | null;
+}
+
+/** 上游接口格式偏差（字段数或必需键不符，通常意味着数据源改版） */
+export interface SchemaDrift {
+  source: string;
+  count: number;
+  last_at: string;
+  detail: string;
+}
+
+export interface CacheStat {
+  table: string;
+  label: string;
+  rows: number;
+  latest: string | null;
+}
+
+export interface DiagnosticsReport {
+  generated_at: string;
+  app_version: string;
+  os: string;
+  db_path: string;
+  db_size_bytes: number;
+  sources: SourceHealth[];
+  schema_drifts: SchemaDrift[];
+  caches: CacheStat[];
+  jobs: ScheduledJob[];
+  last_successes: Record<string, string>;
+  active_tasks: number;
+  ai_picking: boolean;
+}
+
+export interface PickPreferences {
+  risk_appetite: '' | 'conservative' | 'balanced' | 'aggressive';
+  include_sectors: string[];
+  exclude_sectors: string[];
+  /** 总市值区间（亿元） */
+  min_market_cap: number | null;
+  max_market_cap: number | null;
+  holding_horizon: '' | 'short' | 'swing' | 'medium';
+  max_price: number | null;
+  /** 选股范围：指数代码，如 000300（沪深300），空字符串表示不限 */
+  universe: string;
+  /** 动量因子：60 日 RPS 下限（0~100） */
+  min_rps: number | null;
+  /** 价值因子：股息率（TTM）下限 % */
+  min_dividend_yield: number | null;
+  /** 价值因子：现金流收益率（100 / 市现率TTM）下限 % */
+  min_fcf_yield: number | null;
+  /** 质量因子：资产负债率上限 % */
+  max_debt_ratio: number | null;
+  /** 质量因子：净利润同比增长下限 % */
+  min_profit_yoy: number | null;
+}
+
+export interface StrategyZone {
+  id: string;
+  name: string;
+  enabled: boolean;
+  rules: ZoneRule[];
+}
+
+export interface ZoneRule {
+  field: 'open_pct' | 'current_pct' | 'score' | 'bid_amount' | 'streak_days' | 'turnover';
+  min: number | null;
+  max: number | null;
+}
+
+export interface SignalConfig {
+  lookback_days: number;
+  kdj_overbought: number;
+  kdj_oversold: number;
+  rsi_overbought: number;
+  rsi_oversold: number;
+  volume_surge_ratio: number;
+  divergence_window: number;
+  alert_min_strength: number;
+  enabled_signals: string[];  // 为空表示全部启用
 }
 
 export interface AIAnalysisResult {
@@ -53,14 +475,56 @@ export interface AIAnalysisResult {
   created_at: string;
 }
 
+/** 盯盘助手：单只股票每个交易日定时的 AI 快检 */
+export interface StockSchedule {
+  id: string;
+  code: string;
+  name: string;
+  /** HH:MM */
+  time: string;
+  question: string;
+  enabled: boolean;
+  /** 最近一次执行日期，从未执行为空 */
+  last_run_date: string;
+  created_at: string;
+}
+
+/** 一次快检结果，changed_lines 为与上一次相比新增或变化的行号 */
+export interface ScheduleRun {
+  schedule_id: string;
+  analysis: AIAnalysisResult;
+  previous_id: string | null;
+  changed_lines: number[];
+}
+
+export interface AIConnectionTest {
+  model: string;
+  reply: string;
+  latency_ms: number;
+  supports_tools: boolean;
+  tool_probe_message: string | null;
+}
+
 export interface AIStreamEvent {
-  event_type: string;  // "content" | "tool_call" | "tool_result" | "done" | "error"
+  event_type: string;  // "content" | "tool_call" | "tool_result" | "done" | "error" | "thinking" | "mode"
   content: string | null;
   done: boolean;
   usage: { prompt_tokens: number; completion_tokens: number; total_tokens: number } | null;
   tool_name?: string | null;
 }
 
+/** 自然语言入口识别出的意图与参数 */
+export interface RoutedIntent {
+  intent: 'diagnose' | 'screen' | 'news' | 'compare' | 'macro';
+  stocks: { code: string; name: string }[];
+  keyword: string;
+}
+
+export interface RouterAnswer {
+  intent: RoutedIntent;
+  content: string;
+}
+
 // ====== Watchlist Types ======
 
 export interface WatchlistStock {
@@ -96,8 +560,331 @@ export interface WatchlistQuote {
   pct_20d: number;           // %
   revenue_yoy: number;       // %
   amplitude: number;         // %
+  /** 最近一个交易日尾盘竞价标记：尾盘抢筹 / 尾盘砸盘 */
+  tail_flag?: string | null;
+  date: string;
+  time: string;
+}
+
+/** 个股某日综合因子评分（0~100），分项为全市场百分位均值，缺失为 null */
+export interface FactorScore {
+  code: string;
+  name: string;
+  date: string;
+  score: number;
+  quality: number | null;
+  growth: number | null;
+  value: number | null;
+  momentum: number | null;
+  flow: number | null;
+}
+
+/** 条件选股结果，stocks 按涨跌幅降序且可能被截断 */
+export interface ExpressionScreenResult {
+  expression: string;
+  scanned: number;
+  matched: number;
+  stocks: WatchlistQuote[];
+  /** 选股理由（按代码索引），如 ROE前15% + 主力净流入1.2亿 */
+  explanations: Record<string, string>;
+  /** 前 30 只结果的主要题材（按代码索引） */
+  themes: Record<string, string[]>;
+}
+
+/** 个股在某个题材上的暴露，权重之和为 1 */
+export interface ThemeWeight {
+  board_code: string;
+  name: string;
+  weight: number;
+  change_pct: number;
+  mentions: number;
+  /** 驱动该题材的快讯标题 */
+  headlines: string[];
+  /** 近期新纳入该概念板块 */
+  newly_added: boolean;
+}
+
+/** 个股题材暴露向量，themes 按权重降序 */
+export interface ThemeExposure {
+  code: string;
+  themes: ThemeWeight[];
+  updated_at: string;
+}
+
+/** 个股某日资金流向（元），分单净额为 null 表示该日只有主力净流入 */
+export interface FundFlowDay {
+  code: string;
   date: string;
+  main_net: number;
+  super_large_net: number | null;
+  large_net: number | null;
+  medium_net: number | null;
+  small_net: number | null;
+  main_pct: number;
+  close: number;
+  change_pct: number;
+}
+
+/** 盘中累计资金净流入（元） */
+export interface IntradayFlowPoint {
   time: string;
+  main_net: number;
+  super_large_net: number;
+  large_net: number;
+  medium_net: number;
+  small_net: number;
+}
+
+/** 个股当日分单资金流向（元），主力 = 超大单 + 大单 */
+export interface FundFlowBreakdown {
+  code: string;
+  main_net: number;
+  main_pct: number;
+  super_large_net: number;
+  large_net: number;
+  medium_net: number;
+  small_net: number;
+  /** 分单形态，如"超大单流入、大单流出" */
+  pattern: string | null;
+  intraday: IntradayFlowPoint[];
+}
+
+/** 集合竞价异动 */
+export interface AuctionGap {
+  code: string;
+  name: string;
+  pre_close: number;
+  /** 竞价匹配价 */
+  price: number;
+  gap_pct: number;
+  /** 竞价成交量（手） */
+  volume: number;
+  /** 竞价成交额（元） */
+  amount: number;
+  avg_volume_5d: number;
+  /** 竞价成交量占 5 日均量 % */
+  volume_pct: number;
+  turnover_rate: number;
+  streak_days: number;
+  /** 异动强度 0~100 */
+  score: number;
+  labels: string[];
+  /** 落入的策略区间 */
+  zones: string[];
+}
+
+/** 竞价异动扫描结果（auction-gaps 事件内容） */
+export interface AuctionScanResult {
+  time: string;
+  scope: 'market' | 'watchlist';
+  scanned: number;
+  gaps: AuctionGap[];
+}
+
+/** 逐笔成交 */
+export interface TickPrint {
+  code: string;
+  time: string;
+  price: number;
+  /** 成交量（手） */
+  volume: number;
+  /** 成交额（元） */
+  amount: number;
+  side: 'buy' | 'sell' | 'neutral';
+}
+
+/** 焦点盯盘运行状态 */
+export interface FocusMonitorStatus {
+  running: boolean;
+  code: string | null;
+  prints: TickPrint[];
+}
+
+/** 题材事件研究：快讯提及题材后成分股的平均远期收益（%），超额相对沪深300 */
+export interface ThemeEventStats {
+  board_code: string;
+  theme: string;
+  events: number;
+  samples: number;
+  avg_return_1d: number | null;
+  avg_return_5d: number | null;
+  avg_return_10d: number | null;
+  avg_excess_5d: number | null;
+  avg_excess_10d: number | null;
+  win_rate_5d: number | null;
+  t_stat_5d: number | null;
+  /** 样本充足且 |t| ≥ 2 */
+  significant: boolean;
+}
+
+export interface ThemeStudyResult {
+  start: string;
+  end: string;
+  news_count: number;
+  themes: ThemeEventStats[];
+}
+
+/** 某类公告发布后的平均异常收益（%，个股收益减沪深300同期收益） */
+export interface AnnouncementEventStats {
+  category: string;
+  events: number;
+  avg_ar_0d: number | null;
+  avg_ar_1d: number | null;
+  avg_ar_5d: number | null;
+  avg_ar_20d: number | null;
+  win_rate_5d: number | null;
+  t_stat_5d: number | null;
+}
+
+export interface AnnouncementStudyResult {
+  start: string;
+  end: string;
+  announcements: number;
+  stats: AnnouncementEventStats[];
+  updated_at: string;
+}
+
+/** 概念板块成分股（缓存），is_new 表示增量刷新时新纳入 */
+export interface BoardMember {
+  board_code: string;
+  board_name: string;
+  code: string;
+  name: string;
+  first_seen: string;
+  is_new: boolean;
+}
+
+/** 指数（或行业板块）成分股，stocks 按总市值降序 */
+export interface IndexConstituents {
+  index_code: string;
+  index_name: string;
+  stocks: WatchlistQuote[];
+  updated_at: string;
+}
+
+/** 个股相对强度（欧奈尔 RPS），N 日涨幅的全市场百分位 0~100 */
+export interface StockRps {
+  code: string;
+  name: string;
+  date: string;
+  close: number;
+  pct_20d: number | null;
+  pct_60d: number | null;
+  pct_120d: number | null;
+  rps_20: number | null;
+  rps_60: number | null;
+  rps_120: number | null;
+}
+
+/** 市场宽度：涨跌家数、涨跌停与沪深300样本的均线/新高新低分布 */
+export interface MarketBreadth {
+  date: string;
+  total: number;
+  advancers: number;
+  decliners: number;
+  flat: number;
+  limit_up: number;
+  limit_down: number;
+  /** 样本股站上 20 日均线比例 %，本地日线不足时为 null */
+  above_ma20_pct: number | null;
+  above_ma60_pct: number | null;
+  new_high_52w: number;
+  new_low_52w: number;
+  sample_size: number;
+  created_at: string;
+}
+
+/** 市场状态（get_market_regime），按交易日缓存 */
+export interface MarketRegime {
+  date: string;
+  trend: 'up' | 'down' | 'range';
+  volatility: 'high' | 'normal' | 'low';
+  /** 大盘价值（上证50）vs 小盘成长（中证1000） */
+  style: 'large_value' | 'small_growth' | 'balanced';
+  index_close: number;
+  ma20: number;
+  ma60: number;
+  return_20d: number;
+  volatility_20d: number;
+  volatility_1y: number;
+  large_cap_return_20d: number;
+  small_cap_return_20d: number;
+  above_ma20_pct: number | null;
+  /** 两市成交量相对 20 日均量的倍数 */
+  volume_ratio: number | null;
+  summary: string;
+  created_at: string;
+}
+
+/** 批量同步日线缓存的单只结果（history-sync-progress 事件内容） */
+export interface HistorySyncItem {
+  code: string;
+  added: number;
+  /** 本地缓存已含当日K线，未发起请求 */
+  cached: boolean;
+  error: string | null;
+}
+
+export interface HistorySyncProgress {
+  completed: number;
+  total: number;
+  item: HistorySyncItem;
+}
+
+/** 批量同步汇总；failed_codes 可再次传入 sync_history_klines 续传 */
+export interface HistorySyncResult {
+  total: number;
+  synced: number;
+  cached: number;
+  added_rows: number;
+  failed_codes: string[];
+}
+
+/** 行业/概念板块行情 */
+export interface BoardQuote {
+  code: string;
+  name: string;
+  kind: 'industry' | 'concept';
+  change_pct: number;
+  turnover_rate: number;
+  amount: number;
+  total_market_cap: number;
+  main_net_inflow: number;
+  up_count: number;
+  down_count: number;
+  leader_code: string;
+  leader_name: string;
+  leader_change_pct: number;
+}
+
+/** 板块热力图：全部行业与概念板块，均按涨跌幅降序 */
+export interface MarketHeatmap {
+  industries: BoardQuote[];
+  concepts: BoardQuote[];
+  updated_at: string;
+}
+
+/** 板块排名动量：rank_change 为 N 个交易日前名次减最新名次（正数表示上升） */
+export interface BoardRotationItem {
+  code: string;
+  name: string;
+  rank: number;
+  rank_change_3d: number | null;
+  rank_change_5d: number | null;
+  rank_change_10d: number | null;
+  pct_3d: number | null;
+  pct_5d: number | null;
+  pct_10d: number | null;
+}
+
+/** 板块轮动：按 window 日排名变化排序的升温/降温板块 */
+export interface BoardRotation {
+  kind: 'industry' | 'concept';
+  date: string;
+  window: number;
+  history_days: number;
+  gaining: BoardRotationItem[];
+  losing: BoardRotationItem[];
 }
 
 export interface KlineItem {
@@ -112,6 +899,14 @@ export interface KlineItem {
   turnover_rate: number;
 }
 
+/** 指数K线，name 为空表示非常用指数 */
+export interface IndexKline {
+  code: string;
+  name: string;
+  period: string;
+  klines: KlineItem[];
+}
+
 export interface TechnicalIndicators {
   dates: string[];
   ma5: (number | null)[];
@@ -132,6 +927,24 @@ export interface TechnicalIndicators {
   boll_upper: (number | null)[];
   boll_middle: (number | null)[];
   boll_lower: (number | null)[];
+  donchian_upper: (number | null)[];
+  donchian_lower: (number | null)[];
+  keltner_upper: (number | null)[];
+  keltner_middle: (number | null)[];
+  keltner_lower: (number | null)[];
+}
+
+/** 盘中异动（anomaly-radar 事件内容，按 score 降序） */
+export interface AnomalyEvent {
+  code: string;
+  name: string;
+  anomaly_type: 'volume_spike' | 'rapid_rise' | 'rapid_fall' | 'auction_surge' | 'auction_plunge';
+  description: string;
+  /** 异动幅度相对触发阈值的倍数 */
+  score: number;
+  price: number;
+  change_pct: number;
+  time: string;
 }
 
 export interface TechnicalSignal {
@@ -151,6 +964,81 @@ export interface StockTechnicalAnalysis {
   ma_alignment: 'bullish' | 'bearish' | 'tangled';
   volume_price_relation: string;
   summary: string;
+  /** 风险统计，仅日线周期返回 */
+  risk: RiskMetrics | null;
+  gaps: GapAnalysis;
+  /** 成交量价格分布，K线不足时为 null */
+  volume_profile: VolumeProfile | null;
+}
+
+export interface VolumeBin {
+  price_low: number;
+  price_high: number;
+  volume: number;
+}
+
+/** 成交量价格分布：bins 按价格从低到高，poc_price 为成交最密集价位 */
+export interface VolumeProfile {
+  source: 'day' | 'm5';
+  days: number;
+  bins: VolumeBin[];
+  poc_price: number;
+  value_area_low: number;
+  value_area_high: number;
+  high_volume_nodes: number[];
+}
+
+/** 跳空缺口，upper/lower 为尚未回补的价格区间 */
+export interface PriceGap {
+  direction: 'up' | 'down';
+  date: string;
+  upper: number;
+  lower: number;
+  size_pct: number;
+  days_open: number;
+}
+
+/** 未回补缺口（由近到远）与历史回补统计 */
+export interface GapAnalysis {
+  open_gaps: PriceGap[];
+  total: number;
+  filled: number;
+  fill_rate_pct: number | null;
+  median_fill_days: number | null;
+  avg_fill_days: number | null;
+}
+
+/** 个股风险统计：年化波动率 %、相对沪深300的 Beta、近一年最大回撤 % */
+export interface RiskMetrics {
+  volatility_20d: number | null;
+  volatility_60d: number | null;
+  beta: number | null;
+  max_drawdown_1y: number | null;
+  drawdown_peak_date: string;
+  drawdown_trough_date: string;
+}
+
+/** 季节性统计分组：key 为月份 1~12 或星期 1~5 */
+export interface SeasonalityBucket {
+  key: number;
+  label: string;
+  avg_return_pct: number | null;
+  win_rate_pct: number | null;
+  samples: number;
+}
+
+/** 历史季节性：近 N 年各月份、各星期几的平均涨跌幅，附所属行业板块月度统计 */
+export interface Seasonality {
+  code: string;
+  years: number;
+  start_date: string;
+  end_date: string;
+  monthly: SeasonalityBucket[];
+  weekday: SeasonalityBucket[];
+  /** 所属行业板块，未找到时为空字符串 */
+  sector_code: string;
+  sector_name: string;
+  sector_monthly: SeasonalityBucket[];
 }
 
 // ====== AI Pick Tracking Types ======
@@ -236,6 +1124,88 @@ export interface AIPickRecommendation {
   highlights?: string[];
   fund_flow?: string;    // 资金流向状态，如"主力净流入2.3亿"
   valuation?: string;    // 估值水平，如"PE 15.2 低估"
+  verify_status?: PickVerifyStatus;
+  verify_note?: string;
+}
+
+/** 选股结果的行情校验状态 */
+export type PickVerifyStatus = 'passed' | 'flagged' | 'rejected' | 'unverified';
+
+/** 已落库的选股结果条目（ai_picks 表） */
+export interface PickRecord {
+  session_id: string;
+  date: string;
+  code: string;
+  name: string;
+  rating: string;
+  reason: string;
+  sector: string;
+  highlights: string[];
+  fund_flow: string;
+  valuation: string;
+  entry_price: number;   // 选股完成时的最新价，0 表示获取失败
+  verify_status: PickVerifyStatus;
+  verify_note: string;   // 校验未通过的原因，如"涨停（+10.01%）"
+  created_at: string;
+}
+
+/** 可续跑的中断选股（应用异常退出或运行出错时保留的断点） */
+export interface InterruptedPick {
+  session_id: string;
+  model_name: string;
+  started_at: string;
+  updated_at: string;
+  next_round: number;
+  final_phase: boolean;
+  tool_calls: number;
+  reason: string;
+}
+
+/** 当日最近一次选股：完整报告 + 结构化推荐列表 */
+export interface CachedPicks {
+  session_id: string;
+  content: string;
+  picks: PickRecord[];
+  created_at: string;
+}
+
+/** 选股会话列表项 */
+export interface PickSessionSummary {
+  session_id: string;
+  date: string;
+  model_name: string;
+  pick_count: number;
+  total_tokens: number;
+  error: string | null;
+  created_at: string;
+}
+
+export interface AgentToolCallRecord {
+  round: number;
+  tool_name: string;
+  arguments: string;
+  result: string;
+  cached: boolean;
+  elapsed_ms: number;
+}
+
+export interface AgentSession {
+  id: string;
+  kind: string;
+  subject: string;
+  model_name: string;
+  tool_calls: AgentToolCallRecord[];
+  content: string;
+  raw_content: string;
+  error: string | null;
+  usage: { prompt_tokens: number; completion_tokens: number; total_tokens: number } | null;
+  created_at: string;
+}
+
+/** 选股会话详情：完整报告 + 工具日志 + 结构化推荐 */
+export interface PickSessionDetail {
+  session: AgentSession;
+  picks: PickRecord[];
 }
 
 export interface AIPickResult {
@@ -304,3 +1274,47 @@ export interface GlobalIndex {
   change_pct: string;
   region: string;
 }
+
+export type AppErrorCode =
+  | 'network'
+  | 'timeout'
+  | 'unauthorized'
+  | 'rate_limited'
+  | 'upstream'
+  | 'not_configured'
+  | 'not_found'
+  | 'invalid_input'
+  | 'busy'
+  | 'cancelled'
+  | 'database'
+  | 'internal';
+
+/** 后端命令返回的错误 */
+export interface AppErrorPayload {
+  code: AppErrorCode;
+  message: string;
+}
+
+/** 策略模拟表现中单个入选日的样本 */
+export interface PerformancePoint {
+  date: string;
+  samples: number;
+  avg_return: number;
+}
+
+/** 策略近期模拟表现（get_strategy_performance） */
+export interface StrategyPerformance {
+  /** technical_signal / ai_instruction / ai_pick */
+  strategy: string;
+  name: string;
+  days: number;
+  hold_days: number;
+  total: number;
+  evaluated: number;
+  avg_return: number;
+  win_rate: number;
+  best_return: number | null;
+  worst_return: number | null;
+  points: PerformancePoint[];
+  updated_at: string;
+}